@@ -0,0 +1,11 @@
+//! Raw app.toml text through the bundle manifest checks.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = crix::AppBundle::check_manifest(text);
+    }
+});
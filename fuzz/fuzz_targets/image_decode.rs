@@ -0,0 +1,9 @@
+//! Arbitrary bytes through the skin asset decoder.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = crix::skin::decode_image(data);
+});
@@ -0,0 +1,14 @@
+//! Raw skin.json text through the whole skin loading path.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The first two bytes pick the stand-in asset size
+    if let [w, h, rest @ ..] = data
+        && let Ok(json) = std::str::from_utf8(rest)
+    {
+        crix_fuzz::check_skin(json, (*w as u32, *h as u32));
+    }
+});
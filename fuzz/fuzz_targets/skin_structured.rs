@@ -0,0 +1,199 @@
+//! Structurally valid skin.json documents with arbitrary values.
+//!
+//! Raw JSON mostly fails to parse; generating the document from typed parts
+//! gets the fuzzer straight to the builder and widgets with odd sizes,
+//! positions, ranges and nesting.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use serde_json::{json, Value};
+
+/// Every part type the skin loader accepts.
+const PART_TYPES: &[&str] = &[
+    "image",
+    "image_view",
+    "button",
+    "icon_button",
+    "text_input",
+    "file_path_input",
+    "static_text",
+    "vscroll_container",
+    "directory_picker",
+    "file_picker",
+    "checkbox",
+    "custom_paint",
+    "video",
+    "slider",
+    "knob",
+    "toggle_switch",
+    "indicator",
+    "spinner",
+    "wizard_progress",
+    "dropdown",
+    "menu_bar",
+    "text_area",
+    "scroll_view",
+    "list_view",
+    "tree_view",
+    "progress_bar",
+    "gauge",
+    "plot",
+    "seven_segment",
+    "animated_sprite",
+    "group_box",
+    "status_bar",
+    "timer_display",
+    "tab_container",
+    "stack",
+    "link",
+    "canvas",
+    "color_picker",
+    "embed",
+];
+
+#[derive(Arbitrary, Debug)]
+struct FuzzSkin {
+    width: u32,
+    height: u32,
+    asset_size: (u8, u8),
+    parts: Vec<FuzzPart>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzPart {
+    kind: u8,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    z: i32,
+    content: Option<String>,
+    font_size: Option<f32>,
+    padding: Option<u32>,
+    max_length: Option<u32>,
+    content_height: Option<u32>,
+    scrollbar_width: u32,
+    min: Option<f64>,
+    max: Option<f64>,
+    step: Option<f64>,
+    visible_items: Option<u32>,
+    frames: u32,
+    options: Option<Vec<String>>,
+    repeat: bool,
+    repeat_offset: Option<i32>,
+    child: Option<Box<FuzzPart>>,
+    children: Vec<FuzzPart>,
+}
+
+impl FuzzPart {
+    fn to_json(&self, id: &str) -> Value {
+        let kind = PART_TYPES[self.kind as usize % PART_TYPES.len()];
+        let mut part = json!({
+            "id": id,
+            "type": kind,
+            "asset": "a",
+            "x": self.x,
+            "y": self.y,
+            "width": self.width,
+            "height": self.height,
+            "z": self.z,
+            "content": self.content,
+            "font_size": self.font_size,
+            "padding": self.padding,
+            "max_length": self.max_length,
+            "content_height": self.content_height,
+            "min": self.min,
+            "max": self.max,
+            "step": self.step,
+            "visible_items": self.visible_items,
+            "options": self.options,
+            "list": "items",
+            "binding": "value",
+            "repeat_offset": self.repeat_offset,
+            "source": "embedded.json",
+            "url": "https://example.com",
+            "draw": { "normal": "a", "hover": "a", "pressed": "a" },
+            "icon_draw": { "strip": "a" },
+            "browse_draw": { "normal": "a", "hover": "a" },
+            "directory_picker_draw": {
+                "normal": "a", "hover": "a", "button_normal": "a", "button_hover": "a"
+            },
+            "file_picker_draw": {
+                "picker_normal": "a", "picker_hover": "a", "picker_btn_normal": "a", "picker_btn_hover": "a",
+                "track": "a", "thumb": "a", "item_normal": "a", "item_hover": "a", "item_selected": "a"
+            },
+            "text_input_draw": { "normal": "a", "hover": "a", "focused": "a" },
+            "checkbox_draw": { "unchecked": "a", "checked": "a" },
+            "slider_draw": { "track": "a", "thumb": "a" },
+            "progress_draw": { "done": "a", "current": "a", "pending": "a" },
+            "dropdown_draw": {
+                "normal": "a", "hover": "a", "item_normal": "a", "item_hover": "a"
+            },
+            "list_draw": { "row": "a", "row_hover": "a" },
+            "progress_bar_draw": { "track": "a", "fill": "a" },
+            "tabs_draw": { "tab": "a", "tab_hover": "a", "panel": "a" },
+            "scrollbar": { "width": self.scrollbar_width, "track": "a", "thumb": "a" },
+            "children": self
+                .children
+                .iter()
+                .enumerate()
+                .map(|(i, child)| child.to_json(&format!("{}.{}", id, i)))
+                .collect::<Vec<_>>(),
+        });
+        // A second json!, as one big enough for every part type hits the macro recursion limit
+        let draws = json!({
+            "knob_draw": { "strip": "a", "frames": self.frames },
+            "toggle_draw": { "off": "a", "on": "a", "thumb": "a" },
+            "indicator_draw": { "off": "a", "on": "a", "blink": 500 },
+            "spinner_draw": { "field": "a", "up": "a", "down": "a" },
+            "gauge_draw": { "face": "a", "style": if self.repeat { "needle" } else { "arc" }, "warning": self.min },
+            "plot_draw": { "series": [{ "list": "items" }], "y_min": self.min, "y_max": self.max },
+            "segment_draw": { "atlas": "a", "digits": self.frames },
+            "sprite_draw": {
+                "sheet": "a", "frame_width": self.width % 64, "frame_height": self.height % 64, "frames": self.frames
+            },
+            "group_draw": { "frame": "a", "slice": self.padding },
+            "status_draw": { "segments": [{ "id": "s", "binding": "value" }, { "id": "t", "width": self.width }] },
+            "menu_bar_draw": { "title": "a", "title_hover": "a", "item_normal": "a", "item_hover": "a" },
+            "tree_draw": { "row": "a", "row_hover": "a", "expanded": "a", "collapsed": "a" },
+            "menus": [{ "label": "File", "items": [{ "label": "Open", "action": "open" }] }],
+        });
+        if let (Some(part), Value::Object(draws)) = (part.as_object_mut(), draws) {
+            part.extend(draws);
+        }
+        if kind == "tab_container" {
+            // One tab per child part
+            part["tabs"] = self
+                .children
+                .iter()
+                .enumerate()
+                .map(|(i, child)| json!({ "id": format!("t{}", i), "children": [child.to_json(&format!("{}.t{}", id, i))] }))
+                .collect();
+        }
+        if self.repeat {
+            part["repeat"] = json!("items");
+        }
+        if let Some(child) = &self.child {
+            part["child"] = child.to_json(&format!("{}.child", id));
+        }
+        part
+    }
+}
+
+fuzz_target!(|skin: FuzzSkin| {
+    let parts: Vec<_> = skin
+        .parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| part.to_json(&format!("p{}", i)))
+        .collect();
+    let json = json!({
+        "skin": { "name": "fuzz", "author": "fuzz", "version": "0" },
+        "window": { "width": skin.width, "height": skin.height },
+        "assets": { "a": "a.png" },
+        "parts": parts,
+    });
+    crix_fuzz::check_skin(&json.to_string(), (skin.asset_size.0 as u32, skin.asset_size.1 as u32));
+});
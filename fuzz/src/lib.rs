@@ -0,0 +1,82 @@
+//! Shared code for the fuzz targets.
+//!
+//! A fuzzed skin goes through everything a third-party skin pack does when
+//! it's loaded: parsing, asset lookup, report templating, building the widget
+//! tree, a round of input events and drawing. Assets are stand-in images, so
+//! the targets don't touch the filesystem.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Once;
+use std::time::Duration;
+
+use crix::core::{KeyCode, Store, WidgetEvent};
+use crix::export::render_view;
+use crix::skin::{fill_template, LoadedSkin, Skin, SkinBuilder};
+use image::RgbImage;
+
+static FONT: Once = Once::new();
+
+/// Load the demo app's font once, so text widgets can be drawn.
+fn init_font() {
+    FONT.call_once(|| {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../demo_app.crix/skin/font.ttf");
+        crix::init_font(&path, 16.0).expect("demo font");
+    });
+}
+
+/// Load, build, poke and draw a skin. Every asset is a blank image of
+/// `asset_size`. Must never panic, whatever the JSON says.
+pub fn check_skin(json: &str, asset_size: (u32, u32)) {
+    init_font();
+    let Ok(skin) = Skin::parse(json, Path::new("skin")) else {
+        return;
+    };
+    let (width, height) = (asset_size.0 % 64, asset_size.1 % 64);
+    let images: HashMap<_, _> = skin
+        .assets
+        .keys()
+        .map(|key| (key.clone(), RgbImage::new(width, height)))
+        .collect();
+    let mut loaded = LoadedSkin::from_images(skin, images);
+
+    // Give repeated parts and list views something to expand
+    let mut store = Store::new();
+    store.set("items.count", 3.0);
+    for i in 0..3 {
+        store.set(format!("items.{}.name", i), format!("Item {}", i));
+    }
+    fill_template(&mut loaded, &store);
+
+    let Ok((mut tree, window)) = SkinBuilder::build(&loaded) else {
+        return;
+    };
+    let ids: Vec<_> = tree.iter_node_ids().collect();
+    for id in ids {
+        let Some(bounds) = tree.screen_bounds(id) else {
+            continue;
+        };
+        let (x, y) = tree.to_local(id, bounds.x, bounds.y);
+        let Some(node) = tree.get_mut(id) else {
+            continue;
+        };
+        let widget = node.widget_mut();
+        for event in [
+            WidgetEvent::FocusGained,
+            WidgetEvent::MouseMove { x, y },
+            WidgetEvent::MouseDown { x, y },
+            WidgetEvent::MouseUp { x, y },
+            WidgetEvent::Click,
+            WidgetEvent::MouseWheel { delta_y: -120.0 },
+            WidgetEvent::CharInput { c: 'a' },
+            WidgetEvent::KeyDown { key: KeyCode::Down },
+            WidgetEvent::KeyDown { key: KeyCode::End },
+            WidgetEvent::KeyDown { key: KeyCode::Backspace },
+        ] {
+            widget.on_event(&event);
+        }
+    }
+    tree.tick(Duration::from_secs(1));
+    tree.hit_test(window.width as i32 / 2, window.height as i32 / 2);
+    render_view(&tree, window.width, window.height);
+}
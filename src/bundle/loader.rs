@@ -3,9 +3,12 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::Deserialize;
 
+use crate::core::{validate_channel, Hotkey};
+use crate::services::{CatchUp, CronSchedule, ScheduleError};
 use crate::skin::{LoadedSkin, SkinError};
 
 /// App metadata from [app] section.
@@ -16,6 +19,9 @@ pub struct AppMeta {
     pub version: String,
     #[serde(default)]
     pub author: String,
+    /// Optional capabilities requested by the app (e.g. "camera").
+    #[serde(default)]
+    pub capabilities: Vec<String>,
 }
 
 /// Skin configuration from [skin] section.
@@ -23,8 +29,23 @@ pub struct AppMeta {
 pub struct SkinConfig {
     /// Path to skin.json relative to bundle root.
     pub path: String,
+    /// Milliseconds the old screen takes to fade out when the screen is
+    /// swapped (theme switch, lock screen); 0 swaps at once.
+    #[serde(default = "default_transition")]
+    pub transition: u64,
+}
+
+fn default_transition() -> u64 {
+    250
 }
 
+fn default_palette_shortcut() -> String {
+    "Ctrl+K".to_string()
+}
+
+/// Longest cross-fade between screens.
+const MAX_TRANSITION: Duration = Duration::from_secs(5);
+
 /// Font configuration from [fonts] section.
 #[derive(Debug, Clone, Deserialize)]
 pub struct FontConfig {
@@ -48,6 +69,216 @@ impl Default for FontConfig {
     }
 }
 
+/// Print configuration from [print] section.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PrintConfig {
+    /// Name of a layout from [layouts] to print instead of the current screen.
+    #[serde(default)]
+    pub layout: Option<String>,
+}
+
+/// Persistence configuration from [persist] section.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PersistConfig {
+    /// Store key prefixes saved per profile (e.g. "settings" covers "settings.*").
+    #[serde(default)]
+    pub keys: Vec<String>,
+}
+
+/// Shared store keys from [publish] section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublishConfig {
+    /// Channel other apps subscribe to.
+    pub channel: String,
+    /// Store key prefixes published on the channel.
+    pub keys: Vec<String>,
+}
+
+/// Channels read from other apps, from [subscribe] section.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SubscribeConfig {
+    /// Channel names; their values are stored under `<channel>.<key>`.
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+/// Scheduled actions from [schedule] section.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScheduleConfig {
+    /// What happens to runs missed while the app was closed.
+    #[serde(default)]
+    pub catch_up: CatchUp,
+    /// Action names by cron expression (e.g. "0 9 * * MON").
+    #[serde(flatten)]
+    pub jobs: HashMap<String, String>,
+}
+
+/// Lock screen configuration from [lock] section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LockConfig {
+    /// Name of the layout from [layouts] shown while the app is locked.
+    pub layout: String,
+    /// Lock automatically after this many seconds without input.
+    #[serde(default)]
+    pub idle_timeout: Option<u64>,
+}
+
+/// Macro configuration from [macros] section.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MacroConfig {
+    /// Shortcut (e.g. "Ctrl+1") -> name of the recorded macro it plays.
+    #[serde(default)]
+    pub hotkeys: HashMap<String, String>,
+}
+
+/// Command palette configuration from [palette] section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaletteConfig {
+    /// Shortcut (e.g. "Ctrl+Shift+P") that opens and closes the palette.
+    #[serde(default = "default_palette_shortcut")]
+    pub shortcut: String,
+}
+
+impl Default for PaletteConfig {
+    fn default() -> Self {
+        Self {
+            shortcut: default_palette_shortcut(),
+        }
+    }
+}
+
+/// Update configuration from [update] section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateSection {
+    /// URL of the update manifest.
+    pub manifest: String,
+    /// Hex-encoded Ed25519 public key update archives are signed with.
+    pub public_key: String,
+}
+
+/// Spell-check configuration from [spellcheck] section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpellcheckConfig {
+    /// Dictionary language checked at startup, e.g. "en_US".
+    pub language: String,
+    /// Directory with `<language>.aff` and `<language>.dic` files, relative to bundle root.
+    #[serde(default = "default_dictionaries")]
+    pub dictionaries: String,
+}
+
+fn default_dictionaries() -> String {
+    "dictionaries".to_string()
+}
+
+/// Number formatting configuration from [format] section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FormatConfig {
+    /// Character between the whole and fractional part.
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: char,
+    /// Character between groups of thousands.
+    #[serde(default = "default_thousands_separator")]
+    pub thousands_separator: char,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            decimal_separator: default_decimal_separator(),
+            thousands_separator: default_thousands_separator(),
+        }
+    }
+}
+
+fn default_decimal_separator() -> char {
+    '.'
+}
+
+fn default_thousands_separator() -> char {
+    ','
+}
+
+/// Currency configuration from [currency] section.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CurrencyConfig {
+    /// Code of the currency exchange rates are refreshed against.
+    #[serde(default = "default_currency_base")]
+    pub base: String,
+    /// Rates endpoint with an open.er-api.com compatible response, where
+    /// `{base}` is replaced by the base code.
+    #[serde(default)]
+    pub rates_url: Option<String>,
+}
+
+fn default_currency_base() -> String {
+    "USD".to_string()
+}
+
+/// Desktop widget settings from widget.toml's [widget] section.
+#[derive(Debug, Clone, Default)]
+pub struct WidgetConfig {
+    /// Where the widget's window opens on screen.
+    pub position: Option<(i32, i32)>,
+    /// How often `on_refresh` runs.
+    pub refresh: Option<Duration>,
+    /// Action run when the widget opens and every `refresh`.
+    pub on_refresh: Option<String>,
+}
+
+/// File name of a widget pack's manifest.
+pub const WIDGET_MANIFEST: &str = "widget.toml";
+
+/// Shortest interval between widget refreshes.
+const MIN_REFRESH: Duration = Duration::from_millis(100);
+
+/// Raw TOML structure for a widget pack's [widget] section.
+#[derive(Debug, Deserialize)]
+struct WidgetSection {
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    /// Skin path relative to the pack root.
+    #[serde(default = "default_widget_skin")]
+    skin: String,
+    /// Font path relative to the pack root.
+    font: String,
+    #[serde(default = "default_font_size")]
+    font_size: f32,
+    #[serde(default)]
+    x: Option<i32>,
+    #[serde(default)]
+    y: Option<i32>,
+    /// Milliseconds between runs of `on_refresh`.
+    #[serde(default)]
+    refresh: Option<u64>,
+    #[serde(default)]
+    on_refresh: Option<String>,
+}
+
+fn default_widget_skin() -> String {
+    "skin.json".to_string()
+}
+
+/// Raw TOML structure for widget.toml.
+#[derive(Debug, Deserialize)]
+struct WidgetToml {
+    widget: WidgetSection,
+    #[serde(default)]
+    actions: HashMap<String, String>,
+    #[serde(default)]
+    persist: PersistConfig,
+    #[serde(default)]
+    publish: Option<PublishConfig>,
+    #[serde(default)]
+    subscribe: SubscribeConfig,
+    #[serde(default)]
+    schedule: ScheduleConfig,
+}
+
 /// Raw TOML structure for app.toml.
 #[derive(Debug, Deserialize)]
 struct AppToml {
@@ -58,6 +289,32 @@ struct AppToml {
     fonts: Option<FontConfig>,
     #[serde(default)]
     actions: HashMap<String, String>,
+    #[serde(default)]
+    layouts: HashMap<String, String>,
+    #[serde(default)]
+    print: PrintConfig,
+    #[serde(default)]
+    update: Option<UpdateSection>,
+    #[serde(default)]
+    persist: PersistConfig,
+    #[serde(default)]
+    lock: Option<LockConfig>,
+    #[serde(default)]
+    macros: MacroConfig,
+    #[serde(default)]
+    palette: PaletteConfig,
+    #[serde(default)]
+    spellcheck: Option<SpellcheckConfig>,
+    #[serde(default)]
+    format: FormatConfig,
+    #[serde(default)]
+    currency: Option<CurrencyConfig>,
+    #[serde(default)]
+    publish: Option<PublishConfig>,
+    #[serde(default)]
+    subscribe: SubscribeConfig,
+    #[serde(default)]
+    schedule: ScheduleConfig,
 }
 
 /// Errors that can occur when loading an app bundle.
@@ -77,6 +334,18 @@ pub enum BundleError {
     FontNotFound(PathBuf),
     /// Script file not found.
     ScriptNotFound { action: String, path: PathBuf },
+    /// Layout skin file not found.
+    LayoutNotFound { layout: String, path: PathBuf },
+    /// A layout was referenced that isn't declared in [layouts].
+    UnknownLayout(String),
+    /// A [macros] hotkey couldn't be parsed.
+    InvalidHotkey(String),
+    /// A widget's refresh settings are incomplete or too fast.
+    InvalidRefresh(String),
+    /// A [publish] or [subscribe] channel name isn't a safe file name.
+    InvalidChannel(String),
+    /// A [schedule] expression couldn't be parsed.
+    InvalidSchedule(ScheduleError),
     /// Skin not configured.
     NoSkin,
     /// Font not configured.
@@ -87,7 +356,7 @@ impl std::fmt::Display for BundleError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             BundleError::NotFound(path) => write!(f, "Bundle not found: {:?}", path),
-            BundleError::NoAppToml(path) => write!(f, "app.toml not found in: {:?}", path),
+            BundleError::NoAppToml(path) => write!(f, "app.toml or {} not found in: {:?}", WIDGET_MANIFEST, path),
             BundleError::Io(e) => write!(f, "IO error: {}", e),
             BundleError::Toml(e) => write!(f, "TOML parse error: {}", e),
             BundleError::Skin(e) => write!(f, "Skin error: {}", e),
@@ -95,6 +364,14 @@ impl std::fmt::Display for BundleError {
             BundleError::ScriptNotFound { action, path } => {
                 write!(f, "Script for action '{}' not found: {:?}", action, path)
             }
+            BundleError::LayoutNotFound { layout, path } => {
+                write!(f, "Skin for layout '{}' not found: {:?}", layout, path)
+            }
+            BundleError::UnknownLayout(name) => write!(f, "Unknown layout: {}", name),
+            BundleError::InvalidHotkey(hotkey) => write!(f, "Invalid hotkey: {}", hotkey),
+            BundleError::InvalidRefresh(reason) => write!(f, "Invalid widget refresh: {}", reason),
+            BundleError::InvalidChannel(name) => write!(f, "Invalid channel name: '{}'", name),
+            BundleError::InvalidSchedule(e) => write!(f, "{}", e),
             BundleError::NoSkin => write!(f, "No skin configured in app.toml"),
             BundleError::NoFont => write!(f, "No font configured in app.toml"),
         }
@@ -133,15 +410,46 @@ pub struct AppBundle {
     /// Resolved font path and size.
     font_path: PathBuf,
     pub font_size: f32,
+    /// Cross-fade duration when the screen is swapped.
+    pub transition: Duration,
     /// Action name -> script path mapping.
     action_scripts: HashMap<String, PathBuf>,
+    /// Layout name -> skin path mapping for offscreen layouts (print, export).
+    layouts: HashMap<String, PathBuf>,
+    /// Print configuration.
+    pub print: PrintConfig,
+    /// Update configuration, if the app opts into updates.
+    pub update: Option<UpdateSection>,
+    /// Persistence configuration.
+    pub persist: PersistConfig,
+    /// Lock screen configuration, if the app can be locked.
+    pub lock: Option<LockConfig>,
+    /// Macro hotkeys.
+    pub macros: MacroConfig,
+    /// Command palette settings.
+    pub palette: PaletteConfig,
+    /// Spell-check configuration, if the app checks spelling.
+    pub spellcheck: Option<SpellcheckConfig>,
+    /// Number formatting configuration.
+    pub format: FormatConfig,
+    /// Currency configuration, if the app refreshes exchange rates.
+    pub currency: Option<CurrencyConfig>,
+    /// Desktop widget settings, if the bundle is a widget pack.
+    pub widget: Option<WidgetConfig>,
+    /// Store keys shared with other apps, if any.
+    pub publish: Option<PublishConfig>,
+    /// Channels of other apps read into the store.
+    pub subscribe: SubscribeConfig,
+    /// Actions run on cron-style schedules.
+    pub schedule: ScheduleConfig,
 }
 
 impl AppBundle {
     /// Load an app bundle from a directory.
     ///
     /// # Arguments
-    /// * `path` - Path to the bundle directory (e.g., "my_app.crix")
+    /// * `path` - Path to the bundle directory (e.g., "my_app.crix"), or to a
+    ///   widget pack holding `widget.toml` instead of `app.toml`
     ///
     /// # Returns
     /// A loaded AppBundle with all paths resolved and validated.
@@ -153,14 +461,17 @@ impl AppBundle {
 
         let root = path.to_path_buf();
 
-        // Load app.toml
+        // Load app.toml, or widget.toml for a widget pack
         let app_toml_path = root.join("app.toml");
         if !app_toml_path.exists() {
+            if root.join(WIDGET_MANIFEST).exists() {
+                return Self::load_widget(root);
+            }
             return Err(BundleError::NoAppToml(root.clone()));
         }
 
         let content = fs::read_to_string(&app_toml_path)?;
-        let toml: AppToml = toml::from_str(&content)?;
+        let toml = Self::parse_manifest(&content)?;
 
         // Resolve skin path
         let skin_config = toml.skin.ok_or(BundleError::NoSkin)?;
@@ -179,17 +490,19 @@ impl AppBundle {
             return Err(BundleError::FontNotFound(font_path));
         }
 
-        // Resolve action script paths
-        let mut action_scripts = HashMap::new();
-        for (action_name, script_rel_path) in toml.actions {
-            let script_path = root.join(&script_rel_path);
-            if !script_path.exists() {
-                return Err(BundleError::ScriptNotFound {
-                    action: action_name,
-                    path: script_path,
+        let action_scripts = Self::resolve_scripts(&root, toml.actions)?;
+
+        // Resolve layout skin paths
+        let mut layouts = HashMap::new();
+        for (layout_name, skin_rel_path) in toml.layouts {
+            let layout_path = root.join(&skin_rel_path);
+            if !layout_path.exists() {
+                return Err(BundleError::LayoutNotFound {
+                    layout: layout_name,
+                    path: layout_path,
                 });
             }
-            action_scripts.insert(action_name, script_path);
+            layouts.insert(layout_name, layout_path);
         }
 
         Ok(Self {
@@ -198,10 +511,171 @@ impl AppBundle {
             skin_path,
             font_path,
             font_size: font_config.size,
+            transition: Duration::from_millis(skin_config.transition).min(MAX_TRANSITION),
+            action_scripts,
+            layouts,
+            print: toml.print,
+            update: toml.update,
+            persist: toml.persist,
+            lock: toml.lock,
+            macros: toml.macros,
+            palette: toml.palette,
+            spellcheck: toml.spellcheck,
+            format: toml.format,
+            currency: toml.currency,
+            widget: None,
+            publish: toml.publish,
+            subscribe: toml.subscribe,
+            schedule: toml.schedule,
+        })
+    }
+
+    /// Load a widget pack: a skin, a font and optional scripts described by
+    /// widget.toml, for a single desktop widget.
+    fn load_widget(root: PathBuf) -> Result<Self, BundleError> {
+        let content = fs::read_to_string(root.join(WIDGET_MANIFEST))?;
+        let toml = Self::parse_widget_manifest(&content)?;
+        let widget = toml.widget;
+
+        let skin_path = root.join(&widget.skin);
+        if !skin_path.exists() {
+            return Err(BundleError::Skin(SkinError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Skin not found: {:?}", skin_path),
+            ))));
+        }
+        let font_path = root.join(&widget.font);
+        if !font_path.exists() {
+            return Err(BundleError::FontNotFound(font_path));
+        }
+        let action_scripts = Self::resolve_scripts(&root, toml.actions)?;
+
+        Ok(Self {
+            root,
+            meta: AppMeta {
+                name: widget.name,
+                version: widget.version,
+                author: widget.author,
+                capabilities: widget.capabilities,
+            },
+            skin_path,
+            font_path,
+            font_size: widget.font_size,
+            transition: Duration::from_millis(default_transition()),
             action_scripts,
+            layouts: HashMap::new(),
+            print: PrintConfig::default(),
+            update: None,
+            persist: toml.persist,
+            lock: None,
+            macros: MacroConfig::default(),
+            palette: PaletteConfig::default(),
+            spellcheck: None,
+            format: FormatConfig::default(),
+            currency: None,
+            widget: Some(WidgetConfig {
+                position: widget.x.zip(widget.y),
+                refresh: widget.refresh.map(Duration::from_millis),
+                on_refresh: widget.on_refresh,
+            }),
+            publish: toml.publish,
+            subscribe: toml.subscribe,
+            schedule: toml.schedule,
         })
     }
 
+    /// Resolve action script paths against the bundle root.
+    fn resolve_scripts(root: &Path, actions: HashMap<String, String>) -> Result<HashMap<String, PathBuf>, BundleError> {
+        let mut action_scripts = HashMap::new();
+        for (action_name, script_rel_path) in actions {
+            let script_path = root.join(&script_rel_path);
+            if !script_path.exists() {
+                return Err(BundleError::ScriptNotFound {
+                    action: action_name,
+                    path: script_path,
+                });
+            }
+            action_scripts.insert(action_name, script_path);
+        }
+        Ok(action_scripts)
+    }
+
+    /// Check an app.toml's contents without touching the filesystem: it must
+    /// parse, configure a skin and font, reference only declared layouts and
+    /// use valid hotkeys. `load` runs the same checks before resolving paths.
+    pub fn check_manifest(content: &str) -> Result<(), BundleError> {
+        Self::parse_manifest(content).map(|_| ())
+    }
+
+    /// Parse app.toml and run the checks that don't need the bundle files.
+    fn parse_manifest(content: &str) -> Result<AppToml, BundleError> {
+        let toml: AppToml = toml::from_str(content)?;
+        if toml.skin.is_none() {
+            return Err(BundleError::NoSkin);
+        }
+        if toml.fonts.is_none() {
+            return Err(BundleError::NoFont);
+        }
+        if let Some(name) = &toml.print.layout
+            && !toml.layouts.contains_key(name)
+        {
+            return Err(BundleError::UnknownLayout(name.clone()));
+        }
+        if let Some(lock) = &toml.lock
+            && !toml.layouts.contains_key(&lock.layout)
+        {
+            return Err(BundleError::UnknownLayout(lock.layout.clone()));
+        }
+        if let Some(hotkey) = toml.macros.hotkeys.keys().find(|hotkey| Hotkey::parse(hotkey).is_none()) {
+            return Err(BundleError::InvalidHotkey(hotkey.clone()));
+        }
+        if Hotkey::parse(&toml.palette.shortcut).is_none() {
+            return Err(BundleError::InvalidHotkey(toml.palette.shortcut.clone()));
+        }
+        Self::check_channels(toml.publish.as_ref(), &toml.subscribe)?;
+        Self::check_schedule(&toml.schedule)?;
+        Ok(toml)
+    }
+
+    /// Check that [schedule] expressions parse.
+    fn check_schedule(schedule: &ScheduleConfig) -> Result<(), BundleError> {
+        for expr in schedule.jobs.keys() {
+            CronSchedule::parse(expr).map_err(BundleError::InvalidSchedule)?;
+        }
+        Ok(())
+    }
+
+    /// Check that [publish] and [subscribe] channel names are safe file names.
+    fn check_channels(publish: Option<&PublishConfig>, subscribe: &SubscribeConfig) -> Result<(), BundleError> {
+        let channels = publish.map(|publish| &publish.channel).into_iter().chain(&subscribe.channels);
+        for channel in channels {
+            if validate_channel(channel).is_err() {
+                return Err(BundleError::InvalidChannel(channel.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a widget.toml's contents without touching the filesystem: it
+    /// must parse, and a refresh interval comes with an action to run.
+    pub fn check_widget_manifest(content: &str) -> Result<(), BundleError> {
+        Self::parse_widget_manifest(content).map(|_| ())
+    }
+
+    /// Parse widget.toml and run the checks that don't need the pack files.
+    fn parse_widget_manifest(content: &str) -> Result<WidgetToml, BundleError> {
+        let toml: WidgetToml = toml::from_str(content)?;
+        Self::check_channels(toml.publish.as_ref(), &toml.subscribe)?;
+        Self::check_schedule(&toml.schedule)?;
+        match (toml.widget.refresh, &toml.widget.on_refresh) {
+            (Some(refresh), Some(_)) if Duration::from_millis(refresh) < MIN_REFRESH => {
+                Err(BundleError::InvalidRefresh(format!("{} ms is faster than {} ms", refresh, MIN_REFRESH.as_millis())))
+            }
+            (Some(_), None) => Err(BundleError::InvalidRefresh("refresh without on_refresh".to_string())),
+            _ => Ok(toml),
+        }
+    }
+
     /// Get the bundle root directory.
     pub fn root(&self) -> &Path {
         &self.root
@@ -232,6 +706,20 @@ impl AppBundle {
         self.action_scripts.keys()
     }
 
+    /// Get all declared layout names.
+    pub fn layout_names(&self) -> impl Iterator<Item = &String> {
+        self.layouts.keys()
+    }
+
+    /// Load a named layout declared in [layouts].
+    pub fn load_layout(&self, name: &str) -> Result<LoadedSkin, BundleError> {
+        let path = self
+            .layouts
+            .get(name)
+            .ok_or_else(|| BundleError::UnknownLayout(name.to_string()))?;
+        Ok(LoadedSkin::load(path)?)
+    }
+
     /// Load the skin from this bundle.
     pub fn load_skin(&self) -> Result<LoadedSkin, SkinError> {
         LoadedSkin::load(&self.skin_path)
@@ -273,3 +761,42 @@ impl AppConfigAdapter {
         self.action_scripts.keys()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widget_manifest() {
+        let manifest = r#"
+            [widget]
+            name = "Clock"
+            font = "font.ttf"
+            x = 40
+            y = 60
+            refresh = 1000
+            on_refresh = "tick"
+
+            [actions]
+            tick = "clock.lua"
+
+            [schedule]
+            catch_up = "all"
+            "0 * * * *" = "chime"
+        "#;
+        let toml = AppBundle::parse_widget_manifest(manifest).unwrap();
+        assert_eq!((toml.widget.skin.as_str(), toml.widget.x.zip(toml.widget.y)), ("skin.json", Some((40, 60))));
+        assert_eq!(toml.actions.get("tick").map(String::as_str), Some("clock.lua"));
+        assert_eq!(toml.schedule.catch_up, CatchUp::All);
+        assert_eq!(toml.schedule.jobs.get("0 * * * *").map(String::as_str), Some("chime"));
+        let bad = manifest.replace("0 * * * *", "0 25 * * *");
+        assert!(matches!(AppBundle::check_widget_manifest(&bad), Err(BundleError::InvalidSchedule(_))));
+
+        for bad in [
+            "[widget]\nname = \"Clock\"\nfont = \"font.ttf\"\nrefresh = 1000",
+            "[widget]\nname = \"Clock\"\nfont = \"font.ttf\"\nrefresh = 10\non_refresh = \"tick\"",
+        ] {
+            assert!(matches!(AppBundle::check_widget_manifest(bad), Err(BundleError::InvalidRefresh(_))), "{}", bad);
+        }
+    }
+}
@@ -21,6 +21,7 @@
 //! [app]
 //! name = "My Application"
 //! version = "1.0.0"
+//! capabilities = ["camera"]   # optional, gates hardware/network services
 //!
 //! [skin]
 //! path = "skin/skin.json"
@@ -32,8 +33,84 @@
 //! [actions]
 //! calculate = "scripts/calculate.lua"
 //! reset = "scripts/reset.lua"
+//!
+//! [layouts]                    # optional offscreen layouts (print, export)
+//! receipt = "skin/receipt.json"
+//!
+//! [print]
+//! layout = "receipt"           # optional, defaults to printing the screen
+//!
+//! [lock]                       # optional PIN lock screen
+//! layout = "lock"              # a layout from [layouts]
+//! idle_timeout = 300           # optional, seconds without input
+//!
+//! [persist]                    # optional, saved per profile
+//! keys = ["settings", "history"]
+//!
+//! [palette]                    # optional
+//! shortcut = "Ctrl+Shift+P"    # opens the command palette, default "Ctrl+K"
+//!
+//! [publish]                    # optional, shared with other crix apps
+//! channel = "nowplaying"
+//! keys = ["player"]
+//!
+//! [subscribe]                  # optional, stored as "<channel>.<key>"
+//! channels = ["weather"]
+//!
+//! [schedule]                   # optional, cron-style expression = action
+//! catch_up = "once"            # optional: "skip" (default), "once" or "all"
+//! "0 9 * * MON" = "weekly_report"
+//!
+//! [update]                     # optional, needs the "update" capability
+//! manifest = "https://example.com/my_app/manifest.json"
+//! public_key = "<hex Ed25519 public key>"
+//!
+//! [spellcheck]                 # optional, needs the "spellcheck" capability
+//! language = "en_US"           # dictionaries/en_US.aff and .dic
+//! dictionaries = "dictionaries"  # optional, the default
+//!
+//! [format]                     # optional, separators for formatted numbers
+//! decimal_separator = ","      # default "."
+//! thousands_separator = "."    # default ","
+//!
+//! [currency]                   # optional, exchange rates need the "currency" capability
+//! base = "EUR"                 # default "USD"
+//! rates_url = "https://example.com/rates/{base}"  # optional, open.er-api.com compatible
+//! ```
+//!
+//! # Widget Packs
+//!
+//! A widget pack is a lighter bundle for a single desktop widget: a skin, a
+//! font and optional scripts, described by `widget.toml` in place of
+//! `app.toml`. `crix host` shows several packs at once, each in its own
+//! borderless, always-on-top window.
+//!
+//! ```toml
+//! [widget]
+//! name = "Clock"
+//! version = "1.0.0"
+//! skin = "skin.json"           # optional, the default
+//! font = "font.ttf"
+//! font_size = 16.0             # optional
+//! x = 40                       # optional position on screen
+//! y = 40
+//! refresh = 1000               # optional, milliseconds (at least 100)
+//! on_refresh = "tick"          # run on opening and every `refresh`
+//!
+//! [actions]                    # optional
+//! tick = "clock.lua"
+//!
+//! [persist]                    # optional, saved per profile
+//! keys = ["settings"]
+//!
+//! [subscribe]                  # optional, as in app.toml; so is [publish]
+//! channels = ["nowplaying"]
 //! ```
 
 mod loader;
 
-pub use loader::{AppBundle, BundleError};
+pub use loader::{
+    AppBundle, BundleError, CurrencyConfig, FormatConfig, LockConfig, MacroConfig, PaletteConfig, PersistConfig,
+    PrintConfig, PublishConfig, ScheduleConfig, SpellcheckConfig, SubscribeConfig, UpdateSection, WidgetConfig,
+    WIDGET_MANIFEST,
+};
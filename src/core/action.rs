@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
 use super::store::{Store, Value};
+use crate::services::{
+    AlarmService, CameraService, Capabilities, ClipboardService, CurrencyService, DialogService, FormatService,
+    GeoService, NotifyService, PrintService, ScanService, SecretService, ShareService, SpellService, TtsService,
+    UpdateService, UrlService,
+};
 
 /// An action that triggers app logic.
 /// Actions are the main hook point for future scripting integration.
@@ -72,16 +77,196 @@ impl std::fmt::Display for ActionError {
 impl std::error::Error for ActionError {}
 
 /// Services available to action handlers.
-/// Reserved for future expansion (time, random, network, etc.).
+///
+/// Services that reach outside the app (hardware, network) are gated by
+/// the app's granted `Capabilities`.
 #[derive(Debug, Default)]
 pub struct Services {
-    // Currently empty - placeholder for future services
+    /// Capabilities granted to the app.
+    capabilities: Capabilities,
+    /// Camera capture (requires the "camera" capability).
+    camera: CameraService,
+    /// Text-to-speech (requires the "tts" capability).
+    tts: TtsService,
+    /// QR/barcode decoding.
+    scan: ScanService,
+    /// IP-based geolocation (requires the "location" capability).
+    geo: GeoService,
+    /// Printing.
+    print: PrintService,
+    /// Mail/share handoff (requires the "share" capability).
+    share: ShareService,
+    /// Opening links in the browser (requires the "browser" capability).
+    url: UrlService,
+    /// Clipboard text.
+    clipboard: ClipboardService,
+    /// Bundle updates (requires the "update" capability).
+    update: UpdateService,
+    /// Hashed secrets such as the lock-screen PIN.
+    secrets: SecretService,
+    /// Spell-checking of text areas (requires the "spellcheck" capability).
+    spell: SpellService,
+    /// Number formatting with the app's separators.
+    format: FormatService,
+    /// Alarms and reminders.
+    alarms: AlarmService,
+    /// Desktop notifications (requires the "notifications" capability).
+    notify: NotifyService,
+    /// Currency formatting, and exchange rates (refreshing requires the "currency" capability).
+    currency: CurrencyService,
+    /// Native file dialogs.
+    dialog: DialogService,
 }
 
 impl Services {
-    /// Create a new services instance.
+    /// Create a new services instance with no capabilities granted.
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Set the bundle updater.
+    pub fn with_update(mut self, update: UpdateService) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Set the print service.
+    pub fn with_print(mut self, print: PrintService) -> Self {
+        self.print = print;
+        self
+    }
+
+    /// Set the secret store.
+    pub fn with_secrets(mut self, secrets: SecretService) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Set the spell checker.
+    pub fn with_spell(mut self, spell: SpellService) -> Self {
+        self.spell = spell;
+        self
+    }
+
+    /// Set the number formatter.
+    pub fn with_format(mut self, format: FormatService) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the alarm store.
+    pub fn with_alarms(mut self, alarms: AlarmService) -> Self {
+        self.alarms = alarms;
+        self
+    }
+
+    /// Set the currency service.
+    pub fn with_currency(mut self, currency: CurrencyService) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Set the granted capabilities.
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Get the granted capabilities.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Fail with an action error unless the capability has been granted.
+    pub fn require(&self, capability: &str) -> Result<(), ActionError> {
+        if self.capabilities.allows(capability) {
+            Ok(())
+        } else {
+            Err(ActionError::Failed(format!(
+                "capability '{}' not granted in app.toml",
+                capability
+            )))
+        }
+    }
+
+    /// Get the camera service.
+    pub fn camera(&self) -> &CameraService {
+        &self.camera
+    }
+
+    /// Get the text-to-speech service.
+    pub fn tts(&self) -> &TtsService {
+        &self.tts
+    }
+
+    /// Get the scan service.
+    pub fn scan(&self) -> &ScanService {
+        &self.scan
+    }
+
+    /// Get the geolocation service.
+    pub fn geo(&self) -> &GeoService {
+        &self.geo
+    }
+
+    /// Get the print service.
+    pub fn print(&self) -> &PrintService {
+        &self.print
+    }
+
+    /// Get the share service.
+    pub fn share(&self) -> &ShareService {
+        &self.share
+    }
+
+    /// Get the URL opener.
+    pub fn url(&self) -> &UrlService {
+        &self.url
+    }
+
+    /// Get the clipboard service.
+    pub fn clipboard(&self) -> &ClipboardService {
+        &self.clipboard
+    }
+
+    /// Get the bundle updater.
+    pub fn update(&self) -> &UpdateService {
+        &self.update
+    }
+
+    /// Get the secret store.
+    pub fn secrets(&self) -> &SecretService {
+        &self.secrets
+    }
+
+    /// Get the spell checker.
+    pub fn spell(&self) -> &SpellService {
+        &self.spell
+    }
+
+    /// Get the number formatter.
+    pub fn format(&self) -> &FormatService {
+        &self.format
+    }
+
+    /// Get the alarm store.
+    pub fn alarms(&self) -> &AlarmService {
+        &self.alarms
+    }
+
+    /// Get the notification service.
+    pub fn notify(&self) -> &NotifyService {
+        &self.notify
+    }
+
+    /// Get the currency service.
+    pub fn currency(&self) -> &CurrencyService {
+        &self.currency
+    }
+
+    /// Get the file dialog service.
+    pub fn dialog(&self) -> &DialogService {
+        &self.dialog
     }
 }
 
@@ -100,6 +285,24 @@ pub trait ActionHandler {
         store: &mut Store,
         services: &Services,
     ) -> Result<bool, ActionError>;
+
+    /// Names of the actions this handler knows, for listings such as the
+    /// command palette. Defaults to none.
+    fn action_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Continue work left running by earlier actions, such as scripts
+    /// waiting in `app.yield()`. Called once per frame.
+    /// Returns true if the store may have changed.
+    fn poll(&mut self, _store: &mut Store, _services: &Services) -> bool {
+        false
+    }
+
+    /// Check if work is left running that `poll` should continue.
+    fn is_busy(&self) -> bool {
+        false
+    }
 }
 
 /// A composite action handler that chains multiple handlers.
@@ -132,6 +335,28 @@ impl ActionDispatcher {
         }
         Ok(false)
     }
+
+    /// Continue running work in all handlers. Returns true if the store may have changed.
+    pub fn poll(&mut self, store: &mut Store, services: &Services) -> bool {
+        let mut changed = false;
+        for handler in &mut self.handlers {
+            changed |= handler.poll(store, services);
+        }
+        changed
+    }
+
+    /// Check if any handler has running work.
+    pub fn is_busy(&self) -> bool {
+        self.handlers.iter().any(|handler| handler.is_busy())
+    }
+
+    /// Names of the actions known to all handlers, sorted and deduplicated.
+    pub fn action_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.handlers.iter().flat_map(|h| h.action_names()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
 }
 
 impl Default for ActionDispatcher {
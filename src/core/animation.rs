@@ -0,0 +1,433 @@
+//! Widget animations triggered by store changes.
+//!
+//! A skin part can declare animations that play when store keys matching a
+//! pattern change, such as flashing a result label when any `outputs.*` key
+//! changes or shaking an input when its `errors.*` key is set. The tree owns
+//! an `Animator`, which the app shows the store after each update; it starts
+//! the animations whose keys changed and tells the tree how to draw them.
+
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use crate::core::{NodeId, Store, Value};
+
+/// How long an animation plays unless the skin says otherwise.
+pub const DEFAULT_ANIMATION_DURATION: Duration = Duration::from_millis(400);
+
+/// Color of a flash unless the skin says otherwise.
+pub const DEFAULT_FLASH_COLOR: u32 = 0x20C040;
+
+/// How far a shake moves its part to each side, in pixels, unless the skin
+/// says otherwise.
+pub const DEFAULT_SHAKE_DISTANCE: u32 = 6;
+
+/// Opacity of a flash when it starts; it fades out from there.
+const FLASH_OPACITY: f32 = 160.0;
+
+/// Times a shake swings back and forth.
+const SHAKE_SWINGS: f32 = 3.0;
+
+/// Newton steps taken to invert a cubic Bézier's x before falling back to
+/// bisection.
+const BEZIER_NEWTON_STEPS: usize = 8;
+
+/// How the progress of an animation is shaped over its duration.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// A CSS-style cubic Bézier from (0, 0) to (1, 1) with control points
+    /// (x1, y1) and (x2, y2). A y outside 0-1 overshoots.
+    CubicBezier { x1: f32, y1: f32, x2: f32, y2: f32 },
+    /// A spring of unit mass pulled to the end, which may overshoot and
+    /// bounce back before settling. Stiffness and damping are per second,
+    /// so the same spring moves alike whatever the duration.
+    Spring { stiffness: f32, damping: f32 },
+}
+
+impl Easing {
+    /// Parse an easing written as in CSS: `linear`, `ease`, `ease-in`,
+    /// `ease-out`, `ease-in-out` or `cubic-bezier(x1, y1, x2, y2)` (as curve
+    /// editors copy it), or `spring(stiffness, damping)`. Ranges are not
+    /// checked.
+    pub fn parse(text: &str) -> Option<Self> {
+        let bezier = |x1, y1, x2, y2| Some(Easing::CubicBezier { x1, y1, x2, y2 });
+        match text.trim() {
+            "linear" => return Some(Easing::Linear),
+            "ease" => return bezier(0.25, 0.1, 0.25, 1.0),
+            "ease-in" => return bezier(0.42, 0.0, 1.0, 1.0),
+            "ease-out" => return bezier(0.0, 0.0, 0.58, 1.0),
+            "ease-in-out" => return bezier(0.42, 0.0, 0.58, 1.0),
+            _ => {}
+        }
+        let (name, arguments) = text.trim().strip_suffix(')')?.split_once('(')?;
+        let numbers = arguments
+            .split(',')
+            .map(|n| n.trim().parse::<f32>().ok().filter(|n| n.is_finite()))
+            .collect::<Option<Vec<_>>>()?;
+        match (name.trim(), numbers.as_slice()) {
+            ("cubic-bezier", &[x1, y1, x2, y2]) => bezier(x1, y1, x2, y2),
+            ("spring", &[stiffness, damping]) => Some(Easing::Spring { stiffness, damping }),
+            _ => None,
+        }
+    }
+
+    /// Shape `t`, how far through an animation of `duration` it is (0-1).
+    /// The result is 0 at the start and 1 at the end.
+    pub fn apply(&self, t: f32, duration: Duration) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        if t == 1.0 {
+            return 1.0;
+        }
+        match *self {
+            Easing::Linear => t,
+            Easing::CubicBezier { x1, y1, x2, y2 } => bezier(y1, y2, bezier_parameter(x1, x2, t)),
+            Easing::Spring { stiffness, damping } => spring(stiffness, damping, t * duration.as_secs_f32()),
+        }
+    }
+}
+
+/// Evaluate one coordinate of a Bézier from 0 to 1 with control values
+/// `p1` and `p2` at parameter `u`.
+fn bezier(p1: f32, p2: f32, u: f32) -> f32 {
+    let v = 1.0 - u;
+    3.0 * v * v * u * p1 + 3.0 * v * u * u * p2 + u * u * u
+}
+
+/// Find the parameter at which a Bézier's x (with control values `x1` and
+/// `x2`, both within 0-1 so x only grows) reaches `x`.
+fn bezier_parameter(x1: f32, x2: f32, x: f32) -> f32 {
+    let mut u = x;
+    for _ in 0..BEZIER_NEWTON_STEPS {
+        let error = bezier(x1, x2, u) - x;
+        if error.abs() < 1e-5 {
+            return u;
+        }
+        let v = 1.0 - u;
+        let slope = 3.0 * v * v * x1 + 6.0 * v * u * (x2 - x1) + 3.0 * u * u * (1.0 - x2);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        u = (u - error / slope).clamp(0.0, 1.0);
+    }
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..32 {
+        u = (low + high) / 2.0;
+        if bezier(x1, x2, u) < x {
+            low = u;
+        } else {
+            high = u;
+        }
+    }
+    u
+}
+
+/// Get the position of a spring released at rest from 0 towards 1, `secs`
+/// seconds in.
+fn spring(stiffness: f32, damping: f32, secs: f32) -> f32 {
+    let omega = stiffness.max(0.0).sqrt();
+    if omega == 0.0 {
+        return 0.0;
+    }
+    let zeta = damping.max(0.0) / (2.0 * omega);
+    if zeta < 1.0 {
+        // Underdamped: swings around the end, shrinking
+        let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+        let decay = (-zeta * omega * secs).exp();
+        1.0 - decay * ((omega_d * secs).cos() + zeta * omega / omega_d * (omega_d * secs).sin())
+    } else if zeta == 1.0 {
+        1.0 - (-omega * secs).exp() * (1.0 + omega * secs)
+    } else {
+        // Overdamped: creeps up without overshooting
+        let root = (zeta * zeta - 1.0).sqrt();
+        let (r1, r2) = (-omega * (zeta - root), -omega * (zeta + root));
+        1.0 - (r2 * (r1 * secs).exp() - r1 * (r2 * secs).exp()) / (r2 - r1)
+    }
+}
+
+/// What an animation does to its part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationEffect {
+    /// Cover the part with a color that fades out.
+    Flash { color: u32 },
+    /// Move the part from side to side, settling back in place.
+    Shake { distance: u32 },
+}
+
+/// Which store changes start an animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationTrigger {
+    /// Any change to a matching key, including it being set or removed.
+    #[default]
+    Change,
+    /// A matching key getting a new value that isn't null, empty or false.
+    Set,
+}
+
+/// An animation declared on a part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    /// Store key to watch; `*` matches any run of characters, so
+    /// `outputs.*` watches every output.
+    pub key: String,
+    pub trigger: AnimationTrigger,
+    pub effect: AnimationEffect,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Animation {
+    /// Check whether a store key matches the watched pattern.
+    fn matches(&self, key: &str) -> bool {
+        let mut pieces = self.key.split('*');
+        let first = pieces.next().unwrap_or_default();
+        let Some(mut rest) = key.strip_prefix(first) else {
+            return false;
+        };
+        let mut pieces: Vec<&str> = pieces.collect();
+        let Some(last) = pieces.pop() else {
+            // No wildcard: the key must match exactly
+            return rest.is_empty();
+        };
+        for piece in pieces {
+            match rest.find(piece) {
+                Some(index) => rest = &rest[index + piece.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    }
+
+    /// Check whether a key going from `old` to `new` starts the animation.
+    fn starts_on(&self, old: Option<&Value>, new: Option<&Value>) -> bool {
+        match self.trigger {
+            AnimationTrigger::Change => old != new,
+            AnimationTrigger::Set => old != new && new.is_some_and(is_set),
+        }
+    }
+}
+
+/// Check whether a value counts as set for `AnimationTrigger::Set`.
+fn is_set(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.is_empty(),
+        _ => true,
+    }
+}
+
+/// An animation that is playing.
+#[derive(Debug, Clone)]
+struct Playing {
+    node: NodeId,
+    effect: AnimationEffect,
+    duration: Duration,
+    easing: Easing,
+    elapsed: Duration,
+}
+
+impl Playing {
+    /// How far through the animation is, shaped by its easing: 0 at the
+    /// start and 1 at the end, possibly overshooting in between.
+    fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            1.0
+        } else {
+            let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+            self.easing.apply(t, self.duration)
+        }
+    }
+}
+
+/// Starts and advances the animations of a tree's nodes.
+#[derive(Debug, Default)]
+pub struct Animator {
+    /// Declared animations by node.
+    animations: Vec<(NodeId, Animation)>,
+    /// Values of the watched keys when the store was last observed.
+    seen: HashMap<String, Value>,
+    /// Whether the store has been observed yet; the first look only
+    /// records values, so parts don't all animate at startup.
+    primed: bool,
+    playing: Vec<Playing>,
+}
+
+impl Animator {
+    /// Create an animator with no animations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare an animation on a node.
+    pub fn add(&mut self, node: NodeId, animation: Animation) {
+        self.animations.push((node, animation));
+    }
+
+    /// Drop a removed node's animations.
+    pub fn forget(&mut self, node: NodeId) {
+        self.animations.retain(|(id, _)| *id != node);
+        self.playing.retain(|playing| playing.node != node);
+    }
+
+    /// Look for changes to the watched keys since the last call and start
+    /// the animations they trigger. Returns true if any started.
+    pub fn observe(&mut self, store: &Store) -> bool {
+        if self.animations.is_empty() {
+            return false;
+        }
+        let current: HashMap<String, Value> = store
+            .keys()
+            .filter(|key| self.animations.iter().any(|(_, animation)| animation.matches(key)))
+            .filter_map(|key| store.get(key).map(|value| (key.clone(), value.clone())))
+            .collect();
+
+        let mut started = false;
+        if self.primed {
+            let removed = self.seen.keys().filter(|key| !current.contains_key(*key));
+            for key in current.keys().chain(removed) {
+                let (old, new) = (self.seen.get(key), current.get(key));
+                for (node, animation) in &self.animations {
+                    if animation.matches(key) && animation.starts_on(old, new) {
+                        // Restart rather than stack the same effect
+                        self.playing.retain(|playing| {
+                            playing.node != *node
+                                || std::mem::discriminant(&playing.effect) != std::mem::discriminant(&animation.effect)
+                        });
+                        self.playing.push(Playing {
+                            node: *node,
+                            effect: animation.effect,
+                            duration: animation.duration,
+                            easing: animation.easing,
+                            elapsed: Duration::ZERO,
+                        });
+                        started = true;
+                    }
+                }
+            }
+        }
+        self.seen = current;
+        self.primed = true;
+        started
+    }
+
+    /// Advance the playing animations by `dt`. Returns true if any were
+    /// playing, so the tree is redrawn (once more after the last one ends).
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        if self.playing.is_empty() {
+            return false;
+        }
+        for playing in &mut self.playing {
+            playing.elapsed += dt;
+        }
+        self.playing.retain(|playing| playing.elapsed < playing.duration);
+        true
+    }
+
+    /// Check whether any animation is playing.
+    pub fn is_playing(&self) -> bool {
+        !self.playing.is_empty()
+    }
+
+    /// Get how far a node is shaken from its place, as (x, y).
+    pub fn offset(&self, node: NodeId) -> (i32, i32) {
+        let x: f32 = self
+            .playing
+            .iter()
+            .filter(|playing| playing.node == node)
+            .map(|playing| match playing.effect {
+                AnimationEffect::Shake { distance } => {
+                    let t = playing.progress();
+                    distance as f32 * (1.0 - t) * (t * SHAKE_SWINGS * TAU).sin()
+                }
+                AnimationEffect::Flash { .. } => 0.0,
+            })
+            .sum();
+        (x.round() as i32, 0)
+    }
+
+    /// Get the color and opacity (0-255) of a node's flash, if it has one.
+    pub fn flash(&self, node: NodeId) -> Option<(u32, u8)> {
+        self.playing.iter().filter(|playing| playing.node == node).find_map(|playing| match playing.effect {
+            AnimationEffect::Flash { color } => Some((color, (FLASH_OPACITY * (1.0 - playing.progress())) as u8)),
+            AnimationEffect::Shake { .. } => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_changes_start_animations() {
+        let label = NodeId(1);
+        let input = NodeId(2);
+        let mut animator = Animator::new();
+        animator.add(label, Animation {
+            key: "outputs.*".to_string(),
+            trigger: AnimationTrigger::Change,
+            effect: AnimationEffect::Flash { color: DEFAULT_FLASH_COLOR },
+            duration: DEFAULT_ANIMATION_DURATION,
+            easing: Easing::Linear,
+        });
+        animator.add(input, Animation {
+            key: "errors.email".to_string(),
+            trigger: AnimationTrigger::Set,
+            effect: AnimationEffect::Shake { distance: DEFAULT_SHAKE_DISTANCE },
+            duration: DEFAULT_ANIMATION_DURATION,
+            easing: Easing::parse("ease-out").unwrap(),
+        });
+
+        // Values present at startup don't animate
+        let mut store = Store::new();
+        store.set("outputs.result", "1");
+        assert!(!animator.observe(&store));
+
+        store.set("outputs.result", "2");
+        store.set("outputs_other", "x");
+        assert!(animator.observe(&store));
+        assert_eq!(animator.flash(label).map(|(color, _)| color), Some(DEFAULT_FLASH_COLOR));
+        assert_eq!(animator.flash(input), None);
+
+        // Clearing the error doesn't shake; setting it does
+        store.set("errors.email", "");
+        assert!(!animator.observe(&store));
+        store.set("errors.email", "Not an email address");
+        assert!(animator.observe(&store));
+        assert!(animator.tick(Duration::from_millis(30)));
+        assert_ne!(animator.offset(input), (0, 0));
+
+        // Both finish after their duration, with one last redraw
+        assert!(animator.tick(DEFAULT_ANIMATION_DURATION));
+        assert!(!animator.is_playing());
+        assert_eq!(animator.offset(input), (0, 0));
+        assert!(!animator.tick(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn test_easing_curves() {
+        let duration = Duration::from_millis(500);
+        let ease_in = Easing::parse("ease-in").unwrap();
+        assert_eq!(Easing::parse(" cubic-bezier(.42, 0, 1, 1) "), Some(ease_in));
+        assert_eq!(Easing::parse("spring(170, 26)"), Some(Easing::Spring { stiffness: 170.0, damping: 26.0 }));
+        assert_eq!(Easing::parse("cubic-bezier(0, 0, 1)"), None);
+        assert_eq!(Easing::parse("bounce"), None);
+
+        // Ease-in starts slow and ends at full progress
+        assert!(ease_in.apply(0.25, duration) < 0.25);
+        assert!((ease_in.apply(0.5, duration) - 0.3153).abs() < 1e-3);
+        assert_eq!(ease_in.apply(1.0, duration), 1.0);
+        assert_eq!(Easing::Linear.apply(0.3, duration), 0.3);
+
+        // A lightly damped spring overshoots, a heavily damped one doesn't
+        let bouncy = Easing::Spring { stiffness: 300.0, damping: 10.0 };
+        let stiff = Easing::Spring { stiffness: 300.0, damping: 60.0 };
+        let samples = |easing: Easing| (0..100).map(move |i| easing.apply(i as f32 / 100.0, duration));
+        assert!(samples(bouncy).any(|x| x > 1.05));
+        assert!(samples(stiff).all(|x| (0.0..=1.0).contains(&x)));
+        assert_eq!(bouncy.apply(0.0, duration), 0.0);
+    }
+}
@@ -1,6 +1,10 @@
+use std::time::Duration;
+
 use winit::event::WindowEvent;
+use winit::window::CursorIcon;
 
-use crate::core::View;
+use crate::core::{InertiaConfig, View};
+use crate::graphics::{Canvas, RenderStats};
 
 /// Trait for applications using the Crix framework.
 pub trait App {
@@ -12,6 +16,78 @@ pub trait App {
         let _ = event;
         false
     }
+
+    /// Called when the event loop is idle. Return true if the view needs to be
+    /// redrawn because of a change not caused by a window event (e.g. an image
+    /// handle received a new camera frame).
+    fn update(&mut self) -> bool {
+        false
+    }
+
+    /// Advance time-based state (caret blink, animations, timers) by `dt`.
+    /// The event loop calls this before `update` with the real time since the
+    /// last call; the headless harness calls it with fixed steps so tests
+    /// don't depend on the clock. Return true if the view needs to be redrawn.
+    fn tick(&mut self, dt: Duration) -> bool {
+        let _ = dt;
+        false
+    }
+
+    /// How often the event loop should wake up to call `update` without input.
+    /// `None` (the default) means the loop sleeps until the next window event.
+    fn poll_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether something on screen is in motion (an animation, a sliding
+    /// switch, a scrolling marquee). While it is, the event loop wakes once
+    /// per frame of the monitor the window is on, so motion is as smooth on a
+    /// 144 Hz display as on a 60 Hz one; `tick` gets the real time between
+    /// frames either way, so it runs at the same speed.
+    fn is_animating(&self) -> bool {
+        false
+    }
+
+    /// Take on the drag inertia settings of the window's `RunConfig`,
+    /// before the window opens.
+    fn set_inertia(&mut self, inertia: InertiaConfig) {
+        let _ = inertia;
+    }
+
+    /// Mouse cursor to show over the window, checked after each event.
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Default
+    }
+
+    /// Whether the window should start moving with the pointer, checked
+    /// after each event. Windows without a title bar return true once for a
+    /// press on their drag strip.
+    fn take_window_drag(&mut self) -> bool {
+        false
+    }
+
+    /// Whether the window should move to the middle of its monitor, checked
+    /// after each event and update. Lets a window dragged out of reach be
+    /// brought back.
+    fn take_window_center(&mut self) -> bool {
+        false
+    }
+
+    /// Whether the window should close, checked after each event and tick.
+    /// Windows without a title bar return true once to offer their own way
+    /// of closing.
+    fn take_window_close(&mut self) -> bool {
+        false
+    }
+
+    /// Immediate-mode drawing hook, called every frame after the view paints.
+    /// Use this for debug visualizations or effects that don't warrant a widget.
+    fn overlay(&mut self, canvas: &mut Canvas) {
+        let _ = canvas;
+    }
+
+    /// Called after each frame is shown with what drawing it took.
+    fn rendered(&mut self, _stats: &RenderStats) {}
 }
 
 /// A simple app runner that wraps a View without event handling.
@@ -0,0 +1,304 @@
+//! Store keys shared between running apps.
+//!
+//! An app publishing a channel writes the store keys matching its `[publish]
+//! keys` prefixes to a JSON file named after the channel whenever they
+//! change, in a directory shared by all crix apps. Apps subscribed to the
+//! channel read the file back and hold its values under `<channel>.<key>`,
+//! so a media player publishing `player.title` on `nowplaying` shows up as
+//! `nowplaying.player.title` in a visualizer widget. Files are replaced
+//! whole, so readers never see half an update, and a publisher removes its
+//! file when it closes, clearing the values from its subscribers. A channel
+//! has one publisher; a second one overwrites the first's values.
+//!
+//! The file also holds the time it was written, and publishers rewrite it
+//! every `CHANNEL_HEARTBEAT_INTERVAL` even when nothing changed. A file left
+//! behind by a publisher that crashed stops being rewritten, and subscribers
+//! drop its values once it is `CHANNEL_STALE_AFTER` old.
+//!
+//! ```text
+//! <data dir>/crix/.channels/
+//! └─ nowplaying.json   {"heartbeat": <unix seconds>, "values": {...}}
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use super::persist::{data_root, is_safe_name, matches_prefix};
+use crate::core::{Store, Value};
+
+/// How often a publisher rewrites its channel file when nothing changed.
+pub const CHANNEL_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Age of a channel file after which its publisher is taken to be gone.
+pub const CHANNEL_STALE_AFTER: Duration = Duration::from_secs(15);
+
+/// Errors that can occur when sharing store keys on a channel.
+#[derive(Debug)]
+pub enum ChannelError {
+    /// Reading or writing a channel file failed.
+    Io(std::io::Error),
+    /// A channel file is not valid JSON.
+    Json(serde_json::Error),
+    /// Channel names may only contain letters, digits, '-' and '_'.
+    InvalidName(String),
+}
+
+impl std::fmt::Display for ChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelError::Io(e) => write!(f, "Channel IO error: {}", e),
+            ChannelError::Json(e) => write!(f, "Invalid channel data: {}", e),
+            ChannelError::InvalidName(name) => write!(f, "Invalid channel name: '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for ChannelError {}
+
+impl From<std::io::Error> for ChannelError {
+    fn from(e: std::io::Error) -> Self {
+        ChannelError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ChannelError {
+    fn from(e: serde_json::Error) -> Self {
+        ChannelError::Json(e)
+    }
+}
+
+/// Contents of a channel file.
+#[derive(Debug, Deserialize)]
+struct ChannelFile {
+    /// When the publisher wrote the file, in seconds since the Unix epoch.
+    heartbeat: u64,
+    values: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ChannelFile {
+    /// Check whether the publisher has gone without rewriting the file.
+    fn is_stale(&self) -> bool {
+        unix_now().saturating_sub(self.heartbeat) > CHANNEL_STALE_AFTER.as_secs()
+    }
+}
+
+/// Get the current time in seconds since the Unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+/// Get the directory channel files are kept in.
+pub fn channels_dir() -> PathBuf {
+    // App data directories never start with '.', so this can't be one
+    data_root().join(".channels")
+}
+
+/// Check that a channel name is safe to use as a file name.
+pub fn validate_channel(name: &str) -> Result<(), ChannelError> {
+    if is_safe_name(name) {
+        Ok(())
+    } else {
+        Err(ChannelError::InvalidName(name.to_string()))
+    }
+}
+
+/// Get the file of `channel` in `dir`.
+fn channel_file(dir: &Path, channel: &str) -> PathBuf {
+    dir.join(format!("{}.json", channel))
+}
+
+/// Writes selected store keys to a channel for other apps to read.
+#[derive(Debug)]
+pub struct Publisher {
+    /// Channel file.
+    path: PathBuf,
+    /// Key prefixes to publish.
+    keys: Vec<String>,
+    /// Values as last written, or None before the first write.
+    published: Option<HashMap<String, Value>>,
+    /// When the file was last written.
+    written: Instant,
+}
+
+impl Publisher {
+    /// Create a publisher writing keys that equal or start with one of
+    /// `keys` followed by a '.' to `channel` in `dir`.
+    pub fn new(dir: &Path, channel: &str, keys: Vec<String>) -> Result<Self, ChannelError> {
+        validate_channel(channel)?;
+        Ok(Self { path: channel_file(dir, channel), keys, published: None, written: Instant::now() })
+    }
+
+    /// Get the time until the file is due to be rewritten to show the
+    /// publisher is still running.
+    pub fn time_until_heartbeat(&self) -> Duration {
+        CHANNEL_HEARTBEAT_INTERVAL.saturating_sub(self.written.elapsed())
+    }
+
+    /// Write the published keys if they changed since the last write, or
+    /// if the heartbeat is due. Returns true if the file was written.
+    pub fn publish_if_changed(&mut self, store: &Store) -> Result<bool, ChannelError> {
+        let values: HashMap<String, Value> = store
+            .keys()
+            .filter(|key| matches_prefix(key, &self.keys))
+            .filter_map(|key| Some((key.clone(), store.get(key)?.clone())))
+            .collect();
+        if self.published.as_ref() == Some(&values) && !self.time_until_heartbeat().is_zero() {
+            return Ok(false);
+        }
+
+        let json: serde_json::Map<String, serde_json::Value> = values
+            .iter()
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect();
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Replace the file in one step so subscribers never read half of it
+        let temp = self.path.with_extension("json.tmp");
+        let file = serde_json::json!({ "heartbeat": unix_now(), "values": json });
+        fs::write(&temp, serde_json::to_string(&file)?)?;
+        fs::rename(&temp, &self.path)?;
+        self.published = Some(values);
+        self.written = Instant::now();
+        Ok(true)
+    }
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        if self.published.is_some() {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Reads a channel another app publishes into the store.
+#[derive(Debug)]
+pub struct Subscriber {
+    channel: String,
+    /// Channel file.
+    path: PathBuf,
+    /// File contents as last read, or None while there is no file.
+    contents: Option<String>,
+    /// The contents parsed, or None if there is no file or it is invalid.
+    file: Option<ChannelFile>,
+    /// Whether the store holds the file's values, i.e. it was there and fresh.
+    live: bool,
+    /// Store keys set from the channel.
+    keys: Vec<String>,
+}
+
+impl Subscriber {
+    /// Create a subscriber to `channel` in `dir`.
+    pub fn new(dir: &Path, channel: &str) -> Result<Self, ChannelError> {
+        validate_channel(channel)?;
+        Ok(Self {
+            channel: channel.to_string(),
+            path: channel_file(dir, channel),
+            contents: None,
+            file: None,
+            live: false,
+            keys: Vec::new(),
+        })
+    }
+
+    /// Get the channel name.
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Read the channel and bring its values in the store up to date:
+    /// `<channel>.<key>` for each published key, with keys no longer
+    /// published (or all of them, once the publisher closes or its file goes
+    /// stale) removed. Returns true if the store changed.
+    pub fn poll(&mut self, store: &mut Store) -> Result<bool, ChannelError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let read = contents != self.contents;
+        if read {
+            // Remember the contents even if they don't parse, so a bad file
+            // is reported once rather than on every poll
+            self.contents = contents;
+            self.file = None;
+            if let Some(contents) = &self.contents {
+                self.file = Some(serde_json::from_str(contents)?);
+            }
+        }
+        let live = self.file.as_ref().is_some_and(|file| !file.is_stale());
+        if !read && live == self.live {
+            return Ok(false);
+        }
+        self.live = live;
+
+        for key in self.keys.drain(..) {
+            store.remove(&key);
+        }
+        if let Some(file) = self.file.as_ref().filter(|_| live) {
+            for (key, value) in &file.values {
+                let key = format!("{}.{}", self.channel, key);
+                store.set(key.clone(), Value::from_json(value.clone()));
+                self.keys.push(key);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriber_follows_publisher() {
+        let dir = std::env::temp_dir().join(format!("crix-channels-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut player = Store::new();
+        player.set("player.title", "Intro");
+        player.set("player.artist", "The xx");
+        player.set("volume", 3.0);
+        let mut publisher = Publisher::new(&dir, "nowplaying", vec!["player".to_string()]).unwrap();
+        let mut subscriber = Subscriber::new(&dir, "nowplaying").unwrap();
+        let mut widget = Store::new();
+
+        // Nothing is published until the publisher writes
+        assert!(!subscriber.poll(&mut widget).unwrap());
+        assert!(publisher.publish_if_changed(&player).unwrap());
+        assert!(!publisher.publish_if_changed(&player).unwrap());
+        assert!(subscriber.poll(&mut widget).unwrap());
+        assert_eq!(widget.get_str("nowplaying.player.title"), "Intro");
+        assert!(!widget.contains("nowplaying.volume"));
+        assert!(!subscriber.poll(&mut widget).unwrap());
+
+        // Keys no longer published go away, as does everything once the publisher closes
+        player.remove("player.artist");
+        publisher.publish_if_changed(&player).unwrap();
+        subscriber.poll(&mut widget).unwrap();
+        assert!(!widget.contains("nowplaying.player.artist"));
+        drop(publisher);
+        assert!(subscriber.poll(&mut widget).unwrap());
+        assert!(!widget.contains("nowplaying.player.title"));
+
+        // A file its publisher stopped rewriting, e.g. after a crash, is dropped
+        let file = |age: u64| {
+            serde_json::json!({ "heartbeat": unix_now() - age, "values": { "player.title": "Outro" } })
+        };
+        fs::write(channel_file(&dir, "nowplaying"), file(0).to_string()).unwrap();
+        subscriber.poll(&mut widget).unwrap();
+        assert_eq!(widget.get_str("nowplaying.player.title"), "Outro");
+        fs::write(channel_file(&dir, "nowplaying"), file(60).to_string()).unwrap();
+        assert!(subscriber.poll(&mut widget).unwrap());
+        assert!(!widget.contains("nowplaying.player.title"));
+        assert!(!subscriber.poll(&mut widget).unwrap());
+
+        assert!(matches!(Subscriber::new(&dir, "../evil"), Err(ChannelError::InvalidName(_))));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,116 @@
+//! Exact decimal arithmetic on store values.
+//!
+//! Floats can't hold most decimal fractions exactly, so a sum such as
+//! 0.1 + 0.2 shows as 0.30000000000000004. Apps that show money or
+//! measurements keep those values as `Value::Decimal`, or as decimal
+//! strings, and do their arithmetic with these helpers instead. Plain
+//! numbers are taken as the shortest text that reads back as them, so 0.1
+//! counts as exactly 0.1.
+
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use super::Value;
+
+/// Most decimal places results can be rounded to.
+pub const MAX_DECIMAL_PLACES: u32 = 28;
+
+/// Errors in decimal arithmetic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecimalError {
+    /// The value isn't a number or a decimal string.
+    NotANumber(String),
+    /// The result is too large to hold.
+    Overflow,
+    DivisionByZero,
+}
+
+impl std::fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecimalError::NotANumber(text) => write!(f, "Not a decimal number: '{}'", text),
+            DecimalError::Overflow => write!(f, "Decimal result is too large"),
+            DecimalError::DivisionByZero => write!(f, "Decimal division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalError {}
+
+/// Parse decimal text such as `12.50`, `-3` or `1.5e3`.
+pub fn parse_decimal(text: &str) -> Option<Decimal> {
+    let text = text.trim();
+    Decimal::from_str(text).or_else(|_| Decimal::from_scientific(text)).ok()
+}
+
+/// Get a store value as a decimal: decimals as they are, numbers by their
+/// shortest text and strings parsed.
+pub fn to_decimal(value: &Value) -> Result<Decimal, DecimalError> {
+    let decimal = match value {
+        Value::Decimal(d) => Some(*d),
+        Value::Number(n) if n.is_finite() => parse_decimal(&n.to_string()),
+        Value::String(s) => parse_decimal(s),
+        _ => None,
+    };
+    decimal.ok_or_else(|| DecimalError::NotANumber(value.to_string_value()))
+}
+
+/// Add two values exactly.
+pub fn add(a: &Value, b: &Value) -> Result<Decimal, DecimalError> {
+    to_decimal(a)?.checked_add(to_decimal(b)?).ok_or(DecimalError::Overflow)
+}
+
+/// Subtract `b` from `a` exactly.
+pub fn sub(a: &Value, b: &Value) -> Result<Decimal, DecimalError> {
+    to_decimal(a)?.checked_sub(to_decimal(b)?).ok_or(DecimalError::Overflow)
+}
+
+/// Multiply two values exactly.
+pub fn mul(a: &Value, b: &Value) -> Result<Decimal, DecimalError> {
+    to_decimal(a)?.checked_mul(to_decimal(b)?).ok_or(DecimalError::Overflow)
+}
+
+/// Divide `a` by `b`, to 28 significant digits where the result doesn't end
+/// sooner.
+pub fn div(a: &Value, b: &Value) -> Result<Decimal, DecimalError> {
+    let (a, b) = (to_decimal(a)?, to_decimal(b)?);
+    if b.is_zero() {
+        return Err(DecimalError::DivisionByZero);
+    }
+    a.checked_div(b).map(|d| d.normalize()).ok_or(DecimalError::Overflow)
+}
+
+/// Round a value to `places` decimal places, halves away from zero as on a
+/// calculator. The result keeps exactly `places` places, so 2.5 to two
+/// places is 2.50.
+pub fn round(value: &Value, places: u32) -> Result<Decimal, DecimalError> {
+    let places = places.min(MAX_DECIMAL_PLACES);
+    let mut rounded = to_decimal(value)?.round_dp_with_strategy(places, RoundingStrategy::MidpointAwayFromZero);
+    rounded.rescale(places);
+    Ok(rounded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_arithmetic() {
+        let text = |d: Result<Decimal, DecimalError>| d.unwrap().to_string();
+        assert_eq!(text(add(&Value::Number(0.1), &Value::Number(0.2))), "0.3");
+        assert_eq!(text(sub(&"10.00".into(), &Value::Number(0.01))), "9.99");
+        assert_eq!(text(mul(&"19.99".into(), &Value::Number(3.0))), "59.97");
+        assert_eq!(text(div(&Value::Number(1.0), &Value::Number(4.0))), "0.25");
+        assert_eq!(text(div(&Value::Number(10.0), &Value::Number(3.0))), "3.3333333333333333333333333333");
+        assert_eq!(text(round(&"2.345".into(), 2)), "2.35");
+        assert_eq!(text(round(&"-2.5".into(), 0)), "-3");
+        assert_eq!(text(round(&"2.5".into(), 2)), "2.50");
+        assert_eq!(text(add(&"1.5e3".into(), &Value::Decimal(Decimal::ONE))), "1501");
+
+        assert_eq!(div(&Value::Number(1.0), &"0".into()), Err(DecimalError::DivisionByZero));
+        assert_eq!(add(&"abc".into(), &Value::Number(1.0)), Err(DecimalError::NotANumber("abc".to_string())));
+        assert!(add(&Value::Number(f64::NAN), &Value::Number(1.0)).is_err());
+        assert_eq!(mul(&Value::Decimal(Decimal::MAX), &Value::Number(2.0)), Err(DecimalError::Overflow));
+    }
+}
@@ -0,0 +1,179 @@
+//! Store history for time-travel debugging.
+//!
+//! The history is a list of frames, each a snapshot of the store taken after
+//! it changed, with the time since recording started and a label (usually the
+//! action that made the change). Seeking to a frame gives back its snapshot
+//! so the app can restore the store and re-render the UI as it was then.
+//! Recording is suspended while a past frame is shown.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::store::{Store, StoreChange, StoreSnapshot};
+
+/// Frames kept by default before the oldest are dropped.
+pub const DEFAULT_HISTORY_LIMIT: usize = 500;
+
+/// One recorded state of the store.
+#[derive(Debug, Clone)]
+pub struct HistoryFrame {
+    /// Time since recording started.
+    pub at: Duration,
+    /// What made the change (an action name, or e.g. "input").
+    pub label: String,
+    /// Changes from the previous frame.
+    pub changes: Vec<StoreChange>,
+    snapshot: StoreSnapshot,
+}
+
+impl HistoryFrame {
+    /// Get the store values as they were at this frame.
+    pub fn snapshot(&self) -> &StoreSnapshot {
+        &self.snapshot
+    }
+}
+
+/// Recorded store states and the frame being viewed.
+#[derive(Debug)]
+pub struct StoreHistory {
+    started: Instant,
+    frames: VecDeque<HistoryFrame>,
+    limit: usize,
+    /// Frame being viewed, or None when following the live store.
+    cursor: Option<usize>,
+}
+
+impl Default for StoreHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
+
+impl StoreHistory {
+    /// Create an empty history keeping at most `limit` frames.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            started: Instant::now(),
+            frames: VecDeque::new(),
+            limit: limit.max(1),
+            cursor: None,
+        }
+    }
+
+    /// Record the store if it changed since the last frame. Nothing is
+    /// recorded while viewing a past frame. Returns true if a frame was added.
+    pub fn record(&mut self, label: impl Into<String>, store: &Store) -> bool {
+        if self.cursor.is_some() {
+            return false;
+        }
+        let snapshot = store.snapshot();
+        let changes = match self.frames.back() {
+            Some(last) if last.snapshot == snapshot => return false,
+            Some(last) => Store::diff(&last.snapshot, &snapshot),
+            None => Store::diff(&StoreSnapshot::default(), &snapshot),
+        };
+        self.frames.push_back(HistoryFrame {
+            at: self.started.elapsed(),
+            label: label.into(),
+            changes,
+            snapshot,
+        });
+        if self.frames.len() > self.limit {
+            self.frames.pop_front();
+        }
+        true
+    }
+
+    /// Get the recorded frames, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = &HistoryFrame> {
+        self.frames.iter()
+    }
+
+    /// Get a frame by index.
+    pub fn get(&self, index: usize) -> Option<&HistoryFrame> {
+        self.frames.get(index)
+    }
+
+    /// Get the number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Get the index of the frame being viewed, or None when live.
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    /// Get the index of the frame the store is at: the viewed frame, or
+    /// the newest one when live.
+    pub fn position(&self) -> Option<usize> {
+        self.cursor.or_else(|| self.frames.len().checked_sub(1))
+    }
+
+    /// Check if a past frame is being viewed.
+    pub fn is_scrubbing(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    /// View a frame (clamped to the recorded range) and get its snapshot.
+    pub fn seek(&mut self, index: usize) -> Option<&StoreSnapshot> {
+        let last = self.frames.len().checked_sub(1)?;
+        let index = index.min(last);
+        self.cursor = Some(index);
+        Some(&self.frames[index].snapshot)
+    }
+
+    /// Move the viewed frame by `delta` frames (negative goes back).
+    pub fn step(&mut self, delta: isize) -> Option<&StoreSnapshot> {
+        let position = self.position()?;
+        self.seek(position.saturating_add_signed(delta))
+    }
+
+    /// Stop viewing past frames and get the newest snapshot to restore.
+    pub fn live(&mut self) -> Option<&StoreSnapshot> {
+        self.cursor = None;
+        self.frames.back().map(|frame| &frame.snapshot)
+    }
+
+    /// Continue recording from the viewed frame, dropping the frames after it.
+    pub fn resume_here(&mut self) {
+        if let Some(cursor) = self.cursor.take() {
+            self.frames.truncate(cursor + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_seek() {
+        let mut store = Store::new();
+        let mut history = StoreHistory::new(10);
+        store.set("count", 1.0);
+        assert!(history.record("start", &store));
+        assert!(!history.record("input", &store));
+        store.set("count", 2.0);
+        assert!(history.record("increment", &store));
+        assert_eq!(history.get(1).unwrap().changes.len(), 1);
+
+        // Viewing the past restores its values and suspends recording
+        let snapshot = history.step(-1).unwrap().clone();
+        store.restore(&snapshot);
+        assert_eq!(store.get_number("count"), Some(1.0));
+        store.set("count", 5.0);
+        assert!(!history.record("input", &store));
+
+        // Resuming drops the frames after the viewed one
+        history.resume_here();
+        assert!(history.record("branch", &store));
+        let labels: Vec<_> = history.frames().map(|frame| frame.label.as_str()).collect();
+        assert_eq!(labels, ["start", "branch"]);
+    }
+}
@@ -0,0 +1,220 @@
+//! Drag inertia.
+//!
+//! Widgets moved by dragging (scroll views, knobs) keep moving after a flick
+//! and slow down smoothly, as on a touch screen. `Inertia` measures how fast
+//! the pointer moved over the last moments of a drag and, once it is let go,
+//! carries the motion on at a speed that decays exponentially. Time comes
+//! from the widget's `tick`, so the headless harness can step it exactly.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How long before the latest drag sample the pointer's speed is measured
+/// over. A pointer held still for longer before release doesn't flick.
+const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+/// Speed, in units per second, below which a flick comes to rest.
+const MIN_VELOCITY: f32 = 20.0;
+
+/// How drag-driven widgets behave after they are let go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InertiaConfig {
+    /// Whether a flick keeps the widget moving after release.
+    pub enabled: bool,
+    /// How quickly a flick slows down, per second: the speed falls to about
+    /// a third each `1 / friction` seconds, so higher stops sooner.
+    pub friction: f32,
+    /// Whether scroll views can be pulled and flicked past their ends, and
+    /// spring back.
+    pub bounce: bool,
+}
+
+impl Default for InertiaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            friction: 4.0,
+            bounce: false,
+        }
+    }
+}
+
+impl InertiaConfig {
+    /// Settings that stop widgets as soon as they are let go.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    /// Set how quickly a flick slows down, per second.
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        if friction > 0.0 {
+            self.friction = friction;
+        }
+        self
+    }
+
+    /// Set whether scroll views bounce at their ends.
+    pub fn with_bounce(mut self, bounce: bool) -> Self {
+        self.bounce = bounce;
+        self
+    }
+}
+
+/// Tracks a drag's speed and carries it on after release.
+#[derive(Debug, Clone, Default)]
+pub struct Inertia {
+    config: InertiaConfig,
+    /// Time ticked so far, which drag samples are stamped with.
+    clock: Duration,
+    /// Recent (time, position) samples of the drag.
+    samples: VecDeque<(Duration, f32)>,
+    /// Speed of the flick in progress, in units per second.
+    velocity: f32,
+}
+
+impl Inertia {
+    /// Create a tracker with the given settings.
+    pub fn new(config: InertiaConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Get the settings.
+    pub fn config(&self) -> &InertiaConfig {
+        &self.config
+    }
+
+    /// Change the settings, stopping any flick.
+    pub fn set_config(&mut self, config: InertiaConfig) {
+        self.config = config;
+        self.stop();
+    }
+
+    /// Advance the clock drag samples are stamped with. Call from `tick`.
+    pub fn advance(&mut self, dt: Duration) {
+        self.clock += dt;
+    }
+
+    /// Record the dragged position, in whatever units the widget moves in.
+    /// The first sample of a drag also stops any flick in progress.
+    pub fn track(&mut self, position: f32) {
+        self.velocity = 0.0;
+        let clock = self.clock;
+        self.samples.retain(|(time, _)| clock.saturating_sub(*time) <= VELOCITY_WINDOW);
+        self.samples.push_back((clock, position));
+    }
+
+    /// End the drag, starting a flick at the speed the position was last
+    /// moving at. Returns true if a flick started.
+    pub fn release(&mut self) -> bool {
+        let velocity = self.drag_velocity();
+        self.samples.clear();
+        if self.config.enabled && velocity.abs() >= MIN_VELOCITY {
+            self.velocity = velocity;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Stop the flick in progress and forget the drag.
+    pub fn stop(&mut self) {
+        self.velocity = 0.0;
+        self.samples.clear();
+    }
+
+    /// Check whether a flick is in progress.
+    pub fn is_flinging(&self) -> bool {
+        self.velocity != 0.0
+    }
+
+    /// Get the speed of the flick in progress, in units per second.
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Move the flick on by `dt`, slowing it down `drag` times as hard as
+    /// usual. Returns how far it went.
+    pub fn step(&mut self, dt: Duration, drag: f32) -> f32 {
+        if self.velocity == 0.0 {
+            return 0.0;
+        }
+        // Integrate v(t) = v0 * e^(-kt) over the step
+        let k = self.config.friction.max(f32::EPSILON) * drag;
+        let decay = (-k * dt.as_secs_f32()).exp();
+        let distance = self.velocity * (1.0 - decay) / k;
+        self.velocity *= decay;
+        if self.velocity.abs() < MIN_VELOCITY {
+            self.velocity = 0.0;
+        }
+        distance
+    }
+
+    /// Speed of the drag over its last samples, or 0 if it was held still.
+    fn drag_velocity(&self) -> f32 {
+        let (Some(&(first_time, first)), Some(&(last_time, last))) = (self.samples.front(), self.samples.back()) else {
+            return 0.0;
+        };
+        let span = last_time.saturating_sub(first_time);
+        if span.is_zero() || self.clock.saturating_sub(last_time) > VELOCITY_WINDOW {
+            return 0.0;
+        }
+        (last - first) / span.as_secs_f32()
+    }
+}
+
+/// How far content pulled `excess` past its end shows past it, giving more
+/// resistance the further it goes and never reaching `limit`.
+pub fn rubber_band(excess: f32, limit: f32) -> f32 {
+    if limit <= 0.0 {
+        return 0.0;
+    }
+    let shown = limit * (1.0 - 1.0 / (excess.abs() / limit + 1.0));
+    shown.copysign(excess)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flick_slows_to_rest() {
+        let mut inertia = Inertia::new(InertiaConfig::default());
+        for position in [0.0, 10.0, 20.0, 30.0] {
+            inertia.track(position);
+            inertia.advance(Duration::from_millis(10));
+        }
+        // 30 units over the 30 ms between the first and last samples
+        assert!(inertia.release());
+        assert!((inertia.velocity() - 1000.0).abs() < 1.0);
+
+        let mut travelled = 0.0;
+        while inertia.is_flinging() {
+            travelled += inertia.step(Duration::from_millis(16), 1.0);
+        }
+        // A flick at v with friction k covers about v / k
+        assert!((travelled - 245.0).abs() < 5.0, "travelled {}", travelled);
+
+        // Holding still before letting go doesn't flick
+        inertia.track(0.0);
+        inertia.advance(Duration::from_millis(10));
+        inertia.track(50.0);
+        inertia.advance(Duration::from_millis(200));
+        assert!(!inertia.release());
+
+        let mut inertia = Inertia::new(InertiaConfig::disabled());
+        inertia.track(0.0);
+        inertia.advance(Duration::from_millis(10));
+        inertia.track(50.0);
+        assert!(!inertia.release());
+
+        assert_eq!(rubber_band(0.0, 40.0), 0.0);
+        assert!((rubber_band(40.0, 40.0) - 20.0).abs() < 1e-4);
+        assert!((rubber_band(-40.0, 40.0) + 20.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,171 @@
+//! Part geometry.
+//!
+//! A node can keep the geometry it was placed with: a position relative to
+//! its parent and a width and height that may be fixed, a share of the
+//! parent, derived from the other side by an aspect ratio, or measured from
+//! the widget's content. The tree resolves it again whenever the window is
+//! resized, so layouts can follow the window instead of fixed pixel boxes.
+//!
+//! The window's edges may have insets: strips covered by a notch, cut off by
+//! rounded corners or kept for dragging a window without a title bar. Nodes
+//! placed in the safe area are resolved in the part of their parent inside
+//! the insets, so controls stay clear of them.
+
+use crate::core::{Constraints, Rect, Widget};
+
+/// A width or height.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Length {
+    /// The widget's intrinsic size.
+    #[default]
+    Auto,
+    /// A fixed number of pixels.
+    Pixels(u32),
+    /// A fraction of the parent's size.
+    Percent(f32),
+    /// The rest of the parent past the node's position.
+    Fill,
+    /// Derived from the other side by a width:height ratio.
+    Aspect(f32, f32),
+}
+
+impl Length {
+    /// Get the fixed size in pixels, or 0 if the length isn't fixed.
+    pub fn pixels(self) -> u32 {
+        match self {
+            Length::Pixels(pixels) => pixels,
+            _ => 0,
+        }
+    }
+}
+
+/// Where a node goes in its parent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Geometry {
+    /// Offset from the parent's top-left corner.
+    pub x: i32,
+    pub y: i32,
+    pub width: Length,
+    pub height: Length,
+    /// Limits on the resolved size, applied last.
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
+    /// Whether the node is placed in the part of its parent inside the
+    /// window's safe area rather than in the whole parent.
+    pub safe_area: bool,
+}
+
+impl Geometry {
+    /// Create a geometry with a fixed position and size.
+    pub fn new(x: i32, y: i32, width: Length, height: Length) -> Self {
+        Self { x, y, width, height, ..Default::default() }
+    }
+
+    /// Get the area the node is placed in: `parent`, or the part of it in
+    /// `safe_area` for nodes kept inside the insets.
+    pub fn area(&self, parent: &Rect, safe_area: &Rect) -> Rect {
+        if !self.safe_area {
+            return *parent;
+        }
+        // A parent wholly outside the safe area leaves an empty area at the
+        // nearest corner of the safe area
+        parent.intersect(safe_area).unwrap_or_else(|| {
+            let x = parent.x.clamp(safe_area.x, safe_area.right());
+            let y = parent.y.clamp(safe_area.y, safe_area.bottom());
+            Rect::new(x, y, 0, 0)
+        })
+    }
+
+    /// Get the size in `parent` as far as it is known without the widget:
+    /// sides sized to content (and aspect sides derived from them) are 0.
+    pub fn fixed_size(&self, parent: &Rect) -> (u32, u32) {
+        let (x, y) = (parent.x + self.x, parent.y + self.y);
+        let side = |length: Length, size: u32, start: i32, end: i32| match length {
+            Length::Pixels(pixels) => pixels,
+            Length::Percent(fraction) => (size as f32 * fraction).round() as u32,
+            Length::Fill => end.saturating_sub(start).max(0) as u32,
+            Length::Auto | Length::Aspect(..) => 0,
+        };
+        let width = side(self.width, parent.width, x, parent.right());
+        let height = side(self.height, parent.height, y, parent.bottom());
+        self.with_aspect((width, height))
+    }
+
+    /// Get the bounds in `parent`, measuring `widget` for sides sized to
+    /// content within the space left in the parent.
+    pub fn resolve(&self, parent: &Rect, widget: &dyn Widget) -> Rect {
+        let (x, y) = (parent.x + self.x, parent.y + self.y);
+        let (mut width, mut height) = self.fixed_size(parent);
+        if self.width == Length::Auto || self.height == Length::Auto {
+            let space = |end: i32, start: i32| end.saturating_sub(start).max(0) as u32;
+            let max_width = match self.width {
+                Length::Auto => space(parent.right(), x).min(self.max_width.unwrap_or(u32::MAX)),
+                _ => width,
+            };
+            let max_height = match self.height {
+                Length::Auto => space(parent.bottom(), y).min(self.max_height.unwrap_or(u32::MAX)),
+                _ => height,
+            };
+            let (content_width, content_height) = widget.intrinsic_size(Constraints::new(max_width, max_height));
+            if self.width == Length::Auto {
+                width = content_width;
+            }
+            if self.height == Length::Auto {
+                height = content_height;
+            }
+            (width, height) = self.with_aspect((width, height));
+        }
+        let width = width.max(self.min_width.unwrap_or(0)).min(self.max_width.unwrap_or(u32::MAX));
+        let height = height.max(self.min_height.unwrap_or(0)).min(self.max_height.unwrap_or(u32::MAX));
+        Rect::new(x, y, width, height)
+    }
+
+    /// Derive an aspect-ratio side from the other side.
+    fn with_aspect(&self, (width, height): (u32, u32)) -> (u32, u32) {
+        let scale = |side: u32, ratio: f32| (side as f32 * ratio).round() as u32;
+        match (self.width, self.height) {
+            (Length::Aspect(w, h), _) => (scale(height, w / h), height),
+            (_, Length::Aspect(w, h)) => (width, scale(width, h / w)),
+            _ => (width, height),
+        }
+    }
+}
+
+/// Strips along the window's edges that controls should stay out of, in
+/// pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Insets {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+impl Insets {
+    /// Create insets from the sizes of the top, right, bottom and left strips.
+    pub fn new(top: u32, right: u32, bottom: u32, left: u32) -> Self {
+        Self { top, right, bottom, left }
+    }
+
+    /// Combine two sets of insets, keeping the wider strip on each side.
+    pub fn max(self, other: Insets) -> Self {
+        Self {
+            top: self.top.max(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+            left: self.left.max(other.left),
+        }
+    }
+
+    /// Get the part of `rect` inside the insets.
+    pub fn shrink(&self, rect: &Rect) -> Rect {
+        Rect::new(
+            rect.x + self.left.min(rect.width) as i32,
+            rect.y + self.top.min(rect.height) as i32,
+            rect.width.saturating_sub(self.left.saturating_add(self.right)),
+            rect.height.saturating_sub(self.top.saturating_add(self.bottom)),
+        )
+    }
+}
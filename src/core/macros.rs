@@ -0,0 +1,225 @@
+//! Recorded action macros.
+//!
+//! A macro is a named sequence of actions (with their payloads) recorded
+//! while the user works and replayed later from a button or hotkey. Macros
+//! are saved as JSON next to the active profile's persisted store data:
+//!
+//! ```json
+//! {
+//!   "fill_test_data": [
+//!     { "action": "load_defaults", "payload": {} },
+//!     { "action": "calculate", "payload": { "mode": "fast" } }
+//!   ]
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use super::action::Action;
+use super::persist::PersistError;
+use super::store::Value;
+
+/// Action name prefix that plays the macro named by the rest (`macro:<name>`).
+pub const MACRO_PREFIX: &str = "macro:";
+
+/// Check if an action controls macros itself. These are never recorded, so
+/// a macro can't start or replay recordings.
+pub fn is_macro_action(name: &str) -> bool {
+    name.starts_with("macro.") || name.starts_with(MACRO_PREFIX)
+}
+
+/// Named action sequences and the recording in progress.
+#[derive(Debug, Default)]
+pub struct Macros {
+    macros: BTreeMap<String, Vec<Action>>,
+    /// Name and steps of the macro being recorded.
+    recording: Option<(String, Vec<Action>)>,
+}
+
+impl Macros {
+    /// Create an empty macro set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load macros from a JSON file. A missing file loads no macros.
+    pub fn load(path: &Path) -> Result<Self, PersistError> {
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let json: BTreeMap<String, Vec<serde_json::Value>> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let macros = json
+            .into_iter()
+            .map(|(name, steps)| (name, steps.into_iter().filter_map(json_to_action).collect()))
+            .collect();
+        Ok(Self {
+            macros,
+            recording: None,
+        })
+    }
+
+    /// Save all macros to a JSON file.
+    pub fn save(&self, path: &Path) -> Result<(), PersistError> {
+        let json: BTreeMap<&String, Vec<serde_json::Value>> = self
+            .macros
+            .iter()
+            .map(|(name, steps)| (name, steps.iter().map(action_to_json).collect()))
+            .collect();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&json)?)?;
+        Ok(())
+    }
+
+    /// Start recording a macro, discarding any unfinished recording.
+    pub fn start_recording(&mut self, name: impl Into<String>) {
+        self.recording = Some((name.into(), Vec::new()));
+    }
+
+    /// Get the name of the macro being recorded.
+    pub fn recording(&self) -> Option<&str> {
+        self.recording.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    /// Append an action to the recording. Macro actions are skipped.
+    pub fn record(&mut self, action: &Action) {
+        if let Some((_, steps)) = &mut self.recording
+            && !is_macro_action(&action.name)
+        {
+            steps.push(action.clone());
+        }
+    }
+
+    /// Finish the recording, replacing any macro with the same name.
+    /// Returns the name, or None if nothing was recorded.
+    pub fn stop_recording(&mut self) -> Option<String> {
+        let (name, steps) = self.recording.take()?;
+        if steps.is_empty() {
+            return None;
+        }
+        self.macros.insert(name.clone(), steps);
+        Some(name)
+    }
+
+    /// Get the steps of a macro.
+    pub fn get(&self, name: &str) -> Option<&[Action]> {
+        self.macros.get(name).map(Vec::as_slice)
+    }
+
+    /// Remove a macro. Returns true if it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.macros.remove(name).is_some()
+    }
+
+    /// Get the macro names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.macros.keys()
+    }
+}
+
+fn action_to_json(action: &Action) -> serde_json::Value {
+    let payload: serde_json::Map<String, serde_json::Value> = action
+        .payload
+        .iter()
+        .map(|(key, value)| (key.clone(), value.to_json()))
+        .collect();
+    serde_json::json!({ "action": action.name, "payload": payload })
+}
+
+fn json_to_action(json: serde_json::Value) -> Option<Action> {
+    let serde_json::Value::Object(mut step) = json else {
+        return None;
+    };
+    let name = step.remove("action")?.as_str()?.to_string();
+    let payload: HashMap<_, _> = match step.remove("payload") {
+        Some(serde_json::Value::Object(payload)) => {
+            payload.into_iter().map(|(key, value)| (key, Value::from_json(value))).collect()
+        }
+        _ => HashMap::new(),
+    };
+    Some(Action::with_payload(name, payload))
+}
+
+/// A keyboard shortcut such as `"Ctrl+Shift+1"` or `"F5"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// The Windows/Command key.
+    pub logo: bool,
+    /// Lowercase key name: a character ("1", "m") or a named key ("f5").
+    pub key: String,
+}
+
+impl Hotkey {
+    /// Parse a `+`-separated shortcut. Modifiers (`ctrl`, `alt`, `shift`,
+    /// `cmd`/`super`) are case-insensitive; the last part is the key.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut hotkey = Hotkey {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            logo: false,
+            key: String::new(),
+        };
+        let mut parts: Vec<String> = text.split('+').map(|part| part.trim().to_lowercase()).collect();
+        let key = parts.pop().filter(|key| !key.is_empty())?;
+        for modifier in parts {
+            match modifier.as_str() {
+                "ctrl" | "control" => hotkey.ctrl = true,
+                "alt" | "option" => hotkey.alt = true,
+                "shift" => hotkey.shift = true,
+                "cmd" | "super" | "logo" | "meta" => hotkey.logo = true,
+                _ => return None,
+            }
+        }
+        hotkey.key = key;
+        Some(hotkey)
+    }
+
+    /// Check a key press against the shortcut. `key` is compared case-insensitively.
+    pub fn matches(&self, ctrl: bool, alt: bool, shift: bool, logo: bool, key: &str) -> bool {
+        self.ctrl == ctrl
+            && self.alt == alt
+            && self.shift == shift
+            && self.logo == logo
+            && self.key.eq_ignore_ascii_case(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_reload_macro() {
+        let mut macros = Macros::new();
+        macros.record(&Action::new("ignored"));
+        assert_eq!(macros.stop_recording(), None);
+
+        macros.start_recording("fill");
+        macros.record(&Action::new("load_defaults"));
+        macros.record(&Action::new("macro.stop"));
+        macros.record(&Action::new("calculate").with("mode", "fast"));
+        assert_eq!(macros.stop_recording(), Some("fill".to_string()));
+
+        let path = std::env::temp_dir().join(format!("crix-macros-{}.json", std::process::id()));
+        macros.save(&path).unwrap();
+        let reloaded = Macros::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let steps = reloaded.get("fill").unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[1].name, "calculate");
+        assert_eq!(steps[1].get_str("mode"), Some("fast"));
+
+        let hotkey = Hotkey::parse("Ctrl+Shift+1").unwrap();
+        assert!(hotkey.matches(true, false, true, false, "1"));
+        assert!(!hotkey.matches(true, false, false, false, "1"));
+        assert!(Hotkey::parse("Hyper+1").is_none());
+    }
+}
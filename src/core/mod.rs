@@ -1,17 +1,41 @@
 mod action;
+mod animation;
 mod app;
+mod channel;
+pub mod decimal;
+mod history;
+mod inertia;
+mod layout;
+mod macros;
 mod node;
+mod persist;
 mod rect;
 mod store;
+mod timer;
 mod tree;
 mod view;
 mod widget;
 
 pub use action::{Action, ActionDispatcher, ActionError, ActionHandler, Services};
+pub use animation::{
+    Animation, AnimationEffect, AnimationTrigger, Animator, Easing, DEFAULT_ANIMATION_DURATION, DEFAULT_FLASH_COLOR,
+    DEFAULT_SHAKE_DISTANCE,
+};
 pub use app::{App, AppRunner};
-pub use node::{Node, NodeId};
+pub use channel::{
+    channels_dir, validate_channel, ChannelError, Publisher, Subscriber, CHANNEL_HEARTBEAT_INTERVAL, CHANNEL_STALE_AFTER,
+};
+pub use decimal::{parse_decimal, to_decimal, DecimalError, MAX_DECIMAL_PLACES};
+pub use history::{HistoryFrame, StoreHistory};
+pub use inertia::{rubber_band, Inertia, InertiaConfig};
+pub use layout::{Geometry, Insets, Length};
+pub use macros::{is_macro_action, Hotkey, Macros, MACRO_PREFIX};
+pub use node::{MenuItem, Node, NodeId};
+pub use persist::{PersistError, ProfileStore, DEFAULT_PROFILE};
+pub(crate) use persist::is_safe_name;
 pub use rect::Rect;
-pub use store::{Store, Value};
-pub use tree::UiTree;
+pub use store::{Store, StoreChange, StoreSnapshot, Value};
+pub use timer::{Timers, TIMER_RESOLUTION};
+pub use tree::{TreeError, UiTree};
 pub use view::View;
-pub use widget::{KeyCode, Widget, WidgetEvent, WidgetState};
+pub use widget::{Constraints, KeyCode, Widget, WidgetEvent, WidgetState};
@@ -1,4 +1,5 @@
-use crate::core::{Rect, Widget};
+use crate::core::{Geometry, Rect, Widget};
+use crate::graphics::ColorEffect;
 
 /// A handle to a node in the UI tree.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -10,12 +11,36 @@ impl NodeId {
     }
 }
 
+/// An entry of a node's context menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    /// Text shown in the menu.
+    pub label: String,
+    /// Action dispatched when the entry is chosen.
+    pub action: String,
+}
+
 /// A node in the UI tree.
 pub struct Node {
     pub(crate) widget: Box<dyn Widget>,
     pub(crate) children: Vec<NodeId>,
     pub(crate) parent: Option<NodeId>,
     pub(crate) bounds: Rect,
+    /// Set once the node has been given bounds, so its first layout counts
+    /// as a size change.
+    pub(crate) laid_out: bool,
+    /// Where the node goes in its parent when the tree is laid out again.
+    pub(crate) geometry: Option<Geometry>,
+    /// Name used to look the node up (the skin part id).
+    pub(crate) name: Option<String>,
+    /// Text shown in a tooltip while the node is hovered.
+    pub(crate) tooltip: Option<String>,
+    /// Entries of the menu opened by right-clicking the node.
+    pub(crate) context_menu: Vec<MenuItem>,
+    /// Hidden nodes (and their children) are not drawn or hit tested.
+    pub(crate) visible: bool,
+    /// Recoloring of everything the node and its children draw.
+    pub(crate) effect: Option<ColorEffect>,
 }
 
 impl Node {
@@ -25,6 +50,13 @@ impl Node {
             children: Vec::new(),
             parent: None,
             bounds: Rect::default(),
+            laid_out: false,
+            geometry: None,
+            name: None,
+            tooltip: None,
+            context_menu: Vec::new(),
+            visible: true,
+            effect: None,
         }
     }
 
@@ -40,6 +72,30 @@ impl Node {
         self.parent
     }
 
+    pub fn geometry(&self) -> Option<&Geometry> {
+        self.geometry.as_ref()
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn tooltip(&self) -> Option<&str> {
+        self.tooltip.as_deref()
+    }
+
+    pub fn context_menu(&self) -> &[MenuItem] {
+        &self.context_menu
+    }
+
+    pub fn effect(&self) -> Option<&ColorEffect> {
+        self.effect.as_ref()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
     pub fn widget(&self) -> &dyn Widget {
         &*self.widget
     }
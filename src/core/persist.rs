@@ -0,0 +1,273 @@
+//! Persisted store data with per-user profiles.
+//!
+//! Store keys matching the bundle's `[persist] keys` prefixes are saved to a
+//! JSON file in the app's data directory and restored on the next launch.
+//! Each profile has its own file, so users of a shared machine keep separate
+//! settings:
+//!
+//! ```text
+//! <data dir>/crix/<app name>/
+//! ├─ last_profile             # name of the profile used last
+//! └─ profiles/
+//!    ├─ default/
+//!    │  ├─ store.json
+//!    │  └─ macros.json        # recorded macros, see `Macros`
+//!    └─ alice/store.json
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::core::{Store, Value};
+
+/// Profile used when none has been selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Errors that can occur when loading or saving persisted data.
+#[derive(Debug)]
+pub enum PersistError {
+    /// Reading or writing a profile file failed.
+    Io(std::io::Error),
+    /// A profile file is not valid JSON.
+    Json(serde_json::Error),
+    /// Profile names may only contain letters, digits, '-' and '_'.
+    InvalidProfile(String),
+}
+
+impl std::fmt::Display for PersistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersistError::Io(e) => write!(f, "Persistence IO error: {}", e),
+            PersistError::Json(e) => write!(f, "Invalid persisted data: {}", e),
+            PersistError::InvalidProfile(name) => write!(f, "Invalid profile name: '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<std::io::Error> for PersistError {
+    fn from(e: std::io::Error) -> Self {
+        PersistError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(e: serde_json::Error) -> Self {
+        PersistError::Json(e)
+    }
+}
+
+/// Saves and restores selected store keys, one file per profile.
+#[derive(Debug)]
+pub struct ProfileStore {
+    /// App data directory.
+    dir: PathBuf,
+    /// Key prefixes to persist.
+    keys: Vec<String>,
+    /// Active profile name.
+    current: String,
+    /// Persisted values as last written, to skip redundant saves.
+    saved: HashMap<String, Value>,
+}
+
+impl ProfileStore {
+    /// Create a profile store in `dir`, persisting keys that equal or start
+    /// with one of `keys` followed by a '.'.
+    ///
+    /// The active profile is the one used last, or `DEFAULT_PROFILE`.
+    pub fn new(dir: impl Into<PathBuf>, keys: Vec<String>) -> Self {
+        let dir = dir.into();
+        let current = fs::read_to_string(dir.join("last_profile"))
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| validate_profile(name).is_ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+        Self {
+            dir,
+            keys,
+            current,
+            saved: HashMap::new(),
+        }
+    }
+
+    /// Get the default data directory for an app:
+    /// `$XDG_DATA_HOME` or `~/.local/share` on Linux,
+    /// `~/Library/Application Support` on macOS and `%APPDATA%` on Windows.
+    pub fn app_data_dir(app_name: &str) -> PathBuf {
+        let dir_name: String = app_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        data_root().join(dir_name)
+    }
+
+    /// Get the active profile name.
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    /// List the names of all profiles with saved data, sorted.
+    pub fn profiles(&self) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(self.dir.join("profiles"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| validate_profile(name).is_ok())
+            .collect();
+        if !names.iter().any(|name| name == &self.current) {
+            names.push(self.current.clone());
+        }
+        names.sort();
+        names
+    }
+
+    /// Check if a store key is persisted.
+    pub fn is_persisted(&self, key: &str) -> bool {
+        matches_prefix(key, &self.keys)
+    }
+
+    /// Load the active profile's values into the store.
+    /// A profile without saved data loads nothing.
+    pub fn load(&mut self, store: &mut Store) -> Result<(), PersistError> {
+        let path = self.profile_file(&self.current);
+        self.saved = if path.exists() {
+            let json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            json.into_iter()
+                .filter(|(key, _)| self.is_persisted(key))
+                .map(|(key, value)| (key, Value::from_json(value)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        for (key, value) in &self.saved {
+            store.set(key.clone(), value.clone());
+        }
+        Ok(())
+    }
+
+    /// Save the persisted keys if they changed since the last load or save.
+    /// Returns true if the file was written.
+    pub fn save_if_changed(&mut self, store: &Store) -> Result<bool, PersistError> {
+        let values: HashMap<String, Value> = store
+            .keys()
+            .filter(|key| self.is_persisted(key))
+            .filter_map(|key| Some((key.clone(), store.get(key)?.clone())))
+            .collect();
+        if values == self.saved {
+            return Ok(false);
+        }
+
+        let json: serde_json::Map<String, serde_json::Value> = values
+            .iter()
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect();
+        let path = self.profile_file(&self.current);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(&json)?)?;
+        self.saved = values;
+        Ok(true)
+    }
+
+    /// Save the active profile, then switch to `name`: persisted keys are
+    /// cleared from the store and replaced with the new profile's values.
+    /// The choice is remembered for the next launch.
+    pub fn switch(&mut self, name: &str, store: &mut Store) -> Result<(), PersistError> {
+        validate_profile(name)?;
+        self.save_if_changed(store)?;
+
+        let persisted: Vec<String> = store.keys().filter(|key| self.is_persisted(key)).cloned().collect();
+        for key in persisted {
+            store.remove(&key);
+        }
+
+        self.current = name.to_string();
+        self.load(store)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join("last_profile"), &self.current)?;
+        Ok(())
+    }
+
+    /// Get the active profile's directory, for data saved alongside the
+    /// persisted store (such as macros).
+    pub fn profile_dir(&self) -> PathBuf {
+        self.dir.join("profiles").join(&self.current)
+    }
+
+    fn profile_file(&self, name: &str) -> PathBuf {
+        self.dir.join("profiles").join(name).join("store.json")
+    }
+}
+
+/// Get the directory all crix apps keep their data in.
+pub(crate) fn data_root() -> PathBuf {
+    let env_dir = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_DATA_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".local/share")))
+    };
+    base.unwrap_or_else(std::env::temp_dir).join("crix")
+}
+
+/// Check if `key` equals one of `prefixes` or starts with one followed by a '.'.
+pub(crate) fn matches_prefix(key: &str, prefixes: &[String]) -> bool {
+    prefixes.iter().any(|prefix| {
+        key.strip_prefix(prefix.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Check that a name is safe to use as a file or directory name.
+pub(crate) fn is_safe_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Check that a profile name is safe to use as a directory name.
+fn validate_profile(name: &str) -> Result<(), PersistError> {
+    if is_safe_name(name) {
+        Ok(())
+    } else {
+        Err(PersistError::InvalidProfile(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_keep_separate_values() {
+        let dir = std::env::temp_dir().join(format!("crix-persist-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut profiles = ProfileStore::new(&dir, vec!["settings".to_string()]);
+        let mut store = Store::new();
+
+        store.set("settings.volume", 7.0);
+        store.set("scratch", "not persisted");
+        profiles.switch("alice", &mut store).unwrap();
+        assert!(!store.contains("settings.volume"));
+        assert_eq!(store.get_str("scratch"), "not persisted");
+
+        store.set("settings.volume", 2.0);
+        profiles.switch(DEFAULT_PROFILE, &mut store).unwrap();
+        assert_eq!(store.get_number("settings.volume"), Some(7.0));
+
+        // The last used profile is restored on the next launch
+        let mut reopened = ProfileStore::new(&dir, vec!["settings".to_string()]);
+        assert_eq!(reopened.current(), DEFAULT_PROFILE);
+        assert_eq!(reopened.profiles(), vec!["alice", "default"]);
+        reopened.switch("alice", &mut store).unwrap();
+        assert_eq!(store.get_number("settings.volume"), Some(2.0));
+
+        assert!(profiles.switch("../evil", &mut store).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
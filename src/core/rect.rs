@@ -31,4 +31,18 @@ impl Rect {
     pub fn bottom(&self) -> i32 {
         self.y + self.height as i32
     }
+
+    /// Move the rectangle by an offset.
+    pub fn translate(&self, dx: i32, dy: i32) -> Rect {
+        Rect::new(self.x + dx, self.y + dy, self.width, self.height)
+    }
+
+    /// Get the overlapping area of two rectangles, if any.
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        (right > x && bottom > y).then(|| Rect::new(x, y, (right - x) as u32, (bottom - y) as u32))
+    }
 }
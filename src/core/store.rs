@@ -1,4 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::graphics::ImageHandle;
+
+/// Key of the JSON object a decimal is written as, holding its digits.
+const DECIMAL_TAG: &str = "$dec";
 
 /// A dynamic value that can be stored in the Store.
 /// Designed to be language-agnostic for future scripting support.
@@ -7,6 +15,8 @@ pub enum Value {
     Null,
     Bool(bool),
     Number(f64),
+    /// An exact decimal number, for money and measurements (see `core::decimal`).
+    Decimal(Decimal),
     String(String),
 }
 
@@ -21,6 +31,11 @@ impl Value {
         Value::Number(n)
     }
 
+    /// Create a Value from a decimal number.
+    pub fn decimal(d: Decimal) -> Self {
+        Value::Decimal(d)
+    }
+
     /// Create a Value from a bool.
     pub fn bool(b: bool) -> Self {
         Value::Bool(b)
@@ -34,10 +49,11 @@ impl Value {
         }
     }
 
-    /// Try to get as a number.
+    /// Try to get as a number. Decimals are converted to the nearest float.
     pub fn as_number(&self) -> Option<f64> {
         match self {
             Value::Number(n) => Some(*n),
+            Value::Decimal(d) => d.to_f64(),
             _ => None,
         }
     }
@@ -68,6 +84,8 @@ impl Value {
                     n.to_string()
                 }
             }
+            // Decimals keep their places, so 2.50 shows as 2.50
+            Value::Decimal(d) => d.to_string(),
             Value::String(s) => s.clone(),
         }
     }
@@ -76,10 +94,41 @@ impl Value {
     pub fn try_parse_number(&self) -> Option<f64> {
         match self {
             Value::Number(n) => Some(*n),
+            Value::Decimal(d) => d.to_f64(),
             Value::String(s) => s.parse().ok(),
             _ => None,
         }
     }
+
+    /// Convert to a JSON value. Non-finite numbers become null, and decimals
+    /// become `{"$dec": "2.50"}` objects so they read back exactly.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(b) => serde_json::Value::Bool(*b),
+            Value::Number(n) => serde_json::Number::from_f64(*n)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Value::Decimal(d) => serde_json::json!({ DECIMAL_TAG: d.to_string() }),
+            Value::String(s) => serde_json::Value::String(s.clone()),
+        }
+    }
+
+    /// Convert from a JSON value. Arrays and objects other than decimals
+    /// written by `to_json` become null.
+    pub fn from_json(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Number(n) => n.as_f64().map(Value::Number).unwrap_or_default(),
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Object(object) if object.len() == 1 => object
+                .get(DECIMAL_TAG)
+                .and_then(|d| d.as_str()?.parse().ok())
+                .map(Value::Decimal)
+                .unwrap_or_default(),
+            _ => Value::Null,
+        }
+    }
 }
 
 impl Default for Value {
@@ -112,17 +161,83 @@ impl From<i32> for Value {
     }
 }
 
+impl From<Decimal> for Value {
+    fn from(d: Decimal) -> Self {
+        Value::Decimal(d)
+    }
+}
+
 impl From<bool> for Value {
     fn from(b: bool) -> Self {
         Value::Bool(b)
     }
 }
 
+/// A copy of the store's values at one point in time, for diffing.
+/// Image handles are not included.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StoreSnapshot {
+    values: BTreeMap<String, Value>,
+}
+
+impl StoreSnapshot {
+    /// Get a value as it was when the snapshot was taken.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Get the number of values in the snapshot.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if the snapshot has no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// A difference between two store snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoreChange {
+    /// The key was set.
+    Added { key: String, value: Value },
+    /// The key was removed.
+    Removed { key: String, old: Value },
+    /// The key's value changed.
+    Changed { key: String, old: Value, new: Value },
+}
+
+impl StoreChange {
+    /// Get the key that changed.
+    pub fn key(&self) -> &str {
+        match self {
+            StoreChange::Added { key, .. }
+            | StoreChange::Removed { key, .. }
+            | StoreChange::Changed { key, .. } => key,
+        }
+    }
+}
+
+impl std::fmt::Display for StoreChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreChange::Added { key, value } => write!(f, "+ {} = {}", key, value.to_json()),
+            StoreChange::Removed { key, old } => write!(f, "- {} (was {})", key, old.to_json()),
+            StoreChange::Changed { key, old, new } => {
+                write!(f, "~ {}: {} -> {}", key, old.to_json(), new.to_json())
+            }
+        }
+    }
+}
+
 /// Centralized key-value store for application state.
 /// Widgets read/write named keys; actions process and update state.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Store {
     data: HashMap<String, Value>,
+    /// Image handles registered by Rust code, keyed like values.
+    images: HashMap<String, ImageHandle>,
 }
 
 impl Store {
@@ -130,6 +245,7 @@ impl Store {
     pub fn new() -> Self {
         Self {
             data: HashMap::new(),
+            images: HashMap::new(),
         }
     }
 
@@ -184,12 +300,146 @@ impl Store {
         self.data.keys()
     }
 
-    /// Clear all data.
+    /// Take a snapshot of all values.
+    pub fn snapshot(&self) -> StoreSnapshot {
+        StoreSnapshot {
+            values: self.data.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+        }
+    }
+
+    /// Replace all values with those of a snapshot. Image handles are kept.
+    pub fn restore(&mut self, snapshot: &StoreSnapshot) {
+        self.data = snapshot.values.iter().map(|(key, value)| (key.clone(), value.clone())).collect();
+    }
+
+    /// List what changed from snapshot `a` to snapshot `b`, sorted by key.
+    pub fn diff(a: &StoreSnapshot, b: &StoreSnapshot) -> Vec<StoreChange> {
+        let mut changes = Vec::new();
+        for (key, old) in &a.values {
+            match b.values.get(key) {
+                None => changes.push(StoreChange::Removed {
+                    key: key.clone(),
+                    old: old.clone(),
+                }),
+                Some(new) if new != old => changes.push(StoreChange::Changed {
+                    key: key.clone(),
+                    old: old.clone(),
+                    new: new.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (key, value) in &b.values {
+            if !a.values.contains_key(key) {
+                changes.push(StoreChange::Added {
+                    key: key.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+        changes.sort_by(|x, y| x.key().cmp(y.key()));
+        changes
+    }
+
+    /// Export the values under `prefix` (the key itself and keys starting
+    /// with `prefix.`) as a pretty-printed JSON object. An empty prefix
+    /// exports everything. Image handles are not included.
+    pub fn to_json(&self, prefix: &str) -> String {
+        let json: serde_json::Map<String, serde_json::Value> = self
+            .data
+            .iter()
+            .filter(|(key, _)| key_has_prefix(key, prefix))
+            .map(|(key, value)| (key.clone(), value.to_json()))
+            .collect();
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    }
+
+    /// Import values from a JSON object, keeping only keys under `prefix`
+    /// (everything if empty). Existing keys are overwritten; others are kept.
+    /// Returns the number of values imported.
+    pub fn from_json(&mut self, json: &str, prefix: &str) -> Result<usize, serde_json::Error> {
+        let json: serde_json::Map<String, serde_json::Value> = serde_json::from_str(json)?;
+        let mut count = 0;
+        for (key, value) in json {
+            if key_has_prefix(&key, prefix) {
+                self.data.insert(key, Value::from_json(value));
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Register an image handle under a key.
+    /// Image widgets bound to the key display the handle's current contents.
+    pub fn set_image(&mut self, key: impl Into<String>, handle: ImageHandle) {
+        self.images.insert(key.into(), handle);
+    }
+
+    /// Get a registered image handle by key.
+    pub fn get_image(&self, key: &str) -> Option<&ImageHandle> {
+        self.images.get(key)
+    }
+
+    /// Remove a registered image handle.
+    pub fn remove_image(&mut self, key: &str) -> Option<ImageHandle> {
+        self.images.remove(key)
+    }
+
+    /// Clear all data, including registered images.
     pub fn clear(&mut self) {
         self.data.clear();
+        self.images.clear();
+    }
+
+    /// Move entry `from` of the store list `list` (`<list>.<n>.*`) to index
+    /// `to`, shifting the entries between along by one. Returns false,
+    /// changing nothing, if either index is past `<list>.count`.
+    pub fn move_list_entry(&mut self, list: &str, from: usize, to: usize) -> bool {
+        let count = self.get_number(&format!("{}.count", list)).unwrap_or(0.0).max(0.0) as usize;
+        if from >= count || to >= count {
+            return false;
+        }
+        if from == to {
+            return true;
+        }
+
+        // Take out every key of the entries that move, then put them back
+        // under their new indices
+        let (low, high) = (from.min(to), from.max(to));
+        let mut entries: Vec<Vec<(String, Value)>> = (low..=high)
+            .map(|index| {
+                let prefix = format!("{}.{}", list, index);
+                let keys: Vec<String> = self.data.keys().filter(|key| key_has_prefix(key, &prefix)).cloned().collect();
+                keys.into_iter()
+                    .map(|key| {
+                        let value = self.data.remove(&key).unwrap_or(Value::Null);
+                        (key[prefix.len()..].to_string(), value)
+                    })
+                    .collect()
+            })
+            .collect();
+        if from < to {
+            entries.rotate_left(1);
+        } else {
+            entries.rotate_right(1);
+        }
+        for (offset, entry) in entries.into_iter().enumerate() {
+            for (rest, value) in entry {
+                self.data.insert(format!("{}.{}{}", list, low + offset, rest), value);
+            }
+        }
+        true
     }
 }
 
+/// Check if `key` is `prefix` or one of its dotted children.
+fn key_has_prefix(key: &str, prefix: &str) -> bool {
+    prefix.is_empty()
+        || key
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +456,74 @@ mod tests {
         assert!(store.get_bool("active"));
     }
 
+    #[test]
+    fn test_store_json_prefix() {
+        let mut store = Store::new();
+        store.set("inputs.name", "Ada");
+        store.set("inputs.age", 36.0);
+        store.set("inputs_extra", true);
+
+        let json = store.to_json("inputs");
+        let mut copy = Store::new();
+        assert_eq!(copy.from_json(&json, "").unwrap(), 2);
+        assert_eq!(copy.get_str("inputs.name"), "Ada");
+        assert_eq!(copy.get_number("inputs.age"), Some(36.0));
+        assert!(!copy.contains("inputs_extra"));
+
+        // Keys outside the prefix are ignored on import
+        assert_eq!(copy.from_json(r#"{"inputs.name": "Grace", "other": 1}"#, "inputs").unwrap(), 1);
+        assert_eq!(copy.get_str("inputs.name"), "Grace");
+        assert!(!copy.contains("other"));
+        assert!(copy.from_json("[1, 2]", "").is_err());
+
+        // Decimals keep their places through JSON
+        store.set("inputs.price", "2.50".parse::<Decimal>().unwrap());
+        copy.from_json(&store.to_json("inputs"), "").unwrap();
+        assert_eq!(copy.get_string("inputs.price"), "2.50");
+        assert_eq!(copy.get("inputs.price"), store.get("inputs.price"));
+    }
+
+    #[test]
+    fn test_snapshot_diff() {
+        let mut store = Store::new();
+        store.set("a", 1.0);
+        store.set("b", "kept");
+        store.set("c", true);
+        let before = store.snapshot();
+
+        store.set("a", 2.0);
+        store.remove("c");
+        store.set("d", "new");
+        let changes = Store::diff(&before, &store.snapshot());
+
+        let lines: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(lines, vec!["~ a: 1.0 -> 2.0", "- c (was true)", "+ d = \"new\""]);
+        assert!(Store::diff(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_move_list_entry() {
+        let mut store = Store::new();
+        store.set("tasks.count", 4.0);
+        for (i, name) in ["a", "b", "c", "d"].iter().enumerate() {
+            store.set(format!("tasks.{}.name", i), *name);
+        }
+        store.set("tasks.0.children.count", 1.0);
+        store.set("tasks.10", "not an entry of the first four");
+
+        let names = |store: &Store| (0..4).map(|i| store.get_string(&format!("tasks.{}.name", i))).collect::<Vec<_>>();
+        assert!(store.move_list_entry("tasks", 0, 2));
+        assert_eq!(names(&store), ["b", "c", "a", "d"]);
+        // Nested keys move with their entry
+        assert_eq!(store.get_number("tasks.2.children.count"), Some(1.0));
+        assert!(!store.contains("tasks.0.children.count"));
+
+        assert!(store.move_list_entry("tasks", 3, 0));
+        assert_eq!(names(&store), ["d", "b", "c", "a"]);
+        assert_eq!(store.get_str("tasks.10"), "not an entry of the first four");
+        assert!(!store.move_list_entry("tasks", 1, 4));
+    }
+
     #[test]
     fn test_value_conversions() {
         let v = Value::string("42");
@@ -217,4 +535,24 @@ mod tests {
         let v = Value::number(42.0);
         assert_eq!(v.to_string_value(), "42");
     }
+
+    #[test]
+    fn test_store_image_handles() {
+        use crate::graphics::Image;
+
+        let image = Image::from_rgba(vec![255, 0, 0, 255], 1, 1).unwrap();
+        let handle = ImageHandle::new(image);
+
+        let mut store = Store::new();
+        store.set_image("camera.frame", handle.clone());
+        assert_eq!(store.get_image("camera.frame").unwrap().version(), 0);
+
+        // Updates through the original handle are visible via the store
+        handle.set(Image::from_rgba(vec![0; 16], 2, 2).unwrap());
+        let stored = store.get_image("camera.frame").unwrap();
+        assert_eq!(stored.version(), 1);
+        assert_eq!(stored.size(), (2, 2));
+
+        assert!(Image::from_rgba(vec![0; 3], 1, 1).is_none());
+    }
 }
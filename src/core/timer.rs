@@ -0,0 +1,179 @@
+//! Named countdowns and stopwatches.
+//!
+//! Timers are started, stopped and reset by name. One started with a
+//! duration counts down and stops by itself at zero; one without counts up
+//! until stopped. Each timer keeps its time in the store, in tenths of a
+//! second, so parts bound to the keys follow it without any script running
+//! every frame:
+//!
+//! - `<name>.elapsed` - seconds run so far, not counting time stopped
+//! - `<name>.remaining` - seconds left, for countdowns
+//! - `<name>.running` - whether the timer is running
+//! - `<name>.done` - whether a countdown has reached zero
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::Store;
+
+/// Smallest step the store values of a timer move by.
+pub const TIMER_RESOLUTION: Duration = Duration::from_millis(100);
+
+/// One countdown or stopwatch.
+#[derive(Debug)]
+struct Timer {
+    /// Time counted down from, or None for a stopwatch.
+    duration: Option<Duration>,
+    elapsed: Duration,
+    running: bool,
+}
+
+impl Timer {
+    /// Check whether a countdown has reached zero.
+    fn is_done(&self) -> bool {
+        self.duration.is_some_and(|duration| self.elapsed >= duration)
+    }
+
+    /// Get the elapsed and remaining time in whole tenths of a second. The
+    /// remaining time rounds up, so a countdown only shows zero once done.
+    fn tenths(&self) -> (u128, Option<u128>) {
+        let step = TIMER_RESOLUTION.as_millis();
+        let remaining = self.duration.map(|duration| duration.saturating_sub(self.elapsed).as_millis().div_ceil(step));
+        (self.elapsed.as_millis() / step, remaining)
+    }
+}
+
+/// The timers of an app, by name.
+#[derive(Debug, Default)]
+pub struct Timers {
+    timers: BTreeMap<String, Timer>,
+}
+
+impl Timers {
+    /// Create an empty set of timers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start timer `name`. A stopped timer carries on where it stopped; a
+    /// new or reset timer, or a finished countdown, starts from the
+    /// beginning, counting down from `duration` if given and up otherwise.
+    pub fn start(&mut self, name: &str, duration: Option<Duration>, store: &mut Store) {
+        let timer = self
+            .timers
+            .entry(name.to_string())
+            .or_insert(Timer { duration, elapsed: Duration::ZERO, running: false });
+        if timer.elapsed.is_zero() || timer.is_done() {
+            timer.duration = duration;
+            timer.elapsed = Duration::ZERO;
+        }
+        timer.running = true;
+        publish(name, timer, store);
+    }
+
+    /// Stop timer `name`, keeping its time. Returns false if there is no
+    /// such timer.
+    pub fn stop(&mut self, name: &str, store: &mut Store) -> bool {
+        let Some(timer) = self.timers.get_mut(name) else {
+            return false;
+        };
+        timer.running = false;
+        publish(name, timer, store);
+        true
+    }
+
+    /// Stop timer `name` and set it back to the beginning. Returns false if
+    /// there is no such timer.
+    pub fn reset(&mut self, name: &str, store: &mut Store) -> bool {
+        let Some(timer) = self.timers.get_mut(name) else {
+            return false;
+        };
+        timer.running = false;
+        timer.elapsed = Duration::ZERO;
+        publish(name, timer, store);
+        true
+    }
+
+    /// Check whether any timer is running.
+    pub fn is_running(&self) -> bool {
+        self.timers.values().any(|timer| timer.running)
+    }
+
+    /// Advance the running timers by `dt`, writing their store keys when they
+    /// move on a tenth of a second. Returns whether the store changed, and
+    /// the names of the countdowns that reached zero.
+    pub fn tick(&mut self, dt: Duration, store: &mut Store) -> (bool, Vec<String>) {
+        let mut changed = false;
+        let mut finished = Vec::new();
+        for (name, timer) in self.timers.iter_mut().filter(|(_, timer)| timer.running) {
+            let before = timer.tenths();
+            timer.elapsed += dt;
+            if let Some(duration) = timer.duration
+                && timer.elapsed >= duration
+            {
+                timer.elapsed = duration;
+                timer.running = false;
+                finished.push(name.clone());
+            }
+            if timer.tenths() != before || !timer.running {
+                publish(name, timer, store);
+                changed = true;
+            }
+        }
+        (changed, finished)
+    }
+}
+
+/// Write the store keys of timer `name`.
+fn publish(name: &str, timer: &Timer, store: &mut Store) {
+    let (elapsed, remaining) = timer.tenths();
+    store.set(format!("{}.elapsed", name), elapsed as f64 / 10.0);
+    match remaining {
+        Some(remaining) => store.set(format!("{}.remaining", name), remaining as f64 / 10.0),
+        None => {
+            store.remove(&format!("{}.remaining", name));
+        }
+    }
+    store.set(format!("{}.running", name), timer.running);
+    store.set(format!("{}.done", name), timer.is_done());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_countdown_and_stopwatch() {
+        let mut timers = Timers::new();
+        let mut store = Store::new();
+        timers.start("egg", Some(Duration::from_secs(3)), &mut store);
+        timers.start("lap", None, &mut store);
+        assert_eq!(store.get_number("egg.remaining"), Some(3.0));
+        assert!(!store.contains("lap.remaining"));
+
+        // Nothing moves until a tenth of a second has passed
+        assert!(!timers.tick(Duration::from_millis(40), &mut store).0);
+        assert_eq!(timers.tick(Duration::from_millis(1210), &mut store), (true, Vec::new()));
+        assert_eq!(store.get_number("egg.remaining"), Some(1.8));
+        assert_eq!(store.get_number("lap.elapsed"), Some(1.2));
+
+        // Stopped timers hold their time, and carry on from it
+        timers.stop("lap", &mut store);
+        timers.tick(Duration::from_secs(1), &mut store);
+        assert_eq!(store.get_number("lap.elapsed"), Some(1.2));
+        timers.start("lap", None, &mut store);
+        timers.tick(Duration::from_millis(100), &mut store);
+        assert_eq!(store.get_number("lap.elapsed"), Some(1.3));
+
+        // A countdown stops at zero, and starts over from a new duration when started again
+        assert_eq!(timers.tick(Duration::from_secs(5), &mut store).1, ["egg"]);
+        assert_eq!(store.get_number("egg.remaining"), Some(0.0));
+        assert!(store.get_bool("egg.done") && !store.get_bool("egg.running"));
+        timers.start("egg", Some(Duration::from_secs(4)), &mut store);
+        assert_eq!((store.get_number("egg.remaining"), store.get_bool("egg.done")), (Some(4.0), false));
+
+        assert!(timers.reset("lap", &mut store));
+        assert_eq!(store.get_number("lap.elapsed"), Some(0.0));
+        assert!(!timers.stop("missing", &mut store));
+    }
+}
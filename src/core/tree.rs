@@ -1,5 +1,51 @@
-use crate::core::{Node, NodeId, Rect, Widget, WidgetState};
-use crate::graphics::Canvas;
+use std::time::Duration;
+
+use crate::core::{Animation, Animator, Geometry, InertiaConfig, Insets, MenuItem, Node, NodeId, Rect, Store, Widget, WidgetState};
+use crate::graphics::{Canvas, ColorEffect};
+
+/// A broken tree invariant, found by `UiTree::check_invariants`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    /// A node lists a child that isn't in the tree.
+    MissingChild { parent: NodeId, child: NodeId },
+    /// A node's parent link doesn't match the children lists.
+    ParentMismatch { child: NodeId, parent: Option<NodeId> },
+    /// A node is listed as a child more than once.
+    DuplicateChild(NodeId),
+    /// Following parent links from a node loops back on itself.
+    Cycle(NodeId),
+    /// A slot is both in use and on the free list, or on it twice.
+    FreeSlot(usize),
+    /// The root, hover, press, focus or capture refers to a removed node.
+    DeadReference { field: &'static str, id: NodeId },
+    /// The root node has a parent.
+    RootHasParent(NodeId),
+    /// A child of a clipping node lies outside its clip area.
+    OutsideClip { parent: NodeId, child: NodeId },
+}
+
+impl std::fmt::Display for TreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeError::MissingChild { parent, child } => {
+                write!(f, "{:?} lists missing child {:?}", parent, child)
+            }
+            TreeError::ParentMismatch { child, parent } => {
+                write!(f, "{:?} has parent {:?} but isn't listed as its child", child, parent)
+            }
+            TreeError::DuplicateChild(id) => write!(f, "{:?} is listed as a child more than once", id),
+            TreeError::Cycle(id) => write!(f, "{:?} is its own ancestor", id),
+            TreeError::FreeSlot(index) => write!(f, "Slot {} is on the free list but in use or listed twice", index),
+            TreeError::DeadReference { field, id } => write!(f, "The {} node {:?} was removed", field, id),
+            TreeError::RootHasParent(id) => write!(f, "Root {:?} has a parent", id),
+            TreeError::OutsideClip { parent, child } => {
+                write!(f, "{:?} lies outside the clip area of its parent {:?}", child, parent)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
 
 /// The UI tree that owns all nodes in an arena.
 pub struct UiTree {
@@ -10,6 +56,10 @@ pub struct UiTree {
     pressed: Option<NodeId>,
     focused: Option<NodeId>,
     captured: Option<NodeId>,
+    /// Store-triggered animations of the nodes.
+    animator: Animator,
+    /// Strips along the window's edges kept clear of safe-area nodes.
+    insets: Insets,
 }
 
 impl UiTree {
@@ -22,6 +72,8 @@ impl UiTree {
             pressed: None,
             focused: None,
             captured: None,
+            animator: Animator::new(),
+            insets: Insets::default(),
         }
     }
 
@@ -33,15 +85,22 @@ impl UiTree {
 
     /// Add a boxed widget to the tree.
     pub fn add_boxed(&mut self, widget: Box<dyn Widget>, parent: Option<NodeId>) -> NodeId {
-        let node = Node::new(widget);
+        // Look the parent up before allocating: a removed parent's slot may be
+        // reused for the new node, which would then be its own parent
+        let parent_live = parent.is_some_and(|parent_id| self.get(parent_id).is_some());
+        let mut node = Node::new(widget);
+        node.widget.on_mount();
         let id = self.allocate_slot(node);
 
         if let Some(parent_id) = parent {
-            if let Some(parent_node) = self.nodes.get_mut(parent_id.index()).and_then(|n| n.as_mut()) {
+            // A removed parent leaves the node detached rather than pointing at nothing
+            if parent_live
+                && let Some(parent_node) = self.nodes.get_mut(parent_id.index()).and_then(|n| n.as_mut())
+            {
                 parent_node.children.push(id);
-            }
-            if let Some(node) = self.nodes.get_mut(id.index()).and_then(|n| n.as_mut()) {
-                node.parent = Some(parent_id);
+                if let Some(node) = self.nodes.get_mut(id.index()).and_then(|n| n.as_mut()) {
+                    node.parent = Some(parent_id);
+                }
             }
         } else if self.root.is_none() {
             self.root = Some(id);
@@ -52,11 +111,13 @@ impl UiTree {
 
     /// Remove a node and all its children from the tree.
     pub fn remove(&mut self, id: NodeId) {
+        // Removing twice would put the slot on the free list twice
+        let Some(node) = self.get(id) else {
+            return;
+        };
+
         // First collect children to remove
-        let children: Vec<NodeId> = self
-            .get(id)
-            .map(|n| n.children.clone())
-            .unwrap_or_default();
+        let children: Vec<NodeId> = node.children.clone();
 
         // Recursively remove children
         for child_id in children {
@@ -88,9 +149,13 @@ impl UiTree {
         if self.captured == Some(id) {
             self.captured = None;
         }
+        self.animator.forget(id);
 
         // Free the slot
         if let Some(slot) = self.nodes.get_mut(id.index()) {
+            if let Some(node) = slot.as_mut() {
+                node.widget.on_unmount();
+            }
             *slot = None;
             self.free_list.push(id.index());
         }
@@ -120,17 +185,198 @@ impl UiTree {
     }
 
     pub fn set_root(&mut self, id: Option<NodeId>) {
-        self.root = id;
+        self.root = id.filter(|&id| self.get(id).is_some());
     }
 
     /// Set the bounds for a node.
     pub fn set_bounds(&mut self, id: NodeId, bounds: Rect) {
         if let Some(node) = self.get_mut(id) {
+            let resized = !node.laid_out || (node.bounds.width, node.bounds.height) != (bounds.width, bounds.height);
             node.bounds = bounds;
+            node.laid_out = true;
             node.widget.set_bounds(bounds);
+            if resized {
+                node.widget.on_layout(bounds);
+            }
+        }
+    }
+
+    /// Set the geometry a node is placed with when the tree is laid out.
+    pub fn set_geometry(&mut self, id: NodeId, geometry: Geometry) {
+        if let Some(node) = self.get_mut(id) {
+            node.geometry = Some(geometry);
+        }
+    }
+
+    /// Set the window's insets. They take effect at the next layout.
+    pub fn set_insets(&mut self, insets: Insets) {
+        self.insets = insets;
+    }
+
+    /// Get the window's insets.
+    pub fn insets(&self) -> Insets {
+        self.insets
+    }
+
+    /// Get the part of the window inside the insets.
+    pub fn safe_area(&self) -> Rect {
+        let window = self.root.and_then(|root| self.get(root)).map(|node| node.bounds).unwrap_or_default();
+        self.insets.shrink(&window)
+    }
+
+    /// Lay the tree out for a window of `width` x `height`. The root fills
+    /// the window and nodes with a geometry are placed in their parent (or
+    /// its safe part) again, so their sizes follow the window. Nodes without one keep their size
+    /// and their offset from the parent. A side resolving to nothing keeps
+    /// its old size.
+    pub fn layout(&mut self, width: u32, height: u32) {
+        let Some(root) = self.root else {
+            return;
+        };
+        let old = self.get(root).map(|node| node.bounds).unwrap_or_default();
+        self.set_bounds(root, Rect::new(0, 0, width, height));
+        let safe_area = self.safe_area();
+        // Nodes paired with how far they moved
+        let mut stack = vec![(root, (-old.x, -old.y))];
+        while let Some((id, (dx, dy))) = stack.pop() {
+            let Some(node) = self.get(id) else {
+                continue;
+            };
+            let (parent, children) = (node.bounds, node.children.clone());
+            for child in children {
+                let Some(node) = self.get(child) else {
+                    continue;
+                };
+                let old = node.bounds;
+                let bounds = match &node.geometry {
+                    Some(geometry) => {
+                        let bounds = geometry.resolve(&geometry.area(&parent, &safe_area), node.widget.as_ref());
+                        let width = if bounds.width == 0 { old.width } else { bounds.width };
+                        let height = if bounds.height == 0 { old.height } else { bounds.height };
+                        Rect::new(bounds.x, bounds.y, width, height)
+                    }
+                    // Moved along with the parent
+                    None => old.translate(dx, dy),
+                };
+                self.set_bounds(child, bounds);
+                stack.push((child, (bounds.x - old.x, bounds.y - old.y)));
+            }
+        }
+    }
+
+    /// Set the name a node can be found by (the skin part id).
+    pub fn set_name(&mut self, id: NodeId, name: impl Into<String>) {
+        if let Some(node) = self.get_mut(id) {
+            node.name = Some(name.into());
+        }
+    }
+
+    /// Set the text shown in a tooltip while a node is hovered.
+    pub fn set_tooltip(&mut self, id: NodeId, tooltip: impl Into<String>) {
+        if let Some(node) = self.get_mut(id) {
+            node.tooltip = Some(tooltip.into());
+        }
+    }
+
+    /// Get the tooltip for a node: its own, or else the nearest ancestor's.
+    pub fn tooltip_for(&self, id: NodeId) -> Option<&str> {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let node = self.get(id)?;
+            if let Some(tooltip) = node.tooltip() {
+                return Some(tooltip);
+            }
+            current = node.parent;
+        }
+        None
+    }
+
+    /// Set the entries of the menu opened by right-clicking a node.
+    pub fn set_context_menu(&mut self, id: NodeId, items: Vec<MenuItem>) {
+        if let Some(node) = self.get_mut(id) {
+            node.context_menu = items;
+        }
+    }
+
+    /// Recolor everything a node and its children draw, on top of any
+    /// effect of its ancestors; `None` draws them as they are.
+    pub fn set_effect(&mut self, id: NodeId, effect: Option<ColorEffect>) {
+        if let Some(node) = self.get_mut(id) {
+            node.effect = effect;
+        }
+    }
+
+    /// Declare animations that play on a node when store keys change.
+    pub fn set_animations(&mut self, id: NodeId, animations: Vec<Animation>) {
+        if self.get(id).is_some() {
+            for animation in animations {
+                self.animator.add(id, animation);
+            }
+        }
+    }
+
+    /// Start the animations triggered by store changes since the last call.
+    /// Returns true if any started.
+    pub fn animate(&mut self, store: &Store) -> bool {
+        self.animator.observe(store)
+    }
+
+    /// Check whether any node is animating.
+    pub fn is_animating(&self) -> bool {
+        self.animator.is_playing()
+    }
+
+    /// Get the context menu for a node: its own, or else the nearest
+    /// ancestor's. Empty if no node on the way up has one.
+    pub fn context_menu_for(&self, id: NodeId) -> &[MenuItem] {
+        let mut current = Some(id);
+        while let Some(node) = current.and_then(|id| self.get(id)) {
+            if !node.context_menu.is_empty() {
+                return &node.context_menu;
+            }
+            current = node.parent;
+        }
+        &[]
+    }
+
+    /// Find the first node with the given name.
+    pub fn find_by_name(&self, name: &str) -> Option<NodeId> {
+        self.iter_node_ids()
+            .find(|&id| self.get(id).is_some_and(|node| node.name() == Some(name)))
+    }
+
+    /// Show or hide a node. Hidden nodes and their children are skipped when
+    /// drawing and hit testing; hover, press and focus on them are cleared.
+    pub fn set_visible(&mut self, id: NodeId, visible: bool) {
+        if let Some(node) = self.get_mut(id) {
+            node.visible = visible;
+        }
+        if !visible {
+            let hidden = [self.hovered, self.pressed, self.focused, self.captured]
+                .map(|state| state.is_some_and(|state| self.is_within(state, id)));
+            for (state, hidden) in [&mut self.hovered, &mut self.pressed, &mut self.focused, &mut self.captured]
+                .into_iter()
+                .zip(hidden)
+            {
+                if hidden {
+                    *state = None;
+                }
+            }
         }
     }
 
+    /// Check if a node is `ancestor` or one of its descendants.
+    fn is_within(&self, id: NodeId, ancestor: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(current_id) = current {
+            if current_id == ancestor {
+                return true;
+            }
+            current = self.get(current_id).and_then(|node| node.parent);
+        }
+        false
+    }
+
     // State accessors
 
     pub fn hovered(&self) -> Option<NodeId> {
@@ -138,7 +384,7 @@ impl UiTree {
     }
 
     pub fn set_hovered(&mut self, id: Option<NodeId>) {
-        self.hovered = id;
+        self.hovered = id.filter(|&id| self.get(id).is_some());
     }
 
     pub fn pressed(&self) -> Option<NodeId> {
@@ -146,7 +392,7 @@ impl UiTree {
     }
 
     pub fn set_pressed(&mut self, id: Option<NodeId>) {
-        self.pressed = id;
+        self.pressed = id.filter(|&id| self.get(id).is_some());
     }
 
     pub fn focused(&self) -> Option<NodeId> {
@@ -154,7 +400,7 @@ impl UiTree {
     }
 
     pub fn set_focused(&mut self, id: Option<NodeId>) {
-        self.focused = id;
+        self.focused = id.filter(|&id| self.get(id).is_some());
     }
 
     pub fn captured(&self) -> Option<NodeId> {
@@ -162,7 +408,99 @@ impl UiTree {
     }
 
     pub fn set_captured(&mut self, id: Option<NodeId>) {
-        self.captured = id;
+        self.captured = id.filter(|&id| self.get(id).is_some());
+    }
+
+    /// Check the tree's structure and state for consistency:
+    /// - parent and child links agree, with no node listed twice or its own ancestor
+    /// - free slots aren't in use, so no two nodes share an id
+    /// - the root, hovered, pressed, focused and captured nodes are live
+    /// - children of clipping nodes (scroll containers) lie inside the clip
+    ///   area; content may run past its bottom edge, where it scrolls to
+    pub fn check_invariants(&self) -> Result<(), TreeError> {
+        // Each slot is either live or free, once
+        let mut free = vec![false; self.nodes.len()];
+        for &index in &self.free_list {
+            if self.nodes.get(index).is_none_or(|n| n.is_some()) || std::mem::replace(&mut free[index], true) {
+                return Err(TreeError::FreeSlot(index));
+            }
+        }
+
+        let mut listed = vec![false; self.nodes.len()];
+        for id in self.iter_node_ids() {
+            let Some(node) = self.get(id) else {
+                continue;
+            };
+            let clip = node.widget.child_clip(&node.bounds);
+            for &child in &node.children {
+                let Some(child_node) = self.get(child) else {
+                    return Err(TreeError::MissingChild { parent: id, child });
+                };
+                if std::mem::replace(&mut listed[child.index()], true) {
+                    return Err(TreeError::DuplicateChild(child));
+                }
+                if child_node.parent != Some(id) {
+                    return Err(TreeError::ParentMismatch {
+                        child,
+                        parent: child_node.parent,
+                    });
+                }
+                let bounds = child_node.bounds;
+                if clip.is_some_and(|clip| bounds.x < clip.x || bounds.right() > clip.right() || bounds.y < clip.y) {
+                    return Err(TreeError::OutsideClip { parent: id, child });
+                }
+            }
+        }
+
+        for id in self.iter_node_ids() {
+            // Every parent link is matched by a children entry
+            let parent = self.get(id).and_then(|node| node.parent);
+            if parent.is_some() && !listed[id.index()] {
+                return Err(TreeError::ParentMismatch { child: id, parent });
+            }
+            // Parent chains end within the number of nodes
+            let mut ancestor = parent;
+            for _ in 0..self.nodes.len() {
+                match ancestor {
+                    Some(ancestor_id) if ancestor_id == id => return Err(TreeError::Cycle(id)),
+                    Some(ancestor_id) => ancestor = self.get(ancestor_id).and_then(|node| node.parent),
+                    None => break,
+                }
+            }
+            if ancestor.is_some() {
+                return Err(TreeError::Cycle(id));
+            }
+        }
+
+        for (field, id) in [
+            ("root", self.root),
+            ("hovered", self.hovered),
+            ("pressed", self.pressed),
+            ("focused", self.focused),
+            ("captured", self.captured),
+        ] {
+            if let Some(id) = id
+                && self.get(id).is_none()
+            {
+                return Err(TreeError::DeadReference { field, id });
+            }
+        }
+        if let Some(root) = self.root
+            && self.get(root).is_some_and(|node| node.parent.is_some())
+        {
+            return Err(TreeError::RootHasParent(root));
+        }
+        Ok(())
+    }
+
+    /// Panic if the tree breaks an invariant, in debug builds only. Call it
+    /// after building or changing a tree to catch mistakes early; release
+    /// builds skip the check.
+    pub fn debug_validate(&self) {
+        #[cfg(debug_assertions)]
+        if let Err(e) = self.check_invariants() {
+            panic!("UiTree invariant broken: {}", e);
+        }
     }
 
     /// Iterate over all valid node IDs.
@@ -173,26 +511,117 @@ impl UiTree {
             .filter_map(|(i, n)| n.as_ref().map(|_| NodeId(i)))
     }
 
+    /// Give every widget the app's drag inertia settings.
+    pub fn set_inertia(&mut self, inertia: &InertiaConfig) {
+        for node in self.nodes.iter_mut().flatten() {
+            node.widget_mut().set_inertia(inertia);
+        }
+    }
+
+    /// Advance every widget's time-based state by `dt`.
+    /// Returns true if any widget needs to be redrawn.
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        let mut redraw = false;
+        for node in self.nodes.iter_mut().flatten() {
+            redraw |= node.widget_mut().tick(dt);
+        }
+        redraw |= self.animator.tick(dt);
+        redraw
+    }
+
     /// Hit test: find the topmost (deepest) node at the given position.
     /// Children are tested before parents (front-to-back).
     pub fn hit_test(&self, x: i32, y: i32) -> Option<NodeId> {
-        self.root.and_then(|root| self.hit_test_node(root, x, y))
+        let root = self.root?;
+        // Open overlays (e.g. dropdown lists) sit above everything else
+        self.hit_test_overlay(root, x, y, (0, 0))
+            .or_else(|| self.hit_test_node(root, x, y, (0, 0), None))
     }
 
-    fn hit_test_node(&self, id: NodeId, x: i32, y: i32) -> Option<NodeId> {
-        let node = self.get(id)?;
+    /// Total scroll offset of a node's ancestors: the node is drawn shifted
+    /// up and left by this amount.
+    fn ancestor_scroll(&self, id: NodeId) -> (i32, i32) {
+        let mut scroll = (0, 0);
+        let mut parent = self.get(id).and_then(|node| node.parent);
+        while let Some(parent_id) = parent {
+            let Some(node) = self.get(parent_id) else {
+                break;
+            };
+            let (sx, sy) = node.widget.scroll_offset();
+            scroll = (scroll.0 + sx, scroll.1 + sy);
+            parent = node.parent;
+        }
+        scroll
+    }
+
+    /// Convert a window position to the coordinates of a node's layout
+    /// bounds, undoing the scrolling of the containers it is in. Widgets
+    /// compare mouse positions against their layout bounds, so pointer
+    /// events should be sent in these coordinates.
+    pub fn to_local(&self, id: NodeId, x: i32, y: i32) -> (i32, i32) {
+        let (sx, sy) = self.ancestor_scroll(id);
+        (x + sx, y + sy)
+    }
 
-        if !node.bounds.contains(x, y) {
+    /// Get where a node is drawn on screen, after scrolling.
+    pub fn screen_bounds(&self, id: NodeId) -> Option<Rect> {
+        let (sx, sy) = self.ancestor_scroll(id);
+        self.get(id).map(|node| node.bounds.translate(-sx, -sy))
+    }
+
+    /// Offset and clip rect for the children of a node drawn at `bounds`,
+    /// or None if its clip rect hides them entirely.
+    fn child_view(
+        &self,
+        node: &Node,
+        bounds: &Rect,
+        offset: (i32, i32),
+        clip: Option<Rect>,
+    ) -> Option<((i32, i32), Option<Rect>)> {
+        let (sx, sy) = node.widget.scroll_offset();
+        let child_clip = match (node.widget.child_clip(bounds), clip) {
+            (Some(own), Some(outer)) => Some(own.intersect(&outer)?),
+            (own, outer) => own.or(outer),
+        };
+        Some(((offset.0 - sx, offset.1 - sy), child_clip))
+    }
+
+    fn hit_test_overlay(&self, id: NodeId, x: i32, y: i32, offset: (i32, i32)) -> Option<NodeId> {
+        let node = self.get(id)?;
+        if !node.visible {
             return None;
         }
 
-        // Check children in reverse order (last child is on top)
+        let (sx, sy) = node.widget.scroll_offset();
         for &child_id in node.children.iter().rev() {
-            if let Some(hit) = self.hit_test_node(child_id, x, y) {
+            if let Some(hit) = self.hit_test_overlay(child_id, x, y, (offset.0 - sx, offset.1 - sy)) {
                 return Some(hit);
             }
         }
 
+        node.widget
+            .overlay_bounds(&node.bounds.translate(offset.0, offset.1))
+            .filter(|overlay| overlay.contains(x, y))
+            .map(|_| id)
+    }
+
+    fn hit_test_node(&self, id: NodeId, x: i32, y: i32, offset: (i32, i32), clip: Option<Rect>) -> Option<NodeId> {
+        let node = self.get(id)?;
+        let bounds = node.bounds.translate(offset.0, offset.1);
+
+        if !node.visible || !bounds.contains(x, y) || clip.is_some_and(|clip| !clip.contains(x, y)) {
+            return None;
+        }
+
+        // Check children in reverse order (last child is on top)
+        if let Some((child_offset, child_clip)) = self.child_view(node, &bounds, offset, clip) {
+            for &child_id in node.children.iter().rev() {
+                if let Some(hit) = self.hit_test_node(child_id, x, y, child_offset, child_clip) {
+                    return Some(hit);
+                }
+            }
+        }
+
         // No child hit, this node is the target
         Some(id)
     }
@@ -200,14 +629,103 @@ impl UiTree {
     /// Draw the entire tree to the canvas.
     pub fn draw(&self, canvas: &mut Canvas) {
         if let Some(root) = self.root {
-            self.draw_node(root, canvas);
+            self.draw_node(root, canvas, (0, 0), &|widget, canvas, bounds, state| widget.draw(canvas, bounds, state));
+            self.draw_overlays(root, canvas, (0, 0));
+        }
+    }
+
+    /// Draw the tree without overlays, painting each shown widget with
+    /// `paint` instead of its own `draw`. Hidden nodes, scrolling and clips
+    /// are handled as by `draw`.
+    pub fn draw_with(&self, canvas: &mut Canvas, paint: &dyn Fn(&dyn Widget, &mut Canvas, &Rect, WidgetState)) {
+        if let Some(root) = self.root {
+            self.draw_node(root, canvas, (0, 0), paint);
         }
     }
 
-    fn draw_node(&self, id: NodeId, canvas: &mut Canvas) {
+    /// Visit each shown node in drawing order, with its bounds on screen and
+    /// the area it is clipped to, if any.
+    pub fn visit_drawn(&self, visit: &mut dyn FnMut(&Node, &Rect, Option<&Rect>)) {
+        if let Some(root) = self.root {
+            self.visit_drawn_node(root, (0, 0), None, visit);
+        }
+    }
+
+    fn visit_drawn_node(
+        &self,
+        id: NodeId,
+        offset: (i32, i32),
+        clip: Option<Rect>,
+        visit: &mut dyn FnMut(&Node, &Rect, Option<&Rect>),
+    ) {
         let Some(node) = self.get(id) else {
             return;
         };
+        if !node.visible {
+            return;
+        }
+
+        let (dx, dy) = self.animator.offset(id);
+        let offset = (offset.0 + dx, offset.1 + dy);
+        let bounds = node.bounds.translate(offset.0, offset.1);
+        visit(node, &bounds, clip.as_ref());
+        if let Some((child_offset, child_clip)) = self.child_view(node, &bounds, offset, clip) {
+            for &child_id in &node.children {
+                self.visit_drawn_node(child_id, child_offset, child_clip, visit);
+            }
+        }
+    }
+
+    /// Draw open widget overlays on top of the tree.
+    fn draw_overlays(&self, id: NodeId, canvas: &mut Canvas, offset: (i32, i32)) {
+        let Some(node) = self.get(id) else {
+            return;
+        };
+        if !node.visible {
+            return;
+        }
+
+        let bounds = node.bounds.translate(offset.0, offset.1);
+        let outer_effect = Self::enter_effect(node, canvas);
+        if node.widget.overlay_bounds(&bounds).is_some() {
+            let state = WidgetState {
+                hovered: self.hovered == Some(id),
+                pressed: self.pressed == Some(id),
+                focused: self.focused == Some(id),
+            };
+            node.widget.draw_overlay(canvas, &bounds, state);
+        }
+
+        let (sx, sy) = node.widget.scroll_offset();
+        for &child_id in &node.children {
+            self.draw_overlays(child_id, canvas, (offset.0 - sx, offset.1 - sy));
+        }
+        canvas.set_effect(outer_effect);
+    }
+
+    /// Add a node's color effect to the canvas's, applying before those of
+    /// its ancestors. Returns the effect to restore once the node is drawn.
+    fn enter_effect(node: &Node, canvas: &mut Canvas) -> Option<ColorEffect> {
+        let outer = canvas.effect().copied();
+        if let Some(effect) = &node.effect {
+            canvas.set_effect(Some(outer.map_or(*effect, |outer| effect.then(&outer))));
+        }
+        outer
+    }
+
+    fn draw_node(
+        &self,
+        id: NodeId,
+        canvas: &mut Canvas,
+        offset: (i32, i32),
+        paint: &dyn Fn(&dyn Widget, &mut Canvas, &Rect, WidgetState),
+    ) {
+        let Some(node) = self.get(id) else {
+            return;
+        };
+        if !node.visible {
+            return;
+        }
 
         let state = WidgetState {
             hovered: self.hovered == Some(id),
@@ -215,13 +733,32 @@ impl UiTree {
             focused: self.focused == Some(id),
         };
 
-        let bounds = node.bounds;
-        node.widget.draw(canvas, &bounds, state);
+        // A shaking node moves along with its children
+        let (dx, dy) = self.animator.offset(id);
+        let offset = (offset.0 + dx, offset.1 + dy);
+        let bounds = node.bounds.translate(offset.0, offset.1);
+        let outer_effect = Self::enter_effect(node, canvas);
+        paint(node.widget.as_ref(), canvas, &bounds, state);
+        canvas.stats_mut().nodes += 1;
 
-        // Draw children
-        let children: Vec<NodeId> = node.children.clone();
-        for child_id in children {
-            self.draw_node(child_id, canvas);
+        // Draw children, clipped to the node's child area if it has one
+        let outer_clip = canvas.clip_rect().copied();
+        if let Some((child_offset, child_clip)) = self.child_view(node, &bounds, offset, outer_clip) {
+            canvas.set_clip(child_clip);
+            for &child_id in &node.children {
+                self.draw_node(child_id, canvas, child_offset, paint);
+            }
+            canvas.set_clip(outer_clip);
+        }
+        canvas.set_effect(outer_effect);
+
+        // A flash covers the node and its children
+        if let Some((color, alpha)) = self.animator.flash(id) {
+            for y in bounds.y.max(0)..bounds.bottom() {
+                for x in bounds.x.max(0)..bounds.right() {
+                    canvas.blend_pixel(x as u32, y as u32, color, alpha);
+                }
+            }
         }
     }
 }
@@ -233,6 +770,16 @@ impl Default for UiTree {
 }
 
 // Implement View so UiTree can be rendered by the existing Renderer
+impl Drop for UiTree {
+    fn drop(&mut self) {
+        // Widgets of a dropped tree (e.g. one swapped out for a new theme)
+        // leave it too
+        for node in self.nodes.iter_mut().flatten() {
+            node.widget.on_unmount();
+        }
+    }
+}
+
 impl crate::core::View for UiTree {
     fn size(&self) -> (u32, u32) {
         // Size is determined by the root node's bounds
@@ -246,3 +793,260 @@ impl crate::core::View for UiTree {
         UiTree::draw(self, canvas);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    use super::*;
+    use crate::core::Length;
+
+    /// A plain widget, optionally clipping its children to its bounds.
+    struct Block {
+        clip: bool,
+    }
+
+    impl Widget for Block {
+        fn draw(&self, _canvas: &mut Canvas, _bounds: &Rect, _state: WidgetState) {}
+
+        fn child_clip(&self, bounds: &Rect) -> Option<Rect> {
+            self.clip.then_some(*bounds)
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    /// A tree operation; nodes are picked from the ids handed out so far,
+    /// including removed ones, to exercise stale handles.
+    #[derive(Debug, Clone)]
+    enum Op {
+        Add { parent: Option<Index> },
+        Remove(Index),
+        Hover(Index),
+        Focus(Index),
+        Press(Index),
+        SetVisible(Index, bool),
+        SetBounds(Index, i32),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            2 => proptest::option::weighted(0.75, any::<Index>()).prop_map(|parent| Op::Add { parent }),
+            1 => any::<Index>().prop_map(Op::Remove),
+            1 => any::<Index>().prop_map(Op::Hover),
+            1 => any::<Index>().prop_map(Op::Focus),
+            1 => any::<Index>().prop_map(Op::Press),
+            1 => (any::<Index>(), any::<bool>()).prop_map(|(id, visible)| Op::SetVisible(id, visible)),
+            1 => (any::<Index>(), 0..100i32).prop_map(|(id, x)| Op::SetBounds(id, x)),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_random_mutations_keep_invariants(ops in proptest::collection::vec(op(), 0..60)) {
+            let mut tree = UiTree::new();
+            let mut ids: Vec<NodeId> = Vec::new();
+
+            for op in ops {
+                let pick = |index: &Index| (!ids.is_empty()).then(|| *index.get(&ids));
+                match &op {
+                    Op::Add { parent } => {
+                        let parent = parent.as_ref().and_then(pick);
+                        ids.push(tree.add(Block { clip: false }, parent));
+                    }
+                    Op::Remove(id) => {
+                        if let Some(id) = pick(id) {
+                            tree.remove(id);
+                        }
+                    }
+                    Op::Hover(id) => tree.set_hovered(pick(id)),
+                    Op::Focus(id) => tree.set_focused(pick(id)),
+                    Op::Press(id) => {
+                        tree.set_pressed(pick(id));
+                        tree.set_captured(pick(id));
+                    }
+                    Op::SetVisible(id, visible) => {
+                        if let Some(id) = pick(id) {
+                            tree.set_visible(id, *visible);
+                        }
+                    }
+                    Op::SetBounds(id, x) => {
+                        if let Some(id) = pick(id) {
+                            tree.set_bounds(id, Rect::new(*x, 0, 10, 10));
+                        }
+                    }
+                }
+                prop_assert_eq!(tree.check_invariants(), Ok(()), "after {:?}", op);
+            }
+        }
+    }
+
+    #[test]
+    fn test_children_inside_clip() {
+        let mut tree = UiTree::new();
+        let root = tree.add(Block { clip: true }, None);
+        tree.set_bounds(root, Rect::new(0, 0, 100, 50));
+        let child = tree.add(Block { clip: false }, Some(root));
+
+        // Content may extend below the clip area, where it scrolls to
+        tree.set_bounds(child, Rect::new(10, 40, 80, 200));
+        assert_eq!(tree.check_invariants(), Ok(()));
+        tree.set_bounds(child, Rect::new(10, 40, 100, 20));
+        assert_eq!(tree.check_invariants(), Err(TreeError::OutsideClip { parent: root, child }));
+    }
+
+    /// A widget that fills its bounds with a color.
+    struct Swatch(u32);
+
+    impl Widget for Swatch {
+        fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+            canvas.fill_rect(bounds.x as u32, bounds.y as u32, bounds.width, bounds.height, self.0);
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_effects_apply_to_children() {
+        let mut tree = UiTree::new();
+        let root = tree.add(Swatch(0xFFFFFF), None);
+        tree.set_bounds(root, Rect::new(0, 0, 30, 10));
+        let tinted = tree.add(Swatch(0xFFFFFF), Some(root));
+        tree.set_bounds(tinted, Rect::new(10, 0, 10, 10));
+        let plain = tree.add(Swatch(0xFFFFFF), Some(root));
+        tree.set_bounds(plain, Rect::new(20, 0, 10, 10));
+        tree.set_effect(root, Some(ColorEffect::tint(0x808080)));
+        tree.set_effect(tinted, Some(ColorEffect::tint(0xFF0000)));
+
+        let mut buffer = vec![0; 30 * 10];
+        let mut canvas = Canvas::new(&mut buffer, 30, 10);
+        tree.draw(&mut canvas);
+        assert!(canvas.effect().is_none());
+        // The child's tint applies first, then its parent's
+        assert_eq!([5, 15, 25].map(|x| canvas.get_pixel(x, 5)), [0x808080, 0x800000, 0x808080].map(Some));
+    }
+
+    /// A widget that logs its lifecycle hooks.
+    struct Probe {
+        name: &'static str,
+        log: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+    }
+
+    impl Widget for Probe {
+        fn draw(&self, _canvas: &mut Canvas, _bounds: &Rect, _state: WidgetState) {}
+
+        fn on_mount(&mut self) {
+            self.log.borrow_mut().push(format!("mount {}", self.name));
+        }
+
+        fn on_unmount(&mut self) {
+            self.log.borrow_mut().push(format!("unmount {}", self.name));
+        }
+
+        fn on_layout(&mut self, bounds: Rect) {
+            self.log.borrow_mut().push(format!("layout {} {}x{}", self.name, bounds.width, bounds.height));
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_hooks() {
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let probe = |name| Probe { name, log: log.clone() };
+        let mut tree = UiTree::new();
+        let root = tree.add(probe("root"), None);
+        let panel = tree.add(probe("panel"), Some(root));
+        tree.add(probe("video"), Some(panel));
+
+        // Only size changes count as layouts
+        tree.set_bounds(panel, Rect::new(0, 0, 100, 50));
+        tree.set_bounds(panel, Rect::new(20, 20, 100, 50));
+        tree.set_bounds(panel, Rect::new(20, 20, 120, 50));
+
+        // Removing a node unmounts its children too, and dropping the tree
+        // unmounts the rest
+        tree.remove(panel);
+        drop(tree);
+        assert_eq!(
+            *log.borrow(),
+            [
+                "mount root",
+                "mount panel",
+                "mount video",
+                "layout panel 100x50",
+                "layout panel 120x50",
+                "unmount video",
+                "unmount panel",
+                "unmount root",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_follows_window() {
+        let mut tree = UiTree::new();
+        let root = tree.add(Block { clip: false }, None);
+        let panel = tree.add(Block { clip: false }, Some(root));
+        let bar = tree.add(Block { clip: false }, Some(panel));
+        let fixed = tree.add(Block { clip: false }, Some(panel));
+        let geometry = Geometry::new(10, 10, Length::Percent(0.5), Length::Aspect(2.0, 1.0));
+        tree.set_geometry(panel, Geometry { max_height: Some(60), ..geometry });
+        tree.set_geometry(bar, Geometry::new(0, 5, Length::Fill, Length::Pixels(4)));
+
+        tree.layout(200, 100);
+        assert_eq!(tree.get(panel).unwrap().bounds, Rect::new(10, 10, 100, 50));
+        assert_eq!(tree.get(bar).unwrap().bounds, Rect::new(10, 15, 100, 4));
+        tree.set_bounds(fixed, Rect::new(12, 12, 3, 3));
+
+        // Half as wide as the window, its height kept by the limit
+        tree.layout(400, 300);
+        assert_eq!(tree.get(panel).unwrap().bounds, Rect::new(10, 10, 200, 60));
+        assert_eq!(tree.get(bar).unwrap().bounds, Rect::new(10, 15, 200, 4));
+        assert_eq!(tree.get(fixed).unwrap().bounds, Rect::new(12, 12, 3, 3));
+    }
+
+    #[test]
+    fn test_safe_area_keeps_clear_of_insets() {
+        let mut tree = UiTree::new();
+        let root = tree.add(Block { clip: false }, None);
+        let toolbar = tree.add(Block { clip: false }, Some(root));
+        let backdrop = tree.add(Block { clip: false }, Some(root));
+        let full_width = Geometry::new(0, 0, Length::Percent(1.0), Length::Pixels(20));
+        tree.set_geometry(toolbar, Geometry { safe_area: true, ..full_width });
+        tree.set_geometry(backdrop, full_width);
+        tree.set_insets(Insets::new(28, 0, 0, 8));
+
+        tree.layout(200, 100);
+        assert_eq!(tree.safe_area(), Rect::new(8, 28, 192, 72));
+        assert_eq!(tree.get(toolbar).unwrap().bounds, Rect::new(8, 28, 192, 20));
+        // Parts outside the safe area still reach under the insets
+        assert_eq!(tree.get(backdrop).unwrap().bounds, Rect::new(0, 0, 200, 20));
+
+        // Insets reported later apply at the next layout
+        tree.set_insets(Insets::new(0, 10, 0, 0).max(tree.insets()));
+        tree.layout(200, 100);
+        assert_eq!(tree.get(toolbar).unwrap().bounds, Rect::new(8, 28, 182, 20));
+    }
+}
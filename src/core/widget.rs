@@ -1,6 +1,9 @@
 use std::any::Any;
+use std::time::Duration;
 
-use crate::core::Rect;
+use winit::window::CursorIcon;
+
+use crate::core::{InertiaConfig, Rect};
 use crate::graphics::Canvas;
 
 /// State passed to widgets during drawing.
@@ -21,6 +24,33 @@ pub enum KeyCode {
     Home,
     End,
     Enter,
+    Up,
+    Down,
+    Escape,
+    /// Ctrl+Z.
+    Undo,
+    /// Ctrl+Y or Ctrl+Shift+Z.
+    Redo,
+    /// Shift+Left, Shift+Right, Shift+Home and Shift+End: move the caret,
+    /// extending the selection.
+    SelectLeft,
+    SelectRight,
+    SelectHome,
+    SelectEnd,
+}
+
+impl KeyCode {
+    /// Get the plain caret move for a selecting key, for widgets that
+    /// have nothing to select.
+    pub fn without_select(self) -> Self {
+        match self {
+            KeyCode::SelectLeft => KeyCode::Left,
+            KeyCode::SelectRight => KeyCode::Right,
+            KeyCode::SelectHome => KeyCode::Home,
+            KeyCode::SelectEnd => KeyCode::End,
+            key => key,
+        }
+    }
 }
 
 /// Events that widgets can handle.
@@ -37,6 +67,30 @@ pub enum WidgetEvent {
     FocusLost,
 }
 
+/// Largest size a widget may take when it sizes itself to its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraints {
+    pub max_width: u32,
+    pub max_height: u32,
+}
+
+impl Constraints {
+    /// Create constraints with the given maximum size.
+    pub fn new(max_width: u32, max_height: u32) -> Self {
+        Self { max_width, max_height }
+    }
+
+    /// Create constraints that allow any size.
+    pub fn unbounded() -> Self {
+        Self::new(u32::MAX, u32::MAX)
+    }
+
+    /// Shrink `size` to fit within the constraints.
+    pub fn constrain(&self, (width, height): (u32, u32)) -> (u32, u32) {
+        (width.min(self.max_width), height.min(self.max_height))
+    }
+}
+
 /// The core trait for UI widgets.
 pub trait Widget {
     /// Draw the widget to the canvas.
@@ -49,6 +103,13 @@ pub trait Widget {
         (0, 0)
     }
 
+    /// Returns the size the widget's content needs within `constraints`.
+    /// The layout engine uses it for parts the skin leaves unsized, so text
+    /// widgets can fit their text. Defaults to the preferred size.
+    fn intrinsic_size(&self, constraints: Constraints) -> (u32, u32) {
+        constraints.constrain(self.preferred_size())
+    }
+
     /// Handle an event. Return true if the event was consumed.
     fn on_event(&mut self, _event: &WidgetEvent) -> bool {
         false
@@ -58,6 +119,58 @@ pub trait Widget {
     /// Widgets that need to track their bounds for hit testing can override this.
     fn set_bounds(&mut self, _bounds: Rect) {}
 
+    /// Called once the widget has been added to a tree, before it is laid
+    /// out or drawn. Widgets that hold resources (a decoder, a camera, an
+    /// offscreen buffer) can acquire them here rather than when built.
+    fn on_mount(&mut self) {}
+
+    /// Called when the widget leaves its tree, because it or an ancestor
+    /// was removed or the whole tree was dropped. Release resources here.
+    fn on_unmount(&mut self) {}
+
+    /// Called after `set_bounds` when the widget's size changed, including
+    /// its first layout, so it can resize buffers to `bounds`. Moves that
+    /// keep the size don't call it.
+    fn on_layout(&mut self, _bounds: Rect) {}
+
+    /// Area drawn on top of all other widgets, such as an open dropdown list.
+    /// Points inside it hit this widget before anything else.
+    /// `bounds` is the widget's layout rect.
+    fn overlay_bounds(&self, _bounds: &Rect) -> Option<Rect> {
+        None
+    }
+
+    /// Draw the overlay area, after the whole tree has been drawn.
+    /// Only called while `overlay_bounds` returns Some.
+    fn draw_overlay(&self, _canvas: &mut Canvas, _bounds: &Rect, _state: WidgetState) {}
+
+    /// Area this widget's children are clipped to, for scroll containers.
+    /// Children outside it are neither drawn nor hit.
+    fn child_clip(&self, _bounds: &Rect) -> Option<Rect> {
+        None
+    }
+
+    /// How far this widget's children are scrolled, as (x, y). Children are
+    /// drawn and hit tested shifted up and left by this amount.
+    fn scroll_offset(&self) -> (i32, i32) {
+        (0, 0)
+    }
+
+    /// Mouse cursor shown while the pointer is over this widget.
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Default
+    }
+
+    /// Advance time-based state such as caret blinking by `dt`.
+    /// Return true if the widget needs to be redrawn.
+    fn tick(&mut self, _dt: Duration) -> bool {
+        false
+    }
+
+    /// Take on the app's settings for how drags carry on after release.
+    /// Widgets moved by dragging override this.
+    fn set_inertia(&mut self, _inertia: &InertiaConfig) {}
+
     /// Return self as Any for downcasting.
     fn as_any(&self) -> &dyn Any;
 
@@ -0,0 +1,14 @@
+//! Export subsystem: offscreen rendering and document output.
+//!
+//! Views can be rendered without a window into an RGB image, images can be
+//! laid out into PDF documents for printing, and widget trees can be exported
+//! as vector PDFs with real text.
+
+mod pdf;
+mod raster;
+mod vector;
+
+pub use pdf::{PdfDocument, PdfFont, PdfPage};
+pub use raster::{export_png, render_view};
+pub(crate) use raster::to_rgb_image;
+pub use vector::{export_pdf, tree_to_pdf};
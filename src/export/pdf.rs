@@ -0,0 +1,387 @@
+//! Minimal PDF writer.
+//!
+//! Produces PDF 1.4 documents with JPEG-embedded images and single-font
+//! text. Only the features the export subsystem needs are supported.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use encoding_rs::WINDOWS_1252;
+use image::RgbImage;
+use image::codecs::jpeg::JpegEncoder;
+
+/// JPEG quality used for embedded images.
+const JPEG_QUALITY: u8 = 90;
+
+/// An image placed on a page.
+struct PlacedImage {
+    jpeg: Vec<u8>,
+    width: u32,
+    height: u32,
+    /// Placement in points, with the origin at the top-left of the page.
+    x: f32,
+    y: f32,
+    draw_width: f32,
+    draw_height: f32,
+}
+
+/// A run of text placed on a page.
+struct PlacedText {
+    text: String,
+    /// Start of the baseline in points, with the origin at the top-left of the page.
+    x: f32,
+    baseline: f32,
+    size: f32,
+    color: u32,
+}
+
+/// The font used for all text in a document.
+///
+/// Text is encoded as WinAnsi (Windows-1252), which covers Western European
+/// languages and the euro sign; characters it lacks are replaced with `?`.
+pub enum PdfFont {
+    /// The built-in Helvetica font (not embedded).
+    Helvetica,
+    /// An embedded TrueType font.
+    TrueType {
+        /// Raw TTF data.
+        data: Vec<u8>,
+        /// Advance widths for WinAnsi codes 32..=255, in 1/1000 em.
+        widths: Vec<u32>,
+        /// Ascent and descent in 1/1000 em (descent is negative).
+        ascent: i32,
+        descent: i32,
+    },
+}
+
+/// First and last character codes covered by `PdfFont::TrueType` widths.
+const FIRST_CHAR: u32 = 32;
+const LAST_CHAR: u32 = 255;
+
+/// A single page. Coordinates are in points (1/72 inch) from the top-left corner.
+pub struct PdfPage {
+    width: f32,
+    height: f32,
+    images: Vec<PlacedImage>,
+    texts: Vec<PlacedText>,
+}
+
+impl PdfPage {
+    /// Create an empty page of the given size in points.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            images: Vec::new(),
+            texts: Vec::new(),
+        }
+    }
+
+    /// Draw an image scaled into the given rectangle.
+    pub fn draw_image(&mut self, image: &RgbImage, x: f32, y: f32, width: f32, height: f32) -> io::Result<()> {
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+            .encode_image(image)
+            .map_err(io::Error::other)?;
+
+        self.images.push(PlacedImage {
+            jpeg,
+            width: image.width(),
+            height: image.height(),
+            x,
+            y,
+            draw_width: width,
+            draw_height: height,
+        });
+        Ok(())
+    }
+
+    /// Draw a line of text starting at `x` on the given baseline.
+    /// `color` is 0xRRGGBB. Text is drawn above all images on the page.
+    pub fn draw_text(&mut self, text: &str, x: f32, baseline: f32, size: f32, color: u32) {
+        self.texts.push(PlacedText {
+            text: text.to_string(),
+            x,
+            baseline,
+            size,
+            color,
+        });
+    }
+
+    /// Build the page content stream.
+    fn content(&self) -> String {
+        let mut content = String::new();
+        for (i, image) in self.images.iter().enumerate() {
+            // PDF's origin is bottom-left; flip from our top-left coordinates
+            let bottom = self.height - image.y - image.draw_height;
+            let _ = writeln!(
+                content,
+                "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q",
+                image.draw_width, image.draw_height, image.x, bottom, i
+            );
+        }
+        for text in &self.texts {
+            let channel = |shift: u32| ((text.color >> shift) & 0xFF) as f32 / 255.0;
+            let _ = writeln!(
+                content,
+                "BT /F1 {:.2} Tf {:.3} {:.3} {:.3} rg {:.2} {:.2} Td {} Tj ET",
+                text.size,
+                channel(16),
+                channel(8),
+                channel(0),
+                text.x,
+                self.height - text.baseline,
+                pdf_string(&text.text)
+            );
+        }
+        content
+    }
+}
+
+/// A PDF document made of pages.
+pub struct PdfDocument {
+    title: String,
+    font: PdfFont,
+    pages: Vec<PdfPage>,
+}
+
+impl PdfDocument {
+    /// Create an empty document using Helvetica for text.
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            font: PdfFont::Helvetica,
+            pages: Vec::new(),
+        }
+    }
+
+    /// Set the font used for text.
+    pub fn with_font(mut self, font: PdfFont) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Append a page.
+    pub fn add_page(&mut self, page: PdfPage) {
+        self.pages.push(page);
+    }
+
+    /// Get the number of pages.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Serialize the document.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = ObjectWriter::new();
+
+        // Object numbers: 1 catalog, 2 page tree, 3 info, then the font
+        // (only if any text is drawn), then per-page objects
+        let has_text = self.pages.iter().any(|page| !page.texts.is_empty());
+        let font_id = 4;
+        let mut next_id = match (&self.font, has_text) {
+            (_, false) => 4,
+            (PdfFont::Helvetica, true) => 5,
+            (PdfFont::TrueType { .. }, true) => 7,
+        };
+        let mut page_ids = Vec::new();
+        let mut page_objects = Vec::new();
+        for page in &self.pages {
+            let page_id = next_id;
+            let content_id = next_id + 1;
+            let image_ids: Vec<usize> = (0..page.images.len()).map(|i| next_id + 2 + i).collect();
+            next_id += 2 + page.images.len();
+            page_ids.push(page_id);
+            page_objects.push((page, page_id, content_id, image_ids));
+        }
+
+        writer.object(1, b"<< /Type /Catalog /Pages 2 0 R >>");
+        let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+        writer.object(
+            2,
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), page_ids.len()).as_bytes(),
+        );
+        writer.object(
+            3,
+            format!("<< /Title {} /Producer (crix) >>", pdf_text_string(&self.title)).as_bytes(),
+        );
+        if has_text {
+            self.write_font(&mut writer, font_id);
+        }
+        let font_resource = if has_text {
+            format!(" /Font << /F1 {} 0 R >>", font_id)
+        } else {
+            String::new()
+        };
+
+        for (page, page_id, content_id, image_ids) in page_objects {
+            let xobjects: Vec<String> = image_ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| format!("/Im{} {} 0 R", i, id))
+                .collect();
+            writer.object(
+                page_id,
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Contents {} 0 R /Resources << /XObject << {} >>{} >> >>",
+                    page.width, page.height, content_id, xobjects.join(" "), font_resource
+                )
+                .as_bytes(),
+            );
+            writer.stream(content_id, "", page.content().as_bytes());
+
+            for (image, id) in page.images.iter().zip(image_ids) {
+                let dict = format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+                    image.width, image.height
+                );
+                writer.stream(id, &dict, &image.jpeg);
+            }
+        }
+
+        writer.finish(next_id, 1, 3)
+    }
+
+    /// Write the font dictionary (and descriptor and font file if embedded) starting at `id`.
+    fn write_font(&self, writer: &mut ObjectWriter, id: usize) {
+        match &self.font {
+            PdfFont::Helvetica => {
+                writer.object(
+                    id,
+                    b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+                );
+            }
+            PdfFont::TrueType {
+                data,
+                widths,
+                ascent,
+                descent,
+            } => {
+                let widths: Vec<String> = widths.iter().map(|w| w.to_string()).collect();
+                writer.object(
+                    id,
+                    format!(
+                        "<< /Type /Font /Subtype /TrueType /BaseFont /CrixFont /FirstChar {} /LastChar {} /Widths [{}] /FontDescriptor {} 0 R /Encoding /WinAnsiEncoding >>",
+                        FIRST_CHAR,
+                        LAST_CHAR,
+                        widths.join(" "),
+                        id + 1
+                    )
+                    .as_bytes(),
+                );
+                // Flags 32: nonsymbolic (uses the standard Latin character set)
+                writer.object(
+                    id + 1,
+                    format!(
+                        "<< /Type /FontDescriptor /FontName /CrixFont /Flags 32 /FontBBox [0 {} 1000 {}] /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 {} 0 R >>",
+                        descent, ascent, ascent, descent, ascent, id + 2
+                    )
+                    .as_bytes(),
+                );
+                writer.stream(id + 2, &format!("/Length1 {}", data.len()), data);
+            }
+        }
+    }
+
+    /// Write the document to a file.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+}
+
+/// Tracks object offsets while writing the file body.
+struct ObjectWriter {
+    out: Vec<u8>,
+    offsets: Vec<(usize, usize)>,
+}
+
+impl ObjectWriter {
+    fn new() -> Self {
+        Self {
+            out: b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec(),
+            offsets: Vec::new(),
+        }
+    }
+
+    fn object(&mut self, id: usize, body: &[u8]) {
+        self.offsets.push((id, self.out.len()));
+        self.out.extend_from_slice(format!("{} 0 obj\n", id).as_bytes());
+        self.out.extend_from_slice(body);
+        self.out.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn stream(&mut self, id: usize, dict: &str, data: &[u8]) {
+        let mut body = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.object(id, &body);
+    }
+
+    fn finish(mut self, object_count: usize, root: usize, info: usize) -> Vec<u8> {
+        self.offsets.sort_by_key(|(id, _)| *id);
+        let xref_offset = self.out.len();
+
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", object_count);
+        for (_, offset) in &self.offsets {
+            let _ = writeln!(xref, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            xref,
+            "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            object_count, root, info, xref_offset
+        );
+        self.out.extend_from_slice(xref.as_bytes());
+        self.out
+    }
+}
+
+/// Encode text as a WinAnsi PDF literal string for showing in a content
+/// stream. Bytes outside printable ASCII are written as octal escapes.
+fn pdf_string(text: &str) -> String {
+    let mut out = String::from("(");
+    let mut utf8 = [0; 4];
+    for c in text.chars() {
+        let (bytes, _, unmappable) = WINDOWS_1252.encode(c.encode_utf8(&mut utf8));
+        let byte = if unmappable { b'?' } else { bytes[0] };
+        match byte {
+            b'(' | b')' | b'\\' => {
+                out.push('\\');
+                out.push(byte as char);
+            }
+            b' '..=b'~' => out.push(byte as char),
+            _ => {
+                let _ = write!(out, "\\{:03o}", byte);
+            }
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// Encode text as a UTF-16 PDF text string, for document metadata such as
+/// the title, which viewers show outside any font.
+fn pdf_text_string(text: &str) -> String {
+    let mut out = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        let _ = write!(out, "{:04X}", unit);
+    }
+    out.push('>');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodes_text_as_winansi() {
+        assert_eq!(pdf_string("Total (net) 5\\2"), "(Total \\(net\\) 5\\\\2)");
+        assert_eq!(pdf_string("José Müller"), "(Jos\\351 M\\374ller)");
+        assert_eq!(pdf_string("€ 12,50"), "(\\200 12,50)");
+        // Not in WinAnsi
+        assert_eq!(pdf_string("→ 東"), "(? ?)");
+
+        assert_eq!(pdf_text_string("Müller €"), "<FEFF004D00FC006C006C00650072002020AC>");
+    }
+}
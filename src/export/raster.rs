@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use image::{ImageError, Rgb, RgbImage};
+
+use crate::core::View;
+use crate::graphics::Canvas;
+
+/// Render a view offscreen into an RGB image of the given size.
+///
+/// The background is cleared to white, since exported documents are
+/// usually printed or viewed on paper-colored pages.
+pub fn render_view(view: &dyn View, width: u32, height: u32) -> RgbImage {
+    let mut buffer = vec![0u32; width as usize * height as usize];
+    {
+        let mut canvas = Canvas::new(&mut buffer, width, height);
+        canvas.clear(0xFFFFFF);
+        view.draw(&mut canvas);
+    }
+
+    to_rgb_image(&buffer, width, height)
+}
+
+/// Render a view at its own size and save it as a PNG file.
+pub fn export_png(view: &dyn View, path: &Path) -> Result<(), ImageError> {
+    let (width, height) = view.size();
+    render_view(view, width, height).save_with_format(path, image::ImageFormat::Png)
+}
+
+/// Convert a canvas buffer (0x00RRGGBB pixels) into an RGB image.
+pub(crate) fn to_rgb_image(buffer: &[u32], width: u32, height: u32) -> RgbImage {
+    RgbImage::from_fn(width, height, |x, y| {
+        let pixel = buffer[(y * width + x) as usize];
+        Rgb([(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8])
+    })
+}
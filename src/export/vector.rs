@@ -0,0 +1,148 @@
+//! Vector PDF export of widget trees.
+//!
+//! Static text and the values of text inputs and text areas are written as
+//! real PDF text in the app's font, so exported forms stay searchable and
+//! sharp. Everything else (images, button and input backgrounds, custom
+//! paint) is rasterized into a single embedded background image.
+
+use std::io;
+use std::path::Path;
+
+use encoding_rs::WINDOWS_1252;
+
+use crate::core::{Rect, UiTree, View, Widget, WidgetState};
+use crate::graphics::{advance_width_sized, ascent_sized, descent_sized, font_data, Canvas};
+use crate::skin::widgets::{StaticText, TextArea, TextInput, TextLine};
+
+use super::pdf::{PdfDocument, PdfFont, PdfPage};
+use super::raster::to_rgb_image;
+
+/// Build a single-page PDF of a tree, sized to the tree (1px = 1pt). Only
+/// what the tree draws is exported: hidden nodes and text scrolled or
+/// clipped out of view are left out.
+pub fn tree_to_pdf(tree: &UiTree, title: &str) -> io::Result<PdfDocument> {
+    let (width, height) = tree.size();
+
+    // Background: everything but the text written as PDF text
+    let mut buffer = vec![0u32; width as usize * height as usize];
+    {
+        let mut canvas = Canvas::new(&mut buffer, width, height);
+        canvas.clear(0xFFFFFF);
+        tree.draw_with(&mut canvas, &draw_non_text);
+    }
+
+    let mut page = PdfPage::new(width as f32, height as f32);
+    page.draw_image(&to_rgb_image(&buffer, width, height), 0.0, 0.0, width as f32, height as f32)?;
+
+    tree.visit_drawn(&mut |node, bounds, clip| {
+        for line in text_lines(node.widget(), bounds) {
+            let line_height = ascent_sized(line.font_size) - descent_sized(line.font_size);
+            let shown = Rect::new(line.x, line.y, 1, line_height.ceil().max(1.0) as u32);
+            if clip.is_some_and(|clip| clip.intersect(&shown).is_none()) {
+                continue;
+            }
+            let baseline = line.y as f32 + ascent_sized(line.font_size);
+            page.draw_text(&line.text, line.x as f32, baseline, line.font_size, line.color);
+        }
+    });
+
+    let mut document = PdfDocument::new(title).with_font(document_font());
+    document.add_page(page);
+    Ok(document)
+}
+
+/// Export a tree to a PDF file.
+///
+/// There is no entry point taking a layout name: the app runner builds a
+/// named layout from the bundle and then fills it from the store, and those
+/// store bindings live with the runner. Embedders build the tree themselves
+/// (`AppBundle::load_layout`, then `SkinBuilder::build`), set the values they
+/// want shown, and pass it here.
+pub fn export_pdf(tree: &UiTree, title: &str, path: &Path) -> io::Result<()> {
+    tree_to_pdf(tree, title)?.save(path)
+}
+
+/// Draw a widget, leaving out the text exported as PDF text.
+fn draw_non_text(widget: &dyn Widget, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+    let widget_any = widget.as_any();
+    if let Some(input) = widget_any.downcast_ref::<TextInput>() {
+        input.draw_background(canvas, bounds, state);
+    } else if let Some(area) = widget_any.downcast_ref::<TextArea>() {
+        area.draw_background(canvas, bounds, state);
+    } else if !widget_any.is::<StaticText>() {
+        widget.draw(canvas, bounds, state);
+    }
+}
+
+/// Get the lines of text a widget in `bounds` shows.
+fn text_lines(widget: &dyn Widget, bounds: &Rect) -> Vec<TextLine> {
+    let widget_any = widget.as_any();
+    if let Some(input) = widget_any.downcast_ref::<TextInput>() {
+        return input.text_lines(bounds);
+    }
+    if let Some(area) = widget_any.downcast_ref::<TextArea>() {
+        return area.text_lines(bounds);
+    }
+    let Some(text) = widget_any.downcast_ref::<StaticText>() else {
+        return Vec::new();
+    };
+    let plain = text.plain_text();
+    if plain.is_empty() {
+        return Vec::new();
+    }
+    let (x, y) = text.text_origin(bounds);
+    vec![TextLine { text: plain.into_owned(), x, y, font_size: text.font_size(), color: text.text_color() }]
+}
+
+/// Embed the app font if one has been loaded, otherwise fall back to Helvetica.
+fn document_font() -> PdfFont {
+    let Some(data) = font_data() else {
+        return PdfFont::Helvetica;
+    };
+
+    // Text is WinAnsi encoded; measure what each code from 32 to 255 stands for
+    let codes: Vec<u8> = (32..=255).collect();
+    let (chars, _) = WINDOWS_1252.decode_without_bom_handling(&codes);
+    // Metrics at 1000px give widths directly in PDF glyph units (1/1000 em)
+    let widths = chars
+        .chars()
+        .map(|c| advance_width_sized(c, 1000.0).round() as u32)
+        .collect();
+
+    PdfFont::TrueType {
+        data: data.to_vec(),
+        widths,
+        ascent: ascent_sized(1000.0).round() as i32,
+        descent: descent_sized(1000.0).round() as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbImage;
+
+    use super::*;
+    use crate::graphics::init_font;
+
+    #[test]
+    fn test_exports_shown_text_only() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let mut tree = UiTree::new();
+        let root = tree.add(StaticText::new("Shown".to_string()), None);
+        tree.set_bounds(root, Rect::new(0, 0, 200, 100));
+        let hidden = tree.add(StaticText::new("Hidden".to_string()), Some(root));
+        tree.set_bounds(hidden, Rect::new(0, 0, 100, 20));
+        tree.set_visible(hidden, false);
+        let mut input = TextInput::new(RgbImage::new(100, 24), RgbImage::new(100, 24), RgbImage::new(100, 24), None);
+        input.set_text("Ada".to_string());
+        let input = tree.add(input, Some(root));
+        tree.set_bounds(input, Rect::new(0, 60, 100, 24));
+
+        let pdf = String::from_utf8_lossy(&tree_to_pdf(&tree, "Form").unwrap().to_bytes()).into_owned();
+        assert!(pdf.contains("(Shown) Tj"));
+        assert!(pdf.contains("(Ada) Tj"));
+        assert!(!pdf.contains("(Hidden) Tj"));
+    }
+}
@@ -1,4 +1,7 @@
+use image::RgbImage;
+
 use crate::core::Rect;
+use crate::graphics::{ColorEffect, NineSlice, RenderStats};
 
 /// A drawing surface that Views render to.
 /// Wraps a mutable pixel buffer with drawing primitives.
@@ -7,6 +10,10 @@ pub struct Canvas<'a> {
     width: u32,
     height: u32,
     clip_rect: Option<Rect>,
+    /// Recoloring applied to everything drawn, except by `clear`.
+    effect: Option<ColorEffect>,
+    /// What has been drawn so far.
+    stats: RenderStats,
 }
 
 impl<'a> Canvas<'a> {
@@ -16,9 +23,22 @@ impl<'a> Canvas<'a> {
             width,
             height,
             clip_rect: None,
+            effect: None,
+            stats: RenderStats::default(),
         }
     }
 
+    /// Get what has been drawn on the canvas so far.
+    pub fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
+
+    /// Get the statistics to count something drawn outside the canvas's
+    /// own methods, such as a widget node or a glyph.
+    pub fn stats_mut(&mut self) -> &mut RenderStats {
+        &mut self.stats
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -37,6 +57,25 @@ impl<'a> Canvas<'a> {
         self.clip_rect.as_ref()
     }
 
+    /// Set the color effect applied to the pixels drawn from now on.
+    pub fn set_effect(&mut self, effect: Option<ColorEffect>) {
+        self.effect = effect;
+    }
+
+    /// Get the current color effect.
+    pub fn effect(&self) -> Option<&ColorEffect> {
+        self.effect.as_ref()
+    }
+
+    /// Apply the color effect to a color about to be drawn.
+    #[inline]
+    fn recolor(&self, color: u32) -> u32 {
+        match &self.effect {
+            Some(effect) => effect.apply(color),
+            None => color,
+        }
+    }
+
     /// Check if a pixel is within the clip rect (if set).
     #[inline]
     fn is_clipped(&self, x: u32, y: u32) -> bool {
@@ -51,12 +90,44 @@ impl<'a> Canvas<'a> {
 
     /// Set a single pixel. Coordinates outside bounds or clip rect are ignored.
     pub fn set_pixel(&mut self, x: u32, y: u32, color: u32) {
+        let color = self.recolor(color);
+        self.put_pixel(x, y, color);
+    }
+
+    /// Set a single pixel without the color effect.
+    fn put_pixel(&mut self, x: u32, y: u32, color: u32) {
         if x < self.width && y < self.height && !self.is_clipped(x, y) {
             let index = (y * self.width + x) as usize;
             self.buffer[index] = color;
+            self.stats.pixels += 1;
         }
     }
 
+    /// Get a pixel, or None if it is outside the canvas.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<u32> {
+        if x < self.width && y < self.height {
+            Some(self.buffer[(y * self.width + x) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Draw a color over a pixel with the given opacity (0-255).
+    /// Coordinates outside bounds or clip rect are ignored.
+    pub fn blend_pixel(&mut self, x: u32, y: u32, color: u32, alpha: u8) {
+        let Some(dst) = self.get_pixel(x, y) else {
+            return;
+        };
+        let color = self.recolor(color);
+        let alpha = alpha as u32;
+        let mix = |shift: u32| {
+            let src = (color >> shift) & 0xFF;
+            let dst = (dst >> shift) & 0xFF;
+            ((src * alpha + dst * (255 - alpha) + 127) / 255) << shift
+        };
+        self.put_pixel(x, y, mix(16) | mix(8) | mix(0));
+    }
+
     /// Set a pixel using RGB components.
     pub fn set_pixel_rgb(&mut self, x: u32, y: u32, r: u8, g: u8, b: u8) {
         let color = (r as u32) << 16 | (g as u32) << 8 | (b as u32);
@@ -66,10 +137,12 @@ impl<'a> Canvas<'a> {
     /// Fill the entire canvas with a color.
     pub fn clear(&mut self, color: u32) {
         self.buffer.fill(color);
+        self.stats.pixels += self.buffer.len() as u64;
     }
 
     /// Fill a rectangular region.
     pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: u32) {
+        let color = self.recolor(color);
         let x_end = (x + width).min(self.width);
         let y_end = (y + height).min(self.height);
 
@@ -78,7 +151,42 @@ impl<'a> Canvas<'a> {
                 if !self.is_clipped(px, py) {
                     let index = (py * self.width + px) as usize;
                     self.buffer[index] = color;
+                    self.stats.pixels += 1;
+                }
+            }
+        }
+    }
+
+    /// Draw an image at the top left of `bounds`, cut off at their edges,
+    /// or with a nine-slice border, stretched to fill them.
+    pub fn draw_image(&mut self, image: &RgbImage, bounds: &Rect, slice: Option<&NineSlice>) {
+        let Some(slice) = slice else {
+            for (ix, iy, pixel) in image.enumerate_pixels() {
+                let (x, y) = (bounds.x + ix as i32, bounds.y + iy as i32);
+                if x >= 0 && y >= 0 && x < bounds.right() && y < bounds.bottom() {
+                    let [r, g, b] = pixel.0;
+                    self.set_pixel_rgb(x as u32, y as u32, r, g, b);
+                }
+            }
+            return;
+        };
+        if image.width() == 0 || image.height() == 0 {
+            return;
+        }
+        let dest = (bounds.width, bounds.height);
+        for dy in 0..bounds.height {
+            let y = bounds.y + dy as i32;
+            if y < 0 || y as u32 >= self.height {
+                continue;
+            }
+            for dx in 0..bounds.width {
+                let x = bounds.x + dx as i32;
+                if x < 0 || x as u32 >= self.width {
+                    continue;
                 }
+                let (sx, sy) = slice.source_pixel((dx, dy), dest, image.dimensions());
+                let [r, g, b] = image.get_pixel(sx, sy).0;
+                self.set_pixel_rgb(x as u32, y as u32, r, g, b);
             }
         }
     }
@@ -0,0 +1,173 @@
+//! Software renderer.
+//!
+//! Frames are drawn on the CPU into a `Canvas` and handed to the window
+//! through softbuffer. By default a frame is drawn into a back buffer the
+//! renderer owns and copied to the surface only once it is whole and the
+//! same size as the surface, so hover changes and resizes never show a
+//! half-drawn or stretched frame. `PresentMode::Direct` draws straight into
+//! softbuffer's buffer instead, saving the copy and the memory.
+
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use softbuffer::{Context, Surface};
+use winit::window::Window;
+
+use crate::graphics::renderer::draw_frame;
+use crate::graphics::{Canvas, RenderStats, Renderer};
+
+/// How finished frames reach the window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PresentMode {
+    /// Draw straight into the surface's buffer. Uses less memory, but on
+    /// some platforms a frame can show while it is still being drawn.
+    Direct,
+    /// Draw into a back buffer and copy only whole frames to the surface.
+    #[default]
+    DoubleBuffered,
+}
+
+/// A frame drawn off screen, with the size it was drawn at.
+#[derive(Debug, Default)]
+struct BackBuffer {
+    pixels: Vec<u32>,
+    width: u32,
+    height: u32,
+}
+
+impl BackBuffer {
+    /// Draw a frame of `width` x `height`, reallocating if the size changed.
+    fn draw(&mut self, width: u32, height: u32, draw: &mut dyn FnMut(&mut Canvas)) -> RenderStats {
+        if (width, height) != (self.width, self.height) {
+            self.pixels = vec![0; width as usize * height as usize];
+            self.width = width;
+            self.height = height;
+        }
+        draw_frame(&mut self.pixels, width, height, draw)
+    }
+
+    /// Copy the frame to a surface buffer of `width` x `height`. Returns
+    /// false, copying nothing, if it was drawn at another size.
+    fn copy_to(&self, surface: &mut [u32], width: u32, height: u32) -> bool {
+        if (width, height) != (self.width, self.height) || surface.len() != self.pixels.len() {
+            return false;
+        }
+        surface.copy_from_slice(&self.pixels);
+        true
+    }
+}
+
+/// Draws frames on the CPU and shows them in a window.
+pub struct CpuRenderer {
+    surface: Surface<Rc<Window>, Rc<Window>>,
+    width: u32,
+    height: u32,
+    mode: PresentMode,
+    /// Frame being drawn when double buffered.
+    back: BackBuffer,
+    /// What drawing the last frame took.
+    stats: RenderStats,
+}
+
+impl CpuRenderer {
+    /// Create a double-buffered renderer showing frames in `window`.
+    pub fn new(window: Rc<Window>) -> Self {
+        Self::with_mode(window, PresentMode::default())
+    }
+
+    /// Create a renderer showing frames in `window` the given way.
+    pub fn with_mode(window: Rc<Window>, mode: PresentMode) -> Self {
+        let size = window.inner_size();
+        let context = Context::new(window.clone()).expect("Failed to create softbuffer context");
+        let surface = Surface::new(&context, window).expect("Failed to create surface");
+
+        let mut renderer = Self {
+            surface,
+            width: 0,
+            height: 0,
+            mode,
+            back: BackBuffer::default(),
+            stats: RenderStats::default(),
+        };
+        renderer.resize(size.width, size.height);
+        renderer
+    }
+
+    /// Get how frames reach the window.
+    pub fn mode(&self) -> PresentMode {
+        self.mode
+    }
+}
+
+impl Renderer for CpuRenderer {
+    fn resize(&mut self, width: u32, height: u32) {
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+        self.width = width;
+        self.height = height;
+
+        if let (Some(w), Some(h)) = (NonZeroU32::new(width), NonZeroU32::new(height)) {
+            self.surface.resize(w, h).expect("Failed to resize surface");
+        }
+    }
+
+    fn present(&mut self, draw: &mut dyn FnMut(&mut Canvas)) {
+        // A minimised window has no surface to draw to
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        match self.mode {
+            PresentMode::Direct => {
+                let mut buffer = self.surface.buffer_mut().expect("Failed to get buffer");
+                self.stats = draw_frame(&mut buffer, self.width, self.height, draw);
+                buffer.present().expect("Failed to present buffer");
+            }
+            PresentMode::DoubleBuffered => {
+                self.stats = self.back.draw(self.width, self.height, draw);
+                let mut buffer = self.surface.buffer_mut().expect("Failed to get buffer");
+                // The surface changed size under the frame; the redraw after
+                // the resize shows a whole one instead
+                if self.back.copy_to(&mut buffer, self.width, self.height) {
+                    buffer.present().expect("Failed to present buffer");
+                }
+            }
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presents_only_whole_frames() {
+        let mut back = BackBuffer::default();
+        back.draw(4, 3, &mut |canvas| canvas.clear(0x00ff00));
+        let mut surface = vec![0; 4 * 3];
+        assert!(back.copy_to(&mut surface, 4, 3));
+        assert!(surface.iter().all(|&pixel| pixel == 0x00ff00));
+
+        // Resized between drawing and presenting: the surface keeps its last frame
+        let mut resized = vec![0x0000ff; 5 * 3];
+        assert!(!back.copy_to(&mut resized, 5, 3));
+        assert!(resized.iter().all(|&pixel| pixel == 0x0000ff));
+        // Same pixel count, other shape
+        let mut turned = vec![0x0000ff; 3 * 4];
+        assert!(!back.copy_to(&mut turned, 3, 4));
+        assert!(turned.iter().all(|&pixel| pixel == 0x0000ff));
+
+        // The next frame is drawn at the new size and shows
+        back.draw(5, 3, &mut |canvas| canvas.clear(0xff0000));
+        assert!(back.copy_to(&mut resized, 5, 3));
+        assert!(resized.iter().all(|&pixel| pixel == 0xff0000));
+    }
+}
@@ -0,0 +1,249 @@
+//! Draw commands recorded by scripts.
+//!
+//! Lua actions draw on `canvas` parts through `canvas.line`, `canvas.rect` and
+//! `canvas.text`. Each call becomes one command, kept in the store as a line
+//! of text so drawings persist and travel like any other value:
+//!
+//! ```text
+//! line 0 100 50 20 #ff0000 2
+//! rect 10 10 30 40 #00aa00 1
+//! text 5 5 16 #000000 Sales by month
+//! ```
+//!
+//! Coordinates are relative to the part's top-left corner. Lines that don't
+//! parse are skipped, so a bad value can't break the rest of a drawing.
+
+use std::fmt;
+
+use crate::core::Rect;
+use crate::graphics::{draw_text_sized, Canvas, TextStyle};
+
+/// Most commands a drawing may hold; the rest are ignored.
+pub const MAX_DRAW_COMMANDS: usize = 10_000;
+
+/// Widest line a command may draw, in pixels.
+pub const MAX_LINE_WIDTH: u32 = 16;
+
+/// Largest text size a command may use.
+pub const MAX_DRAW_TEXT_SIZE: f32 = 512.0;
+
+/// Farthest from the origin a command may reach, in pixels.
+const MAX_DRAW_COORDINATE: i32 = 16_384;
+
+/// One shape in a scripted drawing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    /// A straight line `width` pixels thick.
+    Line { from: (i32, i32), to: (i32, i32), color: u32, width: u32 },
+    /// A rectangle, filled or outlined.
+    Rect { x: i32, y: i32, width: u32, height: u32, color: u32, filled: bool },
+    /// One line of text whose top-left corner is at `x`, `y`.
+    Text { x: i32, y: i32, size: f32, color: u32, text: String },
+}
+
+impl DrawCommand {
+    /// Create a line command, clamping it to the supported range.
+    pub fn line(from: (f64, f64), to: (f64, f64), color: u32, width: f64) -> Self {
+        DrawCommand::Line {
+            from: (coordinate(from.0), coordinate(from.1)),
+            to: (coordinate(to.0), coordinate(to.1)),
+            color: color & 0xFFFFFF,
+            width: (width.round() as u32).clamp(1, MAX_LINE_WIDTH),
+        }
+    }
+
+    /// Create a rectangle command, clamping it to the supported range.
+    pub fn rect(x: f64, y: f64, width: f64, height: f64, color: u32, filled: bool) -> Self {
+        DrawCommand::Rect {
+            x: coordinate(x),
+            y: coordinate(y),
+            width: coordinate(width).max(0) as u32,
+            height: coordinate(height).max(0) as u32,
+            color: color & 0xFFFFFF,
+            filled,
+        }
+    }
+
+    /// Create a text command, clamping it to the supported range. Line
+    /// breaks become spaces.
+    pub fn text(x: f64, y: f64, text: &str, color: u32, size: f64) -> Self {
+        let size = size as f32;
+        DrawCommand::Text {
+            x: coordinate(x),
+            y: coordinate(y),
+            size: if size > 0.0 { size.min(MAX_DRAW_TEXT_SIZE) } else { 16.0 },
+            color: color & 0xFFFFFF,
+            text: text.replace(['\r', '\n'], " "),
+        }
+    }
+
+    /// Parse a command from one line of a drawing.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.trim_start().splitn(6, ' ');
+        let kind = fields.next()?;
+        let mut number = || fields.next()?.parse::<f64>().ok().filter(|n| n.is_finite());
+        match kind {
+            "line" => {
+                let from = (number()?, number()?);
+                let to = (number()?, number()?);
+                let mut rest = fields.next()?.split(' ');
+                let color = parse_draw_color(rest.next()?)?;
+                let width = rest.next()?.parse::<f64>().ok()?;
+                Some(DrawCommand::line(from, to, color, width))
+            }
+            "rect" => {
+                let (x, y, width, height) = (number()?, number()?, number()?, number()?);
+                let mut rest = fields.next()?.split(' ');
+                let color = parse_draw_color(rest.next()?)?;
+                let filled = rest.next()? == "1";
+                Some(DrawCommand::rect(x, y, width, height, color, filled))
+            }
+            "text" => {
+                let (x, y, size) = (number()?, number()?, number()?);
+                let color = parse_draw_color(fields.next()?)?;
+                Some(DrawCommand::text(x, y, fields.next().unwrap_or_default(), color, size))
+            }
+            _ => None,
+        }
+    }
+
+    /// Draw the command with `origin` as its (0, 0), inside `clip`.
+    pub fn draw(&self, canvas: &mut Canvas, origin: (i32, i32), clip: &Rect) {
+        match self {
+            DrawCommand::Line { from, to, color, width } => {
+                let from = (origin.0 + from.0, origin.1 + from.1);
+                let to = (origin.0 + to.0, origin.1 + to.1);
+                draw_line(canvas, from, to, *color, *width, clip);
+            }
+            DrawCommand::Rect { x, y, width, height, color, filled } => {
+                let rect = Rect::new(origin.0 + x, origin.1 + y, *width, *height);
+                if *filled {
+                    fill(canvas, &rect, *color, clip);
+                } else {
+                    let (w, h) = (rect.width.min(1), rect.height.min(1));
+                    fill(canvas, &Rect::new(rect.x, rect.y, rect.width, h), *color, clip);
+                    fill(canvas, &Rect::new(rect.x, rect.bottom() - 1, rect.width, h), *color, clip);
+                    fill(canvas, &Rect::new(rect.x, rect.y, w, rect.height), *color, clip);
+                    fill(canvas, &Rect::new(rect.right() - 1, rect.y, w, rect.height), *color, clip);
+                }
+            }
+            DrawCommand::Text { x, y, size, color, text } => {
+                let style = TextStyle::with_color(*color);
+                draw_text_sized(canvas, origin.0 + x, origin.1 + y, Some(clip), text, style, *size);
+            }
+        }
+    }
+}
+
+impl fmt::Display for DrawCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawCommand::Line { from, to, color, width } => {
+                write!(f, "line {} {} {} {} #{:06x} {}", from.0, from.1, to.0, to.1, color, width)
+            }
+            DrawCommand::Rect { x, y, width, height, color, filled } => {
+                write!(f, "rect {} {} {} {} #{:06x} {}", x, y, width, height, color, u8::from(*filled))
+            }
+            DrawCommand::Text { x, y, size, color, text } => {
+                write!(f, "text {} {} {} #{:06x} {}", x, y, size, color, text)
+            }
+        }
+    }
+}
+
+/// Parse a drawing, one command per line, keeping at most
+/// `MAX_DRAW_COMMANDS`.
+pub fn parse_draw_commands(drawing: &str) -> Vec<DrawCommand> {
+    drawing.lines().filter_map(DrawCommand::parse).take(MAX_DRAW_COMMANDS).collect()
+}
+
+/// Parse a color like `#ff0000` or `0xff0000`.
+pub fn parse_draw_color(text: &str) -> Option<u32> {
+    let hex = text.strip_prefix('#').or_else(|| text.strip_prefix("0x"))?;
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Round and clamp a coordinate.
+fn coordinate(value: f64) -> i32 {
+    if value.is_nan() {
+        0
+    } else {
+        value.round().clamp(-MAX_DRAW_COORDINATE as f64, MAX_DRAW_COORDINATE as f64) as i32
+    }
+}
+
+/// Fill the part of a rectangle inside `clip`.
+fn fill(canvas: &mut Canvas, rect: &Rect, color: u32, clip: &Rect) {
+    let left = rect.x.max(clip.x).max(0);
+    let top = rect.y.max(clip.y).max(0);
+    let right = rect.right().min(clip.right());
+    let bottom = rect.bottom().min(clip.bottom());
+    if right > left && bottom > top {
+        canvas.fill_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32, color);
+    }
+}
+
+/// Draw a line with square pens `width` pixels across.
+fn draw_line(canvas: &mut Canvas, from: (i32, i32), to: (i32, i32), color: u32, width: u32, clip: &Rect) {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (sx, sy) = (if from.0 < to.0 { 1 } else { -1 }, if from.1 < to.1 { 1 } else { -1 });
+    let half = width as i32 / 2;
+    let reach = Rect::new(
+        from.0.min(to.0) - half,
+        from.1.min(to.1) - half,
+        dx as u32 + width,
+        (-dy) as u32 + width,
+    );
+    if reach.intersect(clip).is_none() {
+        return;
+    }
+    let (mut x, mut y) = from;
+    let mut error = dx + dy;
+    loop {
+        fill(canvas, &Rect::new(x - half, y - half, width, width), color, clip);
+        if (x, y) == to {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += sx;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_round_trip_and_draw() {
+        let commands = [
+            DrawCommand::line((0.0, 0.0), (15.0, 15.0), 0xFF0000, 1.0),
+            DrawCommand::rect(2.0, 6.0, 3.0, 3.0, 0x00FF00, true),
+            DrawCommand::text(1.0, 2.0, "Total:\n42", 0x000000, 12.0),
+        ];
+        let drawing: String = commands.iter().map(|command| format!("{}\n", command)).collect();
+        assert_eq!(drawing.lines().nth(2), Some("text 1 2 12 #000000 Total: 42"));
+        assert_eq!(parse_draw_commands(&format!("{}bogus 1 2\nrect 1 1 1 1 red 1\n", drawing)), commands);
+
+        let mut buffer = vec![0xFFFFFF; 20 * 20];
+        let mut canvas = Canvas::new(&mut buffer, 20, 20);
+        let clip = Rect::new(0, 0, 20, 20);
+        for command in &commands[..2] {
+            command.draw(&mut canvas, (10, 10), &clip);
+        }
+        assert_eq!(canvas.get_pixel(15, 15), Some(0xFF0000));
+        assert_eq!(canvas.get_pixel(13, 17), Some(0x00FF00));
+        // Clipped at the canvas edge
+        assert_eq!(canvas.get_pixel(19, 19), Some(0xFF0000));
+        assert_eq!(canvas.get_pixel(9, 9), Some(0xFFFFFF));
+    }
+}
@@ -0,0 +1,101 @@
+//! Color effects.
+//!
+//! A `ColorEffect` recolors everything a node draws as it is painted: a tint
+//! multiplies each pixel by a color, grayscale desaturates it and a hue
+//! shift turns it around the color wheel, so one set of gray assets can be
+//! colored per theme. Each effect is a 3x3 matrix over RGB, as in the CSS
+//! `grayscale()` and `hue-rotate()` filters, so effects of nested nodes
+//! combine into one.
+
+/// Luminance weights of red, green and blue.
+const LUMA: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// A recoloring applied to pixels as they are drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorEffect {
+    /// Rows give each output channel from the input red, green and blue.
+    matrix: [[f32; 3]; 3],
+}
+
+impl Default for ColorEffect {
+    fn default() -> Self {
+        Self { matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] }
+    }
+}
+
+impl ColorEffect {
+    /// Multiply each channel by that of `color` (0xRRGGBB), so white becomes
+    /// the color and black stays black.
+    pub fn tint(color: u32) -> Self {
+        let channel = |shift: u32| ((color >> shift) & 0xFF) as f32 / 255.0;
+        let (r, g, b) = (channel(16), channel(8), channel(0));
+        Self { matrix: [[r, 0.0, 0.0], [0.0, g, 0.0], [0.0, 0.0, b]] }
+    }
+
+    /// Desaturate by `amount`, from 0 (unchanged) to 1 (fully gray).
+    pub fn grayscale(amount: f32) -> Self {
+        let keep = 1.0 - amount.clamp(0.0, 1.0);
+        let mut matrix = [LUMA; 3];
+        for (channel, row) in matrix.iter_mut().enumerate() {
+            for (input, weight) in row.iter_mut().enumerate() {
+                let identity = if input == channel { 1.0 } else { 0.0 };
+                *weight += (identity - *weight) * keep;
+            }
+        }
+        Self { matrix }
+    }
+
+    /// Turn hues by `degrees` around the color wheel, keeping brightness.
+    pub fn hue_shift(degrees: f32) -> Self {
+        let (s, c) = degrees.to_radians().sin_cos();
+        Self {
+            matrix: [
+                [0.213 + c * 0.787 - s * 0.213, 0.715 - c * 0.715 - s * 0.715, 0.072 - c * 0.072 + s * 0.928],
+                [0.213 - c * 0.213 + s * 0.143, 0.715 + c * 0.285 + s * 0.140, 0.072 - c * 0.072 - s * 0.283],
+                [0.213 - c * 0.213 - s * 0.787, 0.715 - c * 0.715 + s * 0.715, 0.072 + c * 0.928 + s * 0.072],
+            ],
+        }
+    }
+
+    /// Get the effect of applying this one and then `next`.
+    pub fn then(&self, next: &ColorEffect) -> Self {
+        let mut matrix = [[0.0; 3]; 3];
+        for (row, out) in matrix.iter_mut().enumerate() {
+            for (column, value) in out.iter_mut().enumerate() {
+                *value = (0..3).map(|k| next.matrix[row][k] * self.matrix[k][column]).sum();
+            }
+        }
+        Self { matrix }
+    }
+
+    /// Recolor a 0xRRGGBB color.
+    pub fn apply(&self, color: u32) -> u32 {
+        let rgb = [16, 8, 0].map(|shift| ((color >> shift) & 0xFF) as f32);
+        self.matrix.iter().zip([16, 8, 0]).fold(0, |out, (row, shift)| {
+            let value = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+            out | (value.round().clamp(0.0, 255.0) as u32) << shift
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effects_recolor_pixels() {
+        assert_eq!(ColorEffect::tint(0xFF8000).apply(0xFFFFFF), 0xFF8000);
+        assert_eq!(ColorEffect::tint(0xFF8000).apply(0x808080), 0x804000);
+        assert_eq!(ColorEffect::grayscale(1.0).apply(0xFF0000), 0x363636);
+        assert_eq!(ColorEffect::grayscale(0.0).apply(0x12AB34), 0x12AB34);
+        assert_eq!(ColorEffect::hue_shift(0.0).apply(0x12AB34), 0x12AB34);
+        assert_eq!(ColorEffect::hue_shift(360.0).apply(0x12AB34), 0x12AB34);
+        // Gray has no hue to turn
+        assert_eq!(ColorEffect::hue_shift(120.0).apply(0x808080), 0x808080);
+
+        // A gray asset, desaturated and then tinted, takes the tint's color
+        let theme = ColorEffect::grayscale(1.0).then(&ColorEffect::tint(0x3366FF));
+        assert_eq!(theme.apply(0xFFFFFF), 0x3366FF);
+        assert_eq!(theme.apply(0x000000), 0x000000);
+    }
+}
@@ -1,10 +1,13 @@
-use image::{ImageReader, RgbImage};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use image::{ImageReader, RgbImage, RgbaImage};
 
 use crate::core::View;
 use crate::graphics::Canvas;
 
 /// An image that can be displayed as a View.
 /// Supports any format the `image` crate handles (PNG, JPEG, PPM, etc.).
+#[derive(Clone)]
 pub struct Image {
     width: u32,
     height: u32,
@@ -34,6 +37,20 @@ impl Image {
         }
     }
 
+    /// Create an image from raw RGBA bytes (row-major, 4 bytes per pixel).
+    /// Returns None if `bytes` is not exactly `width * height * 4` long.
+    ///
+    /// The canvas has no alpha blending, so alpha is composited against black.
+    pub fn from_rgba(bytes: Vec<u8>, width: u32, height: u32) -> Option<Self> {
+        let rgba = RgbaImage::from_raw(width, height, bytes)?;
+        let rgb = RgbImage::from_fn(width, height, |x, y| {
+            let [r, g, b, a] = rgba.get_pixel(x, y).0;
+            let scale = |c: u8| (c as u32 * a as u32 / 255) as u8;
+            image::Rgb([scale(r), scale(g), scale(b)])
+        });
+        Some(Self::from_rgb(rgb))
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -41,6 +58,11 @@ impl Image {
     pub fn height(&self) -> u32 {
         self.height
     }
+
+    /// Get the underlying RGB pixel data.
+    pub fn rgb(&self) -> &RgbImage {
+        &self.data
+    }
 }
 
 impl View for Image {
@@ -55,3 +77,90 @@ impl View for Image {
         }
     }
 }
+
+struct HandleData {
+    image: Image,
+    version: u64,
+}
+
+/// A shared, updatable image that can be registered in the Store.
+///
+/// Rust code keeps a clone of the handle and pushes new frames with `set`;
+/// widgets bound to the handle pick up the new contents on the next redraw.
+/// Handles are thread-safe so frames can be produced off the UI thread.
+#[derive(Clone)]
+pub struct ImageHandle {
+    inner: Arc<RwLock<HandleData>>,
+}
+
+impl ImageHandle {
+    /// Create a handle holding an initial image.
+    pub fn new(image: Image) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(HandleData { image, version: 0 })),
+        }
+    }
+
+    /// Replace the image contents.
+    pub fn set(&self, image: Image) {
+        let mut data = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        data.image = image;
+        data.version += 1;
+    }
+
+    /// Get a counter that increases every time the image is replaced.
+    pub fn version(&self) -> u64 {
+        self.read().version
+    }
+
+    /// Get the current image dimensions.
+    pub fn size(&self) -> (u32, u32) {
+        let data = self.read();
+        (data.image.width(), data.image.height())
+    }
+
+    /// Run a closure with read access to the current image.
+    pub fn with_image<R>(&self, f: impl FnOnce(&Image) -> R) -> R {
+        f(&self.read().image)
+    }
+
+    /// Run a closure with the version and read access to the current image,
+    /// read together so a frame set in between can't pair with the wrong
+    /// version. Keep it short: `set` waits for it.
+    pub fn with_versioned_image<R>(&self, f: impl FnOnce(u64, &Image) -> R) -> R {
+        let data = self.read();
+        f(data.version, &data.image)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HandleData> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for ImageHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (width, height) = self.size();
+        f.debug_struct("ImageHandle")
+            .field("width", &width)
+            .field("height", &height)
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_versions_frames() {
+        let handle = ImageHandle::new(Image::from_rgb(RgbImage::new(1, 1)));
+        handle.set(Image::from_rgb(RgbImage::new(2, 3)));
+        let (version, image) = handle.with_versioned_image(|version, image| (version, image.clone()));
+        assert_eq!((version, image.width(), image.height()), (1, 2, 3));
+
+        // The copy outlives the lock, so new frames can be set meanwhile
+        handle.set(Image::from_rgb(RgbImage::new(4, 4)));
+        assert_eq!((handle.version(), handle.size(), image.width()), (2, (4, 4), 2));
+    }
+}
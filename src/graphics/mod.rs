@@ -1,13 +1,28 @@
 mod canvas;
+mod cpu_renderer;
+mod draw_commands;
+mod effect;
 mod image;
+mod nine_slice;
 mod renderer;
+mod stats;
 pub mod text;
 
 pub use canvas::Canvas;
-pub use image::Image;
-pub use renderer::Renderer;
+pub use draw_commands::{
+    parse_draw_color, parse_draw_commands, DrawCommand, MAX_DRAW_COMMANDS, MAX_DRAW_TEXT_SIZE, MAX_LINE_WIDTH,
+};
+pub use effect::ColorEffect;
+pub use image::{Image, ImageHandle};
+pub use nine_slice::NineSlice;
+pub use cpu_renderer::{CpuRenderer, PresentMode};
+pub use renderer::{NullRenderer, Renderer};
+pub use stats::RenderStats;
 pub use text::{
-    draw_caret, draw_text, draw_text_sized, measure_text,
-    caret_x, caret_x_sized, line_height, line_height_sized,
-    init_font, TextStyle, FontError,
+    draw_caret, draw_text, CARET_BLINK_INTERVAL, draw_text_sized, measure_text,
+    caret_x, caret_x_sized, caret_x_styled, line_height, line_height_sized, line_height_styled,
+    ascent_sized, descent_sized, line_gap_sized, font_metrics_sized, line_top_for_baseline,
+    advance_width_sized, font_data, has_glyph, FontMetrics,
+    init_font, TextStyle, TextOutline, TextShadow, FontError, TextLayout,
+    draw_spans, parse_markup, spans_line_height, spans_text, spans_width, TextSpan,
 };
@@ -0,0 +1,76 @@
+//! Nine-slice scaling.
+//!
+//! Stretches an image to any size without distorting its border: the
+//! corners are kept as they are, the edges are stretched along their length
+//! and the middle both ways. So one small background image, say a rounded
+//! text field, fits widgets of every size.
+
+/// Widths of the border of an image kept unstretched by nine-slice scaling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NineSlice {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+impl NineSlice {
+    /// Create a border with the given widths.
+    pub fn new(left: u32, right: u32, top: u32, bottom: u32) -> Self {
+        Self { left, right, top, bottom }
+    }
+
+    /// Create a border equally wide on every side.
+    pub fn uniform(width: u32) -> Self {
+        Self::new(width, width, width, width)
+    }
+
+    /// Check whether the border fits in an image of `size`.
+    pub fn fits(&self, (width, height): (u32, u32)) -> bool {
+        self.left as u64 + self.right as u64 <= width as u64
+            && self.top as u64 + self.bottom as u64 <= height as u64
+    }
+
+    /// Get the pixel of an image `source` big drawn at `(x, y)` of an area
+    /// `dest` big.
+    pub fn source_pixel(&self, (x, y): (u32, u32), dest: (u32, u32), source: (u32, u32)) -> (u32, u32) {
+        (
+            stretch(x, dest.0, source.0, self.left, self.right),
+            stretch(y, dest.1, source.1, self.top, self.bottom),
+        )
+    }
+}
+
+/// Map a pixel `d` along a side `dest` long to the image pixel drawn there
+/// from a side `source` long, keeping `start` and `end` pixels at its ends.
+fn stretch(d: u32, dest: u32, source: u32, start: u32, end: u32) -> u32 {
+    let pixel = if d < start {
+        d
+    } else if d >= dest.saturating_sub(end) {
+        source.saturating_sub(dest - d)
+    } else {
+        let middle = source.saturating_sub(start + end).max(1);
+        start + ((d - start) as u64 * middle as u64 / dest.saturating_sub(start + end).max(1) as u64) as u32
+    };
+    pixel.min(source.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_border_while_stretching() {
+        // A 5-pixel side: 2 pixels kept at the start, 1 at the end
+        let along = |slice: NineSlice| {
+            (0..10).map(|d| slice.source_pixel((d, 0), (10, 1), (5, 1)).0).collect::<Vec<_>>()
+        };
+        assert_eq!(along(NineSlice::new(2, 1, 0, 0)), [0, 1, 2, 2, 2, 2, 3, 3, 3, 4]);
+        assert_eq!(along(NineSlice::uniform(2)), [0, 1, 2, 2, 2, 2, 2, 2, 3, 4]);
+        // Shrinking keeps the corners too
+        assert_eq!(NineSlice::uniform(2).source_pixel((3, 3), (4, 4), (5, 5)), (4, 4));
+
+        assert!(NineSlice::uniform(2).fits((4, 5)));
+        assert!(!NineSlice::new(3, 2, 0, 0).fits((4, 5)));
+    }
+}
@@ -1,60 +1,124 @@
-use std::num::NonZeroU32;
-use std::rc::Rc;
+//! Rendering backends.
+//!
+//! Widgets and apps only ever draw into a `Canvas`; a `Renderer` owns the
+//! surface a frame is drawn to and gets it onto the screen. The window
+//! runner draws through the trait, so a new backend (such as one uploading
+//! frames to the GPU) only needs to implement it, and the null renderer
+//! keeps frames in memory for the headless harness and tests.
 
-use softbuffer::Surface;
-use winit::event_loop::OwnedDisplayHandle;
-use winit::window::Window;
+use std::time::Instant;
 
-use crate::core::View;
-use crate::graphics::Canvas;
+use crate::core::{App, View};
+use crate::graphics::{Canvas, RenderStats};
 
-/// Handles rendering Views to the window surface.
-pub struct Renderer {
-    surface: Surface<OwnedDisplayHandle, Rc<Window>>,
+/// Draws frames and shows them.
+pub trait Renderer {
+    /// Resize the surface frames are drawn to.
+    fn resize(&mut self, width: u32, height: u32);
+
+    /// Get the surface size as (width, height).
+    fn size(&self) -> (u32, u32);
+
+    /// Draw a frame on a canvas cleared to black with `draw`, and show it.
+    fn present(&mut self, draw: &mut dyn FnMut(&mut Canvas));
+
+    /// Get what drawing the last frame took.
+    fn stats(&self) -> &RenderStats;
+
+    /// Render a View.
+    fn render(&mut self, view: &dyn View) {
+        self.present(&mut |canvas| view.draw(canvas));
+    }
+
+    /// Render an App's view followed by its immediate-mode overlay.
+    fn render_app(&mut self, app: &mut dyn App) {
+        self.present(&mut |canvas| {
+            app.view().draw(canvas);
+            canvas.set_clip(None);
+            app.overlay(canvas);
+        });
+    }
+}
+
+/// Draw a frame into `buffer` on a canvas cleared to black, returning what
+/// it took.
+pub(crate) fn draw_frame(buffer: &mut [u32], width: u32, height: u32, draw: &mut dyn FnMut(&mut Canvas)) -> RenderStats {
+    let start = Instant::now();
+    let mut canvas = Canvas::new(buffer, width, height);
+    canvas.clear(0x000000);
+    draw(&mut canvas);
+    RenderStats { draw_time: start.elapsed(), ..*canvas.stats() }
+}
+
+/// A renderer that keeps the last frame in memory instead of showing it.
+#[derive(Debug, Default)]
+pub struct NullRenderer {
     width: u32,
     height: u32,
+    frame: Vec<u32>,
+    /// Frames presented so far.
+    frames: u64,
+    /// What drawing the last frame took.
+    stats: RenderStats,
 }
 
-impl Renderer {
-    pub fn new(context: &softbuffer::Context<OwnedDisplayHandle>, window: Rc<Window>) -> Self {
-        let size = window.inner_size();
-        let surface = Surface::new(context, window).expect("Failed to create surface");
+impl NullRenderer {
+    /// Create a renderer with a surface of the given size.
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut renderer = Self::default();
+        renderer.resize(width, height);
+        renderer
+    }
 
-        Self {
-            surface,
-            width: size.width,
-            height: size.height,
-        }
+    /// Get the pixels of the last frame presented (0xRRGGBB, row by row).
+    pub fn frame(&self) -> &[u32] {
+        &self.frame
     }
 
-    /// Resize the rendering surface.
-    pub fn resize(&mut self, width: u32, height: u32) {
+    /// Get how many frames have been presented.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+impl Renderer for NullRenderer {
+    fn resize(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
-
-        if let (Some(w), Some(h)) = (NonZeroU32::new(width), NonZeroU32::new(height)) {
-            self.surface.resize(w, h).expect("Failed to resize surface");
-        }
+        self.frame = vec![0; width as usize * height as usize];
     }
 
-    /// Render a View to the window.
-    pub fn render(&mut self, view: &dyn View) {
-        let mut buffer = self.surface.buffer_mut().expect("Failed to get buffer");
-
-        {
-            let mut canvas = Canvas::new(&mut buffer, self.width, self.height);
-            canvas.clear(0x000000); // Clear to black
-            view.draw(&mut canvas);
-        }
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
 
-        buffer.present().expect("Failed to present buffer");
+    fn present(&mut self, draw: &mut dyn FnMut(&mut Canvas)) {
+        self.stats = draw_frame(&mut self.frame, self.width, self.height, draw);
+        self.frames += 1;
     }
 
-    pub fn width(&self) -> u32 {
-        self.width
+    fn stats(&self) -> &RenderStats {
+        &self.stats
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_renderer_keeps_frame() {
+        let mut renderer = NullRenderer::new(4, 2);
+        renderer.present(&mut |canvas| canvas.set_pixel_rgb(1, 1, 0xff, 0x80, 0x00));
+        assert_eq!(renderer.frames(), 1);
+        assert_eq!(renderer.frame()[5], 0xff8000);
+        // Clearing the frame writes every pixel, then the one drawn
+        assert_eq!(renderer.stats().pixels, 9);
 
-    pub fn height(&self) -> u32 {
-        self.height
+        // Each frame starts from black
+        renderer.present(&mut |_| {});
+        assert!(renderer.frame().iter().all(|&pixel| pixel == 0));
+        renderer.resize(3, 3);
+        assert_eq!((renderer.size(), renderer.frame().len()), ((3, 3), 9));
     }
 }
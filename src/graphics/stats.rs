@@ -0,0 +1,51 @@
+//! Paint statistics.
+//!
+//! Every `Canvas` counts what is drawn on it; renderers keep the counts of
+//! the last frame they showed so the dev console and tests can see what a
+//! frame cost.
+
+use std::fmt;
+use std::time::Duration;
+
+/// What drawing one frame took.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+    /// Widget nodes drawn.
+    pub nodes: u32,
+    /// Pixels written, including clearing the frame.
+    pub pixels: u64,
+    /// Glyphs drawn.
+    pub glyphs: u32,
+    /// Glyphs found already rasterized in the glyph cache.
+    pub glyph_cache_hits: u32,
+    /// Glyphs rasterized because the cache didn't have them.
+    pub glyph_cache_misses: u32,
+    /// Time taken to draw the frame, not counting showing it.
+    pub draw_time: Duration,
+}
+
+impl RenderStats {
+    /// Get the share of glyphs found in the glyph cache, from 0 to 1, or
+    /// `None` if no glyphs were drawn.
+    pub fn glyph_cache_hit_rate(&self) -> Option<f32> {
+        let lookups = self.glyph_cache_hits + self.glyph_cache_misses;
+        (lookups > 0).then(|| self.glyph_cache_hits as f32 / lookups as f32)
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} pixels, {} glyphs in {:.2} ms",
+            self.nodes,
+            self.pixels,
+            self.glyphs,
+            self.draw_time.as_secs_f64() * 1000.0
+        )?;
+        if let Some(rate) = self.glyph_cache_hit_rate() {
+            write!(f, ", glyph cache {:.0}% hits", rate * 100.0)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+//! Outline and shadow text effects.
+//!
+//! Text with effects is rendered into a coverage mask first. The outline is
+//! the mask grown by the outline width, and the shadow is the outlined (or
+//! plain) mask blurred and offset. The shadow, outline and glyphs are then
+//! blended over the canvas in that order.
+
+use crate::core::Rect;
+use crate::graphics::Canvas;
+
+use super::{ascent_sized, get_font, rasterize, TextStyle};
+
+/// Glyph coverage over a rectangle of the canvas.
+struct Mask {
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    alpha: Vec<u8>,
+}
+
+impl Mask {
+    fn get(&self, x: usize, y: usize) -> u8 {
+        self.alpha[y * self.width + x]
+    }
+
+    /// Grow the covered area by `radius` pixels in every direction.
+    fn dilate(&self, radius: u32) -> Mask {
+        let r = radius as i32;
+        let mut alpha = vec![0; self.alpha.len()];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut max = 0;
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        if dx * dx + dy * dy > r * r {
+                            continue;
+                        }
+                        let (sx, sy) = (x as i32 + dx, y as i32 + dy);
+                        if sx >= 0 && sy >= 0 && (sx as usize) < self.width && (sy as usize) < self.height {
+                            max = max.max(self.get(sx as usize, sy as usize));
+                        }
+                    }
+                }
+                alpha[y * self.width + x] = max;
+            }
+        }
+        Mask { alpha, ..*self }
+    }
+
+    /// Soften the mask with a box blur of `radius` pixels, applied
+    /// horizontally then vertically.
+    fn blur(&self, radius: u32) -> Mask {
+        let horizontal = self.box_blur(radius, 1, self.width, self.height);
+        let vertical = Mask { alpha: horizontal, ..*self }.box_blur(radius, self.width, self.height, self.width);
+        Mask { alpha: vertical, ..*self }
+    }
+
+    /// Average each pixel with its neighbours `radius` steps of `stride`
+    /// away along `lines` lines of `len` pixels.
+    fn box_blur(&self, radius: u32, stride: usize, len: usize, lines: usize) -> Vec<u8> {
+        let r = radius as usize;
+        let line_stride = if stride == 1 { self.width } else { 1 };
+        let mut alpha = vec![0; self.alpha.len()];
+        for line in 0..lines {
+            let start = line * line_stride;
+            for i in 0..len {
+                let from = i.saturating_sub(r);
+                let to = (i + r).min(len - 1);
+                let sum: u32 = (from..=to).map(|j| self.alpha[start + j * stride] as u32).sum();
+                alpha[start + i * stride] = (sum / (2 * r as u32 + 1)) as u8;
+            }
+        }
+        alpha
+    }
+
+    /// Blend a color over the canvas wherever the mask covers it, shifted
+    /// by `offset`.
+    fn composite(&self, canvas: &mut Canvas, offset: (i32, i32), color: u32, clip_rect: Option<&Rect>) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let alpha = self.get(x, y);
+                if alpha == 0 {
+                    continue;
+                }
+                let px = self.x + x as i32 + offset.0;
+                let py = self.y + y as i32 + offset.1;
+                if let Some(clip) = clip_rect
+                    && !clip.contains(px, py)
+                {
+                    continue;
+                }
+                if px >= 0 && py >= 0 {
+                    canvas.blend_pixel(px as u32, py as u32, color, alpha);
+                }
+            }
+        }
+    }
+}
+
+/// Draw text with the style's outline and shadow, like `draw_text_sized`.
+pub(super) fn draw_text_with_effects(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    clip_rect: Option<&Rect>,
+    text: &str,
+    style: &TextStyle,
+    size: f32,
+) {
+    let font = get_font();
+    let baseline_y = y as f32 + ascent_sized(size);
+
+    // Rasterize the glyphs where draw_text_sized would put them
+    let mut glyphs = Vec::new();
+    let mut offset = 0.0;
+    for c in text.chars() {
+        let pen_x = x as f32 + offset + style.glyph_offset(font, c, size);
+        offset = style.advance(font, c, offset, size);
+        if c == '\t' {
+            continue;
+        }
+        let glyph = rasterize(canvas, font, c, size);
+        let metrics = glyph.0;
+        let glyph_x = (pen_x + metrics.xmin as f32) as i32;
+        let glyph_y = (baseline_y - metrics.height as f32 - metrics.ymin as f32) as i32;
+        glyphs.push((glyph_x, glyph_y, metrics.width, metrics.height, glyph));
+    }
+    let Some(left) = glyphs.iter().map(|g| g.0).min() else {
+        return;
+    };
+    let top = glyphs.iter().map(|g| g.1).min().unwrap_or(y);
+    let right = glyphs.iter().map(|g| g.0 + g.2 as i32).max().unwrap_or(left);
+    let bottom = glyphs.iter().map(|g| g.1 + g.3 as i32).max().unwrap_or(top);
+
+    // Leave room for the outline and the blur to spread
+    let outline_width = style.outline.map_or(0, |outline| outline.width);
+    let margin = (outline_width + style.shadow.map_or(0, |shadow| shadow.blur)) as i32;
+    let mut mask = Mask {
+        x: left - margin,
+        y: top - margin,
+        width: (right - left + 2 * margin) as usize,
+        height: (bottom - top + 2 * margin) as usize,
+        alpha: Vec::new(),
+    };
+    mask.alpha = vec![0; mask.width * mask.height];
+    for (gx, gy, width, height, glyph) in &glyphs {
+        for row in 0..*height {
+            for col in 0..*width {
+                let mx = (gx - mask.x) as usize + col;
+                let my = (gy - mask.y) as usize + row;
+                let index = my * mask.width + mx;
+                mask.alpha[index] = mask.alpha[index].max(glyph.1[row * width + col]);
+            }
+        }
+    }
+
+    let outline = style.outline.map(|outline| (mask.dilate(outline.width), outline.color));
+    if let Some(shadow) = style.shadow {
+        let source = outline.as_ref().map_or(&mask, |(outline, _)| outline);
+        let blurred;
+        let shadow_mask = if shadow.blur > 0 {
+            blurred = source.blur(shadow.blur);
+            &blurred
+        } else {
+            source
+        };
+        shadow_mask.composite(canvas, shadow.offset, shadow.color, clip_rect);
+    }
+    if let Some((outline, color)) = &outline {
+        outline.composite(canvas, (0, 0), *color, clip_rect);
+    }
+    mask.composite(canvas, (0, 0), style.color, clip_rect);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::graphics::text::{init_font, TextOutline, TextShadow};
+
+    #[test]
+    fn test_effects_draw_around_glyphs() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let mut buffer = vec![0xFFFFFF; 64 * 32];
+        let mut canvas = Canvas::new(&mut buffer, 64, 32);
+        let style = TextStyle::with_color(0xFFFFFF)
+            .with_outline(TextOutline { color: 0xFF0000, width: 1 })
+            .with_shadow(TextShadow { color: 0x0000FF, offset: (3, 3), blur: 1 });
+        draw_text_with_effects(&mut canvas, 4, 4, None, "I", &style, 16.0);
+
+        // White glyphs over a white canvas stay invisible, so every changed
+        // pixel comes from the outline or shadow
+        assert!(buffer.iter().any(|&pixel| pixel == 0xFF0000));
+        assert!(buffer.iter().any(|&pixel| pixel & 0xFFFF00 != 0xFFFF00 && pixel & 0xFF == 0xFF));
+    }
+}
@@ -0,0 +1,310 @@
+//! Text layout.
+//!
+//! `TextLayout` breaks text into lines and places each character on them,
+//! using the same advances as `caret_x_styled` and `draw_text_sized`, so
+//! custom widgets can find caret positions and map pointer positions back to
+//! text offsets. Text offsets are byte offsets into the laid out string.
+//!
+//! Characters are placed one after another by their advance width, plus the
+//! style's tracking, with tabs moving to the next tab stop; there is no
+//! shaping beyond that (no kerning or ligatures).
+
+use crate::core::Rect;
+use crate::graphics::Canvas;
+
+use super::{caret_x_styled, draw_text_sized, get_font, line_height_styled, TextStyle};
+
+/// A character placed on a line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    /// The character.
+    pub c: char,
+    /// Byte offset of the character in the text.
+    pub index: usize,
+    /// Distance from the start of the line to the glyph's origin.
+    pub x: f32,
+    /// Horizontal advance to the next glyph.
+    pub advance: f32,
+}
+
+/// One visual line: a run of glyphs drawn left to right.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutLine {
+    /// Byte offset of the line's first character.
+    pub start: usize,
+    /// Byte offset just past the line's last character. A newline ending
+    /// the line is not part of it.
+    pub end: usize,
+    /// Distance from the top of the layout to the top of the line.
+    pub y: u32,
+    /// Width of the line in pixels.
+    pub width: f32,
+    /// The line's glyphs in text order.
+    pub glyphs: Vec<Glyph>,
+}
+
+/// Text broken into lines with every glyph positioned.
+#[derive(Debug, Clone)]
+pub struct TextLayout {
+    text: String,
+    size: f32,
+    style: TextStyle,
+    line_height: u32,
+    /// Set if the text was wrapped, so soft line ends can be told apart.
+    wrapped: bool,
+    lines: Vec<LayoutLine>,
+}
+
+impl TextLayout {
+    /// Lay out text at a font size, breaking lines only at newlines.
+    pub fn new(text: &str, size: f32) -> Self {
+        Self::styled(text, size, TextStyle::default(), None)
+    }
+
+    /// Lay out text at a font size, also wrapping lines wider than
+    /// `max_width`. Lines break after a space where possible and inside a
+    /// word otherwise.
+    pub fn wrapped(text: &str, size: f32, max_width: u32) -> Self {
+        Self::styled(text, size, TextStyle::default(), Some(max_width))
+    }
+
+    /// Lay out text at a font size with a style's spacing, line height and
+    /// tab stops, wrapping lines wider than `max_width` if given.
+    pub fn styled(text: &str, size: f32, style: TextStyle, max_width: Option<u32>) -> Self {
+        let lines = match max_width {
+            Some(max_width) => wrap_lines(text, max_width, |s| caret_x_styled(s, usize::MAX, size, &style)),
+            None => text_lines(text),
+        };
+        Self::from_lines(text, size, style, max_width.is_some(), lines)
+    }
+
+    fn from_lines(text: &str, size: f32, style: TextStyle, wrapped: bool, ranges: Vec<(usize, usize)>) -> Self {
+        let font = get_font();
+        let line_height = line_height_styled(size, &style);
+        let lines = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(i, (start, end))| {
+                let mut x = 0.0;
+                let glyphs = text[start..end]
+                    .char_indices()
+                    .map(|(offset, c)| {
+                        let next = style.advance(font, c, x, size);
+                        let glyph = Glyph {
+                            c,
+                            index: start + offset,
+                            x,
+                            advance: next - x,
+                        };
+                        x = next;
+                        glyph
+                    })
+                    .collect();
+                LayoutLine {
+                    start,
+                    end,
+                    y: i as u32 * line_height,
+                    width: x,
+                    glyphs,
+                }
+            })
+            .collect();
+
+        Self {
+            text: text.to_string(),
+            size,
+            style,
+            line_height,
+            wrapped,
+            lines,
+        }
+    }
+
+    /// Get the laid out text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the font size.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// Get the style the text is laid out and drawn with.
+    pub fn style(&self) -> &TextStyle {
+        &self.style
+    }
+
+    /// Get the height of each line.
+    pub fn line_height(&self) -> u32 {
+        self.line_height
+    }
+
+    /// Get the lines, top to bottom. There is always at least one.
+    pub fn lines(&self) -> &[LayoutLine] {
+        &self.lines
+    }
+
+    /// Width of the widest line.
+    pub fn width(&self) -> u32 {
+        self.lines.iter().map(|line| line.width).fold(0.0, f32::max).ceil() as u32
+    }
+
+    /// Height of all lines.
+    pub fn height(&self) -> u32 {
+        self.lines.len() as u32 * self.line_height
+    }
+
+    /// Index of the line holding the caret at a byte offset. An offset
+    /// where a line was wrapped belongs to the line it starts.
+    pub fn line_at(&self, index: usize) -> usize {
+        self.lines.iter().rposition(|line| line.start <= index).unwrap_or(0)
+    }
+
+    /// Position of the caret at a byte offset, as the x and top y of the
+    /// caret relative to the layout's top-left corner.
+    pub fn caret_position(&self, index: usize) -> (u32, u32) {
+        let line = &self.lines[self.line_at(index)];
+        let x = line
+            .glyphs
+            .iter()
+            .find(|glyph| glyph.index >= index)
+            .map_or(line.width, |glyph| glyph.x);
+        (x.ceil() as u32, line.y)
+    }
+
+    /// Byte offset of the caret position closest to a point relative to
+    /// the layout's top-left corner. Points above or below the text hit the
+    /// first or last line.
+    pub fn hit_test(&self, x: i32, y: i32) -> usize {
+        let line_index = if y < 0 {
+            0
+        } else {
+            (y as u32 / self.line_height.max(1)).min(self.lines.len() as u32 - 1) as usize
+        };
+        let line = &self.lines[line_index];
+
+        // A wrapped line's end offset is the next line's start, so its last
+        // caret position is before its last character
+        let soft_end = self.wrapped
+            && self
+                .lines
+                .get(line_index + 1)
+                .is_some_and(|next| next.start == line.end);
+        let mut carets: Vec<(f32, usize)> = line.glyphs.iter().map(|glyph| (glyph.x, glyph.index)).collect();
+        if !soft_end || carets.is_empty() {
+            carets.push((line.width, line.end));
+        }
+
+        let x = x as f32;
+        carets
+            .into_iter()
+            .min_by(|a, b| (a.0 - x).abs().total_cmp(&(b.0 - x).abs()))
+            .map_or(line.start, |(_, index)| index)
+    }
+
+    /// Draw the text with its top-left corner at the given position.
+    /// Clips rendering to the optional clip_rect.
+    pub fn draw(&self, canvas: &mut Canvas, x: i32, y: i32, clip_rect: Option<&Rect>) {
+        for line in &self.lines {
+            let text = &self.text[line.start..line.end];
+            draw_text_sized(canvas, x, y + line.y as i32, clip_rect, text, self.style, self.size);
+        }
+    }
+}
+
+/// Split text into lines at newlines, as byte ranges.
+fn text_lines(text: &str) -> Vec<(usize, usize)> {
+    let mut start = 0;
+    text.split('\n')
+        .map(|line| {
+            let range = (start, start + line.len());
+            start = range.1 + 1;
+            range
+        })
+        .collect()
+}
+
+/// Split text into visual lines no wider than `max_width`, as byte ranges.
+/// Lines break after a space where possible and inside a word otherwise; the
+/// newline ending a line is not part of its range.
+pub(crate) fn wrap_lines(text: &str, max_width: u32, measure: impl Fn(&str) -> u32) -> Vec<(usize, usize)> {
+    let mut lines = Vec::new();
+    let mut paragraph_start = 0;
+    for paragraph in text.split('\n') {
+        let end = paragraph_start + paragraph.len();
+        let mut start = paragraph_start;
+        while measure(&text[start..end]) > max_width {
+            let mut fit = start;
+            let mut after_space = None;
+            for (i, c) in text[start..end].char_indices() {
+                let next = start + i + c.len_utf8();
+                if measure(&text[start..next]) > max_width {
+                    // A space may hang past the edge
+                    if c == ' ' {
+                        after_space = Some(next);
+                    }
+                    break;
+                }
+                fit = next;
+                if c == ' ' {
+                    after_space = Some(next);
+                }
+            }
+            // Always take at least one character
+            let first = text[start..].chars().next().map_or(0, char::len_utf8);
+            let break_at = after_space.unwrap_or(fit.max(start + first));
+            lines.push((start, break_at));
+            start = break_at;
+        }
+        lines.push((start, end));
+        paragraph_start = end + 1;
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::graphics::text::{caret_x_sized, init_font};
+
+    #[test]
+    fn test_layout_matches_caret_x() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let layout = TextLayout::new("ab\ncd", 16.0);
+        assert_eq!(layout.lines().len(), 2);
+        assert_eq!((layout.lines()[1].start, layout.lines()[1].end), (3, 5));
+        assert_eq!(layout.caret_position(1), (caret_x_sized("ab", 1, 16.0), 0));
+        assert_eq!(layout.caret_position(5).1, layout.line_height());
+
+        // Hit-testing finds the nearest caret on the line under the point
+        let (x, y) = layout.caret_position(4);
+        assert_eq!(layout.hit_test(x as i32 + 1, y as i32 + 1), 4);
+        assert_eq!(layout.hit_test(-50, -50), 0);
+        assert_eq!(layout.hit_test(1000, 1000), 5);
+
+        // The last caret of a wrapped line is before the character the next
+        // line starts with
+        let width = caret_x_sized("abc ", 4, 16.0);
+        let layout = TextLayout::wrapped("abc def", 16.0, width);
+        assert_eq!(layout.lines().len(), 2);
+        assert_eq!(layout.hit_test(width as i32 + 5, 0), 3);
+        assert_eq!(layout.line_at(4), 1);
+    }
+
+    #[test]
+    fn test_tabular_digits_share_advance() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let style = TextStyle::default().with_tabular_numbers(true);
+        let ones = TextLayout::styled("11:11", 16.0, style, None);
+        let eights = TextLayout::styled("88:88", 16.0, style, None);
+        assert_eq!(ones.width(), eights.width());
+        assert_eq!(ones.caret_position(3), eights.caret_position(3));
+    }
+}
@@ -1,24 +1,50 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 use std::sync::OnceLock;
+use std::time::Duration;
 
-use fontdue::{Font, FontSettings};
+use fontdue::{Font, FontSettings, Metrics};
 
 use crate::core::Rect;
 use crate::graphics::Canvas;
 
+mod effects;
+mod layout;
+mod rich;
+
+pub use layout::{Glyph, LayoutLine, TextLayout};
+pub use rich::{draw_spans, parse_markup, spans_line_height, spans_text, spans_width, TextSpan};
+pub(crate) use layout::wrap_lines;
+
 /// Global font instance.
 static FONT: OnceLock<Font> = OnceLock::new();
 static FONT_SIZE: OnceLock<f32> = OnceLock::new();
+/// Raw bytes of the loaded font, kept for embedding in exported documents.
+static FONT_DATA: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// Most rasterized glyphs kept; the cache starts over when it fills up.
+const MAX_CACHED_GLYPHS: usize = 4096;
+
+/// A rasterized glyph: its metrics and coverage bitmap.
+type RasterGlyph = Rc<(Metrics, Vec<u8>)>;
+
+thread_local! {
+    /// Glyphs already rasterized, by character and font size (as bits).
+    static GLYPH_CACHE: RefCell<HashMap<(char, u32), RasterGlyph>> = RefCell::new(HashMap::new());
+}
 
 /// Initialize the font system with a TTF file.
 /// Must be called before any text rendering.
 pub fn init_font(path: &Path, size: f32) -> Result<(), FontError> {
     let font_data = std::fs::read(path).map_err(|e| FontError::Io(e))?;
-    let font = Font::from_bytes(font_data, FontSettings::default())
+    let font = Font::from_bytes(font_data.as_slice(), FontSettings::default())
         .map_err(|e| FontError::Parse(e.to_string()))?;
 
     FONT.set(font).map_err(|_| FontError::AlreadyInitialized)?;
     FONT_SIZE.set(size).map_err(|_| FontError::AlreadyInitialized)?;
+    FONT_DATA.set(font_data).map_err(|_| FontError::AlreadyInitialized)?;
 
     Ok(())
 }
@@ -28,62 +54,251 @@ fn get_font() -> &'static Font {
     FONT.get().expect("Font not initialized. Call init_font() first.")
 }
 
+/// Rasterize a glyph, reusing it if it was rasterized before, and count it
+/// in the canvas's statistics.
+fn rasterize(canvas: &mut Canvas, font: &Font, c: char, size: f32) -> RasterGlyph {
+    let key = (c, size.to_bits());
+    let cached = GLYPH_CACHE.with(|cache| cache.borrow().get(&key).cloned());
+    let stats = canvas.stats_mut();
+    stats.glyphs += 1;
+    if let Some(glyph) = cached {
+        stats.glyph_cache_hits += 1;
+        return glyph;
+    }
+    stats.glyph_cache_misses += 1;
+
+    let glyph = Rc::new(font.rasterize(c, size));
+    GLYPH_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= MAX_CACHED_GLYPHS {
+            cache.clear();
+        }
+        cache.insert(key, glyph.clone());
+    });
+    glyph
+}
+
 /// Get the font size.
 fn get_font_size() -> f32 {
     *FONT_SIZE.get().expect("Font not initialized. Call init_font() first.")
 }
 
-/// Get the line height for the current font.
-pub fn line_height() -> u32 {
-    line_height_sized(get_font_size())
+/// Get the raw TTF data of the loaded font, if initialized.
+pub fn font_data() -> Option<&'static [u8]> {
+    FONT_DATA.get().map(|data| data.as_slice())
 }
 
-/// Get the line height for a specific font size.
-pub fn line_height_sized(size: f32) -> u32 {
-    let font = get_font();
-    let metrics = font.horizontal_line_metrics(size).unwrap_or(fontdue::LineMetrics {
+/// Vertical metrics of the font at a font size, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// Distance from the top of a line to the baseline.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of a line (negative).
+    pub descent: f32,
+    /// Extra space between the bottom of one line and the top of the next.
+    pub line_gap: f32,
+    /// Distance from one baseline to the next (ascent - descent + line gap).
+    pub line_height: f32,
+}
+
+/// Get the vertical metrics of the font at a specific font size.
+pub fn font_metrics_sized(size: f32) -> FontMetrics {
+    let metrics = get_font().horizontal_line_metrics(size).unwrap_or(fontdue::LineMetrics {
         ascent: size,
         descent: 0.0,
         line_gap: 0.0,
         new_line_size: size,
     });
-    metrics.new_line_size.ceil() as u32
+    FontMetrics {
+        ascent: metrics.ascent,
+        descent: metrics.descent,
+        line_gap: metrics.line_gap,
+        line_height: metrics.new_line_size,
+    }
+}
+
+/// Get the distance from the top of a line to the baseline at a specific font size.
+pub fn ascent_sized(size: f32) -> f32 {
+    font_metrics_sized(size).ascent
+}
+
+/// Get the distance from the baseline to the bottom of a line (negative) at a specific font size.
+pub fn descent_sized(size: f32) -> f32 {
+    font_metrics_sized(size).descent
+}
+
+/// Get the space between lines at a specific font size.
+pub fn line_gap_sized(size: f32) -> f32 {
+    font_metrics_sized(size).line_gap
+}
+
+/// Get the top of a line whose baseline is at `baseline_y`, for drawing
+/// text on a baseline with `draw_text_sized`.
+pub fn line_top_for_baseline(baseline_y: i32, size: f32) -> i32 {
+    baseline_y - ascent_sized(size).round() as i32
+}
+
+/// Get the horizontal advance of a single character at a specific font size.
+pub fn advance_width_sized(c: char, size: f32) -> f32 {
+    get_font().metrics(c, size).advance_width
+}
+
+/// Check whether the loaded font has a glyph for a character.
+pub fn has_glyph(c: char) -> bool {
+    get_font().lookup_glyph_index(c) != 0
+}
+
+/// Get the line height for the current font.
+pub fn line_height() -> u32 {
+    line_height_sized(get_font_size())
+}
+
+/// Get the line height for a specific font size.
+pub fn line_height_sized(size: f32) -> u32 {
+    font_metrics_sized(size).line_height.ceil() as u32
+}
+
+/// Get the line height for a specific font size and style.
+pub fn line_height_styled(size: f32, style: &TextStyle) -> u32 {
+    (font_metrics_sized(size).line_height * style.line_height).ceil() as u32
+}
+
+/// Tab stops are this many space widths apart unless a style sets `tab_width`.
+const DEFAULT_TAB_SPACES: f32 = 4.0;
+
+/// An outline drawn around each glyph, under the glyphs themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextOutline {
+    pub color: u32,
+    /// Thickness in pixels.
+    pub width: u32,
+}
+
+/// A shadow drawn under the text (and its outline, if any).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextShadow {
+    pub color: u32,
+    /// Offset from the text in pixels; positive values move it right and down.
+    pub offset: (i32, i32),
+    /// Blur radius in pixels; zero gives a hard shadow.
+    pub blur: u32,
 }
 
 /// Text style for rendering.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct TextStyle {
     pub color: u32,
+    /// Extra space after each character in pixels (negative to tighten).
+    pub tracking: f32,
+    /// Multiplier for the font's line height.
+    pub line_height: f32,
+    /// Distance between tab stops in pixels; a tab moves to the next stop.
+    /// Zero puts the stops four space widths apart.
+    pub tab_width: f32,
+    /// Outline around the glyphs. Doesn't change the text's measured size.
+    pub outline: Option<TextOutline>,
+    /// Shadow under the text. Doesn't change the text's measured size.
+    pub shadow: Option<TextShadow>,
+    /// Give every digit the advance of the widest one, so numbers don't
+    /// shift sideways as their digits change.
+    pub tabular_numbers: bool,
 }
 
 impl Default for TextStyle {
     fn default() -> Self {
-        Self { color: 0xFFFFFF } // White
+        Self {
+            color: 0xFFFFFF, // White
+            tracking: 0.0,
+            line_height: 1.0,
+            tab_width: 0.0,
+            outline: None,
+            shadow: None,
+            tabular_numbers: false,
+        }
     }
 }
 
 impl TextStyle {
     pub fn with_color(color: u32) -> Self {
-        Self { color }
+        Self { color, ..Self::default() }
+    }
+
+    /// Set the extra space after each character.
+    pub fn with_tracking(mut self, tracking: f32) -> Self {
+        self.tracking = tracking;
+        self
+    }
+
+    /// Set the line height multiplier.
+    pub fn with_line_height(mut self, line_height: f32) -> Self {
+        self.line_height = line_height;
+        self
+    }
+
+    /// Set the distance between tab stops.
+    pub fn with_tab_width(mut self, tab_width: f32) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Set the outline drawn around the glyphs.
+    pub fn with_outline(mut self, outline: TextOutline) -> Self {
+        self.outline = Some(outline);
+        self
+    }
+
+    /// Set the shadow drawn under the text.
+    pub fn with_shadow(mut self, shadow: TextShadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    /// Set whether digits all get the same advance.
+    pub fn with_tabular_numbers(mut self, tabular: bool) -> Self {
+        self.tabular_numbers = tabular;
+        self
+    }
+
+    /// Get the x offset after drawing `c` at offset `x`.
+    fn advance(&self, font: &Font, c: char, x: f32, size: f32) -> f32 {
+        if c == '\t' {
+            let tab_width = if self.tab_width > 0.0 {
+                self.tab_width
+            } else {
+                font.metrics(' ', size).advance_width * DEFAULT_TAB_SPACES
+            };
+            if tab_width > 0.0 {
+                return ((x / tab_width).floor() + 1.0) * tab_width;
+            }
+        }
+        if self.tabular_numbers && c.is_ascii_digit() {
+            return x + digit_width(font, size) + self.tracking;
+        }
+        x + font.metrics(c, size).advance_width + self.tracking
+    }
+
+    /// Get how far right of the pen `c` is drawn: tabular digits are
+    /// centered in their widened advance.
+    fn glyph_offset(&self, font: &Font, c: char, size: f32) -> f32 {
+        if self.tabular_numbers && c.is_ascii_digit() {
+            (digit_width(font, size) - font.metrics(c, size).advance_width) / 2.0
+        } else {
+            0.0
+        }
     }
 }
 
+/// Get the advance of the widest digit at a specific font size.
+fn digit_width(font: &Font, size: f32) -> f32 {
+    ('0'..='9').map(|c| font.metrics(c, size).advance_width).fold(0.0, f32::max)
+}
+
 /// Measure the width of a string in pixels.
 pub fn measure_text(text: &str) -> (u32, u32) {
-    let font = get_font();
-    let size = get_font_size();
-
     if text.is_empty() {
         return (0, line_height());
     }
-
-    let mut width = 0.0;
-    for c in text.chars() {
-        let metrics = font.metrics(c, size);
-        width += metrics.advance_width;
-    }
-
-    (width.ceil() as u32, line_height())
+    (caret_x(text, usize::MAX), line_height())
 }
 
 /// Get the x offset of the caret at the given character index.
@@ -93,18 +308,20 @@ pub fn caret_x(text: &str, cursor_index: usize) -> u32 {
 
 /// Get the x offset of the caret at the given character index with a specific font size.
 pub fn caret_x_sized(text: &str, cursor_index: usize, size: f32) -> u32 {
+    caret_x_styled(text, cursor_index, size, &TextStyle::default())
+}
+
+/// Get the x offset of the caret at the given character index with a
+/// specific font size, spacing characters and tabs as `style` does.
+pub fn caret_x_styled(text: &str, cursor_index: usize, size: f32, style: &TextStyle) -> u32 {
     let font = get_font();
 
     let mut x = 0.0;
-    for (i, c) in text.chars().enumerate() {
-        if i >= cursor_index {
-            break;
-        }
-        let metrics = font.metrics(c, size);
-        x += metrics.advance_width;
+    for c in text.chars().take(cursor_index) {
+        x = style.advance(font, c, x, size);
     }
 
-    x.ceil() as u32
+    x.max(0.0).ceil() as u32
 }
 
 /// Draw text to the canvas at the given position.
@@ -131,24 +348,30 @@ pub fn draw_text_sized(
     style: TextStyle,
     size: f32,
 ) {
+    if style.outline.is_some() || style.shadow.is_some() {
+        effects::draw_text_with_effects(canvas, x, y, clip_rect, text, &style, size);
+        return;
+    }
+
     let font = get_font();
 
-    let mut cursor_x = x as f32;
+    // Pen position relative to x, so tab stops line up across calls
+    let mut offset = 0.0;
 
     // Get baseline offset
-    let metrics = font.horizontal_line_metrics(size).unwrap_or(fontdue::LineMetrics {
-        ascent: size,
-        descent: 0.0,
-        line_gap: 0.0,
-        new_line_size: size,
-    });
-    let baseline_y = y as f32 + metrics.ascent;
+    let baseline_y = y as f32 + ascent_sized(size);
 
     for c in text.chars() {
-        let (glyph_metrics, bitmap) = font.rasterize(c, size);
+        let pen_x = x as f32 + offset + style.glyph_offset(font, c, size);
+        offset = style.advance(font, c, offset, size);
+        if c == '\t' {
+            continue;
+        }
+        let glyph = rasterize(canvas, font, c, size);
+        let (glyph_metrics, bitmap) = (&glyph.0, &glyph.1);
 
         // Calculate glyph position
-        let glyph_x = cursor_x + glyph_metrics.xmin as f32;
+        let glyph_x = pen_x + glyph_metrics.xmin as f32;
         let glyph_y = baseline_y - glyph_metrics.height as f32 - glyph_metrics.ymin as f32;
 
         // Draw the glyph bitmap
@@ -183,10 +406,12 @@ pub fn draw_text_sized(
             }
         }
 
-        cursor_x += glyph_metrics.advance_width;
     }
 }
 
+/// How long a blinking caret stays shown (and then hidden).
+pub const CARET_BLINK_INTERVAL: Duration = Duration::from_millis(530);
+
 /// Draw a vertical caret (cursor) at the given position.
 pub fn draw_caret(
     canvas: &mut Canvas,
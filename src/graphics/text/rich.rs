@@ -0,0 +1,211 @@
+//! Rich text: one line of text made of spans with their own styles.
+//!
+//! Spans come from a small bracket markup, so a single store value can mix
+//! styles: `[b]bold[/b]`, `[color=#ff0000]red[/color]` and `[size=24]big[/size]`.
+//! Tags nest, and closing a tag also closes any opened inside it. Anything in
+//! brackets that isn't a known tag, such as `[x]`, is kept as text. Spans of
+//! different sizes share one baseline.
+
+use crate::core::Rect;
+use crate::graphics::Canvas;
+
+use super::{ascent_sized, caret_x_styled, draw_text_sized, line_height_styled, line_top_for_baseline, TextStyle};
+
+/// Largest font size a `[size]` tag may ask for.
+const MAX_SPAN_SIZE: f32 = 512.0;
+
+/// Bold text is drawn a second time this many pixels to the right, and each
+/// character advances this much further.
+const BOLD_OFFSET: f32 = 1.0;
+
+/// A run of text with one style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    pub bold: bool,
+    /// Text color, or the base style's.
+    pub color: Option<u32>,
+    /// Font size in pixels, or the base size.
+    pub size: Option<f32>,
+}
+
+impl TextSpan {
+    /// Get the span's style on top of a base style.
+    fn style(&self, base: &TextStyle) -> TextStyle {
+        let mut style = *base;
+        if let Some(color) = self.color {
+            style.color = color;
+        }
+        if self.bold {
+            style.tracking += BOLD_OFFSET;
+        }
+        style
+    }
+
+    /// Get the span's width in pixels.
+    fn width(&self, size: f32, base: &TextStyle) -> u32 {
+        caret_x_styled(&self.text, usize::MAX, self.size.unwrap_or(size), &self.style(base))
+    }
+}
+
+/// A markup tag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tag {
+    Bold,
+    Color(u32),
+    Size(f32),
+}
+
+impl Tag {
+    /// Parse the inside of an opening tag, such as `color=#ff0000`.
+    fn parse(inner: &str) -> Option<Self> {
+        match inner.split_once('=') {
+            None if inner == "b" => Some(Tag::Bold),
+            Some(("color", value)) => {
+                let hex = value.strip_prefix('#').or_else(|| value.strip_prefix("0x"))?;
+                if hex.len() != 6 {
+                    return None;
+                }
+                u32::from_str_radix(hex, 16).ok().map(Tag::Color)
+            }
+            Some(("size", value)) => {
+                let size = value.parse::<f32>().ok()?;
+                (size > 0.0 && size <= MAX_SPAN_SIZE).then_some(Tag::Size(size))
+            }
+            _ => None,
+        }
+    }
+
+    /// Name used to close the tag.
+    fn name(self) -> &'static str {
+        match self {
+            Tag::Bold => "b",
+            Tag::Color(_) => "color",
+            Tag::Size(_) => "size",
+        }
+    }
+}
+
+/// Split markup into styled spans.
+pub fn parse_markup(markup: &str) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    let mut open: Vec<Tag> = Vec::new();
+    let mut text = String::new();
+    let mut rest = markup;
+
+    while let Some(start) = rest.find('[') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let inner = &rest[1..end];
+        let closed = inner
+            .strip_prefix('/')
+            .and_then(|name| open.iter().rposition(|tag| tag.name() == name));
+        if let Some(index) = closed {
+            push_span(&mut spans, &mut text, &open);
+            open.truncate(index);
+        } else if let Some(tag) = Tag::parse(inner) {
+            push_span(&mut spans, &mut text, &open);
+            open.push(tag);
+        } else {
+            // Not a tag: keep the bracket as text and look past it
+            text.push('[');
+            rest = &rest[1..];
+            continue;
+        }
+        rest = &rest[end + 1..];
+    }
+    text.push_str(rest);
+    push_span(&mut spans, &mut text, &open);
+    spans
+}
+
+/// Move pending text into a span styled by the open tags.
+fn push_span(spans: &mut Vec<TextSpan>, text: &mut String, open: &[Tag]) {
+    if text.is_empty() {
+        return;
+    }
+    let mut span = TextSpan { text: std::mem::take(text), bold: false, color: None, size: None };
+    for tag in open {
+        match *tag {
+            Tag::Bold => span.bold = true,
+            Tag::Color(color) => span.color = Some(color),
+            Tag::Size(size) => span.size = Some(size),
+        }
+    }
+    spans.push(span);
+}
+
+/// Get the plain text of spans, without markup.
+pub fn spans_text(spans: &[TextSpan]) -> String {
+    spans.iter().map(|span| span.text.as_str()).collect()
+}
+
+/// Measure the width of spans in pixels.
+pub fn spans_width(spans: &[TextSpan], size: f32, style: &TextStyle) -> u32 {
+    spans.iter().map(|span| span.width(size, style)).sum()
+}
+
+/// Get the height of a line of spans: that of its largest size.
+pub fn spans_line_height(spans: &[TextSpan], size: f32, style: &TextStyle) -> u32 {
+    spans
+        .iter()
+        .map(|span| line_height_styled(span.size.unwrap_or(size), style))
+        .fold(line_height_styled(size, style), u32::max)
+}
+
+/// Draw spans on one line whose top is at `y`. Spans share the baseline of
+/// the largest size.
+pub fn draw_spans(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    clip_rect: Option<&Rect>,
+    spans: &[TextSpan],
+    style: TextStyle,
+    size: f32,
+) {
+    let ascent = spans
+        .iter()
+        .map(|span| ascent_sized(span.size.unwrap_or(size)))
+        .fold(ascent_sized(size), f32::max);
+    let baseline_y = y + ascent.round() as i32;
+
+    let mut pen_x = x;
+    for span in spans {
+        let span_size = span.size.unwrap_or(size);
+        let span_style = span.style(&style);
+        let top = line_top_for_baseline(baseline_y, span_size);
+        draw_text_sized(canvas, pen_x, top, clip_rect, &span.text, span_style, span_size);
+        if span.bold {
+            let bold_x = pen_x + BOLD_OFFSET as i32;
+            draw_text_sized(canvas, bold_x, top, clip_rect, &span.text, span_style, span_size);
+        }
+        pen_x += span.width(size, &style) as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markup() {
+        let spans = parse_markup("Status: [b][color=#ff0000]error[/color] in [size=20]line[/size][/b] [x] 4");
+        let texts: Vec<_> = spans.iter().map(|span| span.text.as_str()).collect();
+        assert_eq!(texts, ["Status: ", "error", " in ", "line", " [x] 4"]);
+        assert_eq!((spans[1].bold, spans[1].color), (true, Some(0xFF0000)));
+        assert_eq!((spans[2].bold, spans[2].color), (true, None));
+        assert_eq!(spans[3].size, Some(20.0));
+        assert!(!spans[4].bold);
+        assert_eq!(spans_text(&spans), "Status: error in line [x] 4");
+
+        // Closing an outer tag closes the inner one; stray and bad tags are text
+        let spans = parse_markup("[b][color=0x00ff00]a[/b]b[/color][size=0]c[");
+        let texts: Vec<_> = spans.iter().map(|span| span.text.as_str()).collect();
+        assert_eq!(texts, ["a", "b[/color][size=0]c["]);
+        assert_eq!(spans[1], TextSpan { text: texts[1].to_string(), bold: false, color: None, size: None });
+    }
+}
@@ -1,19 +1,38 @@
 pub mod bundle;
 pub mod core;
+pub mod export;
 pub mod graphics;
 pub mod platform;
 pub mod scripting;
+pub mod services;
 pub mod skin;
 pub mod widgets;
 
 // Re-export commonly used types at the crate root
 pub use bundle::{AppBundle, BundleError};
 pub use core::{
-    Action, ActionDispatcher, ActionError, ActionHandler, App, AppRunner, KeyCode, Node, NodeId,
-    Rect, Services, Store, UiTree, Value, View, Widget, WidgetEvent, WidgetState,
+    Action, ActionDispatcher, ActionError, ActionHandler, Animation, AnimationEffect, AnimationTrigger, App, AppRunner,
+    ChannelError, Constraints, Easing, Geometry, HistoryFrame, Hotkey, InertiaConfig, Insets, KeyCode, Length, Macros,
+    MenuItem, Node, NodeId, PersistError, ProfileStore, Publisher, Rect, Services, Store, StoreChange, StoreHistory,
+    StoreSnapshot, Subscriber, Timers, TreeError, UiTree, Value, View, Widget, WidgetEvent, WidgetState,
 };
-pub use graphics::{Canvas, Image, init_font, FontError};
-pub use platform::{run, RunConfig};
+pub use graphics::{
+    Canvas, ColorEffect, CpuRenderer, Image, ImageHandle, init_font, FontError, NineSlice, NullRenderer,
+    PresentMode, RenderStats, Renderer, TextLayout,
+};
+pub use platform::{run, run_windows, EdgeResistance, Headless, RendererFactory, RunConfig};
 pub use scripting::{AppConfig, AppConfigError, LuaActionHandler, LuaError};
-pub use skin::{LoadedSkin, SkinBuilder, SkinError, SkinVScroll, SkinWindow, StaticText, TextAlign, TextInput, VerticalAlign};
-pub use widgets::{Button, Container, ImageWidget, VScrollContainer};
+pub use services::{Capabilities, ServiceActionHandler};
+pub use skin::{
+    AnimatedSprite, CanvasWidget, ColorPicker, CustomPaint, FilePathInput, Gauge, GaugeStyle, GroupBox, ImageView,
+    Indicator, Knob, KnobDrag, Link, ListView, LoadedSkin, MenuBar, Painter, PainterRegistry, PathDialog, Plot,
+    PlotStyle, ProgressBar, SevenSegment, SkinBuilder, SkinError, SkinTimerDisplay, SkinVScroll, SkinWatcher,
+    SkinWindow, Spinner, Stack, StaticText, StatusBar, TabContainer, TextAlign, TextArea, TextInput, ToggleSwitch,
+    TreeRow, TreeView, VerticalAlign,
+};
+#[cfg(feature = "video")]
+pub use skin::SkinVideo;
+pub use widgets::{
+    Button, CommandPalette, Confetti, ConfettiConfig, Container, ContextMenu, CrossFade, DevConsole, ImageWidget,
+    Tooltip, VScrollContainer,
+};
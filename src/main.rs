@@ -1,17 +1,35 @@
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use chrono::Local;
 use clap::{Parser, Subcommand};
 use crix::{
-    run, init_font, Action, ActionDispatcher, App, AppBundle, KeyCode,
-    LuaActionHandler, RunConfig, Services, SkinBuilder, StaticText,
-    Store, TextInput, UiTree, View, WidgetEvent,
-    skin::widgets::{Checkbox, FilePicker},
+    run, run_windows, init_font, Action, ActionDispatcher, App, AppBundle, Canvas, Capabilities, CommandPalette, Confetti, ContextMenu, CrossFade, DevConsole,
+    Hotkey, InertiaConfig, KeyCode, LoadedSkin, LuaActionHandler, Macros, MenuItem, PathDialog, ProfileStore, Publisher, RunConfig, ServiceActionHandler, Services, SkinBuilder, SkinError, SkinWindow,
+    StaticText, Store, StoreChange, StoreHistory, StoreSnapshot, Subscriber, TextArea, TextInput, Timers, Tooltip, UiTree, Value, View, WidgetEvent,
+    export::{export_pdf, export_png, render_view},
+    graphics::{RenderStats, CARET_BLINK_INTERVAL},
+    services::{
+        AlarmService, CronSchedule, CurrencyService, FormatService, PrintService, Scheduler, SecretService,
+        SpellService, UpdateConfig, UpdateService, ALARM_TIME_FORMAT,
+    },
+    core::{channels_dir, MACRO_PREFIX, TIMER_RESOLUTION},
+    widgets::{LineKind, MenuResponse},
+    skin::{chosen_palette, fill_list_row, fill_template, SkinWatcher, Wizard, PALETTE_KEY_PREFIX, SKIN_WATCH_INTERVAL},
+    skin::widgets::{AnimatedSprite, CanvasWidget, Checkbox, ColorPicker, CustomPaint, Dropdown, FilePathInput, FilePicker, Gauge, ImageView, Indicator, Knob, Link, ListView, MenuBar, Plot, ProgressBar, ScrollView, SevenSegment, SkinButton, SkinImage, SkinTimerDisplay, Slider, Spinner, Stack, StatusBar, StepProgress, TabContainer, ToggleSwitch, TreeRow, TreeView, MAX_PLOT_POINTS},
 };
+#[cfg(feature = "video")]
+use crix::SkinVideo;
 use serde::Deserialize;
-use winit::event::WindowEvent;
-use winit::keyboard::{Key, NamedKey};
+use winit::event::{KeyEvent, MouseButton, WindowEvent};
+use winit::keyboard::{Key, ModifiersState, NamedKey, PhysicalKey};
+use winit::window::CursorIcon;
 
 /// Lightweight app metadata parsed from app.toml for display.
 #[derive(Debug, Deserialize)]
@@ -53,30 +71,201 @@ enum Commands {
     Run {
         /// Path to the .crix bundle directory
         bundle: PathBuf,
+        /// Profile to load persisted data from (defaults to the last used one)
+        #[arg(long)]
+        profile: Option<String>,
+        /// Enable developer tools (F12 opens the dev console, skin edits reload)
+        #[arg(long)]
+        dev: bool,
+    },
+    /// Show widget packs as desktop widgets, each in a borderless window
+    /// that stays above other windows
+    Host {
+        /// Paths to the widget pack directories
+        #[arg(required = true)]
+        packs: Vec<PathBuf>,
     },
 }
 
 struct SkinApp {
+    bundle: AppBundle,
     tree: UiTree,
     title: String,
+    /// Window settings of the app's own skin.
+    window: SkinWindow,
+    /// A press on the drag strip of a window without a title bar is
+    /// waiting to move the window.
+    window_drag: bool,
+    /// The window.close action asked for the window to close.
+    window_close: bool,
+    /// The window.center action asked for the window to move to the middle
+    /// of its screen.
+    window_center: bool,
+    /// Time since a widget pack's refresh action last ran.
+    since_refresh: Duration,
     store: Store,
     dispatcher: ActionDispatcher,
     services: Services,
+    /// Persisted store data, if the bundle declares `[persist] keys`.
+    profiles: Option<ProfileStore>,
+    /// Store keys shared with other apps, if the bundle declares `[publish]`.
+    publisher: Option<Publisher>,
+    /// Channels of other apps read into the store.
+    subscribers: Vec<Subscriber>,
+    /// When the subscribed channels were last read.
+    last_channel_poll: Instant,
+    /// Actions run on the bundle's `[schedule]`, if it has one.
+    scheduler: Option<Scheduler>,
+    /// Countdowns and stopwatches run by the timer.* actions.
+    timers: Timers,
+    /// Revision of the alarms last written to the `alarm.*` store keys.
+    alarms_published: Option<u64>,
+    /// The app screen while the lock layout is shown in `tree`.
+    unlocked_tree: Option<UiTree>,
+    /// Time of the last user input, for the idle auto-lock.
+    last_input: Instant,
+    /// Wrong PINs entered since the last right one.
+    failed_pins: u32,
+    /// Until when PIN attempts are refused after too many wrong ones.
+    pin_locked_until: Option<Instant>,
+    /// Last cursor position, for routing mouse down/up to widgets.
+    cursor: (i32, i32),
+    /// Multi-step form declared by the skin.
+    wizard: Option<Wizard>,
+    /// Index of the active wizard step.
+    wizard_step: usize,
+    /// Keyboard modifiers currently held, for shortcuts.
+    modifiers: ModifiersState,
+    /// Ctrl+K action search overlay.
+    palette: CommandPalette,
+    /// Tooltip of the part under the pointer.
+    tooltip: Tooltip,
+    /// Menu opened by right-clicking a part.
+    context_menu: ContextMenu,
+    /// Confetti thrown by fx.confetti.
+    confetti: Confetti,
+    /// Fade from the old screen after the screen was swapped.
+    cross_fade: CrossFade,
+    /// How scroll views and knobs carry on after a flick, from the RunConfig.
+    inertia: InertiaConfig,
+    /// Text area and misspelled word the open context menu suggests
+    /// replacements for.
+    spelling_target: Option<(crix::NodeId, Range<usize>)>,
+    /// Spell-check language the text areas' underlines were found with.
+    spelling_language: Option<String>,
+    /// Recorded action macros of the active profile.
+    macros: Macros,
+    /// App data directory (secrets, profiles and macros).
+    data_dir: PathBuf,
+    /// F12 developer console, only present with `--dev`.
+    dev_console: Option<DevConsole>,
+    /// Store changes made by the last action, for the console's `diff` command.
+    last_changes: Vec<StoreChange>,
+    /// Log every action's store changes to the console and stdout.
+    trace_changes: bool,
+    /// Action dispatch is paused at a dev console breakpoint.
+    paused: bool,
+    /// Actions triggered while paused, run on `continue` or `step`.
+    paused_actions: VecDeque<Action>,
+    /// Recorded store states for the console's time-travel commands, only
+    /// present with `--dev`.
+    history: Option<StoreHistory>,
+    /// Watches the screen's skin files to rebuild it when they are edited,
+    /// only present with `--dev`.
+    skin_watcher: Option<SkinWatcher>,
+    /// What drawing the last frame shown took, for the console's `stats`.
+    render_stats: RenderStats,
+    /// Colors picked for the skin's palette that the screen was built with.
+    skin_colors: HashMap<String, u32>,
 }
 
+/// Name of the lock-screen PIN in the secret store.
+const LOCK_PIN_SECRET: &str = "lock_pin";
+
+/// Shortest PIN accepted by lock.set_pin.
+const MIN_PIN_LENGTH: usize = 4;
+
+/// Wrong PINs allowed in a row before further attempts have to wait.
+const FREE_PIN_ATTEMPTS: u32 = 3;
+
+/// Wait after the first wrong PIN past the free attempts; it doubles with
+/// each further one.
+const PIN_LOCKOUT: Duration = Duration::from_secs(5);
+
+/// Longest wait between PIN attempts.
+const MAX_PIN_LOCKOUT: Duration = Duration::from_secs(300);
+
+/// Prefix of context menu actions that replace a misspelled word with the
+/// suggestion that follows it.
+const SPELLING_SUGGESTION_PREFIX: &str = "spellcheck.replace:";
+
+/// Most spelling suggestions shown in a context menu.
+const MAX_SPELLING_SUGGESTIONS: usize = 5;
+
+/// Theme name that theme.switch takes back to the app's own skin.
+const DEFAULT_THEME: &str = "default";
+
+/// Most pieces an fx.confetti action may throw at once.
+const MAX_CONFETTI_BURST: u32 = 2000;
+
+/// How often subscribed channels are read for changes.
+const CHANNEL_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Timer the timer.* actions manage when neither the payload nor the
+/// `timer.name` store key names one.
+const DEFAULT_TIMER: &str = "timer";
+
+/// Deepest nesting of store lists shown in a tree view.
+const MAX_TREE_DEPTH: usize = 32;
+
+/// Built-in actions handled by `SkinApp::run_action`, listed in the command palette.
+const RUNTIME_ACTIONS: &[&str] = &[
+    "launch_selected_app",
+    "print",
+    "export_pdf",
+    "export_png",
+    "profile.switch",
+    "app.lock",
+    "app.unlock",
+    "lock.set_pin",
+    "wizard.next",
+    "wizard.back",
+    "macro.record",
+    "macro.stop",
+    "macro.play",
+    "macro.delete",
+    "fx.confetti",
+    "theme.switch",
+    "window.close",
+    "window.center",
+    "timer.start",
+    "timer.stop",
+    "timer.reset",
+];
+
 impl SkinApp {
-    fn new(bundle: AppBundle) -> Result<Self, Box<dyn std::error::Error>> {
+    fn new(bundle: AppBundle, profile: Option<String>, dev: bool) -> Result<Self, Box<dyn std::error::Error>> {
         // Load skin from bundle
-        let skin = bundle.load_skin()?;
+        let mut skin = bundle.load_skin()?;
         let title = format!("{} - {}", bundle.meta.name, skin.name());
-
-        // Build UI tree from skin
-        let (tree, _window_config) = SkinBuilder::build(&skin)?;
+        let wizard = skin.skin.wizard.clone();
+        let sprites = skin
+            .skin
+            .confetti
+            .sprites
+            .iter()
+            .map(|asset| skin.get_image(asset).cloned().ok_or_else(|| SkinError::AssetNotFound(asset.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+        let confetti = Confetti::new(skin.skin.confetti.clone()).with_sprites(sprites);
+        let skin_watcher = dev.then(|| SkinWatcher::new(skin.files()));
 
         // Set up the store and dispatcher
-        let store = Store::new();
+        let mut store = Store::new();
         let mut dispatcher = ActionDispatcher::new();
 
+        // Built-in service actions take precedence over scripts
+        dispatcher.add_handler(ServiceActionHandler::new());
+
         // Create Lua action handler from bundle's action scripts
         let config_adapter = bundle.to_app_config();
         println!("Loaded app: {} v{}", config_adapter.meta_name, config_adapter.meta_version);
@@ -94,38 +283,687 @@ impl SkinApp {
         let lua_handler = LuaActionHandler::from_scripts(action_scripts);
         dispatcher.add_handler(lua_handler);
 
-        let services = Services::new();
+        let capabilities: Capabilities = bundle.meta.capabilities.iter().cloned().collect();
+        let data_dir = ProfileStore::app_data_dir(&bundle.meta.name);
+        let secrets = SecretService::open(&data_dir).unwrap_or_else(|e| {
+            eprintln!("Failed to open secret store: {}", e);
+            SecretService::new()
+        });
+        let alarms = AlarmService::open(&data_dir).unwrap_or_else(|e| {
+            eprintln!("Failed to load alarms: {}", e);
+            AlarmService::new()
+        });
+        let format = FormatService::with_separators(
+            bundle.format.decimal_separator,
+            bundle.format.thousands_separator,
+        );
+        let mut currency = CurrencyService::new().with_format(format.clone());
+        if let Some(config) = &bundle.currency {
+            currency = currency.with_base(&config.base);
+            if let Some(rates_url) = &config.rates_url {
+                currency = currency.with_endpoint(rates_url.clone());
+            }
+        }
+        let mut services = Services::new()
+            .with_capabilities(capabilities)
+            .with_secrets(secrets)
+            .with_print(PrintService::with_dir(data_dir.join("print")))
+            .with_alarms(alarms)
+            .with_format(format)
+            .with_currency(currency);
+
+        // Apps granted "update" with an [update] section check for new versions on launch
+        if let Some(update) = &bundle.update
+            && services.capabilities().allows("update")
+        {
+            services = services.with_update(UpdateService::with_config(UpdateConfig {
+                manifest_url: update.manifest.clone(),
+                public_key: update.public_key.clone(),
+                current_version: bundle.meta.version.clone(),
+                bundle_root: bundle.root().to_path_buf(),
+            }));
+            services.update().check();
+        }
+
+        // Apps granted "spellcheck" with a [spellcheck] section underline
+        // misspelled words in text areas that opt in
+        if let Some(spellcheck) = &bundle.spellcheck
+            && services.capabilities().allows("spellcheck")
+        {
+            let dictionaries = bundle.root().join(&spellcheck.dictionaries);
+            services = services.with_spell(SpellService::with_dictionaries(dictionaries));
+            match services.spell().set_language(&spellcheck.language) {
+                Ok(()) => store.set("spellcheck.language", spellcheck.language.clone()),
+                Err(e) => {
+                    eprintln!("Failed to load spell-check dictionary: {}", e);
+                    store.set("spellcheck.error", e.to_string());
+                }
+            }
+        }
+
+        // Apps granted "location" get sys.location.* filled in without scripting
+        if services.capabilities().allows("location") {
+            services.geo().locate();
+            store.set("sys.location.status", "pending");
+        }
+
+        // Apps granted "currency" with a [currency] section get currency.rate.* filled in on launch
+        if bundle.currency.is_some() && services.capabilities().allows("currency") {
+            services.currency().refresh(None);
+            store.set("currency.status", "pending");
+        }
+
+        // Restore persisted data for the selected (or last used) profile
+        let profiles = if bundle.persist.keys.is_empty() {
+            None
+        } else {
+            let mut profiles = ProfileStore::new(&data_dir, bundle.persist.keys.clone());
+            let loaded = match profile {
+                Some(name) => profiles.switch(&name, &mut store),
+                None => profiles.load(&mut store),
+            };
+            if let Err(e) = loaded {
+                eprintln!("Failed to load profile '{}': {}", profiles.current(), e);
+            }
+            Some(profiles)
+        };
+        // Share store keys with other crix apps, and read the channels they share
+        let publisher = bundle.publish.as_ref().and_then(|publish| {
+            Publisher::new(&channels_dir(), &publish.channel, publish.keys.clone())
+                .map_err(|e| eprintln!("Failed to publish channel '{}': {}", publish.channel, e))
+                .ok()
+        });
+        let subscribers = bundle
+            .subscribe
+            .channels
+            .iter()
+            .filter_map(|channel| {
+                Subscriber::new(&channels_dir(), channel)
+                    .map_err(|e| eprintln!("Failed to subscribe to channel '{}': {}", channel, e))
+                    .ok()
+            })
+            .collect();
+        // Scheduled actions, and those missed since the app last ran
+        let (scheduler, missed) = if bundle.schedule.jobs.is_empty() {
+            (None, Vec::new())
+        } else {
+            let mut jobs: Vec<_> = bundle
+                .schedule
+                .jobs
+                .iter()
+                .filter_map(|(expr, action)| Some((CronSchedule::parse(expr).ok()?, action.clone())))
+                .collect();
+            jobs.sort_by(|a, b| a.1.cmp(&b.1));
+            let now = Local::now().naive_local();
+            let (scheduler, missed) =
+                Scheduler::new(jobs, bundle.schedule.catch_up, now).with_state_dir(&data_dir, now);
+            (Some(scheduler), missed)
+        };
+
+        // Build UI tree from skin, recolored with the palette picked in the restored profile
+        let skin_colors = pick_palette(&skin, &mut store);
+        skin.remap_palette(&skin_colors);
+        let (mut tree, window) = SkinBuilder::build(&skin)?;
+        add_image_view_root(&mut tree, &bundle.root().join("resources"));
 
-        Ok(Self {
+        let macros = Macros::load(&macros_file(profiles.as_ref(), &data_dir)).unwrap_or_else(|e| {
+            eprintln!("Failed to load macros: {}", e);
+            Macros::new()
+        });
+
+        let mut app = Self {
+            bundle,
             tree,
             title,
+            window,
+            window_drag: false,
+            window_close: false,
+            window_center: false,
+            since_refresh: Duration::ZERO,
             store,
             dispatcher,
             services,
-        })
+            profiles,
+            publisher,
+            subscribers,
+            last_channel_poll: Instant::now(),
+            scheduler,
+            timers: Timers::new(),
+            alarms_published: None,
+            unlocked_tree: None,
+            last_input: Instant::now(),
+            failed_pins: 0,
+            pin_locked_until: None,
+            cursor: (0, 0),
+            wizard,
+            wizard_step: 0,
+            modifiers: ModifiersState::empty(),
+            palette: CommandPalette::new(),
+            tooltip: Tooltip::new(),
+            context_menu: ContextMenu::new(),
+            confetti,
+            cross_fade: CrossFade::new(),
+            inertia: InertiaConfig::default(),
+            spelling_target: None,
+            spelling_language: None,
+            macros,
+            data_dir,
+            dev_console: dev.then(DevConsole::new),
+            last_changes: Vec::new(),
+            trace_changes: false,
+            paused: false,
+            paused_actions: VecDeque::new(),
+            history: dev.then(StoreHistory::default),
+            skin_watcher,
+            render_stats: RenderStats::default(),
+            skin_colors,
+        };
+        app.show_wizard_step(0);
+        app.publish_insets();
+        app.publish_profiles();
+        app.publish_macros();
+        app.publish_alarms();
+        app.load_inputs_from_store();
+        app.sync_store_to_outputs();
+        // Widget packs fill in their content as soon as they open
+        if let Some(action) = app.bundle.widget.as_ref().and_then(|widget| widget.on_refresh.clone()) {
+            app.run_action(&Action::new(action));
+        }
+        for action in missed {
+            app.run_action(&Action::new(action));
+        }
+        Ok(app)
+    }
+
+    /// Run the scheduled actions that are due. Returns true if any ran.
+    fn run_scheduled(&mut self) -> bool {
+        let Some(scheduler) = &mut self.scheduler else {
+            return false;
+        };
+        let due = scheduler.due(Local::now().naive_local());
+        for action in &due {
+            self.run_action(&Action::new(action.clone()));
+        }
+        !due.is_empty()
+    }
+
+    /// Run a widget pack's refresh action each time its interval passes.
+    /// Returns true if it ran.
+    fn refresh_widget(&mut self, dt: Duration) -> bool {
+        let Some(widget) = &self.bundle.widget else {
+            return false;
+        };
+        let (Some(interval), Some(action)) = (widget.refresh, widget.on_refresh.clone()) else {
+            return false;
+        };
+        self.since_refresh += dt;
+        if self.since_refresh < interval {
+            return false;
+        }
+        // A long stall runs the action once, not once per missed interval
+        self.since_refresh = Duration::ZERO;
+        self.run_action(&Action::new(action));
+        true
+    }
+
+    /// Write the window's insets to `window.inset.top`, `.right`, `.bottom`
+    /// and `.left`, for scripts and templates that place things themselves.
+    fn publish_insets(&mut self) {
+        let insets = self.tree.insets();
+        self.store.set("window.inset.top", insets.top as f64);
+        self.store.set("window.inset.right", insets.right as f64);
+        self.store.set("window.inset.bottom", insets.bottom as f64);
+        self.store.set("window.inset.left", insets.left as f64);
+    }
+
+    /// Write the active profile to `profile.current` and the available ones
+    /// to the `profile.list` list (`profile.list.count`, `profile.list.<n>.name`).
+    fn publish_profiles(&mut self) {
+        let Some(profiles) = &self.profiles else {
+            return;
+        };
+        self.store.set("profile.current", profiles.current());
+        let names = profiles.profiles();
+        self.store.set("profile.list.count", names.len() as f64);
+        for (i, name) in names.into_iter().enumerate() {
+            self.store.set(format!("profile.list.{}.name", i), name);
+        }
+    }
+
+    /// Handle the built-in profile.switch action: save the active profile and
+    /// load the one named by the `profile.select` store key.
+    fn handle_profile_switch(&mut self) {
+        let name = self.store.get_string("profile.select");
+        let Some(profiles) = &mut self.profiles else {
+            self.store.set("profile.error", "No [persist] keys declared in app.toml");
+            return;
+        };
+        match profiles.switch(&name, &mut self.store) {
+            Ok(()) => {
+                println!("Switched to profile: {}", name);
+                self.store.remove("profile.error");
+                self.publish_profiles();
+                self.load_inputs_from_store();
+                // Each profile has its own macros
+                self.macros = Macros::load(&self.macros_path()).unwrap_or_else(|e| {
+                    eprintln!("Failed to load macros: {}", e);
+                    Macros::new()
+                });
+                self.publish_macros();
+            }
+            Err(e) => {
+                eprintln!("Failed to switch profile: {}", e);
+                self.store.set("profile.error", e.to_string());
+            }
+        }
+    }
+
+    /// Show persisted values in the text inputs and checkboxes bound to them.
+    fn load_inputs_from_store(&mut self) {
+        let Some(profiles) = self.profiles.take() else {
+            return;
+        };
+        self.load_inputs_where(|binding| profiles.is_persisted(binding));
+        self.profiles = Some(profiles);
+    }
+
+    /// Show store values in the text inputs, text areas and checkboxes whose binding
+    /// passes `include`.
+    fn load_inputs_where(&mut self, include: impl Fn(&str) -> bool) {
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id) {
+                let widget = node.widget_mut().as_any_mut();
+                if let Some(text_input) = text_input_mut(widget) {
+                    if let Some(binding) = text_input.binding()
+                        && include(binding)
+                    {
+                        // Numbers are typed with the app's decimal separator
+                        let text = match self.store.get_number(binding) {
+                            Some(number) if text_input.number_format().is_some() => {
+                                self.services.format().format_number(number, None, false)
+                            }
+                            _ => self.store.get_string(binding),
+                        };
+                        text_input.set_text(text);
+                    }
+                } else if let Some(text_area) = widget.downcast_mut::<TextArea>() {
+                    if let Some(binding) = text_area.binding()
+                        && include(binding)
+                    {
+                        let text = self.store.get_string(binding);
+                        text_area.set_text(text);
+                    }
+                } else if let Some(checkbox) = widget.downcast_mut::<Checkbox>()
+                    && let Some(binding) = checkbox.binding()
+                    && include(binding)
+                {
+                    let checked = self.store.get_bool(binding);
+                    checkbox.set_checked(checked);
+                    checkbox.clear_dirty();
+                }
+            }
+        }
+    }
+
+    /// Replace the screen with `tree`, fading the old one out over the
+    /// configured transition. Returns the old screen.
+    fn swap_tree(&mut self, mut tree: UiTree) -> UiTree {
+        // Lay the new screen out for the window as it is now
+        let (width, height) = self.tree.size();
+        tree.layout(width, height);
+        tree.set_inertia(&self.inertia);
+        if !self.bundle.transition.is_zero() {
+            // Capture the old screen as the renderer would draw it
+            let (width, height) = self.tree.size();
+            let mut frame = vec![0u32; width as usize * height as usize];
+            {
+                let mut canvas = Canvas::new(&mut frame, width, height);
+                canvas.clear(0x000000);
+                self.tree.draw(&mut canvas);
+            }
+            self.cross_fade.start(frame, width, height, self.bundle.transition);
+        }
+        self.context_menu.close();
+        self.tooltip = Tooltip::new();
+        let old = std::mem::replace(&mut self.tree, tree);
+        self.publish_insets();
+        old
+    }
+
+    /// Handle the built-in theme.switch action: rebuild the screen from the
+    /// layout named by payload `theme` or the `theme.select` store key, or
+    /// from the app's own skin for `default`, fading from the old look.
+    fn handle_theme_switch(&mut self, action: &Action) {
+        if self.is_locked() {
+            self.store.set("theme.error", "Themes can't be switched while locked");
+            return;
+        }
+        let name = match action.get_str("theme") {
+            Some(name) => name.to_string(),
+            None => self.store.get_string("theme.select"),
+        };
+        let name = if name.is_empty() { DEFAULT_THEME.to_string() } else { name };
+        match self.load_screen(&name) {
+            Ok(()) => {
+                self.store.set("theme.current", name);
+                self.store.remove("theme.error");
+            }
+            Err(e) => {
+                eprintln!("Failed to switch to theme '{}': {}", name, e);
+                self.store.set("theme.error", e.to_string());
+            }
+        }
+    }
+
+    /// Replace the screen with one built from the skin of theme `name` (the
+    /// app's own skin for `default`), showing the store's values in it.
+    fn load_screen(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut skin = if name == DEFAULT_THEME { self.bundle.load_skin()? } else { self.bundle.load_layout(name)? };
+        self.skin_colors = pick_palette(&skin, &mut self.store);
+        skin.remap_palette(&self.skin_colors);
+        let (mut tree, _window_config) = SkinBuilder::build(&skin)?;
+        add_image_view_root(&mut tree, &self.bundle.root().join("resources"));
+
+        self.swap_tree(tree);
+        // Node ids of the old screen mean nothing in the new one
+        self.spelling_target = None;
+        self.spelling_language = None;
+        self.show_wizard_step(self.wizard_step);
+        self.load_inputs_from_store();
+        self.sync_store_to_outputs();
+        if let Some(watcher) = &mut self.skin_watcher {
+            watcher.watch(skin.files());
+        }
+        Ok(())
+    }
+
+    /// In dev mode, rebuild the screen once its skin file or images have
+    /// been edited. Returns true if the screen was rebuilt.
+    fn reload_edited_skin(&mut self) -> bool {
+        // The lock screen isn't rebuilt; edits show once it is unlocked
+        if self.is_locked() || !self.skin_watcher.as_mut().is_some_and(SkinWatcher::poll) {
+            return false;
+        }
+        match self.rebuild_screen() {
+            Ok(()) => {
+                println!("Reloaded skin");
+                true
+            }
+            Err(e) => {
+                // The old screen stays up until the skin loads again
+                eprintln!("Failed to reload skin: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Rebuild the screen once a `palette.<name>` store key picks a new
+    /// color for the skin's palette. While the pointer is pressed it waits,
+    /// so dragging a color picker recolors the skin once it is let go.
+    /// Returns true if the screen was rebuilt.
+    fn recolor_skin(&mut self) -> bool {
+        if self.is_locked() || self.tree.pressed().is_some() {
+            return false;
+        }
+        let chosen = chosen_palette(&self.store);
+        if chosen == self.skin_colors {
+            return false;
+        }
+        match self.rebuild_screen() {
+            Ok(()) => true,
+            Err(e) => {
+                // Not tried again until the picks change
+                eprintln!("Failed to recolor skin: {}", e);
+                self.skin_colors = chosen;
+                false
+            }
+        }
+    }
+
+    /// Rebuild the screen from the current theme's skin, keeping the store
+    /// and focusing the same part again.
+    fn rebuild_screen(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let theme = self.store.get_string("theme.current");
+        let name = if theme.is_empty() { DEFAULT_THEME } else { theme.as_str() };
+        let focused = self
+            .tree
+            .focused()
+            .and_then(|id| self.tree.get(id))
+            .and_then(|node| node.name())
+            .map(str::to_string);
+
+        self.load_screen(name)?;
+        if let Some(id) = focused.and_then(|part| self.tree.find_by_name(&part)) {
+            self.tree.set_focused(Some(id));
+            if let Some(node) = self.tree.get_mut(id) {
+                node.widget_mut().on_event(&WidgetEvent::FocusGained);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if the lock layout is showing.
+    fn is_locked(&self) -> bool {
+        self.unlocked_tree.is_some()
+    }
+
+    /// Handle the built-in app.lock action: replace the screen with the lock
+    /// layout until the PIN is entered.
+    fn handle_lock(&mut self) {
+        if self.is_locked() {
+            return;
+        }
+        let Some(lock) = self.bundle.lock.clone() else {
+            self.store.set("lock.error", "No [lock] section in app.toml");
+            return;
+        };
+        if !self.services.secrets().has(LOCK_PIN_SECRET) {
+            self.store.set("lock.error", "No PIN set");
+            return;
+        }
+
+        self.store.remove("lock.pin");
+        self.store.remove("lock.error");
+        self.store.set("lock.locked", true);
+        match self.build_layout(&lock.layout) {
+            Ok(lock_tree) => {
+                self.unlocked_tree = Some(self.swap_tree(lock_tree));
+                self.palette.close();
+                self.sync_store_to_outputs();
+            }
+            Err(e) => {
+                eprintln!("Failed to build lock layout '{}': {}", lock.layout, e);
+                self.store.set("lock.locked", false);
+                self.store.set("lock.error", e.to_string());
+            }
+        }
+    }
+
+    /// Handle the built-in app.unlock action: check the `lock.pin` store key
+    /// and return to the app screen if it matches.
+    fn handle_unlock(&mut self) {
+        if !self.is_locked() {
+            return;
+        }
+        let pin = self.store.get_string("lock.pin");
+        self.clear_bound_inputs("lock.pin");
+
+        if self.check_pin(&pin) {
+            if let Some(tree) = self.unlocked_tree.take() {
+                self.swap_tree(tree);
+            }
+            self.store.set("lock.locked", false);
+            self.store.remove("lock.error");
+            self.last_input = Instant::now();
+        }
+    }
+
+    /// Check an entered PIN against the lock PIN, making attempts wait
+    /// longer and longer after too many wrong ones. Sets `lock.error` if the
+    /// PIN is refused.
+    fn check_pin(&mut self, pin: &str) -> bool {
+        let now = Instant::now();
+        if let Some(until) = self.pin_locked_until.filter(|until| *until > now) {
+            let wait = until.duration_since(now).as_secs() + 1;
+            self.store.set("lock.error", format!("Too many wrong PINs, try again in {} s", wait));
+            return false;
+        }
+
+        if self.services.secrets().verify(LOCK_PIN_SECRET, pin) {
+            self.failed_pins = 0;
+            self.pin_locked_until = None;
+            return true;
+        }
+        self.failed_pins += 1;
+        if self.failed_pins >= FREE_PIN_ATTEMPTS {
+            let doublings = (self.failed_pins - FREE_PIN_ATTEMPTS).min(16);
+            let wait = PIN_LOCKOUT.saturating_mul(1 << doublings).min(MAX_PIN_LOCKOUT);
+            self.pin_locked_until = Some(now + wait);
+        }
+        self.store.set("lock.error", "Incorrect PIN");
+        false
     }
 
-    /// Sync text inputs to store (write dirty values).
+    /// Handle the built-in lock.set_pin action: store the hash of the
+    /// `lock.new_pin` store key as the lock PIN. Replacing a PIN needs the
+    /// current one in `lock.current_pin`.
+    fn handle_set_pin(&mut self) {
+        if self.is_locked() {
+            return;
+        }
+        let current = self.store.get_string("lock.current_pin");
+        let pin = self.store.get_string("lock.new_pin");
+        self.clear_bound_inputs("lock.current_pin");
+        self.clear_bound_inputs("lock.new_pin");
+
+        if self.services.secrets().has(LOCK_PIN_SECRET) && !self.check_pin(&current) {
+            return;
+        }
+        if pin.len() < MIN_PIN_LENGTH || !pin.chars().all(|c| c.is_ascii_digit()) {
+            self.store.set("lock.error", format!("PIN must be at least {} digits", MIN_PIN_LENGTH));
+            return;
+        }
+        match self.services.secrets().set(LOCK_PIN_SECRET, &pin) {
+            Ok(()) => {
+                self.store.remove("lock.error");
+            }
+            Err(e) => {
+                eprintln!("Failed to store PIN: {}", e);
+                self.store.set("lock.error", e.to_string());
+            }
+        }
+    }
+
+    /// Remove a key from the store and empty the text inputs bound to it,
+    /// so entered secrets don't linger.
+    fn clear_bound_inputs(&mut self, key: &str) {
+        self.store.remove(key);
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(text_input) = text_input_mut(node.widget_mut().as_any_mut())
+                && text_input.binding() == Some(key)
+            {
+                text_input.set_text(String::new());
+                text_input.clear_dirty();
+            }
+        }
+    }
+
+    /// Lock the app once the configured idle timeout has passed without input.
+    /// Returns true if the app was locked.
+    fn check_idle_lock(&mut self) -> bool {
+        let Some(timeout) = self.bundle.lock.as_ref().and_then(|lock| lock.idle_timeout) else {
+            return false;
+        };
+        if self.is_locked() || self.last_input.elapsed() < Duration::from_secs(timeout) {
+            return false;
+        }
+        self.handle_lock();
+        // Don't retry every poll if locking failed (e.g. no PIN set)
+        self.last_input = Instant::now();
+        self.is_locked()
+    }
+
+    /// Save persisted keys of the active profile if they changed.
+    fn save_profile(&mut self) {
+        if let Some(profiles) = &mut self.profiles
+            && let Err(e) = profiles.save_if_changed(&self.store)
+        {
+            eprintln!("Failed to save profile '{}': {}", profiles.current(), e);
+        }
+    }
+
+    /// Publish shared store keys that changed, and read subscribed channels
+    /// every `CHANNEL_POLL_INTERVAL`. Returns true if the store changed.
+    fn sync_channels(&mut self) -> bool {
+        if let Some(publisher) = &mut self.publisher
+            && let Err(e) = publisher.publish_if_changed(&self.store)
+        {
+            eprintln!("Failed to publish shared keys: {}", e);
+        }
+        if self.subscribers.is_empty() || self.last_channel_poll.elapsed() < CHANNEL_POLL_INTERVAL {
+            return false;
+        }
+        self.last_channel_poll = Instant::now();
+
+        let mut changed = Vec::new();
+        for subscriber in &mut self.subscribers {
+            match subscriber.poll(&mut self.store) {
+                Ok(true) => changed.push(format!("{}.", subscriber.channel())),
+                Ok(false) => {}
+                Err(e) => eprintln!("Failed to read channel '{}': {}", subscriber.channel(), e),
+            }
+        }
+        if changed.is_empty() {
+            return false;
+        }
+        self.load_inputs_where(|binding| changed.iter().any(|prefix| binding.starts_with(prefix.as_str())));
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Sync text inputs and text areas to store (write dirty values).
     fn sync_inputs_to_store(&mut self) {
         let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
 
         for id in node_ids {
             if let Some(node) = self.tree.get_mut(id) {
-                if let Some(text_input) = node.widget_mut().as_any_mut().downcast_mut::<TextInput>() {
+                if let Some(text_input) = text_input_mut(node.widget_mut().as_any_mut()) {
                     if text_input.is_dirty() {
                         if let Some(binding) = text_input.binding() {
-                            let text = text_input.text().to_string();
-                            self.store.set(binding.to_string(), text);
+                            let text = text_input.value();
+                            // Numeric inputs keep the plain number in the store
+                            let number = text_input
+                                .number_format()
+                                .and_then(|_| self.services.format().parse_number(&text));
+                            match number {
+                                Some(number) => self.store.set(binding.to_string(), number),
+                                None => self.store.set(binding.to_string(), text),
+                            }
                         }
                         text_input.clear_dirty();
                     }
+                } else if let Some(text_area) = node.widget_mut().as_any_mut().downcast_mut::<TextArea>()
+                    && text_area.is_dirty()
+                {
+                    if let Some(binding) = text_area.binding() {
+                        let text = text_area.text().to_string();
+                        self.store.set(binding.to_string(), text);
+                    }
+                    text_area.clear_dirty();
                 }
             }
         }
     }
 
-    /// Sync checkboxes to store (write dirty values).
-    fn sync_checkboxes_to_store(&mut self) {
+    /// Sync checkboxes and toggle switches to store (write dirty values).
+    /// Returns true if any changed.
+    fn sync_checkboxes_to_store(&mut self) -> bool {
+        let mut changed = false;
         let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
 
         for id in node_ids {
@@ -134,123 +972,1630 @@ impl SkinApp {
                     if checkbox.is_dirty() {
                         if let Some(binding) = checkbox.binding() {
                             self.store.set(binding.to_string(), checkbox.is_checked());
+                            changed = true;
                         }
                         checkbox.clear_dirty();
                     }
+                } else if let Some(switch) = node.widget_mut().as_any_mut().downcast_mut::<ToggleSwitch>()
+                    && switch.is_dirty()
+                {
+                    if let Some(binding) = switch.binding() {
+                        self.store.set(binding.to_string(), switch.is_on());
+                        changed = true;
+                    }
+                    switch.clear_dirty();
                 }
             }
         }
+        changed
     }
 
-    /// Sync store values to static text widgets (update displays).
+    /// Sync store values to display widgets (static text, custom paint, bound images).
     fn sync_store_to_outputs(&mut self) {
+        apply_store_to_tree(&mut self.tree, &self.store);
+    }
+
+    /// Apply store-driven playback requests to video widgets and publish
+    /// their position. Returns true if anything visible changed.
+    #[cfg(feature = "video")]
+    fn sync_videos(&mut self) -> bool {
+        let mut changed = false;
         let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
 
         for id in node_ids {
-            if let Some(node) = self.tree.get_mut(id) {
-                if let Some(static_text) = node.widget_mut().as_any_mut().downcast_mut::<StaticText>() {
-                    if let Some(binding) = static_text.binding() {
-                        let value = self.store.get_string(binding);
-                        if !value.is_empty() && value != static_text.content() {
-                            static_text.set_content(value);
-                        }
-                    }
+            let Some(node) = self.tree.get_mut(id) else {
+                continue;
+            };
+            let Some(video) = node.widget_mut().as_any_mut().downcast_mut::<SkinVideo>() else {
+                continue;
+            };
+            let prefix = video.binding().to_string();
+            let playing_key = format!("{}.playing", prefix);
+
+            // Seek requests are one-shot
+            if let Some(position) = self.store.remove(&format!("{}.seek", prefix)).and_then(|v| v.as_number()) {
+                video.seek(position);
+            }
+            if self.store.contains(&playing_key) {
+                match (self.store.get_bool(&playing_key), video.is_playing()) {
+                    (true, false) => video.play(),
+                    (false, true) => video.pause(),
+                    _ => {}
+                }
+            }
+            video.poll();
+
+            // Publish state, rounded so bound text doesn't churn every frame
+            let position = (video.position() * 10.0).round() / 10.0;
+            let position_key = format!("{}.position", prefix);
+            if self.store.get_number(&position_key) != Some(position) {
+                self.store.set(position_key, position);
+                changed = true;
+            }
+            if let Some(duration) = video.duration() {
+                let duration_key = format!("{}.duration", prefix);
+                if self.store.get_number(&duration_key) != Some(duration) {
+                    self.store.set(duration_key, duration);
+                    changed = true;
+                }
+            }
+            self.store.set(playing_key, video.is_playing());
+            changed |= video.needs_redraw();
+        }
+
+        if changed {
+            self.sync_store_to_outputs();
+        }
+        changed
+    }
+
+    /// Publish finished exchange rates. Returns true if the store changed.
+    fn publish_currency_rates(&mut self) -> bool {
+        match self.services.currency().take_result() {
+            Some(Ok(rates)) => rates.publish(&mut self.store),
+            Some(Err(e)) => {
+                eprintln!("Exchange rate refresh failed: {}", e);
+                self.store.set("currency.status", "error");
+                self.store.set("currency.error", e);
+            }
+            None => return false,
+        }
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Publish a finished location lookup. Returns true if the store changed.
+    fn publish_location(&mut self) -> bool {
+        match self.services.geo().take_result() {
+            Some(Ok(location)) => location.publish(&mut self.store),
+            Some(Err(e)) => {
+                eprintln!("Location lookup failed: {}", e);
+                self.store.set("sys.location.status", "error");
+                self.store.set("sys.location.error", e);
+            }
+            None => return false,
+        }
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Mark the camera stopped once its capture thread has ended on its
+    /// own, publishing why. Returns true if the store changed.
+    fn publish_camera_status(&mut self) -> bool {
+        let camera = self.services.camera();
+        if !self.store.get_bool("camera.active") || camera.is_running() {
+            return false;
+        }
+        self.store.set("camera.active", false);
+        let error = camera.last_error().unwrap_or_else(|| "Camera stopped".to_string());
+        self.store.set("camera.error", error);
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Publish updater progress. Returns true if the store changed.
+    fn publish_update_status(&mut self) -> bool {
+        let statuses = self.services.update().take_statuses();
+        if statuses.is_empty() {
+            return false;
+        }
+        for status in statuses {
+            status.publish(&mut self.store);
+        }
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Publish queued scan results and fire their `on_scan` actions.
+    /// Returns true if any results were published.
+    fn publish_scan_events(&mut self) -> bool {
+        let events = self.services.scan().take_events();
+        if events.is_empty() {
+            return false;
+        }
+
+        for event in events {
+            self.store.set(event.key, event.result.text);
+            self.store.set("scan.format", event.result.format);
+            self.store.remove("scan.error");
+            self.dispatch_action(&event.on_scan);
+        }
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Resume work left running by earlier actions (e.g. scripts in
+    /// `app.yield()`) and show what it changed. Returns true if anything ran.
+    fn poll_handlers(&mut self) -> bool {
+        if !self.dispatcher.poll(&mut self.store, &self.services) {
+            return false;
+        }
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Run a button or palette action, handling the built-ins that need the
+    /// tree or bundle before falling back to the dispatcher.
+    fn run_action(&mut self, action: &Action) {
+        // Hold actions back while stopped at a breakpoint
+        if self.paused {
+            if let Some(console) = &mut self.dev_console {
+                console.info(format!("Queued '{}' (paused)", action.name));
+            }
+            self.paused_actions.push_back(action.clone());
+            self.publish_console_status();
+            return;
+        }
+        // Actions would change a store that is only showing the past
+        if self.history.as_ref().is_some_and(StoreHistory::is_scrubbing) {
+            if let Some(console) = &mut self.dev_console {
+                console.error(format!("Ignored '{}' while viewing history ('live' returns)", action.name));
+            }
+            return;
+        }
+        self.record_history("input");
+        self.macros.record(action);
+        // In dev mode, remember what the action changed
+        let before = self.dev_console.is_some().then(|| self.store.snapshot());
+        let action_name = action.name.as_str();
+        if action_name == "launch_selected_app" {
+            self.handle_launch_selected_app();
+        } else if action_name == "print" {
+            self.handle_print();
+        } else if action_name == "export_pdf" {
+            self.handle_export("pdf");
+        } else if action_name == "export_png" {
+            self.handle_export("png");
+        } else if action_name == "profile.switch" {
+            self.handle_profile_switch();
+        } else if action_name == "app.lock" {
+            self.handle_lock();
+        } else if action_name == "app.unlock" {
+            self.handle_unlock();
+        } else if action_name == "lock.set_pin" {
+            self.handle_set_pin();
+        } else if action_name == "wizard.next" {
+            self.handle_wizard_next();
+        } else if action_name == "wizard.back" {
+            self.handle_wizard_back();
+        } else if action_name == "macro.record" {
+            self.handle_macro_record();
+        } else if action_name == "macro.stop" {
+            self.handle_macro_stop();
+        } else if action_name == "macro.play" {
+            let name = self.store.get_string("macro.name");
+            self.play_macro(&name);
+        } else if action_name == "macro.delete" {
+            self.handle_macro_delete();
+        } else if let Some(name) = action_name.strip_prefix(MACRO_PREFIX) {
+            self.play_macro(name);
+        } else if action_name == "theme.switch" {
+            self.handle_theme_switch(action);
+        } else if action_name == "fx.confetti" {
+            self.handle_confetti(action);
+        } else if action_name == "window.close" {
+            self.window_close = true;
+        } else if action_name == "window.center" {
+            self.window_center = true;
+        } else if matches!(action_name, "timer.start" | "timer.stop" | "timer.reset") {
+            self.handle_timer(action);
+        } else if action_name == "store.import_json" {
+            self.dispatch(action);
+            // Show the imported values in the inputs bound to them
+            let keys: std::collections::HashSet<String> = self.store.keys().cloned().collect();
+            self.load_inputs_where(|binding| keys.contains(binding));
+        } else {
+            // Dispatch the action to Lua handler
+            self.dispatch(action);
+        }
+
+        self.record_history(action_name);
+        if let Some(before) = before {
+            self.last_changes = Store::diff(&before, &self.store.snapshot());
+            if self.trace_changes {
+                self.trace_action_changes(action_name);
+            }
+            self.check_breakpoints(action_name);
+        }
+    }
+
+    /// Pause dispatch if the last action changed a key with a breakpoint.
+    fn check_breakpoints(&mut self, action_name: &str) {
+        let Some(console) = &mut self.dev_console else {
+            return;
+        };
+        let Some(key) = console.breakpoint_hit(&self.last_changes) else {
+            return;
+        };
+        let message = format!("Breakpoint: '{}' changed {}", action_name, key);
+        println!("{}", message);
+        console.error(message);
+        console.info("'continue' resumes, 'step' runs the next queued action, 'diff' shows the changes");
+        console.open();
+        self.paused = true;
+        self.publish_console_status();
+    }
+
+    /// Show the pause and time-travel state in the dev console status line.
+    fn publish_console_status(&mut self) {
+        let Some(console) = &mut self.dev_console else {
+            return;
+        };
+        let viewing = self.history.as_ref().and_then(|history| {
+            let index = history.cursor()?;
+            let frame = history.get(index)?;
+            Some(format!(
+                "HISTORY {}/{} at {:.2}s after '{}'",
+                index,
+                history.len() - 1,
+                frame.at.as_secs_f64(),
+                frame.label
+            ))
+        });
+        let status = if self.paused {
+            Some(format!("PAUSED ({} queued)", self.paused_actions.len()))
+        } else {
+            viewing
+        };
+        console.set_status(status);
+    }
+
+    /// Record the store in the time-travel history if it changed.
+    fn record_history(&mut self, label: &str) {
+        if let Some(history) = &mut self.history {
+            let label = if history.is_empty() { "start" } else { label };
+            history.record(label, &self.store);
+        }
+    }
+
+    /// Move through the store history with `seek`, `step` or `live` and
+    /// re-render the UI from the store as it was at that frame.
+    fn travel(&mut self, to: impl FnOnce(&mut StoreHistory) -> Option<&StoreSnapshot>) {
+        let Some(history) = &mut self.history else {
+            return;
+        };
+        // Keep the latest edits so 'live' can return to them
+        if !history.is_scrubbing() {
+            history.record("input", &self.store);
+        }
+        let Some(snapshot) = to(history).cloned() else {
+            if let Some(console) = &mut self.dev_console {
+                console.error("No history recorded yet");
+            }
+            return;
+        };
+        self.store.restore(&snapshot);
+        self.load_inputs_where(|_| true);
+        self.sync_store_to_outputs();
+        if let Some(console) = &mut self.dev_console {
+            console.update_watches(&self.store);
+        }
+        self.publish_console_status();
+    }
+
+    /// Resume dispatch, running queued actions until the queue is empty or
+    /// another breakpoint is hit. With `single_step`, run one action and
+    /// stay paused.
+    fn resume_actions(&mut self, single_step: bool) {
+        if !self.paused {
+            if let Some(console) = &mut self.dev_console {
+                console.info("Not paused");
+            }
+            return;
+        }
+        self.paused = false;
+        while let Some(action) = self.paused_actions.pop_front() {
+            self.sync_inputs_to_store();
+            self.run_action(&action);
+            self.sync_store_to_outputs();
+            if self.paused || single_step {
+                break;
+            }
+        }
+        if single_step && self.paused_actions.is_empty() && !self.paused {
+            if let Some(console) = &mut self.dev_console {
+                console.info("Queue empty, resumed");
+            }
+        } else if single_step {
+            self.paused = true;
+        }
+        self.publish_console_status();
+    }
+
+    /// Print the last action's store changes to stdout and the dev console.
+    fn trace_action_changes(&mut self, action_name: &str) {
+        let Some(console) = &mut self.dev_console else {
+            return;
+        };
+        println!("[{}] {} change(s)", action_name, self.last_changes.len());
+        console.info(format!("[{}] {} change(s)", action_name, self.last_changes.len()));
+        for change in &self.last_changes {
+            println!("  {}", change);
+            console.push(LineKind::Change, format!("  {}", change));
+        }
+    }
+
+    /// Handle a key press while the dev console is open.
+    fn handle_console_key(&mut self, key: &Key) {
+        let Some(console) = &mut self.dev_console else {
+            return;
+        };
+        match key {
+            Key::Named(NamedKey::Escape) => console.close(),
+            Key::Named(NamedKey::ArrowUp) => console.history_previous(),
+            Key::Named(NamedKey::ArrowDown) => console.history_next(),
+            Key::Named(NamedKey::PageUp) => self.travel(|history| history.step(-1)),
+            Key::Named(NamedKey::PageDown) => self.travel(|history| history.step(1)),
+            Key::Named(NamedKey::Backspace) => console.backspace(),
+            Key::Named(NamedKey::Space) => console.insert_char(' '),
+            Key::Named(NamedKey::Enter) => {
+                if let Some(command) = console.submit() {
+                    self.run_console_command(&command);
+                }
+            }
+            Key::Character(s) => {
+                for c in s.chars().filter(|c| !c.is_control()) {
+                    console.insert_char(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Describe the history frame being shown and its changes.
+    fn push_history_frame(&self, output: &mut Vec<(LineKind, String)>) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        let Some((index, frame)) = history.position().and_then(|index| Some((index, history.get(index)?))) else {
+            return;
+        };
+        output.push((
+            LineKind::Info,
+            format!("State {} at {:.2}s after '{}'", index, frame.at.as_secs_f64(), frame.label),
+        ));
+        for change in &frame.changes {
+            output.push((LineKind::Change, change.to_string()));
+        }
+    }
+
+    /// Run a dev console command.
+    fn run_console_command(&mut self, command: &str) {
+        let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+        let args = args.trim();
+        let mut output: Vec<(LineKind, String)> = Vec::new();
+        match name {
+            "help" => {
+                for line in [
+                    "diff              store changes made by the last action",
+                    "trace on|off      print the changes of every action",
+                    "get <key>         show a store value",
+                    "set <key> <json>  set a store value (plain text is a string)",
+                    "keys [prefix]     list store keys",
+                    "run <action>      run an action and show its changes",
+                    "watch <key>       watch a key (or 'prefix.*') in the side panel",
+                    "break <key>       watch a key and pause when an action changes it",
+                    "unwatch <key>     remove a watch or breakpoint",
+                    "watches           list watches and breakpoints",
+                    "stats             show what drawing the last frame took",
+                    "continue          resume paused actions",
+                    "step              run the next paused action",
+                    "history [count]   list recorded store states (default: last 10)",
+                    "back [n]          show the store n states earlier (or PageUp)",
+                    "forward [n]       show the store n states later (or PageDown)",
+                    "seek <n>          show recorded state n",
+                    "live              return to the current store",
+                    "resume            keep the shown state and drop the later ones",
+                    "clear             clear the console",
+                ] {
+                    output.push((LineKind::Info, line.to_string()));
+                }
+            }
+            "diff" => {
+                if self.last_changes.is_empty() {
+                    output.push((LineKind::Info, "No changes".to_string()));
+                }
+                for change in &self.last_changes {
+                    output.push((LineKind::Change, change.to_string()));
+                }
+            }
+            "trace" => match args {
+                "on" | "off" => {
+                    self.trace_changes = args == "on";
+                    output.push((LineKind::Info, format!("Tracing {}", args)));
+                }
+                _ => output.push((LineKind::Error, "Usage: trace on|off".to_string())),
+            },
+            "get" if !args.is_empty() => {
+                let line = match self.store.get(args) {
+                    Some(value) => format!("{} = {}", args, value.to_json()),
+                    None => format!("{} is not set", args),
+                };
+                output.push((LineKind::Info, line));
+            }
+            "set" if args.contains(' ') => {
+                let (key, text) = args.split_once(' ').unwrap_or_default();
+                let value = serde_json::from_str(text.trim())
+                    .map(Value::from_json)
+                    .unwrap_or_else(|_| Value::string(text.trim()));
+                output.push((LineKind::Info, format!("{} = {}", key, value.to_json())));
+                self.store.set(key, value);
+                self.load_inputs_where(|binding| binding == key);
+                self.sync_store_to_outputs();
+            }
+            "keys" => {
+                let mut keys: Vec<&String> = self.store.keys().filter(|key| key.starts_with(args)).collect();
+                keys.sort();
+                for key in keys {
+                    output.push((LineKind::Info, key.clone()));
+                }
+            }
+            "run" if !args.is_empty() => {
+                self.sync_inputs_to_store();
+                self.run_action(&Action::new(args));
+                self.sync_store_to_outputs();
+                if !self.trace_changes {
+                    output.push((LineKind::Info, format!("{} change(s)", self.last_changes.len())));
+                    for change in &self.last_changes {
+                        output.push((LineKind::Change, change.to_string()));
+                    }
+                }
+            }
+            "watch" | "break" if !args.is_empty() => {
+                if let Some(console) = &mut self.dev_console {
+                    console.watch(args, name == "break");
+                    console.update_watches(&self.store);
+                }
+                let what = if name == "break" { "Breakpoint on" } else { "Watching" };
+                output.push((LineKind::Info, format!("{} {}", what, args)));
+            }
+            "unwatch" if !args.is_empty() => {
+                let removed = self.dev_console.as_mut().is_some_and(|console| console.unwatch(args));
+                if removed {
+                    output.push((LineKind::Info, format!("Removed {}", args)));
+                } else {
+                    output.push((LineKind::Error, format!("'{}' is not watched", args)));
+                }
+            }
+            "watches" => {
+                if let Some(console) = &self.dev_console {
+                    for (pattern, breakpoint) in console.watches() {
+                        let kind = if breakpoint { "break" } else { "watch" };
+                        output.push((LineKind::Info, format!("{} {}", kind, pattern)));
+                    }
+                }
+                if output.is_empty() {
+                    output.push((LineKind::Info, "No watches".to_string()));
+                }
+            }
+            "stats" => {
+                let stats = self.render_stats;
+                output.push((LineKind::Info, stats.to_string()));
+                if stats.glyphs > 0 {
+                    output.push((
+                        LineKind::Info,
+                        format!("  glyph cache: {} hits, {} misses", stats.glyph_cache_hits, stats.glyph_cache_misses),
+                    ));
+                }
+            }
+            "history" => {
+                let count = args.parse().unwrap_or(10);
+                if let Some(history) = &self.history {
+                    let skip = history.len().saturating_sub(count);
+                    for (index, frame) in history.frames().enumerate().skip(skip) {
+                        let marker = if history.position() == Some(index) { ">" } else { " " };
+                        output.push((
+                            LineKind::Info,
+                            format!(
+                                "{}{:4} {:8.2}s {} ({} change(s))",
+                                marker,
+                                index,
+                                frame.at.as_secs_f64(),
+                                frame.label,
+                                frame.changes.len()
+                            ),
+                        ));
+                    }
+                }
+                if output.is_empty() {
+                    output.push((LineKind::Info, "No history recorded yet".to_string()));
+                }
+            }
+            "back" | "forward" => {
+                let steps = args.parse::<isize>().unwrap_or(1);
+                let delta = if name == "back" { -steps } else { steps };
+                self.travel(|history| history.step(delta));
+                self.push_history_frame(&mut output);
+            }
+            "seek" => match args.parse::<usize>() {
+                Ok(index) => {
+                    self.travel(|history| history.seek(index));
+                    self.push_history_frame(&mut output);
+                }
+                Err(_) => output.push((LineKind::Error, "Usage: seek <n>".to_string())),
+            },
+            "live" => {
+                self.travel(StoreHistory::live);
+                output.push((LineKind::Info, "Showing the live store".to_string()));
+            }
+            "resume" => {
+                if let Some(history) = &mut self.history {
+                    history.resume_here();
+                }
+                self.publish_console_status();
+                output.push((LineKind::Info, "Recording from the shown state".to_string()));
+            }
+            "continue" => self.resume_actions(false),
+            "step" => self.resume_actions(true),
+            "clear" => {
+                if let Some(console) = &mut self.dev_console {
+                    console.clear();
+                }
+            }
+            _ => output.push((LineKind::Error, format!("Unknown command '{}' (try 'help')", command))),
+        }
+        if let Some(console) = &mut self.dev_console {
+            for (kind, line) in output {
+                console.push(kind, line);
+            }
+        }
+    }
+
+    /// File the macros of the active profile are saved in.
+    fn macros_path(&self) -> PathBuf {
+        macros_file(self.profiles.as_ref(), &self.data_dir)
+    }
+
+    /// Write the recorded macro names to the `macro.list` list
+    /// (`macro.list.count`, `macro.list.<n>.name`).
+    fn publish_macros(&mut self) {
+        let names: Vec<String> = self.macros.names().cloned().collect();
+        self.store.set("macro.list.count", names.len() as f64);
+        for (i, name) in names.into_iter().enumerate() {
+            self.store.set(format!("macro.list.{}.name", i), name);
+        }
+        self.store.set("macro.recording", self.macros.recording().is_some());
+    }
+
+    /// Save the macros, reporting failures in `macro.error`.
+    fn save_macros(&mut self) {
+        if let Err(e) = self.macros.save(&self.macros_path()) {
+            eprintln!("Failed to save macros: {}", e);
+            self.store.set("macro.error", e.to_string());
+        }
+    }
+
+    /// Handle the built-in macro.record action: record the following actions
+    /// under the name in the `macro.name` store key.
+    fn handle_macro_record(&mut self) {
+        let name = self.store.get_string("macro.name").trim().to_string();
+        if name.is_empty() {
+            self.store.set("macro.error", "Enter a macro name first");
+            return;
+        }
+        self.store.remove("macro.error");
+        self.macros.start_recording(name);
+        self.publish_macros();
+    }
+
+    /// Handle the built-in macro.stop action: save the recording.
+    fn handle_macro_stop(&mut self) {
+        match self.macros.stop_recording() {
+            Some(name) => {
+                println!("Recorded macro: {}", name);
+                self.store.remove("macro.error");
+                self.save_macros();
+            }
+            None => self.store.set("macro.error", "Nothing was recorded"),
+        }
+        self.publish_macros();
+    }
+
+    /// Handle the built-in macro.delete action: delete the macro named by
+    /// the `macro.name` store key.
+    fn handle_macro_delete(&mut self) {
+        let name = self.store.get_string("macro.name");
+        if self.macros.remove(&name) {
+            self.store.remove("macro.error");
+            self.save_macros();
+            self.publish_macros();
+        } else {
+            self.store.set("macro.error", format!("No macro named '{}'", name));
+        }
+    }
+
+    /// Handle the built-in fx.confetti action: throw a burst of confetti
+    /// from the `x`, `y` window position in the payload, or from the pointer.
+    /// A `count` in the payload overrides the skin's.
+    fn handle_confetti(&mut self, action: &Action) {
+        let x = action.get_number("x").map_or(self.cursor.0, |x| x as i32);
+        let y = action.get_number("y").map_or(self.cursor.1, |y| y as i32);
+        let count = action.get_number("count").map(|count| (count.max(0.0) as u32).min(MAX_CONFETTI_BURST));
+        self.confetti.burst((x, y), count);
+    }
+
+    /// Replay a recorded macro's actions in order.
+    fn play_macro(&mut self, name: &str) {
+        let Some(steps) = self.macros.get(name).map(<[Action]>::to_vec) else {
+            self.store.set("macro.error", format!("No macro named '{}'", name));
+            return;
+        };
+        self.store.remove("macro.error");
+        // Macro actions are never recorded, so playback can't recurse
+        for action in &steps {
+            self.run_action(action);
+            self.sync_store_to_outputs();
+        }
+    }
+
+    /// Handle the built-in timer.start, timer.stop and timer.reset actions on
+    /// the timer named by payload `name` or the `timer.name` store key. A
+    /// timer starting from the beginning counts down from payload `duration`
+    /// or its `<name>.duration` store key in seconds, if either is above zero.
+    fn handle_timer(&mut self, action: &Action) {
+        let name = match action.get_str("name") {
+            Some(name) => name.to_string(),
+            None => self.store.get_string("timer.name"),
+        };
+        let name = if name.is_empty() { DEFAULT_TIMER.to_string() } else { name };
+        let found = match action.name.as_str() {
+            "timer.start" => {
+                let seconds = action
+                    .get_number("duration")
+                    .or_else(|| self.store.get_number(&format!("{}.duration", name)));
+                let duration = seconds
+                    .filter(|&seconds| seconds > 0.0)
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+                self.timers.start(&name, duration, &mut self.store);
+                true
+            }
+            "timer.stop" => self.timers.stop(&name, &mut self.store),
+            _ => self.timers.reset(&name, &mut self.store),
+        };
+        if found {
+            self.store.remove("timer.error");
+        } else {
+            self.store.set("timer.error", format!("No timer named '{}'", name));
+        }
+        self.sync_store_to_outputs();
+    }
+
+    /// Advance the running timers, running the `<name>.on_done` action of
+    /// each countdown that reaches zero. Returns true if the store changed.
+    fn tick_timers(&mut self, dt: Duration) -> bool {
+        let (changed, finished) = self.timers.tick(dt, &mut self.store);
+        if changed {
+            self.sync_store_to_outputs();
+        }
+        for name in finished {
+            let on_done = self.store.get_string(&format!("{}.on_done", name));
+            if !on_done.is_empty() {
+                self.run_action(&Action::new(on_done));
+            }
+        }
+        changed
+    }
+
+    /// Set off the alarms that are due: show their notification (with the
+    /// "notifications" capability), write `alarm.fired` and
+    /// `alarm.fired_message`, and run each one's action. Returns true if
+    /// the store changed.
+    fn fire_alarms(&mut self) -> bool {
+        let due = self.services.alarms().due(Local::now().naive_local());
+        for alarm in &due {
+            if self.services.capabilities().allows("notifications") {
+                let body = if alarm.message.is_empty() { &alarm.id } else { &alarm.message };
+                if let Err(e) = self.services.notify().notify(&self.bundle.meta.name, body, alarm.sound) {
+                    eprintln!("Alarm '{}': {}", alarm.id, e);
+                }
+            }
+            self.store.set("alarm.fired", alarm.id.clone());
+            self.store.set("alarm.fired_message", alarm.message.clone());
+            if let Some(action) = &alarm.action {
+                self.run_action(&Action::new(action.clone()));
+            }
+        }
+        let changed = self.publish_alarms() || !due.is_empty();
+        if changed {
+            self.sync_store_to_outputs();
+        }
+        changed
+    }
+
+    /// Write the alarms to `alarm.list.*` and the next one to `alarm.next*`
+    /// if they changed since last time. Returns true if they did.
+    fn publish_alarms(&mut self) -> bool {
+        let revision = self.services.alarms().revision();
+        if self.alarms_published == Some(revision) {
+            return false;
+        }
+        self.alarms_published = Some(revision);
+
+        let alarms = self.services.alarms().list();
+        let stale = self.store.get_number("alarm.list.count").unwrap_or(0.0) as usize;
+        for i in alarms.len()..stale {
+            for field in ["id", "at", "message", "repeats"] {
+                self.store.remove(&format!("alarm.list.{}.{}", i, field));
+            }
+        }
+        self.store.set("alarm.list.count", alarms.len() as f64);
+        for (i, alarm) in alarms.iter().enumerate() {
+            self.store.set(format!("alarm.list.{}.id", i), alarm.id.clone());
+            self.store.set(format!("alarm.list.{}.at", i), alarm.at.format(ALARM_TIME_FORMAT).to_string());
+            self.store.set(format!("alarm.list.{}.message", i), alarm.message.clone());
+            self.store.set(format!("alarm.list.{}.repeats", i), alarm.repeats.as_str());
+        }
+        match alarms.first() {
+            Some(next) => {
+                self.store.set("alarm.next", next.at.format(ALARM_TIME_FORMAT).to_string());
+                self.store.set("alarm.next_message", next.message.clone());
+            }
+            None => {
+                self.store.remove("alarm.next");
+                self.store.remove("alarm.next_message");
+            }
+        }
+        true
+    }
+
+    /// Play the macro bound to a pressed hotkey in `[macros] hotkeys`.
+    /// Returns true if one matched.
+    fn handle_macro_hotkey(&mut self, event: &KeyEvent) -> bool {
+        let Some(key) = hotkey_key_name(event.physical_key) else {
+            return false;
+        };
+        let modifiers = self.modifiers;
+        let name = self.bundle.macros.hotkeys.iter().find_map(|(hotkey, name)| {
+            Hotkey::parse(hotkey)
+                .filter(|hotkey| {
+                    hotkey.matches(
+                        modifiers.control_key(),
+                        modifiers.alt_key(),
+                        modifiers.shift_key(),
+                        modifiers.super_key(),
+                        &key,
+                    )
+                })
+                .map(|_| name.clone())
+        });
+        let Some(name) = name else {
+            return false;
+        };
+        self.sync_inputs_to_store();
+        self.play_macro(&name);
+        self.sync_store_to_outputs();
+        true
+    }
+
+    /// Open the context menu of the part under the pointer (or of its
+    /// nearest ancestor with one). The menu takes keyboard focus.
+    fn open_context_menu(&mut self) {
+        let (x, y) = self.cursor;
+        let Some(hit) = self.tree.hit_test(x, y) else {
+            return;
+        };
+        let mut items = self.spelling_suggestions(hit);
+        items.extend_from_slice(self.tree.context_menu_for(hit));
+        if items.is_empty() {
+            return;
+        }
+
+        if let Some(old_id) = self.tree.focused()
+            && let Some(node) = self.tree.get_mut(old_id)
+        {
+            node.widget_mut().on_event(&WidgetEvent::FocusLost);
+        }
+        self.tree.set_focused(None);
+        self.tooltip.dismiss();
+        self.context_menu.open(items, self.cursor, self.tree.size());
+    }
+
+    /// Menu entries replacing the misspelled word under the pointer, if the
+    /// hit node is a spell-checked text area. Remembers the word so a chosen
+    /// suggestion can replace it.
+    fn spelling_suggestions(&mut self, hit: crix::NodeId) -> Vec<MenuItem> {
+        let (x, y) = self.tree.to_local(hit, self.cursor.0, self.cursor.1);
+        self.spelling_target = self
+            .tree
+            .get(hit)
+            .and_then(|node| node.widget().as_any().downcast_ref::<TextArea>())
+            .filter(|text_area| text_area.spellcheck())
+            .and_then(|text_area| Some((hit, text_area.misspelled_at(x, y)?)));
+
+        let Some((_, range)) = &self.spelling_target else {
+            return Vec::new();
+        };
+        let word = self
+            .tree
+            .get(hit)
+            .and_then(|node| node.widget().as_any().downcast_ref::<TextArea>())
+            .map(|text_area| text_area.text()[range.clone()].to_string())
+            .unwrap_or_default();
+        self.services
+            .spell()
+            .suggest(&word, MAX_SPELLING_SUGGESTIONS)
+            .into_iter()
+            .map(|suggestion| MenuItem {
+                action: format!("{}{}", SPELLING_SUGGESTION_PREFIX, suggestion),
+                label: suggestion,
+            })
+            .collect()
+    }
+
+    /// Run the action of a chosen context menu entry, or replace the
+    /// misspelled word it was opened on with a chosen suggestion.
+    fn handle_menu_response(&mut self, response: MenuResponse) {
+        let target = self.spelling_target.take();
+        let MenuResponse::Chosen(action_name) = response else {
+            return;
+        };
+        if let Some(suggestion) = action_name.strip_prefix(SPELLING_SUGGESTION_PREFIX) {
+            if let Some((node_id, range)) = target
+                && let Some(text_area) = self
+                    .tree
+                    .get_mut(node_id)
+                    .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<TextArea>())
+            {
+                text_area.replace_range(range, suggestion);
+                self.sync_inputs_to_store();
+                self.sync_store_to_outputs();
+            }
+            return;
+        }
+        self.sync_inputs_to_store();
+        self.run_action(&Action::new(action_name));
+        self.sync_store_to_outputs();
+    }
+
+    /// Find misspelled words in spell-checked text areas whose text changed,
+    /// or in all of them if the language changed. Returns true if any were
+    /// checked.
+    fn update_spelling(&mut self) -> bool {
+        let spell = self.services.spell();
+        let language = spell.language();
+        let relanguage = language != self.spelling_language;
+        self.spelling_language = language;
+
+        let mut checked = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(text_area) = node.widget_mut().as_any_mut().downcast_mut::<TextArea>()
+                && (text_area.needs_spellcheck() || (relanguage && text_area.spellcheck()))
+            {
+                text_area.set_misspelled(spell.misspelled(text_area.text()));
+                checked = true;
+            }
+        }
+        checked
+    }
+
+    /// Fill in the rows of list views scrolled past the rows they hold.
+    /// Returns true if any were filled.
+    fn fill_scrolled_list_views(&mut self) -> bool {
+        let mut filled = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(list_view) = node.widget_mut().as_any_mut().downcast_mut::<ListView>()
+                && list_view.needs_rows()
+            {
+                fill_list_view(list_view, &self.store);
+                filled = true;
+            }
+        }
+        filled
+    }
+
+    /// Show numeric text inputs formatted while they aren't focused, and as
+    /// typed while they are. Returns true if any display changed.
+    fn format_number_inputs(&mut self) -> bool {
+        let format = self.services.format();
+        let mut changed = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(text_input) = text_input_mut(node.widget_mut().as_any_mut())
+                && let Some(number_format) = text_input.number_format()
+            {
+                let display = if text_input.has_focus() {
+                    None
+                } else {
+                    format
+                        .parse_number(text_input.text())
+                        .map(|number| format.format_number(number, number_format.decimals, number_format.grouping))
+                };
+                changed |= text_input.set_display(display);
+            }
+        }
+        changed
+    }
+
+    /// Open the command palette with every known action, or close it.
+    fn toggle_command_palette(&mut self) {
+        if self.palette.is_open() {
+            self.palette.close();
+            return;
+        }
+        let mut commands = self.dispatcher.action_names();
+        commands.extend(RUNTIME_ACTIONS.iter().map(|name| name.to_string()));
+        commands.extend(self.macros.names().map(|name| format!("{}{}", MACRO_PREFIX, name)));
+        commands.sort();
+        commands.dedup();
+        self.palette.open(commands);
+    }
+
+    /// Check whether a key press is the `[palette]` shortcut.
+    fn is_palette_shortcut(&self, event: &KeyEvent) -> bool {
+        let (Some(hotkey), Some(key)) =
+            (Hotkey::parse(&self.bundle.palette.shortcut), hotkey_key_name(event.physical_key))
+        else {
+            return false;
+        };
+        let modifiers = self.modifiers;
+        hotkey.matches(modifiers.control_key(), modifiers.alt_key(), modifiers.shift_key(), modifiers.super_key(), &key)
+    }
+
+    /// Handle a key press while the command palette is open.
+    fn handle_palette_key(&mut self, key: &Key) {
+        match key {
+            Key::Named(NamedKey::Escape) => self.palette.close(),
+            Key::Named(NamedKey::ArrowDown) | Key::Named(NamedKey::Tab) => self.palette.select_next(),
+            Key::Named(NamedKey::ArrowUp) => self.palette.select_previous(),
+            Key::Named(NamedKey::Backspace) => self.palette.backspace(),
+            Key::Named(NamedKey::Space) => self.palette.insert_char(' '),
+            Key::Named(NamedKey::Enter) => {
+                let Some(action_name) = self.palette.selected().map(str::to_string) else {
+                    return;
+                };
+                self.palette.close();
+                self.sync_inputs_to_store();
+                self.run_action(&Action::new(action_name));
+                self.sync_store_to_outputs();
+            }
+            Key::Character(s) => {
+                for c in s.chars().filter(|c| !c.is_control()) {
+                    self.palette.insert_char(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Dispatch an action by name.
+    fn dispatch_action(&mut self, name: &str) {
+        self.dispatch(&Action::new(name));
+    }
+
+    /// Dispatch an action, logging failures.
+    fn dispatch(&mut self, action: &Action) {
+        if let Err(e) = self.dispatcher.dispatch(action, &mut self.store, &self.services) {
+            eprintln!("Action error: {}", e);
+        }
+    }
+
+    /// Get the action for a clicked widget (if it's a button).
+    fn get_button_action(&self, node_id: crix::NodeId) -> Option<String> {
+        if let Some(node) = self.tree.get(node_id) {
+            // Try to get the action from a SkinButton; disabled ones have none
+            if let Some(button) = node.widget().as_any().downcast_ref::<SkinButton>() {
+                if button.is_disabled() {
+                    return None;
+                }
+                return button.action().map(|s| s.to_string());
+            }
+        }
+        None
+    }
+
+    /// Build a named layout from the bundle, filled in from the current store.
+    fn build_layout(&self, name: &str) -> Result<UiTree, Box<dyn std::error::Error>> {
+        let mut skin = self.bundle.load_layout(name)?;
+        skin.remap_palette(&chosen_palette(&self.store));
+        fill_template(&mut skin, &self.store);
+        let (mut tree, _window_config) = SkinBuilder::build(&skin)?;
+        add_image_view_root(&mut tree, &self.bundle.root().join("resources"));
+        apply_store_to_tree(&mut tree, &self.store);
+        Ok(tree)
+    }
+
+    /// Handle the built-in print action: print the configured print layout,
+    /// or the current screen if none is configured.
+    fn handle_print(&mut self) {
+        let tree = match self.bundle.print.layout.clone() {
+            Some(name) => match self.build_layout(&name) {
+                Ok(tree) => Some(tree),
+                Err(e) => {
+                    eprintln!("Failed to build print layout '{}': {}", name, e);
+                    self.store.set("print.status", "error");
+                    self.store.set("print.error", e.to_string());
+                    return;
+                }
+            },
+            None => None,
+        };
+        let view = tree.as_ref().unwrap_or(&self.tree);
+        let (width, height) = view.size();
+        let image = render_view(view, width, height);
+
+        match self.services.print().print_image(&image, &self.bundle.meta.name) {
+            Ok(path) => {
+                println!("Opened for printing: {}", path.display());
+                self.store.set("print.status", "sent");
+                self.store.remove("print.error");
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                self.store.set("print.status", "error");
+                self.store.set("print.error", e.to_string());
+            }
+        }
+    }
+
+    /// Handle the built-in export_pdf/export_png actions: export the layout
+    /// named by the `export.layout` store key (or the current screen) to
+    /// `export.path`, asking for a path if none is set.
+    fn handle_export(&mut self, format: &str) {
+        let layout = self.store.get_string("export.layout");
+        let path = match self.store.get_string("export.path") {
+            path if !path.is_empty() => PathBuf::from(path),
+            _ => match rfd::FileDialog::new()
+                .set_title("Export")
+                .add_filter(format.to_uppercase(), &[format])
+                .save_file()
+            {
+                Some(path) => path,
+                None => return,
+            },
+        };
+
+        match self.export_layout((!layout.is_empty()).then_some(layout.as_str()), &path, format) {
+            Ok(()) => {
+                println!("Exported {}: {}", format.to_uppercase(), path.display());
+                self.store.set("export.status", "saved");
+                self.store.remove("export.error");
+            }
+            Err(e) => {
+                eprintln!("Export failed: {}", e);
+                self.store.set("export.status", "error");
+                self.store.set("export.error", e.to_string());
+            }
+        }
+    }
+
+    /// Export a named layout, or the current screen, as "pdf" (vector) or "png".
+    fn export_layout(
+        &self,
+        layout_name: Option<&str>,
+        path: &Path,
+        format: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let layout = layout_name.map(|name| self.build_layout(name)).transpose()?;
+        let tree = layout.as_ref().unwrap_or(&self.tree);
+        match format {
+            "png" => export_png(tree, path)?,
+            _ => export_pdf(tree, &self.bundle.meta.name, path)?,
+        }
+        Ok(())
+    }
+
+    /// Check for FilePicker pending actions and handle them.
+    fn handle_file_picker_actions(&mut self) {
+        // Collect pending actions first to avoid borrow conflicts
+        let mut actions_to_process: Vec<(String, PathBuf)> = Vec::new();
+
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id) {
+                if let Some(picker) = node.widget_mut().as_any_mut().downcast_mut::<FilePicker>() {
+                    if picker.has_pending_action() {
+                        if let Some(action) = picker.on_select_action() {
+                            if let Some(path) = picker.selected_file().cloned() {
+                                actions_to_process.push((action.to_string(), path));
+                            }
+                        }
+                        picker.clear_pending_action();
+                    }
+                }
+            }
+        }
+
+        // Now process the collected actions
+        for (action, path) in actions_to_process {
+            match action.as_str() {
+                "launch_child_app" => {
+                    launch_child_app(&path);
+                }
+                "load_app_info" => {
+                    self.load_app_info_to_store(&path);
+                }
+                _ => {
+                    // Could dispatch to Lua handler here
+                }
+            }
+        }
+    }
+
+    /// Load app metadata from a .crix bundle and populate the store.
+    fn load_app_info_to_store(&mut self, path: &PathBuf) {
+        // Store the selected path
+        self.store.set("selected_app_path".to_string(), path.to_string_lossy().to_string());
+
+        // Try to load and parse app.toml
+        if let Some(meta) = load_app_metadata(path) {
+            self.store.set("app_name".to_string(), meta.name);
+            self.store.set("app_version".to_string(), meta.version);
+            self.store.set("app_author".to_string(), meta.author);
+            self.store.set("app_description".to_string(), meta.description);
+        } else {
+            // Clear metadata if parsing failed
+            let dir_name = path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            self.store.set("app_name".to_string(), dir_name);
+            self.store.set("app_version".to_string(), "".to_string());
+            self.store.set("app_author".to_string(), "".to_string());
+            self.store.set("app_description".to_string(), "(Could not read app.toml)".to_string());
+        }
+
+        // Sync the store values to StaticText widgets
+        self.sync_store_to_outputs();
+    }
+
+    /// Handle the launch_selected_app action (triggered by Run button).
+    fn handle_launch_selected_app(&mut self) {
+        let path_str = self.store.get_string("selected_app_path");
+        if !path_str.is_empty() {
+            let path = PathBuf::from(path_str);
+            if path.exists() && path.is_dir() {
+                launch_child_app(&path);
+            }
+        }
+    }
+
+    /// Make wizard step `index` active: show its parts, hide the parts of the
+    /// other steps and publish the progress keys.
+    fn show_wizard_step(&mut self, index: usize) {
+        let Some(wizard) = &self.wizard else {
+            return;
+        };
+        for (i, step) in wizard.steps.iter().enumerate() {
+            for part in &step.parts {
+                match self.tree.find_by_name(part) {
+                    Some(id) => self.tree.set_visible(id, i == index),
+                    None => eprintln!("Warning: wizard step '{}' references unknown part '{}'", step.id, part),
+                }
+            }
+        }
+        wizard.publish(index, &mut self.store);
+        self.store.set("wizard.error", "");
+        self.wizard_step = index;
+    }
+
+    /// Handle the built-in wizard.next action: validate the active step, then
+    /// advance, or dispatch `on_finish` after the last step.
+    fn handle_wizard_next(&mut self) {
+        let Some(wizard) = &self.wizard else {
+            return;
+        };
+        let Some(step) = wizard.steps.get(self.wizard_step) else {
+            return;
+        };
+        if let Err(message) = step.validate(&self.store) {
+            self.store.set("wizard.error", message);
+            return;
+        }
+
+        if self.wizard_step + 1 < wizard.steps.len() {
+            self.show_wizard_step(self.wizard_step + 1);
+        } else {
+            let on_finish = wizard.on_finish.clone();
+            self.store.set("wizard.error", "");
+            self.store.set("wizard.complete", true);
+            if let Some(action) = on_finish {
+                self.dispatch_action(&action);
+            }
+        }
+    }
+
+    /// Handle the built-in wizard.back action: return to the previous step.
+    fn handle_wizard_back(&mut self) {
+        if self.wizard.is_some() && self.wizard_step > 0 {
+            self.store.set("wizard.complete", false);
+            self.show_wizard_step(self.wizard_step - 1);
+        }
+    }
+
+    /// Sync sliders, knobs, spinners, color pickers and finished sprites to store (write dirty values). Returns true if
+    /// any changed.
+    fn sync_sliders_to_store(&mut self) -> bool {
+        let mut changed = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+
+        for id in node_ids {
+            let Some(node) = self.tree.get_mut(id) else {
+                continue;
+            };
+            if let Some(slider) = node.widget_mut().as_any_mut().downcast_mut::<Slider>()
+                && slider.is_dirty()
+            {
+                if let Some(binding) = slider.binding() {
+                    self.store.set(binding.to_string(), slider.value());
+                    changed = true;
+                }
+                slider.clear_dirty();
+            } else if let Some(knob) = node.widget_mut().as_any_mut().downcast_mut::<Knob>()
+                && knob.is_dirty()
+            {
+                if let Some(binding) = knob.binding() {
+                    self.store.set(binding.to_string(), knob.value());
+                    changed = true;
+                }
+                knob.clear_dirty();
+            } else if let Some(spinner) = node.widget_mut().as_any_mut().downcast_mut::<Spinner>()
+                && spinner.is_dirty()
+            {
+                if let Some(binding) = spinner.binding() {
+                    self.store.set(binding.to_string(), spinner.value());
+                    changed = true;
+                }
+                spinner.clear_dirty();
+            } else if let Some(picker) = node.widget_mut().as_any_mut().downcast_mut::<ColorPicker>()
+                && picker.is_dirty()
+            {
+                if let Some(binding) = picker.binding() {
+                    self.store.set(binding.to_string(), picker.hex());
+                    changed = true;
+                }
+                picker.clear_dirty();
+            } else if let Some(sprite) = node.widget_mut().as_any_mut().downcast_mut::<AnimatedSprite>()
+                && sprite.is_dirty()
+            {
+                // A sprite that stopped on its last frame is no longer playing
+                if let Some(binding) = sprite.binding() {
+                    self.store.set(binding.to_string(), false);
+                    changed = true;
+                }
+                sprite.clear_dirty();
+            }
+        }
+        changed
+    }
+
+    /// Dispatch the release action of a slider, knob or color picker whose
+    /// drag just ended, or of a spinner whose button was released.
+    fn handle_slider_release(&mut self, node_id: crix::NodeId) {
+        let action = self.tree.get_mut(node_id).and_then(|node| {
+            let widget = node.widget_mut().as_any_mut();
+            if let Some(slider) = widget.downcast_mut::<Slider>() {
+                slider.take_release_action()
+            } else if let Some(knob) = widget.downcast_mut::<Knob>() {
+                knob.take_release_action()
+            } else if let Some(spinner) = widget.downcast_mut::<Spinner>() {
+                spinner.take_release_action()
+            } else {
+                widget.downcast_mut::<ColorPicker>()?.take_release_action()
+            }
+        });
+
+        if let Some(action_name) = action {
+            self.sync_inputs_to_store();
+            self.dispatch_action(&action_name);
+            self.sync_store_to_outputs();
+        }
+    }
+
+    /// Write changed dropdown selections to their bound store keys.
+    /// Returns true if any changed.
+    fn sync_dropdowns_to_store(&mut self) -> bool {
+        let mut changed = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(dropdown) = node.widget_mut().as_any_mut().downcast_mut::<Dropdown>()
+                && dropdown.is_dirty()
+            {
+                if let Some(binding) = dropdown.binding() {
+                    let value = dropdown.selected_option().unwrap_or_default().to_string();
+                    self.store.set(binding.to_string(), value);
+                    changed = true;
+                }
+                dropdown.clear_dirty();
+            }
+        }
+        changed
+    }
+
+    /// Dispatch the change action of a dropdown whose selection just changed.
+    fn handle_dropdown_change(&mut self, node_id: crix::NodeId) {
+        let action = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<Dropdown>())
+            .and_then(|dropdown| dropdown.take_change_action());
+
+        if let Some(action_name) = action {
+            self.sync_inputs_to_store();
+            self.dispatch_action(&action_name);
+            self.sync_store_to_outputs();
+        }
+    }
+
+    /// Write changed list view selections to their bound store keys: the
+    /// index to the binding and the row text to `<binding>.value`.
+    /// Returns true if any changed.
+    fn sync_list_views_to_store(&mut self) -> bool {
+        let mut changed = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(list_view) = node.widget_mut().as_any_mut().downcast_mut::<ListView>()
+                && list_view.is_dirty()
+            {
+                if let Some(binding) = list_view.binding() {
+                    match list_view.selected() {
+                        Some(index) => self.store.set(binding.to_string(), index as f64),
+                        None => self.store.set(binding.to_string(), Value::Null),
+                    }
+                    let value = list_view.selected().map(|i| list_row_text(list_view, &self.store, i));
+                    self.store.set(format!("{}.value", binding), value.unwrap_or_default());
+                    changed = true;
+                }
+                list_view.clear_dirty();
+            }
+        }
+        changed
+    }
+
+    /// Write changed tree view selections to their bound store keys: the
+    /// node path to the binding and the row text to `<binding>.value`.
+    /// Returns true if any changed.
+    fn sync_tree_views_to_store(&mut self) -> bool {
+        let mut changed = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(tree_view) = node.widget_mut().as_any_mut().downcast_mut::<TreeView>()
+                && tree_view.is_dirty()
+            {
+                if let Some(binding) = tree_view.binding() {
+                    match tree_view.selected() {
+                        Some(path) => self.store.set(binding.to_string(), path.to_string()),
+                        None => self.store.set(binding.to_string(), Value::Null),
+                    }
+                    let value = tree_view.selected_text().unwrap_or_default().to_string();
+                    self.store.set(format!("{}.value", binding), value);
+                    changed = true;
+                }
+                tree_view.clear_dirty();
+            }
+        }
+        changed
+    }
+
+    /// Write changed active tabs to their bound store keys and show the
+    /// active panels. Returns true if any changed.
+    fn sync_tab_containers_to_store(&mut self) -> bool {
+        let mut changed = false;
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(tabs) = node.widget_mut().as_any_mut().downcast_mut::<TabContainer>()
+                && tabs.is_dirty()
+            {
+                if let Some(binding) = tabs.binding() {
+                    let active = tabs.active_id().unwrap_or_default().to_string();
+                    self.store.set(binding.to_string(), active);
                 }
+                tabs.clear_dirty();
+                changed = true;
             }
         }
+        if changed {
+            SkinBuilder::show_active_panels(&mut self.tree);
+        }
+        changed
     }
 
-    /// Dispatch an action by name.
-    fn dispatch_action(&mut self, name: &str) {
-        let action = Action::new(name);
-        if let Err(e) = self.dispatcher.dispatch(&action, &mut self.store, &self.services) {
-            eprintln!("Action error: {}", e);
+    /// Dispatch the change action of a tab container whose active tab just
+    /// changed, with the active `tab` id as payload.
+    fn handle_tab_change(&mut self, node_id: crix::NodeId) {
+        let change = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<TabContainer>())
+            .and_then(|tabs| Some((tabs.take_change_action()?, tabs.active_id().unwrap_or_default().to_string())));
+
+        if let Some((action_name, tab)) = change {
+            self.sync_inputs_to_store();
+            self.dispatch(&Action::new(action_name).with("tab", tab));
+            self.sync_store_to_outputs();
         }
     }
 
-    /// Get the action for a clicked widget (if it's a button).
-    fn get_button_action(&self, node_id: crix::NodeId) -> Option<String> {
-        if let Some(node) = self.tree.get(node_id) {
-            // Try to get the action from a SkinButton
-            if let Some(button) = node.widget().as_any().downcast_ref::<crix::skin::widgets::SkinButton>() {
-                return button.action().map(|s| s.to_string());
-            }
+    /// Dispatch the action of a menu bar entry that was just chosen.
+    fn handle_menu_bar_choice(&mut self, node_id: crix::NodeId) {
+        let action = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<MenuBar>())
+            .and_then(|menu_bar| menu_bar.take_chosen_action());
+
+        if let Some(action_name) = action {
+            self.sync_inputs_to_store();
+            self.dispatch_action(&action_name);
+            self.sync_store_to_outputs();
         }
-        None
     }
 
-    /// Check for FilePicker pending actions and handle them.
-    fn handle_file_picker_actions(&mut self) {
-        // Collect pending actions first to avoid borrow conflicts
-        let mut actions_to_process: Vec<(String, PathBuf)> = Vec::new();
+    /// Dispatch the change action of a toggle switch that was just flipped,
+    /// with the new state as `value` in the payload.
+    fn handle_toggle_change(&mut self, node_id: crix::NodeId) {
+        let change = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<ToggleSwitch>())
+            .and_then(|switch| Some((switch.take_change_action()?, switch.is_on())));
 
-        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
-        for id in node_ids {
-            if let Some(node) = self.tree.get_mut(id) {
-                if let Some(picker) = node.widget_mut().as_any_mut().downcast_mut::<FilePicker>() {
-                    if picker.has_pending_action() {
-                        if let Some(action) = picker.on_select_action() {
-                            if let Some(path) = picker.selected_file().cloned() {
-                                actions_to_process.push((action.to_string(), path));
-                            }
-                        }
-                        picker.clear_pending_action();
-                    }
-                }
-            }
+        if let Some((action_name, on)) = change {
+            self.sync_inputs_to_store();
+            self.dispatch(&Action::new(action_name).with("value", on));
+            self.sync_store_to_outputs();
         }
+    }
 
-        // Now process the collected actions
-        for (action, path) in actions_to_process {
-            match action.as_str() {
-                "launch_child_app" => {
-                    launch_child_app(&path);
-                }
-                "load_app_info" => {
-                    self.load_app_info_to_store(&path);
-                }
-                _ => {
-                    // Could dispatch to Lua handler here
-                }
-            }
+    /// Dispatch the change action of a list view whose selection just
+    /// changed, with the selected `index` and row `value` as payload.
+    fn handle_list_view_change(&mut self, node_id: crix::NodeId) {
+        let change = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<ListView>())
+            .and_then(|list_view| {
+                let action = list_view.take_change_action()?;
+                let value = list_view.selected().map(|i| list_row_text(list_view, &self.store, i));
+                Some((action, list_view.selected(), value.unwrap_or_default()))
+            });
+
+        if let Some((action_name, index, value)) = change {
+            self.sync_inputs_to_store();
+            let index = index.map_or(Value::Null, |i| Value::from(i as f64));
+            self.dispatch(&Action::new(action_name).with("index", index).with("value", value));
+            self.sync_store_to_outputs();
         }
     }
 
-    /// Load app metadata from a .crix bundle and populate the store.
-    fn load_app_info_to_store(&mut self, path: &PathBuf) {
-        // Store the selected path
-        self.store.set("selected_app_path".to_string(), path.to_string_lossy().to_string());
+    /// Move the store entry of a list view row just dropped in a new place,
+    /// then dispatch the list's reorder action with the `from` and `to`
+    /// indices as payload.
+    fn handle_list_reorder(&mut self, node_id: crix::NodeId) {
+        let Some((list, (from, to), action)) = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<ListView>())
+            .and_then(|list_view| {
+                let moved = list_view.take_move()?;
+                Some((list_view.list().to_string(), moved, list_view.reorder_action().map(str::to_string)))
+            })
+        else {
+            return;
+        };
 
-        // Try to load and parse app.toml
-        if let Some(meta) = load_app_metadata(path) {
-            self.store.set("app_name".to_string(), meta.name);
-            self.store.set("app_version".to_string(), meta.version);
-            self.store.set("app_author".to_string(), meta.author);
-            self.store.set("app_description".to_string(), meta.description);
-        } else {
-            // Clear metadata if parsing failed
-            let dir_name = path.file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-            self.store.set("app_name".to_string(), dir_name);
-            self.store.set("app_version".to_string(), "".to_string());
-            self.store.set("app_author".to_string(), "".to_string());
-            self.store.set("app_description".to_string(), "(Could not read app.toml)".to_string());
+        self.store.move_list_entry(&list, from, to);
+        self.sync_list_views_to_store();
+        if let Some(action_name) = action {
+            self.sync_inputs_to_store();
+            let action = Action::new(action_name).with("list", list).with("from", from as f64).with("to", to as f64);
+            self.dispatch(&action);
         }
-
-        // Sync the store values to StaticText widgets
         self.sync_store_to_outputs();
     }
 
-    /// Handle the launch_selected_app action (triggered by Run button).
-    fn handle_launch_selected_app(&mut self) {
-        let path_str = self.store.get_string("selected_app_path");
-        if !path_str.is_empty() {
-            let path = PathBuf::from(path_str);
-            if path.exists() && path.is_dir() {
-                launch_child_app(&path);
-            }
+    /// Dispatch the change action of a tree view whose selection just
+    /// changed, with the selected node `path` and row `value` as payload.
+    fn handle_tree_view_change(&mut self, node_id: crix::NodeId) {
+        let change = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<TreeView>())
+            .and_then(|tree_view| {
+                let action = tree_view.take_change_action()?;
+                let path = tree_view.selected().map(str::to_string);
+                Some((action, path, tree_view.selected_text().unwrap_or_default().to_string()))
+            });
+
+        if let Some((action_name, path, value)) = change {
+            self.sync_inputs_to_store();
+            let path = path.map_or(Value::Null, Value::from);
+            self.dispatch(&Action::new(action_name).with("path", path).with("value", value));
+            self.sync_store_to_outputs();
+        }
+    }
+
+    /// Follow a clicked link: dispatch its action, or else open its URL
+    /// with the built-in `url.open` action.
+    fn handle_link_click(&mut self, node_id: crix::NodeId) {
+        let action = self
+            .tree
+            .get(node_id)
+            .and_then(|node| node.widget().as_any().downcast_ref::<Link>())
+            .and_then(|link| match (link.action(), link.url()) {
+                (Some(action), _) => Some(Action::new(action)),
+                (None, Some(url)) => Some(Action::new("url.open").with("url", url)),
+                (None, None) => None,
+            });
+
+        if let Some(action) = action {
+            self.sync_inputs_to_store();
+            self.run_action(&action);
+            self.sync_store_to_outputs();
+        }
+    }
+
+    /// Open the file dialog of a file path input whose browse button was
+    /// clicked, and put the chosen path in the input and its binding.
+    fn handle_path_browse(&mut self, node_id: crix::NodeId) {
+        let Some(path_input) = self
+            .tree
+            .get_mut(node_id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<FilePathInput>())
+        else {
+            return;
+        };
+        if !path_input.take_browse_request() {
+            return;
+        }
+
+        let current = Path::new(path_input.input().text());
+        let current = (!current.as_os_str().is_empty()).then_some(current);
+        let (title, filters) = (path_input.dialog_title(), path_input.filters());
+        let dialog = self.services.dialog();
+        let path = match path_input.dialog() {
+            PathDialog::Open => dialog.open_file(title, filters, current),
+            PathDialog::Save => dialog.save_file(title, filters, current),
+            PathDialog::Folder => dialog.pick_folder(title, current),
+        };
+        let Some(path) = path else {
+            return;
+        };
+
+        let path = path.to_string_lossy().into_owned();
+        path_input.input_mut().set_text(path.clone());
+        if let Some(binding) = path_input.input().binding() {
+            self.store.set(binding.to_string(), path.as_str());
+        }
+        path_input.input_mut().clear_dirty();
+        let on_select = path_input.on_select().map(|action| Action::new(action).with("path", path.as_str()));
+
+        self.sync_inputs_to_store();
+        if let Some(action) = on_select {
+            self.run_action(&action);
         }
+        self.sync_store_to_outputs();
     }
 
     /// Handle checkbox actions for the currently pressed widget.
@@ -284,6 +2629,277 @@ impl SkinApp {
     }
 }
 
+/// Push store values into a tree's display widgets (static text, custom paint, bound images)
+/// and show the active tab panels.
+fn apply_store_to_tree(tree: &mut UiTree, store: &Store) {
+    let node_ids: Vec<_> = tree.iter_node_ids().collect();
+    // One snapshot for all custom paint parts, taken only if there are any
+    let mut painted_store: Option<Rc<Store>> = None;
+
+    for id in node_ids {
+        if let Some(node) = tree.get_mut(id) {
+            if let Some(static_text) = node.widget_mut().as_any_mut().downcast_mut::<StaticText>() {
+                if let Some(binding) = static_text.binding() {
+                    let value = store.get_string(binding);
+                    if !value.is_empty() && value != static_text.content() {
+                        static_text.set_content(value);
+                    }
+                }
+            } else if let Some(link) = node.widget_mut().as_any_mut().downcast_mut::<Link>() {
+                if let Some(binding) = link.binding() {
+                    let value = store.get_string(binding);
+                    if !value.is_empty() && value != link.text() {
+                        link.set_text(value);
+                    }
+                }
+            } else if let Some(sprite) = node.widget_mut().as_any_mut().downcast_mut::<AnimatedSprite>()
+                && let Some(binding) = sprite.binding()
+                && store.get(binding).is_some()
+            {
+                let playing = store.get_bool(binding);
+                sprite.set_playing(playing);
+            } else if let Some(indicator) = node.widget_mut().as_any_mut().downcast_mut::<Indicator>()
+                && let Some(binding) = indicator.binding()
+            {
+                let lit = store.get_bool(binding);
+                indicator.set_lit(lit);
+            } else if let Some(display) = node.widget_mut().as_any_mut().downcast_mut::<SevenSegment>()
+                && let Some(binding) = display.binding()
+            {
+                let value = store.get_string(binding);
+                if !value.is_empty() {
+                    display.set_text(&value);
+                }
+            } else if let Some(input) = node.widget_mut().as_any_mut().downcast_mut::<TextInput>()
+                && let Some(reveal) = input.reveal_binding()
+            {
+                let revealed = store.get_bool(reveal);
+                input.set_revealed(revealed);
+            } else if let Some(canvas) = node.widget_mut().as_any_mut().downcast_mut::<CanvasWidget>()
+                && let Some(binding) = canvas.binding()
+            {
+                let drawing = store.get_string(binding);
+                canvas.set_drawing(&drawing);
+            } else if let Some(custom) = node.widget_mut().as_any_mut().downcast_mut::<CustomPaint>() {
+                let snapshot = painted_store.get_or_insert_with(|| Rc::new(store.clone()));
+                custom.set_store(snapshot.clone());
+            } else if let Some(image) = node.widget_mut().as_any_mut().downcast_mut::<SkinImage>() {
+                if let Some(binding) = image.binding() {
+                    let handle = store.get_image(binding).cloned();
+                    image.set_source(handle);
+                }
+            } else if let Some(view) = node.widget_mut().as_any_mut().downcast_mut::<ImageView>()
+                && let Some(binding) = view.binding()
+            {
+                let path = store.get_string(binding);
+                if let Err(e) = view.set_path(&path) {
+                    eprintln!("Failed to load image '{}': {}", path, e);
+                }
+            } else if let Some(slider) = node.widget_mut().as_any_mut().downcast_mut::<Slider>()
+                && let Some(value) = slider.binding().and_then(|binding| store.get_number(binding))
+            {
+                slider.set_value(value);
+            } else if let Some(knob) = node.widget_mut().as_any_mut().downcast_mut::<Knob>()
+                && let Some(value) = knob.binding().and_then(|binding| store.get_number(binding))
+            {
+                knob.set_value(value);
+            } else if let Some(spinner) = node.widget_mut().as_any_mut().downcast_mut::<Spinner>()
+                && let Some(value) = spinner.binding().and_then(|binding| store.get_number(binding))
+            {
+                spinner.set_value(value);
+            } else if let Some(picker) = node.widget_mut().as_any_mut().downcast_mut::<ColorPicker>()
+                && let Some(binding) = picker.binding()
+            {
+                let hex = store.get_string(binding);
+                picker.set_hex(&hex);
+            } else if let Some(bar) = node.widget_mut().as_any_mut().downcast_mut::<ProgressBar>()
+                && let Some(value) = bar.binding().and_then(|binding| store.get_number(binding))
+            {
+                bar.set_value(value);
+            } else if let Some(gauge) = node.widget_mut().as_any_mut().downcast_mut::<Gauge>()
+                && let Some(value) = gauge.binding().and_then(|binding| store.get_number(binding))
+            {
+                gauge.set_value(value);
+            } else if let Some(plot) = node.widget_mut().as_any_mut().downcast_mut::<Plot>() {
+                let lists: Vec<String> = plot.lists().map(String::from).collect();
+                for (index, list) in lists.iter().enumerate() {
+                    let count = store.get_number(&format!("{}.count", list)).unwrap_or(0.0).max(0.0) as usize;
+                    // Entries that aren't numbers leave gaps
+                    let values = (0..count.min(MAX_PLOT_POINTS))
+                        .map(|i| store.get(&format!("{}.{}", list, i)).and_then(Value::try_parse_number))
+                        .map(|value| value.unwrap_or(f64::NAN))
+                        .collect();
+                    plot.set_values(index, values);
+                }
+            } else if let Some(button) = node.widget_mut().as_any_mut().downcast_mut::<SkinButton>()
+                && let Some(binding) = button.disabled_binding()
+            {
+                let disabled = store.get_bool(binding);
+                button.set_disabled(disabled);
+            } else if let Some(display) = node.widget_mut().as_any_mut().downcast_mut::<SkinTimerDisplay>()
+                && let Some(binding) = display.binding()
+            {
+                let seconds = store.get_number(binding).unwrap_or(0.0);
+                display.set_seconds(seconds);
+            } else if let Some(bar) = node.widget_mut().as_any_mut().downcast_mut::<StatusBar>() {
+                // An empty key clears its segment, so an error slot empties once the error is gone
+                let texts: Vec<String> = bar.bindings().map(|binding| store.get_string(binding)).collect();
+                for (index, text) in texts.iter().enumerate() {
+                    bar.set_text(index, text);
+                }
+            } else if let Some(checkbox) = node.widget_mut().as_any_mut().downcast_mut::<Checkbox>()
+                && let Some(checked) = checkbox.binding().and_then(|binding| store.get(binding)?.as_bool())
+                && checked != checkbox.is_checked()
+            {
+                // Store changes (e.g. from scripts) move the checkbox without re-syncing it back
+                checkbox.set_checked(checked);
+                checkbox.clear_dirty();
+            } else if let Some(switch) = node.widget_mut().as_any_mut().downcast_mut::<ToggleSwitch>()
+                && let Some(on) = switch.binding().and_then(|binding| store.get(binding)?.as_bool())
+                && on != switch.is_on()
+            {
+                switch.set_on(on);
+            } else if let Some(dropdown) = node.widget_mut().as_any_mut().downcast_mut::<Dropdown>() {
+                if let Some(list) = dropdown.options_list() {
+                    let count = store.get_number(&format!("{}.count", list)).unwrap_or(0.0) as usize;
+                    let options = (0..count)
+                        .map(|i| store.get_string(&format!("{}.{}.name", list, i)))
+                        .collect();
+                    dropdown.set_options(options);
+                }
+                if let Some(value) = dropdown.binding().and_then(|binding| store.get(binding))
+                    && !dropdown.is_dirty()
+                {
+                    dropdown.set_selected_option(&value.to_string_value());
+                }
+            } else if let Some(tabs) = node.widget_mut().as_any_mut().downcast_mut::<TabContainer>() {
+                // Scripts switch tabs by setting the binding to a tab id
+                if let Some(value) = tabs.binding().and_then(|binding| store.get(binding))
+                    && !tabs.is_dirty()
+                {
+                    tabs.set_active_id(&value.to_string_value());
+                }
+            } else if let Some(stack) = node.widget_mut().as_any_mut().downcast_mut::<Stack>() {
+                // Scripts switch children by setting the binding to a part id or index
+                if let Some(value) = stack.binding().and_then(|binding| store.get(binding)) {
+                    stack.select(&value.to_string_value());
+                }
+            } else if let Some(list_view) = node.widget_mut().as_any_mut().downcast_mut::<ListView>() {
+                let count = store.get_number(&format!("{}.count", list_view.list())).unwrap_or(0.0).max(0.0) as usize;
+                list_view.set_row_count(count);
+                fill_list_view(list_view, store);
+                if let Some(binding) = list_view.binding()
+                    && !list_view.is_dirty()
+                {
+                    let index = store.get_number(binding).filter(|n| *n >= 0.0).map(|n| n as usize);
+                    list_view.set_selected(index);
+                }
+            } else if let Some(tree_view) = node.widget_mut().as_any_mut().downcast_mut::<TreeView>() {
+                let mut nodes = Vec::new();
+                tree_rows(tree_view.row_template(), store, tree_view.list(), 0, &mut nodes);
+                tree_view.set_nodes(nodes);
+                if let Some(binding) = tree_view.binding()
+                    && !tree_view.is_dirty()
+                {
+                    let path = store.get(binding).map(|value| value.to_string_value());
+                    tree_view.set_selected(path.as_deref().filter(|path| !path.is_empty()));
+                }
+            } else if let Some(progress) = node.widget_mut().as_any_mut().downcast_mut::<StepProgress>() {
+                let step = store.get_number("wizard.step").unwrap_or(0.0) as usize;
+                let count = store.get_number("wizard.count").unwrap_or(0.0) as usize;
+                progress.set_progress(step, count);
+            }
+        }
+    }
+    SkinBuilder::show_active_panels(tree);
+}
+
+/// Get the text input of a text input or file path input widget.
+fn text_input_mut(widget: &mut dyn Any) -> Option<&mut TextInput> {
+    if widget.is::<FilePathInput>() {
+        return widget.downcast_mut::<FilePathInput>().map(FilePathInput::input_mut);
+    }
+    widget.downcast_mut::<TextInput>()
+}
+
+/// Fill in the text of the rows around the visible ones of a list view.
+fn fill_list_view(list_view: &mut ListView, store: &Store) {
+    let range = list_view.rows_to_fill();
+    let first = range.start;
+    let rows = range.map(|i| list_row_text(list_view, store, i)).collect();
+    list_view.set_filled_rows(first, rows);
+}
+
+/// Get the text of row `index` of a list view from the store.
+fn list_row_text(list_view: &ListView, store: &Store, index: usize) -> String {
+    fill_list_row(list_view.row_template(), store, list_view.list(), index)
+}
+
+/// Let the image views in a tree load images from the app's resources directory.
+/// Collect the nodes of a tree view depth-first from nested store lists,
+/// where each entry's children are the list `<list>.<n>.children`.
+fn tree_rows(template: &str, store: &Store, list: &str, depth: usize, rows: &mut Vec<TreeRow>) {
+    if depth >= MAX_TREE_DEPTH {
+        return;
+    }
+    let count = store.get_number(&format!("{}.count", list)).unwrap_or(0.0).max(0.0) as usize;
+    for i in 0..count {
+        let path = format!("{}.{}", list, i);
+        let children = format!("{}.children", path);
+        let has_children = store.get_number(&format!("{}.count", children)).unwrap_or(0.0) >= 1.0;
+        rows.push(TreeRow { text: fill_list_row(template, store, list, i), path, depth, has_children });
+        tree_rows(template, store, &children, depth + 1, rows);
+    }
+}
+
+/// Publish the key colors of a skin's palette, and those of the skins it
+/// embeds, as the picks of names that have none yet, so color pickers bound
+/// to them start at the skin's own colors, and get the colors picked for it.
+fn pick_palette(skin: &LoadedSkin, store: &mut Store) -> HashMap<String, u32> {
+    for (name, color) in skin.palettes() {
+        let key = format!("{}{}", PALETTE_KEY_PREFIX, name);
+        if !store.contains(&key) {
+            store.set(key, format!("#{:06x}", color));
+        }
+    }
+    chosen_palette(store)
+}
+
+fn add_image_view_root(tree: &mut UiTree, dir: &Path) {
+    let node_ids: Vec<_> = tree.iter_node_ids().collect();
+    for id in node_ids {
+        if let Some(view) = tree
+            .get_mut(id)
+            .and_then(|node| node.widget_mut().as_any_mut().downcast_mut::<ImageView>())
+        {
+            view.add_root(dir);
+        }
+    }
+}
+
+/// File the macros are saved in: the active profile's directory, or the app
+/// data directory if the app persists no profiles.
+fn macros_file(profiles: Option<&ProfileStore>, data_dir: &Path) -> PathBuf {
+    match profiles {
+        Some(profiles) => profiles.profile_dir().join("macros.json"),
+        None => data_dir.join("macros.json"),
+    }
+}
+
+/// Name of a pressed key for hotkey matching ("a", "1", "f5", "enter").
+/// Uses the physical key so Shift doesn't turn digits into symbols.
+fn hotkey_key_name(key: PhysicalKey) -> Option<String> {
+    let PhysicalKey::Code(code) = key else {
+        return None;
+    };
+    let name = format!("{:?}", code);
+    let name = name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name);
+    Some(name.to_lowercase())
+}
+
 /// Launch a child crix app in a new process.
 fn launch_child_app(path: &PathBuf) {
     println!("Launching app: {}", path.display());
@@ -311,29 +2927,370 @@ impl App for SkinApp {
         &self.tree
     }
 
+    fn cursor(&self) -> CursorIcon {
+        self.tree
+            .hovered()
+            .and_then(|id| self.tree.get(id))
+            .map_or(CursorIcon::Default, |node| node.widget().cursor())
+    }
+
+    fn take_window_drag(&mut self) -> bool {
+        std::mem::take(&mut self.window_drag)
+    }
+
+    fn take_window_close(&mut self) -> bool {
+        std::mem::take(&mut self.window_close)
+    }
+
+    fn take_window_center(&mut self) -> bool {
+        std::mem::take(&mut self.window_center)
+    }
+
+    fn rendered(&mut self, stats: &RenderStats) {
+        self.render_stats = *stats;
+    }
+
+    fn set_inertia(&mut self, inertia: InertiaConfig) {
+        self.inertia = inertia;
+        self.tree.set_inertia(&inertia);
+    }
+
+    fn overlay(&mut self, canvas: &mut Canvas) {
+        self.cross_fade.draw(canvas);
+        self.confetti.draw(canvas);
+        self.tooltip.draw(canvas);
+        self.context_menu.draw(canvas);
+        self.palette.draw(canvas);
+        if let Some(console) = &self.dev_console {
+            console.draw(canvas);
+        }
+    }
+
+    fn update(&mut self) -> bool {
+        #[cfg(feature = "video")]
+        let videos_changed = self.sync_videos();
+        #[cfg(not(feature = "video"))]
+        let videos_changed = false;
+        let scanned = self.publish_scan_events();
+        let located = self.publish_location();
+        let camera_stopped = self.publish_camera_status();
+        let rated = self.publish_currency_rates();
+        let updated = self.publish_update_status();
+        let scripts_ran = self.poll_handlers();
+        self.save_profile();
+        let shared = self.sync_channels();
+        let scheduled = self.run_scheduled();
+        let alarmed = self.fire_alarms();
+        let idle_locked = self.check_idle_lock();
+        let spelled = self.update_spelling();
+        let formatted = self.format_number_inputs();
+        let scrolled = self.fill_scrolled_list_views();
+        let reloaded = self.reload_edited_skin();
+        let recolored = self.recolor_skin();
+        // Parts animate on the store changes made by events and actions
+        let animated = self.tree.animate(&self.store);
+        self.record_history("input");
+        let watches_changed = self
+            .dev_console
+            .as_mut()
+            .is_some_and(|console| console.update_watches(&self.store));
+
+        // Redraw when a bound image handle has received new content
+        videos_changed
+            || scanned
+            || located
+            || camera_stopped
+            || rated
+            || updated
+            || scripts_ran
+            || shared
+            || scheduled
+            || alarmed
+            || idle_locked
+            || spelled
+            || formatted
+            || scrolled
+            || reloaded
+            || recolored
+            || animated
+            || watches_changed
+            || self.tree.iter_node_ids().any(|id| {
+                self.tree
+                    .get(id)
+                    .and_then(|node| node.widget().as_any().downcast_ref::<SkinImage>())
+                    .is_some_and(|image| image.needs_redraw())
+            })
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        // Marquees that pause on hover hold still under the pointer
+        let hovered = self.tree.hovered();
+        let node_ids: Vec<_> = self.tree.iter_node_ids().collect();
+        for id in node_ids {
+            if let Some(node) = self.tree.get_mut(id)
+                && let Some(text) = node.widget_mut().as_any_mut().downcast_mut::<StaticText>()
+            {
+                text.set_hovered(hovered == Some(id));
+            }
+        }
+        let refreshed = self.refresh_widget(dt);
+        let timed = self.tick_timers(dt);
+        let coasting: Vec<_> = self
+            .tree
+            .iter_node_ids()
+            .filter(|&id| {
+                self.tree
+                    .get(id)
+                    .and_then(|node| node.widget().as_any().downcast_ref::<Knob>())
+                    .is_some_and(Knob::is_coasting)
+            })
+            .collect();
+        let redraw = self.tree.tick(dt) | self.tooltip.tick(dt) | self.confetti.tick(dt) | self.cross_fade.tick(dt);
+        // Held spinner buttons step the value as time passes, and flicked
+        // knobs coast on
+        if self.sync_sliders_to_store() {
+            self.sync_store_to_outputs();
+        }
+        // Knobs that coasted to rest run their release action
+        for id in coasting {
+            self.handle_slider_release(id);
+        }
+        redraw || refreshed || timed
+    }
+
+    fn poll_interval(&self) -> Option<Duration> {
+        let has_live_images = self.tree.iter_node_ids().any(|id| {
+            self.tree
+                .get(id)
+                .and_then(|node| node.widget().as_any().downcast_ref::<SkinImage>())
+                .is_some_and(|image| image.has_source())
+        });
+        #[cfg(feature = "video")]
+        let has_live_images = has_live_images
+            || self.tree.iter_node_ids().any(|id| {
+                self.tree
+                    .get(id)
+                    .and_then(|node| node.widget().as_any().downcast_ref::<SkinVideo>())
+                    .is_some_and(|video| video.is_playing() || video.is_probing())
+            });
+        let repeating = self.tree.pressed().and_then(|id| self.tree.get(id)).is_some_and(|node| {
+            node.widget()
+                .as_any()
+                .downcast_ref::<Spinner>()
+                .is_some_and(Spinner::is_repeating)
+        });
+        // Moving parts wake the loop every display frame (see `is_animating`)
+        if has_live_images || repeating || self.dispatcher.is_busy() {
+            Some(Duration::from_millis(16))
+        } else if let Some(wait) = self.tooltip.time_until_visible() {
+            // Wake up to show the tooltip once the pointer has rested
+            Some(wait)
+        } else if self.dev_console.as_ref().is_some_and(DevConsole::has_highlights) {
+            // Wake up to fade changed watches in the dev console
+            Some(Duration::from_millis(250))
+        } else if self.services.scan().is_streaming()
+            || self.services.geo().is_pending()
+            || self.services.currency().is_pending()
+            || self.services.update().is_busy()
+        {
+            Some(Duration::from_millis(100))
+        } else if self.timers.is_running() {
+            // Wake up each time a running timer's store values move on
+            Some(TIMER_RESOLUTION)
+        } else if self.tree.focused().and_then(|id| self.tree.get(id)).is_some_and(|node| {
+            let widget = node.widget().as_any();
+            widget.is::<TextInput>() || widget.is::<FilePathInput>() || widget.is::<TextArea>()
+        }) {
+            // Wake up to blink the caret
+            Some(CARET_BLINK_INTERVAL)
+        } else if !self.is_locked()
+            && self.bundle.lock.as_ref().is_some_and(|lock| lock.idle_timeout.is_some())
+        {
+            // Wake up to notice the idle timeout passing
+            Some(Duration::from_secs(1))
+        } else {
+            // Wake up for whichever comes first: the widget pack's next
+            // refresh, reading the channels subscribed to, the published
+            // channel's heartbeat, the next scheduled action, the next alarm
+            // or checking for skin edits
+            let refresh = self
+                .bundle
+                .widget
+                .as_ref()
+                .filter(|widget| widget.on_refresh.is_some())
+                .and_then(|widget| widget.refresh)
+                .map(|interval| interval.saturating_sub(self.since_refresh));
+            let channels = (!self.subscribers.is_empty()).then_some(CHANNEL_POLL_INTERVAL);
+            let heartbeat = self.publisher.as_ref().map(Publisher::time_until_heartbeat);
+            let scheduled = self
+                .scheduler
+                .as_ref()
+                .and_then(|scheduler| scheduler.time_until_next(Local::now().naive_local()));
+            let alarm = self.services.alarms().time_until_next(Local::now().naive_local());
+            let skin_watch = self.skin_watcher.as_ref().map(|_| SKIN_WATCH_INTERVAL);
+            [refresh, channels, heartbeat, scheduled, alarm, skin_watch].into_iter().flatten().min()
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        let sliding = self.tree.iter_node_ids().any(|id| {
+            self.tree
+                .get(id)
+                .and_then(|node| node.widget().as_any().downcast_ref::<ToggleSwitch>())
+                .is_some_and(ToggleSwitch::is_sliding)
+        });
+        let scrolling = self.tree.iter_node_ids().any(|id| {
+            self.tree
+                .get(id)
+                .and_then(|node| node.widget().as_any().downcast_ref::<StaticText>())
+                .is_some_and(StaticText::is_scrolling)
+        });
+        let playing = self.tree.iter_node_ids().any(|id| {
+            self.tree
+                .get(id)
+                .and_then(|node| node.widget().as_any().downcast_ref::<AnimatedSprite>())
+                .is_some_and(AnimatedSprite::is_playing)
+        });
+        let coasting = self.tree.iter_node_ids().any(|id| {
+            self.tree.get(id).is_some_and(|node| {
+                let widget = node.widget().as_any();
+                widget.downcast_ref::<ScrollView>().is_some_and(ScrollView::is_moving)
+                    || widget.downcast_ref::<Knob>().is_some_and(Knob::is_coasting)
+            })
+        });
+        let blinking = self.tree.iter_node_ids().any(|id| {
+            self.tree
+                .get(id)
+                .and_then(|node| node.widget().as_any().downcast_ref::<Indicator>())
+                .is_some_and(Indicator::is_blinking)
+        });
+        let flashing = self.tree.iter_node_ids().any(|id| {
+            self.tree
+                .get(id)
+                .and_then(|node| node.widget().as_any().downcast_ref::<TextInput>())
+                .is_some_and(TextInput::is_flashing)
+        });
+        sliding
+            || scrolling
+            || playing
+            || coasting
+            || blinking
+            || flashing
+            || self.tree.is_animating()
+            || self.confetti.is_active()
+            || self.cross_fade.is_active()
+    }
+
     fn on_event(&mut self, event: &WindowEvent) -> bool {
+        if matches!(
+            event,
+            WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::KeyboardInput { .. }
+        ) {
+            self.last_input = Instant::now();
+        }
+
         match event {
+            WindowEvent::Resized(size) => {
+                // Percentage and aspect sizes follow the window; a minimized
+                // window keeps its layout
+                if size.width == 0 || size.height == 0 || (size.width, size.height) == self.tree.size() {
+                    return false;
+                }
+                self.tree.layout(size.width, size.height);
+                true
+            }
             WindowEvent::CursorMoved { position, .. } => {
                 let x = position.x as i32;
                 let y = position.y as i32;
+                self.cursor = (x, y);
+
+                // An open context menu takes the pointer
+                if self.context_menu.is_open() {
+                    self.context_menu.pointer_moved(x, y);
+                    return true;
+                }
+
                 let hit = self.tree.hit_test(x, y);
                 self.tree.set_hovered(hit);
+                self.tooltip.pointer_moved(&self.tree, (x, y));
 
                 // Send MouseMove event to hovered widget for position tracking
                 if let Some(hovered_id) = hit {
+                    let (x, y) = self.tree.to_local(hovered_id, x, y);
                     if let Some(node) = self.tree.get_mut(hovered_id) {
                         node.widget_mut().on_event(&WidgetEvent::MouseMove { x, y });
                     }
                 }
+
+                // Keep feeding a pressed widget (e.g. a dragged slider) after the cursor leaves it
+                if let Some(pressed_id) = self.tree.pressed()
+                    && hit != Some(pressed_id)
+                {
+                    let (x, y) = self.tree.to_local(pressed_id, x, y);
+                    if let Some(node) = self.tree.get_mut(pressed_id) {
+                        node.widget_mut().on_event(&WidgetEvent::MouseMove { x, y });
+                    }
+                }
+
+                // Dragged sliders update the store continuously
+                if self.sync_sliders_to_store() {
+                    self.sync_store_to_outputs();
+                }
                 true
             }
-            WindowEvent::MouseInput { state, .. } => {
+            WindowEvent::MouseInput { state, button, .. } => {
+                // While a context menu is open, clicks only go to it; a click
+                // outside it closes it without reaching the widgets below
+                if self.context_menu.is_open() {
+                    if state.is_pressed() {
+                        let response = if *button == MouseButton::Left {
+                            self.context_menu.click(self.cursor.0, self.cursor.1)
+                        } else {
+                            self.context_menu.close();
+                            MenuResponse::Dismissed
+                        };
+                        self.handle_menu_response(response);
+                    }
+                    return true;
+                }
+                if *button == MouseButton::Right {
+                    if state.is_pressed() {
+                        self.open_context_menu();
+                    }
+                    return self.context_menu.is_open();
+                }
+
+                // A press on the background of the top inset moves a window
+                // without a title bar; a widget pack moves by any of its background
+                if state.is_pressed()
+                    && !self.window.decorations
+                    && ((0..self.tree.insets().top as i32).contains(&self.cursor.1) || self.bundle.widget.is_some())
+                    && self.tree.hovered() == self.tree.root()
+                {
+                    self.tooltip.dismiss();
+                    self.window_drag = true;
+                    return false;
+                }
+
                 match state {
                     winit::event::ElementState::Pressed => {
+                        self.tooltip.dismiss();
+
                         // Set pressed state
                         if let Some(hovered) = self.tree.hovered() {
                             self.tree.set_pressed(Some(hovered));
 
+                            let (x, y) = self.tree.to_local(hovered, self.cursor.0, self.cursor.1);
+                            if let Some(node) = self.tree.get_mut(hovered) {
+                                node.widget_mut().on_event(&WidgetEvent::MouseDown { x, y });
+                            }
+                            if self.sync_sliders_to_store() {
+                                self.sync_store_to_outputs();
+                            }
+
                             // Focus the clicked widget (for text inputs)
                             let old_focused = self.tree.focused();
                             if old_focused != Some(hovered) {
@@ -362,6 +3319,13 @@ impl App for SkinApp {
                     }
                     winit::event::ElementState::Released => {
                         if let Some(pressed_id) = self.tree.pressed() {
+                            let (x, y) = self.tree.to_local(pressed_id, self.cursor.0, self.cursor.1);
+                            if let Some(node) = self.tree.get_mut(pressed_id) {
+                                node.widget_mut().on_event(&WidgetEvent::MouseUp { x, y });
+                            }
+                            self.handle_slider_release(pressed_id);
+                            self.handle_list_reorder(pressed_id);
+
                             // Check if we're still hovering the pressed widget
                             if self.tree.hovered() == Some(pressed_id) {
                                 // Get action before mutably borrowing tree
@@ -372,11 +3336,38 @@ impl App for SkinApp {
                                     node.widget_mut().on_event(&WidgetEvent::Click);
                                 }
 
-                                // Sync checkboxes after click (they toggle on click)
-                                self.sync_checkboxes_to_store();
+                                // Sync checkboxes after click (they toggle on click) so
+                                // bound text and scripts see the new value right away
+                                if self.sync_checkboxes_to_store() {
+                                    self.sync_store_to_outputs();
+                                }
 
                                 // Handle checkbox actions (dispatch if checkbox has an action)
                                 self.handle_checkbox_actions();
+                                self.handle_toggle_change(pressed_id);
+
+                                if self.sync_dropdowns_to_store() {
+                                    self.sync_store_to_outputs();
+                                }
+                                self.handle_dropdown_change(pressed_id);
+
+                                if self.sync_list_views_to_store() {
+                                    self.sync_store_to_outputs();
+                                }
+                                self.handle_list_view_change(pressed_id);
+
+                                if self.sync_tree_views_to_store() {
+                                    self.sync_store_to_outputs();
+                                }
+                                self.handle_tree_view_change(pressed_id);
+
+                                if self.sync_tab_containers_to_store() {
+                                    self.sync_store_to_outputs();
+                                }
+                                self.handle_tab_change(pressed_id);
+                                self.handle_menu_bar_choice(pressed_id);
+                                self.handle_link_click(pressed_id);
+                                self.handle_path_browse(pressed_id);
 
                                 // Handle file picker actions (must be after click event)
                                 self.handle_file_picker_actions();
@@ -386,13 +3377,7 @@ impl App for SkinApp {
                                     // Sync inputs first
                                     self.sync_inputs_to_store();
 
-                                    // Handle built-in actions
-                                    if action_name == "launch_selected_app" {
-                                        self.handle_launch_selected_app();
-                                    } else {
-                                        // Dispatch the action to Lua handler
-                                        self.dispatch_action(&action_name);
-                                    }
+                                    self.run_action(&Action::new(action_name));
 
                                     // Sync outputs after action
                                     self.sync_store_to_outputs();
@@ -411,20 +3396,73 @@ impl App for SkinApp {
                     winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
                 };
 
-                // Route to hovered widget
-                if let Some(hovered_id) = self.tree.hovered() {
-                    if let Some(node) = self.tree.get_mut(hovered_id) {
-                        if node.widget_mut().on_event(&WidgetEvent::MouseWheel { delta_y }) {
-                            return true;
-                        }
+                // Route to the hovered widget, then up through its parents
+                // so scroll views scroll when hovering their children
+                let mut target = self.tree.hovered();
+                while let Some(id) = target {
+                    let Some(node) = self.tree.get_mut(id) else {
+                        break;
+                    };
+                    if node.widget_mut().on_event(&WidgetEvent::MouseWheel { delta_y }) {
+                        // Scrolling moves content under the cursor
+                        let (x, y) = self.cursor;
+                        let hit = self.tree.hit_test(x, y);
+                        self.tree.set_hovered(hit);
+                        return true;
                     }
+                    target = node.parent();
                 }
                 false
             }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+                false
+            }
             WindowEvent::KeyboardInput { event, .. } => {
                 if !event.state.is_pressed() {
                     return false;
                 }
+                self.tooltip.dismiss();
+
+                // The palette shortcut toggles the command palette (not while
+                // locked, so it can't be used to run actions behind the lock screen)
+                if !self.is_locked() && self.is_palette_shortcut(&event) {
+                    self.toggle_command_palette();
+                    return true;
+                }
+                if self.palette.is_open() {
+                    self.handle_palette_key(&event.logical_key);
+                    return true;
+                }
+                if self.context_menu.is_open() {
+                    let key = match &event.logical_key {
+                        Key::Named(NamedKey::ArrowUp) => Some(KeyCode::Up),
+                        Key::Named(NamedKey::ArrowDown) => Some(KeyCode::Down),
+                        Key::Named(NamedKey::Enter) => Some(KeyCode::Enter),
+                        Key::Named(NamedKey::Escape) => Some(KeyCode::Escape),
+                        _ => None,
+                    };
+                    if let Some(key) = key {
+                        let response = self.context_menu.key(key);
+                        self.handle_menu_response(response);
+                    }
+                    return true;
+                }
+
+                // F12 toggles the dev console in dev mode
+                if let Some(console) = &mut self.dev_console {
+                    if matches!(event.logical_key, Key::Named(NamedKey::F12)) {
+                        console.toggle();
+                        return true;
+                    }
+                    if console.is_open() {
+                        self.handle_console_key(&event.logical_key);
+                        return true;
+                    }
+                }
+                if !self.is_locked() && self.handle_macro_hotkey(event) {
+                    return true;
+                }
 
                 // Route keyboard events to focused widget
                 if let Some(focused_id) = self.tree.focused() {
@@ -435,21 +3473,49 @@ impl App for SkinApp {
                         Key::Named(NamedKey::Delete) => {
                             Some(WidgetEvent::KeyDown { key: KeyCode::Delete })
                         }
+                        // With Shift, caret moves extend the selection
                         Key::Named(NamedKey::ArrowLeft) => {
-                            Some(WidgetEvent::KeyDown { key: KeyCode::Left })
+                            let key = if self.modifiers.shift_key() { KeyCode::SelectLeft } else { KeyCode::Left };
+                            Some(WidgetEvent::KeyDown { key })
                         }
                         Key::Named(NamedKey::ArrowRight) => {
-                            Some(WidgetEvent::KeyDown { key: KeyCode::Right })
+                            let key = if self.modifiers.shift_key() { KeyCode::SelectRight } else { KeyCode::Right };
+                            Some(WidgetEvent::KeyDown { key })
                         }
                         Key::Named(NamedKey::Home) => {
-                            Some(WidgetEvent::KeyDown { key: KeyCode::Home })
+                            let key = if self.modifiers.shift_key() { KeyCode::SelectHome } else { KeyCode::Home };
+                            Some(WidgetEvent::KeyDown { key })
                         }
                         Key::Named(NamedKey::End) => {
-                            Some(WidgetEvent::KeyDown { key: KeyCode::End })
+                            let key = if self.modifiers.shift_key() { KeyCode::SelectEnd } else { KeyCode::End };
+                            Some(WidgetEvent::KeyDown { key })
                         }
                         Key::Named(NamedKey::Enter) => {
                             Some(WidgetEvent::KeyDown { key: KeyCode::Enter })
                         }
+                        Key::Named(NamedKey::ArrowUp) => {
+                            Some(WidgetEvent::KeyDown { key: KeyCode::Up })
+                        }
+                        Key::Named(NamedKey::ArrowDown) => {
+                            Some(WidgetEvent::KeyDown { key: KeyCode::Down })
+                        }
+                        Key::Named(NamedKey::Escape) => {
+                            Some(WidgetEvent::KeyDown { key: KeyCode::Escape })
+                        }
+                        // Ctrl+Z undoes, Ctrl+Y or Ctrl+Shift+Z redoes
+                        Key::Character(s) if self.modifiers.control_key() && s.eq_ignore_ascii_case("z") => {
+                            let key = if self.modifiers.shift_key() { KeyCode::Redo } else { KeyCode::Undo };
+                            Some(WidgetEvent::KeyDown { key })
+                        }
+                        Key::Character(s) if self.modifiers.control_key() && s.eq_ignore_ascii_case("y") => {
+                            Some(WidgetEvent::KeyDown { key: KeyCode::Redo })
+                        }
+                        Key::Named(NamedKey::Undo) => {
+                            Some(WidgetEvent::KeyDown { key: KeyCode::Undo })
+                        }
+                        Key::Named(NamedKey::Redo) => {
+                            Some(WidgetEvent::KeyDown { key: KeyCode::Redo })
+                        }
                         Key::Character(s) => {
                             // Only handle single ASCII characters
                             if s.len() == 1 {
@@ -475,6 +3541,26 @@ impl App for SkinApp {
                         }
                         // Sync after input
                         self.sync_inputs_to_store();
+                        if self.sync_sliders_to_store() {
+                            self.sync_store_to_outputs();
+                        }
+                        if self.sync_dropdowns_to_store() {
+                            self.sync_store_to_outputs();
+                        }
+                        self.handle_dropdown_change(focused_id);
+                        if self.sync_list_views_to_store() {
+                            self.sync_store_to_outputs();
+                        }
+                        self.handle_list_view_change(focused_id);
+                        if self.sync_tree_views_to_store() {
+                            self.sync_store_to_outputs();
+                        }
+                        self.handle_tree_view_change(focused_id);
+                        if self.sync_tab_containers_to_store() {
+                            self.sync_store_to_outputs();
+                        }
+                        self.handle_tab_change(focused_id);
+                        self.handle_menu_bar_choice(focused_id);
                         return true;
                     }
                 }
@@ -489,7 +3575,15 @@ fn main() {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Run { bundle: bundle_path } => {
+        Commands::Run { bundle: bundle_path, profile, dev } => {
+            // Swap in an update downloaded during a previous run
+            #[cfg(feature = "updater")]
+            match crix::services::apply_staged_update(&bundle_path) {
+                Ok(true) => println!("Applied staged update"),
+                Ok(false) => {}
+                Err(e) => eprintln!("Failed to apply staged update: {}", e),
+            }
+
             // Load the app bundle
             let bundle = match AppBundle::load(&bundle_path) {
                 Ok(b) => b,
@@ -506,7 +3600,7 @@ fn main() {
             }
 
             // Create and run the app
-            let app = match SkinApp::new(bundle) {
+            let app = match SkinApp::new(bundle, profile, dev) {
                 Ok(a) => a,
                 Err(e) => {
                     eprintln!("Failed to create app: {}", e);
@@ -514,8 +3608,54 @@ fn main() {
                 }
             };
 
-            let config = RunConfig::default().with_title(&app.title);
+            let config = RunConfig::default()
+                .with_title(&app.title)
+                .with_resizable(app.window.resizable)
+                .with_decorations(app.window.decorations)
+                .with_edge_resistance(app.window.edge_resistance);
             run(app, config);
         }
+        Commands::Host { packs } => {
+            let mut bundles = Vec::new();
+            for path in &packs {
+                match AppBundle::load(path) {
+                    Ok(bundle) => bundles.push(bundle),
+                    Err(e) => {
+                        eprintln!("Failed to load widget pack {:?}: {}", path, e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            // Text is drawn with one font, so the first pack's serves them all
+            if let Err(e) = init_font(bundles[0].font_path(), bundles[0].font_size) {
+                eprintln!("Failed to load font: {}", e);
+                std::process::exit(1);
+            }
+
+            let mut windows = Vec::new();
+            for (i, bundle) in bundles.into_iter().enumerate() {
+                // Packs without a position cascade from the top-left corner
+                let cascade = 40 + 30 * i as i32;
+                let (x, y) = bundle.widget.as_ref().and_then(|widget| widget.position).unwrap_or((cascade, cascade));
+                let mut app = match SkinApp::new(bundle, None, false) {
+                    Ok(a) => a,
+                    Err(e) => {
+                        eprintln!("Failed to create widget: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                // Dragging by the background relies on there being no title bar
+                app.window.decorations = false;
+                let config = RunConfig::default()
+                    .with_title(&app.title)
+                    .with_decorations(false)
+                    .with_always_on_top(true)
+                    .with_position(x, y)
+                    .with_edge_resistance(app.window.edge_resistance);
+                windows.push((app, config));
+            }
+            run_windows(windows);
+        }
     }
 }
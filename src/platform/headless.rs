@@ -0,0 +1,231 @@
+//! Headless harness for driving an app without a window.
+//!
+//! Time only moves when the harness is told to advance it, so tests of caret
+//! blinking, animations and timers get the same result on every run. Each
+//! frame ticks the app by a fixed step and then calls `update`, like one turn
+//! of the event loop.
+
+use std::time::Duration;
+
+use image::RgbImage;
+use winit::event::WindowEvent;
+
+use crate::core::App;
+use crate::export::to_rgb_image;
+use crate::graphics::{NullRenderer, RenderStats, Renderer};
+
+/// Default frame step: 60 frames per second.
+const DEFAULT_FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// Runs an app frame by frame on a simulated clock.
+pub struct Headless<A: App> {
+    app: A,
+    /// Time advanced per frame by `step` and `run_for`.
+    frame_time: Duration,
+    /// Frames advanced so far.
+    frame: u64,
+    /// Simulated time since the harness was created.
+    elapsed: Duration,
+    /// Set when the app asked for a redraw since the last render.
+    needs_redraw: bool,
+    /// What drawing the last render took.
+    render_stats: RenderStats,
+}
+
+impl<A: App> Headless<A> {
+    /// Create a harness around an app, stepping 60 frames per second.
+    pub fn new(app: A) -> Self {
+        Self {
+            app,
+            frame_time: DEFAULT_FRAME_TIME,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            needs_redraw: true,
+            render_stats: RenderStats::default(),
+        }
+    }
+
+    /// Set the time each frame advances.
+    pub fn with_frame_time(mut self, frame_time: Duration) -> Self {
+        self.frame_time = frame_time;
+        self
+    }
+
+    /// Get the app.
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    /// Get the app mutably, e.g. to set store values between frames.
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app
+    }
+
+    /// Stop the harness and take the app back.
+    pub fn into_app(self) -> A {
+        self.app
+    }
+
+    /// Get the number of frames advanced.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Get the simulated time since the harness was created.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Check if the app asked for a redraw since the last `render`.
+    pub fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
+
+    /// Deliver a window event to the app. Returns true if it asked for a redraw.
+    pub fn send(&mut self, event: &WindowEvent) -> bool {
+        let redraw = self.app.on_event(event);
+        self.needs_redraw |= redraw;
+        redraw
+    }
+
+    /// Advance one frame of `dt`. Returns true if the app asked for a redraw.
+    pub fn advance(&mut self, dt: Duration) -> bool {
+        let ticked = self.app.tick(dt);
+        let updated = self.app.update();
+        self.frame += 1;
+        self.elapsed += dt;
+        self.needs_redraw |= ticked || updated;
+        ticked || updated
+    }
+
+    /// Advance one frame of the configured frame time.
+    pub fn step(&mut self) -> bool {
+        self.advance(self.frame_time)
+    }
+
+    /// Advance `frames` frames. Returns true if any asked for a redraw.
+    pub fn step_frames(&mut self, frames: u32) -> bool {
+        let mut redraw = false;
+        for _ in 0..frames {
+            redraw |= self.step();
+        }
+        redraw
+    }
+
+    /// Advance whole frames covering `duration`, with a shorter last frame
+    /// if needed so exactly `duration` passes. Returns true if any frame
+    /// asked for a redraw.
+    pub fn run_for(&mut self, duration: Duration) -> bool {
+        let mut remaining = duration;
+        let mut redraw = false;
+        while !remaining.is_zero() {
+            let dt = remaining.min(self.frame_time.max(Duration::from_nanos(1)));
+            redraw |= self.advance(dt);
+            remaining -= dt;
+        }
+        redraw
+    }
+
+    /// Get what drawing the last render took.
+    pub fn render_stats(&self) -> &RenderStats {
+        &self.render_stats
+    }
+
+    /// Render the app (view and overlay) as the window would show it.
+    pub fn render(&mut self) -> RgbImage {
+        let (width, height) = self.app.view().size();
+        let mut renderer = NullRenderer::new(width, height);
+        renderer.render_app(&mut self.app);
+        self.render_stats = *renderer.stats();
+        self.app.rendered(&self.render_stats);
+        self.needs_redraw = false;
+        to_rgb_image(renderer.frame(), width, height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::core::{Rect, UiTree, View, WidgetEvent};
+    use crate::graphics::init_font;
+    use crate::skin::{StaticText, TextInput, ToggleSwitch};
+
+    struct TreeApp {
+        tree: UiTree,
+    }
+
+    impl App for TreeApp {
+        fn view(&self) -> &dyn View {
+            &self.tree
+        }
+
+        fn tick(&mut self, dt: Duration) -> bool {
+            self.tree.tick(dt)
+        }
+    }
+
+    #[test]
+    fn test_caret_blink_on_simulated_clock() {
+        let mut tree = UiTree::new();
+        let input = TextInput::new(RgbImage::new(50, 20), RgbImage::new(50, 20), RgbImage::new(50, 20), None);
+        let input = tree.add(input, None);
+        tree.set_bounds(input, Rect::new(0, 0, 50, 20));
+        tree.get_mut(input).unwrap().widget_mut().on_event(&WidgetEvent::FocusGained);
+
+        let mut harness = Headless::new(TreeApp { tree }).with_frame_time(Duration::from_millis(10));
+        // The caret doesn't blink until the interval has passed
+        assert!(!harness.run_for(Duration::from_millis(520)));
+        assert!(harness.step());
+        assert_eq!(harness.frame(), 53);
+        assert_eq!(harness.elapsed(), Duration::from_millis(530));
+    }
+
+    #[test]
+    fn test_animation_speed_ignores_frame_rate() {
+        // A switch takes as long to slide at 60 Hz as at 144 Hz
+        for hz in [60, 144] {
+            let mut tree = UiTree::new();
+            let switch = ToggleSwitch::new(40, 20, RgbImage::new(40, 20), RgbImage::new(40, 20));
+            let switch = tree.add(switch, None);
+            let mut harness =
+                Headless::new(TreeApp { tree }).with_frame_time(Duration::from_nanos(1_000_000_000 / hz));
+            harness.step();
+            let widget = harness.app_mut().tree.get_mut(switch).unwrap().widget_mut();
+            widget.as_any_mut().downcast_mut::<ToggleSwitch>().unwrap().toggle();
+
+            assert!(harness.run_for(Duration::from_millis(140)), "{} Hz", hz);
+            let sliding = |harness: &Headless<TreeApp>| {
+                let widget = harness.app().tree.get(switch).unwrap().widget();
+                widget.as_any().downcast_ref::<ToggleSwitch>().unwrap().is_sliding()
+            };
+            assert!(sliding(&harness), "{} Hz", hz);
+            harness.run_for(Duration::from_millis(20));
+            assert!(!sliding(&harness), "{} Hz", hz);
+        }
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let mut tree = UiTree::new();
+        let root = tree.add(StaticText::new("Stats".to_string()), None);
+        tree.set_bounds(root, Rect::new(0, 0, 60, 20));
+        let label = tree.add(StaticText::new("wxyz".to_string()), Some(root));
+        tree.set_bounds(label, Rect::new(0, 0, 60, 20));
+
+        let mut harness = Headless::new(TreeApp { tree });
+        harness.render();
+        let stats = *harness.render_stats();
+        assert_eq!(stats.nodes, 2);
+        assert_eq!(stats.glyphs, 9);
+        assert!(stats.pixels > 60 * 20);
+
+        // Glyphs drawn before come from the cache
+        harness.render();
+        assert_eq!(harness.render_stats().glyph_cache_hit_rate(), Some(1.0));
+    }
+}
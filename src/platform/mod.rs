@@ -1,3 +1,5 @@
+mod headless;
 mod window;
 
-pub use window::{run, RunConfig};
+pub use headless::Headless;
+pub use window::{run, run_windows, EdgeResistance, RendererFactory, RunConfig};
@@ -1,52 +1,224 @@
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
-use winit::event::WindowEvent;
+use winit::event::{ElementState, MouseButton, StartCause, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
-use winit::window::{Window, WindowAttributes, WindowId};
+use winit::dpi::PhysicalPosition;
+use winit::window::{CursorIcon, Window, WindowAttributes, WindowId, WindowLevel};
 
-use crate::core::App;
-use crate::graphics::Renderer;
+use crate::core::{rubber_band, App, InertiaConfig, Rect};
+use crate::graphics::{CpuRenderer, PresentMode, Renderer};
+
+/// Refresh rate assumed when the monitor doesn't report one, in millihertz.
+const DEFAULT_REFRESH_RATE: u32 = 60_000;
+
+/// Range of refresh rates believed, in millihertz; anything else is
+/// treated as unknown.
+const REFRESH_RATE_RANGE: std::ops::RangeInclusive<u32> = 24_000..=500_000;
+
+/// Furthest an elastic window edge can be pulled past the screen's, in
+/// physical pixels.
+const EDGE_STRETCH: f32 = 60.0;
+
+/// How fast a window pulled past the screen's edge springs back, per second.
+const EDGE_SPRING_RATE: f32 = 14.0;
+
+/// What happens when a window without a title bar is dragged by its skin
+/// past the edge of the screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EdgeResistance {
+    /// The system moves the window, which can leave the screen entirely.
+    Free,
+    /// The window stops at the screen's edges.
+    Clamp,
+    /// The window can be pulled a little past the edges, giving way less
+    /// the further it goes, and springs back when let go.
+    #[default]
+    Elastic,
+}
+
+/// Get the position that keeps a window of `size` at `position` inside
+/// `screen`, or at its top-left corner if the window is larger.
+fn keep_on_screen(position: (i32, i32), size: (u32, u32), screen: &Rect) -> (i32, i32) {
+    let x = position.0.min(screen.right() - size.0 as i32).max(screen.x);
+    let y = position.1.min(screen.bottom() - size.1 as i32).max(screen.y);
+    (x, y)
+}
+
+/// Get the time between frames of a monitor refreshing at
+/// `refresh_millihertz`, or at 60 Hz if that's unknown or implausible.
+fn frame_interval(refresh_millihertz: Option<u32>) -> Duration {
+    let rate = refresh_millihertz
+        .filter(|rate| REFRESH_RATE_RANGE.contains(rate))
+        .unwrap_or(DEFAULT_REFRESH_RATE);
+    Duration::from_nanos(1_000_000_000_000 / rate as u64)
+}
 
 struct AppState<A: App> {
     app: A,
+    /// Configuration the window was opened with, kept to reopen it.
+    config: RunConfig,
     window: Rc<Window>,
-    renderer: Renderer,
+    renderer: Box<dyn Renderer>,
+    /// When the app was last ticked.
+    last_tick: Instant,
+    /// Time between frames of the monitor the window is on.
+    frame_interval: Duration,
+    /// Cursor last set on the window.
+    cursor: CursorIcon,
+    /// Pointer position in the window, in physical pixels.
+    pointer: PhysicalPosition<f64>,
+    /// Pointer position in the window when a drag the window handles itself
+    /// started, and where the drag has moved the window to.
+    drag: Option<((f64, f64), (i32, i32))>,
+    /// Where a window let go past the screen's edge is springing back from,
+    /// and to.
+    spring: Option<((f32, f32), (i32, i32))>,
+}
+
+impl<A: App> AppState<A> {
+    /// Look up the refresh rate of the monitor the window is on now.
+    fn update_frame_interval(&mut self) {
+        let rate = self.window.current_monitor().and_then(|monitor| monitor.refresh_rate_millihertz());
+        self.frame_interval = frame_interval(rate);
+    }
+
+    /// Get the screen area of the monitor under the pointer at `pointer`
+    /// (in screen coordinates), or else of the window's monitor.
+    fn screen_at(&self, pointer: (i32, i32)) -> Option<Rect> {
+        let area = |monitor: winit::monitor::MonitorHandle| {
+            let (position, size) = (monitor.position(), monitor.size());
+            Rect::new(position.x, position.y, size.width, size.height)
+        };
+        self.window
+            .available_monitors()
+            .map(area)
+            .find(|screen| screen.contains(pointer.0, pointer.1))
+            .or_else(|| self.window.current_monitor().map(area))
+    }
+
+    /// Get the window's size including its frame.
+    fn outer_size(&self) -> (u32, u32) {
+        let size = self.window.outer_size();
+        (size.width, size.height)
+    }
+
+    /// Start a drag of the window by its skin, from the pointer's position.
+    fn start_drag(&mut self) {
+        let position = match self.window.outer_position() {
+            Ok(position) if self.config.edge_resistance != EdgeResistance::Free => position,
+            // Without a known position only the system can move the window
+            _ => {
+                if let Err(e) = self.window.drag_window() {
+                    eprintln!("Failed to drag window: {}", e);
+                }
+                return;
+            }
+        };
+        self.spring = None;
+        self.drag = Some(((self.pointer.x, self.pointer.y), (position.x, position.y)));
+    }
+
+    /// Move a dragged window after the pointer, held back at the screen's
+    /// edges.
+    fn drag_to(&mut self, pointer: PhysicalPosition<f64>) {
+        let Some((grab, position)) = self.drag else {
+            return;
+        };
+        // The pointer is reported relative to where the window was last moved
+        let screen_pointer = (position.0 as f64 + pointer.x, position.1 as f64 + pointer.y);
+        let target = ((screen_pointer.0 - grab.0) as i32, (screen_pointer.1 - grab.1) as i32);
+        let Some(screen) = self.screen_at((screen_pointer.0 as i32, screen_pointer.1 as i32)) else {
+            return;
+        };
+        let kept = keep_on_screen(target, self.outer_size(), &screen);
+        let moved = match self.config.edge_resistance {
+            EdgeResistance::Elastic => (
+                kept.0 + rubber_band((target.0 - kept.0) as f32, EDGE_STRETCH) as i32,
+                kept.1 + rubber_band((target.1 - kept.1) as f32, EDGE_STRETCH) as i32,
+            ),
+            _ => kept,
+        };
+        if moved != position {
+            self.window.set_outer_position(PhysicalPosition::new(moved.0, moved.1));
+            self.drag = Some((grab, moved));
+        }
+    }
+
+    /// End a drag, springing back onto the screen if the window was pulled
+    /// past its edge.
+    fn end_drag(&mut self) {
+        let Some((grab, position)) = self.drag.take() else {
+            return;
+        };
+        let pointer = (position.0 + grab.0 as i32, position.1 + grab.1 as i32);
+        if let Some(screen) = self.screen_at(pointer) {
+            let kept = keep_on_screen(position, self.outer_size(), &screen);
+            if kept != position {
+                self.spring = Some(((position.0 as f32, position.1 as f32), kept));
+            }
+        }
+    }
+
+    /// Move a window springing back onto the screen on by `dt`.
+    fn spring_back(&mut self, dt: Duration) {
+        let Some(((x, y), target)) = self.spring else {
+            return;
+        };
+        let keep = (-EDGE_SPRING_RATE * dt.as_secs_f32()).exp();
+        let (x, y) = (target.0 as f32 + (x - target.0 as f32) * keep, target.1 as f32 + (y - target.1 as f32) * keep);
+        let settled = (x - target.0 as f32).abs() < 0.5 && (y - target.1 as f32).abs() < 0.5;
+        let (x, y) = if settled { (target.0 as f32, target.1 as f32) } else { (x, y) };
+        self.window.set_outer_position(PhysicalPosition::new(x.round() as i32, y.round() as i32));
+        self.spring = (!settled).then_some(((x, y), target));
+    }
+
+    /// Move the window to the middle of its monitor.
+    fn center(&mut self) {
+        let Some(monitor) = self.window.current_monitor().or_else(|| self.window.primary_monitor()) else {
+            return;
+        };
+        let (position, size) = (monitor.position(), monitor.size());
+        let (width, height) = self.outer_size();
+        let x = position.x + (size.width as i32 - width as i32) / 2;
+        let y = position.y + (size.height as i32 - height as i32) / 2;
+        self.drag = None;
+        self.spring = None;
+        self.window.set_outer_position(PhysicalPosition::new(x, y));
+    }
 }
 
 struct WinitHandler<A: App> {
-    pending_app: Option<A>,
-    context: softbuffer::Context<winit::event_loop::OwnedDisplayHandle>,
-    size: PhysicalSize<u32>,
-    resizable: bool,
-    title: String,
-    state: Option<AppState<A>>,
+    /// Apps whose windows haven't been opened yet.
+    pending: Vec<(A, RunConfig)>,
+    states: Vec<AppState<A>>,
 }
 
 impl<A: App> WinitHandler<A> {
-    fn new(app: A, context: softbuffer::Context<winit::event_loop::OwnedDisplayHandle>, size: PhysicalSize<u32>, resizable: bool, title: String) -> Self {
+    fn new(apps: Vec<(A, RunConfig)>) -> Self {
         Self {
-            pending_app: Some(app),
-            context,
-            size,
-            resizable,
-            title,
-            state: None,
+            pending: apps,
+            states: Vec::new(),
         }
     }
-}
 
-impl<A: App> ApplicationHandler for WinitHandler<A> {
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        let Some(app) = self.pending_app.take() else {
-            return;
-        };
-
-        let attrs = WindowAttributes::default()
-            .with_inner_size(self.size)
-            .with_resizable(self.resizable)
-            .with_title(&self.title);
+    /// Open a window for `app`, sized by the app's view.
+    fn open(&self, event_loop: &ActiveEventLoop, mut app: A, config: RunConfig) -> AppState<A> {
+        app.set_inertia(config.inertia);
+        let (width, height) = app.view().size();
+        let mut attrs = WindowAttributes::default()
+            .with_inner_size(PhysicalSize::new(width, height))
+            .with_resizable(config.resizable)
+            .with_decorations(config.decorations)
+            .with_title(&config.title);
+        if config.always_on_top {
+            attrs = attrs.with_window_level(WindowLevel::AlwaysOnTop);
+        }
+        if let Some((x, y)) = config.position {
+            attrs = attrs.with_position(PhysicalPosition::new(x, y));
+        }
 
         let window = Rc::new(
             event_loop
@@ -54,45 +226,88 @@ impl<A: App> ApplicationHandler for WinitHandler<A> {
                 .expect("Failed to create window"),
         );
 
-        let renderer = Renderer::new(&self.context, window.clone());
+        let renderer = (config.renderer)(window.clone());
 
-        self.state = Some(AppState {
+        let mut state = AppState {
             app,
+            config,
             window,
             renderer,
-        });
+            last_tick: Instant::now(),
+            frame_interval: frame_interval(None),
+            cursor: CursorIcon::Default,
+            pointer: PhysicalPosition::new(0.0, 0.0),
+            drag: None,
+            spring: None,
+        };
+        state.update_frame_interval();
+        state
     }
 
-    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
-        if let Some(state) = self.state.take() {
-            self.pending_app = Some(state.app);
+    /// Close window `index`, ending the event loop with the last one.
+    fn close(&mut self, event_loop: &ActiveEventLoop, index: usize) {
+        self.states.remove(index);
+        if self.states.is_empty() {
+            event_loop.exit();
+        }
+    }
+}
+
+impl<A: App> ApplicationHandler for WinitHandler<A> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        for (app, config) in std::mem::take(&mut self.pending) {
+            let state = self.open(event_loop, app, config);
+            self.states.push(state);
+        }
+    }
+
+    fn new_events(&mut self, _event_loop: &ActiveEventLoop, cause: StartCause) {
+        // Input after a wait with no wake-up set: nothing was moving, so the
+        // idle time isn't time an animation started by this input has played
+        if let StartCause::WaitCancelled { requested_resume: None, .. } = cause {
+            let now = Instant::now();
+            for state in &mut self.states {
+                state.last_tick = now;
+            }
         }
     }
 
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        self.pending = self.states.drain(..).map(|state| (state.app, state.config)).collect();
+    }
+
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
         window_id: WindowId,
         event: WindowEvent,
     ) {
-        event_loop.set_control_flow(ControlFlow::Wait);
-
-        let Some(state) = &mut self.state else {
+        let Some(index) = self.states.iter().position(|state| state.window.id() == window_id) else {
             return;
         };
-
-        if window_id != state.window.id() {
-            return;
-        }
+        let state = &mut self.states[index];
 
         match &event {
             WindowEvent::RedrawRequested => {
                 let size = state.window.inner_size();
                 state.renderer.resize(size.width, size.height);
-                state.renderer.render(state.app.view());
+                state.renderer.render_app(&mut state.app);
+                state.app.rendered(state.renderer.stats());
             }
             WindowEvent::CloseRequested => {
-                event_loop.exit();
+                self.close(event_loop, index);
+                return;
+            }
+            // The window may have moved to a monitor with another refresh rate
+            WindowEvent::Moved(_) | WindowEvent::ScaleFactorChanged { .. } => {
+                state.update_frame_interval();
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                state.drag_to(*position);
+                state.pointer = *position;
+            }
+            WindowEvent::MouseInput { state: ElementState::Released, button: MouseButton::Left, .. } => {
+                state.end_drag();
             }
             _ => {}
         }
@@ -100,20 +315,105 @@ impl<A: App> ApplicationHandler for WinitHandler<A> {
         if state.app.on_event(&event) {
             state.window.request_redraw();
         }
+
+        let cursor = state.app.cursor();
+        if cursor != state.cursor {
+            state.window.set_cursor(cursor);
+            state.cursor = cursor;
+        }
+
+        if state.app.take_window_drag() {
+            state.start_drag();
+        }
+        if state.app.take_window_center() {
+            state.center();
+        }
+
+        if state.app.take_window_close() {
+            self.close(event_loop, index);
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        // Advance each app's clock by the real time since its last tick
+        let now = Instant::now();
+        let mut wake: Option<Duration> = None;
+        let mut index = 0;
+        while index < self.states.len() {
+            let state = &mut self.states[index];
+            let dt = now - state.last_tick;
+            let ticked = state.app.tick(dt);
+            state.last_tick = now;
+            state.spring_back(dt);
+
+            if state.app.take_window_close() {
+                self.close(event_loop, index);
+                continue;
+            }
+            if state.app.update() || ticked {
+                state.window.request_redraw();
+            }
+            if state.app.take_window_center() {
+                state.center();
+            }
+
+            // Wake up every display frame while something moves, and keep
+            // waking up while the app has externally-driven content
+            let moving = state.app.is_animating() || state.spring.is_some();
+            let interval = match state.app.poll_interval() {
+                Some(interval) if moving => Some(interval.min(state.frame_interval)),
+                None if moving => Some(state.frame_interval),
+                interval => interval,
+            };
+            // The soonest wake-up of any window wins
+            wake = match (wake, interval) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            index += 1;
+        }
+
+        match wake {
+            Some(interval) => {
+                event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now() + interval));
+            }
+            None => event_loop.set_control_flow(ControlFlow::Wait),
+        }
     }
 }
 
+/// Creates the renderer a window's frames are drawn with.
+pub type RendererFactory = Rc<dyn Fn(Rc<Window>) -> Box<dyn Renderer>>;
+
 /// Configuration for running an application.
 pub struct RunConfig {
     pub resizable: bool,
+    /// Whether the window has a title bar and borders.
+    pub decorations: bool,
     pub title: String,
+    /// Whether the window stays above other windows.
+    pub always_on_top: bool,
+    /// Where the window opens on screen, or wherever the system puts it.
+    pub position: Option<(i32, i32)>,
+    /// How scroll views and knobs carry on after a flick.
+    pub inertia: InertiaConfig,
+    /// What happens when the app drags the window past the screen's edge.
+    pub edge_resistance: EdgeResistance,
+    /// Creates the window's renderer; a `CpuRenderer` by default.
+    pub renderer: RendererFactory,
 }
 
 impl Default for RunConfig {
     fn default() -> Self {
         Self {
             resizable: false,
+            decorations: true,
             title: String::from("Crix"),
+            always_on_top: false,
+            position: None,
+            inertia: InertiaConfig::default(),
+            edge_resistance: EdgeResistance::default(),
+            renderer: Rc::new(|window| Box::new(CpuRenderer::new(window))),
         }
     }
 }
@@ -124,19 +424,97 @@ impl RunConfig {
         self.title = title.into();
         self
     }
+
+    /// Set whether the window can be resized.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Set whether the window has a title bar and borders.
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Set whether the window stays above other windows.
+    pub fn with_always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    /// Set where the window opens on screen, in physical pixels.
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Set what happens when the app drags the window past the screen's edge.
+    pub fn with_edge_resistance(mut self, edge_resistance: EdgeResistance) -> Self {
+        self.edge_resistance = edge_resistance;
+        self
+    }
+
+    /// Set how scroll views and knobs carry on after a flick.
+    pub fn with_inertia(mut self, inertia: InertiaConfig) -> Self {
+        self.inertia = inertia;
+        self
+    }
+
+    /// Draw the window's frames with a `CpuRenderer` presenting them the
+    /// given way.
+    pub fn with_present_mode(mut self, mode: PresentMode) -> Self {
+        self.renderer = Rc::new(move |window| Box::new(CpuRenderer::with_mode(window, mode)));
+        self
+    }
+
+    /// Draw the window's frames with renderers made by `factory` instead
+    /// of a `CpuRenderer`.
+    pub fn with_renderer(mut self, factory: impl Fn(Rc<Window>) -> Box<dyn Renderer> + 'static) -> Self {
+        self.renderer = Rc::new(factory);
+        self
+    }
 }
 
 /// Run an application with the given configuration.
 /// The window size is determined by the app's view size.
 pub fn run<A: App + 'static>(app: A, config: RunConfig) {
-    let event_loop = EventLoop::new().expect("Failed to create event loop");
-    let context = softbuffer::Context::new(event_loop.owned_display_handle())
-        .expect("Failed to create softbuffer context");
+    run_windows(vec![(app, config)]);
+}
 
-    // Get the size from the app's view
-    let (width, height) = app.view().size();
-    let size = PhysicalSize::new(width, height);
-    let mut handler = WinitHandler::new(app, context, size, config.resizable, config.title);
+/// Run several applications, each in its own window, on one event loop.
+/// The loop ends when the last window closes.
+pub fn run_windows<A: App + 'static>(apps: Vec<(A, RunConfig)>) {
+    let event_loop = EventLoop::new().expect("Failed to create event loop");
+    let mut handler = WinitHandler::new(apps);
 
     event_loop.run_app(&mut handler).expect("Event loop failed");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_interval_follows_refresh_rate() {
+        assert_eq!(frame_interval(Some(144_000)), Duration::from_nanos(6_944_444));
+        assert_eq!(frame_interval(Some(59_940)), Duration::from_nanos(16_683_350));
+        // Unknown or nonsense rates fall back to 60 Hz
+        assert_eq!(frame_interval(None), Duration::from_nanos(16_666_666));
+        assert_eq!(frame_interval(Some(0)), frame_interval(None));
+        assert_eq!(frame_interval(Some(10_000_000)), frame_interval(None));
+    }
+
+    #[test]
+    fn test_keep_on_screen() {
+        let screen = Rect::new(0, 0, 1920, 1080);
+        assert_eq!(keep_on_screen((100, 200), (400, 300), &screen), (100, 200));
+        assert_eq!(keep_on_screen((-50, 900), (400, 300), &screen), (0, 780));
+        assert_eq!(keep_on_screen((1800, -20), (400, 300), &screen), (1520, 0));
+        // A second monitor to the left has negative coordinates
+        let left = Rect::new(-1280, 0, 1280, 1024);
+        assert_eq!(keep_on_screen((-1300, 10), (400, 300), &left), (-1280, 10));
+        // Windows larger than the screen keep their top-left corner on it
+        assert_eq!(keep_on_screen((-10, -10), (2000, 1200), &screen), (0, 0));
+    }
+}
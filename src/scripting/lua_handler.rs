@@ -11,8 +11,38 @@
 //! - `app.get(key)` - Get a value from the Store. Returns string, number, boolean, or nil.
 //! - `app.set(key, value)` - Set a value in the Store. Value can be string, number, or boolean.
 //! - `app.log(message)` - Log a message for debugging purposes.
+//! - `app.speak(text)` - Speak text aloud. Requires the "tts" capability.
+//! - `app.yield()` - Pause the script until the next frame. Values set so far
+//!   are written to the Store first, so long-running scripts can show progress.
+//! - `app.alarms.set{id=, at=, message=, repeats=, sound=, action=}` - Set or replace a saved
+//!   alarm; `at` is `"HH:MM"`, `"YYYY-MM-DD HH:MM"` or `"+N"` minutes from now.
+//! - `app.alarms.cancel(id)` - Remove an alarm. Returns false if there was none.
+//! - `app.alarms.list()` - Get the alarms, soonest first, as tables with `id`, `at`,
+//!   `message`, `repeats`, `sound` and `action`.
+//!
+//! - `app.dec.add(a, b)`, `app.dec.sub(a, b)`, `app.dec.mul(a, b)`, `app.dec.div(a, b)` - Exact
+//!   decimal arithmetic on numbers or decimal strings, so `app.dec.add(0.1, 0.2)` is `"0.3"`.
+//!   Results are decimal strings.
+//! - `app.dec.round(a, places)` - Round to `places` decimal places (default 0), halves away
+//!   from zero, keeping that many places: `app.dec.round("2.5", 2)` is `"2.50"`.
+//! - `app.currency.format(amount, code)` - Format an amount of money by ISO code with the
+//!   app's separators, e.g. `app.currency.format(1234.5, "EUR")` is `"€1,234.50"`.
+//! - `app.currency.convert(amount, from, to)` - Convert an amount at the current exchange
+//!   rates, as a decimal string.
 //!
 //! The `app.payload` table contains any payload data passed with the action (optional).
+//! Decimal values in the Store and payload reach scripts as decimal strings. Scripts can't
+//! create `Value::Decimal`s: what they store, `app.dec` results included, is stored as a string.
+//!
+//! A second global table, `canvas`, draws on `canvas` parts:
+//!
+//! - `canvas.begin(key)` - Start a new drawing in the Store key a canvas part is bound to.
+//!   Without it, commands are added to the drawing already there.
+//! - `canvas.line(x1, y1, x2, y2, color, width)` - Draw a line (width defaults to 1).
+//! - `canvas.rect(x, y, width, height, color, filled)` - Draw a rectangle (filled unless `filled` is false).
+//! - `canvas.text(x, y, text, color, size)` - Draw a line of text (size defaults to 16).
+//!
+//! Colors are numbers like `0xFF0000` or strings like `"#ff0000"`, black when omitted.
 //!
 //! # Security Model
 //!
@@ -21,7 +51,8 @@
 //! - NO network access
 //! - NO OS commands
 //! - NO widget/node references
-//! - Only Store read/write is permitted
+//! - Only Store read/write is permitted, plus services the app was granted
+//!   capabilities for in app.toml
 //!
 //! # Example Script
 //!
@@ -47,9 +78,13 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use mlua::{Lua, Value as LuaValue};
+use chrono::Local;
+use mlua::{Function, Lua, Table, Thread, ThreadStatus, Value as LuaValue};
 
+use crate::core::decimal::{self, DecimalError};
 use crate::core::{Action, ActionError, ActionHandler, Services, Store, Value};
+use crate::graphics::{parse_draw_color, DrawCommand, MAX_DRAW_COMMANDS};
+use crate::services::{parse_alarm_time, Alarm, Repeat, ALARM_TIME_FORMAT};
 
 use super::app_config::AppConfig;
 
@@ -82,6 +117,30 @@ impl From<mlua::Error> for LuaError {
     }
 }
 
+/// Registry key of the store values the running script reads with `app.get`.
+const STORE_DATA: &str = "crix.store";
+
+/// Registry key of the Store key `canvas.*` commands draw in.
+const CANVAS_TARGET: &str = "crix.canvas";
+
+/// A script that has started running, possibly suspended in `app.yield()`.
+struct LuaTask {
+    /// Action that started the script.
+    action: String,
+    /// The script's VM, kept alive while the script is suspended.
+    lua: Lua,
+    /// The script body, run as a coroutine so it can yield.
+    thread: Thread,
+    /// Values set with `app.set` since the last flush.
+    output_data: Table,
+    /// Messages logged with `app.log` since the last flush.
+    log_messages: Table,
+    /// Text queued with `app.speak` since the last flush.
+    speech_queue: Table,
+    /// Drawings made with `canvas.*` since the last flush, by Store key.
+    drawings: Table,
+}
+
 /// A Lua-backed action handler.
 ///
 /// Executes Lua scripts in response to actions, allowing scripts to
@@ -89,6 +148,8 @@ impl From<mlua::Error> for LuaError {
 pub struct LuaActionHandler {
     /// Action name -> script path mappings.
     action_scripts: HashMap<String, PathBuf>,
+    /// Scripts suspended in `app.yield()`, resumed on the next poll.
+    tasks: Vec<LuaTask>,
 }
 
 impl LuaActionHandler {
@@ -106,14 +167,17 @@ impl LuaActionHandler {
                 action_scripts.insert(action_name.clone(), path.to_path_buf());
             }
         }
-        Self { action_scripts }
+        Self::from_scripts(action_scripts)
     }
 
     /// Create a new Lua action handler from a HashMap of action -> script path.
     ///
     /// This is useful when loading from an app bundle.
     pub fn from_scripts(action_scripts: HashMap<String, PathBuf>) -> Self {
-        Self { action_scripts }
+        Self {
+            action_scripts,
+            tasks: Vec::new(),
+        }
     }
 
     /// Get the script path for an action.
@@ -126,16 +190,16 @@ impl LuaActionHandler {
         self.action_scripts.keys()
     }
 
-    /// Execute a Lua script with access to the Store.
+    /// Get the number of scripts suspended in `app.yield()`.
+    pub fn running_scripts(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Load a Lua script with access to the Store, ready to run.
     ///
     /// Creates a fresh Lua VM for each script execution to ensure isolation.
     /// Sets up the `app` global table with get/set/log functions.
-    fn execute_script(
-        &self,
-        script_path: &Path,
-        action: &Action,
-        store: &mut Store,
-    ) -> Result<(), LuaError> {
+    fn start_script(&self, script_path: &Path, action: &Action, services: &Services) -> Result<LuaTask, LuaError> {
         // Read the script
         let script_content = fs::read_to_string(script_path).map_err(LuaError::Io)?;
 
@@ -151,23 +215,10 @@ impl LuaActionHandler {
         lua.globals().set("load", LuaValue::Nil)?;
 
         // We'll use a two-phase approach:
-        // 1. Copy store values into Lua tables
-        // 2. Run the script
+        // 1. Copy store values into a Lua table before each run
+        // 2. Run the script until it finishes or yields
         // 3. Copy modified values back to the store
 
-        // Create a table to hold store values (for reading)
-        let store_data = lua.create_table()?;
-        for key in store.keys() {
-            if let Some(value) = store.get(key) {
-                match value {
-                    Value::String(s) => store_data.set(key.clone(), s.clone())?,
-                    Value::Number(n) => store_data.set(key.clone(), *n)?,
-                    Value::Bool(b) => store_data.set(key.clone(), *b)?,
-                    Value::Null => store_data.set(key.clone(), LuaValue::Nil)?,
-                }
-            }
-        }
-
         // Create a table to collect outputs
         let output_data = lua.create_table()?;
 
@@ -177,10 +228,10 @@ impl LuaActionHandler {
         // Create the app table with get/set/log functions
         let app_table = lua.create_table()?;
 
-        // app.get(key) - read from store_data
-        let store_data_ref = store_data.clone();
-        let get_fn = lua.create_function(move |_lua, key: String| {
-            let value: LuaValue = store_data_ref.get(key)?;
+        // app.get(key) - read from the copy of the store values
+        let get_fn = lua.create_function(move |lua, key: String| {
+            let store_data: Table = lua.named_registry_value(STORE_DATA)?;
+            let value: LuaValue = store_data.get(key)?;
             Ok(value)
         })?;
         app_table.set("get", get_fn)?;
@@ -202,12 +253,114 @@ impl LuaActionHandler {
         })?;
         app_table.set("log", log_fn)?;
 
+        // app.speak(text) - queue text to speak once the script finishes or yields
+        let speech_queue = lua.create_table()?;
+        let speech_queue_ref = speech_queue.clone();
+        let tts_allowed = services.capabilities().allows("tts");
+        let speak_fn = lua.create_function(move |_, text: String| {
+            if !tts_allowed {
+                return Err(mlua::Error::RuntimeError(
+                    "capability 'tts' not granted in app.toml".to_string(),
+                ));
+            }
+            let len: i64 = speech_queue_ref.len()? + 1;
+            speech_queue_ref.set(len, text)?;
+            Ok(())
+        })?;
+        app_table.set("speak", speak_fn)?;
+
+        // app.yield() - hand the values set so far to the app and continue next frame
+        let coroutine: Table = lua.globals().get("coroutine")?;
+        let yield_fn: Function = coroutine.get("yield")?;
+        app_table.set("yield", yield_fn)?;
+
+        // app.alarms - set, cancel and list saved alarms
+        let alarms_table = lua.create_table()?;
+        let alarms = services.alarms().clone();
+        let alarm_set_fn = lua.create_function(move |_, options: Table| {
+            let id: String = options.get("id")?;
+            let at: String = options.get("at")?;
+            let message: Option<String> = options.get("message")?;
+            let repeats: Option<String> = options.get("repeats")?;
+            let sound: Option<bool> = options.get("sound")?;
+            let action: Option<String> = options.get("action")?;
+            let result = parse_alarm_time(&at, Local::now().naive_local()).and_then(|at| {
+                let mut alarm = Alarm::new(id, at)
+                    .with_message(message.unwrap_or_default())
+                    .with_repeats(repeats.as_deref().unwrap_or_default().parse::<Repeat>()?)
+                    .with_sound(sound.unwrap_or(true));
+                if let Some(action) = action {
+                    alarm = alarm.with_action(action);
+                }
+                alarms.set(alarm)
+            });
+            result.map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        })?;
+        alarms_table.set("set", alarm_set_fn)?;
+        let alarms = services.alarms().clone();
+        let alarm_cancel_fn = lua.create_function(move |_, id: String| {
+            alarms.cancel(&id).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        })?;
+        alarms_table.set("cancel", alarm_cancel_fn)?;
+        let alarms = services.alarms().clone();
+        let alarm_list_fn = lua.create_function(move |lua, ()| {
+            let list = lua.create_table()?;
+            for (i, alarm) in alarms.list().into_iter().enumerate() {
+                let entry = lua.create_table()?;
+                entry.set("id", alarm.id)?;
+                entry.set("at", alarm.at.format(ALARM_TIME_FORMAT).to_string())?;
+                entry.set("message", alarm.message)?;
+                entry.set("repeats", alarm.repeats.as_str())?;
+                entry.set("sound", alarm.sound)?;
+                entry.set("action", alarm.action)?;
+                list.set(i + 1, entry)?;
+            }
+            Ok(list)
+        })?;
+        alarms_table.set("list", alarm_list_fn)?;
+        app_table.set("alarms", alarms_table)?;
+
+        // app.dec - exact decimal arithmetic, returning decimal strings
+        let dec_table = lua.create_table()?;
+        type DecimalOp = fn(&Value, &Value) -> Result<rust_decimal::Decimal, DecimalError>;
+        let ops: [(&str, DecimalOp); 4] =
+            [("add", decimal::add), ("sub", decimal::sub), ("mul", decimal::mul), ("div", decimal::div)];
+        for (name, op) in ops {
+            let op_fn = lua.create_function(move |_, (a, b): (LuaValue, LuaValue)| {
+                let result = op(&decimal_arg(a)?, &decimal_arg(b)?);
+                result.map(|d| d.to_string()).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            })?;
+            dec_table.set(name, op_fn)?;
+        }
+        let round_fn = lua.create_function(|_, (value, places): (LuaValue, Option<u32>)| {
+            let result = decimal::round(&decimal_arg(value)?, places.unwrap_or(0));
+            result.map(|d| d.to_string()).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        })?;
+        dec_table.set("round", round_fn)?;
+        app_table.set("dec", dec_table)?;
+
+        // app.currency - format and convert amounts of money
+        let currency_table = lua.create_table()?;
+        let currency = services.currency().clone();
+        let format_fn = lua.create_function(move |_, (amount, code): (LuaValue, String)| {
+            currency.format(&decimal_arg(amount)?, &code).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        })?;
+        currency_table.set("format", format_fn)?;
+        let currency = services.currency().clone();
+        let convert_fn = lua.create_function(move |_, (amount, from, to): (LuaValue, String, String)| {
+            let result = currency.convert(&decimal_arg(amount)?, &from, &to);
+            result.map(|d| d.to_string()).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        })?;
+        currency_table.set("convert", convert_fn)?;
+        app_table.set("currency", currency_table)?;
+
         // app.payload - action payload table
         let payload_table = lua.create_table()?;
         for (key, value) in &action.payload {
             match value {
                 Value::String(s) => payload_table.set(key.clone(), s.clone())?,
                 Value::Number(n) => payload_table.set(key.clone(), *n)?,
+                Value::Decimal(d) => payload_table.set(key.clone(), d.to_string())?,
                 Value::Bool(b) => payload_table.set(key.clone(), *b)?,
                 Value::Null => payload_table.set(key.clone(), LuaValue::Nil)?,
             }
@@ -217,13 +370,87 @@ impl LuaActionHandler {
         // Set the app global
         lua.globals().set("app", app_table)?;
 
-        // Execute the script
-        lua.load(&script_content)
+        // canvas.begin(key) - select the drawing later commands go to, starting it over
+        let drawings = lua.create_table()?;
+        let canvas_table = lua.create_table()?;
+        let drawings_ref = drawings.clone();
+        let begin_fn = lua.create_function(move |lua, key: String| {
+            lua.set_named_registry_value(CANVAS_TARGET, key.clone())?;
+            let drawing = lua.create_table()?;
+            drawing.set("restart", true)?;
+            drawing.set("commands", lua.create_table()?)?;
+            drawings_ref.set(key, drawing)
+        })?;
+        canvas_table.set("begin", begin_fn)?;
+
+        // canvas.line/rect/text - add a command to the selected drawing
+        let drawings_ref = drawings.clone();
+        let line_fn = lua.create_function(
+            move |lua, (x1, y1, x2, y2, color, width): (f64, f64, f64, f64, LuaValue, Option<f64>)| {
+                let command = DrawCommand::line((x1, y1), (x2, y2), draw_color(color)?, width.unwrap_or(1.0));
+                push_draw_command(lua, &drawings_ref, command)
+            },
+        )?;
+        canvas_table.set("line", line_fn)?;
+        let drawings_ref = drawings.clone();
+        let rect_fn = lua.create_function(
+            move |lua, (x, y, width, height, color, filled): (f64, f64, f64, f64, LuaValue, Option<bool>)| {
+                let command = DrawCommand::rect(x, y, width, height, draw_color(color)?, filled.unwrap_or(true));
+                push_draw_command(lua, &drawings_ref, command)
+            },
+        )?;
+        canvas_table.set("rect", rect_fn)?;
+        let drawings_ref = drawings.clone();
+        let text_fn = lua.create_function(
+            move |lua, (x, y, text, color, size): (f64, f64, String, LuaValue, Option<f64>)| {
+                let command = DrawCommand::text(x, y, &text, draw_color(color)?, size.unwrap_or(16.0));
+                push_draw_command(lua, &drawings_ref, command)
+            },
+        )?;
+        canvas_table.set("text", text_fn)?;
+        lua.globals().set("canvas", canvas_table)?;
+
+        // Load the script as a coroutine so app.yield can suspend it
+        let function = lua
+            .load(&script_content)
             .set_name(script_path.to_string_lossy())
-            .exec()?;
+            .into_function()?;
+        let thread = lua.create_thread(function)?;
+
+        Ok(LuaTask {
+            action: action.name.clone(),
+            lua,
+            thread,
+            output_data,
+            log_messages,
+            speech_queue,
+            drawings,
+        })
+    }
+
+    /// Run a script until it finishes or yields, then apply what it did.
+    /// Returns true if the script finished.
+    fn resume(task: &LuaTask, store: &mut Store, services: &Services) -> Result<bool, LuaError> {
+        // Create a table to hold store values (for reading)
+        let store_data = task.lua.create_table()?;
+        for key in store.keys() {
+            if let Some(value) = store.get(key) {
+                match value {
+                    Value::String(s) => store_data.set(key.clone(), s.clone())?,
+                    Value::Number(n) => store_data.set(key.clone(), *n)?,
+                    Value::Decimal(d) => store_data.set(key.clone(), d.to_string())?,
+                    Value::Bool(b) => store_data.set(key.clone(), *b)?,
+                    Value::Null => store_data.set(key.clone(), LuaValue::Nil)?,
+                }
+            }
+        }
+        task.lua.set_named_registry_value(STORE_DATA, store_data)?;
 
-        // Copy output values back to the store
-        for pair in output_data.pairs::<String, LuaValue>() {
+        // Execute the script
+        let result = task.thread.resume::<()>(());
+
+        // Copy output values back to the store, even from a failed run
+        for pair in task.output_data.pairs::<String, LuaValue>() {
             let (key, value) = pair?;
             match value {
                 LuaValue::String(s) => store.set(key, s.to_str()?.to_string()),
@@ -237,23 +464,107 @@ impl LuaActionHandler {
                 }
             }
         }
+        task.output_data.clear()?;
+
+        // Write drawings to their keys, after what earlier runs drew unless restarted
+        for pair in task.drawings.pairs::<String, Table>() {
+            let (key, drawing) = pair?;
+            let mut text = if drawing.get::<bool>("restart")? { String::new() } else { store.get_string(&key) };
+            for command in drawing.get::<Table>("commands")?.sequence_values::<String>() {
+                text.push_str(&command?);
+                text.push('\n');
+            }
+            store.set(key, text);
+        }
+        task.drawings.clear()?;
 
         // Print any log messages
-        for i in 1..=log_messages.len()? {
-            let msg: String = log_messages.get(i)?;
+        for i in 1..=task.log_messages.len()? {
+            let msg: String = task.log_messages.get(i)?;
             println!("[Lua] {}", msg);
         }
+        task.log_messages.clear()?;
 
-        Ok(())
+        // Speak queued text in order
+        for i in 1..=task.speech_queue.len()? {
+            let text: String = task.speech_queue.get(i)?;
+            if let Err(e) = services.tts().speak(&text, false) {
+                eprintln!("[Lua] app.speak failed: {}", e);
+            }
+        }
+        task.speech_queue.clear()?;
+
+        result?;
+        Ok(task.thread.status() != ThreadStatus::Resumable)
+    }
+
+    /// Log a script error and store it for UI feedback.
+    fn report_error(action_name: &str, error: &LuaError, store: &mut Store) {
+        eprintln!("Lua script error for action '{}': {}", action_name, error);
+        store.set(
+            format!("errors.action.{}", action_name),
+            format!("Script error: {}", error),
+        );
+    }
+}
+
+/// Convert an argument of an `app.dec` or `app.currency` function to a store value.
+fn decimal_arg(value: LuaValue) -> mlua::Result<Value> {
+    match value {
+        // Integers convert exactly, however large
+        LuaValue::Integer(i) => Ok(Value::Decimal(i.into())),
+        LuaValue::Number(n) => Ok(Value::Number(n)),
+        LuaValue::String(s) => Ok(Value::String(s.to_str()?.to_string())),
+        other => Err(mlua::Error::RuntimeError(format!(
+            "expected a number or decimal string, got {}",
+            other.type_name()
+        ))),
     }
 }
 
+/// Read a `canvas.*` color argument: a number, a `#rrggbb` string or nil for black.
+fn draw_color(value: LuaValue) -> mlua::Result<u32> {
+    match value {
+        LuaValue::Nil => Ok(0x000000),
+        LuaValue::Integer(color) => Ok(color as u32),
+        LuaValue::Number(color) => Ok(color as u32),
+        LuaValue::String(color) => {
+            let color = color.to_str()?;
+            parse_draw_color(&color).ok_or_else(|| mlua::Error::RuntimeError(format!("'{}' is not a color", &*color)))
+        }
+        other => Err(mlua::Error::RuntimeError(format!("{} is not a color", other.type_name()))),
+    }
+}
+
+/// Add a command to the drawing selected with `canvas.begin`.
+fn push_draw_command(lua: &Lua, drawings: &Table, command: DrawCommand) -> mlua::Result<()> {
+    let Some(key) = lua.named_registry_value::<Option<String>>(CANVAS_TARGET)? else {
+        return Err(mlua::Error::RuntimeError("call canvas.begin(key) before drawing".to_string()));
+    };
+    let drawing = match drawings.get::<Option<Table>>(key.as_str())? {
+        Some(drawing) => drawing,
+        None => {
+            // The drawing was flushed by app.yield(); keep adding to it
+            let drawing = lua.create_table()?;
+            drawing.set("restart", false)?;
+            drawing.set("commands", lua.create_table()?)?;
+            drawings.set(key, drawing.clone())?;
+            drawing
+        }
+    };
+    let commands: Table = drawing.get("commands")?;
+    if commands.raw_len() >= MAX_DRAW_COMMANDS {
+        return Err(mlua::Error::RuntimeError(format!("a drawing can't have more than {} commands", MAX_DRAW_COMMANDS)));
+    }
+    commands.raw_push(command.to_string())
+}
+
 impl ActionHandler for LuaActionHandler {
     fn handle(
         &mut self,
         action: &Action,
         store: &mut Store,
-        _services: &Services,
+        services: &Services,
     ) -> Result<bool, ActionError> {
         // Look up the script for this action
         let script_path = match self.get_script(&action.name) {
@@ -261,23 +572,39 @@ impl ActionHandler for LuaActionHandler {
             None => return Ok(false), // Action not handled by Lua
         };
 
-        // Execute the script
-        match self.execute_script(&script_path, action, store) {
-            Ok(()) => Ok(true),
-            Err(e) => {
-                // Log the error
-                eprintln!("Lua script error for action '{}': {}", action.name, e);
+        // Run the script up to its end or its first app.yield()
+        let result = self
+            .start_script(&script_path, action, services)
+            .and_then(|task| Ok((Self::resume(&task, store, services)?, task)));
+        match result {
+            Ok((true, _)) => {}
+            Ok((false, task)) => self.tasks.push(task),
+            // Don't crash - return handled but with error logged
+            Err(e) => Self::report_error(&action.name, &e, store),
+        }
+        Ok(true)
+    }
 
-                // Store error message for UI feedback
-                store.set(
-                    format!("errors.action.{}", action.name),
-                    format!("Script error: {}", e),
-                );
+    fn action_names(&self) -> Vec<String> {
+        self.action_scripts.keys().cloned().collect()
+    }
 
-                // Don't crash - return handled but with error logged
-                Ok(true)
-            }
+    fn poll(&mut self, store: &mut Store, services: &Services) -> bool {
+        if self.tasks.is_empty() {
+            return false;
         }
+        self.tasks.retain(|task| match Self::resume(task, store, services) {
+            Ok(finished) => !finished,
+            Err(e) => {
+                Self::report_error(&task.action, &e, store);
+                false
+            }
+        });
+        true
+    }
+
+    fn is_busy(&self) -> bool {
+        !self.tasks.is_empty()
     }
 }
 
@@ -286,8 +613,116 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_lua_basic_execution() {
-        // This would require a test app config and script
-        // For now, just verify the types compile
+    fn test_yield_resumes_next_poll() {
+        let path = std::env::temp_dir().join(format!("crix_yield_{}.lua", std::process::id()));
+        fs::write(
+            &path,
+            "for i = 1, 3 do\n  app.set('progress', i * 25 + (app.get('bonus') or 0))\n  app.yield()\nend\napp.set('progress', 100)\n",
+        )
+        .unwrap();
+        let mut handler = LuaActionHandler::from_scripts(HashMap::from([("work".to_string(), path.clone())]));
+        let mut store = Store::new();
+        let services = Services::new();
+
+        // Each yield shows the progress so far and waits for the next poll
+        assert!(handler.handle(&Action::new("work"), &mut store, &services).unwrap());
+        assert_eq!(store.get_number("progress"), Some(25.0));
+        assert!(handler.is_busy());
+        store.set("bonus", 1.0);
+        assert!(handler.poll(&mut store, &services));
+        assert_eq!(store.get_number("progress"), Some(51.0));
+        handler.poll(&mut store, &services);
+        handler.poll(&mut store, &services);
+        assert_eq!(store.get_number("progress"), Some(100.0));
+        assert!(!handler.is_busy());
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_canvas_draws_into_store() {
+        let path = std::env::temp_dir().join(format!("crix_canvas_{}.lua", std::process::id()));
+        fs::write(
+            &path,
+            "canvas.begin('chart')\ncanvas.rect(0, 0, 10, 5, '#00ff00')\napp.yield()\n\
+             canvas.line(0, 0, 9, 9, 0xff0000, 2)\ncanvas.text(1, 2, 'Total', nil)\n",
+        )
+        .unwrap();
+        let mut handler = LuaActionHandler::from_scripts(HashMap::from([("draw".to_string(), path.clone())]));
+        let mut store = Store::new();
+        store.set("chart", "rect 1 1 1 1 #000000 1\n");
+        let services = Services::new();
+
+        // begin() replaces the old drawing; commands after a yield add to it
+        handler.handle(&Action::new("draw"), &mut store, &services).unwrap();
+        assert_eq!(store.get_string("chart"), "rect 0 0 10 5 #00ff00 1\n");
+        handler.poll(&mut store, &services);
+        assert_eq!(
+            store.get_string("chart"),
+            "rect 0 0 10 5 #00ff00 1\nline 0 0 9 9 #ff0000 2\ntext 1 2 16 #000000 Total\n"
+        );
+
+        // Drawing without a target is a script error
+        fs::write(&path, "canvas.line(0, 0, 1, 1)\n").unwrap();
+        handler.handle(&Action::new("draw"), &mut store, &services).unwrap();
+        assert!(store.get_string("errors.action.draw").contains("canvas.begin"));
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_alarms_api() {
+        let path = std::env::temp_dir().join(format!("crix_alarms_{}.lua", std::process::id()));
+        fs::write(
+            &path,
+            "app.alarms.set{id='tea', at='+5', message='Tea is ready'}
+             app.alarms.set{id='wake', at='2030-01-02 07:00', repeats='weekdays', sound=false}
+             local list = app.alarms.list()
+             app.set('count', #list)
+app.set('first', list[1].id .. ' ' .. list[1].message)
+             app.set('wake', list[2].at .. ' ' .. list[2].repeats)
+             app.set('cancelled', app.alarms.cancel('tea'))
+app.set('again', app.alarms.cancel('tea'))
+             app.alarms.set{id='bad', at='soon'}
+",
+        )
+        .unwrap();
+        let mut handler = LuaActionHandler::from_scripts(HashMap::from([("remind".to_string(), path.clone())]));
+        let mut store = Store::new();
+        let services = Services::new();
+
+        handler.handle(&Action::new("remind"), &mut store, &services).unwrap();
+        assert_eq!(store.get_number("count"), Some(2.0));
+        assert_eq!(store.get_string("first"), "tea Tea is ready");
+        assert_eq!(store.get_string("wake"), "2030-01-02 07:00 weekdays");
+        assert!(store.get_bool("cancelled") && !store.get_bool("again"));
+        assert_eq!(services.alarms().list().len(), 1);
+        assert!(store.get_string("errors.action.remind").contains("Invalid alarm time"));
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_decimal_api() {
+        let path = std::env::temp_dir().join(format!("crix_decimal_{}.lua", std::process::id()));
+        fs::write(
+            &path,
+            "app.set('sum', app.dec.add(0.1, 0.2))
+             app.set('total', app.dec.round(app.dec.mul(app.get('price'), 3), 2))
+             app.set('share', app.dec.div(100, 3))
+             app.set('price_text', app.currency.format(app.get('price'), 'EUR'))
+             app.dec.div(1, 0)
+",
+        )
+        .unwrap();
+        let mut handler = LuaActionHandler::from_scripts(HashMap::from([("sum".to_string(), path.clone())]));
+        let mut store = Store::new();
+        store.set("price", "19.99".parse::<rust_decimal::Decimal>().unwrap());
+        let services = Services::new();
+
+        handler.handle(&Action::new("sum"), &mut store, &services).unwrap();
+        assert_eq!(store.get_string("sum"), "0.3");
+        assert_eq!(store.get_string("total"), "59.97");
+        assert_eq!(store.get_string("share"), "33.333333333333333333333333333");
+        assert_eq!(store.get_string("price_text"), "€19.99");
+        assert!(store.get_string("errors.action.sum").contains("division by zero"));
+        fs::remove_file(path).ok();
     }
 }
@@ -0,0 +1,455 @@
+//! Built-in actions backed by services.
+//!
+//! Skins can trigger these like any other action (e.g. a button with
+//! `"action": "camera.start"`). They are handled in Rust before any
+//! script handlers are consulted.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::core::{Action, ActionError, ActionHandler, Services, Store, Value};
+use crate::graphics::{Image, ImageHandle};
+use crate::skin::fill_placeholders;
+
+use super::{parse_alarm_time, Alarm, EmailMessage, Repeat, ScanTarget, CURRENCY_PREFIX, LOCATION_PREFIX};
+
+/// Default store key that camera frames are published under.
+pub const CAMERA_FRAME_KEY: &str = "camera.frame";
+
+/// Default store key scan results are written to.
+pub const SCAN_RESULT_KEY: &str = "scan.result";
+
+/// Default action dispatched after each scan.
+const SCAN_ACTION: &str = "on_scan";
+
+/// Default store key spoken by `tts.speak` when no text is given.
+const TTS_TEXT_KEY: &str = "tts.text";
+
+/// Store key holding the spell-check language.
+const SPELLCHECK_LANGUAGE_KEY: &str = "spellcheck.language";
+
+/// Default store key `currency.convert` writes its result to.
+const CURRENCY_RESULT_KEY: &str = "currency.result";
+
+/// Default binding prefix targeted by `video.*` actions.
+const VIDEO_TARGET: &str = "video";
+
+/// Built-in actions handled by `ServiceActionHandler`.
+const BUILTIN_ACTIONS: &[&str] = &[
+    "alarm.set",
+    "alarm.cancel",
+    "camera.start",
+    "camera.stop",
+    "currency.convert",
+    "currency.refresh",
+    "location.refresh",
+    "scan.start",
+    "scan.stop",
+    "scan.file",
+    "share.email",
+    "spellcheck.language",
+    "store.export_json",
+    "store.import_json",
+    "tts.speak",
+    "tts.stop",
+    "update.check",
+    "update.install",
+    "url.open",
+    "video.play",
+    "video.pause",
+    "video.toggle",
+    "video.seek",
+];
+
+/// Action handler for the built-in service actions.
+///
+/// | Action | Payload | Description |
+/// |--------|---------|-------------|
+/// | `alarm.set` | `id`, `at`, `message`, `repeats`, `action` (string), `sound` (bool) | Set or replace a saved alarm |
+/// | `alarm.cancel` | `id` (string) | Remove a saved alarm |
+/// | `camera.start` | `device` (number), `key` (string) | Start streaming camera frames into an image key |
+/// | `camera.stop` | | Stop the camera |
+/// | `currency.convert` | `amount`, `from`, `to`, `key` (string) | Convert an amount at the current rates into a store key (default `currency.result`) |
+/// | `currency.refresh` | `base` (string) | Refresh exchange rates into `currency.rate.*` |
+/// | `location.refresh` | | Look up the location again into `sys.location.*` |
+/// | `scan.start` | `source` (string), `key` (string), `on_scan` (string) | Scan codes from an image key (default `camera.frame`) |
+/// | `scan.stop` | | Stop scanning the stream |
+/// | `scan.file` | `path` (string), `key` (string), `on_scan` (string) | Scan an image file (default path from `scan.path`) |
+/// | `share.email` | `to`, `subject`, `body`, `attachment` (string templates) | Open the mail client with a prefilled message |
+/// | `spellcheck.language` | `language` (string) | Check spelling with another dictionary, e.g. `de_DE` (default from `spellcheck.language`) |
+/// | `store.export_json` | `prefix` (string), `path` (string) | Write store values under a prefix as JSON to a file, or the clipboard if no path |
+/// | `store.import_json` | `prefix` (string), `path` (string) | Read store values under a prefix from a JSON file, or the clipboard if no path |
+/// | `tts.speak` | `text` (string), `key` (string) | Speak text, or the value of a store key (default `tts.text`) |
+/// | `tts.stop` | | Stop speaking |
+/// | `update.check` | | Check the update manifest for a newer version |
+/// | `update.install` | | Download, verify and stage the newest version for the next launch |
+/// | `url.open` | `url` (string template) | Open an http, https or mailto URL in the system browser or mail client |
+/// | `video.play` | `target` (string) | Start or resume a video widget |
+/// | `video.pause` | `target` (string) | Pause a video widget |
+/// | `video.toggle` | `target` (string) | Toggle play/pause on a video widget |
+/// | `video.seek` | `target` (string), `position` (number) | Seek a video widget to a position in seconds |
+#[derive(Debug, Default)]
+pub struct ServiceActionHandler;
+
+impl ServiceActionHandler {
+    /// Create a new built-in action handler.
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn camera_start(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        services.require("camera")?;
+
+        let device = action.get_number("device").unwrap_or(0.0) as u32;
+        let key = action.get_str("key").unwrap_or(CAMERA_FRAME_KEY).to_string();
+
+        // Start with a blank frame so bound widgets have something to draw
+        let blank = Image::from_rgba(vec![0, 0, 0, 255], 1, 1).expect("1x1 RGBA is valid");
+        let handle = ImageHandle::new(blank);
+
+        match services.camera().start(device, handle.clone()) {
+            Ok(()) => {
+                store.set_image(key, handle);
+                store.set("camera.active", true);
+                store.remove("camera.error");
+                Ok(())
+            }
+            Err(e) => {
+                store.set("camera.active", false);
+                store.set("camera.error", e.to_string());
+                Err(ActionError::Failed(e.to_string()))
+            }
+        }
+    }
+
+    /// Set or cancel an alarm. Payload fields fall back to `alarm.<field>`
+    /// store keys, and the message may contain `{{key}}` placeholders filled
+    /// from the store. Failures are written to `alarm.error`.
+    fn alarm(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        let field = |store: &Store, name: &str| match action.payload.get(name) {
+            Some(value) => value.to_string_value(),
+            None => store.get_string(&format!("alarm.{}", name)),
+        };
+        let id = field(store, "id");
+
+        let result = if action.name == "alarm.set" {
+            let sound = action.payload.get("sound").or(store.get("alarm.sound")).and_then(Value::as_bool);
+            let alarm_action = field(store, "action");
+            parse_alarm_time(&field(store, "at"), Local::now().naive_local())
+                .and_then(|at| {
+                    let mut alarm = Alarm::new(id, at)
+                        .with_message(fill_placeholders(&field(store, "message"), store))
+                        .with_repeats(field(store, "repeats").parse::<Repeat>()?)
+                        .with_sound(sound.unwrap_or(true));
+                    if !alarm_action.is_empty() {
+                        alarm = alarm.with_action(alarm_action);
+                    }
+                    services.alarms().set(alarm)
+                })
+                .map_err(|e| e.to_string())
+        } else {
+            match services.alarms().cancel(&id) {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(format!("No alarm '{}'", id)),
+                Err(e) => Err(e.to_string()),
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                store.remove("alarm.error");
+                Ok(())
+            }
+            Err(e) => {
+                store.set("alarm.error", e.clone());
+                Err(ActionError::Failed(e))
+            }
+        }
+    }
+
+    /// Convert an amount between currencies. Payload fields fall back to
+    /// `currency.<field>` store keys. The result goes to `key` as a decimal
+    /// and to `<key>.text` formatted in the target currency; failures are
+    /// written to `currency.error`.
+    fn currency_convert(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        let field = |name: &str| match action.payload.get(name) {
+            Some(value) => value.clone(),
+            None => store.get(&format!("{}.{}", CURRENCY_PREFIX, name)).cloned().unwrap_or(Value::Null),
+        };
+        let (amount, from, to) = (field("amount"), field("from").to_string_value(), field("to").to_string_value());
+        let key = action.get_str("key").unwrap_or(CURRENCY_RESULT_KEY).to_string();
+
+        let currency = services.currency();
+        let result = currency.convert(&amount, &from, &to).and_then(|converted| {
+            let text = currency.format(&Value::Decimal(converted), &to)?;
+            Ok((converted, text))
+        });
+        match result {
+            Ok((converted, text)) => {
+                store.set(key.clone(), converted);
+                store.set(format!("{}.text", key), text);
+                store.remove(&format!("{}.error", CURRENCY_PREFIX));
+                Ok(())
+            }
+            Err(e) => {
+                store.set(format!("{}.error", CURRENCY_PREFIX), e.to_string());
+                Err(ActionError::Failed(e.to_string()))
+            }
+        }
+    }
+
+    fn scan_target(action: &Action) -> ScanTarget {
+        ScanTarget {
+            key: action.get_str("key").unwrap_or(SCAN_RESULT_KEY).to_string(),
+            on_scan: action.get_str("on_scan").unwrap_or(SCAN_ACTION).to_string(),
+        }
+    }
+
+    fn scan_start(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        let source = action.get_str("source").unwrap_or(CAMERA_FRAME_KEY);
+        let handle = store
+            .get_image(source)
+            .cloned()
+            .ok_or_else(|| ActionError::Failed(format!("no image published under '{}'", source)))?;
+
+        services
+            .scan()
+            .start_stream(handle, Self::scan_target(action))
+            .map_err(|e| ActionError::Failed(e.to_string()))?;
+        store.set("scan.active", true);
+        Ok(())
+    }
+
+    fn scan_file(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        let path = match action.get_str("path") {
+            Some(path) => path.to_string(),
+            None => store.get_string("scan.path"),
+        };
+        if path.is_empty() {
+            return Err(ActionError::Failed("scan.file requires a 'path'".to_string()));
+        }
+
+        let found = services
+            .scan()
+            .scan_file(Path::new(&path), Self::scan_target(action))
+            .map_err(|e| ActionError::Failed(e.to_string()))?;
+        if found.is_none() {
+            store.set("scan.error", "No code found");
+        }
+        Ok(())
+    }
+
+    /// Payload fields fall back to `share.<field>` store keys; both may
+    /// contain `{{key}}` placeholders filled from the store.
+    fn share_email(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        services.require("share")?;
+
+        let field = |name: &str| {
+            let template = match action.get_str(name) {
+                Some(value) => value.to_string(),
+                None => store.get_string(&format!("share.{}", name)),
+            };
+            fill_placeholders(&template, store)
+        };
+        let attachment = field("attachment");
+        let message = EmailMessage {
+            to: field("to"),
+            subject: field("subject"),
+            body: field("body"),
+            attachment: (!attachment.is_empty()).then(|| PathBuf::from(attachment)),
+        };
+
+        let share = services.share();
+        share.email(&message).map_err(|e| ActionError::Failed(e.to_string()))?;
+        // Tell the skin when the mail client only got the attachment's path
+        let status = if message.attachment.is_some() && !share.attaches_files() {
+            "attachment_in_body"
+        } else {
+            "sent"
+        };
+        store.set("share.status", status);
+        Ok(())
+    }
+
+    /// The URL may contain `{{key}}` placeholders filled from the store.
+    fn url_open(action: &Action, store: &Store, services: &Services) -> Result<(), ActionError> {
+        services.require("browser")?;
+
+        let url = fill_placeholders(action.get_str("url").unwrap_or_default(), store);
+        services.url().open(&url).map_err(|e| ActionError::Failed(e.to_string()))
+    }
+
+    /// Export or import store values as JSON. Payload fields fall back to
+    /// `store.<field>` store keys; the outcome is written to `store.status`
+    /// (`exported`, `imported` or `error`, with `store.error`).
+    fn store_json(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        let field = |store: &Store, name: &str| match action.get_str(name) {
+            Some(value) => value.to_string(),
+            None => store.get_string(&format!("store.{}", name)),
+        };
+        let prefix = field(store, "prefix");
+        let path = field(store, "path");
+
+        let result = if action.name == "store.export_json" {
+            let json = store.to_json(&prefix);
+            if path.is_empty() {
+                services.clipboard().set_text(&json).map_err(|e| e.to_string())
+            } else {
+                std::fs::write(&path, json).map_err(|e| format!("{}: {}", path, e))
+            }
+            .map(|()| "exported")
+        } else {
+            let json = if path.is_empty() {
+                services.clipboard().get_text().map_err(|e| e.to_string())
+            } else {
+                std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))
+            };
+            json.and_then(|json| {
+                store
+                    .from_json(&json, &prefix)
+                    .map_err(|e| format!("Invalid JSON: {}", e))
+            })
+            .map(|count| {
+                store.set("store.count", count as f64);
+                "imported"
+            })
+        };
+
+        match result {
+            Ok(status) => {
+                store.set("store.status", status);
+                store.remove("store.error");
+                Ok(())
+            }
+            Err(e) => {
+                store.set("store.status", "error");
+                store.set("store.error", e.clone());
+                Err(ActionError::Failed(e))
+            }
+        }
+    }
+
+    fn tts_speak(action: &Action, store: &Store, services: &Services) -> Result<(), ActionError> {
+        services.require("tts")?;
+
+        let text = match action.get_str("text") {
+            Some(text) => text.to_string(),
+            None => store.get_string(action.get_str("key").unwrap_or(TTS_TEXT_KEY)),
+        };
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        services
+            .tts()
+            .speak(&text, true)
+            .map_err(|e| ActionError::Failed(e.to_string()))
+    }
+
+    fn spellcheck_language(action: &Action, store: &mut Store, services: &Services) -> Result<(), ActionError> {
+        services.require("spellcheck")?;
+
+        let language = match action.get_str("language") {
+            Some(language) => language.to_string(),
+            None => store.get_string(SPELLCHECK_LANGUAGE_KEY),
+        };
+        if language.is_empty() {
+            return Err(ActionError::Failed("spellcheck.language requires a 'language'".to_string()));
+        }
+
+        match services.spell().set_language(&language) {
+            Ok(()) => {
+                store.set(SPELLCHECK_LANGUAGE_KEY, language);
+                store.remove("spellcheck.error");
+                Ok(())
+            }
+            Err(e) => {
+                store.set("spellcheck.error", e.to_string());
+                Err(ActionError::Failed(e.to_string()))
+            }
+        }
+    }
+
+    /// Video actions only write to the store; the runtime applies them to
+    /// the widget bound to `target` on the next sync.
+    fn video(action: &Action, store: &mut Store) -> Result<(), ActionError> {
+        let target = action.get_str("target").unwrap_or(VIDEO_TARGET);
+        let playing_key = format!("{}.playing", target);
+
+        match action.name.as_str() {
+            "video.play" => store.set(playing_key, true),
+            "video.pause" => store.set(playing_key, false),
+            "video.toggle" => {
+                let playing = store.get_bool(&playing_key);
+                store.set(playing_key, !playing);
+            }
+            "video.seek" => {
+                let position = action
+                    .get_number("position")
+                    .ok_or_else(|| ActionError::Failed("video.seek requires a 'position'".to_string()))?;
+                store.set(format!("{}.seek", target), position);
+            }
+            _ => unreachable!("video action names are matched by the caller"),
+        }
+        Ok(())
+    }
+}
+
+impl ActionHandler for ServiceActionHandler {
+    fn handle(
+        &mut self,
+        action: &Action,
+        store: &mut Store,
+        services: &Services,
+    ) -> Result<bool, ActionError> {
+        match action.name.as_str() {
+            "alarm.set" | "alarm.cancel" => Self::alarm(action, store, services)?,
+            "camera.start" => Self::camera_start(action, store, services)?,
+            "camera.stop" => {
+                services.camera().stop();
+                store.set("camera.active", false);
+            }
+            "currency.convert" => Self::currency_convert(action, store, services)?,
+            "currency.refresh" => {
+                services.require("currency")?;
+                services.currency().refresh(action.get_str("base"));
+                store.set(format!("{}.status", CURRENCY_PREFIX), "pending");
+            }
+            "location.refresh" => {
+                services.require("location")?;
+                services.geo().locate();
+                store.set(format!("{}.status", LOCATION_PREFIX), "pending");
+            }
+            "scan.start" => Self::scan_start(action, store, services)?,
+            "scan.stop" => {
+                services.scan().stop_stream();
+                store.set("scan.active", false);
+            }
+            "scan.file" => Self::scan_file(action, store, services)?,
+            "share.email" => Self::share_email(action, store, services)?,
+            "spellcheck.language" => Self::spellcheck_language(action, store, services)?,
+            "store.export_json" | "store.import_json" => Self::store_json(action, store, services)?,
+            "tts.speak" => Self::tts_speak(action, store, services)?,
+            "tts.stop" => services.tts().stop(),
+            "update.check" => {
+                services.require("update")?;
+                services.update().check();
+            }
+            "update.install" => {
+                services.require("update")?;
+                services.update().install();
+            }
+            "url.open" => Self::url_open(action, store, services)?,
+            "video.play" | "video.pause" | "video.toggle" | "video.seek" => {
+                Self::video(action, store)?
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn action_names(&self) -> Vec<String> {
+        BUILTIN_ACTIONS.iter().map(|name| name.to_string()).collect()
+    }
+}
@@ -0,0 +1,401 @@
+//! Alarms and reminders.
+//!
+//! An alarm goes off at a local wall-clock time, once or again every day,
+//! every weekday or every week. Alarms are kept in an `alarms.json` file in
+//! the app's data directory, so they survive restarts; one that came due
+//! while the app was closed goes off at the next launch, and a repeating
+//! one then moves on to its next time after now.
+//!
+//! Times are given as `HH:MM` (the next time the clock shows it),
+//! `YYYY-MM-DD HH:MM`, or `+N` for N minutes from now.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Format alarm times are shown and listed in.
+pub const ALARM_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Format alarm times are saved in.
+const SAVED_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Errors reported by the alarm service.
+#[derive(Debug)]
+pub enum AlarmError {
+    /// Reading or writing the alarms file failed.
+    Io(std::io::Error),
+    /// The alarms file is not valid JSON.
+    Json(serde_json::Error),
+    /// An alarm has no id.
+    MissingId,
+    /// A time isn't `HH:MM`, `YYYY-MM-DD HH:MM` or `+N`.
+    InvalidTime(String),
+    /// A repeat isn't `once`, `daily`, `weekdays` or `weekly`.
+    InvalidRepeat(String),
+}
+
+impl std::fmt::Display for AlarmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlarmError::Io(e) => write!(f, "Alarm storage IO error: {}", e),
+            AlarmError::Json(e) => write!(f, "Invalid alarms file: {}", e),
+            AlarmError::MissingId => write!(f, "Alarm needs an id"),
+            AlarmError::InvalidTime(text) => write!(f, "Invalid alarm time: '{}'", text),
+            AlarmError::InvalidRepeat(text) => write!(f, "Invalid alarm repeat: '{}'", text),
+        }
+    }
+}
+
+impl std::error::Error for AlarmError {}
+
+impl From<std::io::Error> for AlarmError {
+    fn from(e: std::io::Error) -> Self {
+        AlarmError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for AlarmError {
+    fn from(e: serde_json::Error) -> Self {
+        AlarmError::Json(e)
+    }
+}
+
+/// How often an alarm goes off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Repeat {
+    /// Once, after which the alarm is removed.
+    #[default]
+    Once,
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+    Weekly,
+}
+
+impl Repeat {
+    /// Get the name used in payloads and listings.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Repeat::Once => "once",
+            Repeat::Daily => "daily",
+            Repeat::Weekdays => "weekdays",
+            Repeat::Weekly => "weekly",
+        }
+    }
+
+    /// Check whether the alarm may go off on `date`.
+    fn allows(self, date: NaiveDate) -> bool {
+        self != Repeat::Weekdays || !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// Get the next time after `at` a repeating alarm goes off.
+    fn after(self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        let step = match self {
+            Repeat::Once => return None,
+            Repeat::Daily | Repeat::Weekdays => TimeDelta::days(1),
+            Repeat::Weekly => TimeDelta::weeks(1),
+        };
+        let mut next = at + step;
+        while !self.allows(next.date()) {
+            next += step;
+        }
+        Some(next)
+    }
+}
+
+impl FromStr for Repeat {
+    type Err = AlarmError;
+
+    fn from_str(text: &str) -> Result<Self, AlarmError> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "" | "once" => Ok(Repeat::Once),
+            "daily" => Ok(Repeat::Daily),
+            "weekdays" => Ok(Repeat::Weekdays),
+            "weekly" => Ok(Repeat::Weekly),
+            _ => Err(AlarmError::InvalidRepeat(text.to_string())),
+        }
+    }
+}
+
+/// One alarm.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alarm {
+    /// Name the alarm is replaced and cancelled by.
+    pub id: String,
+    /// Next time the alarm goes off.
+    #[serde(serialize_with = "save_time", deserialize_with = "load_time")]
+    pub at: NaiveDateTime,
+    /// Text of the notification.
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub repeats: Repeat,
+    /// Play the alert sound with the notification.
+    #[serde(default = "default_sound")]
+    pub sound: bool,
+    /// Action run when the alarm goes off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+}
+
+fn default_sound() -> bool {
+    true
+}
+
+fn save_time<S: Serializer>(at: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&at.format(SAVED_TIME_FORMAT).to_string())
+}
+
+fn load_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    NaiveDateTime::parse_from_str(&text, SAVED_TIME_FORMAT).map_err(serde::de::Error::custom)
+}
+
+impl Alarm {
+    /// Create a one-off alarm with sound and without a message.
+    pub fn new(id: impl Into<String>, at: NaiveDateTime) -> Self {
+        Self {
+            id: id.into(),
+            at,
+            message: String::new(),
+            repeats: Repeat::Once,
+            sound: true,
+            action: None,
+        }
+    }
+
+    /// Set the text of the notification.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Set how often the alarm goes off.
+    pub fn with_repeats(mut self, repeats: Repeat) -> Self {
+        self.repeats = repeats;
+        self
+    }
+
+    /// Set whether the alert sound plays.
+    pub fn with_sound(mut self, sound: bool) -> Self {
+        self.sound = sound;
+        self
+    }
+
+    /// Set the action run when the alarm goes off.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+}
+
+/// Parse an alarm time relative to `now`: `HH:MM` is its next occurrence,
+/// today or tomorrow, and `+N` is N minutes from now.
+pub fn parse_alarm_time(text: &str, now: NaiveDateTime) -> Result<NaiveDateTime, AlarmError> {
+    let text = text.trim();
+    let invalid = || AlarmError::InvalidTime(text.to_string());
+
+    if let Some(minutes) = text.strip_prefix('+') {
+        let minutes: f64 = minutes.trim().parse().map_err(|_| invalid())?;
+        if !minutes.is_finite() || minutes <= 0.0 {
+            return Err(invalid());
+        }
+        let seconds = TimeDelta::try_seconds((minutes * 60.0).round() as i64).ok_or_else(invalid)?;
+        return now.checked_add_signed(seconds).ok_or_else(invalid);
+    }
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(text, format) {
+            return Ok(at);
+        }
+    }
+    let time = NaiveTime::parse_from_str(text, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M:%S"))
+        .map_err(|_| invalid())?;
+    let today = now.date().and_time(time);
+    Ok(if today > now { today } else { today + TimeDelta::days(1) })
+}
+
+/// Alarms and the file they are saved in.
+#[derive(Debug, Default)]
+struct AlarmState {
+    path: Option<PathBuf>,
+    alarms: BTreeMap<String, Alarm>,
+    /// Bumped on every change, so listings know when to refresh.
+    revision: u64,
+}
+
+impl AlarmState {
+    /// Note a change and save the alarms.
+    fn changed(&mut self) -> Result<(), AlarmError> {
+        self.revision += 1;
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let alarms: Vec<&Alarm> = self.alarms.values().collect();
+        fs::write(path, serde_json::to_string_pretty(&alarms)?)?;
+        Ok(())
+    }
+}
+
+/// Stores alarms and reports those that are due.
+///
+/// Clones share the same alarms, so scripts can hold on to one. Without a
+/// file (`AlarmService::new`), alarms only live for the session.
+#[derive(Debug, Clone, Default)]
+pub struct AlarmService {
+    state: Arc<Mutex<AlarmState>>,
+}
+
+impl AlarmService {
+    /// Create an in-memory alarm store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an alarm store backed by `<dir>/alarms.json`, loading any
+    /// alarms already saved there.
+    pub fn open(dir: &Path) -> Result<Self, AlarmError> {
+        let path = dir.join("alarms.json");
+        let alarms: Vec<Alarm> = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        let state = AlarmState {
+            path: Some(path),
+            alarms: alarms.into_iter().map(|alarm| (alarm.id.clone(), alarm)).collect(),
+            revision: 0,
+        };
+        Ok(Self { state: Arc::new(Mutex::new(state)) })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, AlarmState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Add an alarm, replacing any with the same id. Weekday alarms set for
+    /// a weekend move on to Monday.
+    pub fn set(&self, mut alarm: Alarm) -> Result<(), AlarmError> {
+        if alarm.id.trim().is_empty() {
+            return Err(AlarmError::MissingId);
+        }
+        while !alarm.repeats.allows(alarm.at.date()) {
+            alarm.at += TimeDelta::days(1);
+        }
+        let mut state = self.lock();
+        state.alarms.insert(alarm.id.clone(), alarm);
+        state.changed()
+    }
+
+    /// Remove alarm `id`. Returns false if there is no such alarm.
+    pub fn cancel(&self, id: &str) -> Result<bool, AlarmError> {
+        let mut state = self.lock();
+        if state.alarms.remove(id).is_none() {
+            return Ok(false);
+        }
+        state.changed()?;
+        Ok(true)
+    }
+
+    /// Get the alarms, soonest first.
+    pub fn list(&self) -> Vec<Alarm> {
+        let mut alarms: Vec<Alarm> = self.lock().alarms.values().cloned().collect();
+        alarms.sort_by(|a, b| a.at.cmp(&b.at).then_with(|| a.id.cmp(&b.id)));
+        alarms
+    }
+
+    /// Get the number of changes made so far.
+    pub fn revision(&self) -> u64 {
+        self.lock().revision
+    }
+
+    /// Take the alarms due at `now`, soonest first. One-off alarms are
+    /// removed and repeating ones move on to their next time after `now`,
+    /// so an alarm missed several times goes off once.
+    pub fn due(&self, now: NaiveDateTime) -> Vec<Alarm> {
+        let mut state = self.lock();
+        let mut due: Vec<Alarm> = state.alarms.values().filter(|alarm| alarm.at <= now).cloned().collect();
+        if due.is_empty() {
+            return due;
+        }
+        for alarm in &due {
+            let mut next = alarm.repeats.after(alarm.at);
+            while let Some(at) = next.filter(|at| *at <= now) {
+                next = alarm.repeats.after(at);
+            }
+            match next {
+                Some(at) => state.alarms.get_mut(&alarm.id).expect("due alarms are stored").at = at,
+                None => {
+                    state.alarms.remove(&alarm.id);
+                }
+            }
+        }
+        if let Err(e) = state.changed() {
+            eprintln!("Failed to save alarms: {}", e);
+        }
+        due.sort_by(|a, b| a.at.cmp(&b.at).then_with(|| a.id.cmp(&b.id)));
+        due
+    }
+
+    /// Get the time from `now` until the next alarm, if any are set.
+    pub fn time_until_next(&self, now: NaiveDateTime) -> Option<Duration> {
+        let next = self.lock().alarms.values().map(|alarm| alarm.at).min()?;
+        Some((next - now).to_std().unwrap_or(Duration::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_alarms_go_off_and_persist() {
+        // 2026-03-06 is a Friday
+        let now = at("2026-03-06 08:00");
+        assert_eq!(parse_alarm_time("07:30", now).unwrap(), at("2026-03-07 07:30"));
+        assert_eq!(parse_alarm_time("9:15", now).unwrap(), at("2026-03-06 09:15"));
+        assert_eq!(parse_alarm_time("+90", now).unwrap(), at("2026-03-06 09:30"));
+        assert_eq!(parse_alarm_time("2026-12-24 18:00", now).unwrap(), at("2026-12-24 18:00"));
+        assert!(parse_alarm_time("soon", now).is_err());
+        assert!(parse_alarm_time("+0", now).is_err());
+        assert!("hourly".parse::<Repeat>().is_err());
+
+        let dir = std::env::temp_dir().join(format!("crix-alarms-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let alarms = AlarmService::open(&dir).unwrap();
+        alarms.set(Alarm::new("tea", at("2026-03-06 08:05")).with_message("Tea is ready")).unwrap();
+        // Set for Saturday, so it first goes off on Monday
+        alarms.set(Alarm::new("standup", at("2026-03-07 09:00")).with_repeats(Repeat::Weekdays)).unwrap();
+        assert!(matches!(alarms.set(Alarm::new(" ", now)), Err(AlarmError::MissingId)));
+        assert_eq!(alarms.time_until_next(now), Some(Duration::from_secs(300)));
+
+        // Alarms survive a restart, and go off once however late
+        let alarms = AlarmService::open(&dir).unwrap();
+        let times = |alarms: &AlarmService| alarms.list().iter().map(|a| a.at).collect::<Vec<_>>();
+        assert_eq!(times(&alarms), [at("2026-03-06 08:05"), at("2026-03-09 09:00")]);
+        let due = alarms.due(at("2026-03-11 12:00"));
+        assert_eq!(due.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), ["tea", "standup"]);
+        assert_eq!(due[0].message, "Tea is ready");
+        assert_eq!(times(&alarms), [at("2026-03-12 09:00")]);
+        assert!(alarms.due(at("2026-03-11 12:01")).is_empty());
+
+        assert!(alarms.cancel("standup").unwrap());
+        assert!(!alarms.cancel("standup").unwrap());
+        assert!(AlarmService::open(&dir).unwrap().list().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
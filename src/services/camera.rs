@@ -0,0 +1,197 @@
+//! Camera capture service.
+//!
+//! Streams frames from a system camera into an `ImageHandle` on a background
+//! thread. Native capture is provided by nokhwa and requires the `camera`
+//! cargo feature; without it, `start` reports `CameraError::Unsupported`.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+
+use crate::graphics::ImageHandle;
+
+/// Errors reported by the camera service.
+#[derive(Debug, Clone)]
+pub enum CameraError {
+    /// Crix was built without the `camera` feature.
+    Unsupported,
+    /// A capture session is already running.
+    AlreadyRunning,
+    /// The camera backend failed to open or stream.
+    Backend(String),
+}
+
+impl std::fmt::Display for CameraError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CameraError::Unsupported => write!(f, "Camera support not compiled in (enable the 'camera' feature)"),
+            CameraError::AlreadyRunning => write!(f, "Camera is already running"),
+            CameraError::Backend(msg) => write!(f, "Camera error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CameraError {}
+
+/// A running capture session.
+struct CaptureSession {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+    handle: ImageHandle,
+}
+
+/// Captures camera frames into an image handle.
+#[derive(Default)]
+pub struct CameraService {
+    session: Mutex<Option<CaptureSession>>,
+    /// Last error reported by the capture thread.
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+impl CameraService {
+    /// Create an idle camera service.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start streaming frames from the camera at `device` into `handle`.
+    pub fn start(&self, device: u32, handle: ImageHandle) -> Result<(), CameraError> {
+        let mut session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+        if session.as_ref().is_some_and(|s| !s.thread.is_finished()) {
+            return Err(CameraError::AlreadyRunning);
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        let thread = backend::spawn(device, handle.clone(), stop.clone(), self.last_error.clone())?;
+
+        *session = Some(CaptureSession { stop, thread, handle });
+        Ok(())
+    }
+
+    /// Stop the current capture session, if any.
+    pub fn stop(&self) {
+        let session = self.session.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(session) = session {
+            session.stop.store(true, Ordering::Relaxed);
+            let _ = session.thread.join();
+        }
+    }
+
+    /// Check if a capture session is currently streaming.
+    pub fn is_running(&self) -> bool {
+        self.session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|s| !s.thread.is_finished())
+    }
+
+    /// Get the handle frames are being written to.
+    pub fn handle(&self) -> Option<ImageHandle> {
+        self.session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|s| s.handle.clone())
+    }
+
+    /// Get the last error reported by the capture thread.
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl Drop for CameraService {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl std::fmt::Debug for CameraService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CameraService")
+            .field("running", &self.is_running())
+            .finish()
+    }
+}
+
+#[cfg(feature = "camera")]
+mod backend {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+
+    use image::RgbImage;
+    use nokhwa::Camera;
+    use nokhwa::pixel_format::RgbFormat;
+    use nokhwa::utils::{CameraIndex, RequestedFormat, RequestedFormatType};
+
+    use super::CameraError;
+    use crate::graphics::{Image, ImageHandle};
+
+    pub(super) fn spawn(
+        device: u32,
+        handle: ImageHandle,
+        stop: Arc<AtomicBool>,
+        last_error: Arc<Mutex<Option<String>>>,
+    ) -> Result<JoinHandle<()>, CameraError> {
+        let report = move |msg: String| {
+            eprintln!("Camera error: {}", msg);
+            *last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(msg);
+        };
+
+        thread::Builder::new()
+            .name("crix-camera".to_string())
+            .spawn(move || {
+                // The camera is opened on the capture thread; it isn't Send on every platform
+                let format = RequestedFormat::new::<RgbFormat>(RequestedFormatType::AbsoluteHighestFrameRate);
+                let mut camera = match Camera::new(CameraIndex::Index(device), format) {
+                    Ok(camera) => camera,
+                    Err(e) => return report(e.to_string()),
+                };
+                if let Err(e) = camera.open_stream() {
+                    return report(e.to_string());
+                }
+
+                while !stop.load(Ordering::Relaxed) {
+                    let decoded = camera.frame().and_then(|frame| frame.decode_image::<RgbFormat>());
+                    match decoded {
+                        Ok(frame) => {
+                            let (width, height) = (frame.width(), frame.height());
+                            if let Some(rgb) = RgbImage::from_raw(width, height, frame.into_raw()) {
+                                handle.set(Image::from_rgb(rgb));
+                            }
+                        }
+                        Err(e) => {
+                            report(e.to_string());
+                            break;
+                        }
+                    }
+                }
+
+                let _ = camera.stop_stream();
+            })
+            .map_err(|e| CameraError::Backend(e.to_string()))
+    }
+}
+
+#[cfg(not(feature = "camera"))]
+mod backend {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+    use std::thread::JoinHandle;
+
+    use super::CameraError;
+    use crate::graphics::ImageHandle;
+
+    pub(super) fn spawn(
+        _device: u32,
+        _handle: ImageHandle,
+        _stop: Arc<AtomicBool>,
+        _last_error: Arc<Mutex<Option<String>>>,
+    ) -> Result<JoinHandle<()>, CameraError> {
+        Err(CameraError::Unsupported)
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+
+/// The set of optional capabilities an app has been granted.
+///
+/// Capabilities are declared in app.toml and gate access to services that
+/// touch hardware or the outside world (camera, speech, network, ...).
+///
+/// ```toml
+/// [app]
+/// name = "Check-in Kiosk"
+/// capabilities = ["camera"]
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    granted: HashSet<String>,
+}
+
+impl Capabilities {
+    /// Create an empty capability set (nothing granted).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grant a capability.
+    pub fn grant(&mut self, name: impl Into<String>) {
+        self.granted.insert(name.into());
+    }
+
+    /// Check whether a capability has been granted.
+    pub fn allows(&self, name: &str) -> bool {
+        self.granted.contains(name)
+    }
+
+    /// Iterate over all granted capabilities.
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.granted.iter()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Capabilities {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self {
+            granted: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
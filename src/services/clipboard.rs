@@ -0,0 +1,124 @@
+//! Clipboard service.
+//!
+//! Copies and pastes text through the platform's clipboard tools
+//! (`pbcopy`/`pbpaste` on macOS, PowerShell on Windows, and `wl-copy`,
+//! `xclip` or `xsel` on Linux).
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Errors reported by the clipboard service.
+#[derive(Debug, Clone)]
+pub enum ClipboardError {
+    /// No clipboard tool could be run.
+    Unavailable(String),
+    /// The clipboard tool failed.
+    Failed(String),
+}
+
+impl std::fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardError::Unavailable(msg) => write!(f, "Clipboard unavailable: {}", msg),
+            ClipboardError::Failed(msg) => write!(f, "Clipboard error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
+/// Reads and writes clipboard text.
+#[derive(Debug, Default)]
+pub struct ClipboardService;
+
+impl ClipboardService {
+    /// Create a clipboard service.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Replace the clipboard contents with `text`.
+    pub fn set_text(&self, text: &str) -> Result<(), ClipboardError> {
+        let mut last_error = None;
+        for (program, args) in copy_commands() {
+            let child = Command::new(program)
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) => {
+                    last_error = Some(format!("{}: {}", program, e));
+                    continue;
+                }
+            };
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin
+                    .write_all(text.as_bytes())
+                    .map_err(|e| ClipboardError::Failed(e.to_string()))?;
+            }
+            let status = child.wait().map_err(|e| ClipboardError::Failed(e.to_string()))?;
+            return if status.success() {
+                Ok(())
+            } else {
+                Err(ClipboardError::Failed(format!("{} exited with {}", program, status)))
+            };
+        }
+        Err(ClipboardError::Unavailable(last_error.unwrap_or_default()))
+    }
+
+    /// Get the clipboard contents as text.
+    pub fn get_text(&self) -> Result<String, ClipboardError> {
+        let mut last_error = None;
+        for (program, args) in paste_commands() {
+            match Command::new(program).args(args).stderr(Stdio::null()).output() {
+                Ok(output) if output.status.success() => {
+                    return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+                }
+                Ok(output) => {
+                    return Err(ClipboardError::Failed(format!("{} exited with {}", program, output.status)));
+                }
+                Err(e) => last_error = Some(format!("{}: {}", program, e)),
+            }
+        }
+        Err(ClipboardError::Unavailable(last_error.unwrap_or_default()))
+    }
+}
+
+/// Commands that copy their stdin to the clipboard, in order of preference.
+fn copy_commands() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else if cfg!(target_os = "windows") {
+        vec![("clip", &[])]
+    } else {
+        let mut commands: Vec<(&str, &[&str])> = vec![
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ];
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.insert(0, ("wl-copy", &[]));
+        }
+        commands
+    }
+}
+
+/// Commands that print the clipboard to stdout, in order of preference.
+fn paste_commands() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        vec![("pbpaste", &[])]
+    } else if cfg!(target_os = "windows") {
+        vec![("powershell", &["-NoProfile", "-Command", "Get-Clipboard -Raw"])]
+    } else {
+        let mut commands: Vec<(&str, &[&str])> = vec![
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ];
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.insert(0, ("wl-paste", &["--no-newline"]));
+        }
+        commands
+    }
+}
@@ -0,0 +1,374 @@
+//! Currency formatting and exchange rates.
+//!
+//! Amounts are formatted by ISO 4217 code with the currency's symbol and
+//! usual number of decimals, using the app's separators, without any
+//! network access. Apps granted the "currency" capability can also refresh
+//! exchange rates on a background thread from an open.er-api.com compatible
+//! endpoint. Refreshed rates are published to `currency.rate.<CODE>` store
+//! keys and used to convert amounts, in exact decimal arithmetic. Requests
+//! go through the shared HTTP client and need the `http` cargo feature.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::core::decimal::{self, DecimalError};
+use crate::core::{Store, Value};
+
+use super::{http, FormatService};
+
+/// Default rates endpoint (free, no API key); `{base}` is replaced by the
+/// base currency code.
+const DEFAULT_ENDPOINT: &str = "https://open.er-api.com/v6/latest/{base}";
+
+/// Store key prefix rates are published under.
+pub const CURRENCY_PREFIX: &str = "currency";
+
+/// A currency's display conventions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency {
+    /// ISO 4217 code, e.g. `EUR`.
+    pub code: &'static str,
+    pub symbol: &'static str,
+    /// Decimal places amounts are shown with.
+    pub decimals: u32,
+    /// Whether the symbol goes after the amount, as in `12,50 kr`.
+    pub symbol_after: bool,
+}
+
+const fn currency_entry(code: &'static str, symbol: &'static str, decimals: u32, symbol_after: bool) -> Currency {
+    Currency { code, symbol, decimals, symbol_after }
+}
+
+/// Currencies known for formatting, by code.
+const CURRENCIES: &[Currency] = &[
+    currency_entry("AED", "AED", 2, false),
+    currency_entry("AUD", "A$", 2, false),
+    currency_entry("BHD", "BHD", 3, false),
+    currency_entry("BRL", "R$", 2, false),
+    currency_entry("CAD", "CA$", 2, false),
+    currency_entry("CHF", "CHF", 2, false),
+    currency_entry("CLP", "CLP", 0, false),
+    currency_entry("CNY", "CN¥", 2, false),
+    currency_entry("CZK", "Kč", 2, true),
+    currency_entry("DKK", "kr", 2, true),
+    currency_entry("EUR", "€", 2, false),
+    currency_entry("GBP", "£", 2, false),
+    currency_entry("HKD", "HK$", 2, false),
+    currency_entry("HUF", "Ft", 2, true),
+    currency_entry("IDR", "Rp", 2, false),
+    currency_entry("ILS", "₪", 2, false),
+    currency_entry("INR", "₹", 2, false),
+    currency_entry("ISK", "kr", 0, true),
+    currency_entry("JPY", "¥", 0, false),
+    currency_entry("KRW", "₩", 0, false),
+    currency_entry("KWD", "KWD", 3, false),
+    currency_entry("MXN", "MX$", 2, false),
+    currency_entry("NOK", "kr", 2, true),
+    currency_entry("NZD", "NZ$", 2, false),
+    currency_entry("PHP", "₱", 2, false),
+    currency_entry("PLN", "zł", 2, true),
+    currency_entry("RUB", "₽", 2, true),
+    currency_entry("SAR", "SAR", 2, false),
+    currency_entry("SEK", "kr", 2, true),
+    currency_entry("SGD", "S$", 2, false),
+    currency_entry("THB", "฿", 2, false),
+    currency_entry("TRY", "₺", 2, false),
+    currency_entry("TWD", "NT$", 2, false),
+    currency_entry("USD", "$", 2, false),
+    currency_entry("VND", "₫", 0, true),
+    currency_entry("ZAR", "R", 2, false),
+];
+
+/// Look up a currency by ISO code, in any case.
+pub fn currency(code: &str) -> Option<&'static Currency> {
+    let code = code.trim();
+    CURRENCIES.iter().find(|currency| currency.code.eq_ignore_ascii_case(code))
+}
+
+/// Errors reported by the currency service.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurrencyError {
+    /// The code isn't a known ISO 4217 currency.
+    UnknownCurrency(String),
+    /// There is no exchange rate for the currency yet.
+    NoRate(String),
+    /// The amount isn't a number, or the result doesn't fit.
+    Decimal(DecimalError),
+}
+
+impl std::fmt::Display for CurrencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurrencyError::UnknownCurrency(code) => write!(f, "Unknown currency: '{}'", code),
+            CurrencyError::NoRate(code) => write!(f, "No exchange rate for {}", code),
+            CurrencyError::Decimal(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CurrencyError {}
+
+impl From<DecimalError> for CurrencyError {
+    fn from(e: DecimalError) -> Self {
+        CurrencyError::Decimal(e)
+    }
+}
+
+/// Exchange rates against a base currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rates {
+    /// Code of the currency the rates are against.
+    pub base: String,
+    /// Units of each currency one unit of the base buys, by code.
+    pub rates: BTreeMap<String, Decimal>,
+    /// When the rates were last updated, as reported by the endpoint.
+    pub updated: String,
+}
+
+impl Rates {
+    /// Create rates against `base`, without any other currencies.
+    pub fn new(base: &str) -> Self {
+        let base = base.trim().to_ascii_uppercase();
+        let rates = BTreeMap::from([(base.clone(), Decimal::ONE)]);
+        Self { base, rates, updated: String::new() }
+    }
+
+    /// Add the rate of a currency against the base.
+    pub fn with_rate(mut self, code: &str, rate: Decimal) -> Self {
+        self.rates.insert(code.trim().to_ascii_uppercase(), rate);
+        self
+    }
+
+    /// Get the rate of a currency against the base.
+    pub fn rate(&self, code: &str) -> Result<Decimal, CurrencyError> {
+        let code = code.trim().to_ascii_uppercase();
+        self.rates.get(&code).copied().filter(|rate| !rate.is_zero()).ok_or(CurrencyError::NoRate(code))
+    }
+
+    /// Convert an amount from one currency to another.
+    pub fn convert(&self, amount: &Value, from: &str, to: &str) -> Result<Decimal, CurrencyError> {
+        let in_base = decimal::div(amount, &Value::Decimal(self.rate(from)?))?;
+        Ok(decimal::mul(&Value::Decimal(in_base), &Value::Decimal(self.rate(to)?))?.normalize())
+    }
+
+    /// Write the rates into `currency.*` store keys.
+    pub fn publish(&self, store: &mut Store) {
+        let key = |field: &str| format!("{}.{}", CURRENCY_PREFIX, field);
+        store.set(key("base"), self.base.clone());
+        for (code, rate) in &self.rates {
+            store.set(key(&format!("rate.{}", code)), *rate);
+        }
+        store.set(key("updated"), self.updated.clone());
+        store.set(key("status"), "ok");
+        store.remove(&key("error"));
+    }
+}
+
+/// Response shape of the rates endpoint.
+#[derive(Deserialize)]
+struct RatesResponse {
+    result: String,
+    #[serde(default, rename = "error-type")]
+    error_type: Option<String>,
+    #[serde(default)]
+    base_code: String,
+    #[serde(default)]
+    time_last_update_utc: String,
+    #[serde(default)]
+    rates: BTreeMap<String, f64>,
+}
+
+type RatesResult = Result<Rates, String>;
+
+/// Rates and the refresh in flight, shared with the refresh thread.
+#[derive(Debug, Default)]
+struct CurrencyState {
+    rates: Option<Rates>,
+    refresh: Option<JoinHandle<()>>,
+    result: Option<RatesResult>,
+}
+
+/// Formats amounts of money and converts them between currencies.
+#[derive(Debug, Clone)]
+pub struct CurrencyService {
+    format: FormatService,
+    base: String,
+    endpoint: String,
+    state: Arc<Mutex<CurrencyState>>,
+}
+
+impl Default for CurrencyService {
+    fn default() -> Self {
+        Self {
+            format: FormatService::new(),
+            base: "USD".to_string(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            state: Arc::new(Mutex::new(CurrencyState::default())),
+        }
+    }
+}
+
+impl CurrencyService {
+    /// Create a currency service with US dollars as the base, the default
+    /// separators and no rates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Format amounts with the app's separators.
+    pub fn with_format(mut self, format: FormatService) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Refresh rates against another base currency.
+    pub fn with_base(mut self, base: &str) -> Self {
+        self.base = base.trim().to_ascii_uppercase();
+        self
+    }
+
+    /// Use a different rates endpoint with an open.er-api.com compatible
+    /// response; `{base}` is replaced by the base currency code.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Get the base currency code.
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Format an amount in a currency, rounded halves away from zero to the
+    /// currency's decimals, e.g. `-$1,234.50` or `1.234,50 kr`.
+    pub fn format(&self, amount: &Value, code: &str) -> Result<String, CurrencyError> {
+        let currency = currency(code).ok_or_else(|| CurrencyError::UnknownCurrency(code.trim().to_string()))?;
+        let rounded = decimal::round(amount, currency.decimals)?;
+        let number = self.format.format_decimal(rounded.abs(), true);
+        let sign = if rounded.is_sign_negative() && !rounded.is_zero() { "-" } else { "" };
+        Ok(if currency.symbol_after {
+            format!("{}{} {}", sign, number, currency.symbol)
+        } else if currency.symbol.ends_with(|c: char| c.is_ascii_alphabetic()) {
+            format!("{}{} {}", sign, currency.symbol, number)
+        } else {
+            format!("{}{}{}", sign, currency.symbol, number)
+        })
+    }
+
+    /// Get the current exchange rates, if any have been set or refreshed.
+    pub fn rates(&self) -> Option<Rates> {
+        self.lock().rates.clone()
+    }
+
+    /// Set the exchange rates, e.g. from rates an app keeps itself.
+    pub fn set_rates(&self, rates: Rates) {
+        self.lock().rates = Some(rates);
+    }
+
+    /// Convert an amount from one currency to another at the current rates.
+    pub fn convert(&self, amount: &Value, from: &str, to: &str) -> Result<Decimal, CurrencyError> {
+        match &self.lock().rates {
+            Some(rates) => rates.convert(amount, from, to),
+            None => Err(CurrencyError::NoRate(from.trim().to_ascii_uppercase())),
+        }
+    }
+
+    /// Start refreshing the rates against `base` (default the service's
+    /// base) in the background. Does nothing if a refresh is in flight.
+    pub fn refresh(&self, base: Option<&str>) {
+        let mut state = self.lock();
+        if state.refresh.as_ref().is_some_and(|t| !t.is_finished()) {
+            return;
+        }
+
+        let base = base.map_or_else(|| self.base.clone(), |base| base.trim().to_ascii_uppercase());
+        let url = self.endpoint.replace("{base}", &base);
+        let shared = self.state.clone();
+        let spawned = thread::Builder::new()
+            .name("crix-currency".to_string())
+            .spawn(move || {
+                let result = fetch(&url);
+                let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+                if let Ok(rates) = &result {
+                    state.rates = Some(rates.clone());
+                }
+                state.result = Some(result);
+            });
+
+        match spawned {
+            Ok(thread) => state.refresh = Some(thread),
+            Err(e) => state.result = Some(Err(e.to_string())),
+        }
+    }
+
+    /// Check if a refresh is in flight.
+    pub fn is_pending(&self) -> bool {
+        self.lock().refresh.as_ref().is_some_and(|t| !t.is_finished())
+    }
+
+    /// Take the result of the last finished refresh, if it hasn't been taken yet.
+    pub fn take_result(&self) -> Option<RatesResult> {
+        self.lock().result.take()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CurrencyState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn fetch(url: &str) -> RatesResult {
+    let body = http::get_text(url).map_err(|e| e.to_string())?;
+    parse_rates(&body)
+}
+
+/// Parse a rates response body.
+fn parse_rates(body: &str) -> RatesResult {
+    let response: RatesResponse = serde_json::from_str(body).map_err(|e| e.to_string())?;
+    if response.result != "success" {
+        return Err(response.error_type.unwrap_or_else(|| "rates lookup failed".to_string()));
+    }
+
+    let mut rates = Rates::new(&response.base_code);
+    for (code, rate) in response.rates {
+        if let Ok(rate) = decimal::to_decimal(&Value::Number(rate)) {
+            rates = rates.with_rate(&code, rate);
+        }
+    }
+    rates.updated = response.time_last_update_utc;
+    Ok(rates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_convert() {
+        let currencies = CurrencyService::new();
+        assert_eq!(currencies.format(&Value::Number(-1234.5), "usd").unwrap(), "-$1,234.50");
+        assert_eq!(currencies.format(&"1234.5".into(), "JPY").unwrap(), "¥1,235");
+        assert_eq!(currencies.format(&Value::Number(9.99), "CHF").unwrap(), "CHF 9.99");
+        let unknown = CurrencyError::UnknownCurrency("XXX".to_string());
+        assert_eq!(currencies.format(&Value::Number(1.0), "XXX"), Err(unknown));
+        let european = CurrencyService::new().with_format(FormatService::with_separators(',', '.'));
+        assert_eq!(european.format(&Value::Number(1234.5), "SEK").unwrap(), "1.234,50 kr");
+
+        let no_rate = CurrencyError::NoRate("USD".to_string());
+        assert_eq!(currencies.convert(&Value::Number(10.0), "USD", "EUR"), Err(no_rate));
+        let body = r#"{"result":"success","base_code":"USD","time_last_update_utc":"Sat, 17 Oct 2026 00:00:01 +0000",
+            "rates":{"USD":1,"EUR":0.8,"JPY":150}}"#;
+        currencies.set_rates(parse_rates(body).unwrap());
+        assert_eq!(currencies.convert(&Value::Number(10.0), "usd", "EUR").unwrap().to_string(), "8");
+        assert_eq!(currencies.convert(&"12".into(), "EUR", "JPY").unwrap().to_string(), "2250");
+        assert!(parse_rates(r#"{"result":"error","error-type":"unsupported-code"}"#).is_err());
+
+        let mut store = Store::new();
+        currencies.rates().unwrap().publish(&mut store);
+        assert_eq!(store.get_number("currency.rate.EUR"), Some(0.8));
+        assert_eq!(store.get_string("currency.base"), "USD");
+    }
+}
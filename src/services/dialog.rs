@@ -0,0 +1,77 @@
+//! Native file dialogs.
+//!
+//! Opens the system's dialogs for opening a file, saving one or picking a
+//! folder. Dialogs block until closed, so they are only opened in answer to
+//! a click, and start in the folder of the path already chosen.
+
+use std::path::{Path, PathBuf};
+
+/// Opens native file dialogs.
+#[derive(Debug, Default)]
+pub struct DialogService;
+
+impl DialogService {
+    /// Create a dialog service.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Ask for a file to open, showing only files with one of `extensions`
+    /// (without the dot) unless the list is empty.
+    pub fn open_file(&self, title: &str, extensions: &[String], current: Option<&Path>) -> Option<PathBuf> {
+        with_filter(dialog(title, current), extensions).pick_file()
+    }
+
+    /// Ask for a file to save to, suggesting the current file's name.
+    pub fn save_file(&self, title: &str, extensions: &[String], current: Option<&Path>) -> Option<PathBuf> {
+        let mut dialog = with_filter(dialog(title, current), extensions);
+        if let Some(name) = current.filter(|path| !path.is_dir()).and_then(Path::file_name) {
+            dialog = dialog.set_file_name(name.to_string_lossy());
+        }
+        dialog.save_file()
+    }
+
+    /// Ask for a folder.
+    pub fn pick_folder(&self, title: &str, current: Option<&Path>) -> Option<PathBuf> {
+        dialog(title, current).pick_folder()
+    }
+}
+
+/// Create a dialog starting in the folder of the current path.
+fn dialog(title: &str, current: Option<&Path>) -> rfd::FileDialog {
+    let dialog = rfd::FileDialog::new().set_title(title);
+    match current.and_then(start_directory) {
+        Some(directory) => dialog.set_directory(directory),
+        None => dialog,
+    }
+}
+
+fn with_filter(dialog: rfd::FileDialog, extensions: &[String]) -> rfd::FileDialog {
+    if extensions.is_empty() {
+        return dialog;
+    }
+    let name = extensions.iter().map(|extension| extension.to_uppercase()).collect::<Vec<_>>().join(", ");
+    dialog.add_filter(name, extensions)
+}
+
+/// Get the folder a dialog for `path` starts in: the path itself if it is a
+/// folder, or else the folder it is in, if that exists.
+fn start_directory(path: &Path) -> Option<&Path> {
+    if path.is_dir() {
+        return Some(path);
+    }
+    path.parent().filter(|parent| parent.is_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starts_in_folder_of_current_path() {
+        let dir = std::env::temp_dir();
+        assert_eq!(start_directory(&dir), Some(dir.as_path()));
+        assert_eq!(start_directory(&dir.join("report.pdf")), Some(dir.as_path()));
+        assert_eq!(start_directory(Path::new("/no/such/folder/report.pdf")), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Number formatting service.
+//!
+//! Formats numbers for display with the app's decimal and thousands
+//! separators (set in the `[format]` section of app.toml) and parses
+//! displayed numbers back. Numeric text inputs use it to show a formatted
+//! value when focus leaves them while the store keeps the plain number.
+
+use rust_decimal::Decimal;
+
+/// Formats and parses numbers with the app's separators.
+#[derive(Debug, Clone)]
+pub struct FormatService {
+    decimal_separator: char,
+    thousands_separator: char,
+}
+
+impl Default for FormatService {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            thousands_separator: ',',
+        }
+    }
+}
+
+impl FormatService {
+    /// Create a format service using `.` for decimals and `,` for thousands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a format service with other separators. Separators that
+    /// are the same or are digits keep the defaults.
+    pub fn with_separators(decimal_separator: char, thousands_separator: char) -> Self {
+        if decimal_separator == thousands_separator
+            || decimal_separator.is_ascii_digit()
+            || thousands_separator.is_ascii_digit()
+        {
+            return Self::default();
+        }
+        Self {
+            decimal_separator,
+            thousands_separator,
+        }
+    }
+
+    /// Get the decimal separator.
+    pub fn decimal_separator(&self) -> char {
+        self.decimal_separator
+    }
+
+    /// Get the thousands separator.
+    pub fn thousands_separator(&self) -> char {
+        self.thousands_separator
+    }
+
+    /// Format a number with a fixed number of decimals (or as many as it
+    /// needs), optionally grouping thousands.
+    pub fn format_number(&self, value: f64, decimals: Option<usize>, grouping: bool) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let digits = match decimals {
+            Some(decimals) => format!("{:.*}", decimals, value.abs()),
+            None => value.abs().to_string(),
+        };
+        self.format_digits(&digits, value < 0.0, grouping)
+    }
+
+    /// Format a decimal with the places it keeps, optionally grouping
+    /// thousands. Unlike `format_number`, no digits are lost to floats.
+    pub fn format_decimal(&self, value: Decimal, grouping: bool) -> String {
+        self.format_digits(&value.abs().to_string(), value.is_sign_negative(), grouping)
+    }
+
+    /// Put the app's separators into plain `digits` such as `1234.50`.
+    fn format_digits(&self, digits: &str, negative: bool, grouping: bool) -> String {
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let mut out = String::new();
+        // Rounding can turn a small negative number into zero
+        if negative && digits.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            out.push('-');
+        }
+        for (i, digit) in whole.chars().enumerate() {
+            if grouping && i > 0 && (whole.len() - i) % 3 == 0 {
+                out.push(self.thousands_separator);
+            }
+            out.push(digit);
+        }
+        if !fraction.is_empty() {
+            out.push(self.decimal_separator);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    /// Parse a number typed or formatted with the app's separators.
+    /// Thousands separators and spaces are ignored.
+    pub fn parse_number(&self, text: &str) -> Option<f64> {
+        let plain: String = text
+            .trim()
+            .chars()
+            .filter(|&c| c != self.thousands_separator && c != ' ')
+            .map(|c| if c == self.decimal_separator { '.' } else { c })
+            .collect();
+        plain.parse::<f64>().ok().filter(|value| value.is_finite())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_parse() {
+        let format = FormatService::new();
+        assert_eq!(format.format_number(1234567.891, Some(2), true), "1,234,567.89");
+        assert_eq!(format.format_number(-1234.5, None, true), "-1,234.5");
+        assert_eq!(format.format_number(999.0, Some(0), true), "999");
+        assert_eq!(format.format_number(-0.001, Some(2), false), "0.00");
+        assert_eq!(format.parse_number(" 1,234.50 "), Some(1234.5));
+        assert_eq!(format.parse_number("abc"), None);
+
+        let european = FormatService::with_separators(',', '.');
+        assert_eq!(european.format_number(1234.5, Some(2), true), "1.234,50");
+        assert_eq!(european.parse_number("1.234,50"), Some(1234.5));
+        assert_eq!(european.format_decimal(Decimal::new(-123456789012345678, 2), true), "-1.234.567.890.123.456,78");
+    }
+}
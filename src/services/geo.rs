@@ -0,0 +1,202 @@
+//! IP-based geolocation service.
+//!
+//! Looks up the approximate location of the machine from its public IP
+//! address on a background thread, so weather and clock skins can bind to
+//! `sys.location.*` store keys without any scripting. Lookups go through the
+//! shared HTTP client and need the `http` cargo feature.
+//!
+//! The default endpoint is ipapi.co over HTTPS, so the location can't be
+//! read or altered on the way. Its free tier is rate limited per address.
+
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use serde::Deserialize;
+
+use crate::core::Store;
+
+use super::http;
+
+/// Default lookup endpoint (free, no API key, HTTPS, returns JSON).
+const DEFAULT_ENDPOINT: &str = "https://ipapi.co/json/";
+
+/// Store key prefix location fields are published under.
+pub const LOCATION_PREFIX: &str = "sys.location";
+
+/// An approximate location resolved from the public IP address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub city: String,
+    pub region: String,
+    pub country: String,
+    pub country_code: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: String,
+    pub ip: String,
+}
+
+impl Location {
+    /// Write the location into `sys.location.*` store keys.
+    pub fn publish(&self, store: &mut Store) {
+        let key = |field: &str| format!("{}.{}", LOCATION_PREFIX, field);
+        store.set(key("city"), self.city.clone());
+        store.set(key("region"), self.region.clone());
+        store.set(key("country"), self.country.clone());
+        store.set(key("country_code"), self.country_code.clone());
+        store.set(key("latitude"), self.latitude);
+        store.set(key("longitude"), self.longitude);
+        store.set(key("timezone"), self.timezone.clone());
+        store.set(key("ip"), self.ip.clone());
+        store.set(key("status"), "ok");
+        store.remove(&key("error"));
+    }
+}
+
+/// Response shape of the lookup endpoint.
+#[derive(Deserialize)]
+struct LookupResponse {
+    /// Set on failures, with the cause in `reason`.
+    #[serde(default)]
+    error: bool,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    city: String,
+    #[serde(default)]
+    region: String,
+    #[serde(default)]
+    country_name: String,
+    #[serde(default)]
+    country_code: String,
+    #[serde(default)]
+    latitude: f64,
+    #[serde(default)]
+    longitude: f64,
+    #[serde(default)]
+    timezone: String,
+    #[serde(default)]
+    ip: String,
+}
+
+type LookupResult = Result<Location, String>;
+
+/// Resolves the machine's approximate location.
+pub struct GeoService {
+    endpoint: String,
+    lookup: Mutex<Option<JoinHandle<()>>>,
+    result: Arc<Mutex<Option<LookupResult>>>,
+}
+
+impl Default for GeoService {
+    fn default() -> Self {
+        Self {
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            lookup: Mutex::new(None),
+            result: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl GeoService {
+    /// Create an idle geolocation service using the default endpoint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a different lookup endpoint with an ipapi.co compatible response.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Start a lookup in the background. Does nothing if one is in flight.
+    pub fn locate(&self) {
+        let mut lookup = self.lookup.lock().unwrap_or_else(|e| e.into_inner());
+        if lookup.as_ref().is_some_and(|t| !t.is_finished()) {
+            return;
+        }
+
+        let endpoint = self.endpoint.clone();
+        let result = self.result.clone();
+        let spawned = thread::Builder::new()
+            .name("crix-geo".to_string())
+            .spawn(move || {
+                let location = fetch(&endpoint);
+                *result.lock().unwrap_or_else(|e| e.into_inner()) = Some(location);
+            });
+
+        match spawned {
+            Ok(thread) => *lookup = Some(thread),
+            Err(e) => *self.result.lock().unwrap_or_else(|e| e.into_inner()) = Some(Err(e.to_string())),
+        }
+    }
+
+    /// Check if a lookup is in flight.
+    pub fn is_pending(&self) -> bool {
+        self.lookup
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|t| !t.is_finished())
+    }
+
+    /// Take the result of the last finished lookup, if it hasn't been taken yet.
+    pub fn take_result(&self) -> Option<LookupResult> {
+        self.result.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl std::fmt::Debug for GeoService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GeoService")
+            .field("endpoint", &self.endpoint)
+            .field("pending", &self.is_pending())
+            .finish()
+    }
+}
+
+fn fetch(endpoint: &str) -> LookupResult {
+    let body = http::get_text(endpoint).map_err(|e| e.to_string())?;
+    parse_response(&body)
+}
+
+/// Read a location out of a lookup response body.
+fn parse_response(body: &str) -> LookupResult {
+    let response: LookupResponse = serde_json::from_str(body).map_err(|e| e.to_string())?;
+
+    if response.error {
+        return Err(response.reason.unwrap_or_else(|| "lookup failed".to_string()));
+    }
+
+    Ok(Location {
+        city: response.city,
+        region: response.region,
+        country: response.country_name,
+        country_code: response.country_code,
+        latitude: response.latitude,
+        longitude: response.longitude,
+        timezone: response.timezone,
+        ip: response.ip,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let body = r#"{"ip": "203.0.113.7", "city": "Berlin", "region": "Land Berlin", "country": "DE",
+            "country_name": "Germany", "country_code": "DE", "latitude": 52.52, "longitude": 13.405,
+            "timezone": "Europe/Berlin"}"#;
+        let location = parse_response(body).unwrap();
+        assert_eq!(location.city, "Berlin");
+        assert_eq!(location.country, "Germany");
+        assert_eq!(location.timezone, "Europe/Berlin");
+        assert_eq!(location.latitude, 52.52);
+
+        let limited = r#"{"error": true, "reason": "RateLimited", "message": "Visit ipapi.co"}"#;
+        assert_eq!(parse_response(limited), Err("RateLimited".to_string()));
+    }
+}
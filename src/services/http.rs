@@ -0,0 +1,70 @@
+//! Minimal blocking HTTP client shared by network services.
+//!
+//! Requests are made with ureq and require the `http` cargo feature; without
+//! it, every request reports `HttpError::Unsupported`. Callers are expected to
+//! run requests off the UI thread.
+
+/// Errors reported by HTTP requests.
+#[derive(Debug, Clone)]
+pub enum HttpError {
+    /// Crix was built without the `http` feature.
+    Unsupported,
+    /// The request failed or returned an error status.
+    Request(String),
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpError::Unsupported => write!(f, "Network support not compiled in (enable the 'http' feature)"),
+            HttpError::Request(msg) => write!(f, "HTTP error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Fetch a URL and return the response body as text.
+pub fn get_text(url: &str) -> Result<String, HttpError> {
+    backend::get(url)
+        .and_then(|bytes| String::from_utf8(bytes).map_err(|e| HttpError::Request(e.to_string())))
+}
+
+/// Fetch a URL and return the raw response body.
+pub fn get_bytes(url: &str) -> Result<Vec<u8>, HttpError> {
+    backend::get(url)
+}
+
+#[cfg(feature = "http")]
+mod backend {
+    use std::io::Read;
+    use std::time::Duration;
+
+    use super::HttpError;
+
+    /// Timeout applied to every request.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+    pub(super) fn get(url: &str) -> Result<Vec<u8>, HttpError> {
+        let response = ureq::get(url)
+            .timeout(REQUEST_TIMEOUT)
+            .call()
+            .map_err(|e| HttpError::Request(e.to_string()))?;
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|e| HttpError::Request(e.to_string()))?;
+        Ok(body)
+    }
+}
+
+#[cfg(not(feature = "http"))]
+mod backend {
+    use super::HttpError;
+
+    pub(super) fn get(_url: &str) -> Result<Vec<u8>, HttpError> {
+        Err(HttpError::Unsupported)
+    }
+}
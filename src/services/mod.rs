@@ -0,0 +1,47 @@
+//! Optional runtime services for crix applications.
+//!
+//! Services are owned by `Services` and handed to every `ActionHandler`.
+//! Services that touch hardware or the outside world are gated by
+//! `Capabilities` declared in the app bundle's app.toml.
+
+mod actions;
+mod alarm;
+mod camera;
+mod capabilities;
+mod clipboard;
+mod currency;
+mod dialog;
+mod format;
+mod geo;
+mod http;
+mod notify;
+mod print;
+mod scan;
+mod schedule;
+mod secret;
+mod share;
+mod spell;
+mod tts;
+mod update;
+mod url;
+
+pub use actions::{ServiceActionHandler, CAMERA_FRAME_KEY, SCAN_RESULT_KEY};
+pub use alarm::{parse_alarm_time, Alarm, AlarmError, AlarmService, Repeat, ALARM_TIME_FORMAT};
+pub use camera::{CameraError, CameraService};
+pub use capabilities::Capabilities;
+pub use clipboard::{ClipboardError, ClipboardService};
+pub use currency::{currency, Currency, CurrencyError, CurrencyService, Rates, CURRENCY_PREFIX};
+pub use dialog::DialogService;
+pub use format::FormatService;
+pub use geo::{GeoService, Location, LOCATION_PREFIX};
+pub use http::HttpError;
+pub use notify::{NotifyError, NotifyService};
+pub use print::{PrintError, PrintService};
+pub use scan::{ScanError, ScanEvent, ScanResult, ScanService, ScanTarget};
+pub use schedule::{CatchUp, CronSchedule, ScheduleError, Scheduler, MAX_CATCH_UP_RUNS};
+pub use secret::{SecretError, SecretService};
+pub use share::{EmailMessage, ShareError, ShareService};
+pub use spell::{SpellError, SpellService};
+pub use tts::{TtsError, TtsService};
+pub use update::{apply_staged_update, UpdateConfig, UpdateError, UpdateService, UpdateStatus};
+pub use url::{UrlError, UrlService};
@@ -0,0 +1,111 @@
+//! Desktop notification service.
+//!
+//! Shows a notification with the system's notifier (`notify-send` on Linux,
+//! `osascript` on macOS, a tray balloon from PowerShell on Windows),
+//! optionally with the system's alert sound.
+
+use std::process::Command;
+
+/// Errors reported by the notification service.
+#[derive(Debug, Clone)]
+pub enum NotifyError {
+    /// The system notifier could not be launched.
+    Launch(String),
+}
+
+impl std::fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifyError::Launch(msg) => write!(f, "Failed to show notification: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+/// Shows desktop notifications.
+#[derive(Debug, Default)]
+pub struct NotifyService;
+
+impl NotifyService {
+    /// Create a notification service.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Show a notification with `title` and `body`, playing the alert sound
+    /// if `sound` is set.
+    pub fn notify(&self, title: &str, body: &str, sound: bool) -> Result<(), NotifyError> {
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = Command::new("powershell");
+            command.args(["-NoProfile", "-Command", &windows_script(title, body, sound)]);
+            command
+        } else if cfg!(target_os = "macos") {
+            let mut script = format!("display notification {} with title {}", quote(body), quote(title));
+            if sound {
+                script.push_str(" sound name \"default\"");
+            }
+            let mut command = Command::new("osascript");
+            command.args(["-e", &script]);
+            command
+        } else {
+            let mut command = Command::new("notify-send");
+            if sound {
+                command.arg("--hint=string:sound-name:alarm-clock-elapsed");
+            }
+            command.args(["--app-name=crix", "--", title, body]);
+            command
+        };
+
+        let mut child = command.spawn().map_err(|e| NotifyError::Launch(e.to_string()))?;
+        // Reap the notifier off the UI thread once it exits, so none is left a zombie
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+/// Quote text as an AppleScript string.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Build a PowerShell script showing a tray balloon, since toasts need a
+/// registered app id. The script stays only while the balloon shows, as
+/// the balloon goes with its tray icon.
+fn windows_script(title: &str, body: &str, sound: bool) -> String {
+    let literal = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let mut script = String::from(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; ",
+    );
+    if sound {
+        script.push_str("[System.Media.SystemSounds]::Exclamation.Play(); ");
+    }
+    script.push_str(&format!(
+        "$script:shown = $true; $n.add_BalloonTipClosed({{ $script:shown = $false }}); \
+         $n.add_BalloonTipClicked({{ $script:shown = $false }}); \
+         $n.ShowBalloonTip(10000, {}, {}, 'Info'); $end = (Get-Date).AddSeconds(10); \
+         while ($script:shown -and (Get-Date) -lt $end) {{ \
+         [System.Windows.Forms.Application]::DoEvents(); Start-Sleep -Milliseconds 100 }}; \
+         $n.Dispose()",
+        literal(title),
+        literal(body)
+    ));
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotes_text_for_scripts() {
+        assert_eq!(quote(r#"Say "hi" \o/"#), r#""Say \"hi\" \\o/""#);
+        let script = windows_script("Tea", "It's ready", false);
+        assert!(script.contains("'Tea', 'It''s ready'"));
+        // Leaves as soon as the balloon is dismissed
+        assert!(script.contains("add_BalloonTipClosed({ $script:shown = $false })"));
+        assert!(!script.contains("Start-Sleep -Seconds"));
+    }
+}
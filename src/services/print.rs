@@ -0,0 +1,218 @@
+//! Printing service.
+//!
+//! Lays rendered output out on paginated A4 PDF pages and opens the file in
+//! the system's PDF viewer (`xdg-open` on Linux, `open` on macOS, the file
+//! protocol handler on Windows). The user prints from the viewer with its
+//! own Print command; this service never prints by itself or opens a print
+//! dialog, as there is no portable way to do either.
+//!
+//! PDFs are written to a directory of the app's own, each to a new file, and
+//! ones older than `SPOOL_MAX_AGE` are removed on the next print.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use image::RgbImage;
+
+use crate::export::{PdfDocument, PdfPage};
+
+/// A4 page size in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+/// Page margin in points.
+const PAGE_MARGIN: f32 = 36.0;
+
+/// How long a PDF is kept for the viewer before the next print removes it.
+const SPOOL_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Errors reported by the print service.
+#[derive(Debug)]
+pub enum PrintError {
+    /// Writing the PDF failed.
+    Io(std::io::Error),
+    /// The PDF viewer could not be launched.
+    Viewer(String),
+}
+
+impl std::fmt::Display for PrintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrintError::Io(e) => write!(f, "Print IO error: {}", e),
+            PrintError::Viewer(msg) => write!(f, "Failed to open the PDF viewer: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PrintError {}
+
+impl From<std::io::Error> for PrintError {
+    fn from(e: std::io::Error) -> Self {
+        PrintError::Io(e)
+    }
+}
+
+/// Opens rendered layouts as PDFs for the user to print.
+#[derive(Debug)]
+pub struct PrintService {
+    /// Directory the PDFs are written to.
+    dir: PathBuf,
+}
+
+impl Default for PrintService {
+    fn default() -> Self {
+        Self::with_dir(std::env::temp_dir().join("crix-print"))
+    }
+}
+
+impl PrintService {
+    /// Create a print service writing PDFs to a directory in the system's
+    /// temporary directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a print service writing PDFs to `dir`, such as a directory
+    /// in the app's data directory.
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Paginate a rendered image onto A4 pages.
+    ///
+    /// The image is scaled to the printable width (never enlarged) and split
+    /// across as many pages as its height needs. An empty image gives one
+    /// blank page, as a PDF needs at least one.
+    pub fn paginate(&self, image: &RgbImage, title: &str) -> Result<PdfDocument, PrintError> {
+        let printable_width = PAGE_WIDTH - PAGE_MARGIN * 2.0;
+        let printable_height = PAGE_HEIGHT - PAGE_MARGIN * 2.0;
+        let scale = (printable_width / image.width().max(1) as f32).min(1.0);
+
+        // Height of one page worth of the image, in image pixels
+        let slice_height = ((printable_height / scale) as u32).max(1);
+
+        let mut document = PdfDocument::new(title);
+        let mut top = 0;
+        while top < image.height() && image.width() > 0 {
+            let height = slice_height.min(image.height() - top);
+            let slice = image::imageops::crop_imm(image, 0, top, image.width(), height).to_image();
+
+            let mut page = PdfPage::new(PAGE_WIDTH, PAGE_HEIGHT);
+            page.draw_image(
+                &slice,
+                PAGE_MARGIN,
+                PAGE_MARGIN,
+                slice.width() as f32 * scale,
+                slice.height() as f32 * scale,
+            )?;
+            document.add_page(page);
+            top += height;
+        }
+        if document.page_count() == 0 {
+            document.add_page(PdfPage::new(PAGE_WIDTH, PAGE_HEIGHT));
+        }
+        Ok(document)
+    }
+
+    /// Write a rendered image as a PDF and open it in the viewer for the
+    /// user to print. Returns the path of the PDF.
+    pub fn print_image(&self, image: &RgbImage, title: &str) -> Result<PathBuf, PrintError> {
+        let document = self.paginate(image, title)?;
+        let path = self.write_file(&document)?;
+        open_in_viewer(&path)?;
+        Ok(path)
+    }
+
+    /// Write a document to a new file in the print directory, first removing
+    /// files the viewer has long been done with.
+    fn write_file(&self, document: &PdfDocument) -> Result<PathBuf, PrintError> {
+        fs::create_dir_all(&self.dir)?;
+        self.remove_old_files();
+
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let mut attempt = 0;
+        loop {
+            let path = self.dir.join(format!("print-{}-{}.pdf", stamp, attempt));
+            // Never write through a file or link already there
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(&document.to_bytes())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => attempt += 1,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Remove PDFs older than `SPOOL_MAX_AGE` from the print directory.
+    fn remove_old_files(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let old = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > SPOOL_MAX_AGE));
+            if old && path.extension().is_some_and(|ext| ext == "pdf") {
+                // A viewer may still hold it open; it goes on a later print
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+/// Open a PDF in the system viewer.
+fn open_in_viewer(path: &Path) -> Result<(), PrintError> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+
+    let mut child = command.arg(path).spawn().map_err(|e| PrintError::Viewer(e.to_string()))?;
+    // Some openers stay until the viewer closes; reap them off the UI thread
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate() {
+        let service = PrintService::new();
+        assert_eq!(service.paginate(&RgbImage::new(0, 0), "empty").unwrap().page_count(), 1);
+        assert_eq!(service.paginate(&RgbImage::new(300, 0), "empty").unwrap().page_count(), 1);
+        assert_eq!(service.paginate(&RgbImage::new(300, 100), "short").unwrap().page_count(), 1);
+
+        // Narrower than the printable width, so not scaled: 770 pixels a page
+        assert_eq!(service.paginate(&RgbImage::new(300, 1600), "long").unwrap().page_count(), 3);
+
+        // Each print gets a new file, and day-old ones are cleaned up
+        let dir = std::env::temp_dir().join(format!("crix-print-{}", std::process::id()));
+        let service = PrintService::with_dir(&dir);
+        let document = service.paginate(&RgbImage::new(10, 10), "file").unwrap();
+        let first = service.write_file(&document).unwrap();
+        let second = service.write_file(&document).unwrap();
+        assert_ne!(first, second);
+        let old = fs::File::options().write(true).open(&first).unwrap();
+        old.set_modified(SystemTime::now() - SPOOL_MAX_AGE * 2).unwrap();
+        service.write_file(&document).unwrap();
+        assert!(!first.exists());
+        assert!(second.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
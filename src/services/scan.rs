@@ -0,0 +1,237 @@
+//! QR code and barcode scanning service.
+//!
+//! Decodes codes from image files or continuously from an `ImageHandle`
+//! (typically the camera feed). Results are queued as `ScanEvent`s for the
+//! runtime to publish to the store and turn into `on_scan` actions. Decoding
+//! is provided by rxing and requires the `scan` cargo feature; without it,
+//! scans report `ScanError::Unsupported`.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::graphics::{Image, ImageHandle};
+
+/// How often the stream scanner checks for a new frame.
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Errors reported by the scan service.
+#[derive(Debug, Clone)]
+pub enum ScanError {
+    /// Crix was built without the `scan` feature.
+    Unsupported,
+    /// The image could not be loaded.
+    Image(String),
+    /// A stream scan is already running.
+    AlreadyRunning,
+}
+
+impl std::fmt::Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::Unsupported => write!(f, "Scan support not compiled in (enable the 'scan' feature)"),
+            ScanError::Image(msg) => write!(f, "Scan image error: {}", msg),
+            ScanError::AlreadyRunning => write!(f, "A stream scan is already running"),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// A decoded code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanResult {
+    /// The decoded text.
+    pub text: String,
+    /// The symbology, e.g. "qrcode" or "ean 13".
+    pub format: String,
+}
+
+/// A scan result waiting to be published by the runtime.
+#[derive(Debug, Clone)]
+pub struct ScanEvent {
+    /// The decoded code.
+    pub result: ScanResult,
+    /// Store key to write the decoded text to.
+    pub key: String,
+    /// Action to dispatch after the store is updated.
+    pub on_scan: String,
+}
+
+/// Where scan results are published.
+#[derive(Debug, Clone)]
+pub struct ScanTarget {
+    /// Store key to write the decoded text to.
+    pub key: String,
+    /// Action to dispatch after each scan.
+    pub on_scan: String,
+}
+
+/// A running stream scan.
+struct StreamScan {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// Decodes QR codes and barcodes from images and image streams.
+#[derive(Default)]
+pub struct ScanService {
+    stream: Mutex<Option<StreamScan>>,
+    events: Arc<Mutex<Vec<ScanEvent>>>,
+}
+
+impl ScanService {
+    /// Create an idle scan service.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the first code found in an image.
+    pub fn decode(&self, image: &Image) -> Result<Option<ScanResult>, ScanError> {
+        backend::decode(image)
+    }
+
+    /// Decode an image file and queue the result, if a code was found.
+    pub fn scan_file(&self, path: &Path, target: ScanTarget) -> Result<Option<ScanResult>, ScanError> {
+        let image = Image::from_file(&path.to_string_lossy()).map_err(|e| ScanError::Image(e.to_string()))?;
+        let result = self.decode(&image)?;
+        if let Some(result) = &result {
+            push_event(&self.events, result.clone(), &target);
+        }
+        Ok(result)
+    }
+
+    /// Continuously scan frames written to `handle`, queueing each new code.
+    ///
+    /// The same code is only reported again after a frame without it.
+    pub fn start_stream(&self, handle: ImageHandle, target: ScanTarget) -> Result<(), ScanError> {
+        backend::check_supported()?;
+
+        let mut stream = self.stream.lock().unwrap_or_else(|e| e.into_inner());
+        if stream.as_ref().is_some_and(|s| !s.thread.is_finished()) {
+            return Err(ScanError::AlreadyRunning);
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let events = self.events.clone();
+
+        let thread = thread::Builder::new()
+            .name("crix-scan".to_string())
+            .spawn(move || {
+                let mut seen_version = None;
+                let mut last_text: Option<String> = None;
+
+                while !stop_flag.load(Ordering::Relaxed) {
+                    // Copy a new frame out, so decoding doesn't hold up the camera's `set`
+                    let frame = handle.with_versioned_image(|version, image| {
+                        (seen_version != Some(version)).then(|| (version, image.clone()))
+                    });
+                    if let Some((version, image)) = frame {
+                        seen_version = Some(version);
+                        let decoded = backend::decode(&image).ok().flatten();
+                        match decoded {
+                            Some(result) if last_text.as_deref() != Some(result.text.as_str()) => {
+                                last_text = Some(result.text.clone());
+                                push_event(&events, result, &target);
+                            }
+                            Some(_) => {}
+                            None => last_text = None,
+                        }
+                    }
+                    thread::sleep(STREAM_POLL_INTERVAL);
+                }
+            })
+            .map_err(|e| ScanError::Image(e.to_string()))?;
+
+        *stream = Some(StreamScan { stop, thread });
+        Ok(())
+    }
+
+    /// Stop the current stream scan, if any.
+    pub fn stop_stream(&self) {
+        let stream = self.stream.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(stream) = stream {
+            stream.stop.store(true, Ordering::Relaxed);
+            let _ = stream.thread.join();
+        }
+    }
+
+    /// Check if a stream scan is running.
+    pub fn is_streaming(&self) -> bool {
+        self.stream
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|s| !s.thread.is_finished())
+    }
+
+    /// Take all queued scan events.
+    pub fn take_events(&self) -> Vec<ScanEvent> {
+        std::mem::take(&mut *self.events.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+fn push_event(events: &Mutex<Vec<ScanEvent>>, result: ScanResult, target: &ScanTarget) {
+    events.lock().unwrap_or_else(|e| e.into_inner()).push(ScanEvent {
+        result,
+        key: target.key.clone(),
+        on_scan: target.on_scan.clone(),
+    });
+}
+
+impl Drop for ScanService {
+    fn drop(&mut self) {
+        self.stop_stream();
+    }
+}
+
+impl std::fmt::Debug for ScanService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScanService")
+            .field("streaming", &self.is_streaming())
+            .finish()
+    }
+}
+
+#[cfg(feature = "scan")]
+mod backend {
+    use image::imageops;
+    use rxing::helpers::detect_in_luma;
+
+    use super::{ScanError, ScanResult};
+    use crate::graphics::Image;
+
+    pub(super) fn check_supported() -> Result<(), ScanError> {
+        Ok(())
+    }
+
+    pub(super) fn decode(image: &Image) -> Result<Option<ScanResult>, ScanError> {
+        let luma = imageops::grayscale(image.rgb());
+        let (width, height) = luma.dimensions();
+
+        // rxing reports "nothing found" as an error; treat every failure as no code
+        Ok(detect_in_luma(luma.into_raw(), width, height, None)
+            .ok()
+            .map(|result| ScanResult {
+                text: result.getText().to_string(),
+                format: result.getBarcodeFormat().to_string(),
+            }))
+    }
+}
+
+#[cfg(not(feature = "scan"))]
+mod backend {
+    use super::{ScanError, ScanResult};
+    use crate::graphics::Image;
+
+    pub(super) fn check_supported() -> Result<(), ScanError> {
+        Err(ScanError::Unsupported)
+    }
+
+    pub(super) fn decode(_image: &Image) -> Result<Option<ScanResult>, ScanError> {
+        Err(ScanError::Unsupported)
+    }
+}
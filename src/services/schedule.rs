@@ -0,0 +1,343 @@
+//! Scheduled actions.
+//!
+//! Apps list cron-style schedules in app.toml, each running an action:
+//!
+//! ```toml
+//! [schedule]
+//! catch_up = "once"            # optional: "skip" (default), "once" or "all"
+//! "0 9 * * MON" = "weekly_report"
+//! "*/15 * * * *" = "sync"
+//! ```
+//!
+//! An expression has five fields: minute (0-59), hour (0-23), day of the
+//! month (1-31), month (1-12 or JAN-DEC) and day of the week (0-7 or
+//! SUN-SAT, 0 and 7 both Sunday). Each field is `*`, a value, a range
+//! `a-b`, a step `*/n` or `a-b/n`, or a list of those separated by commas.
+//! As in cron, a day matches if either day field matches when both are
+//! restricted. `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` are
+//! shorthands. Times are local wall-clock times.
+//!
+//! The time the schedule was last checked is saved in the app's data
+//! directory, so runs missed while the app was closed can be caught up on
+//! the next launch according to `catch_up`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use serde::Deserialize;
+
+/// Most missed runs of one schedule caught up with `catch_up = "all"`.
+pub const MAX_CATCH_UP_RUNS: usize = 100;
+
+/// How far ahead the next run of a schedule is looked for; expressions
+/// that never match (such as February 31st) have no next run.
+const SEARCH_YEARS: i32 = 5;
+
+/// Format of the saved last-checked time.
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Errors in a schedule expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// The expression doesn't have five fields.
+    FieldCount(String),
+    /// A field has an invalid or out-of-range value.
+    InvalidField(String),
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::FieldCount(expr) => write!(f, "Schedule '{}' must have five fields", expr),
+            ScheduleError::InvalidField(field) => write!(f, "Invalid schedule field: '{}'", field),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// What happens to runs missed while the app was closed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CatchUp {
+    /// Missed runs are dropped.
+    #[default]
+    Skip,
+    /// Each schedule that missed runs runs once at launch.
+    Once,
+    /// Every missed run is made up at launch, up to `MAX_CATCH_UP_RUNS`.
+    All,
+}
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    /// Bit n is set if value n matches.
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    /// Whether the day fields were restricted rather than `*`.
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field expression or a shorthand like `@daily`.
+    pub fn parse(expr: &str) -> Result<Self, ScheduleError> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(ScheduleError::FieldCount(expr.to_string()));
+        };
+        const MONTHS: &[&str] = &["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+        const WEEKDAYS: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+        // Sunday may be written as 7
+        let weekdays = parse_field(weekday, 0, 7, WEEKDAYS, 0)?;
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[], 0)?,
+            hours: parse_field(hour, 0, 23, &[], 0)? as u32,
+            days: parse_field(day, 1, 31, &[], 0)? as u32,
+            months: parse_field(month, 1, 12, MONTHS, 1)? as u16,
+            weekdays: ((weekdays | weekdays >> 7) & 0x7F) as u8,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        })
+    }
+
+    /// Check whether the schedule runs in the minute of `time`.
+    pub fn matches(&self, time: NaiveDateTime) -> bool {
+        self.minutes & (1 << time.minute()) != 0
+            && self.hours & (1 << time.hour()) != 0
+            && self.months & (1 << time.month()) != 0
+            && self.matches_day(time.date())
+    }
+
+    /// Check the day fields, either of which matches when both are restricted.
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// Get the first minute after `time` the schedule runs in, if any
+    /// within the next few years.
+    pub fn next_after(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = time.with_second(0)?.with_nanosecond(0)?;
+        let limit = start.with_year(start.year() + SEARCH_YEARS).unwrap_or(NaiveDateTime::MAX);
+        let mut t = start + chrono::Duration::minutes(1);
+        // Skip whole months, days and hours that can't match
+        while t <= limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + chrono::Duration::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += chrono::Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// Parse one field into a bit set of the values `min..=max` it matches.
+/// `names` name the values from `first_name` on.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], first_name: u32) -> Result<u64, ScheduleError> {
+    let invalid = || ScheduleError::InvalidField(field.to_string());
+    let value = |s: &str| -> Result<u32, ScheduleError> {
+        let value = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(index) => index as u32 + first_name,
+            None => s.parse().map_err(|_| invalid())?,
+        };
+        if (min..=max).contains(&value) { Ok(value) } else { Err(invalid()) }
+    };
+
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|&step| step > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // A single value with a step runs from it to the end
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// One schedule and the action it runs.
+#[derive(Debug)]
+struct Job {
+    schedule: CronSchedule,
+    action: String,
+    /// Next minute the job runs in.
+    next: Option<NaiveDateTime>,
+}
+
+/// Runs actions on their schedules, remembering when it last checked.
+#[derive(Debug)]
+pub struct Scheduler {
+    jobs: Vec<Job>,
+    catch_up: CatchUp,
+    /// File the last-checked time is saved in, if any.
+    state_file: Option<PathBuf>,
+    /// Minute the schedule was last checked in.
+    checked: Option<NaiveDateTime>,
+}
+
+impl Scheduler {
+    /// Create a scheduler running each action on its schedule from `now`,
+    /// without saving when it last checked.
+    pub fn new(jobs: Vec<(CronSchedule, String)>, catch_up: CatchUp, now: NaiveDateTime) -> Self {
+        let jobs = jobs
+            .into_iter()
+            .map(|(schedule, action)| Job { next: schedule.next_after(now), schedule, action })
+            .collect();
+        Self { jobs, catch_up, state_file: None, checked: None }
+    }
+
+    /// Save the last-checked time in `dir`, and get the actions missed since
+    /// the time saved there by the last run, according to the catch-up
+    /// policy.
+    pub fn with_state_dir(mut self, dir: &Path, now: NaiveDateTime) -> (Self, Vec<String>) {
+        let path = dir.join("schedule_checked");
+        let last = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| NaiveDateTime::parse_from_str(text.trim(), TIME_FORMAT).ok());
+        self.state_file = Some(path);
+
+        let mut missed = Vec::new();
+        if let Some(last) = last.filter(|&last| last < now) {
+            for job in &self.jobs {
+                let runs = match self.catch_up {
+                    CatchUp::Skip => 0,
+                    CatchUp::Once => 1,
+                    CatchUp::All => MAX_CATCH_UP_RUNS,
+                };
+                let mut time = last;
+                for _ in 0..runs {
+                    match job.schedule.next_after(time) {
+                        Some(next) if next <= now => {
+                            missed.push(job.action.clone());
+                            time = next;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+        self.save(now);
+        (self, missed)
+    }
+
+    /// Get the actions due at `now`, in schedule order.
+    pub fn due(&mut self, now: NaiveDateTime) -> Vec<String> {
+        let mut due = Vec::new();
+        for job in &mut self.jobs {
+            if job.next.is_some_and(|next| next <= now) {
+                due.push(job.action.clone());
+                job.next = job.schedule.next_after(now);
+            }
+        }
+        self.save(now);
+        due
+    }
+
+    /// Get the time from `now` until the next job runs.
+    pub fn time_until_next(&self, now: NaiveDateTime) -> Option<Duration> {
+        let next = self.jobs.iter().filter_map(|job| job.next).min()?;
+        Some((next - now).to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// Save the minute of `now` as the last-checked time, once a minute.
+    fn save(&mut self, now: NaiveDateTime) {
+        let minute = now.with_second(0).and_then(|time| time.with_nanosecond(0));
+        if minute == self.checked {
+            return;
+        }
+        self.checked = minute;
+        if let Some(path) = &self.state_file {
+            let written = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| {
+                fs::write(path, now.format(TIME_FORMAT).to_string())
+            });
+            if let Err(e) = written {
+                eprintln!("Failed to save schedule time: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_next_run_and_catch_up() {
+        // 2026-03-02 is a Monday
+        let weekly = CronSchedule::parse("0 9 * * MON").unwrap();
+        assert_eq!(weekly.next_after(at("2026-03-02 08:59")), Some(at("2026-03-02 09:00")));
+        assert_eq!(weekly.next_after(at("2026-03-02 09:00")), Some(at("2026-03-09 09:00")));
+        let steps = CronSchedule::parse("*/20 8-9 1,15 * *").unwrap();
+        assert_eq!(steps.next_after(at("2026-03-01 09:40")), Some(at("2026-03-15 08:00")));
+        assert_eq!(CronSchedule::parse("0 0 1 * SUN,7").unwrap().next_after(at("2026-03-01 00:00")), Some(at("2026-03-08 00:00")));
+        assert_eq!(CronSchedule::parse("0 0 31 FEB *").unwrap().next_after(at("2026-01-01 00:00")), None);
+        for bad in ["* * * *", "60 * * * *", "* * 0 * *", "*/0 * * * *", "5-1 * * * *", "0 9 * * FUNDAY"] {
+            assert!(CronSchedule::parse(bad).is_err(), "{}", bad);
+        }
+
+        let mut scheduler = Scheduler::new(vec![(weekly.clone(), "report".to_string())], CatchUp::Skip, at("2026-03-02 08:00"));
+        assert!(scheduler.due(at("2026-03-02 08:59")).is_empty());
+        assert_eq!(scheduler.due(at("2026-03-02 09:00")), ["report"]);
+        assert!(scheduler.due(at("2026-03-02 09:01")).is_empty());
+        assert_eq!(scheduler.time_until_next(at("2026-03-08 09:00")), Some(Duration::from_secs(24 * 3600)));
+
+        // Three Mondays pass while the app is closed
+        let dir = std::env::temp_dir().join(format!("crix-schedule-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let launch = |catch_up, now| {
+            Scheduler::new(vec![(weekly.clone(), "report".to_string())], catch_up, now).with_state_dir(&dir, now).1
+        };
+        assert!(launch(CatchUp::All, at("2026-03-02 10:00")).is_empty());
+        assert_eq!(launch(CatchUp::All, at("2026-03-23 10:00")).len(), 3);
+        fs::write(dir.join("schedule_checked"), "2026-03-02T10:00:00").unwrap();
+        assert_eq!(launch(CatchUp::Once, at("2026-03-23 10:00")), ["report"]);
+        fs::write(dir.join("schedule_checked"), "2026-03-02T10:00:00").unwrap();
+        assert!(launch(CatchUp::Skip, at("2026-03-23 10:00")).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,214 @@
+//! Secret storage service.
+//!
+//! Stores PBKDF2-HMAC-SHA256 hashes of secrets such as the lock-screen PIN,
+//! with a random salt each, so the secret itself is never written to disk.
+//! Hashes are kept in a `secrets.json` file in the app's data directory,
+//! along with the algorithm and iteration count they were made with.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Name stored with hashes made by `hash_secret`.
+const HASH_ALGORITHM: &str = "pbkdf2-sha256";
+
+/// PBKDF2 iterations for new hashes, slowing down guessing (OWASP's figure
+/// for PBKDF2-HMAC-SHA256). Stored hashes keep the count they were made with.
+const HASH_ITERATIONS: u32 = 600_000;
+
+/// Length of salts and hashes in bytes.
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+
+/// Errors reported by the secret service.
+#[derive(Debug)]
+pub enum SecretError {
+    /// Reading or writing the secrets file failed.
+    Io(std::io::Error),
+    /// The secrets file is not valid JSON.
+    Json(serde_json::Error),
+    /// The system random number generator failed.
+    Random(getrandom::Error),
+}
+
+impl std::fmt::Display for SecretError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretError::Io(e) => write!(f, "Secret storage IO error: {}", e),
+            SecretError::Json(e) => write!(f, "Invalid secrets file: {}", e),
+            SecretError::Random(e) => write!(f, "Failed to generate a salt: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SecretError {}
+
+impl From<std::io::Error> for SecretError {
+    fn from(e: std::io::Error) -> Self {
+        SecretError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SecretError {
+    fn from(e: serde_json::Error) -> Self {
+        SecretError::Json(e)
+    }
+}
+
+/// A stored secret hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SecretHash {
+    /// Key derivation the hash was made with; only `HASH_ALGORITHM` verifies.
+    algorithm: String,
+    /// PBKDF2 iterations the hash was made with.
+    iterations: u32,
+    /// Hex-encoded random salt.
+    salt: String,
+    /// Hex-encoded hash of the secret.
+    hash: String,
+}
+
+/// Stores and verifies hashed secrets.
+///
+/// Without a file (`SecretService::new`), secrets only live for the session.
+#[derive(Debug)]
+pub struct SecretService {
+    path: Option<PathBuf>,
+    secrets: Mutex<HashMap<String, SecretHash>>,
+    /// PBKDF2 iterations for new hashes.
+    iterations: u32,
+}
+
+impl Default for SecretService {
+    fn default() -> Self {
+        Self {
+            path: None,
+            secrets: Mutex::default(),
+            iterations: HASH_ITERATIONS,
+        }
+    }
+}
+
+impl SecretService {
+    /// Create an in-memory secret store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a secret store backed by `<dir>/secrets.json`, loading any
+    /// secrets already saved there.
+    pub fn open(dir: &Path) -> Result<Self, SecretError> {
+        let path = dir.join("secrets.json");
+        let secrets = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            secrets: Mutex::new(secrets),
+            ..Self::default()
+        })
+    }
+
+    /// Check if a secret has been set.
+    pub fn has(&self, name: &str) -> bool {
+        self.secrets.lock().unwrap_or_else(|e| e.into_inner()).contains_key(name)
+    }
+
+    /// Store the hash of `secret` under `name`, replacing any previous one.
+    pub fn set(&self, name: &str, secret: &str) -> Result<(), SecretError> {
+        let salt = new_salt()?;
+        let hash = hash_secret(&salt, secret, self.iterations);
+        let stored = SecretHash {
+            algorithm: HASH_ALGORITHM.to_string(),
+            iterations: self.iterations,
+            salt,
+            hash,
+        };
+        let mut secrets = self.secrets.lock().unwrap_or_else(|e| e.into_inner());
+        secrets.insert(name.to_string(), stored);
+        self.save(&secrets)
+    }
+
+    /// Remove a secret.
+    pub fn remove(&self, name: &str) -> Result<(), SecretError> {
+        let mut secrets = self.secrets.lock().unwrap_or_else(|e| e.into_inner());
+        if secrets.remove(name).is_some() {
+            self.save(&secrets)?;
+        }
+        Ok(())
+    }
+
+    /// Check `secret` against the stored hash. Unknown names, and hashes
+    /// made another way, never verify.
+    pub fn verify(&self, name: &str, secret: &str) -> bool {
+        let secrets = self.secrets.lock().unwrap_or_else(|e| e.into_inner());
+        secrets.get(name).is_some_and(|stored| {
+            if stored.algorithm != HASH_ALGORITHM || stored.iterations == 0 {
+                return false;
+            }
+            let hash = hash_secret(&stored.salt, secret, stored.iterations);
+            // Compare without an early exit so timing doesn't leak the prefix
+            hash.len() == stored.hash.len()
+                && hash.bytes().zip(stored.hash.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+        })
+    }
+
+    fn save(&self, secrets: &HashMap<String, SecretHash>) -> Result<(), SecretError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(secrets)?)?;
+        Ok(())
+    }
+}
+
+/// Derive the hex-encoded PBKDF2-HMAC-SHA256 hash of `secret`.
+fn hash_secret(salt: &str, secret: &str, iterations: u32) -> String {
+    let mut hash = [0u8; HASH_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha256>(secret.as_bytes(), salt.as_bytes(), iterations, &mut hash);
+    to_hex(&hash)
+}
+
+/// Generate a hex-encoded salt from the system random number generator.
+fn new_salt() -> Result<String, SecretError> {
+    let mut salt = [0u8; SALT_LEN];
+    getrandom::getrandom(&mut salt).map_err(SecretError::Random)?;
+    Ok(to_hex(&salt))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_secret() {
+        // Full strength takes seconds in unoptimized builds
+        let secrets = SecretService { iterations: 1000, ..SecretService::new() };
+        assert!(!secrets.verify("pin", "1234"));
+
+        secrets.set("pin", "1234").unwrap();
+        assert!(secrets.verify("pin", "1234"));
+        assert!(!secrets.verify("pin", "4321"));
+        assert!(!secrets.verify("other", "1234"));
+
+        // RFC 7914 PBKDF2-HMAC-SHA256 test vector
+        assert_eq!(
+            hash_secret("salt", "passwd", 1),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+        assert_ne!(new_salt().unwrap(), new_salt().unwrap());
+    }
+}
@@ -0,0 +1,154 @@
+//! Share service.
+//!
+//! Opens the user's default mail client with a prefilled message, using the
+//! platform's standard handoff (`xdg-email` on Linux, `mailto:` URLs on
+//! macOS and Windows).
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Errors reported by the share service.
+#[derive(Debug, Clone)]
+pub enum ShareError {
+    /// The attachment file doesn't exist.
+    AttachmentNotFound(PathBuf),
+    /// The mail client could not be launched.
+    Launch(String),
+}
+
+impl std::fmt::Display for ShareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareError::AttachmentNotFound(path) => write!(f, "Attachment not found: {}", path.display()),
+            ShareError::Launch(msg) => write!(f, "Failed to open mail client: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/// A message to hand to the mail client.
+#[derive(Debug, Clone, Default)]
+pub struct EmailMessage {
+    /// Recipient address (may be empty to let the user choose).
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    /// Optional file to attach. Only supported by `xdg-email` on Linux;
+    /// elsewhere the path is appended to the body instead.
+    pub attachment: Option<PathBuf>,
+}
+
+/// Hands content off to other applications.
+#[derive(Debug, Default)]
+pub struct ShareService;
+
+impl ShareService {
+    /// Create a share service.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether the mail client receives attachments as files. Where it
+    /// doesn't, `email` adds the attachment's path to the body instead.
+    pub fn attaches_files(&self) -> bool {
+        cfg!(target_os = "linux")
+    }
+
+    /// Open the default mail client with a prefilled message.
+    pub fn email(&self, message: &EmailMessage) -> Result<(), ShareError> {
+        if let Some(path) = &message.attachment
+            && !path.exists()
+        {
+            return Err(ShareError::AttachmentNotFound(path.clone()));
+        }
+
+        let mut command = if self.attaches_files() {
+            let mut command = Command::new("xdg-email");
+            command
+                .arg("--subject")
+                .arg(&message.subject)
+                .arg("--body")
+                .arg(&message.body);
+            if let Some(path) = &message.attachment {
+                command.arg("--attach").arg(path);
+            }
+            if !message.to.is_empty() {
+                command.arg(&message.to);
+            }
+            command
+        } else {
+            let url = mailto_url(message);
+            if cfg!(target_os = "windows") {
+                // `start` would hand the `&` between query fields to the shell
+                let mut command = Command::new("rundll32");
+                command.arg("url.dll,FileProtocolHandler").arg(url);
+                command
+            } else {
+                let mut command = Command::new("open");
+                command.arg(url);
+                command
+            }
+        };
+
+        let mut child = command.spawn().map_err(|e| ShareError::Launch(e.to_string()))?;
+        // Openers exit once the mail client has the message; reap them off the UI thread
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+/// Build a `mailto:` URL for a message.
+fn mailto_url(message: &EmailMessage) -> String {
+    let mut body = message.body.clone();
+    if let Some(path) = &message.attachment {
+        // mailto: can't carry attachments; point the user at the file instead
+        body.push_str(&format!("\n\nAttachment: {}", path.display()));
+    }
+    format!(
+        "mailto:{}?subject={}&body={}",
+        percent_encode(&message.to),
+        percent_encode(&message.subject),
+        percent_encode(&body)
+    )
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters (and `@` for addresses).
+fn percent_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'@' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mailto_url_encodes_fields() {
+        let message = EmailMessage {
+            to: "fleet@example.com".to_string(),
+            subject: "Fuel & mix? 50/50".to_string(),
+            body: "Add 12 L\nof E85".to_string(),
+            attachment: None,
+        };
+        assert_eq!(
+            mailto_url(&message),
+            "mailto:fleet@example.com?subject=Fuel%20%26%20mix%3F%2050%2F50&body=Add%2012%20L%0Aof%20E85"
+        );
+
+        // The attachment's path is pointed at from the body
+        let message = EmailMessage {
+            attachment: Some(PathBuf::from("sheet.pdf")),
+            ..message
+        };
+        assert!(mailto_url(&message).ends_with("of%20E85%0A%0AAttachment%3A%20sheet.pdf"));
+    }
+}
@@ -0,0 +1,571 @@
+//! Spell-check service.
+//!
+//! Checks words against a Hunspell-format dictionary (an `<language>.aff`
+//! affix file and `<language>.dic` word list) from the app bundle, and suggests
+//! corrections for misspelled words. Dictionary words are expanded with
+//! their prefix and suffix rules when the dictionary loads, so checking a
+//! word is a set lookup.
+//!
+//! This is not Hunspell itself but a small reader of its file format. Only
+//! the parts of the affix format needed to check and suggest are read:
+//! `SET`, `FLAG`, `TRY`, `REP`, `PFX`, `SFX`, `NEEDAFFIX`, `FORBIDDENWORD`
+//! and `NOSUGGEST`. Compounding and affixes on affixes are not supported.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::core::is_safe_name;
+
+/// Letters tried by suggestions when the affix file has no `TRY` line.
+const DEFAULT_TRY: &str = "esianrtolcdugmphbyfvkwz'";
+
+/// Errors reported by the spell-check service.
+#[derive(Debug)]
+pub enum SpellError {
+    /// No dictionary directory has been configured.
+    NoDictionaries,
+    /// The language name isn't a plain file name.
+    InvalidLanguage(String),
+    /// Reading a dictionary file failed.
+    Io { path: PathBuf, error: std::io::Error },
+    /// A dictionary file is malformed.
+    Invalid(String),
+}
+
+impl std::fmt::Display for SpellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpellError::NoDictionaries => write!(f, "No spell-check dictionaries configured"),
+            SpellError::InvalidLanguage(language) => write!(f, "Invalid dictionary language: '{}'", language),
+            SpellError::Io { path, error } => write!(f, "Failed to read dictionary {:?}: {}", path, error),
+            SpellError::Invalid(msg) => write!(f, "Invalid dictionary: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SpellError {}
+
+/// How affix flags are written in a dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagMode {
+    /// One character per flag (the default, and `FLAG UTF-8`).
+    Char,
+    /// Two characters per flag (`FLAG long`).
+    Long,
+    /// Comma-separated numbers (`FLAG num`).
+    Num,
+}
+
+impl FlagMode {
+    fn parse(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagMode::Char => flags.chars().map(String::from).collect(),
+            FlagMode::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagMode::Num => flags.split(',').map(|n| n.trim().to_string()).collect(),
+        }
+    }
+}
+
+/// One character of an affix condition.
+#[derive(Debug, Clone)]
+enum Condition {
+    /// `.`: any character.
+    Any,
+    /// A literal character, or a `[...]` / `[^...]` class.
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl Condition {
+    fn parse_all(pattern: &str) -> Vec<Condition> {
+        let mut conditions = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            conditions.push(match c {
+                '.' => Condition::Any,
+                '[' => {
+                    let mut class: Vec<char> = chars.by_ref().take_while(|&c| c != ']').collect();
+                    let negated = class.first() == Some(&'^');
+                    if negated {
+                        class.remove(0);
+                    }
+                    Condition::Set { chars: class, negated }
+                }
+                c => Condition::Set {
+                    chars: vec![c],
+                    negated: false,
+                },
+            });
+        }
+        conditions
+    }
+
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Condition::Any => true,
+            Condition::Set { chars, negated } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+/// One prefix or suffix rule.
+#[derive(Debug, Clone)]
+struct AffixRule {
+    /// Text removed from the word before `add` is attached.
+    strip: String,
+    add: String,
+    /// What the start (prefix) or end (suffix) of the word must look like.
+    condition: Vec<Condition>,
+}
+
+impl AffixRule {
+    /// Apply the rule to a word, if its condition matches.
+    fn apply(&self, word: &str, suffix: bool) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() < self.condition.len() {
+            return None;
+        }
+        let edge = if suffix {
+            &chars[chars.len() - self.condition.len()..]
+        } else {
+            &chars[..self.condition.len()]
+        };
+        if !edge.iter().zip(&self.condition).all(|(&c, cond)| cond.matches(c)) {
+            return None;
+        }
+
+        if suffix {
+            let stem = word.strip_suffix(self.strip.as_str())?;
+            (!stem.is_empty()).then(|| format!("{}{}", stem, self.add))
+        } else {
+            let stem = word.strip_prefix(self.strip.as_str())?;
+            (!stem.is_empty()).then(|| format!("{}{}", self.add, stem))
+        }
+    }
+}
+
+/// The rules for one affix flag.
+#[derive(Debug, Clone, Default)]
+struct AffixGroup {
+    /// Whether prefixes and suffixes may be combined on one word.
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+/// A loaded dictionary: every accepted word form, plus what suggestions try.
+#[derive(Debug, Default)]
+struct Dictionary {
+    words: HashSet<String>,
+    /// Accepted words that are never suggested.
+    no_suggest: HashSet<String>,
+    /// Characters inserted and substituted when suggesting.
+    try_chars: Vec<char>,
+    /// Common misspellings, as (wrong, right) fragments.
+    replacements: Vec<(String, String)>,
+}
+
+impl Dictionary {
+    /// Build a dictionary from the contents of its affix and word files.
+    fn parse(aff: &str, dic: &str) -> Result<Self, SpellError> {
+        let mut mode = FlagMode::Char;
+        let mut try_chars = DEFAULT_TRY.chars().collect();
+        let mut replacements = Vec::new();
+        let mut prefixes: HashMap<String, AffixGroup> = HashMap::new();
+        let mut suffixes: HashMap<String, AffixGroup> = HashMap::new();
+        let mut need_affix = None;
+        let mut forbidden = None;
+        let mut no_suggest_flag = None;
+
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => mode = FlagMode::Long,
+                ["FLAG", "num", ..] => mode = FlagMode::Num,
+                ["TRY", chars, ..] => try_chars = chars.chars().collect(),
+                // The first REP line only holds the count
+                ["REP", from, to, ..] => replacements.push((from.replace('_', " "), to.replace('_', " "))),
+                ["NEEDAFFIX", flag, ..] => need_affix = Some(flag.to_string()),
+                ["FORBIDDENWORD", flag, ..] => forbidden = Some(flag.to_string()),
+                ["NOSUGGEST", flag, ..] => no_suggest_flag = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, rest @ ..] => {
+                    let groups = if *kind == "PFX" { &mut prefixes } else { &mut suffixes };
+                    match (groups.get_mut(*flag), rest) {
+                        // Header: cross product flag and rule count
+                        (None, [cross, _count, ..]) => {
+                            let group = AffixGroup {
+                                cross_product: *cross == "Y",
+                                rules: Vec::new(),
+                            };
+                            groups.insert(flag.to_string(), group);
+                        }
+                        (Some(group), [strip, add, condition, ..]) => {
+                            let unset = |s: &str| if s == "0" { String::new() } else { s.to_string() };
+                            // Flags on the affix itself (`add/flags`) are not supported
+                            let add = add.split('/').next().unwrap_or_default();
+                            group.rules.push(AffixRule {
+                                strip: unset(strip),
+                                add: unset(add),
+                                condition: Condition::parse_all(condition),
+                            });
+                        }
+                        _ => return Err(SpellError::Invalid(format!("malformed affix line '{}'", line))),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut dictionary = Dictionary {
+            try_chars,
+            replacements,
+            ..Self::default()
+        };
+        let mut forbidden_words = Vec::new();
+
+        // The first line is the approximate word count
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = match entry.find('/').filter(|&i| i > 0) {
+                Some(i) => (&entry[..i], mode.parse(&entry[i + 1..])),
+                None => (entry, Vec::new()),
+            };
+            let has = |flag: &Option<String>| flag.as_ref().is_some_and(|flag| flags.contains(flag));
+            if has(&forbidden) {
+                forbidden_words.push(word.to_string());
+                continue;
+            }
+
+            let mut forms = Vec::new();
+            if !has(&need_affix) {
+                forms.push(word.to_string());
+            }
+            let word_prefixes: Vec<&AffixGroup> = flags.iter().filter_map(|flag| prefixes.get(flag)).collect();
+            for group in flags.iter().filter_map(|flag| suffixes.get(flag)) {
+                for rule in &group.rules {
+                    let Some(form) = rule.apply(word, true) else {
+                        continue;
+                    };
+                    if group.cross_product {
+                        for prefix_group in word_prefixes.iter().filter(|g| g.cross_product) {
+                            forms.extend(prefix_group.rules.iter().filter_map(|rule| rule.apply(&form, false)));
+                        }
+                    }
+                    forms.push(form);
+                }
+            }
+            for group in &word_prefixes {
+                forms.extend(group.rules.iter().filter_map(|rule| rule.apply(word, false)));
+            }
+
+            if has(&no_suggest_flag) {
+                dictionary.no_suggest.extend(forms.iter().cloned());
+            }
+            dictionary.words.extend(forms);
+        }
+        for word in forbidden_words {
+            dictionary.words.remove(&word);
+        }
+        Ok(dictionary)
+    }
+
+    /// Check a word, accepting capitalized and upper case forms of
+    /// lower case dictionary words.
+    fn check(&self, word: &str) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+        match case_of(word) {
+            Case::Title | Case::Upper => {
+                let lower = word.to_lowercase();
+                self.words.contains(&lower) || self.words.contains(&capitalize(&lower))
+            }
+            Case::Lower | Case::Mixed => false,
+        }
+    }
+
+    /// Up to `limit` corrections one edit away from `word`, in the case it
+    /// was typed in.
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let case = case_of(word);
+        let base = match case {
+            Case::Title | Case::Upper => word.to_lowercase(),
+            Case::Lower | Case::Mixed => word.to_string(),
+        };
+        let chars: Vec<char> = base.chars().collect();
+        let joined = |chars: &[char]| chars.iter().collect::<String>();
+
+        let mut candidates = Vec::new();
+        for (from, to) in &self.replacements {
+            for (i, _) in base.match_indices(from.as_str()) {
+                candidates.push(format!("{}{}{}", &base[..i], to, &base[i + from.len()..]));
+            }
+        }
+        for i in 1..chars.len() {
+            let mut swapped = chars.clone();
+            swapped.swap(i - 1, i);
+            candidates.push(joined(&swapped));
+        }
+        for i in 0..chars.len() {
+            for &c in &self.try_chars {
+                let mut replaced = chars.clone();
+                replaced[i] = c;
+                candidates.push(joined(&replaced));
+            }
+        }
+        for i in 0..chars.len() {
+            let mut removed = chars.clone();
+            removed.remove(i);
+            candidates.push(joined(&removed));
+        }
+        for i in 0..=chars.len() {
+            for &c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                candidates.push(joined(&inserted));
+            }
+        }
+        for i in 1..chars.len() {
+            let (left, right) = (joined(&chars[..i]), joined(&chars[i..]));
+            if self.check(&left) && self.check(&right) {
+                candidates.push(format!("{} {}", left, right));
+            }
+        }
+
+        let mut suggestions: Vec<String> = Vec::new();
+        for candidate in candidates {
+            if suggestions.len() >= limit {
+                break;
+            }
+            let valid = candidate.contains(' ')
+                || (self.check(&candidate) && !self.no_suggest.contains(&candidate));
+            let candidate = match case {
+                Case::Title => capitalize(&candidate),
+                Case::Upper => candidate.to_uppercase(),
+                Case::Lower | Case::Mixed => candidate,
+            };
+            if valid && candidate != word && !suggestions.contains(&candidate) {
+                suggestions.push(candidate);
+            }
+        }
+        suggestions
+    }
+}
+
+/// Capitalization of a word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Lower,
+    /// First letter upper case, the rest lower case.
+    Title,
+    Upper,
+    Mixed,
+}
+
+fn case_of(word: &str) -> Case {
+    let mut letters = word.chars().filter(|c| c.is_alphabetic());
+    let Some(first) = letters.next() else {
+        return Case::Lower;
+    };
+    let rest: Vec<char> = letters.collect();
+    let rest_upper = rest.iter().all(|c| c.is_uppercase());
+    let rest_lower = rest.iter().all(|c| c.is_lowercase());
+    match (first.is_uppercase(), rest_lower, rest_upper) {
+        (false, true, _) => Case::Lower,
+        (true, _, true) if !rest.is_empty() => Case::Upper,
+        (true, true, _) => Case::Title,
+        _ => Case::Mixed,
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Byte ranges of the words in `text`: runs of letters, with apostrophes
+/// inside words kept. Runs containing digits are not words.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let is_apostrophe = |c: char| c == '\'' || c == '\u{2019}';
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        let in_word = c.is_alphanumeric() || is_apostrophe(c);
+        match (start, in_word) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                let word = &text[s..i];
+                let trimmed = word.trim_start_matches(is_apostrophe);
+                let offset = s + word.len() - trimmed.len();
+                let trimmed = trimmed.trim_end_matches(is_apostrophe);
+                if !trimmed.is_empty() && !trimmed.chars().any(|c| c.is_numeric()) {
+                    ranges.push(offset..offset + trimmed.len());
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+/// Get the encoding an affix file's `SET` line declares its dictionary is
+/// written in, or UTF-8 without one.
+fn dictionary_encoding(aff: &[u8]) -> Result<&'static Encoding, SpellError> {
+    let set = aff.split(|&b| b == b'\n').find_map(|line| {
+        let line = String::from_utf8_lossy(line);
+        let mut fields = line.split_whitespace();
+        (fields.next() == Some("SET")).then(|| fields.next().unwrap_or_default().to_string())
+    });
+    let Some(label) = set else {
+        return Ok(UTF_8);
+    };
+    // Hunspell writes Windows code pages as `microsoft-cp1251`
+    let name = label.to_ascii_lowercase().replace("microsoft-cp", "windows-");
+    Encoding::for_label(name.as_bytes())
+        .ok_or_else(|| SpellError::Invalid(format!("unsupported encoding '{}'", label)))
+}
+
+/// The active dictionary and its language.
+#[derive(Debug)]
+struct Loaded {
+    language: String,
+    dictionary: Dictionary,
+}
+
+/// Checks spelling against the dictionary of the selected language.
+///
+/// Until a language is selected every word is accepted.
+#[derive(Debug, Default)]
+pub struct SpellService {
+    /// Directory holding `<language>.aff` and `<language>.dic` files.
+    dictionaries: Option<PathBuf>,
+    loaded: Mutex<Option<Loaded>>,
+}
+
+impl SpellService {
+    /// Create a spell checker with no dictionaries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a spell checker that reads dictionaries from a directory.
+    pub fn with_dictionaries(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dictionaries: Some(dir.into()),
+            loaded: Mutex::new(None),
+        }
+    }
+
+    /// Load the dictionary for a language (e.g. `en_US`), replacing the
+    /// current one. On failure the current dictionary is kept.
+    pub fn set_language(&self, language: &str) -> Result<(), SpellError> {
+        let dir = self.dictionaries.as_ref().ok_or(SpellError::NoDictionaries)?;
+        if !is_safe_name(language) {
+            return Err(SpellError::InvalidLanguage(language.to_string()));
+        }
+        let read = |extension: &str| {
+            let path = dir.join(format!("{}.{}", language, extension));
+            fs::read(&path).map_err(|error| SpellError::Io { path, error })
+        };
+        let (aff, dic) = (read("aff")?, read("dic")?);
+        let encoding = dictionary_encoding(&aff)?;
+        let decode = |bytes: &[u8]| encoding.decode_with_bom_removal(bytes).0.into_owned();
+        let dictionary = Dictionary::parse(&decode(&aff), &decode(&dic))?;
+
+        *self.loaded.lock().unwrap_or_else(|e| e.into_inner()) = Some(Loaded {
+            language: language.to_string(),
+            dictionary,
+        });
+        Ok(())
+    }
+
+    /// Get the language of the loaded dictionary.
+    pub fn language(&self) -> Option<String> {
+        let loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        loaded.as_ref().map(|loaded| loaded.language.clone())
+    }
+
+    /// Check if a dictionary is loaded.
+    pub fn is_active(&self) -> bool {
+        self.loaded.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+
+    /// Check if a word is spelled correctly.
+    pub fn check(&self, word: &str) -> bool {
+        let loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        loaded.as_ref().is_none_or(|loaded| loaded.dictionary.check(word))
+    }
+
+    /// Byte ranges of the misspelled words in `text`.
+    pub fn misspelled(&self, text: &str) -> Vec<Range<usize>> {
+        let loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(loaded) = loaded.as_ref() else {
+            return Vec::new();
+        };
+        words(text)
+            .into_iter()
+            .filter(|range| !loaded.dictionary.check(&text[range.clone()]))
+            .collect()
+    }
+
+    /// Up to `limit` corrections for a misspelled word.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        loaded
+            .as_ref()
+            .map(|loaded| loaded.dictionary.suggest(word, limit))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwz\nREP 1\nREP f ph\n\
+        PFX U Y 1\nPFX U 0 un .\n\
+        SFX S Y 2\nSFX S y ies [^aeiou]y\nSFX S 0 s [^y]\n\
+        SFX D Y 1\nSFX D 0 ed [^e]\n";
+    const DIC: &str = "5\nhappy/U\ncity/S\nlock/USD\nphone/S\nParis\n";
+
+    #[test]
+    fn test_check_and_suggest() {
+        let dictionary = Dictionary::parse(AFF, DIC).unwrap();
+        for word in ["happy", "unhappy", "cities", "locks", "unlocked", "Paris", "City", "LOCKED"] {
+            assert!(dictionary.check(word), "{}", word);
+        }
+        for word in ["citys", "happys", "paris", "unphone"] {
+            assert!(!dictionary.check(word), "{}", word);
+        }
+
+        assert_eq!(dictionary.suggest("lcok", 3), vec!["lock"]);
+        assert_eq!(dictionary.suggest("Fones", 3), vec!["Phones"]);
+        assert_eq!(dictionary.suggest("citylock", 3), vec!["city lock"]);
+
+        let text = "Unlocked citys, 42nd happy’ don't";
+        let found: Vec<&str> = words(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(found, vec!["Unlocked", "citys", "happy", "don't"]);
+
+        let dir = std::env::temp_dir().join(format!("crix-spell-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("de_DE.aff"), b"SET ISO8859-1\n").unwrap();
+        fs::write(dir.join("de_DE.dic"), b"1\nStra\xdfe\n").unwrap();
+        let service = SpellService::with_dictionaries(&dir);
+        service.set_language("de_DE").unwrap();
+        assert!(service.check("Stra\u{df}e"));
+        assert!(matches!(service.set_language("../de_DE"), Err(SpellError::InvalidLanguage(_))));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+//! Text-to-speech service.
+//!
+//! Speaks text through the platform speech engine (SAPI/WinRT, AVFoundation,
+//! Speech Dispatcher). Native speech is provided by the tts crate and requires
+//! the `tts` cargo feature; without it, `speak` reports `TtsError::Unsupported`.
+
+use std::sync::Mutex;
+
+/// Errors reported by the text-to-speech service.
+#[derive(Debug, Clone)]
+pub enum TtsError {
+    /// Crix was built without the `tts` feature.
+    Unsupported,
+    /// The speech engine failed to initialize or speak.
+    Backend(String),
+}
+
+impl std::fmt::Display for TtsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TtsError::Unsupported => write!(f, "Speech support not compiled in (enable the 'tts' feature)"),
+            TtsError::Backend(msg) => write!(f, "Speech error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TtsError {}
+
+/// Speaks text aloud using the platform speech engine.
+///
+/// The engine is created lazily on first use so apps that never speak
+/// don't pay for connecting to it.
+#[derive(Default)]
+pub struct TtsService {
+    engine: Mutex<Option<backend::Engine>>,
+}
+
+impl TtsService {
+    /// Create an idle speech service.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Speak `text`. If `interrupt` is true, any utterance in progress is cut off.
+    pub fn speak(&self, text: &str, interrupt: bool) -> Result<(), TtsError> {
+        let mut engine = self.engine.lock().unwrap_or_else(|e| e.into_inner());
+        if engine.is_none() {
+            *engine = Some(backend::Engine::new()?);
+        }
+        engine.as_mut().expect("engine was just initialized").speak(text, interrupt)
+    }
+
+    /// Stop speaking.
+    pub fn stop(&self) {
+        let mut engine = self.engine.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(engine) = engine.as_mut() {
+            engine.stop();
+        }
+    }
+}
+
+impl std::fmt::Debug for TtsService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let initialized = self.engine.lock().unwrap_or_else(|e| e.into_inner()).is_some();
+        f.debug_struct("TtsService")
+            .field("initialized", &initialized)
+            .finish()
+    }
+}
+
+#[cfg(feature = "tts")]
+mod backend {
+    use tts::Tts;
+
+    use super::TtsError;
+
+    pub(super) struct Engine(Tts);
+
+    impl Engine {
+        pub(super) fn new() -> Result<Self, TtsError> {
+            Tts::default()
+                .map(Engine)
+                .map_err(|e| TtsError::Backend(e.to_string()))
+        }
+
+        pub(super) fn speak(&mut self, text: &str, interrupt: bool) -> Result<(), TtsError> {
+            self.0
+                .speak(text, interrupt)
+                .map(|_| ())
+                .map_err(|e| TtsError::Backend(e.to_string()))
+        }
+
+        pub(super) fn stop(&mut self) {
+            let _ = self.0.stop();
+        }
+    }
+}
+
+#[cfg(not(feature = "tts"))]
+mod backend {
+    use super::TtsError;
+
+    pub(super) struct Engine;
+
+    impl Engine {
+        pub(super) fn new() -> Result<Self, TtsError> {
+            Err(TtsError::Unsupported)
+        }
+
+        pub(super) fn speak(&mut self, _text: &str, _interrupt: bool) -> Result<(), TtsError> {
+            Err(TtsError::Unsupported)
+        }
+
+        pub(super) fn stop(&mut self) {}
+    }
+}
@@ -0,0 +1,460 @@
+//! App bundle update service.
+//!
+//! Checks a manifest URL for a newer version of the running bundle,
+//! downloads the signed `.crixapp` archive (a zip of the bundle directory),
+//! verifies its Ed25519 signature against the public key in app.toml and,
+//! if the `[app] version` inside it is the newer one the manifest offers,
+//! stages it next to the bundle. The staged bundle replaces the installed
+//! one the next time the app is launched (see `apply_staged_update`).
+//!
+//! The manifest is JSON:
+//!
+//! ```json
+//! {
+//!   "version": "1.3.0",
+//!   "url": "https://example.com/my_app-1.3.0.crixapp",
+//!   "signature": "<hex Ed25519 signature of the archive>"
+//! }
+//! ```
+//!
+//! Downloading and verifying requires the `updater` cargo feature.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use serde::Deserialize;
+
+use crate::core::Store;
+
+use super::http;
+
+/// Errors reported by the update service.
+#[derive(Debug, Clone)]
+pub enum UpdateError {
+    /// Crix was built without the `updater` feature.
+    Unsupported,
+    /// No `[update]` section in app.toml.
+    NotConfigured,
+    /// Fetching the manifest or archive failed.
+    Network(String),
+    /// The manifest could not be parsed.
+    Manifest(String),
+    /// The archive signature didn't verify.
+    BadSignature,
+    /// Extracting or installing the bundle failed.
+    Install(String),
+}
+
+impl std::fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateError::Unsupported => write!(f, "Update support not compiled in (enable the 'updater' feature)"),
+            UpdateError::NotConfigured => write!(f, "No [update] section in app.toml"),
+            UpdateError::Network(msg) => write!(f, "Update download failed: {}", msg),
+            UpdateError::Manifest(msg) => write!(f, "Invalid update manifest: {}", msg),
+            UpdateError::BadSignature => write!(f, "Update signature verification failed"),
+            UpdateError::Install(msg) => write!(f, "Update install failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {}
+
+/// Update settings for a bundle.
+#[derive(Debug, Clone)]
+pub struct UpdateConfig {
+    /// URL of the update manifest.
+    pub manifest_url: String,
+    /// Hex-encoded Ed25519 public key that archives must be signed with.
+    pub public_key: String,
+    /// Version of the running bundle.
+    pub current_version: String,
+    /// Root directory of the running bundle.
+    pub bundle_root: PathBuf,
+}
+
+/// Progress of the updater, as published to the store.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateStatus {
+    Checking,
+    UpToDate,
+    Available { version: String },
+    Downloading { version: String },
+    /// Installed next to the bundle; applied on the next launch.
+    Staged { version: String },
+    Error(String),
+}
+
+impl UpdateStatus {
+    /// Write the status to `update.status`, `update.version` and `update.error`.
+    pub fn publish(&self, store: &mut Store) {
+        let (status, version) = match self {
+            UpdateStatus::Checking => ("checking", None),
+            UpdateStatus::UpToDate => ("up_to_date", None),
+            UpdateStatus::Available { version } => ("available", Some(version)),
+            UpdateStatus::Downloading { version } => ("downloading", Some(version)),
+            UpdateStatus::Staged { version } => ("staged", Some(version)),
+            UpdateStatus::Error(_) => ("error", None),
+        };
+        store.set("update.status", status);
+        if let Some(version) = version {
+            store.set("update.version", version.clone());
+        }
+        match self {
+            UpdateStatus::Error(e) => store.set("update.error", e.clone()),
+            _ => {
+                store.remove("update.error");
+            }
+        }
+    }
+}
+
+/// Update manifest served at the configured URL.
+#[derive(Debug, Clone, Deserialize)]
+struct Manifest {
+    version: String,
+    url: String,
+    signature: String,
+}
+
+/// Checks for, downloads and stages bundle updates in the background.
+#[derive(Default)]
+pub struct UpdateService {
+    config: Option<UpdateConfig>,
+    task: Mutex<Option<JoinHandle<()>>>,
+    /// Status changes waiting to be published.
+    statuses: Arc<Mutex<Vec<UpdateStatus>>>,
+}
+
+impl UpdateService {
+    /// Create an updater with no configuration (every request fails).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an updater for a bundle.
+    pub fn with_config(config: UpdateConfig) -> Self {
+        Self {
+            config: Some(config),
+            ..Self::default()
+        }
+    }
+
+    /// Check the manifest for a newer version in the background.
+    pub fn check(&self) {
+        self.spawn(false);
+    }
+
+    /// Download, verify and stage the newest version in the background.
+    pub fn install(&self) {
+        self.spawn(true);
+    }
+
+    /// Check if a check or install is running.
+    pub fn is_busy(&self) -> bool {
+        self.task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_some_and(|t| !t.is_finished())
+    }
+
+    /// Take status changes since the last call, oldest first.
+    pub fn take_statuses(&self) -> Vec<UpdateStatus> {
+        std::mem::take(&mut *self.statuses.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn spawn(&self, install: bool) {
+        let report = {
+            let statuses = self.statuses.clone();
+            move |status: UpdateStatus| statuses.lock().unwrap_or_else(|e| e.into_inner()).push(status)
+        };
+
+        let Some(config) = self.config.clone() else {
+            return report(UpdateStatus::Error(UpdateError::NotConfigured.to_string()));
+        };
+        let mut task = self.task.lock().unwrap_or_else(|e| e.into_inner());
+        if task.as_ref().is_some_and(|t| !t.is_finished()) {
+            return;
+        }
+
+        report(UpdateStatus::Checking);
+        let spawned = thread::Builder::new().name("crix-update".to_string()).spawn({
+            let report = report.clone();
+            move || {
+                let status = run(&config, install, &report).unwrap_or_else(|e| UpdateStatus::Error(e.to_string()));
+                report(status);
+            }
+        });
+
+        match spawned {
+            Ok(thread) => *task = Some(thread),
+            Err(e) => report(UpdateStatus::Error(e.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Debug for UpdateService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpdateService")
+            .field("configured", &self.config.is_some())
+            .field("busy", &self.is_busy())
+            .finish()
+    }
+}
+
+/// Check (and optionally install) an update. Returns the final status.
+fn run(config: &UpdateConfig, install: bool, report: &dyn Fn(UpdateStatus)) -> Result<UpdateStatus, UpdateError> {
+    let body = http::get_text(&config.manifest_url).map_err(|e| UpdateError::Network(e.to_string()))?;
+    let manifest: Manifest = serde_json::from_str(&body).map_err(|e| UpdateError::Manifest(e.to_string()))?;
+
+    if compare_versions(&manifest.version, &config.current_version) != Ordering::Greater {
+        return Ok(UpdateStatus::UpToDate);
+    }
+    let version = manifest.version.clone();
+    if !install {
+        return Ok(UpdateStatus::Available { version });
+    }
+
+    report(UpdateStatus::Downloading { version: version.clone() });
+    let archive = http::get_bytes(&manifest.url).map_err(|e| UpdateError::Network(e.to_string()))?;
+    backend::verify(&archive, &manifest.signature, &config.public_key)?;
+    stage(&archive, &config.bundle_root, &version, &config.current_version)?;
+
+    Ok(UpdateStatus::Staged { version })
+}
+
+/// Compare dotted version strings numerically ("1.10.0" > "1.9.2").
+/// Non-numeric components compare as 0.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    let (a, b) = (parse(a), parse(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Directory a verified update is staged in, next to the bundle.
+fn staging_dir(bundle_root: &Path) -> PathBuf {
+    let mut name = bundle_root.file_name().unwrap_or_default().to_os_string();
+    name.push(".update");
+    bundle_root.with_file_name(name)
+}
+
+/// Extract a verified archive into the staging directory, if it holds the
+/// `offered` version and that is newer than `current`.
+fn stage(archive: &[u8], bundle_root: &Path, offered: &str, current: &str) -> Result<(), UpdateError> {
+    let staging = staging_dir(bundle_root);
+    let mut unpack = staging.clone().into_os_string();
+    unpack.push(".tmp");
+    let unpack = PathBuf::from(unpack);
+
+    let install_err = |e: std::io::Error| UpdateError::Install(e.to_string());
+    for dir in [&staging, &unpack] {
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(install_err)?;
+        }
+    }
+    backend::extract(archive, &unpack)?;
+
+    // Accept archives with app.toml at the root or inside a single top-level directory
+    let root = if unpack.join("app.toml").exists() {
+        unpack.clone()
+    } else {
+        let mut entries = fs::read_dir(&unpack).map_err(install_err)?.flatten();
+        match (entries.next(), entries.next()) {
+            (Some(entry), None) if entry.path().join("app.toml").exists() => entry.path(),
+            _ => return Err(UpdateError::Install("archive does not contain an app.toml".to_string())),
+        }
+    };
+
+    if let Err(e) = check_version(&root, offered, current) {
+        let _ = fs::remove_dir_all(&unpack);
+        return Err(e);
+    }
+    fs::rename(&root, &staging).map_err(install_err)?;
+    if unpack.exists() {
+        fs::remove_dir_all(&unpack).map_err(install_err)?;
+    }
+    Ok(())
+}
+
+/// Check that the unpacked bundle at `root` is the `offered` version and
+/// newer than `current`. Only the archive is signed, not the manifest, so
+/// this keeps an old signed archive offered as a new version from rolling
+/// the app back.
+fn check_version(root: &Path, offered: &str, current: &str) -> Result<(), UpdateError> {
+    let content = fs::read_to_string(root.join("app.toml")).map_err(|e| UpdateError::Install(e.to_string()))?;
+    let toml: toml::Table = content.parse().map_err(|e: toml::de::Error| UpdateError::Install(e.to_string()))?;
+    let version = toml
+        .get("app")
+        .and_then(|app| app.get("version"))
+        .and_then(|version| version.as_str())
+        .unwrap_or_default();
+    let newer = compare_versions(version, current) == Ordering::Greater;
+    if compare_versions(version, offered) != Ordering::Equal || !newer {
+        return Err(UpdateError::Install(format!(
+            "archive holds version '{}', not the newer version '{}' offered",
+            version, offered
+        )));
+    }
+    Ok(())
+}
+
+/// Replace a bundle with its staged update, if one exists and the installed
+/// bundle takes updates (has an `[update]` section).
+///
+/// Call before loading the bundle. A swap cut short by a crash, which leaves
+/// only `<root>.old`, is put back first. Returns true if an update was applied.
+pub fn apply_staged_update(bundle_root: &Path) -> Result<bool, UpdateError> {
+    let mut old = bundle_root.as_os_str().to_owned();
+    old.push(".old");
+    let old = PathBuf::from(old);
+
+    let install_err = |e: std::io::Error| UpdateError::Install(e.to_string());
+    if !bundle_root.exists() && old.exists() {
+        fs::rename(&old, bundle_root).map_err(install_err)?;
+    }
+
+    let staging = staging_dir(bundle_root);
+    if !staging.join("app.toml").exists() || !takes_updates(bundle_root) {
+        return Ok(false);
+    }
+
+    if old.exists() {
+        fs::remove_dir_all(&old).map_err(install_err)?;
+    }
+    fs::rename(bundle_root, &old).map_err(install_err)?;
+    if let Err(e) = fs::rename(&staging, bundle_root) {
+        // Put the previous version back so the app still launches
+        let _ = fs::rename(&old, bundle_root);
+        return Err(install_err(e));
+    }
+    // The update is in place; a leftover copy is removed on the next update
+    if let Err(e) = fs::remove_dir_all(&old) {
+        eprintln!("Warning: failed to remove previous version {}: {}", old.display(), e);
+    }
+    Ok(true)
+}
+
+/// Check whether the bundle at `root` has an `[update]` section.
+fn takes_updates(root: &Path) -> bool {
+    fs::read_to_string(root.join("app.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .is_some_and(|toml| toml.contains_key("update"))
+}
+
+#[cfg(feature = "updater")]
+mod backend {
+    use std::io::Cursor;
+    use std::path::Path;
+
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    use super::UpdateError;
+
+    pub(super) fn verify(archive: &[u8], signature: &str, public_key: &str) -> Result<(), UpdateError> {
+        let key: [u8; 32] = decode_hex(public_key)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| UpdateError::Manifest("public_key must be 32 hex-encoded bytes".to_string()))?;
+        let signature: [u8; 64] = decode_hex(signature)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| UpdateError::Manifest("signature must be 64 hex-encoded bytes".to_string()))?;
+
+        let key = VerifyingKey::from_bytes(&key).map_err(|_| UpdateError::BadSignature)?;
+        key.verify(archive, &Signature::from_bytes(&signature))
+            .map_err(|_| UpdateError::BadSignature)
+    }
+
+    pub(super) fn extract(archive: &[u8], dest: &Path) -> Result<(), UpdateError> {
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive)).map_err(|e| UpdateError::Install(e.to_string()))?;
+        zip.extract(dest).map_err(|e| UpdateError::Install(e.to_string()))
+    }
+
+    fn decode_hex(text: &str) -> Option<Vec<u8>> {
+        let text = text.trim();
+        if !text.len().is_multiple_of(2) {
+            return None;
+        }
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
+}
+
+#[cfg(not(feature = "updater"))]
+mod backend {
+    use std::path::Path;
+
+    use super::UpdateError;
+
+    pub(super) fn verify(_archive: &[u8], _signature: &str, _public_key: &str) -> Result<(), UpdateError> {
+        Err(UpdateError::Unsupported)
+    }
+
+    pub(super) fn extract(_archive: &[u8], _dest: &Path) -> Result<(), UpdateError> {
+        Err(UpdateError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10.0", "1.9.2"), Ordering::Greater);
+        assert_eq!(compare_versions("v2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.9", "1.0.0"), Ordering::Less);
+
+        let root = std::env::temp_dir().join(format!("crix-update-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("app.toml"), "[app]\nname = \"App\"\nversion = \"1.2.0\"\n").unwrap();
+        assert!(check_version(&root, "1.2.0", "1.1.0").is_ok());
+        // An old archive offered as a newer version is refused
+        assert!(check_version(&root, "1.3.0", "1.1.0").is_err());
+        assert!(check_version(&root, "1.2.0", "1.2.0").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_apply_staged_update() {
+        let dir = std::env::temp_dir().join(format!("crix-apply-{}", std::process::id()));
+        let root = dir.join("app");
+        let staging = staging_dir(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(root.join("app.toml"), "[app]\nversion = \"1.0.0\"\n").unwrap();
+        fs::write(staging.join("app.toml"), "[app]\nversion = \"1.1.0\"\n").unwrap();
+
+        // A bundle without [update] keeps what is installed
+        assert!(!apply_staged_update(&root).unwrap());
+        assert!(fs::read_to_string(root.join("app.toml")).unwrap().contains("1.0.0"));
+
+        let takes_updates = "[app]\nversion = \"1.0.0\"\n[update]\nmanifest = \"https://example.com\"\n";
+        fs::write(root.join("app.toml"), takes_updates).unwrap();
+        assert!(apply_staged_update(&root).unwrap());
+        assert!(fs::read_to_string(root.join("app.toml")).unwrap().contains("1.1.0"));
+        assert!(!staging.exists());
+
+        // A swap interrupted after moving the bundle aside is undone
+        fs::rename(&root, dir.join("app.old")).unwrap();
+        assert!(!apply_staged_update(&root).unwrap());
+        assert!(root.join("app.toml").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
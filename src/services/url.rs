@@ -0,0 +1,93 @@
+//! URL opener service.
+//!
+//! Opens web pages and mail links with the system's default handler
+//! (`xdg-open` on Linux, `open` on macOS, the URL protocol handler on
+//! Windows). Only `http:`, `https:` and `mailto:` URLs are opened, so a skin
+//! or store value can't use a link to launch local files or programs.
+
+use std::process::Command;
+
+/// URL schemes the service opens.
+const SUPPORTED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Errors reported by the URL opener.
+#[derive(Debug, Clone)]
+pub enum UrlError {
+    /// The URL is empty or doesn't use a supported scheme.
+    Unsupported(String),
+    /// The system handler could not be launched.
+    Launch(String),
+}
+
+impl std::fmt::Display for UrlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlError::Unsupported(url) => write!(f, "Not an http, https or mailto URL: '{}'", url),
+            UrlError::Launch(msg) => write!(f, "Failed to open browser: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UrlError {}
+
+/// Opens URLs in the system browser or mail client.
+#[derive(Debug, Default)]
+pub struct UrlService;
+
+impl UrlService {
+    /// Create a URL opener.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Open a URL with the system's default handler.
+    pub fn open(&self, url: &str) -> Result<(), UrlError> {
+        let url = url.trim();
+        if !is_supported(url) {
+            return Err(UrlError::Unsupported(url.to_string()));
+        }
+
+        let mut command = if cfg!(target_os = "windows") {
+            // `start` would hand `&` in the URL to the shell
+            let mut command = Command::new("rundll32");
+            command.arg("url.dll,FileProtocolHandler");
+            command
+        } else if cfg!(target_os = "macos") {
+            Command::new("open")
+        } else {
+            Command::new("xdg-open")
+        };
+
+        let mut child = command.arg(url).spawn().map_err(|e| UrlError::Launch(e.to_string()))?;
+        // Openers exit once the browser has the URL; reap them off the UI thread
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+/// Check that a URL has a supported scheme and something after it.
+fn is_supported(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return false;
+    };
+    SUPPORTED_SCHEMES.iter().any(|supported| scheme.eq_ignore_ascii_case(supported))
+        && !rest.trim_start_matches('/').is_empty()
+        && !url.chars().any(char::is_control)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_urls() {
+        assert!(is_supported("https://example.com/docs?a=1&b=2"));
+        assert!(is_supported("HTTP://example.com"));
+        assert!(is_supported("mailto:help@example.com"));
+        assert!(!is_supported("https://"));
+        assert!(!is_supported("file:///etc/passwd"));
+        assert!(!is_supported("calc.exe"));
+        assert!(!is_supported("https://example.com/\nrm"));
+        assert!(matches!(UrlService::new().open("javascript:alert(1)"), Err(UrlError::Unsupported(_))));
+    }
+}
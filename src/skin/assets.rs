@@ -1,32 +1,109 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 
-use image::{ImageReader, RgbImage};
+use image::{ImageReader, Limits, RgbImage};
 
+use super::embed::{self, MAX_EMBED_DEPTH};
+use super::pack::{self, SkinPack, PACK_SKIN_FILE};
+use super::palette;
 use super::types::{Skin, SkinError, SkinWindow};
+use crate::graphics::NineSlice;
 
 /// A skin with all assets loaded and ready to use.
 pub struct LoadedSkin {
     pub skin: Skin,
+    /// Files on disk the skin was loaded from: its skin file and image
+    /// assets, or the skin pack holding them.
+    files: Vec<PathBuf>,
     images: HashMap<String, RgbImage>,
+    /// Skins shown by embed parts, by part id.
+    embeds: HashMap<String, LoadedSkin>,
 }
 
 impl LoadedSkin {
-    /// Load a skin and all its assets from a TOML file path.
+    /// Load a skin and all its assets from a skin file or a `.skinpack`
+    /// archive, along with the skins its embed parts show.
     pub fn load(path: &Path) -> Result<Self, SkinError> {
-        let skin = Skin::load(path)?;
+        Self::load_nested(path, None, 0)
+    }
+
+    /// Load a skin at embed depth `depth`, namespacing it under `namespace`
+    /// when it is embedded.
+    fn load_nested(path: &Path, namespace: Option<&str>, depth: usize) -> Result<Self, SkinError> {
+        if pack::is_pack(path) {
+            let pack = SkinPack::open(path)?;
+            let mut loaded = Self::load_packed(&pack, Path::new(PACK_SKIN_FILE), namespace, depth)?;
+            loaded.files = vec![path.to_path_buf()];
+            return Ok(loaded);
+        }
+
+        let mut skin = Skin::load(path)?;
+        if let Some(namespace) = namespace {
+            embed::namespace_skin(&mut skin, namespace);
+        }
 
         let mut images = HashMap::new();
 
         // Load all image assets
         for (key, asset_path) in &skin.assets {
-            let reader = ImageReader::open(asset_path)?;
-            let img = reader.decode()?;
-            let rgb = img.to_rgb8();
-            images.insert(key.clone(), rgb);
+            let bytes = std::fs::read(asset_path)?;
+            images.insert(key.clone(), decode_image(&bytes)?);
+        }
+        check_slices(&skin, &images)?;
+
+        let mut embeds = HashMap::new();
+        for (id, source) in embed::embeds(&skin.parts) {
+            check_embed_depth(id, depth)?;
+            let embedded = Self::load_nested(&embed::skin_file(source), Some(id), depth + 1)?;
+            embeds.insert(id.to_string(), embedded);
+        }
+
+        let mut files = vec![path.to_path_buf()];
+        files.extend(skin.assets.values().cloned());
+        Ok(Self { skin, files, images, embeds })
+    }
+
+    /// Load the skin at `path` inside a skin pack, reading its assets and
+    /// embedded skins from the pack too.
+    fn load_packed(pack: &SkinPack, path: &Path, namespace: Option<&str>, depth: usize) -> Result<Self, SkinError> {
+        let content = std::str::from_utf8(pack.read(path)?)
+            .map_err(|_| SkinError::Pack(format!("{:?} is not UTF-8", path)))?;
+        let mut skin = Skin::parse(content, path.parent().unwrap_or(Path::new("")))?;
+        if let Some(namespace) = namespace {
+            embed::namespace_skin(&mut skin, namespace);
+        }
+
+        let mut images = HashMap::new();
+        for (key, asset_path) in &skin.assets {
+            images.insert(key.clone(), decode_image(pack.read(asset_path)?)?);
+        }
+        check_slices(&skin, &images)?;
+
+        let mut embeds = HashMap::new();
+        for (id, source) in embed::embeds(&skin.parts) {
+            check_embed_depth(id, depth)?;
+            let embedded = Self::load_packed(pack, &pack.skin_file(source), Some(id), depth + 1)?;
+            embeds.insert(id.to_string(), embedded);
         }
 
-        Ok(Self { skin, images })
+        Ok(Self { skin, files: Vec::new(), images, embeds })
+    }
+
+    /// Pair a parsed skin with already decoded asset images.
+    pub fn from_images(skin: Skin, images: HashMap<String, RgbImage>) -> Self {
+        Self { skin, files: Vec::new(), images, embeds: HashMap::new() }
+    }
+
+    /// Get the files the skin was loaded from: its skin file, its image
+    /// assets and those of the skins it embeds, or the skin pack they came
+    /// out of.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = self.files.clone();
+        for embedded in self.embeds.values() {
+            files.extend(embedded.files());
+        }
+        files
     }
 
     /// Get the window configuration from the skin.
@@ -43,4 +120,83 @@ impl LoadedSkin {
     pub fn get_image(&self, key: &str) -> Option<&RgbImage> {
         self.images.get(key)
     }
+
+    /// Replace the pixels of the images that are exactly a key color of the
+    /// skin's palette with the color chosen for its name, here and in the
+    /// skins it embeds. Names without a choice keep their color.
+    pub fn remap_palette(&mut self, chosen: &HashMap<String, u32>) {
+        let remap = palette::remapping(&self.skin.palette, chosen);
+        for image in self.images.values_mut() {
+            palette::remap_image(image, &remap);
+        }
+        for embedded in self.embeds.values_mut() {
+            embedded.remap_palette(chosen);
+        }
+    }
+
+    /// Get the palette names and key colors of the skin and the skins it
+    /// embeds, whose names are under their embed part's id.
+    pub fn palettes(&self) -> Vec<(&str, u32)> {
+        let mut palettes: Vec<_> = self.skin.palette.iter().map(|(name, &color)| (name.as_str(), color)).collect();
+        for embedded in self.embeds.values() {
+            palettes.extend(embedded.palettes());
+        }
+        palettes
+    }
+
+    /// Get the nine-slice border of an image by asset key, if it is
+    /// stretched to fit.
+    pub fn get_slice(&self, key: &str) -> Option<&NineSlice> {
+        self.skin.slices.get(key)
+    }
+
+    /// Get the skin shown by embed part `id`.
+    pub fn embed(&self, id: &str) -> Option<&LoadedSkin> {
+        self.embeds.get(id)
+    }
+}
+
+/// Refuse nine-slice borders wider or taller than their images.
+fn check_slices(skin: &Skin, images: &HashMap<String, RgbImage>) -> Result<(), SkinError> {
+    for (key, slice) in &skin.slices {
+        if let Some(image) = images.get(key)
+            && !slice.fits(image.dimensions())
+        {
+            return Err(SkinError::InvalidValue(format!(
+                "asset '{}': border doesn't fit in its {}x{} image",
+                key,
+                image.width(),
+                image.height()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Refuse an embed part `id` found at embed depth `depth` if it would nest
+/// skins too deep.
+fn check_embed_depth(id: &str, depth: usize) -> Result<(), SkinError> {
+    if depth >= MAX_EMBED_DEPTH {
+        return Err(SkinError::InvalidValue(format!(
+            "part '{}': embedded skins nest more than {} deep",
+            id, MAX_EMBED_DEPTH
+        )));
+    }
+    Ok(())
+}
+
+/// Largest width or height accepted for a skin image asset.
+pub const MAX_ASSET_DIMENSION: u32 = 8192;
+
+/// Decode an image asset (format detected from its contents). Images larger
+/// than `MAX_ASSET_DIMENSION` on a side are rejected before decoding, so a
+/// small malformed file can't claim gigabytes of pixels.
+pub fn decode_image(bytes: &[u8]) -> Result<RgbImage, SkinError> {
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_ASSET_DIMENSION);
+    limits.max_image_height = Some(MAX_ASSET_DIMENSION);
+    limits.max_alloc = Some(512 * 1024 * 1024);
+    reader.limits(limits);
+    Ok(reader.decode()?.to_rgb8())
 }
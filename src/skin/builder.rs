@@ -1,9 +1,12 @@
-use crate::core::{Rect, UiTree, Widget};
+use crate::core::{Geometry, Length, NodeId, Rect, UiTree, Widget};
+use crate::graphics::TextStyle;
 use crate::widgets::Container;
 
 use super::assets::LoadedSkin;
-use super::types::{PartType, SkinError, SkinPart, SkinWindow};
-use super::widgets::{Checkbox, DirectoryPicker, FilePicker, SkinButton, SkinImage, SkinVScroll, StaticText, TextInput};
+use super::types::{GaugeStyle, PartType, SkinError, SkinPart, SkinWindow, TextAlign};
+use super::widgets::{AnimatedSprite, CanvasWidget, Checkbox, ColorPicker, CustomPaint, DirectoryPicker, Dropdown, FilePathInput, FilePicker, Gauge, GroupBox, ImageView, Indicator, Knob, Link, ListView, MenuBar, Plot, ProgressBar, ScrollView, SevenSegment, SkinButton, SkinImage, PainterRegistry, SkinVScroll, SkinTimerDisplay, Slider, Spinner, Stack, StaticText, StatusBar, StepProgress, TabContainer, TextArea, TextInput, ToggleSwitch, TreeView, DEFAULT_END_ANGLE, DEFAULT_GROUP_BORDER_COLOR, DEFAULT_START_ANGLE, DEFAULT_STATUS_SEPARATOR_COLOR};
+#[cfg(feature = "video")]
+use super::widgets::SkinVideo;
 
 /// Builds a UiTree from a loaded skin.
 pub struct SkinBuilder;
@@ -12,6 +15,15 @@ impl SkinBuilder {
     /// Build a UiTree from a loaded skin.
     /// Returns the tree and window configuration.
     pub fn build(skin: &LoadedSkin) -> Result<(UiTree, SkinWindow), SkinError> {
+        Self::build_with_painters(skin, &PainterRegistry::new())
+    }
+
+    /// Build a UiTree from a loaded skin, resolving `custom_paint` parts
+    /// against the given painter registry.
+    pub fn build_with_painters(
+        skin: &LoadedSkin,
+        painters: &PainterRegistry,
+    ) -> Result<(UiTree, SkinWindow), SkinError> {
         let mut tree = UiTree::new();
         let window = &skin.skin.window;
 
@@ -19,30 +31,165 @@ impl SkinBuilder {
         let root_container = Container::transparent(window.width, window.height);
         let root_id = tree.add(root_container, None);
         tree.set_bounds(root_id, Rect::new(0, 0, window.width, window.height));
+        tree.set_insets(window.insets);
+
+        // Create widgets and add to tree as children of root
+        Self::add_parts(&mut tree, &skin.skin.parts, root_id, skin, painters)?;
+        Self::show_active_panels(&mut tree);
+
+        Ok((tree, skin.skin.window.clone()))
+    }
+
+    /// Show the active tab's panel in every tab container and the active
+    /// child of every stack, and hide the others.
+    pub fn show_active_panels(tree: &mut UiTree) {
+        let containers: Vec<_> = tree
+            .iter_node_ids()
+            .filter_map(|id| {
+                let node = tree.get(id)?;
+                let widget = node.widget().as_any();
+                let active = match widget.downcast_ref::<TabContainer>() {
+                    Some(tabs) => tabs.active(),
+                    None => widget.downcast_ref::<Stack>()?.active(),
+                };
+                Some((node.children().to_vec(), active))
+            })
+            .collect();
+        for (panels, active) in containers {
+            for (index, panel) in panels.into_iter().enumerate() {
+                tree.set_visible(panel, index == active);
+            }
+        }
+    }
+
+    /// Add parts as children of `parent`, in z-order, placing them with
+    /// their geometry in the parent's bounds. Scroll view children are added below their
+    /// scroll view, relative to its top-left corner. Each tab of a tab
+    /// container gets a panel node below the tab strip holding its parts.
+    fn add_parts(
+        tree: &mut UiTree,
+        parts: &[SkinPart],
+        parent: NodeId,
+        skin: &LoadedSkin,
+        painters: &PainterRegistry,
+    ) -> Result<(), SkinError> {
+        let parent_bounds = tree.get(parent).map(|node| *node.bounds()).unwrap_or_default();
+        let safe_area = tree.safe_area();
 
         // Sort parts by z-order
-        let mut parts: Vec<_> = skin.skin.parts.iter().collect();
+        let mut parts: Vec<_> = parts.iter().collect();
         parts.sort_by_key(|p| p.z);
 
-        // Create widgets and add to tree as children of root
         for part in parts {
-            let widget = Self::create_widget(part, skin)?;
-            let bounds = Rect::new(part.x, part.y, part.width, part.height);
+            // Widgets sized to their content are built before it is measured
+            let geometry = Self::geometry(part);
+            let area = geometry.area(&parent_bounds, &safe_area);
+            let widget = Self::create_widget(part, geometry.fixed_size(&area), skin, painters)?;
+            let bounds = geometry.resolve(&area, widget.as_ref());
+            if bounds.width == 0 || bounds.height == 0 {
+                return Err(SkinError::InvalidValue(format!(
+                    "part '{}': has no size and no content to fit it to",
+                    part.id
+                )));
+            }
 
-            let node_id = tree.add_boxed(widget, Some(root_id));
+            let node_id = tree.add_boxed(widget, Some(parent));
             tree.set_bounds(node_id, bounds);
+            tree.set_geometry(node_id, geometry);
+            tree.set_name(node_id, part.id.clone());
+            if let Some(tooltip) = &part.tooltip {
+                tree.set_tooltip(node_id, tooltip.clone());
+            }
+            if part.color_effect.is_some() {
+                tree.set_effect(node_id, part.color_effect);
+            }
+            if !part.context_menu.is_empty() {
+                tree.set_context_menu(node_id, part.context_menu.clone());
+            }
+            if !part.animations.is_empty() {
+                tree.set_animations(node_id, part.animations.clone());
+            }
+
+            if !part.children.is_empty() {
+                Self::add_parts(tree, &part.children, node_id, skin, painters)?;
+            }
+            if let PartType::Embed { .. } = part.part_type
+                && let Some(embedded) = skin.embed(&part.id)
+            {
+                // Embedded parts come with their own assets
+                Self::add_parts(tree, &embedded.skin.parts, node_id, embedded, painters)?;
+            }
+
+            let strip_height = tree
+                .get(node_id)
+                .and_then(|node| node.widget().as_any().downcast_ref::<TabContainer>())
+                .map(TabContainer::strip_height);
+            if let Some(strip_height) = strip_height {
+                let panel_bounds = Rect::new(
+                    bounds.x,
+                    bounds.y + strip_height as i32,
+                    bounds.width,
+                    bounds.height.saturating_sub(strip_height),
+                );
+                // Panels fill the tab container below the strip as it resizes
+                let panel_geometry = Geometry::new(0, strip_height as i32, Length::Percent(1.0), Length::Fill);
+                for tab in &part.tabs {
+                    let container = Container::transparent(panel_bounds.width, panel_bounds.height);
+                    let panel = tree.add(container, Some(node_id));
+                    tree.set_bounds(panel, panel_bounds);
+                    tree.set_geometry(panel, panel_geometry);
+                    tree.set_name(panel, tab.id.clone());
+                    Self::add_parts(tree, &tab.children, panel, skin, painters)?;
+                }
+            }
         }
+        Ok(())
+    }
 
-        Ok((tree, skin.skin.window.clone()))
+    /// Get the geometry a part is placed with in its parent.
+    fn geometry(part: &SkinPart) -> Geometry {
+        Geometry {
+            min_width: part.min_width,
+            max_width: part.max_width,
+            min_height: part.min_height,
+            max_height: part.max_height,
+            safe_area: part.safe_area,
+            ..Geometry::new(part.x, part.y, part.width, part.height)
+        }
     }
 
-    fn create_widget(part: &SkinPart, skin: &LoadedSkin) -> Result<Box<dyn Widget>, SkinError> {
+    fn create_widget(
+        part: &SkinPart,
+        (width, height): (u32, u32),
+        skin: &LoadedSkin,
+        painters: &PainterRegistry,
+    ) -> Result<Box<dyn Widget>, SkinError> {
         match &part.part_type {
             PartType::Image { asset } => {
                 let image = skin
                     .get_image(asset)
                     .ok_or_else(|| SkinError::AssetNotFound(asset.clone()))?;
-                Ok(Box::new(SkinImage::new(image.clone())))
+                let mut skin_image = SkinImage::new(image.clone());
+                if let Some(slice) = skin.get_slice(asset) {
+                    skin_image = skin_image.with_slice(*slice);
+                }
+                if let Some(binding) = &part.binding {
+                    skin_image = skin_image.with_binding(binding.clone());
+                }
+                Ok(Box::new(skin_image))
+            }
+            PartType::ImageView { asset, skin_dir } => {
+                let mut view = ImageView::new(width, height).with_root(skin_dir);
+                if let Some(asset) = asset {
+                    let image = skin
+                        .get_image(asset)
+                        .ok_or_else(|| SkinError::AssetNotFound(asset.clone()))?;
+                    view = view.with_fallback(image.clone());
+                }
+                if let Some(binding) = &part.binding {
+                    view = view.with_binding(binding.clone());
+                }
+                Ok(Box::new(view))
             }
             PartType::Button => {
                 let draw = part
@@ -60,60 +207,74 @@ impl SkinBuilder {
                     .get_image(&draw.pressed)
                     .ok_or_else(|| SkinError::AssetNotFound(draw.pressed.clone()))?;
 
-                Ok(Box::new(SkinButton::new(
-                    normal.clone(),
-                    hover.clone(),
-                    pressed.clone(),
-                    part.action.clone(),
-                )))
+                let mut button = SkinButton::new(normal.clone(), hover.clone(), pressed.clone(), part.action.clone());
+                if let Some(slice) = skin.get_slice(&draw.normal) {
+                    button = button.with_slice(*slice);
+                }
+
+                if let Some(disabled) = &part.disabled {
+                    button = button.with_disabled_binding(disabled.clone());
+                }
+
+                Ok(Box::new(button))
             }
-            PartType::TextInput => {
+            PartType::IconButton => {
                 let draw = part
-                    .text_input_draw
+                    .icon_draw
                     .as_ref()
                     .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
 
-                let normal = skin
+                let strip = skin
+                    .get_image(&draw.strip)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.strip.clone()))?;
+                let length = if draw.vertical { strip.height() } else { strip.width() };
+                if length < draw.frames {
+                    return Err(SkinError::InvalidValue(format!(
+                        "part '{}': strip '{}' is too small for {} frames",
+                        part.id, draw.strip, draw.frames
+                    )));
+                }
+
+                let mut button = SkinButton::from_strip(strip, draw.frames, draw.vertical, part.action.clone());
+
+                if let Some(disabled) = &part.disabled {
+                    button = button.with_disabled_binding(disabled.clone());
+                }
+
+                Ok(Box::new(button))
+            }
+            PartType::TextInput => Ok(Box::new(Self::text_input(part, skin)?)),
+            PartType::FilePathInput { dialog } => {
+                let draw = part
+                    .browse_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+                let button_normal = skin
                     .get_image(&draw.normal)
                     .ok_or_else(|| SkinError::AssetNotFound(draw.normal.clone()))?;
-                let hover = skin
+                let button_hover = skin
                     .get_image(&draw.hover)
                     .ok_or_else(|| SkinError::AssetNotFound(draw.hover.clone()))?;
-                let focused = skin
-                    .get_image(&draw.focused)
-                    .ok_or_else(|| SkinError::AssetNotFound(draw.focused.clone()))?;
-                let invalid = draw.invalid.as_ref().and_then(|key| skin.get_image(key).cloned());
-
-                let mut text_input = TextInput::new(
-                    normal.clone(),
-                    hover.clone(),
-                    focused.clone(),
-                    invalid,
-                );
 
-                if let Some(action) = &part.action {
-                    text_input = text_input.with_on_change(action.clone());
+                let mut path_input =
+                    FilePathInput::new(Self::text_input(part, skin)?, button_normal.clone(), button_hover.clone())
+                        .with_dialog(*dialog);
+                if let Some(filter) = &part.filter {
+                    let filters = filter
+                        .split(',')
+                        .map(|extension| extension.trim().trim_start_matches('.').to_string())
+                        .filter(|extension| !extension.is_empty())
+                        .collect();
+                    path_input = path_input.with_filters(filters);
                 }
-                if let Some(color) = part.text_color {
-                    text_input = text_input.with_text_color(color);
-                }
-                if let Some(padding) = part.padding {
-                    text_input = text_input.with_padding(padding);
-                }
-                if let Some(size) = part.font_size {
-                    text_input = text_input.with_font_size(size);
-                }
-                if let Some(max) = part.max_length {
-                    text_input = text_input.with_max_length(max);
-                }
-                if let Some(validation) = &part.validation {
-                    text_input = text_input.with_validation(validation.clone());
+                if let Some(title) = &part.label {
+                    path_input = path_input.with_dialog_title(title.clone());
                 }
-                if let Some(binding) = &part.binding {
-                    text_input = text_input.with_binding(binding.clone());
+                if let Some(on_select) = &part.on_select {
+                    path_input = path_input.with_on_select(on_select.clone());
                 }
 
-                Ok(Box::new(text_input))
+                Ok(Box::new(path_input))
             }
             PartType::StaticText => {
                 let content = part.content.clone().unwrap_or_default();
@@ -131,15 +292,86 @@ impl SkinBuilder {
                 if let Some(valign) = part.vertical_align {
                     static_text = static_text.with_vertical_align(valign);
                 }
+                let style = Self::text_style(part, *static_text.text_style());
+                static_text = static_text.with_text_style(style);
                 if let Some(padding) = part.padding {
                     static_text = static_text.with_padding(padding);
                 }
                 if let Some(binding) = &part.binding {
                     static_text = static_text.with_binding(binding.clone());
                 }
+                if let Some(markup) = part.markup {
+                    static_text = static_text.with_markup(markup);
+                }
+                if let Some(marquee) = part.marquee {
+                    static_text = static_text.with_marquee(marquee);
+                }
 
                 Ok(Box::new(static_text))
             }
+            PartType::Link => {
+                let mut link = Link::new(part.content.clone().unwrap_or_default());
+
+                if let Some(size) = part.font_size {
+                    link = link.with_font_size(size);
+                }
+                if let Some(color) = part.text_color {
+                    link = link.with_text_color(color);
+                }
+                let style = Self::text_style(part, *link.text_style());
+                link = link.with_text_style(style);
+                if let Some(color) = part.hover_color {
+                    link = link.with_hover_color(color);
+                }
+                if let Some(padding) = part.padding {
+                    link = link.with_padding(padding);
+                }
+                if let Some(url) = &part.url {
+                    link = link.with_url(url.clone());
+                }
+                if let Some(action) = &part.action {
+                    link = link.with_action(action.clone());
+                }
+                if let Some(binding) = &part.binding {
+                    link = link.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(link))
+            }
+            PartType::Canvas => {
+                let mut canvas = CanvasWidget::new(width, height);
+
+                if let Some(color) = part.background_color {
+                    canvas = canvas.with_background(color);
+                }
+                if let Some(binding) = &part.binding {
+                    canvas = canvas.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(canvas))
+            }
+            PartType::ColorPicker => {
+                let mut picker = ColorPicker::new(width, height);
+
+                if let Some(binding) = &part.binding {
+                    picker = picker.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    picker = picker.with_action(action.clone());
+                }
+
+                Ok(Box::new(picker))
+            }
+            PartType::Embed { source } => {
+                let embedded = skin
+                    .embed(&part.id)
+                    .ok_or_else(|| SkinError::AssetNotFound(source.to_string_lossy().into_owned()))?;
+                // Sides the skin leaves unsized take the embedded window's size
+                let window = embedded.window();
+                let width = if width == 0 { window.width } else { width };
+                let height = if height == 0 { window.height } else { height };
+                Ok(Box::new(Container::transparent(width, height)))
+            }
             PartType::VScrollContainer => {
                 let scrollbar = part
                     .scrollbar
@@ -154,8 +386,8 @@ impl SkinBuilder {
                     .ok_or_else(|| SkinError::AssetNotFound(scrollbar.thumb.clone()))?;
 
                 let mut scroll = SkinVScroll::new(
-                    part.width,
-                    part.height,
+                    width,
+                    height,
                     track.clone(),
                     thumb.clone(),
                 );
@@ -167,12 +399,43 @@ impl SkinBuilder {
 
                 // Build child widget if present
                 if let Some(ref child_part) = part.child {
-                    let child_widget = Self::create_widget(child_part, skin)?;
+                    let size = Self::geometry(child_part).fixed_size(&Rect::new(0, 0, width, height));
+                    let child_widget = Self::create_widget(child_part, size, skin, painters)?;
                     scroll = scroll.with_child(child_widget);
                 }
 
                 Ok(Box::new(scroll))
             }
+            PartType::ScrollView => {
+                let scrollbar = part
+                    .scrollbar
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(format!("{} (scrollbar)", part.id)))?;
+
+                let track = skin
+                    .get_image(&scrollbar.track)
+                    .ok_or_else(|| SkinError::AssetNotFound(scrollbar.track.clone()))?;
+                let thumb = skin
+                    .get_image(&scrollbar.thumb)
+                    .ok_or_else(|| SkinError::AssetNotFound(scrollbar.thumb.clone()))?;
+
+                // Content extends to the lowest child unless set explicitly
+                let content_height = part.content_height.unwrap_or_else(|| {
+                    part.children
+                        .iter()
+                        .map(|child| {
+                            let (_, child_height) = Self::geometry(child).fixed_size(&Rect::new(0, 0, width, height));
+                            (child.y + child_height as i32).max(0) as u32
+                        })
+                        .max()
+                        .unwrap_or(0)
+                });
+
+                Ok(Box::new(
+                    ScrollView::new(width, height, track.clone(), thumb.clone())
+                        .with_content_height(content_height),
+                ))
+            }
             PartType::DirectoryPicker => {
                 let draw = part
                     .directory_picker_draw
@@ -249,8 +512,8 @@ impl SkinBuilder {
                     .ok_or_else(|| SkinError::AssetNotFound(draw.item_selected.clone()))?;
 
                 let mut picker = FilePicker::new(
-                    part.width,
-                    part.height,
+                    width,
+                    height,
                     picker_normal.clone(),
                     picker_hover.clone(),
                     picker_btn_normal.clone(),
@@ -295,6 +558,19 @@ impl SkinBuilder {
 
                 let mut checkbox = Checkbox::new(unchecked.clone(), checked.clone());
 
+                if let Some(key) = &draw.unchecked_hover {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    checkbox = checkbox.with_unchecked_hover(image.clone());
+                }
+                if let Some(key) = &draw.checked_hover {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    checkbox = checkbox.with_checked_hover(image.clone());
+                }
+
                 if let Some(ref label) = part.label {
                     checkbox = checkbox.with_label(label.clone());
                 }
@@ -316,6 +592,947 @@ impl SkinBuilder {
 
                 Ok(Box::new(checkbox))
             }
+            PartType::Slider => {
+                let draw = part
+                    .slider_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let track = skin
+                    .get_image(&draw.track)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.track.clone()))?;
+                let thumb = skin
+                    .get_image(&draw.thumb)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.thumb.clone()))?;
+
+                let mut slider = Slider::new(width, height, track.clone(), thumb.clone())
+                    .with_range(part.min.unwrap_or(0.0), part.max.unwrap_or(1.0));
+
+                if let Some(key) = &draw.thumb_hover {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    slider = slider.with_thumb_hover(image.clone());
+                }
+                if let Some(key) = &draw.thumb_pressed {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    slider = slider.with_thumb_pressed(image.clone());
+                }
+                if let Some(step) = part.step {
+                    slider = slider.with_step(step);
+                }
+                if let Some(binding) = &part.binding {
+                    slider = slider.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    slider = slider.with_action(action.clone());
+                }
+
+                Ok(Box::new(slider))
+            }
+            PartType::Knob => {
+                let draw = part
+                    .knob_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let strip = skin
+                    .get_image(&draw.strip)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.strip.clone()))?;
+                if strip.height() < draw.frames {
+                    return Err(SkinError::InvalidValue(format!(
+                        "part '{}': strip '{}' is shorter than {} frames",
+                        part.id, draw.strip, draw.frames
+                    )));
+                }
+
+                let mut knob = Knob::new(width, height, strip.clone(), draw.frames)
+                    .with_range(part.min.unwrap_or(0.0), part.max.unwrap_or(1.0))
+                    .with_drag(draw.drag);
+
+                if let Some(step) = part.step {
+                    knob = knob.with_step(step);
+                }
+                if let Some(binding) = &part.binding {
+                    knob = knob.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    knob = knob.with_action(action.clone());
+                }
+
+                Ok(Box::new(knob))
+            }
+            PartType::ToggleSwitch => {
+                let draw = part
+                    .toggle_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let off = skin
+                    .get_image(&draw.off)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.off.clone()))?;
+                let on = skin
+                    .get_image(&draw.on)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.on.clone()))?;
+
+                let mut switch = ToggleSwitch::new(width, height, off.clone(), on.clone());
+
+                if let Some(key) = &draw.thumb {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    switch = switch.with_thumb(image.clone());
+                }
+                if let Some(binding) = &part.binding {
+                    switch = switch.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    switch = switch.with_action(action.clone());
+                }
+
+                Ok(Box::new(switch))
+            }
+            PartType::Indicator => {
+                let draw = part
+                    .indicator_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let off = skin
+                    .get_image(&draw.off)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.off.clone()))?;
+                let on = skin
+                    .get_image(&draw.on)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.on.clone()))?;
+
+                let mut indicator = Indicator::new(width, height, off.clone(), on.clone());
+
+                if let Some(blink) = draw.blink {
+                    indicator = indicator.with_blink(blink);
+                }
+                if let Some(binding) = &part.binding {
+                    indicator = indicator.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(indicator))
+            }
+            PartType::Spinner => {
+                let draw = part
+                    .spinner_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let field = skin
+                    .get_image(&draw.field)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.field.clone()))?;
+                let up = skin
+                    .get_image(&draw.up)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.up.clone()))?;
+                let down = skin
+                    .get_image(&draw.down)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.down.clone()))?;
+
+                let mut spinner = Spinner::new(width, height, field.clone(), up.clone(), down.clone())
+                    .with_range(part.min.unwrap_or(0.0), part.max.unwrap_or(100.0));
+
+                if let Some(key) = &draw.up_pressed {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    spinner = spinner.with_up_pressed(image.clone());
+                }
+                if let Some(key) = &draw.down_pressed {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    spinner = spinner.with_down_pressed(image.clone());
+                }
+                if let Some(step) = part.step {
+                    spinner = spinner.with_step(step);
+                }
+                if let Some(color) = part.text_color {
+                    spinner = spinner.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    spinner = spinner.with_font_size(size);
+                }
+                if let Some(align) = part.text_align {
+                    spinner = spinner.with_text_align(align);
+                }
+                let style = Self::text_style(part, *spinner.text_style());
+                spinner = spinner.with_text_style(style);
+                if let Some(padding) = part.padding {
+                    spinner = spinner.with_padding(padding);
+                }
+                if let Some(binding) = &part.binding {
+                    spinner = spinner.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    spinner = spinner.with_action(action.clone());
+                }
+
+                Ok(Box::new(spinner))
+            }
+            PartType::Gauge => {
+                let draw = part
+                    .gauge_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let face = skin
+                    .get_image(&draw.face)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.face.clone()))?;
+
+                // Arcs read better a little wider than needles
+                let thickness = draw.thickness.unwrap_or(match draw.style {
+                    GaugeStyle::Needle => 3,
+                    GaugeStyle::Arc => 10,
+                });
+                let mut gauge = Gauge::new(width, height, face.clone())
+                    .with_style(draw.style)
+                    .with_range(part.min.unwrap_or(0.0), part.max.unwrap_or(100.0))
+                    .with_angles(
+                        draw.start_angle.unwrap_or(DEFAULT_START_ANGLE),
+                        draw.end_angle.unwrap_or(DEFAULT_END_ANGLE),
+                    )
+                    .with_thickness(thickness);
+
+                if let Some(color) = draw.color {
+                    gauge = gauge.with_color(color);
+                }
+                if let Some(warning) = draw.warning {
+                    gauge = gauge.with_warning(warning);
+                }
+                if let Some(color) = draw.warning_color {
+                    gauge = gauge.with_warning_color(color);
+                }
+                if let Some(danger) = draw.danger {
+                    gauge = gauge.with_danger(danger);
+                }
+                if let Some(color) = draw.danger_color {
+                    gauge = gauge.with_danger_color(color);
+                }
+                if let Some(binding) = &part.binding {
+                    gauge = gauge.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(gauge))
+            }
+            PartType::Plot => {
+                let draw = part.plot_draw.clone().unwrap_or_default();
+                let mut plot = Plot::new(width, height)
+                    .with_style(draw.style)
+                    .with_x_range(draw.x_min, draw.x_max)
+                    .with_y_range(draw.y_min, draw.y_max);
+
+                if draw.series.is_empty() {
+                    plot = plot.with_series(part.binding.clone().unwrap_or_else(|| part.id.clone()), None);
+                }
+                for series in draw.series {
+                    plot = plot.with_series(series.list, series.color);
+                }
+                if let Some(thickness) = draw.thickness {
+                    plot = plot.with_thickness(thickness);
+                }
+                if let Some(color) = draw.axis_color {
+                    plot = plot.with_axis_color(color);
+                }
+                if let Some(color) = part.background_color {
+                    plot = plot.with_background(color);
+                }
+
+                Ok(Box::new(plot))
+            }
+            PartType::AnimatedSprite => {
+                let draw = part
+                    .sprite_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let sheet = skin
+                    .get_image(&draw.sheet)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.sheet.clone()))?;
+                let columns = sheet.width() / draw.frame_width;
+                let rows = sheet.height() / draw.frame_height;
+                if (columns as u64 * rows as u64) < draw.frames as u64 {
+                    return Err(SkinError::InvalidValue(format!(
+                        "part '{}': sheet '{}' holds fewer than {} frames",
+                        part.id, draw.sheet, draw.frames
+                    )));
+                }
+
+                let mut sprite =
+                    AnimatedSprite::new(width, height, sheet.clone(), (draw.frame_width, draw.frame_height), draw.frames)
+                        .with_fps(draw.fps)
+                        .with_looping(part.looping.unwrap_or(true));
+
+                if part.autoplay.unwrap_or(false) {
+                    sprite = sprite.with_autoplay();
+                }
+                if let Some(binding) = &part.binding {
+                    sprite = sprite.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(sprite))
+            }
+            PartType::SevenSegment => {
+                let draw = part
+                    .segment_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let atlas = skin
+                    .get_image(&draw.atlas)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.atlas.clone()))?;
+                let glyphs = draw.glyphs.chars().count() as u32;
+                if atlas.width() < glyphs {
+                    return Err(SkinError::InvalidValue(format!(
+                        "part '{}': atlas '{}' is narrower than {} glyphs",
+                        part.id, draw.atlas, glyphs
+                    )));
+                }
+
+                let mut display = SevenSegment::new(width, height, atlas.clone(), &draw.glyphs)
+                    .with_spacing(draw.spacing)
+                    .with_align(part.text_align.unwrap_or(TextAlign::Right));
+
+                if let Some(digits) = draw.digits {
+                    display = display.with_digits(digits as usize, draw.pad);
+                }
+                if let Some(content) = &part.content {
+                    display = display.with_text(content.clone());
+                }
+                if let Some(binding) = &part.binding {
+                    display = display.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(display))
+            }
+            PartType::ProgressBar => {
+                let draw = part
+                    .progress_bar_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let track = skin
+                    .get_image(&draw.track)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.track.clone()))?;
+                let fill = skin
+                    .get_image(&draw.fill)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.fill.clone()))?;
+
+                let mut bar = ProgressBar::new(width, height, track.clone(), fill.clone());
+
+                if let Some(binding) = &part.binding {
+                    bar = bar.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(bar))
+            }
+            PartType::GroupBox => {
+                let draw = part.group_draw.clone().unwrap_or_default();
+                let mut group = GroupBox::new(width, height);
+
+                if let Some(key) = &draw.frame {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    // Corners from both ends must fit in the image
+                    let slice = draw.slice.unwrap_or(0);
+                    if slice.saturating_mul(2) >= image.width().min(image.height()) {
+                        return Err(SkinError::InvalidValue(format!(
+                            "part '{}': group slice {} leaves nothing of a {}x{} frame to stretch",
+                            part.id,
+                            slice,
+                            image.width(),
+                            image.height()
+                        )));
+                    }
+                    group = group.with_frame(image.clone(), slice);
+                } else if draw.border_color.is_some() || draw.thickness.is_some() {
+                    group = group.with_border(
+                        draw.border_color.unwrap_or(DEFAULT_GROUP_BORDER_COLOR),
+                        draw.thickness.unwrap_or(1),
+                    );
+                }
+                if let Some(title) = &part.label {
+                    group = group.with_title(title.clone());
+                }
+                if let Some(color) = part.background_color {
+                    group = group.with_background(color);
+                }
+                if let Some(color) = part.text_color {
+                    group = group.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    group = group.with_font_size(size);
+                }
+                if let Some(align) = part.text_align {
+                    group = group.with_align(align);
+                }
+
+                Ok(Box::new(group))
+            }
+            PartType::StatusBar => {
+                let draw = part
+                    .status_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let mut bar = StatusBar::new(width, height)
+                    .with_separator_color(draw.separator_color.unwrap_or(DEFAULT_STATUS_SEPARATOR_COLOR));
+                for segment in &draw.segments {
+                    let binding = segment.binding.clone().unwrap_or_else(|| format!("{}.{}", part.id, segment.id));
+                    bar = bar.with_segment(binding, segment.width, segment.align, segment.text_color);
+                }
+                if let Some(color) = part.background_color {
+                    bar = bar.with_background(color);
+                }
+                if let Some(color) = part.text_color {
+                    bar = bar.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    bar = bar.with_font_size(size);
+                }
+                if let Some(padding) = part.padding {
+                    bar = bar.with_padding(padding);
+                }
+
+                Ok(Box::new(bar))
+            }
+            PartType::Stack => {
+                let mut stack = Stack::new(width, height);
+                // Children are indexed in the z-order they are added in
+                let mut children: Vec<_> = part.children.iter().collect();
+                children.sort_by_key(|child| child.z);
+                for child in children {
+                    stack = stack.with_child(child.id.clone());
+                }
+                if let Some(color) = part.background_color {
+                    stack = stack.with_background(color);
+                }
+                if let Some(binding) = &part.binding {
+                    stack = stack.with_binding(binding.clone());
+                }
+                Ok(Box::new(stack))
+            }
+            PartType::TimerDisplay => {
+                let mut display = SkinTimerDisplay::new(width, height).with_round_up(part.round_up.unwrap_or(false));
+
+                if let Some(format) = &part.time_format {
+                    display = display.with_format(format.clone());
+                }
+                if let Some(color) = part.background_color {
+                    display = display.with_background(color);
+                }
+                if let Some(color) = part.text_color {
+                    display = display.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    display = display.with_font_size(size);
+                }
+                if let Some(align) = part.text_align {
+                    display = display.with_align(align);
+                }
+                if let Some(binding) = &part.binding {
+                    display = display.with_binding(binding.clone());
+                }
+
+                Ok(Box::new(display))
+            }
+            PartType::TabContainer => {
+                let draw = part
+                    .tabs_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let tab = skin
+                    .get_image(&draw.tab)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.tab.clone()))?;
+                let tab_hover = skin
+                    .get_image(&draw.tab_hover)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.tab_hover.clone()))?;
+
+                let mut tabs = TabContainer::new(width, height, tab.clone(), tab_hover.clone());
+
+                for tab in &part.tabs {
+                    tabs = tabs.with_tab(tab.id.clone(), tab.label.clone());
+                }
+                if let Some(key) = &draw.tab_active {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    tabs = tabs.with_tab_active(image.clone());
+                }
+                if let Some(key) = &draw.panel {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    tabs = tabs.with_panel(image.clone());
+                }
+                if let Some(color) = part.text_color {
+                    tabs = tabs.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    tabs = tabs.with_font_size(size);
+                }
+                if let Some(binding) = &part.binding {
+                    tabs = tabs.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    tabs = tabs.with_action(action.clone());
+                }
+
+                Ok(Box::new(tabs))
+            }
+            PartType::MenuBar => {
+                let draw = part
+                    .menu_bar_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let title = skin
+                    .get_image(&draw.title)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.title.clone()))?;
+                let title_hover = skin
+                    .get_image(&draw.title_hover)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.title_hover.clone()))?;
+                let item_normal = skin
+                    .get_image(&draw.item_normal)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.item_normal.clone()))?;
+                let item_hover = skin
+                    .get_image(&draw.item_hover)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.item_hover.clone()))?;
+
+                let mut menu_bar = MenuBar::new(
+                    width,
+                    height,
+                    title.clone(),
+                    title_hover.clone(),
+                    item_normal.clone(),
+                    item_hover.clone(),
+                );
+
+                for menu in &part.menus {
+                    menu_bar = menu_bar.with_menu(menu.label.clone(), menu.items.clone());
+                }
+                if let Some(key) = &draw.bar {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    menu_bar = menu_bar.with_bar(image.clone());
+                }
+                if let Some(key) = &draw.title_open {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    menu_bar = menu_bar.with_title_open(image.clone());
+                }
+                if let Some(color) = part.text_color {
+                    menu_bar = menu_bar.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    menu_bar = menu_bar.with_font_size(size);
+                }
+                if let Some(padding) = part.padding {
+                    menu_bar = menu_bar.with_padding(padding);
+                }
+
+                Ok(Box::new(menu_bar))
+            }
+            PartType::Dropdown => {
+                let draw = part
+                    .dropdown_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let normal = skin
+                    .get_image(&draw.normal)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.normal.clone()))?;
+                let hover = skin
+                    .get_image(&draw.hover)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.hover.clone()))?;
+                let item_normal = skin
+                    .get_image(&draw.item_normal)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.item_normal.clone()))?;
+                let item_hover = skin
+                    .get_image(&draw.item_hover)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.item_hover.clone()))?;
+
+                let mut dropdown = Dropdown::new(
+                    width,
+                    height,
+                    normal.clone(),
+                    hover.clone(),
+                    item_normal.clone(),
+                    item_hover.clone(),
+                );
+
+                if let Some(key) = &draw.open {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    dropdown = dropdown.with_open_image(image.clone());
+                }
+                if let Some(key) = &draw.item_selected {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    dropdown = dropdown.with_item_selected(image.clone());
+                }
+                if let Some(options) = &part.options {
+                    dropdown = dropdown.with_options(options.clone());
+                }
+                if let Some(list) = &part.options_list {
+                    dropdown = dropdown.with_options_list(list.clone());
+                }
+                if let Some(label) = &part.label {
+                    dropdown = dropdown.with_placeholder(label.clone());
+                }
+                if let Some(color) = part.text_color {
+                    dropdown = dropdown.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    dropdown = dropdown.with_font_size(size);
+                }
+                if let Some(padding) = part.padding {
+                    dropdown = dropdown.with_padding(padding);
+                }
+                if let Some(count) = part.visible_items {
+                    dropdown = dropdown.with_visible_items(count as usize);
+                }
+                if let Some(binding) = &part.binding {
+                    dropdown = dropdown.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    dropdown = dropdown.with_action(action.clone());
+                }
+
+                Ok(Box::new(dropdown))
+            }
+            PartType::TextArea => {
+                let draw = part
+                    .text_input_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let normal = skin
+                    .get_image(&draw.normal)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.normal.clone()))?;
+                let hover = skin
+                    .get_image(&draw.hover)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.hover.clone()))?;
+                let focused = skin
+                    .get_image(&draw.focused)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.focused.clone()))?;
+
+                let mut text_area = TextArea::new(normal.clone(), hover.clone(), focused.clone());
+                if let Some(slice) = skin.get_slice(&draw.normal) {
+                    text_area = text_area.with_slice(*slice);
+                }
+
+                if let Some(action) = &part.action {
+                    text_area = text_area.with_on_change(action.clone());
+                }
+                if let Some(color) = part.text_color {
+                    text_area = text_area.with_text_color(color).with_caret_color(color);
+                }
+                if let Some(padding) = part.padding {
+                    text_area = text_area.with_padding(padding);
+                }
+                if let Some(size) = part.font_size {
+                    text_area = text_area.with_font_size(size);
+                }
+                let style = Self::text_style(part, *text_area.text_style());
+                text_area = text_area.with_text_style(style);
+                if let Some(max) = part.max_length {
+                    text_area = text_area.with_max_length(max);
+                }
+                if let Some(binding) = &part.binding {
+                    text_area = text_area.with_binding(binding.clone());
+                }
+                if let Some(spellcheck) = part.spellcheck {
+                    text_area = text_area.with_spellcheck(spellcheck);
+                }
+
+                Ok(Box::new(text_area))
+            }
+            PartType::ListView => {
+                let draw = part
+                    .list_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let row = skin
+                    .get_image(&draw.row)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.row.clone()))?;
+                let row_hover = skin
+                    .get_image(&draw.row_hover)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.row_hover.clone()))?;
+
+                // The rows come from the store list named by `list`, or by the part id
+                let list = part.list.clone().unwrap_or_else(|| part.id.clone());
+                let mut list_view = ListView::new(width, height, list, row.clone(), row_hover.clone());
+
+                if let Some(key) = &draw.row_selected {
+                    let image = skin
+                        .get_image(key)
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))?;
+                    list_view = list_view.with_row_selected(image.clone());
+                }
+                if let Some(template) = &part.content {
+                    list_view = list_view.with_row_template(template.clone());
+                }
+                if let Some(color) = part.text_color {
+                    list_view = list_view.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    list_view = list_view.with_font_size(size);
+                }
+                if let Some(padding) = part.padding {
+                    list_view = list_view.with_padding(padding);
+                }
+                if let Some(binding) = &part.binding {
+                    list_view = list_view.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    list_view = list_view.with_action(action.clone());
+                }
+                if let Some(reorderable) = part.reorderable {
+                    list_view = list_view.with_reorderable(reorderable);
+                }
+                if let Some(action) = &part.on_reorder {
+                    list_view = list_view.with_reorder_action(action.clone());
+                }
+
+                Ok(Box::new(list_view))
+            }
+            PartType::TreeView => {
+                let draw = part
+                    .tree_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let image = |key: &String| {
+                    skin.get_image(key)
+                        .cloned()
+                        .ok_or_else(|| SkinError::AssetNotFound(key.clone()))
+                };
+
+                // The nodes come from the store list named by `list`, or by the part id
+                let list = part.list.clone().unwrap_or_else(|| part.id.clone());
+                let mut tree_view = TreeView::new(
+                    width,
+                    height,
+                    list,
+                    image(&draw.row)?,
+                    image(&draw.row_hover)?,
+                    image(&draw.expanded)?,
+                    image(&draw.collapsed)?,
+                );
+
+                if let Some(key) = &draw.row_selected {
+                    tree_view = tree_view.with_row_selected(image(key)?);
+                }
+                if let Some(template) = &part.content {
+                    tree_view = tree_view.with_row_template(template.clone());
+                }
+                if let Some(color) = part.text_color {
+                    tree_view = tree_view.with_text_color(color);
+                }
+                if let Some(size) = part.font_size {
+                    tree_view = tree_view.with_font_size(size);
+                }
+                if let Some(padding) = part.padding {
+                    tree_view = tree_view.with_padding(padding);
+                }
+                if let Some(binding) = &part.binding {
+                    tree_view = tree_view.with_binding(binding.clone());
+                }
+                if let Some(action) = &part.action {
+                    tree_view = tree_view.with_action(action.clone());
+                }
+
+                Ok(Box::new(tree_view))
+            }
+            PartType::WizardProgress => {
+                let draw = part
+                    .progress_draw
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+                let done = skin
+                    .get_image(&draw.done)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.done.clone()))?;
+                let current = skin
+                    .get_image(&draw.current)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.current.clone()))?;
+                let pending = skin
+                    .get_image(&draw.pending)
+                    .ok_or_else(|| SkinError::AssetNotFound(draw.pending.clone()))?;
+
+                Ok(Box::new(StepProgress::new(
+                    width,
+                    height,
+                    done.clone(),
+                    current.clone(),
+                    pending.clone(),
+                )))
+            }
+            PartType::CustomPaint => {
+                let name = part
+                    .painter
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(format!("{} (painter)", part.id)))?;
+
+                // Unregistered painters are allowed; the embedder may attach one later
+                let painter = painters.get(name);
+                if painter.is_none() {
+                    eprintln!("Warning: no painter registered for '{}' (part '{}')", name, part.id);
+                }
+
+                Ok(Box::new(CustomPaint::new(
+                    name.clone(),
+                    painter,
+                    width,
+                    height,
+                )))
+            }
+            #[cfg(feature = "video")]
+            PartType::Video => {
+                let source = part
+                    .source
+                    .as_ref()
+                    .ok_or_else(|| SkinError::MissingDrawSection(format!("{} (source)", part.id)))?;
+
+                let mut video = SkinVideo::new(source.clone(), width, height)
+                    .with_looping(part.looping.unwrap_or(false));
+                if let Some(binding) = &part.binding {
+                    video = video.with_binding(binding.clone());
+                }
+                if part.autoplay.unwrap_or(false) {
+                    video.play();
+                }
+
+                Ok(Box::new(video))
+            }
+            #[cfg(not(feature = "video"))]
+            PartType::Video => Err(SkinError::InvalidPartType(format!(
+                "video (part '{}' requires the 'video' feature)",
+                part.id
+            ))),
+        }
+    }
+
+    /// Build a text input part, or the text field of a file path input.
+    fn text_input(part: &SkinPart, skin: &LoadedSkin) -> Result<TextInput, SkinError> {
+        let draw = part
+            .text_input_draw
+            .as_ref()
+            .ok_or_else(|| SkinError::MissingDrawSection(part.id.clone()))?;
+
+        let normal = skin
+            .get_image(&draw.normal)
+            .ok_or_else(|| SkinError::AssetNotFound(draw.normal.clone()))?;
+        let hover = skin
+            .get_image(&draw.hover)
+            .ok_or_else(|| SkinError::AssetNotFound(draw.hover.clone()))?;
+        let focused = skin
+            .get_image(&draw.focused)
+            .ok_or_else(|| SkinError::AssetNotFound(draw.focused.clone()))?;
+        let invalid = draw.invalid.as_ref().and_then(|key| skin.get_image(key).cloned());
+
+        let mut text_input = TextInput::new(
+            normal.clone(),
+            hover.clone(),
+            focused.clone(),
+            invalid,
+        );
+        if let Some(slice) = skin.get_slice(&draw.normal) {
+            text_input = text_input.with_slice(*slice);
+        }
+
+        if let Some(action) = &part.action {
+            text_input = text_input.with_on_change(action.clone());
+        }
+        if let Some(color) = part.text_color {
+            text_input = text_input.with_text_color(color);
+        }
+        if let Some(padding) = part.padding {
+            text_input = text_input.with_padding(padding);
+        }
+        if let Some(size) = part.font_size {
+            text_input = text_input.with_font_size(size);
+        }
+        if let Some(valign) = part.vertical_align {
+            text_input = text_input.with_vertical_align(valign);
+        }
+        let style = Self::text_style(part, *text_input.text_style());
+        text_input = text_input.with_text_style(style);
+        if let Some(max) = part.max_length {
+            text_input = text_input.with_max_length(max);
+        }
+        if let Some(validation) = &part.validation {
+            text_input = text_input.with_validation(validation.clone());
+        }
+        if let Some(flash) = part.flash_invalid {
+            text_input = text_input.with_flash_invalid(flash);
+        }
+        if let Some(mask) = &part.mask {
+            text_input = text_input.with_mask(mask);
+        }
+        if let Some(format) = part.number_format {
+            text_input = text_input.with_number_format(format);
+        }
+        if let Some(placeholder) = &part.placeholder {
+            // Spaced like the text, in a dimmer color
+            let mut placeholder_style = Self::text_style(part, *text_input.placeholder_style());
+            if let Some(color) = part.placeholder_color {
+                placeholder_style.color = color;
+            }
+            text_input = text_input
+                .with_placeholder(placeholder.clone())
+                .with_placeholder_style(placeholder_style);
+        }
+        if let Some(color) = part.selection_color {
+            text_input = text_input.with_selection_color(color);
+        }
+        if let Some(password) = part.password {
+            text_input = text_input.with_password(password);
+        }
+        if let Some(reveal) = &part.reveal {
+            text_input = text_input.with_reveal_binding(reveal.clone());
+        }
+        if let Some(binding) = &part.binding {
+            text_input = text_input.with_binding(binding.clone());
+        }
+
+        Ok(text_input)
+    }
+
+    /// Apply a part's letter spacing, line height, tab width, outline,
+    /// shadow and digit spacing to a style.
+    fn text_style(part: &SkinPart, mut style: TextStyle) -> TextStyle {
+        if let Some(spacing) = part.letter_spacing {
+            style = style.with_tracking(spacing);
+        }
+        if let Some(line_height) = part.line_height {
+            style = style.with_line_height(line_height);
+        }
+        if let Some(tab_width) = part.tab_width {
+            style = style.with_tab_width(tab_width);
+        }
+        if let Some(outline) = part.text_outline {
+            style = style.with_outline(outline);
+        }
+        if let Some(shadow) = part.text_shadow {
+            style = style.with_shadow(shadow);
+        }
+        if let Some(tabular) = part.tabular_numbers {
+            style = style.with_tabular_numbers(tabular);
         }
+        style
     }
 }
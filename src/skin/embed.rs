@@ -0,0 +1,209 @@
+//! Embedded skins.
+//!
+//! A part with `"type": "embed"` shows another skin, such as a clock from a
+//! pack of dashboard widgets, in its region. The embedded skin is loaded with
+//! its own assets and its parts are placed relative to the embed part; its
+//! window size is the part's default size. Part ids, store keys and action
+//! names in the embedded skin are namespaced under the embed part's id, so a
+//! clock embedded as `clock` reads `clock.time` where it would read `time` on
+//! its own and its `start` button triggers `clock.start`, and two copies of a
+//! widget don't share state. Palette names go under the id after the
+//! `palette.` prefix (`palette.clock.accent`), where recoloring looks for
+//! them.
+
+use std::path::{Path, PathBuf};
+
+use super::palette::PALETTE_KEY_PREFIX;
+use super::types::{PartType, Skin, SkinPart};
+
+/// Deepest embeds may nest, which also stops skins embedding themselves.
+pub const MAX_EMBED_DEPTH: usize = 4;
+
+/// Get the skin file of an embed source: `skin.json` in a directory, or the
+/// file itself.
+pub(crate) fn skin_file(source: &Path) -> PathBuf {
+    if source.is_dir() {
+        source.join("skin.json")
+    } else {
+        source.to_path_buf()
+    }
+}
+
+/// Get the ids and sources of the embed parts among `parts`, including
+/// those inside containers and tabs.
+pub(crate) fn embeds(parts: &[SkinPart]) -> Vec<(&str, &Path)> {
+    let mut found = Vec::new();
+    for part in parts {
+        if let PartType::Embed { source } = &part.part_type {
+            found.push((part.id.as_str(), source.as_path()));
+        }
+        found.extend(embeds(&part.children));
+        for tab in &part.tabs {
+            found.extend(embeds(&tab.children));
+        }
+        if let Some(child) = &part.child {
+            found.extend(embeds(std::slice::from_ref(child.as_ref())));
+        }
+    }
+    found
+}
+
+/// Move the part ids, store keys, action names and palette names of an
+/// embedded skin under `namespace`.
+pub(crate) fn namespace_skin(skin: &mut Skin, namespace: &str) {
+    namespace_parts(&mut skin.parts, namespace);
+    skin.palette = std::mem::take(&mut skin.palette)
+        .into_iter()
+        .map(|(name, color)| (format!("{}.{}", namespace, name), color))
+        .collect();
+}
+
+/// Get `key` moved under `namespace`, keeping a `palette.` prefix in front.
+fn namespaced(key: &str, namespace: &str) -> String {
+    match key.strip_prefix(PALETTE_KEY_PREFIX) {
+        Some(name) => format!("{}{}.{}", PALETTE_KEY_PREFIX, namespace, name),
+        None => format!("{}.{}", namespace, key),
+    }
+}
+
+/// Move the ids, store keys and actions of `parts` under `namespace`.
+fn namespace_parts(parts: &mut [SkinPart], namespace: &str) {
+    let key = |key: &mut String| *key = namespaced(key, namespace);
+    for part in parts {
+        key(&mut part.id);
+        // Bindings starting with `.` refer to the entry of a repeated part
+        for binding in [
+            &mut part.binding,
+            &mut part.reveal,
+            &mut part.disabled,
+            &mut part.options_list,
+            &mut part.list,
+            &mut part.repeat,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !binding.starts_with('.') {
+                key(binding);
+            }
+        }
+        for animation in &mut part.animations {
+            key(&mut animation.key);
+        }
+        for action in [&mut part.action, &mut part.on_select, &mut part.on_reorder].into_iter().flatten() {
+            key(action);
+        }
+        let menus = part.menus.iter_mut().flat_map(|menu| menu.items.iter_mut());
+        for item in part.context_menu.iter_mut().chain(menus) {
+            key(&mut item.action);
+        }
+        if let Some(plot) = &mut part.plot_draw {
+            for series in &mut plot.series {
+                key(&mut series.list);
+            }
+        }
+        if let Some(status) = &mut part.status_draw {
+            for binding in status.segments.iter_mut().filter_map(|segment| segment.binding.as_mut()) {
+                key(binding);
+            }
+        }
+        for text in [&mut part.content, &mut part.label].into_iter().flatten() {
+            *text = namespace_placeholders(text, namespace);
+        }
+
+        namespace_parts(&mut part.children, namespace);
+        for tab in &mut part.tabs {
+            namespace_parts(&mut tab.children, namespace);
+        }
+        if let Some(child) = &mut part.child {
+            namespace_parts(std::slice::from_mut(child.as_mut()), namespace);
+        }
+    }
+}
+
+/// Move the store keys of `{{key}}` placeholders in `text` under
+/// `namespace`, leaving the row placeholders `{{.field}}` and `{{#}}`.
+fn namespace_placeholders(text: &str, namespace: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let key = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+        if key == "#" || key.starts_with('.') {
+            out.push_str(&rest[start..start + 2 + len + 2]);
+        } else {
+            out.push_str(&format!("{{{{{}}}}}", namespaced(key, namespace)));
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{Rect, Store};
+    use crate::skin::{fill_placeholders, LoadedSkin, SkinBuilder};
+
+    fn write_skin(path: &std::path::Path, width: u32, parts: &str) {
+        let json = format!(
+            r##"{{"skin": {{"name": "t", "author": "t", "version": "1"}},
+                "window": {{"width": {}, "height": 40}},
+                "assets": {{}},
+                "palette": {{"hand": "#ff00ff"}},
+                "parts": [{}]}}"##,
+            width, parts
+        );
+        std::fs::write(path, json).unwrap();
+    }
+
+    #[test]
+    fn test_embedded_skin_is_namespaced() {
+        let dir = std::env::temp_dir().join(format!("crix_embed_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("widgets/clock")).unwrap();
+        write_skin(
+            &dir.join("widgets/clock/skin.json"),
+            80,
+            r#"{"id": "face", "type": "static_text", "x": 5, "y": 5, "width": 70, "height": 20,
+                "binding": "time", "content": "{{zone}} time",
+                "context_menu": [{"label": "Reset", "action": "reset"}]},
+               {"id": "start", "type": "link", "x": 5, "y": 25, "width": 30, "height": 10,
+                "content": "Start", "action": "start"},
+               {"id": "tint", "type": "color_picker", "x": 40, "y": 25, "width": 30, "height": 10,
+                "binding": "palette.hand"}"#,
+        );
+        write_skin(
+            &dir.join("skin.json"),
+            300,
+            r#"{"id": "clock", "type": "embed", "source": "widgets/clock", "x": 100, "y": 0}"#,
+        );
+
+        let skin = LoadedSkin::load(&dir.join("skin.json")).unwrap();
+        let clock = skin.embed("clock").unwrap();
+        let face = &clock.skin.parts[0];
+        assert_eq!((face.id.as_str(), face.binding.as_deref()), ("clock.face", Some("clock.time")));
+        let mut store = Store::new();
+        store.set("clock.zone", "UTC");
+        assert_eq!(fill_placeholders(face.content.as_deref().unwrap(), &store), "UTC time");
+        assert_eq!(face.context_menu[0].action, "clock.reset");
+        assert_eq!(clock.skin.parts[1].action.as_deref(), Some("clock.start"));
+        assert_eq!(clock.skin.parts[2].binding.as_deref(), Some("palette.clock.hand"));
+        assert_eq!(clock.skin.palette.get("clock.hand"), Some(&0xFF00FF));
+
+        // The embed takes the embedded window's size, and its parts sit inside it
+        let (tree, _) = SkinBuilder::build(&skin).unwrap();
+        let bounds = |name: &str| tree.find_by_name(name).and_then(|id| tree.get(id)).map(|node| *node.bounds());
+        assert_eq!(bounds("clock"), Some(Rect::new(100, 0, 80, 40)));
+        assert_eq!(bounds("clock.face"), Some(Rect::new(105, 5, 70, 20)));
+
+        // A skin embedding itself is stopped
+        write_skin(&dir.join("loop.json"), 80, r#"{"id": "me", "type": "embed", "source": "loop.json", "x": 0, "y": 0}"#);
+        assert!(LoadedSkin::load(&dir.join("loop.json")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,21 +1,106 @@
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 
 use serde::Deserialize;
 
 use super::types::{
-    CheckboxDraw, DirectoryPickerDraw, FilePickerDraw, HitType, PartDraw, PartHit, PartType,
-    ScrollbarDraw, Skin, SkinError, SkinMeta, SkinPart, SkinWindow, TextAlign, TextInputDraw,
-    TextValidation, VerticalAlign,
+    CheckboxDraw, DirectoryPickerDraw, DropdownDraw, FilePickerDraw, GaugeDraw, GaugeStyle, GroupDraw, HitType, IndicatorDraw, ListDraw,
+    MenuBarDraw, NumberFormat, PartDraw, PartHit, PartType, KnobDraw, KnobDrag, Marquee, PlotDraw, PlotSeries, PlotStyle,
+    BrowseDraw, IconDraw, PathDialog, ProgressBarDraw, ProgressDraw,
+    ScrollbarDraw, SegmentDraw, Skin, SkinError, SkinMenu, SkinMeta, SkinPart, SkinTab, SkinWindow, SliderDraw, SpinnerDraw,
+    SpriteDraw, StatusDraw, StatusSegment, TabsDraw, ToggleDraw, TextAlign, TextInputDraw, TextValidation, TreeDraw, VerticalAlign,
 };
+use super::widgets::{is_time_format, DEFAULT_END_ANGLE, DEFAULT_SEGMENT_GLYPHS, DEFAULT_SPRITE_FPS, DEFAULT_START_ANGLE};
+use super::wizard::{Rule, ValidationRule, Wizard, WizardStep};
+use crate::core::{
+    Animation, AnimationEffect, AnimationTrigger, Easing, Insets, Length, MenuItem, DEFAULT_ANIMATION_DURATION,
+    DEFAULT_FLASH_COLOR, DEFAULT_SHAKE_DISTANCE,
+};
+use crate::graphics::{parse_draw_color, ColorEffect, NineSlice, TextOutline, TextShadow, MAX_LINE_WIDTH};
+use crate::platform::EdgeResistance;
+use crate::widgets::ConfettiConfig;
 
 #[derive(Deserialize)]
 struct SkinJson {
     skin: SkinMetaJson,
     window: SkinWindowJson,
-    assets: HashMap<String, String>,
+    assets: HashMap<String, AssetJson>,
+    #[serde(default)]
+    palette: HashMap<String, String>,
     #[serde(default)]
     parts: Vec<SkinPartJson>,
+    #[serde(default)]
+    wizard: Option<WizardJson>,
+    #[serde(default)]
+    confetti: Option<ConfettiJson>,
+}
+
+/// An image asset: its path, or its path and a nine-slice border.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AssetJson {
+    Path(String),
+    Sliced { path: String, border: BorderJson },
+}
+
+#[derive(Deserialize)]
+struct BorderJson {
+    #[serde(default)]
+    left: u32,
+    #[serde(default)]
+    right: u32,
+    #[serde(default)]
+    top: u32,
+    #[serde(default)]
+    bottom: u32,
+}
+
+#[derive(Deserialize)]
+struct ConfettiJson {
+    #[serde(default)]
+    count: Option<u32>,
+    #[serde(default)]
+    colors: Vec<String>,
+    #[serde(default)]
+    sprites: Vec<String>,
+    #[serde(default)]
+    gravity: Option<f32>,
+    #[serde(default)]
+    speed: Option<f32>,
+    #[serde(default)]
+    spread: Option<f32>,
+    /// Milliseconds.
+    #[serde(default)]
+    lifetime: Option<u64>,
+    #[serde(default)]
+    size: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct WizardJson {
+    steps: Vec<WizardStepJson>,
+    #[serde(default)]
+    on_finish: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WizardStepJson {
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    parts: Vec<String>,
+    #[serde(default)]
+    rules: Vec<ValidationRuleJson>,
+}
+
+#[derive(Deserialize)]
+struct ValidationRuleJson {
+    key: String,
+    rule: String,
+    #[serde(default)]
+    message: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -31,6 +116,36 @@ struct SkinWindowJson {
     height: u32,
     #[serde(default)]
     resizable: bool,
+    #[serde(default = "default_decorations")]
+    decorations: bool,
+    #[serde(default)]
+    safe_area: InsetsJson,
+    #[serde(default)]
+    edge_resistance: Option<String>,
+}
+
+fn default_decorations() -> bool {
+    true
+}
+
+#[derive(Deserialize, Default)]
+struct InsetsJson {
+    #[serde(default)]
+    top: u32,
+    #[serde(default)]
+    right: u32,
+    #[serde(default)]
+    bottom: u32,
+    #[serde(default)]
+    left: u32,
+}
+
+/// A part width or height: pixels, or a string such as `"50%"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LengthJson {
+    Pixels(u32),
+    Text(String),
 }
 
 #[derive(Deserialize)]
@@ -42,8 +157,20 @@ struct SkinPartJson {
     asset: Option<String>,
     x: i32,
     y: i32,
-    width: u32,
-    height: u32,
+    #[serde(default)]
+    width: Option<LengthJson>,
+    #[serde(default)]
+    height: Option<LengthJson>,
+    #[serde(default)]
+    min_width: Option<u32>,
+    #[serde(default)]
+    max_width: Option<u32>,
+    #[serde(default)]
+    min_height: Option<u32>,
+    #[serde(default)]
+    max_height: Option<u32>,
+    #[serde(default)]
+    safe_area: bool,
     #[serde(default)]
     z: i32,
     #[serde(default)]
@@ -51,20 +178,64 @@ struct SkinPartJson {
     #[serde(default)]
     draw: Option<PartDrawJson>,
     #[serde(default)]
+    icon_draw: Option<IconDrawJson>,
+    #[serde(default)]
     text_input_draw: Option<TextInputDrawJson>,
     #[serde(default)]
+    browse_draw: Option<BrowseDrawJson>,
+    #[serde(default)]
     directory_picker_draw: Option<DirectoryPickerDrawJson>,
     #[serde(default)]
     file_picker_draw: Option<FilePickerDrawJson>,
     #[serde(default)]
     checkbox_draw: Option<CheckboxDrawJson>,
     #[serde(default)]
+    slider_draw: Option<SliderDrawJson>,
+    #[serde(default)]
+    knob_draw: Option<KnobDrawJson>,
+    #[serde(default)]
+    toggle_draw: Option<ToggleDrawJson>,
+    #[serde(default)]
+    indicator_draw: Option<IndicatorDrawJson>,
+    #[serde(default)]
+    spinner_draw: Option<SpinnerDrawJson>,
+    #[serde(default)]
+    progress_bar_draw: Option<ProgressBarDrawJson>,
+    #[serde(default)]
+    gauge_draw: Option<GaugeDrawJson>,
+    #[serde(default)]
+    plot_draw: Option<PlotDrawJson>,
+    #[serde(default)]
+    segment_draw: Option<SegmentDrawJson>,
+    #[serde(default)]
+    sprite_draw: Option<SpriteDrawJson>,
+    #[serde(default)]
+    group_draw: Option<GroupDrawJson>,
+    #[serde(default)]
+    status_draw: Option<StatusDrawJson>,
+    #[serde(default)]
+    progress_draw: Option<ProgressDrawJson>,
+    #[serde(default)]
+    dropdown_draw: Option<DropdownDrawJson>,
+    #[serde(default)]
+    menu_bar_draw: Option<MenuBarDrawJson>,
+    #[serde(default)]
+    list_draw: Option<ListDrawJson>,
+    #[serde(default)]
+    tree_draw: Option<TreeDrawJson>,
+    #[serde(default)]
     scrollbar: Option<ScrollbarDrawJson>,
     #[serde(default)]
     hit: Option<PartHitJson>,
     #[serde(default)]
     text_color: Option<String>,
     #[serde(default)]
+    hover_color: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    background_color: Option<String>,
+    #[serde(default)]
     padding: Option<u32>,
     #[serde(default)]
     font_size: Option<f32>,
@@ -73,73 +244,487 @@ struct SkinPartJson {
     #[serde(default)]
     validation: Option<String>,
     #[serde(default)]
+    flash_invalid: Option<bool>,
+    #[serde(default)]
+    mask: Option<String>,
+    #[serde(default)]
+    placeholder: Option<String>,
+    #[serde(default)]
+    placeholder_color: Option<String>,
+    #[serde(default)]
+    selection_color: Option<String>,
+    #[serde(default)]
+    password: Option<bool>,
+    #[serde(default)]
+    reveal: Option<String>,
+    #[serde(default)]
+    disabled: Option<String>,
+    #[serde(default)]
+    number_format: Option<NumberFormatJson>,
+    #[serde(default)]
+    time_format: Option<String>,
+    #[serde(default)]
+    round_up: Option<bool>,
+    #[serde(default)]
     content: Option<String>,
     #[serde(default)]
+    marquee: Option<MarqueeJson>,
+    #[serde(default)]
     label: Option<String>,
     #[serde(default)]
     text_align: Option<String>,
     #[serde(default)]
     vertical_align: Option<String>,
     #[serde(default)]
+    baseline: Option<u32>,
+    #[serde(default)]
+    letter_spacing: Option<f32>,
+    #[serde(default)]
+    line_height: Option<f32>,
+    #[serde(default)]
+    tab_width: Option<f32>,
+    #[serde(default)]
+    text_outline: Option<TextOutlineJson>,
+    #[serde(default)]
+    text_shadow: Option<TextShadowJson>,
+    #[serde(default)]
+    tabular_numbers: Option<bool>,
+    #[serde(default)]
+    markup: Option<bool>,
+    #[serde(default)]
+    spellcheck: Option<bool>,
+    #[serde(default)]
+    tooltip: Option<String>,
+    #[serde(default)]
+    tint: Option<String>,
+    #[serde(default)]
+    grayscale: Option<f32>,
+    #[serde(default)]
+    hue_shift: Option<f32>,
+    #[serde(default)]
+    context_menu: Vec<MenuItemJson>,
+    #[serde(default)]
+    animations: Vec<AnimationJson>,
+    #[serde(default)]
     binding: Option<String>,
     #[serde(default)]
     content_height: Option<u32>,
     #[serde(default)]
-    child: Option<Box<SkinPartJson>>,
+    child: Option<Box<SkinPartJson>>,
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    on_select: Option<String>,
+    #[serde(default)]
+    dialog: Option<String>,
+    #[serde(default)]
+    painter: Option<String>,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default, rename = "loop")]
+    looping: Option<bool>,
+    #[serde(default)]
+    autoplay: Option<bool>,
+    #[serde(default)]
+    repeat: Option<String>,
+    #[serde(default)]
+    repeat_offset: Option<i32>,
+    #[serde(default)]
+    min: Option<f64>,
+    #[serde(default)]
+    max: Option<f64>,
+    #[serde(default)]
+    step: Option<f64>,
+    #[serde(default)]
+    options: Option<Vec<String>>,
+    #[serde(default)]
+    options_list: Option<String>,
+    #[serde(default)]
+    visible_items: Option<u32>,
+    #[serde(default)]
+    list: Option<String>,
+    #[serde(default)]
+    reorderable: Option<bool>,
+    #[serde(default)]
+    on_reorder: Option<String>,
+    #[serde(default)]
+    children: Vec<SkinPartJson>,
+    #[serde(default)]
+    tabs_draw: Option<TabsDrawJson>,
+    #[serde(default)]
+    tabs: Vec<SkinTabJson>,
+    #[serde(default)]
+    menus: Vec<SkinMenuJson>,
+}
+
+#[derive(Deserialize)]
+struct TabsDrawJson {
+    tab: String,
+    tab_hover: String,
+    #[serde(default)]
+    tab_active: Option<String>,
+    #[serde(default)]
+    panel: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SkinTabJson {
+    id: String,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    children: Vec<SkinPartJson>,
+}
+
+#[derive(Deserialize)]
+struct PartDrawJson {
+    normal: String,
+    hover: String,
+    pressed: String,
+}
+
+#[derive(Deserialize)]
+struct IconDrawJson {
+    strip: String,
+    #[serde(default)]
+    frames: Option<u32>,
+    #[serde(default)]
+    vertical: bool,
+}
+
+#[derive(Deserialize)]
+struct BrowseDrawJson {
+    normal: String,
+    hover: String,
+}
+
+#[derive(Deserialize)]
+struct TextInputDrawJson {
+    normal: String,
+    hover: String,
+    focused: String,
+    #[serde(default)]
+    invalid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ScrollbarDrawJson {
+    width: u32,
+    track: String,
+    thumb: String,
+}
+
+#[derive(Deserialize)]
+struct MenuItemJson {
+    label: String,
+    action: String,
+}
+
+#[derive(Deserialize)]
+struct AnimationJson {
+    on: String,
+    #[serde(default)]
+    when: Option<String>,
+    effect: String,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    distance: Option<u32>,
+    /// Milliseconds.
+    #[serde(default)]
+    duration: Option<u64>,
+    #[serde(default)]
+    easing: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SkinMenuJson {
+    label: String,
+    #[serde(default)]
+    items: Vec<MenuItemJson>,
+}
+
+#[derive(Deserialize)]
+struct TextOutlineJson {
+    #[serde(default)]
+    color: Option<String>,
+    width: u32,
+}
+
+#[derive(Deserialize)]
+struct NumberFormatJson {
+    #[serde(default)]
+    decimals: Option<usize>,
+    #[serde(default = "default_grouping")]
+    grouping: bool,
+}
+
+fn default_grouping() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct MarqueeJson {
+    #[serde(default = "default_marquee_speed")]
+    speed: f32,
+    #[serde(default = "default_pause_on_hover")]
+    pause_on_hover: bool,
+}
+
+fn default_marquee_speed() -> f32 {
+    40.0
+}
+
+fn default_pause_on_hover() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct TextShadowJson {
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    offset_x: i32,
+    #[serde(default)]
+    offset_y: i32,
+    #[serde(default)]
+    blur: u32,
+}
+
+#[derive(Deserialize)]
+struct DirectoryPickerDrawJson {
+    normal: String,
+    hover: String,
+    button_normal: String,
+    button_hover: String,
+}
+
+#[derive(Deserialize)]
+struct FilePickerDrawJson {
+    picker_normal: String,
+    picker_hover: String,
+    picker_btn_normal: String,
+    picker_btn_hover: String,
+    track: String,
+    thumb: String,
+    item_normal: String,
+    item_hover: String,
+    item_selected: String,
+}
+
+#[derive(Deserialize)]
+struct CheckboxDrawJson {
+    unchecked: String,
+    checked: String,
+    #[serde(default)]
+    unchecked_hover: Option<String>,
+    #[serde(default)]
+    checked_hover: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SliderDrawJson {
+    track: String,
+    thumb: String,
+    #[serde(default)]
+    thumb_hover: Option<String>,
+    #[serde(default)]
+    thumb_pressed: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KnobDrawJson {
+    strip: String,
+    frames: u32,
+    #[serde(default)]
+    drag: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ToggleDrawJson {
+    off: String,
+    on: String,
+    #[serde(default)]
+    thumb: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IndicatorDrawJson {
+    off: String,
+    on: String,
+    #[serde(default)]
+    blink: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SpinnerDrawJson {
+    field: String,
+    up: String,
+    down: String,
+    #[serde(default)]
+    up_pressed: Option<String>,
+    #[serde(default)]
+    down_pressed: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GaugeDrawJson {
+    face: String,
+    #[serde(default)]
+    style: Option<String>,
+    #[serde(default)]
+    start_angle: Option<f64>,
+    #[serde(default)]
+    end_angle: Option<f64>,
+    #[serde(default)]
+    thickness: Option<u32>,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    warning: Option<f64>,
+    #[serde(default)]
+    warning_color: Option<String>,
+    #[serde(default)]
+    danger: Option<f64>,
+    #[serde(default)]
+    danger_color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PlotDrawJson {
+    #[serde(default)]
+    style: Option<String>,
+    #[serde(default)]
+    series: Vec<PlotSeriesJson>,
+    #[serde(default)]
+    x_min: Option<f64>,
+    #[serde(default)]
+    x_max: Option<f64>,
+    #[serde(default)]
+    y_min: Option<f64>,
+    #[serde(default)]
+    y_max: Option<f64>,
+    #[serde(default)]
+    thickness: Option<u32>,
+    #[serde(default)]
+    axis_color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SegmentDrawJson {
+    atlas: String,
+    #[serde(default)]
+    glyphs: Option<String>,
+    #[serde(default)]
+    spacing: Option<u32>,
+    #[serde(default)]
+    digits: Option<u32>,
+    #[serde(default)]
+    pad: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SpriteDrawJson {
+    sheet: String,
+    frame_width: u32,
+    frame_height: u32,
+    frames: u32,
+    #[serde(default)]
+    fps: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct GroupDrawJson {
+    #[serde(default)]
+    frame: Option<String>,
+    #[serde(default)]
+    slice: Option<u32>,
+    #[serde(default)]
+    border_color: Option<String>,
+    #[serde(default)]
+    thickness: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct StatusDrawJson {
+    segments: Vec<StatusSegmentJson>,
+    #[serde(default)]
+    separator_color: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StatusSegmentJson {
+    id: String,
+    #[serde(default)]
+    binding: Option<String>,
+    #[serde(default)]
+    width: Option<LengthJson>,
     #[serde(default)]
-    filter: Option<String>,
+    align: Option<String>,
     #[serde(default)]
-    on_select: Option<String>,
+    text_color: Option<String>,
 }
 
 #[derive(Deserialize)]
-struct PartDrawJson {
-    normal: String,
-    hover: String,
-    pressed: String,
+struct PlotSeriesJson {
+    list: String,
+    #[serde(default)]
+    color: Option<String>,
 }
 
 #[derive(Deserialize)]
-struct TextInputDrawJson {
-    normal: String,
-    hover: String,
-    focused: String,
-    #[serde(default)]
-    invalid: Option<String>,
+struct ProgressBarDrawJson {
+    track: String,
+    fill: String,
 }
 
 #[derive(Deserialize)]
-struct ScrollbarDrawJson {
-    width: u32,
-    track: String,
-    thumb: String,
+struct ProgressDrawJson {
+    done: String,
+    current: String,
+    pending: String,
 }
 
 #[derive(Deserialize)]
-struct DirectoryPickerDrawJson {
+struct DropdownDrawJson {
     normal: String,
     hover: String,
-    button_normal: String,
-    button_hover: String,
+    #[serde(default)]
+    open: Option<String>,
+    item_normal: String,
+    item_hover: String,
+    #[serde(default)]
+    item_selected: Option<String>,
 }
 
 #[derive(Deserialize)]
-struct FilePickerDrawJson {
-    picker_normal: String,
-    picker_hover: String,
-    picker_btn_normal: String,
-    picker_btn_hover: String,
-    track: String,
-    thumb: String,
+struct MenuBarDrawJson {
+    #[serde(default)]
+    bar: Option<String>,
+    title: String,
+    title_hover: String,
+    #[serde(default)]
+    title_open: Option<String>,
     item_normal: String,
     item_hover: String,
-    item_selected: String,
 }
 
 #[derive(Deserialize)]
-struct CheckboxDrawJson {
-    unchecked: String,
-    checked: String,
+struct ListDrawJson {
+    row: String,
+    row_hover: String,
+    #[serde(default)]
+    row_selected: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TreeDrawJson {
+    row: String,
+    row_hover: String,
+    #[serde(default)]
+    row_selected: Option<String>,
+    expanded: String,
+    collapsed: String,
 }
 
 #[derive(Deserialize)]
@@ -152,11 +737,36 @@ impl Skin {
     /// Load a skin from a JSON file path.
     pub fn load(path: &Path) -> Result<Self, SkinError> {
         let content = std::fs::read_to_string(path)?;
-        let json: SkinJson = serde_json::from_str(&content)?;
+        Self::parse(&content, path.parent().unwrap_or(Path::new(".")))
+    }
+
+    /// Parse a skin from JSON text. Asset and media paths are resolved
+    /// relative to `base_path` but not checked.
+    pub fn parse(content: &str, base_path: &Path) -> Result<Self, SkinError> {
+        let json: SkinJson = serde_json::from_str(content)?;
 
-        let base_path = path.parent().unwrap_or(Path::new("."));
+        let mut assets = HashMap::new();
+        let mut slices = HashMap::new();
+        for (key, asset) in json.assets {
+            let path = match asset {
+                AssetJson::Path(path) => path,
+                AssetJson::Sliced { path, border } => {
+                    slices.insert(key.clone(), NineSlice::new(border.left, border.right, border.top, border.bottom));
+                    path
+                }
+            };
+            assets.insert(key, base_path.join(path));
+        }
 
-        Ok(Skin {
+        let mut palette = HashMap::new();
+        for (name, color) in json.palette {
+            let key = parse_draw_color(&color)
+                .ok_or_else(|| SkinError::InvalidValue(format!("palette color '{}' is not a hex color", color)))?;
+            palette.insert(name, key);
+        }
+        check_palette(&palette)?;
+
+        let skin = Skin {
             meta: SkinMeta {
                 name: json.skin.name,
                 author: json.skin.author,
@@ -166,21 +776,98 @@ impl Skin {
                 width: json.window.width,
                 height: json.window.height,
                 resizable: json.window.resizable,
+                decorations: json.window.decorations,
+                insets: Insets::new(
+                    json.window.safe_area.top,
+                    json.window.safe_area.right,
+                    json.window.safe_area.bottom,
+                    json.window.safe_area.left,
+                ),
+                edge_resistance: match json.window.edge_resistance.as_deref() {
+                    None | Some("elastic") => EdgeResistance::Elastic,
+                    Some("clamp") => EdgeResistance::Clamp,
+                    Some("free") => EdgeResistance::Free,
+                    Some(other) => {
+                        return Err(SkinError::InvalidValue(format!(
+                            "window edge_resistance must be free, clamp or elastic, got {:?}",
+                            other
+                        )))
+                    }
+                },
             },
-            assets: json
-                .assets
-                .into_iter()
-                .map(|(k, v)| (k, base_path.join(v)))
-                .collect(),
+            assets,
+            slices,
+            palette,
             parts: json
                 .parts
                 .into_iter()
-                .map(|p| Self::convert_part(p))
+                .map(|p| Self::convert_part(p, base_path))
                 .collect::<Result<Vec<_>, _>>()?,
+            wizard: json.wizard.map(Self::convert_wizard).transpose()?,
+            confetti: json.confetti.map(Self::convert_confetti).transpose()?.unwrap_or_default(),
+        };
+        check_window(&skin.window)?;
+        check_confetti(&skin.confetti)?;
+        Ok(skin)
+    }
+
+    fn convert_confetti(c: ConfettiJson) -> Result<ConfettiConfig, SkinError> {
+        let defaults = ConfettiConfig::default();
+        let colors = c
+            .colors
+            .iter()
+            .map(|color| {
+                parse_color(color)
+                    .ok_or_else(|| SkinError::InvalidValue(format!("confetti color '{}' is not a hex color", color)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ConfettiConfig {
+            count: c.count.unwrap_or(defaults.count),
+            colors: if colors.is_empty() { defaults.colors } else { colors },
+            sprites: c.sprites,
+            gravity: c.gravity.unwrap_or(defaults.gravity),
+            speed: c.speed.unwrap_or(defaults.speed),
+            spread: c.spread.unwrap_or(defaults.spread),
+            lifetime: c.lifetime.map_or(defaults.lifetime, Duration::from_millis),
+            size: c.size.unwrap_or(defaults.size),
+        })
+    }
+
+    fn convert_wizard(w: WizardJson) -> Result<Wizard, SkinError> {
+        let steps = w
+            .steps
+            .into_iter()
+            .map(|step| {
+                let rules = step
+                    .rules
+                    .into_iter()
+                    .map(|r| {
+                        let rule = Rule::parse(&r.rule).ok_or_else(|| {
+                            SkinError::InvalidRule(format!("'{}' for '{}' in step '{}'", r.rule, r.key, step.id))
+                        })?;
+                        Ok(ValidationRule {
+                            key: r.key,
+                            rule,
+                            message: r.message,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, SkinError>>()?;
+                Ok(WizardStep {
+                    id: step.id,
+                    title: step.title,
+                    parts: step.parts,
+                    rules,
+                })
+            })
+            .collect::<Result<Vec<_>, SkinError>>()?;
+
+        Ok(Wizard {
+            steps,
+            on_finish: w.on_finish,
         })
     }
 
-    fn convert_part(p: SkinPartJson) -> Result<SkinPart, SkinError> {
+    fn convert_part(p: SkinPartJson, base_path: &Path) -> Result<SkinPart, SkinError> {
         let part_type = match p.part_type.as_str() {
             "image" => {
                 let asset = p.asset.ok_or_else(|| {
@@ -188,13 +875,64 @@ impl Skin {
                 })?;
                 PartType::Image { asset }
             }
+            "image_view" => PartType::ImageView {
+                asset: p.asset,
+                skin_dir: base_path.to_path_buf(),
+            },
             "button" => PartType::Button,
+            "icon_button" => PartType::IconButton,
             "text_input" => PartType::TextInput,
+            "file_path_input" => PartType::FilePathInput {
+                dialog: match p.dialog.as_deref() {
+                    None | Some("open") => PathDialog::Open,
+                    Some("save") => PathDialog::Save,
+                    Some("folder") => PathDialog::Folder,
+                    Some(other) => {
+                        return Err(SkinError::InvalidValue(format!(
+                            "part '{}': dialog '{}' is not open, save or folder",
+                            p.id, other
+                        )));
+                    }
+                },
+            },
             "static_text" => PartType::StaticText,
             "vscroll_container" => PartType::VScrollContainer,
             "directory_picker" => PartType::DirectoryPicker,
             "file_picker" => PartType::FilePicker,
             "checkbox" => PartType::Checkbox,
+            "custom_paint" => PartType::CustomPaint,
+            "video" => PartType::Video,
+            "slider" => PartType::Slider,
+            "knob" => PartType::Knob,
+            "toggle_switch" => PartType::ToggleSwitch,
+            "indicator" => PartType::Indicator,
+            "spinner" => PartType::Spinner,
+            "wizard_progress" => PartType::WizardProgress,
+            "dropdown" => PartType::Dropdown,
+            "menu_bar" => PartType::MenuBar,
+            "text_area" => PartType::TextArea,
+            "scroll_view" => PartType::ScrollView,
+            "list_view" => PartType::ListView,
+            "tree_view" => PartType::TreeView,
+            "progress_bar" => PartType::ProgressBar,
+            "gauge" => PartType::Gauge,
+            "plot" => PartType::Plot,
+            "seven_segment" => PartType::SevenSegment,
+            "animated_sprite" => PartType::AnimatedSprite,
+            "group_box" => PartType::GroupBox,
+            "status_bar" => PartType::StatusBar,
+            "timer_display" => PartType::TimerDisplay,
+            "tab_container" => PartType::TabContainer,
+            "stack" => PartType::Stack,
+            "link" => PartType::Link,
+            "canvas" => PartType::Canvas,
+            "color_picker" => PartType::ColorPicker,
+            "embed" => {
+                let source = p.source.as_ref().ok_or_else(|| {
+                    SkinError::InvalidValue(format!("part '{}': embed parts need a 'source' skin", p.id))
+                })?;
+                PartType::Embed { source: base_path.join(source) }
+            }
             other => return Err(SkinError::InvalidPartType(other.to_string())),
         };
 
@@ -204,6 +942,12 @@ impl Skin {
             pressed: d.pressed,
         });
 
+        let icon_draw = p.icon_draw.map(|d| IconDraw {
+            strip: d.strip,
+            frames: d.frames.unwrap_or(4),
+            vertical: d.vertical,
+        });
+
         let text_input_draw = p.text_input_draw.map(|d| TextInputDraw {
             normal: d.normal,
             hover: d.hover,
@@ -211,6 +955,11 @@ impl Skin {
             invalid: d.invalid,
         });
 
+        let browse_draw = p.browse_draw.map(|d| BrowseDraw {
+            normal: d.normal,
+            hover: d.hover,
+        });
+
         let directory_picker_draw = p.directory_picker_draw.map(|d| DirectoryPickerDraw {
             normal: d.normal,
             hover: d.hover,
@@ -233,6 +982,87 @@ impl Skin {
         let checkbox_draw = p.checkbox_draw.map(|d| CheckboxDraw {
             unchecked: d.unchecked,
             checked: d.checked,
+            unchecked_hover: d.unchecked_hover,
+            checked_hover: d.checked_hover,
+        });
+
+        let slider_draw = p.slider_draw.map(|d| SliderDraw {
+            track: d.track,
+            thumb: d.thumb,
+            thumb_hover: d.thumb_hover,
+            thumb_pressed: d.thumb_pressed,
+        });
+
+        let knob_draw = p.knob_draw.map(|d| KnobDraw {
+            strip: d.strip,
+            frames: d.frames,
+            drag: match d.drag.as_deref() {
+                Some("circular") => KnobDrag::Circular,
+                _ => KnobDrag::Vertical,
+            },
+        });
+
+        let toggle_draw = p.toggle_draw.map(|d| ToggleDraw {
+            off: d.off,
+            on: d.on,
+            thumb: d.thumb,
+        });
+
+        let indicator_draw = p.indicator_draw.map(|d| IndicatorDraw {
+            off: d.off,
+            on: d.on,
+            blink: d.blink.map(Duration::from_millis),
+        });
+
+        let spinner_draw = p.spinner_draw.map(|d| SpinnerDraw {
+            field: d.field,
+            up: d.up,
+            down: d.down,
+            up_pressed: d.up_pressed,
+            down_pressed: d.down_pressed,
+        });
+
+        let progress_bar_draw = p.progress_bar_draw.map(|d| ProgressBarDraw {
+            track: d.track,
+            fill: d.fill,
+        });
+
+        let progress_draw = p.progress_draw.map(|d| ProgressDraw {
+            done: d.done,
+            current: d.current,
+            pending: d.pending,
+        });
+
+        let dropdown_draw = p.dropdown_draw.map(|d| DropdownDraw {
+            normal: d.normal,
+            hover: d.hover,
+            open: d.open,
+            item_normal: d.item_normal,
+            item_hover: d.item_hover,
+            item_selected: d.item_selected,
+        });
+
+        let menu_bar_draw = p.menu_bar_draw.map(|d| MenuBarDraw {
+            bar: d.bar,
+            title: d.title,
+            title_hover: d.title_hover,
+            title_open: d.title_open,
+            item_normal: d.item_normal,
+            item_hover: d.item_hover,
+        });
+
+        let list_draw = p.list_draw.map(|d| ListDraw {
+            row: d.row,
+            row_hover: d.row_hover,
+            row_selected: d.row_selected,
+        });
+
+        let tree_draw = p.tree_draw.map(|d| TreeDraw {
+            row: d.row,
+            row_hover: d.row_hover,
+            row_selected: d.row_selected,
+            expanded: d.expanded,
+            collapsed: d.collapsed,
         });
 
         let scrollbar = p.scrollbar.map(|s| ScrollbarDraw {
@@ -248,19 +1078,181 @@ impl Skin {
         });
 
         // Parse text_color from hex string like "0x000000"
-        let text_color = p.text_color.and_then(|s| {
-            let s = s.trim_start_matches("0x").trim_start_matches("0X");
-            u32::from_str_radix(s, 16).ok()
+        let text_color = p.text_color.as_deref().and_then(parse_color);
+        let hover_color = p.hover_color.as_deref().and_then(parse_color);
+        let background_color = p.background_color.as_deref().and_then(parse_color);
+        let placeholder_color = p.placeholder_color.as_deref().and_then(parse_color);
+        let selection_color = p.selection_color.as_deref().and_then(parse_color);
+
+        // Effect colors default to black, but a malformed one is an error
+        let effect_color = |color: Option<String>, field: &str| match color {
+            Some(color) => parse_color(&color)
+                .ok_or_else(|| SkinError::InvalidValue(format!("{} color '{}' is not a hex color", field, color))),
+            None => Ok(0x000000),
+        };
+        let text_outline = match p.text_outline {
+            Some(outline) => Some(TextOutline {
+                color: effect_color(outline.color, "text_outline")?,
+                width: outline.width,
+            }),
+            None => None,
+        };
+        let text_shadow = match p.text_shadow {
+            Some(shadow) => Some(TextShadow {
+                color: effect_color(shadow.color, "text_shadow")?,
+                offset: (shadow.offset_x, shadow.offset_y),
+                blur: shadow.blur,
+            }),
+            None => None,
+        };
+
+        // Gauge and plot colors are optional, but a malformed one is an error too
+        let optional_color = |color: Option<String>, field: &str| {
+            color
+                .map(|color| {
+                    parse_color(&color)
+                        .ok_or_else(|| SkinError::InvalidValue(format!("{} '{}' is not a hex color", field, color)))
+                })
+                .transpose()
+        };
+
+        // Grayscale goes first, so a tint colors gray whatever the asset's hue
+        if p.grayscale.is_some_and(|amount| !(0.0..=1.0).contains(&amount)) {
+            return Err(SkinError::InvalidValue(format!("part '{}': grayscale must be between 0 and 1", p.id)));
+        }
+        let color_effect = [
+            p.grayscale.map(ColorEffect::grayscale),
+            p.hue_shift.map(ColorEffect::hue_shift),
+            optional_color(p.tint, "tint")?.map(ColorEffect::tint),
+        ]
+        .into_iter()
+        .flatten()
+        .reduce(|effect, next| effect.then(&next));
+
+        let gauge_draw = match p.gauge_draw {
+            Some(d) => Some(GaugeDraw {
+                face: d.face,
+                style: match d.style.as_deref() {
+                    Some("arc") => GaugeStyle::Arc,
+                    _ => GaugeStyle::Needle,
+                },
+                start_angle: d.start_angle,
+                end_angle: d.end_angle,
+                thickness: d.thickness,
+                color: optional_color(d.color, "gauge color")?,
+                warning: d.warning,
+                warning_color: optional_color(d.warning_color, "gauge warning_color")?,
+                danger: d.danger,
+                danger_color: optional_color(d.danger_color, "gauge danger_color")?,
+            }),
+            None => None,
+        };
+
+        let plot_draw = match p.plot_draw {
+            Some(d) => Some(PlotDraw {
+                style: match d.style.as_deref() {
+                    Some("bar") => PlotStyle::Bar,
+                    _ => PlotStyle::Line,
+                },
+                series: d
+                    .series
+                    .into_iter()
+                    .map(|series| {
+                        Ok(PlotSeries { list: series.list, color: optional_color(series.color, "plot series color")? })
+                    })
+                    .collect::<Result<_, SkinError>>()?,
+                x_min: d.x_min,
+                x_max: d.x_max,
+                y_min: d.y_min,
+                y_max: d.y_max,
+                thickness: d.thickness,
+                axis_color: optional_color(d.axis_color, "plot axis_color")?,
+            }),
+            None => None,
+        };
+
+        let segment_draw = match p.segment_draw {
+            Some(d) => {
+                let mut pad = d.pad.as_deref().unwrap_or(" ").chars();
+                let (Some(pad), None) = (pad.next(), pad.next()) else {
+                    return Err(SkinError::InvalidValue(format!("part '{}': segment pad must be one character", p.id)));
+                };
+                Some(SegmentDraw {
+                    atlas: d.atlas,
+                    glyphs: d.glyphs.unwrap_or_else(|| DEFAULT_SEGMENT_GLYPHS.to_string()),
+                    spacing: d.spacing.unwrap_or(0),
+                    digits: d.digits,
+                    pad,
+                })
+            }
+            None => None,
+        };
+
+        let sprite_draw = p.sprite_draw.map(|d| SpriteDraw {
+            sheet: d.sheet,
+            frame_width: d.frame_width,
+            frame_height: d.frame_height,
+            frames: d.frames,
+            fps: d.fps.unwrap_or(DEFAULT_SPRITE_FPS),
         });
 
+        let group_draw = match p.group_draw {
+            Some(d) => Some(GroupDraw {
+                frame: d.frame,
+                slice: d.slice,
+                border_color: optional_color(d.border_color, "group border_color")?,
+                thickness: d.thickness,
+            }),
+            None => None,
+        };
+
+        let status_draw = match p.status_draw {
+            Some(d) => Some(StatusDraw {
+                segments: d
+                    .segments
+                    .into_iter()
+                    .map(|segment| {
+                        let align = match segment.align.as_deref() {
+                            None | Some("left") => TextAlign::Left,
+                            Some("center") => TextAlign::Center,
+                            Some("right") => TextAlign::Right,
+                            Some(other) => {
+                                return Err(SkinError::InvalidValue(format!(
+                                    "part '{}': status segment align '{}' is not left, center or right",
+                                    p.id, other
+                                )));
+                            }
+                        };
+                        Ok(StatusSegment {
+                            width: match segment.width {
+                                None => Length::Fill,
+                                width => parse_length(width, &p.id)?,
+                            },
+                            text_color: optional_color(segment.text_color, "status segment text_color")?,
+                            id: segment.id,
+                            binding: segment.binding,
+                            align,
+                        })
+                    })
+                    .collect::<Result<_, SkinError>>()?,
+                separator_color: optional_color(d.separator_color, "status separator_color")?,
+            }),
+            None => None,
+        };
+
         // Parse validation mode
-        let validation = p.validation.map(|s| match s.as_str() {
-            "numeric" => TextValidation::Numeric,
-            "alpha" => TextValidation::Alpha,
-            "alphanumeric" => TextValidation::Alphanumeric,
-            "any" => TextValidation::Any,
-            pattern => TextValidation::Pattern(pattern.to_string()),
-        });
+        let validation = match p.validation.as_deref() {
+            None => None,
+            Some("numeric") => Some(TextValidation::Numeric),
+            Some("decimal") => Some(TextValidation::Decimal),
+            Some("alpha") => Some(TextValidation::Alpha),
+            Some("alphanumeric") => Some(TextValidation::Alphanumeric),
+            Some("any") => Some(TextValidation::Any),
+            Some(class) if class.starts_with('[') => Some(parse_char_class(class).ok_or_else(|| {
+                SkinError::InvalidValue(format!("part '{}': validation class '{}' is malformed", p.id, class))
+            })?),
+            Some(pattern) => Some(TextValidation::Pattern(pattern.to_string())),
+        };
 
         // Parse text alignment
         let text_align = p.text_align.map(|s| match s.as_str() {
@@ -270,50 +1262,836 @@ impl Skin {
             _ => TextAlign::Left,
         });
 
-        // Parse vertical alignment
-        let vertical_align = p.vertical_align.map(|s| match s.as_str() {
-            "top" => VerticalAlign::Top,
-            "center" => VerticalAlign::Center,
-            "bottom" => VerticalAlign::Bottom,
-            _ => VerticalAlign::Center,
+        // Parse vertical alignment; a baseline offset implies baseline alignment
+        let vertical_align = match (p.vertical_align.as_deref(), p.baseline) {
+            (Some("baseline") | None, Some(baseline)) => Some(VerticalAlign::Baseline(baseline)),
+            (Some("baseline"), None) => {
+                return Err(SkinError::InvalidValue(format!(
+                    "part '{}': baseline alignment needs a 'baseline' offset",
+                    p.id
+                )));
+            }
+            (Some(s), _) => Some(match s {
+                "top" => VerticalAlign::Top,
+                "center" => VerticalAlign::Center,
+                "bottom" => VerticalAlign::Bottom,
+                _ => VerticalAlign::Center,
+            }),
+            (None, None) => None,
+        };
+
+        // Resolve media sources relative to the skin, leaving URLs untouched
+        let source = p.source.map(|s| {
+            if s.contains("://") {
+                s
+            } else {
+                base_path.join(s).to_string_lossy().into_owned()
+            }
         });
 
         // Parse child recursively
         let child = match p.child {
-            Some(child_json) => Some(Box::new(Self::convert_part(*child_json)?)),
+            Some(child_json) => Some(Box::new(Self::convert_part(*child_json, base_path)?)),
             None => None,
         };
+        let children = p
+            .children
+            .into_iter()
+            .map(|child_json| Self::convert_part(child_json, base_path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tabs_draw = p.tabs_draw.map(|d| TabsDraw {
+            tab: d.tab,
+            tab_hover: d.tab_hover,
+            tab_active: d.tab_active,
+            panel: d.panel,
+        });
+        let tabs = p
+            .tabs
+            .into_iter()
+            .map(|tab| {
+                let children = tab
+                    .children
+                    .into_iter()
+                    .map(|child_json| Self::convert_part(child_json, base_path))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(SkinTab {
+                    // Tabs without a label show their id
+                    label: tab.label.unwrap_or_else(|| tab.id.clone()),
+                    id: tab.id,
+                    children,
+                })
+            })
+            .collect::<Result<Vec<_>, SkinError>>()?;
+
+        let menu_items = |items: Vec<MenuItemJson>| {
+            items
+                .into_iter()
+                .map(|item| MenuItem {
+                    label: item.label,
+                    action: item.action,
+                })
+                .collect::<Vec<_>>()
+        };
+        let animations = p
+            .animations
+            .into_iter()
+            .map(|a| {
+                let invalid = |what: String| SkinError::InvalidValue(format!("part '{}': animation {}", p.id, what));
+                let effect = match a.effect.as_str() {
+                    "flash" => AnimationEffect::Flash {
+                        color: match a.color {
+                            Some(color) => parse_color(&color)
+                                .ok_or_else(|| invalid(format!("color '{}' is not a hex color", color)))?,
+                            None => DEFAULT_FLASH_COLOR,
+                        },
+                    },
+                    "shake" => AnimationEffect::Shake { distance: a.distance.unwrap_or(DEFAULT_SHAKE_DISTANCE) },
+                    other => return Err(invalid(format!("effect '{}' is not flash or shake", other))),
+                };
+                let trigger = match a.when.as_deref() {
+                    Some("set") => AnimationTrigger::Set,
+                    _ => AnimationTrigger::Change,
+                };
+                let easing = match a.easing {
+                    Some(easing) => Easing::parse(&easing)
+                        .ok_or_else(|| invalid(format!("easing '{}' is not a known easing", easing)))?,
+                    None => Easing::Linear,
+                };
+                Ok(Animation {
+                    key: a.on,
+                    trigger,
+                    effect,
+                    duration: a.duration.map_or(DEFAULT_ANIMATION_DURATION, Duration::from_millis),
+                    easing,
+                })
+            })
+            .collect::<Result<Vec<_>, SkinError>>()?;
+        let menus = p
+            .menus
+            .into_iter()
+            .map(|menu| SkinMenu {
+                label: menu.label,
+                items: menu_items(menu.items),
+            })
+            .collect();
 
-        Ok(SkinPart {
+        let (width, height) = (parse_length(p.width, &p.id)?, parse_length(p.height, &p.id)?);
+        let part = SkinPart {
             id: p.id,
             part_type,
             x: p.x,
             y: p.y,
-            width: p.width,
-            height: p.height,
+            width,
+            height,
+            min_width: p.min_width,
+            max_width: p.max_width,
+            min_height: p.min_height,
+            max_height: p.max_height,
+            safe_area: p.safe_area,
             z: p.z,
             draw,
+            icon_draw,
             text_input_draw,
+            browse_draw,
             directory_picker_draw,
             file_picker_draw,
             checkbox_draw,
+            slider_draw,
+            knob_draw,
+            toggle_draw,
+            indicator_draw,
+            spinner_draw,
+            progress_bar_draw,
+            gauge_draw,
+            plot_draw,
+            segment_draw,
+            sprite_draw,
+            group_draw,
+            status_draw,
+            progress_draw,
+            dropdown_draw,
+            menu_bar_draw,
+            list_draw,
+            tree_draw,
+            tabs_draw,
             scrollbar,
             hit,
             action: p.action,
             text_color,
+            hover_color,
+            url: p.url,
+            background_color,
             padding: p.padding,
             font_size: p.font_size,
             max_length: p.max_length,
             validation,
+            flash_invalid: p.flash_invalid,
+            mask: p.mask,
+            placeholder: p.placeholder,
+            placeholder_color,
+            selection_color,
+            password: p.password,
+            reveal: p.reveal,
+            disabled: p.disabled,
+            number_format: p.number_format.map(|f| NumberFormat {
+                decimals: f.decimals,
+                grouping: f.grouping,
+            }),
+            time_format: p.time_format,
+            round_up: p.round_up,
             content: p.content,
+            marquee: p.marquee.map(|m| Marquee {
+                speed: m.speed,
+                pause_on_hover: m.pause_on_hover,
+            }),
             label: p.label,
             text_align,
             vertical_align,
+            letter_spacing: p.letter_spacing,
+            line_height: p.line_height,
+            tab_width: p.tab_width,
+            text_outline,
+            text_shadow,
+            tabular_numbers: p.tabular_numbers,
+            markup: p.markup,
+            spellcheck: p.spellcheck,
+            tooltip: p.tooltip,
+            color_effect,
+            context_menu: menu_items(p.context_menu),
+            animations,
             binding: p.binding,
             content_height: p.content_height,
             child,
             filter: p.filter,
             on_select: p.on_select,
-        })
+            painter: p.painter,
+            source,
+            looping: p.looping,
+            autoplay: p.autoplay,
+            repeat: p.repeat,
+            repeat_offset: p.repeat_offset,
+            min: p.min,
+            max: p.max,
+            step: p.step,
+            options: p.options,
+            options_list: p.options_list,
+            visible_items: p.visible_items,
+            list: p.list,
+            reorderable: p.reorderable,
+            on_reorder: p.on_reorder,
+            children,
+            tabs,
+            menus,
+        };
+        check_part(&part)?;
+        Ok(part)
+    }
+}
+
+/// Largest window or part width/height a skin may declare.
+pub(crate) const MAX_SKIN_DIMENSION: u32 = 8192;
+
+/// Largest distance of a part from the origin, and largest scroll content.
+const MAX_SKIN_COORDINATE: u32 = 1 << 20;
+
+/// Largest font size a part may ask for.
+const MAX_FONT_SIZE: f32 = 512.0;
+
+/// Largest line height multiplier a part may ask for.
+const MAX_LINE_HEIGHT: f32 = 16.0;
+
+/// Largest text outline width or shadow blur a part may ask for.
+const MAX_TEXT_EFFECT_RADIUS: u32 = 32;
+
+/// Fastest a marquee may scroll, in pixels per second.
+const MAX_MARQUEE_SPEED: f32 = 10_000.0;
+
+/// Longest an animation may play.
+const MAX_ANIMATION_DURATION: Duration = Duration::from_secs(10);
+
+/// Farthest a shake may move its part, in pixels.
+const MAX_SHAKE_DISTANCE: u32 = 100;
+
+/// Range of the y values of a cubic-bezier easing's control points.
+const BEZIER_Y_RANGE: std::ops::RangeInclusive<f32> = -1.0..=2.0;
+
+/// Stiffest a spring easing may be, per second squared.
+const MAX_SPRING_STIFFNESS: f32 = 10_000.0;
+
+/// Most damping a spring easing may have, per second.
+const MAX_SPRING_DAMPING: f32 = 1000.0;
+
+/// Most pieces a confetti burst may throw.
+const MAX_CONFETTI_COUNT: u32 = 2000;
+
+/// Largest confetti speed or gravity, in pixels per second (squared).
+const MAX_CONFETTI_SPEED: f32 = 10_000.0;
+
+/// Longest a confetti piece may live.
+const MAX_CONFETTI_LIFETIME: Duration = Duration::from_secs(10);
+
+/// Largest confetti rectangle, in pixels.
+const MAX_CONFETTI_SIZE: u32 = 64;
+
+/// Most decimals a number format may show.
+const MAX_NUMBER_DECIMALS: usize = 15;
+
+/// Most cells a seven-segment display may show.
+const MAX_SEGMENT_DIGITS: u32 = 64;
+
+/// Fastest frame rate a sprite may play at.
+const MAX_SPRITE_FPS: f32 = 1000.0;
+
+/// Shortest and longest time an indicator may blink on or off for.
+const MIN_BLINK_INTERVAL: Duration = Duration::from_millis(50);
+const MAX_BLINK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Parse a hex color like "0x000000".
+fn parse_color(s: &str) -> Option<u32> {
+    let s = s.trim_start_matches("0x").trim_start_matches("0X");
+    u32::from_str_radix(s, 16).ok()
+}
+
+/// Reject a window size that couldn't be allocated.
+fn check_window(window: &SkinWindow) -> Result<(), SkinError> {
+    if window.width > MAX_SKIN_DIMENSION || window.height > MAX_SKIN_DIMENSION {
+        return Err(SkinError::InvalidValue(format!(
+            "window size {}x{} is larger than {}",
+            window.width, window.height, MAX_SKIN_DIMENSION
+        )));
+    }
+    let insets = window.insets;
+    let (across, down) = (insets.left.saturating_add(insets.right), insets.top.saturating_add(insets.bottom));
+    if across > window.width || down > window.height {
+        return Err(SkinError::InvalidValue("window safe_area insets are larger than the window".to_string()));
+    }
+    Ok(())
+}
+
+/// Reject confetti settings that would throw pieces without bound.
+/// Reject palette names sharing a key color, which couldn't be told apart.
+fn check_palette(palette: &HashMap<String, u32>) -> Result<(), SkinError> {
+    let mut names: Vec<_> = palette.keys().collect();
+    names.sort();
+    for (index, first) in names.iter().enumerate() {
+        if let Some(second) = names[index + 1..].iter().find(|name| palette[**name] == palette[*first]) {
+            return Err(SkinError::InvalidValue(format!(
+                "palette: '{}' and '{}' have the same color",
+                first, second
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn check_confetti(confetti: &ConfettiConfig) -> Result<(), SkinError> {
+    let invalid = |what: String| Err(SkinError::InvalidValue(format!("confetti: {}", what)));
+
+    if confetti.count > MAX_CONFETTI_COUNT {
+        return invalid(format!("count is above {}", MAX_CONFETTI_COUNT));
+    }
+    if !(confetti.speed > 0.0 && confetti.speed <= MAX_CONFETTI_SPEED) {
+        return invalid(format!("speed {} is not between 0 and {}", confetti.speed, MAX_CONFETTI_SPEED));
+    }
+    if !(confetti.gravity.is_finite() && confetti.gravity.abs() <= MAX_CONFETTI_SPEED) {
+        return invalid(format!("gravity {} is not between -{max} and {max}", confetti.gravity, max = MAX_CONFETTI_SPEED));
+    }
+    if !(confetti.spread >= 0.0 && confetti.spread <= 360.0) {
+        return invalid(format!("spread {} is not between 0 and 360", confetti.spread));
+    }
+    if confetti.lifetime.is_zero() || confetti.lifetime > MAX_CONFETTI_LIFETIME {
+        return invalid(format!("lifetime must be between 1 and {} ms", MAX_CONFETTI_LIFETIME.as_millis()));
+    }
+    if confetti.size == 0 || confetti.size > MAX_CONFETTI_SIZE {
+        return invalid(format!("size must be between 1 and {}", MAX_CONFETTI_SIZE));
+    }
+    Ok(())
+}
+
+/// Parse a part width or height.
+fn parse_length(length: Option<LengthJson>, id: &str) -> Result<Length, SkinError> {
+    let text = match length {
+        None | Some(LengthJson::Pixels(0)) => return Ok(Length::Auto),
+        Some(LengthJson::Pixels(pixels)) => return Ok(Length::Pixels(pixels)),
+        Some(LengthJson::Text(text)) => text,
+    };
+    let invalid = || SkinError::InvalidValue(format!("part '{}': size '{}' is not understood", id, text));
+    let trimmed = text.trim();
+    match trimmed {
+        "auto" => return Ok(Length::Auto),
+        "fill" => return Ok(Length::Fill),
+        _ => {}
+    }
+    if let Some(percent) = trimmed.strip_suffix('%') {
+        let percent: f32 = percent.trim().parse().map_err(|_| invalid())?;
+        return Ok(Length::Percent(percent / 100.0));
+    }
+    let ratio = trimmed
+        .strip_prefix("aspect(")
+        .and_then(|rest| rest.strip_suffix(')'))
+        .ok_or_else(invalid)?;
+    let (width, height) = ratio.split_once(':').ok_or_else(invalid)?;
+    let width: f32 = width.trim().parse().map_err(|_| invalid())?;
+    let height: f32 = height.trim().parse().map_err(|_| invalid())?;
+    Ok(Length::Aspect(width, height))
+}
+
+/// Parse a regex-style character class such as `[^a-z_]` into inclusive
+/// ranges. A backslash escapes the next character, and a `-` that ends the
+/// class is literal.
+fn parse_char_class(pattern: &str) -> Option<TextValidation> {
+    let class = pattern.strip_prefix('[')?.strip_suffix(']')?;
+    let (negated, class) = match class.strip_prefix('^') {
+        Some(class) => (true, class),
+        None => (false, class),
+    };
+    // Characters paired with whether they were escaped
+    let mut chars = Vec::new();
+    let mut iter = class.chars();
+    while let Some(c) = iter.next() {
+        match c {
+            '\\' => chars.push((iter.next()?, true)),
+            c => chars.push((c, false)),
+        }
+    }
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let first = chars[i].0;
+        if i + 2 < chars.len() && chars[i + 1] == ('-', false) {
+            let last = chars[i + 2].0;
+            if last < first {
+                return None;
+            }
+            ranges.push((first, last));
+            i += 3;
+        } else {
+            ranges.push((first, first));
+            i += 1;
+        }
+    }
+    (!ranges.is_empty()).then_some(TextValidation::Class { ranges, negated })
+}
+
+/// Reject part values that would overflow layout arithmetic or make
+/// widgets allocate or draw without bound.
+fn check_part(part: &SkinPart) -> Result<(), SkinError> {
+    let invalid = |what: String| Err(SkinError::InvalidValue(format!("part '{}': {}", part.id, what)));
+
+    let (width, height) = (part.width.pixels(), part.height.pixels());
+    if width > MAX_SKIN_DIMENSION || height > MAX_SKIN_DIMENSION {
+        return invalid(format!("size {}x{} is larger than {}", width, height, MAX_SKIN_DIMENSION));
+    }
+    for length in [part.width, part.height] {
+        match length {
+            Length::Percent(fraction) if !(fraction > 0.0 && fraction <= 1.0) => {
+                return invalid("percentage sizes must be above 0% and at most 100%".to_string());
+            }
+            Length::Aspect(w, h) if ![w, h].iter().all(|&side| side > 0.0 && side <= MAX_SKIN_DIMENSION as f32) => {
+                return invalid(format!("aspect ratio sides must be above 0 and at most {}", MAX_SKIN_DIMENSION));
+            }
+            _ => {}
+        }
+    }
+    if matches!((part.width, part.height), (Length::Aspect(..), Length::Aspect(..))) {
+        return invalid("width and height can't both be aspect ratios".to_string());
+    }
+    for (min, max) in [(part.min_width, part.max_width), (part.min_height, part.max_height)] {
+        if min.into_iter().chain(max).any(|limit| limit > MAX_SKIN_DIMENSION) {
+            return invalid(format!("size limits must be at most {}", MAX_SKIN_DIMENSION));
+        }
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return invalid(format!("minimum size {} is above maximum {}", min, max));
+        }
+    }
+    if part.x.unsigned_abs() > MAX_SKIN_COORDINATE || part.y.unsigned_abs() > MAX_SKIN_COORDINATE {
+        return invalid(format!("position {},{} is out of range", part.x, part.y));
+    }
+    if part.repeat_offset.is_some_and(|offset| offset.unsigned_abs() > MAX_SKIN_DIMENSION) {
+        return invalid("repeat_offset is out of range".to_string());
+    }
+    if part.repeat.is_some() && part.repeat_offset.is_none() && part.height.pixels() == 0 {
+        return invalid("repeated part needs a height in pixels or repeat_offset".to_string());
+    }
+    if part.content_height.is_some_and(|height| height > MAX_SKIN_COORDINATE) {
+        return invalid("content_height is out of range".to_string());
+    }
+    if part
+        .number_format
+        .is_some_and(|format| format.decimals.is_some_and(|decimals| decimals > MAX_NUMBER_DECIMALS))
+    {
+        return invalid(format!("number_format decimals is above {}", MAX_NUMBER_DECIMALS));
+    }
+    if part
+        .marquee
+        .is_some_and(|marquee| !(marquee.speed > 0.0 && marquee.speed <= MAX_MARQUEE_SPEED))
+    {
+        return invalid(format!("marquee speed must be above 0 and at most {}", MAX_MARQUEE_SPEED));
+    }
+    for animation in &part.animations {
+        if animation.key.is_empty() {
+            return invalid("animation 'on' key is empty".to_string());
+        }
+        if animation.duration > MAX_ANIMATION_DURATION {
+            return invalid(format!("animation duration is above {} ms", MAX_ANIMATION_DURATION.as_millis()));
+        }
+        if let AnimationEffect::Shake { distance } = animation.effect
+            && distance > MAX_SHAKE_DISTANCE
+        {
+            return invalid(format!("animation shake distance is above {}", MAX_SHAKE_DISTANCE));
+        }
+        match animation.easing {
+            Easing::Linear => {}
+            Easing::CubicBezier { x1, y1, x2, y2 } => {
+                if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                    return invalid("animation cubic-bezier x values must be between 0 and 1".to_string());
+                }
+                if !BEZIER_Y_RANGE.contains(&y1) || !BEZIER_Y_RANGE.contains(&y2) {
+                    return invalid(format!(
+                        "animation cubic-bezier y values must be between {} and {}",
+                        BEZIER_Y_RANGE.start(),
+                        BEZIER_Y_RANGE.end()
+                    ));
+                }
+            }
+            Easing::Spring { stiffness, damping } => {
+                if !(stiffness > 0.0 && stiffness <= MAX_SPRING_STIFFNESS) {
+                    let most = MAX_SPRING_STIFFNESS;
+                    return invalid(format!("animation spring stiffness must be above 0 and at most {}", most));
+                }
+                if !(damping > 0.0 && damping <= MAX_SPRING_DAMPING) {
+                    let most = MAX_SPRING_DAMPING;
+                    return invalid(format!("animation spring damping must be above 0 and at most {}", most));
+                }
+            }
+        }
+    }
+    if part.mask.as_ref().is_some_and(|mask| !mask.is_ascii()) {
+        return invalid("mask must be ASCII".to_string());
+    }
+    if part.padding.is_some_and(|padding| padding > MAX_SKIN_DIMENSION) {
+        return invalid("padding is out of range".to_string());
+    }
+    if let Some(VerticalAlign::Baseline(baseline)) = part.vertical_align
+        && baseline > MAX_SKIN_DIMENSION
+    {
+        return invalid("baseline is out of range".to_string());
+    }
+    if part.scrollbar.as_ref().is_some_and(|scrollbar| scrollbar.width > MAX_SKIN_DIMENSION) {
+        return invalid("scrollbar width is out of range".to_string());
+    }
+    if part.time_format.as_deref().is_some_and(|format| !is_time_format(format)) {
+        return invalid("time_format needs an h, m, s or t field".to_string());
+    }
+    if let Some(size) = part.font_size
+        && !(size > 0.0 && size <= MAX_FONT_SIZE)
+    {
+        return invalid(format!("font_size {} is not between 0 and {}", size, MAX_FONT_SIZE));
+    }
+    if part.icon_draw.as_ref().is_some_and(|draw| !(3..=4).contains(&draw.frames)) {
+        return invalid("icon button frames must be 3 or 4".to_string());
+    }
+    if part
+        .knob_draw
+        .as_ref()
+        .is_some_and(|draw| draw.frames == 0 || draw.frames > MAX_SKIN_DIMENSION)
+    {
+        return invalid(format!("knob frames must be between 1 and {}", MAX_SKIN_DIMENSION));
+    }
+    if let Some(spacing) = part.letter_spacing
+        && !(spacing.is_finite() && spacing.abs() <= MAX_FONT_SIZE)
+    {
+        return invalid(format!("letter_spacing {} is not between -{} and {}", spacing, MAX_FONT_SIZE, MAX_FONT_SIZE));
+    }
+    if let Some(line_height) = part.line_height
+        && !(line_height > 0.0 && line_height <= MAX_LINE_HEIGHT)
+    {
+        return invalid(format!("line_height {} is not between 0 and {}", line_height, MAX_LINE_HEIGHT));
+    }
+    if let Some(tab_width) = part.tab_width
+        && !(tab_width >= 0.0 && tab_width <= MAX_SKIN_DIMENSION as f32)
+    {
+        return invalid(format!("tab_width {} is not between 0 and {}", tab_width, MAX_SKIN_DIMENSION));
+    }
+    if part.text_outline.is_some_and(|outline| outline.width > MAX_TEXT_EFFECT_RADIUS) {
+        return invalid(format!("text_outline width is above {}", MAX_TEXT_EFFECT_RADIUS));
+    }
+    if let Some(shadow) = part.text_shadow {
+        if shadow.blur > MAX_TEXT_EFFECT_RADIUS {
+            return invalid(format!("text_shadow blur is above {}", MAX_TEXT_EFFECT_RADIUS));
+        }
+        if shadow.offset.0.unsigned_abs() > MAX_SKIN_DIMENSION || shadow.offset.1.unsigned_abs() > MAX_SKIN_DIMENSION {
+            return invalid("text_shadow offset is out of range".to_string());
+        }
+    }
+    for (name, value) in [("min", part.min), ("max", part.max), ("step", part.step)] {
+        if value.is_some_and(|value| !value.is_finite()) {
+            return invalid(format!("{} is not a finite number", name));
+        }
+    }
+    if let (Some(min), Some(max)) = (part.min, part.max)
+        && min > max
+    {
+        return invalid(format!("min {} is greater than max {}", min, max));
+    }
+    if let Some(draw) = &part.gauge_draw {
+        if draw.thickness.is_some_and(|thickness| thickness == 0 || thickness > MAX_SKIN_DIMENSION) {
+            return invalid(format!("gauge thickness must be between 1 and {}", MAX_SKIN_DIMENSION));
+        }
+        for (name, value) in [
+            ("start_angle", draw.start_angle),
+            ("end_angle", draw.end_angle),
+            ("warning", draw.warning),
+            ("danger", draw.danger),
+        ] {
+            if value.is_some_and(|value| !value.is_finite()) {
+                return invalid(format!("gauge {} is not a finite number", name));
+            }
+        }
+        let sweep = draw.end_angle.unwrap_or(DEFAULT_END_ANGLE) - draw.start_angle.unwrap_or(DEFAULT_START_ANGLE);
+        if !(sweep > 0.0 && sweep <= 360.0) {
+            return invalid("gauge end_angle must be after start_angle, at most 360 degrees on".to_string());
+        }
+    }
+    if let Some(draw) = &part.segment_draw {
+        if draw.glyphs.is_empty() {
+            return invalid("segment glyphs can't be empty".to_string());
+        }
+        if draw.digits.is_some_and(|digits| digits == 0 || digits > MAX_SEGMENT_DIGITS) {
+            return invalid(format!("segment digits must be between 1 and {}", MAX_SEGMENT_DIGITS));
+        }
+        if draw.spacing > MAX_SKIN_DIMENSION {
+            return invalid(format!("segment spacing must be at most {}", MAX_SKIN_DIMENSION));
+        }
+    }
+    if let Some(draw) = &part.sprite_draw {
+        let sides = [draw.frame_width, draw.frame_height, draw.frames];
+        if sides.iter().any(|&side| side == 0 || side > MAX_SKIN_DIMENSION) {
+            return invalid(format!("sprite frame sizes and count must be between 1 and {}", MAX_SKIN_DIMENSION));
+        }
+        if !(draw.fps > 0.0 && draw.fps <= MAX_SPRITE_FPS) {
+            return invalid(format!("sprite fps {} is not between 0 and {}", draw.fps, MAX_SPRITE_FPS));
+        }
+    }
+    if let Some(blink) = part.indicator_draw.as_ref().and_then(|draw| draw.blink)
+        && !(MIN_BLINK_INTERVAL..=MAX_BLINK_INTERVAL).contains(&blink)
+    {
+        return invalid(format!(
+            "indicator blink must be between {} and {} ms",
+            MIN_BLINK_INTERVAL.as_millis(),
+            MAX_BLINK_INTERVAL.as_millis()
+        ));
+    }
+    if let Some(draw) = &part.group_draw {
+        if draw.thickness.is_some_and(|thickness| thickness == 0 || thickness > MAX_SKIN_DIMENSION) {
+            return invalid(format!("group thickness must be between 1 and {}", MAX_SKIN_DIMENSION));
+        }
+        if draw.slice.is_some() && draw.frame.is_none() {
+            return invalid("group slice needs a frame image".to_string());
+        }
+    }
+    if matches!(part.part_type, PartType::Stack) && part.children.is_empty() {
+        return invalid("stack needs at least one child".to_string());
+    }
+    if let Some(draw) = &part.status_draw {
+        if draw.segments.is_empty() {
+            return invalid("status bar needs at least one segment".to_string());
+        }
+        for (i, segment) in draw.segments.iter().enumerate() {
+            if segment.id.is_empty() || draw.segments[..i].iter().any(|other| other.id == segment.id) {
+                return invalid(format!("status segment id '{}' is empty or used twice", segment.id));
+            }
+            match segment.width {
+                Length::Pixels(pixels) if pixels > MAX_SKIN_DIMENSION => {
+                    return invalid(format!("status segment widths must be at most {}", MAX_SKIN_DIMENSION));
+                }
+                Length::Percent(fraction) if !(fraction > 0.0 && fraction <= 1.0) => {
+                    return invalid("status segment percentages must be above 0% and at most 100%".to_string());
+                }
+                Length::Aspect(..) => return invalid("status segment widths can't be aspect ratios".to_string()),
+                _ => {}
+            }
+        }
+    }
+    if let Some(draw) = &part.plot_draw {
+        if draw.thickness.is_some_and(|thickness| thickness == 0 || thickness > MAX_LINE_WIDTH) {
+            return invalid(format!("plot thickness must be between 1 and {}", MAX_LINE_WIDTH));
+        }
+        let ranges = [("x_min", draw.x_min), ("x_max", draw.x_max), ("y_min", draw.y_min), ("y_max", draw.y_max)];
+        for (name, value) in ranges {
+            if value.is_some_and(|value| !value.is_finite()) {
+                return invalid(format!("plot {} is not a finite number", name));
+            }
+        }
+        for (axis, min, max) in [("x", draw.x_min, draw.x_max), ("y", draw.y_min, draw.y_max)] {
+            if let (Some(min), Some(max)) = (min, max)
+                && min >= max
+            {
+                return invalid(format!("plot {}_min {} is not below {}_max {}", axis, min, axis, max));
+            }
+        }
+        if draw.series.iter().any(|series| series.list.is_empty()) {
+            return invalid("plot series list is empty".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_part(part: &str) -> Result<Skin, SkinError> {
+        let json = format!(
+            r#"{{"skin": {{"name": "t", "author": "t", "version": "1"}},
+                "window": {{"width": 100, "height": 100}},
+                "assets": {{}},
+                "parts": [{}]}}"#,
+            part
+        );
+        Skin::parse(&json, Path::new("."))
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_values() {
+        assert!(parse_part(r#"{"id": "a", "type": "slider", "x": 0, "y": 0, "width": 10, "height": 10}"#).is_ok());
+        assert!(parse_part(r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "content": "Fits"}"#).is_ok());
+        let part = r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "content": "Fits", "grayscale": 1, "tint": "0x3366FF"}"#;
+        let effect = parse_part(part).unwrap().parts[0].color_effect.unwrap();
+        assert_eq!(effect.apply(0xFF0000), ColorEffect::tint(0x363636).apply(0x3366FF));
+
+        // Values found by the fuzzer that used to overflow or panic
+        for part in [
+            r#"{"id": "a", "type": "static_text", "x": -2147483648, "y": 0, "width": 10, "height": 10}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 4000000000, "height": 10}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "repeat": "items"}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": "150%", "height": 10}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": "aspect(4:3)", "height": "aspect(3:4)"}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": "wide", "height": 10}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "min_height": 20, "max_height": 10}"#,
+            r#"{"id": "a", "type": "slider", "x": 0, "y": 0, "width": 10, "height": 10, "min": 5, "max": 1}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "font_size": 0}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "vertical_align": "baseline"}"#,
+            r#"{"id": "a", "type": "text_area", "x": 0, "y": 0, "width": 10, "height": 10, "line_height": 0}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "text_shadow": {"color": "red"}}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "tint": "teal"}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "grayscale": 1.5}"#,
+            r#"{"id": "a", "type": "icon_button", "x": 0, "y": 0, "icon_draw": {"strip": "s", "frames": 2}}"#,
+            r#"{"id": "a", "type": "knob", "x": 0, "y": 0, "width": 10, "height": 10, "knob_draw": {"strip": "k", "frames": 0}}"#,
+            r#"{"id": "a", "type": "gauge", "x": 0, "y": 0, "width": 10, "height": 10, "gauge_draw": {"face": "f", "end_angle": -180}}"#,
+            r#"{"id": "a", "type": "plot", "x": 0, "y": 0, "width": 10, "height": 10, "plot_draw": {"y_min": 5, "y_max": 5}}"#,
+            r#"{"id": "a", "type": "embed", "x": 0, "y": 0, "width": 10, "height": 10}"#,
+            r#"{"id": "a", "type": "seven_segment", "x": 0, "y": 0, "segment_draw": {"atlas": "d", "digits": 0}}"#,
+            r#"{"id": "a", "type": "seven_segment", "x": 0, "y": 0, "segment_draw": {"atlas": "d", "pad": "00"}}"#,
+            r#"{"id": "a", "type": "animated_sprite", "x": 0, "y": 0, "sprite_draw": {"sheet": "s", "frame_width": 8, "frame_height": 8, "frames": 0}}"#,
+            r#"{"id": "a", "type": "animated_sprite", "x": 0, "y": 0, "sprite_draw": {"sheet": "s", "frame_width": 8, "frame_height": 8, "frames": 4, "fps": 0}}"#,
+            r#"{"id": "a", "type": "group_box", "x": 0, "y": 0, "width": 10, "height": 10, "group_draw": {"thickness": 0}}"#,
+            r#"{"id": "a", "type": "group_box", "x": 0, "y": 0, "width": 10, "height": 10, "group_draw": {"slice": 4}}"#,
+            r#"{"id": "a", "type": "status_bar", "x": 0, "y": 0, "width": 10, "height": 10, "status_draw": {"segments": []}}"#,
+            r#"{"id": "a", "type": "status_bar", "x": 0, "y": 0, "width": 10, "height": 10, "status_draw": {"segments": [{"id": "s"}, {"id": "s"}]}}"#,
+            r#"{"id": "a", "type": "status_bar", "x": 0, "y": 0, "width": 10, "height": 10, "status_draw": {"segments": [{"id": "s", "width": "120%"}]}}"#,
+            r#"{"id": "a", "type": "timer_display", "x": 0, "y": 0, "width": 10, "height": 10, "time_format": "--"}"#,
+            r#"{"id": "a", "type": "stack", "x": 0, "y": 0, "width": 10, "height": 10}"#,
+            r#"{"id": "a", "type": "indicator", "x": 0, "y": 0, "indicator_draw": {"off": "a", "on": "b", "blink": 5}}"#,
+            r#"{"id": "a", "type": "file_path_input", "x": 0, "y": 0, "width": 10, "height": 10, "dialog": "print"}"#,
+            r#"{"id": "a", "type": "text_input", "x": 0, "y": 0, "width": 10, "height": 10, "mask": "€###"}"#,
+            r#"{"id": "a", "type": "text_input", "x": 0, "y": 0, "width": 10, "height": 10, "validation": "[z-a]"}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "marquee": {"speed": 0}}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "animations": [{"on": "k", "effect": "spin"}]}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "animations": [{"on": "k", "effect": "shake", "duration": 60000}]}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "animations": [{"on": "k", "effect": "shake", "easing": "bounce"}]}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "animations": [{"on": "k", "effect": "shake", "easing": "cubic-bezier(1.5, 0, 0.5, 1)"}]}"#,
+            r#"{"id": "a", "type": "static_text", "x": 0, "y": 0, "width": 10, "height": 10, "animations": [{"on": "k", "effect": "shake", "easing": "spring(100, 0)"}]}"#,
+        ] {
+            assert!(matches!(parse_part(part), Err(SkinError::InvalidValue(_))), "{}", part);
+        }
+    }
+
+    #[test]
+    fn test_confetti_settings() {
+        let skin = |confetti: &str| {
+            let json = format!(
+                r#"{{"skin": {{"name": "t", "author": "t", "version": "1"}},
+                    "window": {{"width": 100, "height": 100}},
+                    "assets": {{}},
+                    "confetti": {}}}"#,
+                confetti
+            );
+            Skin::parse(&json, Path::new("."))
+        };
+        let confetti = skin(r#"{"count": 20, "colors": ["0xff0000"], "lifetime": 1000}"#).unwrap().confetti;
+        assert_eq!((confetti.count, confetti.colors.as_slice()), (20, &[0xFF0000][..]));
+        assert_eq!(confetti.lifetime, Duration::from_secs(1));
+        assert_eq!(confetti.speed, ConfettiConfig::default().speed);
+
+        for bad in [r#"{"count": 100000}"#, r#"{"colors": ["pink"]}"#, r#"{"spread": 400}"#, r#"{"lifetime": 0}"#] {
+            assert!(matches!(skin(bad), Err(SkinError::InvalidValue(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_sliced_assets() {
+        let json = r#"{"skin": {"name": "t", "author": "t", "version": "1"},
+            "window": {"width": 100, "height": 100},
+            "assets": {"field": {"path": "field.png", "border": {"left": 4, "right": 4, "top": 3}}, "logo": "logo.png"}}"#;
+        let skin = Skin::parse(json, Path::new("skins")).unwrap();
+        assert_eq!(skin.assets["field"], Path::new("skins/field.png"));
+        assert_eq!(skin.slices.get("field"), Some(&NineSlice::new(4, 4, 3, 0)));
+        assert_eq!(skin.slices.get("logo"), None);
+    }
+
+    #[test]
+    fn test_palette_colors() {
+        let skin = |palette: &str| {
+            let json = format!(
+                r#"{{"skin": {{"name": "t", "author": "t", "version": "1"}},
+                    "window": {{"width": 100, "height": 100}},
+                    "assets": {{}},
+                    "palette": {}}}"#,
+                palette
+            );
+            Skin::parse(&json, Path::new("."))
+        };
+        let palette = skin(r##"{"accent": "0xFF00FF", "panel": "#00ffff"}"##).unwrap().palette;
+        assert_eq!(palette, HashMap::from([("accent".to_string(), 0xFF00FF), ("panel".to_string(), 0x00FFFF)]));
+
+        for bad in [
+            r#"{"accent": "magenta"}"#,
+            r#"{"accent": "0x1FF00FF"}"#,
+            r#"{"accent": "0xFF00FF", "border": "0xff00ff"}"#,
+        ] {
+            assert!(matches!(skin(bad), Err(SkinError::InvalidValue(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_window_safe_area() {
+        let skin = |window: &str| {
+            let json = format!(
+                r#"{{"skin": {{"name": "t", "author": "t", "version": "1"}}, "window": {}, "assets": {{}}}}"#,
+                window
+            );
+            Skin::parse(&json, Path::new("."))
+        };
+        let window = skin(r#"{"width": 100, "height": 100}"#).unwrap().window;
+        assert!(window.decorations);
+        assert_eq!(window.insets, Insets::default());
+
+        let window = skin(r#"{"width": 100, "height": 100, "decorations": false, "safe_area": {"top": 28, "left": 4}}"#);
+        let window = window.unwrap().window;
+        assert!(!window.decorations);
+        assert_eq!(window.insets, Insets::new(28, 0, 0, 4));
+        assert_eq!(window.edge_resistance, EdgeResistance::Elastic);
+
+        let window = skin(r#"{"width": 100, "height": 100, "edge_resistance": "clamp"}"#).unwrap().window;
+        assert_eq!(window.edge_resistance, EdgeResistance::Clamp);
+        let bad = skin(r#"{"width": 100, "height": 100, "edge_resistance": "sticky"}"#);
+        assert!(matches!(bad, Err(SkinError::InvalidValue(_))));
+
+        let bad = skin(r#"{"width": 100, "height": 100, "safe_area": {"top": 60, "bottom": 60}}"#);
+        assert!(matches!(bad, Err(SkinError::InvalidValue(_))));
     }
 }
@@ -1,10 +1,28 @@
 mod assets;
 mod builder;
+mod embed;
 mod loader;
+mod pack;
+mod palette;
+mod template;
 mod types;
+mod watch;
 pub mod widgets;
+mod wizard;
 
-pub use assets::LoadedSkin;
+pub use assets::{decode_image, LoadedSkin, MAX_ASSET_DIMENSION};
 pub use builder::SkinBuilder;
-pub use types::{SkinError, SkinWindow, TextAlign, VerticalAlign};
-pub use widgets::{SkinVScroll, StaticText, TextInput};
+pub use embed::MAX_EMBED_DEPTH;
+pub use pack::SKIN_PACK_EXTENSION;
+pub use palette::{chosen_palette, PALETTE_KEY_PREFIX};
+pub use template::{fill_list_row, fill_placeholders, fill_template};
+pub use watch::{SkinWatcher, SKIN_WATCH_INTERVAL};
+pub use types::{GaugeStyle, KnobDrag, PathDialog, PlotStyle, Skin, SkinError, SkinWindow, TextAlign, VerticalAlign};
+pub use wizard::{Rule, ValidationRule, Wizard, WizardStep};
+pub use widgets::{
+    AnimatedSprite, CanvasWidget, ColorPicker, CustomPaint, FilePathInput, Gauge, GroupBox, ImageView, Indicator, Knob,
+    Link, ListView, MenuBar, Painter, PainterRegistry, Plot, ProgressBar, SevenSegment, SkinTimerDisplay, SkinVScroll,
+    Spinner, Stack, StaticText, StatusBar, TabContainer, TextArea, TextInput, ToggleSwitch, TreeRow, TreeView,
+};
+#[cfg(feature = "video")]
+pub use widgets::SkinVideo;
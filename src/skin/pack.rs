@@ -0,0 +1,186 @@
+//! Skin packs.
+//!
+//! A `.skinpack` file is a zip archive holding a whole skin, so it can be
+//! handed around as a single file: `skin.json` at the root of the archive
+//! and the images and embedded skins it names, at the paths it names them
+//! by. The archive is read into memory once when the skin loads; reading
+//! zip archives needs the `skinpack` feature.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use super::types::SkinError;
+
+/// File extension of skin packs.
+pub const SKIN_PACK_EXTENSION: &str = "skinpack";
+
+/// Skin file at the root of a skin pack.
+pub(crate) const PACK_SKIN_FILE: &str = "skin.json";
+
+/// Largest total size of the files in a skin pack once unpacked, so a small
+/// archive can't claim gigabytes of memory.
+#[cfg(feature = "skinpack")]
+const MAX_PACK_SIZE: u64 = 256 * 1024 * 1024;
+
+/// The files of a skin pack, by their path inside it.
+#[derive(Debug, Default)]
+pub(crate) struct SkinPack {
+    files: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl SkinPack {
+    /// Read the skin pack at `path`.
+    #[cfg(feature = "skinpack")]
+    pub(crate) fn open(path: &Path) -> Result<Self, SkinError> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// Read the skin pack at `path`, which needs the `skinpack` feature.
+    #[cfg(not(feature = "skinpack"))]
+    pub(crate) fn open(path: &Path) -> Result<Self, SkinError> {
+        Err(SkinError::Pack(format!("{:?}: reading skin packs needs the skinpack feature", path)))
+    }
+
+    /// Read a skin pack from zip data.
+    #[cfg(feature = "skinpack")]
+    pub(crate) fn from_reader(reader: impl std::io::Read + std::io::Seek) -> Result<Self, SkinError> {
+        use std::io::Read;
+
+        let error = |e: zip::result::ZipError| SkinError::Pack(e.to_string());
+        let mut archive = zip::ZipArchive::new(reader).map_err(error)?;
+        let mut files = HashMap::new();
+        let mut total = 0u64;
+        for index in 0..archive.len() {
+            let entry = archive.by_index(index).map_err(error)?;
+            if entry.is_dir() {
+                continue;
+            }
+            // Entries that would land outside the archive's root are skipped
+            let Some(name) = entry.enclosed_name().and_then(|name| normalize(&name)) else {
+                continue;
+            };
+            // Count what is actually unpacked; an entry's declared size can lie
+            let room = MAX_PACK_SIZE - total;
+            let mut bytes = Vec::new();
+            entry.take(room + 1).read_to_end(&mut bytes)?;
+            if bytes.len() as u64 > room {
+                return Err(SkinError::Pack(format!("unpacks to more than {} bytes", MAX_PACK_SIZE)));
+            }
+            total += bytes.len() as u64;
+            files.insert(name, bytes);
+        }
+        if !files.contains_key(Path::new(PACK_SKIN_FILE)) {
+            return Err(SkinError::Pack(format!("no {} at the root", PACK_SKIN_FILE)));
+        }
+        Ok(Self { files })
+    }
+
+    /// Get the contents of the file at `path` inside the pack.
+    pub(crate) fn read(&self, path: &Path) -> Result<&[u8], SkinError> {
+        normalize(path)
+            .and_then(|path| self.files.get(&path))
+            .map(Vec::as_slice)
+            .ok_or_else(|| SkinError::Pack(format!("no file {:?}", path)))
+    }
+
+    /// Get the skin file of an embed source inside the pack: `skin.json` in
+    /// a directory, or the file itself.
+    pub(crate) fn skin_file(&self, source: &Path) -> PathBuf {
+        let inside = source.join(PACK_SKIN_FILE);
+        if normalize(&inside).is_some_and(|path| self.files.contains_key(&path)) {
+            inside
+        } else {
+            source.to_path_buf()
+        }
+    }
+}
+
+/// Check whether `path` names a skin pack.
+pub(crate) fn is_pack(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == SKIN_PACK_EXTENSION)
+}
+
+/// Resolve `.` and `..` in a path inside a pack, or `None` if it leaves the
+/// pack's root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normal.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normal.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_stay_inside_pack() {
+        assert_eq!(normalize(Path::new("images/../images/./knob.png")), Some(PathBuf::from("images/knob.png")));
+        assert_eq!(normalize(Path::new("../secret.png")), None);
+        assert_eq!(normalize(Path::new("/etc/passwd")), None);
+        assert!(is_pack(Path::new("skins/night.skinpack")));
+        assert!(!is_pack(Path::new("skins/night/skin.json")));
+
+        let pack = SkinPack {
+            files: HashMap::from([
+                (PathBuf::from("skin.json"), b"{}".to_vec()),
+                (PathBuf::from("clock/skin.json"), b"{}".to_vec()),
+            ]),
+        };
+        assert_eq!(pack.read(Path::new("./skin.json")).unwrap(), b"{}");
+        assert!(pack.read(Path::new("missing.png")).is_err());
+        assert_eq!(pack.skin_file(Path::new("clock")), PathBuf::from("clock/skin.json"));
+    }
+
+    #[cfg(feature = "skinpack")]
+    #[test]
+    fn test_loads_skin_from_pack() {
+        use std::io::Write;
+
+        use crate::skin::LoadedSkin;
+
+        let mut png = Vec::new();
+        image::RgbImage::from_pixel(4, 2, image::Rgb([255, 0, 0]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("crix_pack_{}.skinpack", std::process::id()));
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("skin.json", options).unwrap();
+        zip.write_all(
+            br#"{"skin": {"name": "packed", "author": "t", "version": "1"},
+                "window": {"width": 80, "height": 40},
+                "assets": {"logo": "images/logo.png"},
+                "parts": [{"id": "clock", "type": "embed", "source": "clock", "x": 0, "y": 0}]}"#,
+        )
+        .unwrap();
+        zip.start_file("images/logo.png", options).unwrap();
+        zip.write_all(&png).unwrap();
+        zip.start_file("clock/skin.json", options).unwrap();
+        zip.write_all(
+            br#"{"skin": {"name": "clock", "author": "t", "version": "1"},
+                "window": {"width": 40, "height": 20},
+                "assets": {"face": "../images/logo.png"}}"#,
+        )
+        .unwrap();
+        zip.finish().unwrap();
+
+        let skin = LoadedSkin::load(&path).unwrap();
+        assert_eq!(skin.name(), "packed");
+        assert_eq!(skin.get_image("logo").map(|image| image.dimensions()), Some((4, 2)));
+        assert!(skin.embed("clock").unwrap().get_image("face").is_some());
+        // Only the pack itself is watched for changes
+        assert_eq!(skin.files(), vec![path.clone()]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+//! Palette remapping.
+//!
+//! A skin can name the key colors its images are drawn in under `palette`.
+//! When the skin loads, every pixel that is exactly one of those colors is
+//! replaced by the color the user picked for its name, so users can recolor
+//! a skin without new assets. Picks are read from `palette.<name>` store
+//! keys, which a color picker part bound to one of them sets.
+
+use std::collections::HashMap;
+
+use image::RgbImage;
+
+use crate::core::Store;
+use crate::graphics::parse_draw_color;
+
+/// Prefix of the store keys holding the colors picked for a skin's palette.
+pub const PALETTE_KEY_PREFIX: &str = "palette.";
+
+/// Get the colors picked for palette names in the store, from
+/// `palette.<name>` keys holding `#rrggbb` or `0xrrggbb` colors.
+pub fn chosen_palette(store: &Store) -> HashMap<String, u32> {
+    store
+        .keys()
+        .filter_map(|key| {
+            let name = key.strip_prefix(PALETTE_KEY_PREFIX)?;
+            let color = parse_draw_color(store.get_str(key).trim())?;
+            Some((name.to_string(), color))
+        })
+        .collect()
+}
+
+/// Get the key color to color replacements for a skin's `palette`, leaving
+/// out names with no pick and picks that keep the key color.
+pub(crate) fn remapping(palette: &HashMap<String, u32>, chosen: &HashMap<String, u32>) -> HashMap<[u8; 3], [u8; 3]> {
+    palette
+        .iter()
+        .filter_map(|(name, &key)| Some((key, *chosen.get(name)?)))
+        .filter(|(key, color)| key != color)
+        .map(|(key, color)| (rgb(key), rgb(color)))
+        .collect()
+}
+
+/// Replace the pixels of `image` that are a key color of `remap`.
+pub(crate) fn remap_image(image: &mut RgbImage, remap: &HashMap<[u8; 3], [u8; 3]>) {
+    if remap.is_empty() {
+        return;
+    }
+    for pixel in image.pixels_mut() {
+        if let Some(color) = remap.get(&pixel.0) {
+            pixel.0 = *color;
+        }
+    }
+}
+
+fn rgb(color: u32) -> [u8; 3] {
+    let [_, r, g, b] = color.to_be_bytes();
+    [r, g, b]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaps_key_colors() {
+        let mut store = Store::new();
+        store.set("palette.accent", "#3366ff");
+        store.set("palette.panel", "0x202020");
+        store.set("palette.broken", "blue");
+        store.set("accent", "#ff0000");
+        let chosen = chosen_palette(&store);
+        assert_eq!(chosen, HashMap::from([("accent".to_string(), 0x3366FF), ("panel".to_string(), 0x202020)]));
+
+        // The panel keeps its key color, and the unpicked outline stays too
+        let palette = HashMap::from([
+            ("accent".to_string(), 0xFF00FF),
+            ("panel".to_string(), 0x202020),
+            ("outline".to_string(), 0x00FF00),
+        ]);
+        let remap = remapping(&palette, &chosen);
+        assert_eq!(remap, HashMap::from([([0xFF, 0x00, 0xFF], [0x33, 0x66, 0xFF])]));
+
+        let colors = [[0xFF, 0x00, 0xFF], [0xFE, 0x00, 0xFF], [0x00, 0xFF, 0x00]];
+        let mut image = RgbImage::from_fn(3, 1, |x, _| image::Rgb(colors[x as usize]));
+        remap_image(&mut image, &remap);
+        // Only exact key colors change
+        let pixels: Vec<_> = image.pixels().map(|pixel| pixel.0).collect();
+        assert_eq!(pixels, [[0x33, 0x66, 0xFF], [0xFE, 0x00, 0xFF], [0x00, 0xFF, 0x00]]);
+    }
+}
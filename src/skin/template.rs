@@ -0,0 +1,187 @@
+//! Report templating for skin layouts.
+//!
+//! Layouts used for printing and export can be filled from the store before
+//! they are built:
+//!
+//! - `{{key}}` in a part's `content` or `label` is replaced with the store value.
+//! - A part with `"repeat": "items"` is stamped out once per list entry, where
+//!   the list is `items.count` entries stored as `items.0.*`, `items.1.*`, ...
+//!   Each copy is offset by `repeat_offset` pixels (default: the part height),
+//!   and parts below the repeated one move down to make room for the copies.
+//!   At most 1000 rows are stamped out.
+//!   Inside a repeated part, `{{.field}}` and a binding of `.field` refer to
+//!   the current entry (`items.<n>.field`), and `{{#}}` is its 1-based number.
+//!
+//! The layout's height grows to fit repeated rows (up to 8192 pixels).
+
+use crate::core::Store;
+
+use super::assets::LoadedSkin;
+use super::loader::MAX_SKIN_DIMENSION;
+use super::types::SkinPart;
+
+/// Most rows a repeated part is stamped out into.
+const MAX_REPEAT_ROWS: usize = 1000;
+
+/// Fill a layout's templates from the store, expanding repeated parts.
+pub fn fill_template(skin: &mut LoadedSkin, store: &Store) {
+    let expanded = expand_parts(std::mem::take(&mut skin.skin.parts), store);
+
+    // Grow the layout so repeated rows aren't cut off, up to the largest
+    // window a skin may declare
+    let bottom = expanded
+        .iter()
+        .map(|part| (part.y.max(0) as u32).saturating_add(part.height.pixels()))
+        .max()
+        .unwrap_or(0);
+    skin.skin.window.height = skin.skin.window.height.max(bottom.min(MAX_SKIN_DIMENSION));
+    skin.skin.parts = expanded;
+}
+
+/// Fill parts' templates, stamping out repeated parts.
+fn expand_parts(parts: Vec<SkinPart>, store: &Store) -> Vec<SkinPart> {
+    let mut expanded = Vec::with_capacity(parts.len());
+    // Where each repeated block ended before expanding, and how far it grew
+    let mut grown: Vec<(i32, i32)> = Vec::new();
+
+    for part in parts {
+        // Parts below a repeated block move down with its extra rows
+        let shift = grown
+            .iter()
+            .filter(|(end, _)| part.y >= *end)
+            .fold(0i32, |shift, (_, growth)| shift.saturating_add(*growth));
+        let mut part = part;
+        part.y = part.y.saturating_add(shift);
+
+        match part.repeat.clone() {
+            Some(list) => {
+                let count = store.get_number(&format!("{}.count", list)).unwrap_or(0.0).max(0.0) as usize;
+                let offset = part.repeat_offset.unwrap_or(part.height.pixels() as i32);
+                let mut rows = 0;
+                for index in 0..count.min(MAX_REPEAT_ROWS) {
+                    let y = part.y.saturating_add(offset.saturating_mul(index as i32));
+                    // Rows past the largest layout would never be seen
+                    if y > MAX_SKIN_DIMENSION as i32 {
+                        break;
+                    }
+                    let mut row = part.clone();
+                    row.id = format!("{}.{}", part.id, index);
+                    row.y = y;
+                    row.repeat = None;
+                    fill_part(&mut row, store, Some((&list, index)));
+                    expanded.push(row);
+                    rows += 1;
+                }
+                let end = part.y.saturating_sub(shift).saturating_add(part.height.pixels() as i32);
+                let growth = offset.saturating_mul((rows - 1).max(0)).max(0);
+                grown.push((end, growth));
+            }
+            None => {
+                fill_part(&mut part, store, None);
+                expanded.push(part);
+            }
+        }
+    }
+
+    expanded
+}
+
+/// Replace `{{key}}` placeholders in `text` with store values.
+pub fn fill_placeholders(text: &str, store: &Store) -> String {
+    substitute(text, store, None)
+}
+
+/// Fill a row template for entry `index` of a store list, as in a repeated part.
+pub fn fill_list_row(text: &str, store: &Store, list: &str, index: usize) -> String {
+    substitute(text, store, Some((list, index)))
+}
+
+/// Substitute placeholders in one part (and its child).
+fn fill_part(part: &mut SkinPart, store: &Store, row: Option<(&str, usize)>) {
+    if let Some(content) = &part.content {
+        part.content = Some(substitute(content, store, row));
+    }
+    if let Some(label) = &part.label {
+        part.label = Some(substitute(label, store, row));
+    }
+    if let (Some(binding), Some((list, index))) = (&part.binding, row)
+        && let Some(field) = binding.strip_prefix('.')
+    {
+        part.binding = Some(format!("{}.{}.{}", list, index, field));
+    }
+    if let Some(child) = &mut part.child {
+        fill_part(child, store, row);
+    }
+}
+
+/// Replace `{{...}}` placeholders in `text`.
+fn substitute(text: &str, store: &Store, row: Option<(&str, usize)>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+
+        let key = rest[start + 2..start + 2 + len].trim();
+        match (key, row) {
+            ("#", Some((_, index))) => out.push_str(&(index + 1).to_string()),
+            (field, Some((list, index))) if field.starts_with('.') => {
+                out.push_str(&store.get_string(&format!("{}.{}{}", list, index, field)));
+            }
+            (key, _) => out.push_str(&store.get_string(key)),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::skin::types::Skin;
+
+    #[test]
+    fn test_substitute_placeholders() {
+        let mut store = Store::new();
+        store.set("total", 42.5);
+        store.set("items.1.name", "Widget");
+
+        assert_eq!(substitute("Total: {{ total }}", &store, None), "Total: 42.5");
+        assert_eq!(substitute("{{#}}. {{.name}}", &store, Some(("items", 1))), "2. Widget");
+        assert_eq!(substitute("{{missing}}!", &store, None), "!");
+        assert_eq!(substitute("open {{ brace", &store, None), "open {{ brace");
+    }
+
+    #[test]
+    fn test_repeated_rows_push_later_parts_down() {
+        let mut store = Store::new();
+        store.set("items.1.name", "Widget");
+
+        let json = r#"{"skin": {"name": "t", "author": "t", "version": "1"},
+            "window": {"width": 100, "height": 100},
+            "assets": {},
+            "parts": [
+                {"id": "title", "type": "static_text", "x": 0, "y": 0, "width": 100, "height": 20},
+                {"id": "row", "type": "static_text", "x": 0, "y": 20, "width": 100, "height": 10,
+                 "repeat": "items", "content": "{{#}}. {{.name}}"},
+                {"id": "total", "type": "static_text", "x": 0, "y": 40, "width": 100, "height": 20}
+            ]}"#;
+        let parts = Skin::parse(json, Path::new(".")).unwrap().parts;
+        store.set("items.count", 3.0);
+        let expanded = expand_parts(parts.clone(), &store);
+        let placed: Vec<_> = expanded.iter().map(|part| (part.id.as_str(), part.y)).collect();
+        // The total moves down by the two extra rows
+        assert_eq!(placed, [("title", 0), ("row.0", 20), ("row.1", 30), ("row.2", 40), ("total", 60)]);
+        assert_eq!(expanded[2].content.as_deref(), Some("2. Widget"));
+
+        // A huge count stops at the largest layout
+        store.set("items.count", 1e12);
+        assert_eq!(expand_parts(parts, &store).len(), 2 + (MAX_SKIN_DIMENSION as usize - 20) / 10 + 1);
+    }
+}
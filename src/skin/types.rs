@@ -1,5 +1,12 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use super::wizard::Wizard;
+use crate::core::{Animation, Insets, Length, MenuItem};
+use crate::graphics::{ColorEffect, NineSlice, TextOutline, TextShadow};
+use crate::platform::EdgeResistance;
+use crate::widgets::ConfettiConfig;
 
 /// Skin metadata from [skin] section.
 #[derive(Debug, Clone)]
@@ -15,6 +22,14 @@ pub struct SkinWindow {
     pub width: u32,
     pub height: u32,
     pub resizable: bool,
+    /// Whether the window has a title bar and borders.
+    pub decorations: bool,
+    /// Strips along the edges that safe-area parts stay out of. Without
+    /// decorations, the top strip drags the window.
+    pub insets: Insets,
+    /// How a window without decorations holds at the screen's edges when
+    /// dragged past them.
+    pub edge_resistance: EdgeResistance,
 }
 
 /// Drawing configuration for stateful widgets (buttons).
@@ -25,6 +40,18 @@ pub struct PartDraw {
     pub pressed: String,
 }
 
+/// Drawing configuration for icon buttons.
+#[derive(Debug, Clone)]
+pub struct IconDraw {
+    /// Sprite strip with the normal, hover, pressed and (optionally)
+    /// disabled frames, side by side.
+    pub strip: String,
+    /// Number of frames in the strip, 3 or 4.
+    pub frames: u32,
+    /// Frames are stacked top to bottom instead.
+    pub vertical: bool,
+}
+
 /// Drawing configuration for text inputs.
 #[derive(Debug, Clone)]
 pub struct TextInputDraw {
@@ -34,6 +61,13 @@ pub struct TextInputDraw {
     pub invalid: Option<String>,
 }
 
+/// Drawing configuration for the browse button of file path inputs.
+#[derive(Debug, Clone)]
+pub struct BrowseDraw {
+    pub normal: String,
+    pub hover: String,
+}
+
 /// Scrollbar configuration for scroll containers.
 #[derive(Debug, Clone)]
 pub struct ScrollbarDraw {
@@ -81,6 +115,324 @@ pub struct CheckboxDraw {
     pub unchecked: String,
     /// Checked state image.
     pub checked: String,
+    /// Unchecked state image while hovered.
+    pub unchecked_hover: Option<String>,
+    /// Checked state image while hovered.
+    pub checked_hover: Option<String>,
+}
+
+/// Slider drawing configuration.
+#[derive(Debug, Clone)]
+pub struct SliderDraw {
+    /// Track image.
+    pub track: String,
+    /// Thumb image.
+    pub thumb: String,
+    /// Thumb image while hovered (defaults to `thumb`).
+    pub thumb_hover: Option<String>,
+    /// Thumb image while pressed or dragged (defaults to `thumb`).
+    pub thumb_pressed: Option<String>,
+}
+
+/// Toggle switch drawing configuration.
+#[derive(Debug, Clone)]
+pub struct ToggleDraw {
+    /// Image for the off state.
+    pub off: String,
+    /// Image for the on state.
+    pub on: String,
+    /// Thumb image that slides between the two sides.
+    pub thumb: Option<String>,
+}
+
+/// Indicator drawing configuration.
+#[derive(Debug, Clone)]
+pub struct IndicatorDraw {
+    /// Image while the light is off.
+    pub off: String,
+    /// Image while the light is on.
+    pub on: String,
+    /// Time between switching images while lit; steady when unset.
+    pub blink: Option<Duration>,
+}
+
+/// Spinner drawing configuration.
+#[derive(Debug, Clone)]
+pub struct SpinnerDraw {
+    /// Field image, drawn behind the value.
+    pub field: String,
+    /// Up button image, placed at the top right.
+    pub up: String,
+    /// Down button image, placed at the bottom right.
+    pub down: String,
+    /// Up button image while held (defaults to `up`).
+    pub up_pressed: Option<String>,
+    /// Down button image while held (defaults to `down`).
+    pub down_pressed: Option<String>,
+}
+
+/// How pointer drags turn a knob.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KnobDrag {
+    /// Dragging up increases the value, dragging down decreases it.
+    #[default]
+    Vertical,
+    /// The knob points at the pointer's angle around its center.
+    Circular,
+}
+
+/// Knob drawing configuration.
+#[derive(Debug, Clone)]
+pub struct KnobDraw {
+    /// Sprite strip with the rotation frames stacked top to bottom.
+    pub strip: String,
+    /// Number of frames in the strip.
+    pub frames: u32,
+    /// How pointer drags turn the knob.
+    pub drag: KnobDrag,
+}
+
+/// How a gauge shows its value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GaugeStyle {
+    /// A needle pointing at the value.
+    #[default]
+    Needle,
+    /// An arc along the rim filled up to the value.
+    Arc,
+}
+
+/// Gauge drawing configuration.
+#[derive(Debug, Clone)]
+pub struct GaugeDraw {
+    /// Face image, drawn centered behind the needle or arc.
+    pub face: String,
+    /// Needle or arc.
+    pub style: GaugeStyle,
+    /// Angle of `min` in degrees, clockwise from straight up.
+    pub start_angle: Option<f64>,
+    /// Angle of `max` in degrees, clockwise from straight up.
+    pub end_angle: Option<f64>,
+    /// Needle or arc width in pixels.
+    pub thickness: Option<u32>,
+    /// Needle or arc color below the thresholds.
+    pub color: Option<u32>,
+    /// Value at which the warning color starts.
+    pub warning: Option<f64>,
+    pub warning_color: Option<u32>,
+    /// Value at which the danger color starts.
+    pub danger: Option<f64>,
+    pub danger_color: Option<u32>,
+}
+
+/// How a plot draws its samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlotStyle {
+    /// Samples joined by lines.
+    #[default]
+    Line,
+    /// A bar from zero to each sample.
+    Bar,
+}
+
+/// A series of a plot.
+#[derive(Debug, Clone)]
+pub struct PlotSeries {
+    /// Store list holding the samples.
+    pub list: String,
+    pub color: Option<u32>,
+}
+
+/// Animated sprite drawing configuration.
+#[derive(Debug, Clone)]
+pub struct SpriteDraw {
+    /// Sprite sheet with the frames left to right, wrapping onto further rows.
+    pub sheet: String,
+    /// Size of one frame.
+    pub frame_width: u32,
+    pub frame_height: u32,
+    /// Number of frames in the sheet.
+    pub frames: u32,
+    /// Frames per second.
+    pub fps: f32,
+}
+
+/// Seven-segment display drawing configuration.
+#[derive(Debug, Clone)]
+pub struct SegmentDraw {
+    /// Digit atlas with equal-width glyph cells side by side.
+    pub atlas: String,
+    /// Character shown by each cell of the atlas, in order.
+    pub glyphs: String,
+    /// Pixels between cells.
+    pub spacing: u32,
+    /// Number of cells shown, or one per character when unset.
+    pub digits: Option<u32>,
+    /// Character filling cells the value doesn't reach.
+    pub pad: char,
+}
+
+/// Plot drawing configuration.
+#[derive(Debug, Clone, Default)]
+pub struct PlotDraw {
+    /// Lines or bars.
+    pub style: PlotStyle,
+    /// Series to draw; the part's binding (or id) when empty.
+    pub series: Vec<PlotSeries>,
+    /// Axis ranges; unset sides fit the data.
+    pub x_min: Option<f64>,
+    pub x_max: Option<f64>,
+    pub y_min: Option<f64>,
+    pub y_max: Option<f64>,
+    /// Line width in pixels.
+    pub thickness: Option<u32>,
+    pub axis_color: Option<u32>,
+}
+
+/// Group box drawing configuration.
+#[derive(Debug, Clone, Default)]
+pub struct GroupDraw {
+    /// Frame image stretched to the box by nine-slice scaling; a plain
+    /// border is drawn when unset.
+    pub frame: Option<String>,
+    /// Size of the frame image's corners, kept as they are.
+    pub slice: Option<u32>,
+    /// Plain border color and width.
+    pub border_color: Option<u32>,
+    pub thickness: Option<u32>,
+}
+
+/// One slot of a status bar.
+#[derive(Debug, Clone)]
+pub struct StatusSegment {
+    pub id: String,
+    /// Store key shown; `<part id>.<segment id>` when unset.
+    pub binding: Option<String>,
+    /// Fixed or percentage width; anything else fills.
+    pub width: Length,
+    pub align: TextAlign,
+    pub text_color: Option<u32>,
+}
+
+/// Status bar drawing configuration.
+#[derive(Debug, Clone, Default)]
+pub struct StatusDraw {
+    /// Segments from left to right.
+    pub segments: Vec<StatusSegment>,
+    pub separator_color: Option<u32>,
+}
+
+/// Progress bar drawing configuration.
+#[derive(Debug, Clone)]
+pub struct ProgressBarDraw {
+    /// Track image, drawn behind the fill.
+    pub track: String,
+    /// Fill image, shown up to the current value.
+    pub fill: String,
+}
+
+/// Dropdown drawing configuration.
+#[derive(Debug, Clone)]
+pub struct DropdownDraw {
+    /// Box image.
+    pub normal: String,
+    /// Box image while hovered.
+    pub hover: String,
+    /// Box image while the list is open (defaults to `hover`).
+    pub open: Option<String>,
+    /// List row image.
+    pub item_normal: String,
+    /// List row image under the pointer or keyboard highlight.
+    pub item_hover: String,
+    /// List row image for the selected option (defaults to `item_hover`).
+    pub item_selected: Option<String>,
+}
+
+/// Menu bar drawing configuration.
+#[derive(Debug, Clone)]
+pub struct MenuBarDraw {
+    /// Background drawn behind the titles.
+    pub bar: Option<String>,
+    /// Title image; its size sets the title width and the title strip height.
+    pub title: String,
+    /// Title image under the pointer or keyboard highlight.
+    pub title_hover: String,
+    /// Title image while its menu is open (defaults to `title_hover`).
+    pub title_open: Option<String>,
+    /// Menu row image; its size sets the menu width and row height.
+    pub item_normal: String,
+    /// Menu row image under the pointer or keyboard highlight.
+    pub item_hover: String,
+}
+
+/// List view drawing configuration.
+#[derive(Debug, Clone)]
+pub struct ListDraw {
+    /// Row image; its height sets the row height.
+    pub row: String,
+    /// Row image under the pointer.
+    pub row_hover: String,
+    /// Row image for the selected row (defaults to `row_hover`).
+    pub row_selected: Option<String>,
+}
+
+/// Tree view drawing configuration.
+#[derive(Debug, Clone)]
+pub struct TreeDraw {
+    /// Row image; its height sets the row height.
+    pub row: String,
+    /// Row image under the pointer.
+    pub row_hover: String,
+    /// Row image for the selected row (defaults to `row_hover`).
+    pub row_selected: Option<String>,
+    /// Toggle image for expanded nodes.
+    pub expanded: String,
+    /// Toggle image for collapsed nodes; its width sets the indent per level.
+    pub collapsed: String,
+}
+
+/// Tab container drawing configuration.
+#[derive(Debug, Clone)]
+pub struct TabsDraw {
+    /// Tab image; its size sets the tab width and the tab strip height.
+    pub tab: String,
+    /// Tab image under the pointer.
+    pub tab_hover: String,
+    /// Tab image for the active tab (defaults to `tab_hover`).
+    pub tab_active: Option<String>,
+    /// Background drawn behind the panels.
+    pub panel: Option<String>,
+}
+
+/// One tab of a tab container and the parts on its panel.
+#[derive(Debug, Clone)]
+pub struct SkinTab {
+    /// Tab id, stored in the container's binding while the tab is active.
+    pub id: String,
+    /// Text shown on the tab.
+    pub label: String,
+    /// Parts on the tab's panel, positioned relative to the panel.
+    pub children: Vec<SkinPart>,
+}
+
+/// One drop-down menu of a menu bar.
+#[derive(Debug, Clone)]
+pub struct SkinMenu {
+    /// Text shown on the bar.
+    pub label: String,
+    /// Entries of the menu, in order.
+    pub items: Vec<MenuItem>,
+}
+
+/// Wizard progress indicator drawing configuration.
+#[derive(Debug, Clone)]
+pub struct ProgressDraw {
+    /// Marker for completed steps.
+    pub done: String,
+    /// Marker for the current step.
+    pub current: String,
+    /// Marker for upcoming steps.
+    pub pending: String,
 }
 
 /// Hit testing configuration.
@@ -104,26 +456,97 @@ pub enum TextAlign {
     Right,
 }
 
+/// The kind of dialog a file path input's browse button opens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathDialog {
+    /// Pick an existing file.
+    #[default]
+    Open,
+    /// Pick a file to save to, which may not exist yet.
+    Save,
+    /// Pick a folder.
+    Folder,
+}
+
 /// Vertical text alignment.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum VerticalAlign {
     Top,
     #[default]
     Center,
     Bottom,
+    /// Baseline this many pixels below the part's top edge (padding is
+    /// not added), so text can sit on a line drawn in the artwork.
+    Baseline(u32),
 }
 
 /// Part type discriminator.
 #[derive(Debug, Clone)]
 pub enum PartType {
     Image { asset: String },
+    /// Image loaded from a store-bound path inside the skin directory or
+    /// the app's asset directory.
+    ImageView { asset: Option<String>, skin_dir: PathBuf },
     Button,
+    IconButton,
     TextInput,
+    /// Text input with a button opening a file dialog.
+    FilePathInput { dialog: PathDialog },
     StaticText,
     VScrollContainer,
     DirectoryPicker,
     FilePicker,
     Checkbox,
+    CustomPaint,
+    Video,
+    Slider,
+    Knob,
+    ToggleSwitch,
+    /// A light showing a bool.
+    Indicator,
+    Spinner,
+    WizardProgress,
+    Dropdown,
+    MenuBar,
+    TextArea,
+    ScrollView,
+    ListView,
+    TreeView,
+    ProgressBar,
+    Gauge,
+    Plot,
+    SevenSegment,
+    AnimatedSprite,
+    GroupBox,
+    StatusBar,
+    TimerDisplay,
+    TabContainer,
+    /// A container showing one of its children at a time.
+    Stack,
+    Link,
+    Canvas,
+    ColorPicker,
+    /// Another skin shown in the part's region: a skin file, or a directory
+    /// holding `skin.json`.
+    Embed { source: PathBuf },
+}
+
+/// Display format for numeric text inputs, shown while they aren't focused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Fixed number of decimals, or as many as the number needs.
+    pub decimals: Option<usize>,
+    /// Whether thousands are grouped.
+    pub grouping: bool,
+}
+
+/// Scrolling for static text that is wider than its part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Marquee {
+    /// Scroll speed in pixels per second.
+    pub speed: f32,
+    /// Whether scrolling stops while the pointer is over the text.
+    pub pause_on_hover: bool,
 }
 
 /// Validation mode for text input.
@@ -133,11 +556,16 @@ pub enum TextValidation {
     Any,
     /// Digits only (0-9)
     Numeric,
+    /// A number: digits, one decimal point and a leading minus sign
+    Decimal,
     /// Letters only (a-z, A-Z)
     Alpha,
     /// Letters and digits
     Alphanumeric,
-    /// Custom regex pattern
+    /// Regex-style character class such as `[A-Fa-f0-9]`: inclusive
+    /// ranges, matching characters outside them when negated
+    Class { ranges: Vec<(char, char)>, negated: bool },
+    /// Characters allowed, listed one by one
     Pattern(String),
 }
 
@@ -148,18 +576,52 @@ pub struct SkinPart {
     pub part_type: PartType,
     pub x: i32,
     pub y: i32,
-    pub width: u32,
-    pub height: u32,
+    /// Size, resolved against the parent when the tree is laid out.
+    pub width: Length,
+    pub height: Length,
+    /// Limits on the resolved size in pixels.
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
+    /// Place the part in the window's safe area, clear of its insets
+    pub safe_area: bool,
     pub z: i32,
     pub draw: Option<PartDraw>,
+    pub icon_draw: Option<IconDraw>,
     pub text_input_draw: Option<TextInputDraw>,
+    pub browse_draw: Option<BrowseDraw>,
     pub directory_picker_draw: Option<DirectoryPickerDraw>,
     pub file_picker_draw: Option<FilePickerDraw>,
     pub checkbox_draw: Option<CheckboxDraw>,
+    pub slider_draw: Option<SliderDraw>,
+    pub knob_draw: Option<KnobDraw>,
+    pub toggle_draw: Option<ToggleDraw>,
+    pub indicator_draw: Option<IndicatorDraw>,
+    pub spinner_draw: Option<SpinnerDraw>,
+    pub progress_bar_draw: Option<ProgressBarDraw>,
+    pub gauge_draw: Option<GaugeDraw>,
+    pub plot_draw: Option<PlotDraw>,
+    pub segment_draw: Option<SegmentDraw>,
+    pub sprite_draw: Option<SpriteDraw>,
+    pub group_draw: Option<GroupDraw>,
+    pub status_draw: Option<StatusDraw>,
+    pub progress_draw: Option<ProgressDraw>,
+    pub dropdown_draw: Option<DropdownDraw>,
+    pub menu_bar_draw: Option<MenuBarDraw>,
+    pub list_draw: Option<ListDraw>,
+    pub tree_draw: Option<TreeDraw>,
+    pub tabs_draw: Option<TabsDraw>,
     pub scrollbar: Option<ScrollbarDraw>,
     pub hit: Option<PartHit>,
     pub action: Option<String>,
     pub text_color: Option<u32>,
+    /// Text color under the pointer, for links
+    pub hover_color: Option<u32>,
+    /// URL opened by clicking a link
+    pub url: Option<String>,
+    /// Color filled in behind a canvas drawing or inside a group box
+    pub background_color: Option<u32>,
     pub padding: Option<u32>,
     /// Font size in pixels (uses global font size if not specified)
     pub font_size: Option<f32>,
@@ -167,14 +629,63 @@ pub struct SkinPart {
     pub max_length: Option<u32>,
     /// Character validation mode
     pub validation: Option<TextValidation>,
+    /// Flash the invalid image when a keystroke is rejected
+    pub flash_invalid: Option<bool>,
+    /// Input mask such as `(###) ###-####` for text inputs
+    pub mask: Option<String>,
+    /// Hint shown in an empty, unfocused text input
+    pub placeholder: Option<String>,
+    /// Color of the placeholder
+    pub placeholder_color: Option<u32>,
+    /// Highlight color behind selected text
+    pub selection_color: Option<u32>,
+    /// Hide a text input's text behind bullets
+    pub password: Option<bool>,
+    /// Store key of a bool that shows a password as typed while true
+    pub reveal: Option<String>,
+    /// Store key of a bool that disables a button while true
+    pub disabled: Option<String>,
+    /// Number display format for text inputs, applied when focus leaves
+    pub number_format: Option<NumberFormat>,
+    /// Pattern a timer display shows its seconds in, such as `mm:ss`
+    pub time_format: Option<String>,
+    /// Round a timer display up to its smallest field, for countdowns
+    pub round_up: Option<bool>,
     /// Static text content
     pub content: Option<String>,
-    /// Label text for checkboxes
+    /// Scrolling for static text too wide for the part
+    pub marquee: Option<Marquee>,
+    /// Label text for checkboxes, and the title of group boxes
     pub label: Option<String>,
     /// Horizontal text alignment
     pub text_align: Option<TextAlign>,
     /// Vertical text alignment
     pub vertical_align: Option<VerticalAlign>,
+    /// Extra space between characters in pixels
+    pub letter_spacing: Option<f32>,
+    /// Line height as a multiple of the font's line height
+    pub line_height: Option<f32>,
+    /// Distance between tab stops in pixels
+    pub tab_width: Option<f32>,
+    /// Outline drawn around text glyphs
+    pub text_outline: Option<TextOutline>,
+    /// Shadow drawn under text
+    pub text_shadow: Option<TextShadow>,
+    /// Give all digits the same width
+    pub tabular_numbers: Option<bool>,
+    /// Parse static text as markup with bold, color and size spans
+    pub markup: Option<bool>,
+    /// Underline misspelled words in text areas
+    pub spellcheck: Option<bool>,
+    /// Text shown in a tooltip while the part is hovered
+    pub tooltip: Option<String>,
+    /// Recoloring of everything the part and its children draw, from its
+    /// `grayscale`, `hue_shift` and `tint`
+    pub color_effect: Option<ColorEffect>,
+    /// Entries of the menu opened by right-clicking the part
+    pub context_menu: Vec<MenuItem>,
+    /// Animations played when store keys change
+    pub animations: Vec<Animation>,
     /// Store binding key for reading/writing values
     pub binding: Option<String>,
     /// Content height for scroll containers
@@ -185,6 +696,42 @@ pub struct SkinPart {
     pub filter: Option<String>,
     /// Action to trigger on file selection
     pub on_select: Option<String>,
+    /// Registered painter name for custom paint parts
+    pub painter: Option<String>,
+    /// Media source for video parts (resolved file path or stream URL)
+    pub source: Option<String>,
+    /// Restart video or sprite playback when it reaches the end
+    pub looping: Option<bool>,
+    /// Start video or sprite playback as soon as the skin is loaded
+    pub autoplay: Option<bool>,
+    /// Store list to repeat this part for in report layouts
+    pub repeat: Option<String>,
+    /// Vertical offset between repeated rows (defaults to the part height)
+    pub repeat_offset: Option<i32>,
+    /// Minimum value for sliders, knobs and spinners
+    pub min: Option<f64>,
+    /// Maximum value for sliders, knobs and spinners
+    pub max: Option<f64>,
+    /// Value step for sliders, knobs and spinners
+    pub step: Option<f64>,
+    /// Fixed options for dropdowns
+    pub options: Option<Vec<String>>,
+    /// Store list supplying dropdown options (`<list>.count`, `<list>.<n>.name`)
+    pub options_list: Option<String>,
+    /// Number of dropdown options shown before the list scrolls
+    pub visible_items: Option<u32>,
+    /// Store list supplying list view rows (`<list>.count`, `<list>.<n>.*`)
+    pub list: Option<String>,
+    /// Whether list view rows can be dragged to a new place
+    pub reorderable: Option<bool>,
+    /// Action to trigger after a list view row is dropped in a new place
+    pub on_reorder: Option<String>,
+    /// Child parts of scroll views, positioned relative to the scrolled content
+    pub children: Vec<SkinPart>,
+    /// Tabs of tab containers, in order
+    pub tabs: Vec<SkinTab>,
+    /// Menus of menu bars, in order
+    pub menus: Vec<SkinMenu>,
 }
 
 /// The root skin structure parsed from skin.toml.
@@ -193,7 +740,16 @@ pub struct Skin {
     pub meta: SkinMeta,
     pub window: SkinWindow,
     pub assets: HashMap<String, PathBuf>,
+    /// Borders of the image assets stretched by nine-slice scaling, by key.
+    pub slices: HashMap<String, NineSlice>,
+    /// Key colors the images are drawn in, by palette name, replaced by the
+    /// colors users pick for them.
+    pub palette: HashMap<String, u32>,
     pub parts: Vec<SkinPart>,
+    /// Multi-step form declared by the skin, if any.
+    pub wizard: Option<Wizard>,
+    /// How `fx.confetti` bursts look and move.
+    pub confetti: ConfettiConfig,
 }
 
 /// Errors that can occur when loading a skin.
@@ -204,7 +760,12 @@ pub enum SkinError {
     AssetNotFound(String),
     MissingDrawSection(String),
     InvalidPartType(String),
+    InvalidRule(String),
+    /// A size, position or range is out of bounds.
+    InvalidValue(String),
     Image(image::ImageError),
+    /// A skin pack can't be read.
+    Pack(String),
 }
 
 impl std::fmt::Display for SkinError {
@@ -215,7 +776,10 @@ impl std::fmt::Display for SkinError {
             SkinError::AssetNotFound(key) => write!(f, "Asset not found: {}", key),
             SkinError::MissingDrawSection(id) => write!(f, "Missing 'draw' for button: {}", id),
             SkinError::InvalidPartType(t) => write!(f, "Invalid part type: {}", t),
+            SkinError::InvalidRule(r) => write!(f, "Invalid validation rule: {}", r),
+            SkinError::InvalidValue(v) => write!(f, "Invalid value: {}", v),
             SkinError::Image(e) => write!(f, "Image error: {}", e),
+            SkinError::Pack(e) => write!(f, "Skin pack error: {}", e),
         }
     }
 }
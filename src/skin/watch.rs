@@ -0,0 +1,110 @@
+//! Skin file watching.
+//!
+//! In dev mode the app rebuilds its screen when its skin is edited. The
+//! watcher polls the modification times of the skin file, the images it
+//! references and the skins it embeds. Polling works the same on every
+//! platform and needs no background thread, and a skin has few enough files
+//! that checking them a couple of times a second costs nothing.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often watched skin files are checked for changes.
+pub const SKIN_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Notices changes to a skin's files.
+#[derive(Debug)]
+pub struct SkinWatcher {
+    /// Watched files and when each was last modified, or `None` while a
+    /// file is missing (such as midway through an editor's save).
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    last_check: Instant,
+}
+
+impl SkinWatcher {
+    /// Watch `files`, taking how they are now as unchanged.
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        let mut watcher = Self {
+            files: Vec::new(),
+            last_check: Instant::now(),
+        };
+        watcher.watch(files);
+        watcher
+    }
+
+    /// Watch `files` instead, taking how they are now as unchanged.
+    pub fn watch(&mut self, mut files: Vec<PathBuf>) {
+        files.sort();
+        files.dedup();
+        self.files = files
+            .into_iter()
+            .map(|path| {
+                let modified = modified(&path);
+                (path, modified)
+            })
+            .collect();
+    }
+
+    /// Get the watched files.
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().map(|(path, _)| path)
+    }
+
+    /// Check whether any watched file changed since the last check, once
+    /// `SKIN_WATCH_INTERVAL` has passed since it.
+    pub fn poll(&mut self) -> bool {
+        if self.last_check.elapsed() < SKIN_WATCH_INTERVAL {
+            return false;
+        }
+        self.changed()
+    }
+
+    /// Check whether any watched file changed, appeared or disappeared since
+    /// the last check.
+    pub fn changed(&mut self) -> bool {
+        self.last_check = Instant::now();
+        let mut changed = false;
+        for (path, last) in &mut self.files {
+            let modified = modified(path);
+            if modified != *last {
+                *last = modified;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notices_changed_files() {
+        let dir = std::env::temp_dir().join(format!("crix-skin-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let skin = dir.join("skin.json");
+        let image = dir.join("button.png");
+        std::fs::write(&skin, "{}").unwrap();
+        std::fs::write(&image, "png").unwrap();
+
+        let mut watcher = SkinWatcher::new(vec![skin.clone(), image.clone(), skin.clone()]);
+        assert_eq!(watcher.files().count(), 2);
+        assert!(!watcher.changed());
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options().write(true).open(&image).unwrap().set_modified(later).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        // Checking again straight away waits for the interval
+        std::fs::remove_file(&skin).unwrap();
+        assert!(!watcher.poll());
+        assert!(watcher.changed());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+//! Animated sprite widget.
+//!
+//! Plays an animation from a sprite sheet: an image holding equal-size
+//! frames left to right, wrapping onto further rows, so a single strip or a
+//! grid both work. Frames advance at a fixed rate while the sprite is
+//! playing. A store binding holds whether it plays, so scripts can start and
+//! stop it; a sprite that doesn't loop stops on its last frame and sets the
+//! binding back to false, and playing it again starts from the beginning.
+
+use std::any::Any;
+use std::time::Duration;
+
+use image::RgbImage;
+
+use crate::core::{Constraints, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+
+/// Frames per second unless the skin says otherwise.
+pub const DEFAULT_SPRITE_FPS: f32 = 12.0;
+
+/// A sprite-sheet animation.
+pub struct AnimatedSprite {
+    /// Frames side by side, wrapping onto further rows.
+    sheet: RgbImage,
+    /// Size of one frame.
+    frame_width: u32,
+    frame_height: u32,
+    /// Number of frames in the sheet.
+    frames: u32,
+    /// How long each frame shows.
+    frame_time: Duration,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Start over after the last frame instead of stopping on it.
+    looping: bool,
+    playing: bool,
+    /// Frame shown.
+    frame: u32,
+    /// Time the current frame has shown for.
+    elapsed: Duration,
+    /// Store binding key of whether the sprite plays.
+    binding: Option<String>,
+    /// Flag indicating the sprite stopped by itself since last sync.
+    dirty: bool,
+}
+
+impl AnimatedSprite {
+    /// Create a stopped, looping sprite from a sheet of `frames` frames of
+    /// `frame_width` x `frame_height`.
+    pub fn new(width: u32, height: u32, sheet: RgbImage, (frame_width, frame_height): (u32, u32), frames: u32) -> Self {
+        Self {
+            sheet,
+            frame_width: frame_width.max(1),
+            frame_height: frame_height.max(1),
+            frames: frames.max(1),
+            frame_time: Duration::from_secs_f32(1.0 / DEFAULT_SPRITE_FPS),
+            width,
+            height,
+            looping: true,
+            playing: false,
+            frame: 0,
+            elapsed: Duration::ZERO,
+            binding: None,
+            dirty: false,
+        }
+    }
+
+    /// Set the frame rate in frames per second, at most 1000.
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        if fps > 0.0 {
+            self.frame_time = Duration::from_secs_f32(1.0 / fps.min(1000.0));
+        }
+        self
+    }
+
+    /// Set whether the animation starts over after the last frame.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Start playing right away.
+    pub fn with_autoplay(mut self) -> Self {
+        self.playing = true;
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the store binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Check whether the sprite is playing.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Get the frame shown.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Play or pause. Playing a sprite that stopped on its last frame
+    /// starts it from the beginning.
+    pub fn set_playing(&mut self, playing: bool) {
+        if playing && !self.playing && !self.looping && self.frame + 1 == self.frames {
+            self.frame = 0;
+            self.elapsed = Duration::ZERO;
+        }
+        self.playing = playing;
+    }
+
+    /// Check if the sprite stopped by itself since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+}
+
+impl Widget for AnimatedSprite {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let columns = (self.sheet.width() / self.frame_width).max(1);
+        let left = (self.frame % columns) * self.frame_width;
+        let top = (self.frame / columns) * self.frame_height;
+        // Frames past the sheet's edge show nothing
+        let width = self.frame_width.min(self.sheet.width().saturating_sub(left));
+        let height = self.frame_height.min(self.sheet.height().saturating_sub(top));
+
+        // Frame centered in the bounds
+        let x = bounds.x + (bounds.width as i32 - self.frame_width as i32) / 2;
+        let y = bounds.y + (bounds.height as i32 - self.frame_height as i32) / 2;
+        for iy in 0..height {
+            for ix in 0..width {
+                let px = x + ix as i32;
+                let py = y + iy as i32;
+
+                if px >= bounds.x && px < bounds.right() && py >= bounds.y && py < bounds.bottom() && px >= 0 && py >= 0 {
+                    let [r, g, b] = self.sheet.get_pixel(left + ix, top + iy).0;
+                    canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+                }
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn intrinsic_size(&self, constraints: Constraints) -> (u32, u32) {
+        constraints.constrain((self.frame_width, self.frame_height))
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        if !self.playing {
+            return false;
+        }
+        self.elapsed += dt;
+        let steps = (self.elapsed.as_nanos() / self.frame_time.as_nanos()) as u64;
+        if steps == 0 {
+            return false;
+        }
+        self.elapsed -= self.frame_time * steps.min(u32::MAX as u64) as u32;
+
+        let frame = self.frame as u64 + steps;
+        if self.looping {
+            self.frame = (frame % self.frames as u64) as u32;
+        } else if frame + 1 >= self.frames as u64 {
+            self.frame = self.frames - 1;
+            self.playing = false;
+            self.dirty = true;
+        } else {
+            self.frame = frame as u32;
+        }
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plays_through_frames() {
+        // Four 2x2 frames in a 2x2 grid, 10 frames per second
+        let sheet = RgbImage::from_fn(4, 4, |x, y| image::Rgb([(x / 2 + 2 * (y / 2)) as u8; 3]));
+        let mut sprite = AnimatedSprite::new(2, 2, sheet, (2, 2), 4).with_fps(10.0).with_looping(false);
+        assert!(!sprite.tick(Duration::from_millis(500)));
+
+        sprite.set_playing(true);
+        assert!(!sprite.tick(Duration::from_millis(50)));
+        assert!(sprite.tick(Duration::from_millis(160)));
+        assert_eq!(sprite.frame(), 2);
+        let mut buffer = vec![0xFFFFFF; 4];
+        sprite.draw(&mut Canvas::new(&mut buffer, 2, 2), &Rect::new(0, 0, 2, 2), WidgetState::default());
+        assert_eq!(buffer, [0x020202; 4]);
+
+        // Without looping it stops on the last frame, and plays again from the start
+        sprite.tick(Duration::from_secs(5));
+        assert_eq!((sprite.frame(), sprite.is_playing(), sprite.is_dirty()), (3, false, true));
+        sprite.set_playing(true);
+        assert_eq!(sprite.frame(), 0);
+
+        // Looping wraps around
+        let mut sprite = sprite.with_looping(true);
+        sprite.tick(Duration::from_millis(550));
+        assert_eq!(sprite.frame(), 1);
+    }
+}
@@ -1,14 +1,15 @@
 //! Checkbox widget.
 //!
-//! A toggleable checkbox widget with checked and unchecked image states.
-//! Supports an optional text label and store binding.
+//! A toggleable checkbox widget with checked and unchecked image states,
+//! plus optional hover images for each. Supports an optional text label
+//! and store binding.
 
 use std::any::Any;
 
 use image::RgbImage;
 
-use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
-use crate::graphics::{draw_text_sized, line_height_sized, Canvas, TextStyle};
+use crate::core::{Constraints, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{caret_x_sized, draw_text_sized, line_height_sized, Canvas, TextStyle};
 
 /// A checkbox widget with two states: checked and unchecked.
 pub struct Checkbox {
@@ -16,6 +17,9 @@ pub struct Checkbox {
     unchecked: RgbImage,
     /// Image for checked state.
     checked: RgbImage,
+    /// Images shown while hovered (fall back to the normal images).
+    unchecked_hover: Option<RgbImage>,
+    checked_hover: Option<RgbImage>,
     /// Widget dimensions.
     width: u32,
     height: u32,
@@ -45,6 +49,8 @@ impl Checkbox {
         Self {
             unchecked,
             checked,
+            unchecked_hover: None,
+            checked_hover: None,
             width,
             height,
             is_checked: false,
@@ -58,6 +64,18 @@ impl Checkbox {
         }
     }
 
+    /// Set the image shown while hovered and unchecked.
+    pub fn with_unchecked_hover(mut self, image: RgbImage) -> Self {
+        self.unchecked_hover = Some(image);
+        self
+    }
+
+    /// Set the image shown while hovered and checked.
+    pub fn with_checked_hover(mut self, image: RgbImage) -> Self {
+        self.checked_hover = Some(image);
+        self
+    }
+
     /// Set the label text.
     pub fn with_label(mut self, label: impl Into<String>) -> Self {
         self.label = Some(label.into());
@@ -164,12 +182,16 @@ impl Checkbox {
 }
 
 impl Widget for Checkbox {
-    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
-        // Choose image based on checked state
-        let image = if self.is_checked {
-            &self.checked
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        // Choose image based on checked and hover state
+        let (normal, hover) = if self.is_checked {
+            (&self.checked, &self.checked_hover)
         } else {
-            &self.unchecked
+            (&self.unchecked, &self.unchecked_hover)
+        };
+        let image = match hover {
+            Some(hover) if state.hovered => hover,
+            _ => normal,
         };
 
         // Center the checkbox image vertically
@@ -198,6 +220,17 @@ impl Widget for Checkbox {
         (self.width, self.height)
     }
 
+    fn intrinsic_size(&self, constraints: Constraints) -> (u32, u32) {
+        let Some(label) = &self.label else {
+            return constraints.constrain(self.preferred_size());
+        };
+        // The box, then the label beside it, centered on one line
+        let font_size = self.effective_font_size();
+        let width = self.width + self.padding + caret_x_sized(label, usize::MAX, font_size);
+        let height = self.height.max(line_height_sized(font_size));
+        constraints.constrain((width, height))
+    }
+
     fn on_event(&mut self, event: &WidgetEvent) -> bool {
         if let WidgetEvent::Click = event {
             self.toggle();
@@ -214,3 +247,29 @@ impl Widget for Checkbox {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::graphics::init_font;
+
+    #[test]
+    fn test_intrinsic_size_fits_label() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let image = RgbImage::new(12, 12);
+        let checkbox = Checkbox::new(image.clone(), image.clone());
+        assert_eq!(checkbox.intrinsic_size(Constraints::unbounded()), (12, 12));
+
+        let checkbox = checkbox.with_label("Remember me").with_padding(4);
+        let (width, height) = checkbox.intrinsic_size(Constraints::unbounded());
+        assert_eq!(width, 12 + 4 + caret_x_sized("Remember me", usize::MAX, 16.0));
+        assert_eq!(height, line_height_sized(16.0).max(12));
+
+        // Cut down to the space left
+        assert_eq!(checkbox.intrinsic_size(Constraints::new(40, 10)), (40, 10));
+    }
+}
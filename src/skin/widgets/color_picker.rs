@@ -0,0 +1,371 @@
+//! Color picker widget.
+//!
+//! A saturation/value square next to a vertical hue strip, both drawn from
+//! the color itself so the part needs no images. Dragging in the square picks
+//! the saturation (left to right) and value (bottom to top); dragging in the
+//! strip picks the hue. The color syncs to a store key as a `#rrggbb` string
+//! while dragging, and an action can be dispatched when the pointer is
+//! released.
+
+use std::any::Any;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{parse_draw_color, Canvas};
+
+/// Width of the hue strip in pixels.
+pub const HUE_STRIP_WIDTH: u32 = 16;
+
+/// Space between the square and the hue strip.
+const STRIP_GAP: u32 = 6;
+
+/// Radius of the ring marking the picked point in the square.
+const MARKER_RADIUS: i32 = 4;
+
+/// Which area a drag started in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DragArea {
+    Square,
+    Strip,
+}
+
+/// A color chooser bound to a hex string.
+pub struct ColorPicker {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Hue in degrees (0-360).
+    hue: f64,
+    /// Saturation and value (0-1).
+    saturation: f64,
+    value: f64,
+    /// Store binding key.
+    binding: Option<String>,
+    /// Action to trigger when the pointer is released.
+    action: Option<String>,
+    /// Current layout bounds, for mapping pointer positions to colors.
+    bounds: Option<Rect>,
+    /// Area being dragged, if any.
+    drag: Option<DragArea>,
+    /// Flag indicating the color was modified since last sync.
+    dirty: bool,
+    /// Set when a drag ended and the release action hasn't been taken yet.
+    released: bool,
+}
+
+impl ColorPicker {
+    /// Create a picker showing pure red.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            hue: 0.0,
+            saturation: 1.0,
+            value: 1.0,
+            binding: None,
+            action: None,
+            bounds: None,
+            drag: None,
+            dirty: false,
+            released: false,
+        }
+    }
+
+    /// Set the initial color (0xRRGGBB).
+    pub fn with_color(mut self, color: u32) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the pointer is released.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the picked color (0xRRGGBB).
+    pub fn color(&self) -> u32 {
+        hsv_to_rgb(self.hue, self.saturation, self.value)
+    }
+
+    /// Get the picked color as a `#rrggbb` string.
+    pub fn hex(&self) -> String {
+        format!("#{:06x}", self.color())
+    }
+
+    /// Set the color without marking the picker dirty (e.g. from the store).
+    /// The hue is kept when the color is a gray, so it isn't lost while the
+    /// square is dragged to its edges.
+    pub fn set_color(&mut self, color: u32) {
+        if color == self.color() {
+            return;
+        }
+        let (hue, saturation, value) = rgb_to_hsv(color);
+        if saturation > 0.0 && value > 0.0 {
+            self.hue = hue;
+        }
+        self.saturation = saturation;
+        self.value = value;
+    }
+
+    /// Set the color from a `#rrggbb` or `0xrrggbb` string. Returns false if
+    /// it isn't one.
+    pub fn set_hex(&mut self, text: &str) -> bool {
+        match parse_draw_color(text.trim()) {
+            Some(color) => {
+                self.set_color(color);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check if the color has been modified since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the release action if a drag just ended.
+    pub fn take_release_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.released) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Split the bounds into the square and the hue strip.
+    fn areas(bounds: &Rect) -> (Rect, Rect) {
+        let strip_width = HUE_STRIP_WIDTH.min(bounds.width);
+        let square_width = bounds.width.saturating_sub(strip_width + STRIP_GAP);
+        let square = Rect::new(bounds.x, bounds.y, square_width, bounds.height);
+        let strip = Rect::new(bounds.right() - strip_width as i32, bounds.y, strip_width, bounds.height);
+        (square, strip)
+    }
+
+    /// Update the color from a pointer position in the dragged area.
+    fn set_from_pointer(&mut self, x: i32, y: i32) {
+        let (Some(bounds), Some(drag)) = (self.bounds, self.drag) else {
+            return;
+        };
+        let (square, strip) = Self::areas(&bounds);
+        let (hue, saturation, value) = match drag {
+            DragArea::Square => {
+                let saturation = fraction(x - square.x, square.width);
+                let value = 1.0 - fraction(y - square.y, square.height);
+                (self.hue, saturation, value)
+            }
+            DragArea::Strip => (360.0 * fraction(y - strip.y, strip.height), self.saturation, self.value),
+        };
+        if (hue, saturation, value) != (self.hue, self.saturation, self.value) {
+            self.hue = hue;
+            self.saturation = saturation;
+            self.value = value;
+            self.dirty = true;
+        }
+    }
+}
+
+impl Widget for ColorPicker {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let Some(clip) = canvas.clip_rect().map_or(Some(*bounds), |clip| clip.intersect(bounds)) else {
+            return;
+        };
+        let (square, strip) = Self::areas(bounds);
+        let plot = |canvas: &mut Canvas, x: i32, y: i32, color: u32| {
+            if x >= clip.x.max(0) && x < clip.right() && y >= clip.y.max(0) && y < clip.bottom() {
+                canvas.set_pixel(x as u32, y as u32, color);
+            }
+        };
+
+        // Saturation across, value up, at the current hue
+        for y in 0..square.height {
+            let value = 1.0 - fraction(y as i32, square.height);
+            for x in 0..square.width {
+                let color = hsv_to_rgb(self.hue, fraction(x as i32, square.width), value);
+                plot(canvas, square.x + x as i32, square.y + y as i32, color);
+            }
+        }
+        for y in 0..strip.height {
+            let color = hsv_to_rgb(360.0 * fraction(y as i32, strip.height), 1.0, 1.0);
+            for x in 0..strip.width {
+                plot(canvas, strip.x + x as i32, strip.y + y as i32, color);
+            }
+        }
+
+        // A ring around the picked point, dark on light colors and light on dark ones
+        let ring = if self.value > 0.5 { 0x000000 } else { 0xFFFFFF };
+        if square.width > 0 && square.height > 0 {
+            let cx = square.x + (self.saturation * (square.width - 1) as f64).round() as i32;
+            let cy = square.y + ((1.0 - self.value) * (square.height - 1) as f64).round() as i32;
+            let (inner, outer) = ((MARKER_RADIUS - 1).pow(2), MARKER_RADIUS.pow(2));
+            for dy in -MARKER_RADIUS..=MARKER_RADIUS {
+                for dx in -MARKER_RADIUS..=MARKER_RADIUS {
+                    let distance = dx * dx + dy * dy;
+                    if distance > inner && distance <= outer {
+                        plot(canvas, cx + dx, cy + dy, ring);
+                    }
+                }
+            }
+        }
+
+        // A line across the strip at the hue
+        if strip.height > 0 {
+            let y = strip.y + (self.hue / 360.0 * (strip.height - 1) as f64).round() as i32;
+            for x in strip.x..strip.right() {
+                plot(canvas, x, y, 0x000000);
+                plot(canvas, x, y + 1, 0xFFFFFF);
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseDown { x, y } => {
+                let Some(bounds) = self.bounds else {
+                    return true;
+                };
+                let (_, strip) = Self::areas(&bounds);
+                self.drag = Some(if *x >= strip.x { DragArea::Strip } else { DragArea::Square });
+                self.set_from_pointer(*x, *y);
+                true
+            }
+            WidgetEvent::MouseMove { x, y } if self.drag.is_some() => {
+                self.set_from_pointer(*x, *y);
+                true
+            }
+            WidgetEvent::MouseUp { .. } if self.drag.is_some() => {
+                self.drag = None;
+                self.released = true;
+                true
+            }
+            WidgetEvent::Click => true,
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = Some(bounds);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Get how far `offset` is across `length` pixels, from 0 to 1.
+fn fraction(offset: i32, length: u32) -> f64 {
+    if length <= 1 {
+        0.0
+    } else {
+        (offset as f64 / (length - 1) as f64).clamp(0.0, 1.0)
+    }
+}
+
+/// Convert a hue (degrees), saturation and value to 0xRRGGBB.
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> u32 {
+    let sector = (hue.rem_euclid(360.0) / 60.0).min(5.999_999);
+    let chroma = value * saturation;
+    let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+    let m = value - chroma;
+    let channel = |c: f64| ((c + m) * 255.0).round().clamp(0.0, 255.0) as u32;
+    channel(r) << 16 | channel(g) << 8 | channel(b)
+}
+
+/// Convert 0xRRGGBB to a hue (degrees), saturation and value.
+fn rgb_to_hsv(color: u32) -> (f64, f64, f64) {
+    let r = ((color >> 16) & 0xFF) as f64 / 255.0;
+    let g = ((color >> 8) & 0xFF) as f64 / 255.0;
+    let b = (color & 0xFF) as f64 / 255.0;
+    let max = r.max(g).max(b);
+    let chroma = max - r.min(g).min(b);
+    let hue = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { chroma / max };
+    (hue, saturation, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dragging_picks_color() {
+        // A 94px square, a 6px gap and the 16px hue strip
+        let bounds = Rect::new(10, 10, 116, 101);
+        let mut picker = ColorPicker::new(116, 101).with_binding("light.color");
+        picker.set_bounds(bounds);
+        assert!(picker.set_hex("#3366cc"));
+        assert_eq!(picker.hex(), "#3366cc");
+        assert!(!picker.set_hex("blue"));
+        assert!(!picker.is_dirty());
+
+        // The square's top-right corner is the hue at full strength, and
+        // the drag keeps going outside the part
+        picker.on_event(&WidgetEvent::MouseDown { x: 50, y: 50 });
+        picker.on_event(&WidgetEvent::MouseMove { x: 500, y: -500 });
+        assert_eq!(picker.hex(), "#0055ff");
+        assert!(picker.is_dirty());
+
+        // The bottom edge is black, and the hue survives it
+        picker.on_event(&WidgetEvent::MouseMove { x: 500, y: 500 });
+        assert_eq!(picker.hex(), "#000000");
+        picker.on_event(&WidgetEvent::MouseUp { x: 500, y: 500 });
+        assert_eq!(picker.take_release_action(), None);
+
+        // Halfway down the strip is cyan
+        picker.on_event(&WidgetEvent::MouseDown { x: 120, y: 60 });
+        picker.on_event(&WidgetEvent::MouseUp { x: 120, y: 60 });
+        picker.on_event(&WidgetEvent::MouseDown { x: 50, y: 50 });
+        picker.on_event(&WidgetEvent::MouseMove { x: 500, y: -500 });
+        assert_eq!(picker.hex(), "#00ffff");
+
+        let mut buffer = vec![0x808080; 140 * 120];
+        let mut canvas = Canvas::new(&mut buffer, 140, 120);
+        picker.draw(&mut canvas, &bounds, WidgetState::default());
+        assert_eq!(canvas.get_pixel(10, 10), Some(0xFFFFFF));
+        assert_eq!(canvas.get_pixel(10, 110), Some(0x000000));
+        assert_eq!(canvas.get_pixel(125, 10), Some(0xFF0000));
+        // The ring around the picked corner
+        assert_eq!(canvas.get_pixel(99, 10), Some(0x000000));
+        assert_eq!(canvas.get_pixel(9, 9), Some(0x808080));
+    }
+}
@@ -0,0 +1,166 @@
+//! Custom paint widget.
+//!
+//! An escape hatch for Rust embedders: painting is delegated to a named
+//! `Painter` registered before the skin is built. The painter receives the
+//! widget's canvas region and a snapshot of the store, shared by every
+//! custom paint part in the tree.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::core::{Rect, Store, Widget, WidgetState};
+use crate::graphics::Canvas;
+
+/// Trait for Rust code that paints a `CustomPaint` widget.
+///
+/// Implemented automatically for closures with the matching signature.
+pub trait Painter {
+    /// Paint into `bounds` on the canvas. The canvas clip is set to the part
+    /// of `bounds` that is on screen.
+    fn paint(&self, canvas: &mut Canvas, bounds: &Rect, store: &Store);
+}
+
+impl<F> Painter for F
+where
+    F: Fn(&mut Canvas, &Rect, &Store),
+{
+    fn paint(&self, canvas: &mut Canvas, bounds: &Rect, store: &Store) {
+        self(canvas, bounds, store)
+    }
+}
+
+/// Registry of named painters available to `custom_paint` skin parts.
+#[derive(Default, Clone)]
+pub struct PainterRegistry {
+    painters: HashMap<String, Rc<dyn Painter>>,
+}
+
+impl PainterRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a painter under a name referenced by the skin's `painter` field.
+    pub fn register(&mut self, name: impl Into<String>, painter: impl Painter + 'static) {
+        self.painters.insert(name.into(), Rc::new(painter));
+    }
+
+    /// Look up a painter by name.
+    pub fn get(&self, name: &str) -> Option<Rc<dyn Painter>> {
+        self.painters.get(name).cloned()
+    }
+
+    /// Check if a painter is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.painters.contains_key(name)
+    }
+}
+
+/// A widget whose contents are drawn by an embedder-provided `Painter`.
+pub struct CustomPaint {
+    /// Name of the painter (as referenced in the skin).
+    painter_name: String,
+    /// The resolved painter, if one was registered.
+    painter: Option<Rc<dyn Painter>>,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Snapshot of the store passed to the painter.
+    store: Rc<Store>,
+}
+
+impl CustomPaint {
+    /// Create a custom paint widget with an optional resolved painter.
+    pub fn new(
+        painter_name: impl Into<String>,
+        painter: Option<Rc<dyn Painter>>,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            painter_name: painter_name.into(),
+            painter,
+            width,
+            height,
+            store: Rc::new(Store::new()),
+        }
+    }
+
+    /// Get the painter name.
+    pub fn painter_name(&self) -> &str {
+        &self.painter_name
+    }
+
+    /// Replace the painter (e.g., when registering after the tree was built).
+    pub fn set_painter(&mut self, painter: Rc<dyn Painter>) {
+        self.painter = Some(painter);
+    }
+
+    /// Update the store snapshot passed to the painter.
+    pub fn set_store(&mut self, store: Rc<Store>) {
+        self.store = store;
+    }
+}
+
+impl Widget for CustomPaint {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let Some(ref painter) = self.painter else {
+            return;
+        };
+
+        // Restrict the painter to its own region, within what's already clipped
+        let previous_clip = canvas.clip_rect().copied();
+        let Some(clip) = previous_clip.map_or(Some(*bounds), |clip| clip.intersect(bounds)) else {
+            return;
+        };
+        canvas.set_clip(Some(clip));
+        painter.paint(canvas, bounds, &self.store);
+        canvas.set_clip(previous_clip);
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_within_clip() {
+        let painter = |canvas: &mut Canvas, bounds: &Rect, store: &Store| {
+            let color = store.get_number("color").unwrap_or(0.0) as u32;
+            canvas.fill_rect(bounds.x as u32, bounds.y as u32, bounds.width, bounds.height, color);
+        };
+        let mut widget = CustomPaint::new("fill", Some(Rc::new(painter)), 8, 8);
+        let mut store = Store::new();
+        store.set("color", 0xFF0000 as f64);
+        widget.set_store(Rc::new(store));
+
+        // Scrolled half out of a clipped view
+        let mut buffer = vec![0u32; 16 * 16];
+        let mut canvas = Canvas::new(&mut buffer, 16, 16);
+        let view = Rect::new(0, 0, 16, 4);
+        canvas.set_clip(Some(view));
+        widget.draw(&mut canvas, &Rect::new(2, 0, 8, 8), WidgetState::default());
+        assert_eq!(canvas.clip_rect(), Some(&view));
+        assert_eq!(canvas.get_pixel(2, 3), Some(0xFF0000));
+        assert_eq!(canvas.get_pixel(2, 4), Some(0));
+        assert_eq!(canvas.get_pixel(12, 0), Some(0));
+
+        // Entirely outside the clip
+        widget.draw(&mut canvas, &Rect::new(0, 8, 8, 8), WidgetState::default());
+        assert_eq!(canvas.get_pixel(0, 8), Some(0));
+    }
+}
@@ -236,9 +236,7 @@ impl Widget for DirectoryPicker {
             text
         };
 
-        let style = TextStyle {
-            color,
-        };
+        let style = TextStyle::with_color(color);
 
         draw_text(canvas, text_x, text_y, Some(&text_clip), &display_text, style);
     }
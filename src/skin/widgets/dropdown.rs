@@ -0,0 +1,481 @@
+//! Dropdown widget.
+//!
+//! A skinned box showing the selected option. Clicking it opens a popup list
+//! of options below the box, drawn on top of the other widgets; clicking an
+//! option selects it and writes it to the bound store key, while clicking
+//! anywhere else dismisses the list. Options come from the skin, or from a
+//! store list (`<list>.count` and `<list>.<n>.name`).
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{KeyCode, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{draw_text_sized, line_height_sized, Canvas, TextStyle};
+
+/// Default number of options shown before the list scrolls.
+const DEFAULT_VISIBLE_ITEMS: usize = 6;
+
+/// A box that opens a list of options to choose from.
+pub struct Dropdown {
+    /// Box images.
+    normal: RgbImage,
+    hover: RgbImage,
+    open_image: RgbImage,
+    /// Popup list row images.
+    item_normal: RgbImage,
+    item_hover: RgbImage,
+    item_selected: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Option labels.
+    options: Vec<String>,
+    /// Store list the options are read from.
+    options_list: Option<String>,
+    /// Index of the selected option.
+    selected: Option<usize>,
+    /// Text shown while nothing is selected.
+    placeholder: String,
+    /// Text appearance.
+    text_color: u32,
+    font_size: Option<f32>,
+    padding: u32,
+    /// Rows shown before the list scrolls.
+    visible_items: usize,
+    /// Store binding key.
+    binding: Option<String>,
+    /// Action to trigger when the selection changes.
+    action: Option<String>,
+    /// True while the popup list is showing.
+    open: bool,
+    /// Option under the pointer or keyboard highlight.
+    highlighted: Option<usize>,
+    /// First option shown in the list.
+    scroll: usize,
+    /// Last pointer position.
+    pointer: (i32, i32),
+    /// Current layout bounds.
+    bounds: Option<Rect>,
+    /// Flag indicating the selection changed since last sync.
+    dirty: bool,
+    /// Set when the selection changed and the action hasn't been taken yet.
+    changed: bool,
+}
+
+impl Dropdown {
+    /// Create a dropdown from box and list item images.
+    pub fn new(
+        width: u32,
+        height: u32,
+        normal: RgbImage,
+        hover: RgbImage,
+        item_normal: RgbImage,
+        item_hover: RgbImage,
+    ) -> Self {
+        Self {
+            open_image: hover.clone(),
+            item_selected: item_hover.clone(),
+            normal,
+            hover,
+            item_normal,
+            item_hover,
+            width,
+            height,
+            options: Vec::new(),
+            options_list: None,
+            selected: None,
+            placeholder: String::new(),
+            text_color: 0x000000,
+            font_size: None,
+            padding: 6,
+            visible_items: DEFAULT_VISIBLE_ITEMS,
+            binding: None,
+            action: None,
+            open: false,
+            highlighted: None,
+            scroll: 0,
+            pointer: (0, 0),
+            bounds: None,
+            dirty: false,
+            changed: false,
+        }
+    }
+
+    /// Set the box image shown while the list is open.
+    pub fn with_open_image(mut self, image: RgbImage) -> Self {
+        self.open_image = image;
+        self
+    }
+
+    /// Set the row image for the selected option.
+    pub fn with_item_selected(mut self, image: RgbImage) -> Self {
+        self.item_selected = image;
+        self
+    }
+
+    /// Set the options.
+    pub fn with_options(mut self, options: Vec<String>) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Read the options from a store list (`<list>.count`, `<list>.<n>.name`).
+    pub fn with_options_list(mut self, list: impl Into<String>) -> Self {
+        self.options_list = Some(list.into());
+        self
+    }
+
+    /// Set the text shown while nothing is selected.
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Set the horizontal text padding.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set how many options are shown before the list scrolls.
+    pub fn with_visible_items(mut self, count: usize) -> Self {
+        self.visible_items = count.max(1);
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the selection changes.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the store list the options are read from.
+    pub fn options_list(&self) -> Option<&str> {
+        self.options_list.as_deref()
+    }
+
+    /// Get the options.
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Replace the options, keeping the selection if its label still exists.
+    pub fn set_options(&mut self, options: Vec<String>) {
+        if options == self.options {
+            return;
+        }
+        let selected = self.selected_option().map(str::to_string);
+        self.options = options;
+        self.selected = selected.and_then(|label| self.options.iter().position(|o| *o == label));
+        self.highlighted = None;
+        self.scroll = 0;
+    }
+
+    /// Get the selected option's label.
+    pub fn selected_option(&self) -> Option<&str> {
+        self.selected.and_then(|i| self.options.get(i)).map(String::as_str)
+    }
+
+    /// Select the option with this label without marking the dropdown dirty
+    /// (e.g. from the store). Unknown labels clear the selection.
+    pub fn set_selected_option(&mut self, label: &str) {
+        self.selected = self.options.iter().position(|o| o == label);
+    }
+
+    /// Check if the popup list is showing.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Check if the selection has changed since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the change action if the selection just changed.
+    pub fn take_change_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.changed) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    fn item_height(&self) -> u32 {
+        self.item_normal.height().max(1)
+    }
+
+    fn visible_rows(&self) -> usize {
+        self.options.len().min(self.visible_items)
+    }
+
+    fn list_rect(&self, bounds: &Rect) -> Rect {
+        Rect::new(
+            bounds.x,
+            bounds.bottom(),
+            bounds.width,
+            self.item_height() * self.visible_rows() as u32,
+        )
+    }
+
+    /// Get the option under a point in the popup list.
+    fn option_at(&self, x: i32, y: i32) -> Option<usize> {
+        let list = self.list_rect(&self.bounds?);
+        if !list.contains(x, y) {
+            return None;
+        }
+        let index = self.scroll + ((y - list.y) as u32 / self.item_height()) as usize;
+        (index < self.options.len()).then_some(index)
+    }
+
+    fn open_list(&mut self) {
+        if self.options.is_empty() {
+            return;
+        }
+        self.open = true;
+        self.highlighted = self.selected;
+        // Start with the selection in view
+        let max_scroll = self.options.len() - self.visible_rows();
+        self.scroll = self.selected.unwrap_or(0).min(max_scroll);
+    }
+
+    fn select(&mut self, index: usize) {
+        if self.selected != Some(index) {
+            self.selected = Some(index);
+            self.dirty = true;
+            self.changed = true;
+        }
+    }
+
+    /// Move the highlight (open) or the selection (closed) by `delta` rows.
+    fn step(&mut self, delta: isize) {
+        if self.options.is_empty() {
+            return;
+        }
+        let last = self.options.len() - 1;
+        let current = if self.open { self.highlighted } else { self.selected };
+        let next = match current {
+            Some(i) => i.saturating_add_signed(delta).min(last),
+            None => 0,
+        };
+        if self.open {
+            self.highlighted = Some(next);
+            // Scroll to keep the highlight visible
+            let rows = self.visible_rows();
+            if next < self.scroll {
+                self.scroll = next;
+            } else if next >= self.scroll + rows {
+                self.scroll = next + 1 - rows;
+            }
+        } else {
+            self.select(next);
+        }
+    }
+
+    fn effective_font_size(&self) -> f32 {
+        self.font_size.unwrap_or(16.0)
+    }
+
+    fn draw_label(&self, canvas: &mut Canvas, rect: &Rect, text: &str, clip: &Rect) {
+        let size = self.effective_font_size();
+        let y = rect.y + (rect.height as i32 - line_height_sized(size) as i32) / 2;
+        draw_text_sized(
+            canvas,
+            rect.x + self.padding as i32,
+            y,
+            Some(clip),
+            text,
+            TextStyle::with_color(self.text_color),
+            size,
+        );
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for Dropdown {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        let image = if self.open {
+            &self.open_image
+        } else if state.hovered {
+            &self.hover
+        } else {
+            &self.normal
+        };
+        self.draw_image(canvas, bounds.x, bounds.y, image, bounds);
+
+        let text = self.selected_option().unwrap_or(&self.placeholder);
+        self.draw_label(canvas, bounds, text, bounds);
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::Click => {
+                if !self.open {
+                    self.open_list();
+                } else {
+                    let (x, y) = self.pointer;
+                    if let Some(index) = self.option_at(x, y) {
+                        self.select(index);
+                    }
+                    self.open = false;
+                }
+                true
+            }
+            WidgetEvent::MouseMove { x, y } => {
+                self.pointer = (*x, *y);
+                if self.open
+                    && let Some(index) = self.option_at(*x, *y)
+                {
+                    self.highlighted = Some(index);
+                }
+                true
+            }
+            WidgetEvent::MouseWheel { delta_y } if self.open => {
+                let max_scroll = self.options.len() - self.visible_rows();
+                self.scroll = if *delta_y > 0.0 {
+                    self.scroll.saturating_sub(1)
+                } else {
+                    (self.scroll + 1).min(max_scroll)
+                };
+                true
+            }
+            WidgetEvent::KeyDown { key } => match key {
+                KeyCode::Up => {
+                    self.step(-1);
+                    true
+                }
+                KeyCode::Down => {
+                    self.step(1);
+                    true
+                }
+                KeyCode::Enter => {
+                    if !self.open {
+                        self.open_list();
+                    } else {
+                        if let Some(index) = self.highlighted {
+                            self.select(index);
+                        }
+                        self.open = false;
+                    }
+                    true
+                }
+                KeyCode::Escape if self.open => {
+                    self.open = false;
+                    true
+                }
+                _ => false,
+            },
+            // Clicking elsewhere moves focus away and dismisses the list
+            WidgetEvent::FocusLost => {
+                self.open = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = Some(bounds);
+    }
+
+    fn overlay_bounds(&self, bounds: &Rect) -> Option<Rect> {
+        self.open.then(|| self.list_rect(bounds))
+    }
+
+    fn draw_overlay(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let list = self.list_rect(bounds);
+        let item_height = self.item_height() as i32;
+        for (row, index) in (self.scroll..self.scroll + self.visible_rows()).enumerate() {
+            let row_rect = Rect::new(list.x, list.y + row as i32 * item_height, list.width, item_height as u32);
+            let image = if self.highlighted == Some(index) {
+                &self.item_hover
+            } else if self.selected == Some(index) {
+                &self.item_selected
+            } else {
+                &self.item_normal
+            };
+            self.draw_image(canvas, row_rect.x, row_rect.y, image, &row_rect);
+            self.draw_label(canvas, &row_rect, &self.options[index], &row_rect);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_from_popup() {
+        let image = |h| RgbImage::new(100, h);
+        let mut dropdown = Dropdown::new(100, 20, image(20), image(20), image(10), image(10))
+            .with_options(vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()]);
+        dropdown.set_bounds(Rect::new(0, 0, 100, 20));
+        assert_eq!(dropdown.overlay_bounds(&Rect::new(0, 0, 100, 20)), None);
+
+        dropdown.on_event(&WidgetEvent::Click);
+        assert_eq!(dropdown.overlay_bounds(&Rect::new(0, 0, 100, 20)), Some(Rect::new(0, 20, 100, 30)));
+
+        // Third row of the list
+        dropdown.on_event(&WidgetEvent::MouseMove { x: 5, y: 45 });
+        dropdown.on_event(&WidgetEvent::Click);
+        assert!(!dropdown.is_open());
+        assert_eq!(dropdown.selected_option(), Some("Blue"));
+        assert_eq!(dropdown.take_change_action(), None);
+
+        // Options from the store keep the selection by label
+        dropdown.set_options(vec!["Blue".to_string(), "Cyan".to_string()]);
+        assert_eq!(dropdown.selected_option(), Some("Blue"));
+    }
+}
@@ -0,0 +1,131 @@
+//! Undo/redo history for the text editing widgets.
+//!
+//! The history keeps snapshots of the text and caret taken before each edit.
+//! Runs of typing (or of deleting) are coalesced into one step, so Ctrl+Z
+//! takes back a word at a time rather than a character. A step ends when the
+//! kind of edit changes, the caret is moved, or after typing whitespace.
+
+/// Most undo steps kept per widget.
+pub const MAX_UNDO_STEPS: usize = 100;
+
+/// What an edit did, for coalescing runs of the same edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    /// Typed text.
+    Insert,
+    /// Backspace or Delete.
+    Delete,
+    /// Any other change; never coalesced.
+    Replace,
+}
+
+/// Text and caret position before an edit.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+}
+
+/// Per-widget undo and redo stacks.
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// Kind of the step still open for coalescing.
+    group: Option<EditKind>,
+}
+
+impl EditHistory {
+    /// Create an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the state before an edit. Edits of the same kind as the open
+    /// step are merged into it. Any redo steps are dropped.
+    pub fn record(&mut self, kind: EditKind, text: &str, cursor: usize) {
+        if kind != EditKind::Replace && self.group == Some(kind) {
+            return;
+        }
+        self.undo.push(Snapshot { text: text.to_string(), cursor });
+        if self.undo.len() > MAX_UNDO_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.group = Some(kind);
+    }
+
+    /// Forget all undo and redo steps.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// End the open step, so the next edit starts a new one.
+    pub fn break_group(&mut self) {
+        self.group = None;
+    }
+
+    /// Restore the state before the last step. Returns false if there is
+    /// nothing to undo.
+    pub fn undo(&mut self, text: &mut String, cursor: &mut usize) -> bool {
+        self.group = None;
+        Self::step(&mut self.undo, &mut self.redo, text, cursor)
+    }
+
+    /// Reapply the last undone step. Returns false if there is nothing to redo.
+    pub fn redo(&mut self, text: &mut String, cursor: &mut usize) -> bool {
+        self.group = None;
+        Self::step(&mut self.redo, &mut self.undo, text, cursor)
+    }
+
+    /// Move the current state onto `to` and restore the top of `from`.
+    fn step(from: &mut Vec<Snapshot>, to: &mut Vec<Snapshot>, text: &mut String, cursor: &mut usize) -> bool {
+        let Some(snapshot) = from.pop() else {
+            return false;
+        };
+        to.push(Snapshot { text: std::mem::replace(text, snapshot.text), cursor: *cursor });
+        *cursor = snapshot.cursor;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesced_undo_redo() {
+        let mut history = EditHistory::new();
+        let mut text = String::new();
+        let mut cursor = 0;
+
+        // Type "hi there": the space ends the first word's step
+        for c in "hi there".chars() {
+            history.record(EditKind::Insert, &text, cursor);
+            text.push(c);
+            cursor += 1;
+            if c.is_whitespace() {
+                history.break_group();
+            }
+        }
+        history.record(EditKind::Delete, &text, cursor);
+        text.pop();
+        cursor -= 1;
+
+        assert!(history.undo(&mut text, &mut cursor));
+        assert_eq!((text.as_str(), cursor), ("hi there", 8));
+        assert!(history.undo(&mut text, &mut cursor));
+        assert_eq!((text.as_str(), cursor), ("hi ", 3));
+        assert!(history.undo(&mut text, &mut cursor));
+        assert_eq!(text, "");
+        assert!(!history.undo(&mut text, &mut cursor));
+
+        assert!(history.redo(&mut text, &mut cursor));
+        assert_eq!(text, "hi ");
+
+        // A new edit drops the redo steps
+        history.record(EditKind::Insert, &text, cursor);
+        text.push('!');
+        assert!(!history.redo(&mut text, &mut cursor));
+    }
+}
@@ -0,0 +1,228 @@
+//! File path input widget.
+//!
+//! A text input with a browse button at its right end. The path can be
+//! typed, or picked with the system file dialog the button opens; either way
+//! it goes to the input's binding like any text input's text. The widget only
+//! asks for the dialog: the app opens it through its `DialogService` and
+//! hands back the chosen path.
+
+use std::any::Any;
+
+use image::RgbImage;
+use winit::window::CursorIcon;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+use crate::skin::types::PathDialog;
+
+use super::TextInput;
+
+/// A text input for a file path, with a browse button.
+pub struct FilePathInput {
+    /// The text field, left of the button.
+    input: TextInput,
+    button_normal: RgbImage,
+    button_hover: RgbImage,
+    bounds: Rect,
+    /// Whether the pointer is over the button.
+    button_hovered: bool,
+    /// Whether the last press was on the button.
+    button_pressed: bool,
+    dialog: PathDialog,
+    /// Extensions the dialog shows, without the dot; empty for all files.
+    filters: Vec<String>,
+    dialog_title: String,
+    /// Whether the button was clicked since the app last asked.
+    browse_requested: bool,
+    /// Action dispatched after a path is picked with the dialog.
+    on_select: Option<String>,
+}
+
+impl FilePathInput {
+    /// Create a file path input from a text input sized to the field and the
+    /// button's images.
+    pub fn new(input: TextInput, button_normal: RgbImage, button_hover: RgbImage) -> Self {
+        Self {
+            input,
+            button_normal,
+            button_hover,
+            bounds: Rect::default(),
+            button_hovered: false,
+            button_pressed: false,
+            dialog: PathDialog::Open,
+            filters: Vec::new(),
+            dialog_title: "Select File".to_string(),
+            browse_requested: false,
+            on_select: None,
+        }
+    }
+
+    /// Set the kind of dialog the button opens.
+    pub fn with_dialog(mut self, dialog: PathDialog) -> Self {
+        self.dialog = dialog;
+        self
+    }
+
+    /// Only show files with these extensions (without the dot) in the dialog.
+    pub fn with_filters(mut self, filters: Vec<String>) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Set the dialog title.
+    pub fn with_dialog_title(mut self, title: impl Into<String>) -> Self {
+        self.dialog_title = title.into();
+        self
+    }
+
+    /// Set the action dispatched after a path is picked with the dialog.
+    pub fn with_on_select(mut self, action: impl Into<String>) -> Self {
+        self.on_select = Some(action.into());
+        self
+    }
+
+    /// Get the text field.
+    pub fn input(&self) -> &TextInput {
+        &self.input
+    }
+
+    /// Get the text field to change.
+    pub fn input_mut(&mut self) -> &mut TextInput {
+        &mut self.input
+    }
+
+    /// Get the kind of dialog the button opens.
+    pub fn dialog(&self) -> PathDialog {
+        self.dialog
+    }
+
+    /// Get the extensions the dialog shows.
+    pub fn filters(&self) -> &[String] {
+        &self.filters
+    }
+
+    /// Get the dialog title.
+    pub fn dialog_title(&self) -> &str {
+        &self.dialog_title
+    }
+
+    /// Get the action dispatched after a path is picked.
+    pub fn on_select(&self) -> Option<&str> {
+        self.on_select.as_deref()
+    }
+
+    /// Check whether the button was clicked, clearing the request.
+    pub fn take_browse_request(&mut self) -> bool {
+        std::mem::take(&mut self.browse_requested)
+    }
+
+    /// Get the button's area inside `bounds`.
+    fn button_rect(&self, bounds: &Rect) -> Rect {
+        let width = self.button_normal.width().min(bounds.width);
+        Rect::new(bounds.right() - width as i32, bounds.y, width, bounds.height)
+    }
+
+    /// Get the text field's area inside `bounds`.
+    fn field_rect(&self, bounds: &Rect) -> Rect {
+        Rect::new(bounds.x, bounds.y, bounds.width - self.button_rect(bounds).width, bounds.height)
+    }
+
+    fn in_button(&self, x: i32, y: i32) -> bool {
+        let button = self.button_rect(&self.bounds);
+        x >= button.x && x < button.right() && y >= button.y && y < button.bottom()
+    }
+}
+
+impl Widget for FilePathInput {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        let field_state = WidgetState { hovered: state.hovered && !self.button_hovered, ..state };
+        self.input.draw(canvas, &self.field_rect(bounds), field_state);
+
+        let button = self.button_rect(bounds);
+        let image = if self.button_hovered && state.hovered { &self.button_hover } else { &self.button_normal };
+        let visible = Rect::new(0, 0, canvas.width(), canvas.height());
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let (px, py) = (button.x + ix as i32, button.y + iy as i32);
+            if px < button.right() && py < button.bottom() && visible.contains(px, py) {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        let (width, height) = self.input.preferred_size();
+        (width + self.button_normal.width(), height.max(self.button_normal.height()))
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+        self.input.set_bounds(self.field_rect(&bounds));
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseMove { x, y } => {
+                let hovered = self.in_button(*x, *y);
+                let changed = hovered != self.button_hovered;
+                self.button_hovered = hovered;
+                // Drag selections carry on over the button
+                self.input.on_event(event) || changed
+            }
+            WidgetEvent::MouseDown { x, y } if self.in_button(*x, *y) => {
+                self.button_pressed = true;
+                true
+            }
+            WidgetEvent::MouseDown { .. } => {
+                self.button_pressed = false;
+                self.input.on_event(event)
+            }
+            WidgetEvent::Click if self.button_pressed => {
+                self.button_pressed = false;
+                self.browse_requested = true;
+                true
+            }
+            _ => self.input.on_event(event),
+        }
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        if self.button_hovered { CursorIcon::Pointer } else { self.input.cursor() }
+    }
+
+    fn tick(&mut self, dt: std::time::Duration) -> bool {
+        self.input.tick(dt)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browse_button_requests_dialog() {
+        let field = RgbImage::new(80, 20);
+        let input = TextInput::new(field.clone(), field.clone(), field, None);
+        let mut path_input = FilePathInput::new(input, RgbImage::new(20, 20), RgbImage::new(20, 20));
+        path_input.set_bounds(Rect::new(0, 0, 100, 20));
+
+        // Typing goes to the text field
+        path_input.on_event(&WidgetEvent::CharInput { c: 'a' });
+        assert_eq!(path_input.input().text(), "a");
+        path_input.on_event(&WidgetEvent::Click);
+        assert!(!path_input.take_browse_request());
+
+        path_input.on_event(&WidgetEvent::MouseDown { x: 90, y: 10 });
+        path_input.on_event(&WidgetEvent::Click);
+        assert!(path_input.take_browse_request());
+        assert!(!path_input.take_browse_request());
+    }
+}
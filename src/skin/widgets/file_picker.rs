@@ -402,7 +402,7 @@ impl FilePicker {
             text
         };
 
-        let style = TextStyle { color: self.text_color };
+        let style = TextStyle::with_color(self.text_color);
         let text_clip = Rect::new(
             text_x,
             bounds.y,
@@ -492,7 +492,7 @@ impl FilePicker {
             let display_name = format!("{}{}", prefix, entry.name);
             let color = if entry.is_dir { self.dir_color } else { self.text_color };
 
-            let style = TextStyle { color };
+            let style = TextStyle::with_color(color);
             draw_text(canvas, text_x, text_y, Some(&list_area), &display_name, style);
         }
 
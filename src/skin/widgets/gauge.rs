@@ -0,0 +1,318 @@
+//! Gauge widget.
+//!
+//! A read-only dial showing a numeric value from a store binding, drawn over
+//! a face image either as a needle pointing at the value or as an arc filled
+//! up to it. The dial sweeps clockwise from `start_angle` to `end_angle`
+//! (degrees from straight up) as the value goes from `min` to `max`. Optional
+//! warning and danger thresholds recolor the needle or arc once the value
+//! reaches them, as on a car's rev counter.
+
+use std::any::Any;
+use std::f64::consts::TAU;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+use crate::skin::types::GaugeStyle;
+
+/// Angle of the minimum value in degrees, clockwise from straight up.
+pub const DEFAULT_START_ANGLE: f64 = -135.0;
+
+/// Angle of the maximum value in degrees, clockwise from straight up.
+pub const DEFAULT_END_ANGLE: f64 = 135.0;
+
+/// Needle or arc color below the thresholds.
+pub const DEFAULT_GAUGE_COLOR: u32 = 0x2080E0;
+
+/// Color once the value reaches the warning threshold.
+pub const DEFAULT_WARNING_COLOR: u32 = 0xE0A000;
+
+/// Color once the value reaches the danger threshold.
+pub const DEFAULT_DANGER_COLOR: u32 = 0xE02020;
+
+/// A dial showing a numeric value.
+pub struct Gauge {
+    /// Face image, drawn centered behind the needle or arc.
+    face: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Needle or arc.
+    style: GaugeStyle,
+    /// Value range.
+    min: f64,
+    max: f64,
+    /// Current value.
+    value: f64,
+    /// Angles of `min` and `max` in radians, clockwise from straight up.
+    start_angle: f64,
+    end_angle: f64,
+    /// Needle or arc width in pixels.
+    thickness: u32,
+    /// Needle or arc colors.
+    color: u32,
+    warning_color: u32,
+    danger_color: u32,
+    /// Values at which the warning and danger colors start.
+    warning: Option<f64>,
+    danger: Option<f64>,
+    /// Store binding key for the value.
+    binding: Option<String>,
+}
+
+impl Gauge {
+    /// Create a needle gauge ranging from 0 to 100 over a face image.
+    pub fn new(width: u32, height: u32, face: RgbImage) -> Self {
+        Self {
+            face,
+            width,
+            height,
+            style: GaugeStyle::Needle,
+            min: 0.0,
+            max: 100.0,
+            value: 0.0,
+            start_angle: DEFAULT_START_ANGLE.to_radians(),
+            end_angle: DEFAULT_END_ANGLE.to_radians(),
+            thickness: 3,
+            color: DEFAULT_GAUGE_COLOR,
+            warning_color: DEFAULT_WARNING_COLOR,
+            danger_color: DEFAULT_DANGER_COLOR,
+            warning: None,
+            danger: None,
+            binding: None,
+        }
+    }
+
+    /// Set whether the value is shown with a needle or an arc.
+    pub fn with_style(mut self, style: GaugeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the value range. The current value is clamped into it.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max.max(min);
+        self.set_value(self.value);
+        self
+    }
+
+    /// Set the angles of `min` and `max` in degrees, clockwise from straight up.
+    pub fn with_angles(mut self, start: f64, end: f64) -> Self {
+        self.start_angle = start.to_radians();
+        self.end_angle = end.to_radians();
+        self
+    }
+
+    /// Set the needle or arc width in pixels.
+    pub fn with_thickness(mut self, thickness: u32) -> Self {
+        self.thickness = thickness.max(1);
+        self
+    }
+
+    /// Set the needle or arc color below the thresholds.
+    pub fn with_color(mut self, color: u32) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the value at which the warning color starts.
+    pub fn with_warning(mut self, threshold: f64) -> Self {
+        self.warning = Some(threshold);
+        self
+    }
+
+    /// Set the warning color.
+    pub fn with_warning_color(mut self, color: u32) -> Self {
+        self.warning_color = color;
+        self
+    }
+
+    /// Set the value at which the danger color starts.
+    pub fn with_danger(mut self, threshold: f64) -> Self {
+        self.danger = Some(threshold);
+        self
+    }
+
+    /// Set the danger color.
+    pub fn with_danger_color(mut self, color: u32) -> Self {
+        self.danger_color = color;
+        self
+    }
+
+    /// Set the initial value.
+    pub fn with_value(mut self, value: f64) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value, clamped to the range. Returns true if it changed.
+    pub fn set_value(&mut self, value: f64) -> bool {
+        let value = if value.is_nan() { self.min } else { value.clamp(self.min, self.max) };
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+
+    /// Angle of the current value in radians, clockwise from straight up.
+    fn angle(&self) -> f64 {
+        let fraction = if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        };
+        self.start_angle + fraction * (self.end_angle - self.start_angle)
+    }
+
+    /// Needle or arc color for the current value.
+    fn current_color(&self) -> u32 {
+        if self.danger.is_some_and(|danger| self.value >= danger) {
+            self.danger_color
+        } else if self.warning.is_some_and(|warning| self.value >= warning) {
+            self.warning_color
+        } else {
+            self.color
+        }
+    }
+
+    /// Draw a needle from the center to near the rim, with a hub over its base.
+    fn draw_needle(&self, canvas: &mut Canvas, center: (f64, f64), radius: f64, clip: &Rect) {
+        let angle = self.angle();
+        let (dx, dy) = (angle.sin(), -angle.cos());
+        let half = self.thickness as f64 / 2.0;
+        let length = (radius - half - 2.0).max(0.0);
+        let hub = self.thickness as f64;
+        let color = self.current_color();
+
+        let reach = length + hub;
+        let (x0, y0) = ((center.0 - reach).floor() as i32, (center.1 - reach).floor() as i32);
+        let (x1, y1) = ((center.0 + reach).ceil() as i32, (center.1 + reach).ceil() as i32);
+        for py in y0.max(clip.y)..y1.min(clip.bottom()) {
+            for px in x0.max(clip.x)..x1.min(clip.right()) {
+                let (rx, ry) = (px as f64 + 0.5 - center.0, py as f64 + 0.5 - center.1);
+                // Distance from the needle's center line, measured along and across it
+                let along = (rx * dx + ry * dy).clamp(0.0, length);
+                let across = ((rx - along * dx).powi(2) + (ry - along * dy).powi(2)).sqrt();
+                let on_hub = rx * rx + ry * ry <= hub * hub;
+                if (across <= half || on_hub) && px >= 0 && py >= 0 {
+                    canvas.set_pixel(px as u32, py as u32, color);
+                }
+            }
+        }
+    }
+
+    /// Draw an arc along the rim from the start angle to the value.
+    fn draw_arc(&self, canvas: &mut Canvas, center: (f64, f64), radius: f64, clip: &Rect) {
+        let inner = (radius - self.thickness as f64).max(0.0);
+        let sweep = self.angle() - self.start_angle;
+        let color = self.current_color();
+
+        let (x0, y0) = ((center.0 - radius).floor() as i32, (center.1 - radius).floor() as i32);
+        let (x1, y1) = ((center.0 + radius).ceil() as i32, (center.1 + radius).ceil() as i32);
+        for py in y0.max(clip.y)..y1.min(clip.bottom()) {
+            for px in x0.max(clip.x)..x1.min(clip.right()) {
+                let (rx, ry) = (px as f64 + 0.5 - center.0, py as f64 + 0.5 - center.1);
+                let distance = (rx * rx + ry * ry).sqrt();
+                if distance < inner || distance > radius {
+                    continue;
+                }
+                // Clockwise angle from straight up, relative to the start
+                let angle = (rx.atan2(-ry) - self.start_angle).rem_euclid(TAU);
+                if angle <= sweep && px >= 0 && py >= 0 {
+                    canvas.set_pixel(px as u32, py as u32, color);
+                }
+            }
+        }
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for Gauge {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let face_x = bounds.x + (bounds.width as i32 - self.face.width() as i32) / 2;
+        let face_y = bounds.y + (bounds.height as i32 - self.face.height() as i32) / 2;
+        self.draw_image(canvas, face_x, face_y, &self.face, bounds);
+
+        let center = (
+            bounds.x as f64 + bounds.width as f64 / 2.0,
+            bounds.y as f64 + bounds.height as f64 / 2.0,
+        );
+        let radius = bounds.width.min(bounds.height) as f64 / 2.0;
+        match self.style {
+            GaugeStyle::Needle => self.draw_needle(canvas, center, radius, bounds),
+            GaugeStyle::Arc => self.draw_arc(canvas, center, radius, bounds),
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needle_angle_and_thresholds() {
+        let mut gauge = Gauge::new(100, 100, RgbImage::new(100, 100))
+            .with_range(0.0, 8000.0)
+            .with_warning(6000.0)
+            .with_danger(7000.0);
+
+        assert!((gauge.angle() - DEFAULT_START_ANGLE.to_radians()).abs() < 1e-9);
+        assert!(gauge.set_value(4000.0));
+        assert!(gauge.angle().abs() < 1e-9);
+        assert_eq!(gauge.current_color(), DEFAULT_GAUGE_COLOR);
+
+        gauge.set_value(6500.0);
+        assert_eq!(gauge.current_color(), DEFAULT_WARNING_COLOR);
+        gauge.set_value(9000.0);
+        assert_eq!(gauge.value(), 8000.0);
+        assert_eq!(gauge.current_color(), DEFAULT_DANGER_COLOR);
+
+        // Values from the store that aren't numbers drop to the minimum
+        gauge.set_value(f64::NAN);
+        assert_eq!(gauge.value(), 0.0);
+    }
+}
@@ -0,0 +1,231 @@
+//! Group box widget.
+//!
+//! A frame with a title around related parts, like the group boxes of a
+//! form. The frame is a plain border, or an image stretched to the box by
+//! nine-slice scaling: corners of the slice size are kept as they are, edges
+//! are stretched along their length and the middle both ways. The title sits
+//! in a gap in the top edge, whose line runs through the middle of the title.
+//! The box only draws; its children are placed relative to it like any other
+//! part's.
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{caret_x_sized, draw_text_sized, line_height_sized, Canvas, NineSlice, TextStyle};
+use crate::skin::types::TextAlign;
+
+/// Border color unless the skin says otherwise.
+pub const DEFAULT_GROUP_BORDER_COLOR: u32 = 0x808080;
+
+/// Distance from the box's side to the title gap.
+const TITLE_INDENT: i32 = 8;
+
+/// Space either side of the title inside its gap.
+const TITLE_GAP: i32 = 4;
+
+/// How the frame is drawn.
+enum Frame {
+    /// Lines `thickness` pixels wide.
+    Border { color: u32, thickness: u32 },
+    /// An image whose `slice`-pixel corners are kept and the rest stretched.
+    Image { image: RgbImage, slice: u32 },
+}
+
+/// A titled frame grouping other parts.
+pub struct GroupBox {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    title: String,
+    frame: Frame,
+    /// Color filled in inside the frame; transparent when unset.
+    background: Option<u32>,
+    /// Title appearance.
+    text_color: u32,
+    font_size: f32,
+    /// Which end of the top edge the title sits at.
+    align: TextAlign,
+}
+
+impl GroupBox {
+    /// Create an untitled box with a one-pixel gray border.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            title: String::new(),
+            frame: Frame::Border { color: DEFAULT_GROUP_BORDER_COLOR, thickness: 1 },
+            background: None,
+            text_color: 0x000000,
+            font_size: 16.0,
+            align: TextAlign::Left,
+        }
+    }
+
+    /// Set the title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Draw the frame as a plain border.
+    pub fn with_border(mut self, color: u32, thickness: u32) -> Self {
+        self.frame = Frame::Border { color, thickness: thickness.max(1) };
+        self
+    }
+
+    /// Draw the frame from an image, keeping `slice`-pixel corners.
+    pub fn with_frame(mut self, image: RgbImage, slice: u32) -> Self {
+        self.frame = Frame::Image { image, slice };
+        self
+    }
+
+    /// Set the color filled in inside the frame.
+    pub fn with_background(mut self, color: u32) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Set the title color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the title font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set which end of the top edge the title sits at.
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Get the title.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Get the frame's area in `bounds`: all of it, less half the title's
+    /// height at the top so the top edge runs through the title.
+    fn frame_rect(&self, bounds: &Rect) -> Rect {
+        let top = if self.title.is_empty() { 0 } else { line_height_sized(self.font_size) / 2 };
+        Rect::new(bounds.x, bounds.y + top as i32, bounds.width, bounds.height.saturating_sub(top))
+    }
+
+    /// Get the gap in the top edge the title is drawn in, if there is a title.
+    fn title_gap(&self, bounds: &Rect) -> Option<Rect> {
+        if self.title.is_empty() {
+            return None;
+        }
+        let width = caret_x_sized(&self.title, usize::MAX, self.font_size) as i32 + 2 * TITLE_GAP;
+        let x = match self.align {
+            TextAlign::Left => bounds.x + TITLE_INDENT,
+            TextAlign::Center => bounds.x + (bounds.width as i32 - width) / 2,
+            TextAlign::Right => bounds.right() - TITLE_INDENT - width,
+        };
+        Some(Rect::new(x, bounds.y, width.max(0) as u32, line_height_sized(self.font_size)))
+    }
+}
+
+impl Widget for GroupBox {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let frame = self.frame_rect(bounds);
+        let gap = self.title_gap(bounds);
+        // The gap only cuts the top edge; the inside stays filled under the title
+        let top_edge = match &self.frame {
+            Frame::Border { thickness, .. } => *thickness,
+            Frame::Image { slice, .. } => *slice,
+        };
+        let mut plot = |x: u32, y: u32, color: u32| {
+            let (px, py) = (frame.x + x as i32, frame.y + y as i32);
+            let in_gap = y < top_edge && gap.is_some_and(|gap| gap.contains(px, py));
+            if px >= bounds.x.max(0) && px < bounds.right() && py >= bounds.y.max(0) && py < bounds.bottom() && !in_gap {
+                canvas.set_pixel(px as u32, py as u32, color);
+            }
+        };
+
+        match &self.frame {
+            Frame::Border { color, thickness } => {
+                for y in 0..frame.height {
+                    for x in 0..frame.width {
+                        let edge = x < *thickness
+                            || y < *thickness
+                            || x >= frame.width.saturating_sub(*thickness)
+                            || y >= frame.height.saturating_sub(*thickness);
+                        if edge {
+                            plot(x, y, *color);
+                        } else if let Some(background) = self.background {
+                            plot(x, y, background);
+                        }
+                    }
+                }
+            }
+            Frame::Image { image, slice } => {
+                let slice = NineSlice::uniform(*slice);
+                for y in 0..frame.height {
+                    for x in 0..frame.width {
+                        let (sx, sy) = slice.source_pixel((x, y), (frame.width, frame.height), image.dimensions());
+                        let [r, g, b] = image.get_pixel(sx, sy).0;
+                        plot(x, y, u32::from_be_bytes([0, r, g, b]));
+                    }
+                }
+            }
+        }
+
+        if let Some(gap) = gap {
+            draw_text_sized(
+                canvas,
+                gap.x + TITLE_GAP,
+                gap.y,
+                Some(bounds),
+                &self.title,
+                TextStyle::with_color(self.text_color),
+                self.font_size,
+            );
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nine_slice_keeps_corners() {
+        // A 5x5 frame image: 2-pixel corners, a 1-pixel middle
+        let image = RgbImage::from_fn(5, 5, |x, y| image::Rgb([(x * 5 + y) as u8; 3]));
+        let group = GroupBox::new(10, 8).with_frame(image, 2);
+        let mut buffer = vec![0xFFFFFF; 10 * 8];
+        group.draw(&mut Canvas::new(&mut buffer, 10, 8), &Rect::new(0, 0, 10, 8), WidgetState::default());
+        // Corners come from the image's corners, the middle from its middle
+        assert_eq!([buffer[0], buffer[9], buffer[7 * 10 + 9], buffer[4 * 10 + 4]], [0x000000, 0x141414, 0x181818, 0x0C0C0C]);
+
+        // A plain border leaves the inside alone
+        let group = GroupBox::new(6, 6).with_border(0xFF0000, 2);
+        let mut buffer = vec![0xFFFFFF; 36];
+        group.draw(&mut Canvas::new(&mut buffer, 6, 6), &Rect::new(0, 0, 6, 6), WidgetState::default());
+        assert_eq!([buffer[0], buffer[7], buffer[14], buffer[35]], [0xFF0000, 0xFF0000, 0xFFFFFF, 0xFF0000]);
+    }
+}
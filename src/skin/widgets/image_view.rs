@@ -0,0 +1,199 @@
+//! Image view widget.
+//!
+//! Shows an image file whose path is read from a store binding, so an action
+//! can swap a status icon by setting one key. Paths are resolved against the
+//! view's asset directories (the skin directory and the app's `resources/`
+//! directory) and may not point outside them. Decoded images are cached per
+//! path, so switching back and forth between icons doesn't reload them.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetState};
+use crate::graphics::Canvas;
+use crate::skin::decode_image;
+use crate::skin::types::SkinError;
+
+/// An image loaded at runtime from a store-bound path.
+pub struct ImageView {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Image shown while no path is set (or the path can't be loaded).
+    fallback: Option<RgbImage>,
+    /// Directories images may be loaded from, searched in order.
+    roots: Vec<PathBuf>,
+    /// Store binding key for the image path.
+    binding: Option<String>,
+    /// Path last set from the store, as given.
+    path: String,
+    /// Image loaded for `path`, if it could be loaded.
+    current: Option<PathBuf>,
+    /// Decoded images by resolved path.
+    cache: HashMap<PathBuf, RgbImage>,
+}
+
+impl ImageView {
+    /// Create an empty image view.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            fallback: None,
+            roots: Vec::new(),
+            binding: None,
+            path: String::new(),
+            current: None,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Set the image shown while no path is set.
+    pub fn with_fallback(mut self, image: RgbImage) -> Self {
+        self.fallback = Some(image);
+        self
+    }
+
+    /// Allow images to be loaded from a directory.
+    pub fn with_root(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.add_root(dir);
+        self
+    }
+
+    /// Set the store binding key for the image path.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Allow images to be loaded from a directory.
+    pub fn add_root(&mut self, dir: impl Into<PathBuf>) {
+        let dir = dir.into();
+        if !self.roots.contains(&dir) {
+            self.roots.push(dir);
+        }
+    }
+
+    /// Get the path last set.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Show the image at a path, relative to one of the asset directories.
+    /// An empty path shows the fallback image. Setting the same path again
+    /// does nothing. If the image can't be loaded the fallback is shown and
+    /// the error returned.
+    pub fn set_path(&mut self, path: &str) -> Result<(), SkinError> {
+        if path == self.path {
+            return Ok(());
+        }
+        self.path = path.to_string();
+        self.current = None;
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        let resolved = self.resolve(path)?;
+        if !self.cache.contains_key(&resolved) {
+            let bytes = std::fs::read(&resolved)?;
+            self.cache.insert(resolved.clone(), decode_image(&bytes)?);
+        }
+        self.current = Some(resolved);
+        Ok(())
+    }
+
+    /// Find a path inside one of the asset directories. Symlinks and `..`
+    /// are resolved first, so neither can lead outside them.
+    fn resolve(&self, path: &str) -> Result<PathBuf, SkinError> {
+        for root in &self.roots {
+            let Ok(root) = root.canonicalize() else {
+                continue;
+            };
+            if let Ok(candidate) = root.join(Path::new(path)).canonicalize()
+                && candidate.starts_with(&root)
+                && candidate.is_file()
+            {
+                return Ok(candidate);
+            }
+        }
+        Err(SkinError::AssetNotFound(format!("'{}' is not in the skin or app asset directories", path)))
+    }
+
+    fn image(&self) -> Option<&RgbImage> {
+        match &self.current {
+            Some(path) => self.cache.get(path),
+            None => self.fallback.as_ref(),
+        }
+    }
+}
+
+impl Widget for ImageView {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let Some(image) = self.image() else {
+            return;
+        };
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let x = bounds.x + ix as i32;
+            let y = bounds.y + iy as i32;
+
+            // Clip to bounds
+            if x >= bounds.x && x < bounds.right() && y >= bounds.y && y < bounds.bottom() && x >= 0 && y >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(x as u32, y as u32, r, g, b);
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_stay_in_roots() {
+        let dir = std::env::temp_dir().join(format!("crix_image_view_{}", std::process::id()));
+        let assets = dir.join("assets");
+        std::fs::create_dir_all(assets.join("icons")).unwrap();
+        RgbImage::new(3, 2).save(assets.join("icons/ok.png")).unwrap();
+        RgbImage::new(1, 1).save(dir.join("secret.png")).unwrap();
+
+        let mut view = ImageView::new(10, 10).with_root(&assets);
+        view.set_path("icons/ok.png").unwrap();
+        assert_eq!(view.image().map(|i| i.dimensions()), Some((3, 2)));
+
+        // Escaping the root, by `..` or an absolute path, is refused
+        assert!(view.set_path("../secret.png").is_err());
+        assert!(view.image().is_none());
+        let absolute = dir.join("secret.png").to_string_lossy().into_owned();
+        assert!(view.set_path(&absolute).is_err());
+
+        // Absolute paths inside the root are fine
+        let inside = assets.join("icons/ok.png").to_string_lossy().into_owned();
+        view.set_path(&inside).unwrap();
+        assert!(view.image().is_some());
+        view.set_path("").unwrap();
+        assert!(view.image().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,169 @@
+//! Indicator widget.
+//!
+//! A dashboard light: shows its "on" image while the bool it is bound to is
+//! true and its "off" image otherwise. An indicator with a blink interval
+//! flashes while lit, switching between the two images each interval, and
+//! always starts a blink lit so a light that just came on shows at once.
+
+use std::any::Any;
+use std::time::Duration;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+
+/// A light showing a bool.
+pub struct Indicator {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    off: RgbImage,
+    on: RgbImage,
+    lit: bool,
+    /// Time between switching images while lit; steady when unset.
+    blink: Option<Duration>,
+    /// Whether a blinking light is in its dark half.
+    dark: bool,
+    /// Time since the light last switched images.
+    elapsed: Duration,
+    /// Store binding key of whether the light is on.
+    binding: Option<String>,
+}
+
+impl Indicator {
+    /// Create an indicator that is off.
+    pub fn new(width: u32, height: u32, off: RgbImage, on: RgbImage) -> Self {
+        Self {
+            width,
+            height,
+            off,
+            on,
+            lit: false,
+            blink: None,
+            dark: false,
+            elapsed: Duration::ZERO,
+            binding: None,
+        }
+    }
+
+    /// Blink while lit, switching images every `interval`.
+    pub fn with_blink(mut self, interval: Duration) -> Self {
+        self.blink = (!interval.is_zero()).then_some(interval);
+        self
+    }
+
+    /// Set the store binding key of whether the light is on.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Check whether the light is on.
+    pub fn is_lit(&self) -> bool {
+        self.lit
+    }
+
+    /// Check whether the light is on and blinking.
+    pub fn is_blinking(&self) -> bool {
+        self.lit && self.blink.is_some()
+    }
+
+    /// Turn the light on or off. Returns true if it changed.
+    pub fn set_lit(&mut self, lit: bool) -> bool {
+        if lit == self.lit {
+            return false;
+        }
+        self.lit = lit;
+        self.dark = false;
+        self.elapsed = Duration::ZERO;
+        true
+    }
+
+    /// Check whether the "on" image shows.
+    fn shows_on(&self) -> bool {
+        self.lit && !self.dark
+    }
+}
+
+impl Widget for Indicator {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let image = if self.shows_on() { &self.on } else { &self.off };
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = bounds.x + ix as i32;
+            let py = bounds.y + iy as i32;
+
+            if px < bounds.right() && py < bounds.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        let Some(interval) = self.blink.filter(|_| self.lit) else {
+            return false;
+        };
+        self.elapsed += dt;
+        let switches = self.elapsed.as_nanos() / interval.as_nanos();
+        if switches == 0 {
+            return false;
+        }
+        self.elapsed = Duration::from_nanos((self.elapsed.as_nanos() % interval.as_nanos()) as u64);
+        // An even number of switches comes back to the same image
+        if switches.is_multiple_of(2) {
+            return false;
+        }
+        self.dark = !self.dark;
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blinks_only_while_lit() {
+        let mut indicator = Indicator::new(8, 8, RgbImage::new(8, 8), RgbImage::new(8, 8))
+            .with_blink(Duration::from_millis(500));
+        assert!(!indicator.tick(Duration::from_secs(1)));
+        assert!(!indicator.shows_on());
+
+        assert!(indicator.set_lit(true));
+        assert!(indicator.shows_on());
+        assert!(!indicator.tick(Duration::from_millis(300)));
+        assert!(indicator.tick(Duration::from_millis(300)));
+        assert!(!indicator.shows_on());
+        assert!(indicator.tick(Duration::from_millis(500)));
+        assert!(indicator.shows_on());
+
+        // Coming on again starts a blink lit
+        assert!(indicator.tick(Duration::from_millis(500)));
+        indicator.set_lit(false);
+        assert!(indicator.set_lit(true));
+        assert!(indicator.shows_on());
+        assert!(!indicator.set_lit(true));
+    }
+}
@@ -0,0 +1,160 @@
+//! Input masks for text inputs.
+//!
+//! A mask such as `(###) ###-####` describes formatted entry: `#` is a slot
+//! for a digit, `A` for a letter and `*` for a letter or digit; every other
+//! character is a literal that is inserted automatically. The text input
+//! keeps the formatted text for display, while its store binding holds only
+//! the characters typed into slots (the raw value, e.g. `5551234567`).
+
+/// Kind of character a mask slot accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Digit,
+    Letter,
+    Alphanumeric,
+}
+
+impl Slot {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(Slot::Digit),
+            'A' => Some(Slot::Letter),
+            '*' => Some(Slot::Alphanumeric),
+            _ => None,
+        }
+    }
+
+    fn accepts(self, c: char) -> bool {
+        match self {
+            Slot::Digit => c.is_ascii_digit(),
+            Slot::Letter => c.is_ascii_alphabetic(),
+            Slot::Alphanumeric => c.is_ascii_alphanumeric(),
+        }
+    }
+}
+
+/// A parsed input mask.
+#[derive(Debug, Clone)]
+pub struct InputMask {
+    /// Mask characters; `Some` for slots, `None` for literals.
+    positions: Vec<(char, Option<Slot>)>,
+}
+
+impl InputMask {
+    /// Parse a mask pattern.
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            positions: pattern.chars().map(|c| (c, Slot::from_char(c))).collect(),
+        }
+    }
+
+    /// Check that every character of a raw value fits its slot (and so
+    /// that there are enough slots for it).
+    pub fn fits(&self, raw: &str) -> bool {
+        let mut slots = self.positions.iter().filter_map(|(_, slot)| *slot);
+        raw.chars().all(|c| slots.next().is_some_and(|slot| slot.accepts(c)))
+    }
+
+    /// Format a raw value. Literals are written up to the next empty slot,
+    /// so `555` in `(###) ###-####` becomes `(555) `.
+    pub fn format(&self, raw: &str) -> String {
+        let mut out = String::new();
+        let mut chars = raw.chars().peekable();
+        for &(c, slot) in &self.positions {
+            match slot {
+                Some(_) => match chars.next() {
+                    Some(next) => out.push(next),
+                    None => break,
+                },
+                None if chars.peek().is_some() || !out.is_empty() => out.push(c),
+                None => break,
+            }
+        }
+        out
+    }
+
+    /// Pull the raw value out of text, which may be formatted or raw.
+    /// Literals of the mask are skipped, as are characters that don't fit
+    /// the next slot.
+    pub fn raw(&self, text: &str) -> String {
+        let mut raw = String::new();
+        let mut position = 0;
+        for c in text.chars() {
+            // A literal in its place is just stepped over
+            if let Some(&(literal, None)) = self.positions.get(position)
+                && literal == c
+            {
+                position += 1;
+                continue;
+            }
+            while self.positions.get(position).is_some_and(|(_, slot)| slot.is_none()) {
+                position += 1;
+            }
+            match self.positions.get(position) {
+                Some(&(_, Some(slot))) if slot.accepts(c) => {
+                    raw.push(c);
+                    position += 1;
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        raw
+    }
+
+    /// Number of slots before a text position.
+    pub fn slots_before(&self, position: usize) -> usize {
+        self.positions.iter().take(position).filter(|(_, slot)| slot.is_some()).count()
+    }
+
+    /// Text position of the slot holding the `index`-th raw character.
+    pub fn slot_position(&self, index: usize) -> usize {
+        self.positions
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, slot))| slot.is_some())
+            .nth(index)
+            .map_or(self.positions.len(), |(position, _)| position)
+    }
+
+    /// Get the literal at a text position, if it isn't a slot.
+    pub fn literal_at(&self, position: usize) -> Option<char> {
+        match self.positions.get(position) {
+            Some(&(c, None)) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Move a text position forward past any literals.
+    pub fn skip_literals(&self, mut position: usize) -> usize {
+        while self.literal_at(position).is_some() {
+            position += 1;
+        }
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_raw() {
+        let mask = InputMask::new("(###) ###-####");
+        assert_eq!(mask.format(""), "");
+        assert_eq!(mask.format("555"), "(555) ");
+        assert_eq!(mask.format("5551234567"), "(555) 123-4567");
+
+        // Raw values come back out of formatted text, raw text and junk
+        assert_eq!(mask.raw("(555) 123-4567"), "5551234567");
+        assert_eq!(mask.raw("5551234567"), "5551234567");
+        assert_eq!(mask.raw("555.123.4567 x89"), "5551234567");
+
+        assert!(mask.fits("555"));
+        assert!(!mask.fits("55a"));
+        assert!(!mask.fits("55512345678"));
+        assert_eq!(mask.slot_position(3), 6);
+        assert_eq!(mask.slots_before(6), 3);
+        assert_eq!(mask.skip_literals(4), 6);
+    }
+}
@@ -0,0 +1,370 @@
+//! Rotary knob widget.
+//!
+//! A knob drawn from a sprite strip: an image holding the knob's rotation
+//! frames stacked top to bottom, from the minimum to the maximum value.
+//! Dragging up/down (or around the knob's center, in circular mode) changes
+//! the value, which is kept in a `min..=max` range, optionally snapped to
+//! `step`, and synced to a store key while dragging. A knob let go while
+//! turning fast coasts on and slows to a stop. An action can be dispatched
+//! when the knob is released, or once it has coasted to rest.
+
+use std::any::Any;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+use image::RgbImage;
+
+use crate::core::{Inertia, InertiaConfig, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+use crate::skin::types::KnobDrag;
+
+/// Pixels of vertical drag that turn the knob through its whole range.
+const VERTICAL_DRAG_PIXELS: f64 = 200.0;
+
+/// Angle a circular knob turns through, centered on straight up.
+const CIRCULAR_SWEEP: f64 = 1.5 * PI;
+
+/// A rotary knob bound to a numeric value.
+pub struct Knob {
+    /// Rotation frames, stacked vertically.
+    strip: RgbImage,
+    /// Number of frames in the strip.
+    frames: u32,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Value range and optional step.
+    min: f64,
+    max: f64,
+    step: Option<f64>,
+    /// Current value.
+    value: f64,
+    /// Value before snapping to the step, which a coasting knob moves on from.
+    raw: f64,
+    /// How drags turn the knob.
+    drag: KnobDrag,
+    /// Store binding key.
+    binding: Option<String>,
+    /// Action to trigger when the knob is released.
+    action: Option<String>,
+    /// Current layout bounds, for mapping pointer positions to values.
+    bounds: Option<Rect>,
+    /// Pointer y and value when a vertical drag started.
+    drag_start: Option<(i32, f64)>,
+    /// Flick tracking, in pixels of vertical drag.
+    inertia: Inertia,
+    /// Flag indicating the value was modified since last sync.
+    dirty: bool,
+    /// Set when a drag ended and the release action hasn't been taken yet.
+    released: bool,
+}
+
+impl Knob {
+    /// Create a knob from a strip of `frames` rotation frames, ranging
+    /// from 0 to 1.
+    pub fn new(width: u32, height: u32, strip: RgbImage, frames: u32) -> Self {
+        Self {
+            strip,
+            frames: frames.max(1),
+            width,
+            height,
+            min: 0.0,
+            max: 1.0,
+            step: None,
+            value: 0.0,
+            raw: 0.0,
+            drag: KnobDrag::Vertical,
+            binding: None,
+            action: None,
+            bounds: None,
+            drag_start: None,
+            inertia: Inertia::default(),
+            dirty: false,
+            released: false,
+        }
+    }
+
+    /// Set the value range. The current value is clamped into it.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max.max(min);
+        self.value = self.constrain(self.value);
+        self.raw = self.value;
+        self
+    }
+
+    /// Snap values to multiples of `step` from `min`.
+    pub fn with_step(mut self, step: f64) -> Self {
+        if step > 0.0 {
+            self.step = Some(step);
+            self.value = self.constrain(self.value);
+            self.raw = self.value;
+        }
+        self
+    }
+
+    /// Set the initial value.
+    pub fn with_value(mut self, value: f64) -> Self {
+        self.value = self.constrain(value);
+        self.raw = self.value;
+        self
+    }
+
+    /// Set how drags turn the knob.
+    pub fn with_drag(mut self, drag: KnobDrag) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the knob is released.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value without marking the knob dirty (e.g. from the store).
+    /// Ignored while dragging so the store can't fight the pointer; a new
+    /// value stops a coasting knob.
+    pub fn set_value(&mut self, value: f64) {
+        let value = self.constrain(value);
+        if self.drag_start.is_none() && value != self.value {
+            if self.inertia.is_flinging() {
+                self.inertia.stop();
+                self.released = true;
+            }
+            self.value = value;
+            self.raw = value;
+        }
+    }
+
+    /// Check if the knob is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag_start.is_some()
+    }
+
+    /// Check if the knob is coasting after being let go.
+    pub fn is_coasting(&self) -> bool {
+        self.inertia.is_flinging()
+    }
+
+    /// Check if the value has been modified since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the release action if a drag just ended, or coasting just
+    /// stopped.
+    pub fn take_release_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.released) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Clamp a value into the range and snap it to the step.
+    fn constrain(&self, value: f64) -> f64 {
+        let value = match self.step {
+            Some(step) => self.min + ((value - self.min) / step).round() * step,
+            None => value,
+        };
+        value.clamp(self.min, self.max)
+    }
+
+    /// Pixels of vertical drag from the minimum to `value`, the units the
+    /// knob's flicks are measured in.
+    fn drag_pixels(&self, value: f64) -> f32 {
+        let range = self.max - self.min;
+        if range > 0.0 { ((value - self.min) / range * VERTICAL_DRAG_PIXELS) as f32 } else { 0.0 }
+    }
+
+    /// Move to an unsnapped value, clamped into the range.
+    fn set_raw(&mut self, raw: f64) {
+        self.raw = raw.clamp(self.min, self.max);
+        let value = self.constrain(raw);
+        if value != self.value {
+            self.value = value;
+            self.dirty = true;
+        }
+    }
+
+    /// Frame of the strip showing the current value.
+    fn frame(&self) -> u32 {
+        let fraction = if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        };
+        (fraction * (self.frames - 1) as f64).round() as u32
+    }
+
+    /// Update the value from a pointer position.
+    fn set_from_pointer(&mut self, x: i32, y: i32) {
+        let value = match self.drag {
+            KnobDrag::Vertical => {
+                let Some((start_y, start_value)) = self.drag_start else {
+                    return;
+                };
+                let fraction = (start_y - y) as f64 / VERTICAL_DRAG_PIXELS;
+                start_value + fraction * (self.max - self.min)
+            }
+            KnobDrag::Circular => {
+                let Some(bounds) = self.bounds else {
+                    return;
+                };
+                let dx = (x - bounds.x) as f64 - bounds.width as f64 / 2.0;
+                let dy = (y - bounds.y) as f64 - bounds.height as f64 / 2.0;
+                if dx == 0.0 && dy == 0.0 {
+                    return;
+                }
+                // Clockwise angle from straight up, in -PI..=PI
+                let angle = dx.atan2(-dy);
+                let fraction = (angle / CIRCULAR_SWEEP + 0.5).clamp(0.0, 1.0);
+                self.min + fraction * (self.max - self.min)
+            }
+        };
+        self.inertia.track(self.drag_pixels(value));
+        self.set_raw(value);
+    }
+}
+
+impl Widget for Knob {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let frame_width = self.strip.width();
+        let frame_height = self.strip.height() / self.frames;
+        let top = self.frame() * frame_height;
+
+        // Frame centered in the bounds
+        let x = bounds.x + (bounds.width as i32 - frame_width as i32) / 2;
+        let y = bounds.y + (bounds.height as i32 - frame_height as i32) / 2;
+        for iy in 0..frame_height {
+            for ix in 0..frame_width {
+                let px = x + ix as i32;
+                let py = y + iy as i32;
+
+                if px >= bounds.x && px < bounds.right() && py >= bounds.y && py < bounds.bottom() && px >= 0 && py >= 0 {
+                    let [r, g, b] = self.strip.get_pixel(ix, top + iy).0;
+                    canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+                }
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseDown { x, y } => {
+                // Grabbing a coasting knob stops it where it is
+                self.inertia.track(self.drag_pixels(self.raw));
+                self.drag_start = Some((*y, self.value));
+                if self.drag == KnobDrag::Circular {
+                    self.set_from_pointer(*x, *y);
+                }
+                true
+            }
+            WidgetEvent::MouseMove { x, y } if self.drag_start.is_some() => {
+                self.set_from_pointer(*x, *y);
+                true
+            }
+            WidgetEvent::MouseUp { .. } if self.drag_start.is_some() => {
+                self.drag_start = None;
+                // The release action waits until a flicked knob comes to rest
+                self.released = !self.inertia.release();
+                true
+            }
+            WidgetEvent::Click => true,
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = Some(bounds);
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        self.inertia.advance(dt);
+        if !self.inertia.is_flinging() {
+            return false;
+        }
+        let pixels = self.inertia.step(dt, 1.0) as f64;
+        let raw = self.raw + pixels / VERTICAL_DRAG_PIXELS * (self.max - self.min);
+        let value = self.value;
+        self.set_raw(raw);
+        // Coasting into either end stops the knob there
+        if raw != self.raw {
+            self.inertia.stop();
+        }
+        if !self.inertia.is_flinging() {
+            self.released = true;
+        }
+        self.value != value
+    }
+
+    fn set_inertia(&mut self, inertia: &InertiaConfig) {
+        self.inertia.set_config(*inertia);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drag_turns_knob() {
+        let mut knob = Knob::new(20, 20, RgbImage::new(20, 100), 5).with_range(0.0, 100.0);
+        knob.set_bounds(Rect::new(10, 10, 20, 20));
+        assert_eq!(knob.frame(), 0);
+
+        // Dragging up by half the drag distance goes halfway
+        knob.on_event(&WidgetEvent::MouseDown { x: 20, y: 200 });
+        knob.on_event(&WidgetEvent::MouseMove { x: 20, y: 100 });
+        assert_eq!(knob.value(), 50.0);
+        assert_eq!(knob.frame(), 2);
+        assert!(knob.is_dirty());
+        knob.on_event(&WidgetEvent::MouseMove { x: 20, y: -500 });
+        assert_eq!(knob.value(), 100.0);
+        assert_eq!(knob.frame(), 4);
+        knob.on_event(&WidgetEvent::MouseUp { x: 20, y: -500 });
+        assert!(!knob.is_dragging());
+
+        // Circular knobs follow the pointer's angle: straight up is the middle,
+        // and straight right is a quarter turn along the 270 degree sweep
+        let mut knob = knob.with_drag(KnobDrag::Circular);
+        knob.on_event(&WidgetEvent::MouseDown { x: 20, y: 0 });
+        assert_eq!(knob.value(), 50.0);
+        knob.on_event(&WidgetEvent::MouseMove { x: 100, y: 20 });
+        assert!((knob.value() - 100.0 * 5.0 / 6.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,216 @@
+//! Hyperlink widget.
+//!
+//! Underlined text that either dispatches an action or opens a URL in the
+//! system browser when clicked. The pointer turns into a hand over it and
+//! the text takes the hover color, as links do on web pages.
+
+use std::any::Any;
+
+use winit::window::CursorIcon;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{ascent_sized, caret_x_styled, draw_text_sized, line_height_styled, Canvas, TextStyle};
+
+/// Link text color unless the skin says otherwise.
+pub const DEFAULT_LINK_COLOR: u32 = 0x0066CC;
+
+/// Link text color under the pointer unless the skin says otherwise.
+pub const DEFAULT_LINK_HOVER_COLOR: u32 = 0x003F80;
+
+/// A clickable, underlined piece of text.
+pub struct Link {
+    /// Text shown.
+    text: String,
+    /// Font size in pixels.
+    font_size: f32,
+    /// Text color and spacing.
+    style: TextStyle,
+    /// Text color under the pointer.
+    hover_color: u32,
+    /// Padding from the left edge.
+    padding: u32,
+    /// URL opened when clicked, if there is no action.
+    url: Option<String>,
+    /// Action dispatched when clicked.
+    action: Option<String>,
+    /// Store binding key for the text.
+    binding: Option<String>,
+}
+
+impl Link {
+    /// Create a link showing `text`.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            font_size: 16.0,
+            style: TextStyle::with_color(DEFAULT_LINK_COLOR),
+            hover_color: DEFAULT_LINK_HOVER_COLOR,
+            padding: 0,
+            url: None,
+            action: None,
+            binding: None,
+        }
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.style.color = color;
+        self
+    }
+
+    /// Set the text style (color, letter spacing, line height and tab stops).
+    pub fn with_text_style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the text color under the pointer.
+    pub fn with_hover_color(mut self, color: u32) -> Self {
+        self.hover_color = color;
+        self
+    }
+
+    /// Set the padding.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the URL opened when clicked.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Set the action dispatched when clicked. It takes precedence over the URL.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Set the store binding key for the text.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the URL.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Get the action.
+    pub fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the text style.
+    pub fn text_style(&self) -> &TextStyle {
+        &self.style
+    }
+
+    /// Get the text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set the text.
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+    }
+
+    /// Measure the width of the text.
+    fn text_width(&self) -> u32 {
+        caret_x_styled(&self.text, usize::MAX, self.font_size, &self.style)
+    }
+}
+
+impl Widget for Link {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        let content = Rect::new(
+            bounds.x + self.padding as i32,
+            bounds.y,
+            bounds.width.saturating_sub(self.padding * 2),
+            bounds.height,
+        );
+        let text_height = line_height_styled(self.font_size, &self.style);
+        let text_x = content.x;
+        let text_y = content.y + (content.height as i32 - text_height as i32) / 2;
+
+        let mut style = self.style;
+        if state.hovered {
+            style.color = self.hover_color;
+        }
+        draw_text_sized(canvas, text_x, text_y, Some(&content), &self.text, style, self.font_size);
+
+        // Underline a pixel below the baseline, as wide as the text
+        let underline_y = text_y + ascent_sized(self.font_size).round() as i32 + 1;
+        let right = (text_x + self.text_width() as i32).min(content.right());
+        if underline_y >= content.y && underline_y < content.bottom() && underline_y >= 0 {
+            for x in text_x.max(0)..right {
+                canvas.set_pixel(x as u32, underline_y as u32, style.color);
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        let width = self.text_width() + self.padding * 2;
+        (width, line_height_styled(self.font_size, &self.style))
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        matches!(event, WidgetEvent::Click)
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        CursorIcon::Pointer
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::graphics::init_font;
+
+    #[test]
+    fn test_link_draws_underline_in_hover_color() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let link = Link::new("Help").with_url("https://example.com/help");
+        assert_eq!(link.action(), None);
+        assert_eq!(link.cursor(), CursorIcon::Pointer);
+
+        let mut buffer = vec![0xFFFFFF; 100 * 30];
+        let mut canvas = Canvas::new(&mut buffer, 100, 30);
+        let hovered = WidgetState { hovered: true, ..Default::default() };
+        link.draw(&mut canvas, &Rect::new(0, 0, 100, 30), hovered);
+
+        // The underline runs under the text and stops after it
+        let text_y = (30 - line_height_styled(16.0, &link.style) as i32) / 2;
+        let underline_y = (text_y + ascent_sized(16.0).round() as i32 + 1) as u32;
+        assert_eq!(canvas.get_pixel(0, underline_y), Some(DEFAULT_LINK_HOVER_COLOR));
+        assert_eq!(canvas.get_pixel(link.text_width(), underline_y), Some(0xFFFFFF));
+    }
+}
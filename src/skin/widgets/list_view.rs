@@ -0,0 +1,600 @@
+//! List view widget.
+//!
+//! A scrollable list with one row per entry of a store list (`<list>.count`
+//! entries stored as `<list>.0.*`, `<list>.1.*`, ...). Each row is drawn with
+//! the skin's row images and a text template such as `{{#}}. {{.name}}`,
+//! filled in the same way as repeated report rows. Clicking a row (or moving
+//! with Up/Down while focused) selects it.
+//!
+//! Lists can be long, such as a log of tens of thousands of lines, so the
+//! view only holds the text of the rows around the visible ones: the app
+//! sets the row count and fills in `rows_to_fill`, and fills in again when
+//! `needs_rows` says scrolling has moved past them.
+//!
+//! A reorderable list lets rows be dragged to a new place. While dragging,
+//! the rows are drawn in the order they would end up in; on drop the app
+//! takes the move with `take_move` and moves the store entry to match.
+
+use std::any::Any;
+use std::ops::Range;
+
+use image::RgbImage;
+
+use crate::core::{KeyCode, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{draw_text_sized, line_height_sized, Canvas, TextStyle};
+
+/// Row template used when the skin doesn't give one.
+pub const DEFAULT_ROW_TEMPLATE: &str = "{{.name}}";
+
+/// How far, in pixels, a pressed row must be moved before it is dragged
+/// rather than just selected.
+const REORDER_THRESHOLD: i32 = 4;
+
+/// A row being dragged to a new place.
+#[derive(Debug, Clone, Copy)]
+struct RowDrag {
+    /// Index of the dragged row.
+    from: usize,
+    /// Index the row would be dropped at.
+    to: usize,
+    /// Pointer y where the row was pressed.
+    start_y: i32,
+    /// Whether the pointer has moved far enough to drag.
+    active: bool,
+}
+
+/// A selectable list of rows generated from a store list.
+pub struct ListView {
+    /// Row images.
+    row_normal: RgbImage,
+    row_hover: RgbImage,
+    row_selected: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Store list the rows are generated from.
+    list: String,
+    /// Text template for each row.
+    row_template: String,
+    /// Number of rows in the list.
+    row_count: usize,
+    /// Text of the rows from `first_row` on, filled from the template.
+    rows: Vec<String>,
+    first_row: usize,
+    /// Index of the selected row.
+    selected: Option<usize>,
+    /// Row under the pointer.
+    hovered: Option<usize>,
+    /// Vertical scroll offset in pixels.
+    scroll_y: u32,
+    /// Text appearance.
+    text_color: u32,
+    font_size: Option<f32>,
+    padding: u32,
+    /// Store binding key for the selected index.
+    binding: Option<String>,
+    /// Action to trigger when the selection changes.
+    action: Option<String>,
+    /// Current layout bounds.
+    bounds: Rect,
+    /// Flag indicating the selection changed since last sync.
+    dirty: bool,
+    /// Set when the selection changed and the action hasn't been taken yet.
+    changed: bool,
+    /// Whether rows can be dragged to a new place.
+    reorderable: bool,
+    /// Action to trigger after a row is dropped in a new place.
+    reorder_action: Option<String>,
+    /// Row pressed in a reorderable list.
+    drag: Option<RowDrag>,
+    /// Row move made by a drop that the app hasn't taken yet.
+    moved: Option<(usize, usize)>,
+}
+
+impl ListView {
+    /// Create a list view showing the rows of a store list.
+    pub fn new(width: u32, height: u32, list: impl Into<String>, row_normal: RgbImage, row_hover: RgbImage) -> Self {
+        Self {
+            row_selected: row_hover.clone(),
+            row_normal,
+            row_hover,
+            width,
+            height,
+            list: list.into(),
+            row_template: DEFAULT_ROW_TEMPLATE.to_string(),
+            row_count: 0,
+            rows: Vec::new(),
+            first_row: 0,
+            selected: None,
+            hovered: None,
+            scroll_y: 0,
+            text_color: 0x000000,
+            font_size: None,
+            padding: 6,
+            binding: None,
+            action: None,
+            bounds: Rect::new(0, 0, width, height),
+            dirty: false,
+            changed: false,
+            reorderable: false,
+            reorder_action: None,
+            drag: None,
+            moved: None,
+        }
+    }
+
+    /// Set the row image for the selected row.
+    pub fn with_row_selected(mut self, image: RgbImage) -> Self {
+        self.row_selected = image;
+        self
+    }
+
+    /// Set the row text template (`{{.field}}`, `{{#}}` and `{{key}}` placeholders).
+    pub fn with_row_template(mut self, template: impl Into<String>) -> Self {
+        self.row_template = template.into();
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Set the horizontal text padding.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the store binding key for the selected index.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the selection changes.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Let rows be dragged to a new place.
+    pub fn with_reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// Set the action to trigger after a row is dropped in a new place.
+    pub fn with_reorder_action(mut self, action: impl Into<String>) -> Self {
+        self.reorder_action = Some(action.into());
+        self
+    }
+
+    /// Check whether rows can be dragged to a new place.
+    pub fn is_reorderable(&self) -> bool {
+        self.reorderable
+    }
+
+    /// Get the action to trigger after a row is dropped in a new place.
+    pub fn reorder_action(&self) -> Option<&str> {
+        self.reorder_action.as_deref()
+    }
+
+    /// Take the `(from, to)` row indices of a drop the app hasn't moved the
+    /// store entry for yet.
+    pub fn take_move(&mut self) -> Option<(usize, usize)> {
+        self.moved.take()
+    }
+
+    /// Get the store list the rows are generated from.
+    pub fn list(&self) -> &str {
+        &self.list
+    }
+
+    /// Get the row text template.
+    pub fn row_template(&self) -> &str {
+        &self.row_template
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the text of the rows filled in, from `first_row` on.
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+
+    /// Get the index of the first row filled in.
+    pub fn first_row(&self) -> usize {
+        self.first_row
+    }
+
+    /// Get the number of rows in the list.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Replace all the rows with the given text. A selection past the end is
+    /// cleared.
+    pub fn set_rows(&mut self, rows: Vec<String>) {
+        self.set_row_count(rows.len());
+        self.set_filled_rows(0, rows);
+    }
+
+    /// Set the number of rows in the list, keeping the scroll position where
+    /// it still fits. A selection past the end is cleared.
+    pub fn set_row_count(&mut self, count: usize) {
+        if count == self.row_count {
+            return;
+        }
+        self.row_count = count;
+        if self.selected.is_some_and(|i| i >= count) {
+            self.selected = None;
+            self.dirty = true;
+        }
+        if self.hovered.is_some_and(|i| i >= count) {
+            self.hovered = None;
+        }
+        self.scroll_y = self.scroll_y.min(self.max_scroll());
+    }
+
+    /// Fill in the text of the rows from `first` on. Rows past the end of
+    /// the list are dropped.
+    pub fn set_filled_rows(&mut self, first: usize, mut rows: Vec<String>) {
+        rows.truncate(self.row_count.saturating_sub(first));
+        self.first_row = first;
+        self.rows = rows;
+    }
+
+    /// Get the rows to fill in: those visible and a page either side, so
+    /// small scrolls don't need new text.
+    pub fn rows_to_fill(&self) -> Range<usize> {
+        let visible = self.visible_rows();
+        let page = visible.len().max(1);
+        visible.start.saturating_sub(page)..(visible.end + page).min(self.row_count)
+    }
+
+    /// Check whether a visible row's text hasn't been filled in.
+    pub fn needs_rows(&self) -> bool {
+        let visible = self.visible_rows();
+        !visible.is_empty() && (visible.start < self.first_row || visible.end > self.first_row + self.rows.len())
+    }
+
+    /// Get the rows at least partly inside the box.
+    fn visible_rows(&self) -> Range<usize> {
+        let row_height = self.row_height() as usize;
+        let first = self.scroll_y as usize / row_height;
+        let end = (self.scroll_y as usize + self.height as usize).div_ceil(row_height);
+        first.min(self.row_count)..end.min(self.row_count)
+    }
+
+    /// Get the text of row `index`, if it has been filled in.
+    fn row_text(&self, index: usize) -> Option<&str> {
+        self.rows.get(index.checked_sub(self.first_row)?).map(String::as_str)
+    }
+
+    /// Get the selected row index.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Get the selected row's text, if it has been filled in.
+    pub fn selected_text(&self) -> Option<&str> {
+        self.row_text(self.selected?)
+    }
+
+    /// Select a row without marking the list dirty (e.g. from the store).
+    /// Out of range indices clear the selection.
+    pub fn set_selected(&mut self, index: Option<usize>) {
+        self.selected = index.filter(|&i| i < self.row_count);
+    }
+
+    /// Check if the selection has changed since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the change action if the selection just changed.
+    pub fn take_change_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.changed) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    fn row_height(&self) -> u32 {
+        self.row_normal.height().max(1)
+    }
+
+    fn max_scroll(&self) -> u32 {
+        let height = (self.row_count as u64 * self.row_height() as u64).min(u32::MAX as u64) as u32;
+        height.saturating_sub(self.height)
+    }
+
+    /// Get the row under a point.
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.bounds.contains(x, y) {
+            return None;
+        }
+        let index = ((y - self.bounds.y) as u32 + self.scroll_y) / self.row_height();
+        (index < self.row_count as u32).then_some(index as usize)
+    }
+
+    /// Get the row shown at `position` while a row is dragged: the dragged
+    /// row where it would be dropped, and the rows it passed shifted along.
+    fn row_shown_at(&self, position: usize) -> usize {
+        let Some(RowDrag { from, to, active: true, .. }) = self.drag else {
+            return position;
+        };
+        if position == to {
+            from
+        } else if from < to && (from..to).contains(&position) {
+            position + 1
+        } else if to < from && (to + 1..=from).contains(&position) {
+            position - 1
+        } else {
+            position
+        }
+    }
+
+    /// Follow the pointer with a pressed row, once it has moved far enough.
+    fn drag_row(&mut self, y: i32) {
+        let Some(mut drag) = self.drag else {
+            return;
+        };
+        if !drag.active && (y - drag.start_y).abs() < REORDER_THRESHOLD {
+            return;
+        }
+        drag.active = true;
+
+        // Past the top or bottom edge the list scrolls a row at a time
+        let row_height = self.row_height();
+        if y < self.bounds.y {
+            self.scroll_y = self.scroll_y.saturating_sub(row_height);
+        } else if y >= self.bounds.bottom() {
+            self.scroll_y = (self.scroll_y + row_height).min(self.max_scroll());
+        }
+        let inside = (y - self.bounds.y).clamp(0, self.bounds.height.saturating_sub(1) as i32) as u32;
+        let index = ((inside + self.scroll_y) / row_height) as usize;
+        drag.to = index.min(self.row_count.saturating_sub(1));
+        self.drag = Some(drag);
+    }
+
+    /// Drop a dragged row where it is, moving its text and the selection
+    /// along with it.
+    fn drop_row(&mut self) {
+        let Some(RowDrag { from, to, active: true, .. }) = self.drag.take() else {
+            return;
+        };
+        if from == to {
+            return;
+        }
+        // Rows not filled in yet are filled from the moved store entries
+        let filled = self.first_row..self.first_row + self.rows.len();
+        if filled.contains(&from) && filled.contains(&to) {
+            let text = self.rows.remove(from - self.first_row);
+            self.rows.insert(to - self.first_row, text);
+        }
+        self.moved = Some((from, to));
+        // The same entry stays selected, so only the bound index changes
+        self.selected = Some(to);
+        self.dirty = true;
+        self.scroll_to_row(to);
+    }
+
+    fn select(&mut self, index: usize) {
+        if self.selected != Some(index) {
+            self.selected = Some(index);
+            self.dirty = true;
+            self.changed = true;
+        }
+        self.scroll_to_row(index);
+    }
+
+    /// Scroll just enough to show a row.
+    fn scroll_to_row(&mut self, index: usize) {
+        let top = index as u32 * self.row_height();
+        if top < self.scroll_y {
+            self.scroll_y = top;
+        } else if top + self.row_height() > self.scroll_y + self.height {
+            self.scroll_y = top + self.row_height() - self.height.min(top + self.row_height());
+        }
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for ListView {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let row_height = self.row_height();
+        let size = self.font_size.unwrap_or(16.0);
+        let text_offset = (row_height as i32 - line_height_sized(size) as i32) / 2;
+
+        // Only draw the rows inside the box
+        let first = (self.scroll_y / row_height) as usize;
+        let visible = (bounds.height / row_height) as usize + 2;
+        for position in first..(first + visible).min(self.row_count) {
+            let y = bounds.y + (position as u32 * row_height) as i32 - self.scroll_y as i32;
+            let index = self.row_shown_at(position);
+            let image = if self.selected == Some(index) {
+                &self.row_selected
+            } else if self.hovered == Some(index) {
+                &self.row_hover
+            } else {
+                &self.row_normal
+            };
+            self.draw_image(canvas, bounds.x, y, image, bounds);
+            // Rows scrolled to since the last fill stay blank until filled
+            let Some(text) = self.row_text(index) else {
+                continue;
+            };
+            draw_text_sized(
+                canvas,
+                bounds.x + self.padding as i32,
+                y + text_offset,
+                Some(bounds),
+                text,
+                TextStyle::with_color(self.text_color),
+                size,
+            );
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseMove { x, y } => {
+                self.hovered = self.row_at(*x, *y);
+                self.drag_row(*y);
+                true
+            }
+            WidgetEvent::MouseDown { x, y } => {
+                if let Some(index) = self.row_at(*x, *y) {
+                    self.select(index);
+                    if self.reorderable {
+                        self.drag = Some(RowDrag { from: index, to: index, start_y: *y, active: false });
+                    }
+                }
+                true
+            }
+            WidgetEvent::MouseUp { .. } => {
+                self.drop_row();
+                true
+            }
+            WidgetEvent::MouseWheel { delta_y } if self.max_scroll() > 0 => {
+                self.scroll_y = (self.scroll_y as f32 - delta_y).clamp(0.0, self.max_scroll() as f32) as u32;
+                true
+            }
+            WidgetEvent::KeyDown { key } => {
+                let Some(last) = self.row_count.checked_sub(1) else {
+                    return false;
+                };
+                let next = match (key.without_select(), self.selected) {
+                    (KeyCode::Up, Some(i)) => i.saturating_sub(1),
+                    (KeyCode::Down, Some(i)) => (i + 1).min(last),
+                    (KeyCode::Up | KeyCode::Down, None) => 0,
+                    (KeyCode::Home, _) => 0,
+                    (KeyCode::End, _) => last,
+                    _ => return false,
+                };
+                self.select(next);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_rows() {
+        let mut list = ListView::new(100, 30, "items", RgbImage::new(100, 10), RgbImage::new(100, 10));
+        list.set_bounds(Rect::new(0, 0, 100, 30));
+        list.set_rows((1..=5).map(|i| format!("Item {}", i)).collect());
+
+        list.on_event(&WidgetEvent::MouseDown { x: 5, y: 15 });
+        assert_eq!(list.selected_text(), Some("Item 2"));
+        assert!(list.is_dirty());
+        assert_eq!(list.take_change_action(), None);
+
+        // Moving past the visible rows scrolls them into view
+        list.on_event(&WidgetEvent::KeyDown { key: KeyCode::End });
+        assert_eq!(list.selected(), Some(4));
+        list.on_event(&WidgetEvent::MouseDown { x: 5, y: 25 });
+        assert_eq!(list.selected(), Some(4));
+
+        // Shrinking the list drops a selection past the end
+        list.set_rows(vec!["Only".to_string()]);
+        assert_eq!(list.selected(), None);
+    }
+
+    #[test]
+    fn test_fills_only_rows_near_view() {
+        let mut list = ListView::new(100, 30, "log", RgbImage::new(100, 10), RgbImage::new(100, 10));
+        list.set_bounds(Rect::new(0, 0, 100, 30));
+        list.set_row_count(50_000);
+        assert!(list.needs_rows());
+        assert_eq!(list.rows_to_fill(), 0..6);
+        list.set_filled_rows(0, (0..6).map(|i| format!("Line {}", i)).collect());
+        assert!(!list.needs_rows());
+
+        // Jumping to the end needs the last rows filled in, and the selection's text until then
+        list.on_event(&WidgetEvent::KeyDown { key: KeyCode::End });
+        assert_eq!(list.selected(), Some(49_999));
+        assert_eq!(list.selected_text(), None);
+        assert!(list.needs_rows());
+        assert_eq!(list.rows_to_fill(), 49_994..50_000);
+        list.set_filled_rows(49_994, (49_994..50_003).map(|i| format!("Line {}", i)).collect());
+        assert_eq!(list.rows().len(), 6);
+        assert_eq!(list.selected_text(), Some("Line 49999"));
+    }
+
+    #[test]
+    fn test_drag_row_to_new_place() {
+        let mut list = ListView::new(100, 50, "tasks", RgbImage::new(100, 10), RgbImage::new(100, 10))
+            .with_reorderable(true);
+        list.set_bounds(Rect::new(0, 0, 100, 50));
+        list.set_rows(["a", "b", "c", "d"].map(String::from).to_vec());
+
+        // A press without moving only selects
+        list.on_event(&WidgetEvent::MouseDown { x: 5, y: 5 });
+        list.on_event(&WidgetEvent::MouseMove { x: 5, y: 7 });
+        list.on_event(&WidgetEvent::MouseUp { x: 5, y: 7 });
+        assert_eq!(list.take_move(), None);
+
+        list.on_event(&WidgetEvent::MouseDown { x: 5, y: 5 });
+        list.on_event(&WidgetEvent::MouseMove { x: 5, y: 25 });
+        let shown: Vec<usize> = (0..4).map(|position| list.row_shown_at(position)).collect();
+        assert_eq!(shown, [1, 2, 0, 3]);
+        list.clear_dirty();
+        list.on_event(&WidgetEvent::MouseUp { x: 5, y: 25 });
+        assert_eq!(list.take_move(), Some((0, 2)));
+        assert_eq!(list.rows(), ["b", "c", "a", "d"]);
+        assert_eq!(list.selected(), Some(2));
+        assert!(list.is_dirty());
+        assert_eq!(list.row_shown_at(0), 0);
+    }
+}
@@ -0,0 +1,442 @@
+//! Menu bar widget.
+//!
+//! A row of menu titles along the top of the part. Clicking a title opens
+//! its menu below it, drawn on top of the other widgets; clicking an entry
+//! closes the menu and hands its action to the app. While a menu is open,
+//! moving the pointer onto another title switches to that menu. With the
+//! bar focused, Left/Right move between titles, Up/Down move through the
+//! open menu, Enter opens a menu or chooses the highlighted entry and Escape
+//! closes the menu.
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{KeyCode, MenuItem, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{caret_x_sized, draw_text_sized, line_height_sized, Canvas, TextStyle};
+
+/// A bar of titles that open drop-down menus of actions.
+pub struct MenuBar {
+    /// Background drawn behind the titles.
+    bar: Option<RgbImage>,
+    /// Title images; their width sets the width of each title.
+    title: RgbImage,
+    title_hover: RgbImage,
+    title_open: RgbImage,
+    /// Menu row images; their size sets the menu width and row height.
+    item_normal: RgbImage,
+    item_hover: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Menu titles and their entries, in order.
+    menus: Vec<(String, Vec<MenuItem>)>,
+    /// Label appearance.
+    text_color: u32,
+    font_size: f32,
+    padding: u32,
+    /// Title with the keyboard highlight, whose menu shows while `open`.
+    current: usize,
+    open: bool,
+    /// Title under the pointer.
+    hovered: Option<usize>,
+    /// Entry of the open menu under the pointer or keyboard highlight.
+    highlighted: Option<usize>,
+    /// Last pointer position.
+    pointer: (i32, i32),
+    /// Current layout bounds.
+    bounds: Rect,
+    /// Action of the entry just chosen, not yet taken by the app.
+    chosen: Option<String>,
+}
+
+impl MenuBar {
+    /// Create a menu bar with no menus.
+    pub fn new(
+        width: u32,
+        height: u32,
+        title: RgbImage,
+        title_hover: RgbImage,
+        item_normal: RgbImage,
+        item_hover: RgbImage,
+    ) -> Self {
+        Self {
+            bar: None,
+            title_open: title_hover.clone(),
+            title,
+            title_hover,
+            item_normal,
+            item_hover,
+            width,
+            height,
+            menus: Vec::new(),
+            text_color: 0x000000,
+            font_size: 16.0,
+            padding: 6,
+            current: 0,
+            open: false,
+            hovered: None,
+            highlighted: None,
+            pointer: (0, 0),
+            bounds: Rect::new(0, 0, width, height),
+            chosen: None,
+        }
+    }
+
+    /// Add a menu.
+    pub fn with_menu(mut self, title: impl Into<String>, items: Vec<MenuItem>) -> Self {
+        self.menus.push((title.into(), items));
+        self
+    }
+
+    /// Set the background drawn behind the titles.
+    pub fn with_bar(mut self, image: RgbImage) -> Self {
+        self.bar = Some(image);
+        self
+    }
+
+    /// Set the image of the title whose menu is open.
+    pub fn with_title_open(mut self, image: RgbImage) -> Self {
+        self.title_open = image;
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set the padding left of menu entry labels.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Get the menu titles, in order.
+    pub fn titles(&self) -> impl Iterator<Item = &str> {
+        self.menus.iter().map(|(title, _)| title.as_str())
+    }
+
+    /// Get the index of the open menu.
+    pub fn open_menu(&self) -> Option<usize> {
+        self.open.then_some(self.current)
+    }
+
+    /// Close the open menu.
+    pub fn close(&mut self) {
+        self.open = false;
+        self.highlighted = None;
+    }
+
+    /// Take the action of the entry just chosen.
+    pub fn take_chosen_action(&mut self) -> Option<String> {
+        self.chosen.take()
+    }
+
+    fn title_width(&self) -> u32 {
+        self.title.width().max(1)
+    }
+
+    fn row_height(&self) -> u32 {
+        self.item_normal.height().max(1)
+    }
+
+    fn items(&self) -> &[MenuItem] {
+        self.menus.get(self.current).map_or(&[], |(_, items)| items.as_slice())
+    }
+
+    /// Area of the open menu below its title.
+    fn menu_rect(&self, bounds: &Rect) -> Rect {
+        Rect::new(
+            bounds.x + (self.current as u32 * self.title_width()) as i32,
+            bounds.y + self.title.height() as i32,
+            self.item_normal.width(),
+            self.row_height() * self.items().len() as u32,
+        )
+    }
+
+    /// Get the title under a point.
+    fn title_at(&self, x: i32, y: i32) -> Option<usize> {
+        let strip = Rect::new(self.bounds.x, self.bounds.y, self.bounds.width, self.title.height());
+        if !strip.contains(x, y) {
+            return None;
+        }
+        let index = ((x - strip.x) as u32 / self.title_width()) as usize;
+        (index < self.menus.len()).then_some(index)
+    }
+
+    /// Get the entry of the open menu under a point.
+    fn item_at(&self, x: i32, y: i32) -> Option<usize> {
+        let menu = self.menu_rect(&self.bounds);
+        if !self.open || !menu.contains(x, y) {
+            return None;
+        }
+        let index = ((y - menu.y) as u32 / self.row_height()) as usize;
+        (index < self.items().len()).then_some(index)
+    }
+
+    fn open_at(&mut self, index: usize) {
+        if index >= self.menus.len() {
+            return;
+        }
+        self.current = index;
+        self.open = true;
+        self.highlighted = None;
+    }
+
+    fn choose(&mut self, index: usize) {
+        self.chosen = self.items().get(index).map(|item| item.action.clone());
+        self.close();
+    }
+
+    /// Move the keyboard highlight to the next or previous title, wrapping.
+    fn step_title(&mut self, forward: bool) {
+        let count = self.menus.len();
+        if count == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % count
+        } else {
+            (self.current + count - 1) % count
+        };
+        self.highlighted = None;
+    }
+
+    /// Move the highlight in the open menu, opening it first if needed.
+    fn step_item(&mut self, forward: bool) {
+        if !self.open {
+            self.open_at(self.current);
+        }
+        let Some(last) = self.items().len().checked_sub(1) else {
+            return;
+        };
+        self.highlighted = Some(match (self.highlighted, forward) {
+            (None, true) => 0,
+            (None, false) => last,
+            (Some(i), true) => (i + 1).min(last),
+            (Some(i), false) => i.saturating_sub(1),
+        });
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for MenuBar {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        if let Some(bar) = &self.bar {
+            self.draw_image(canvas, bounds.x, bounds.y, bar, bounds);
+        }
+
+        let strip_height = self.title.height() as i32;
+        let text_y = bounds.y + (strip_height - line_height_sized(self.font_size) as i32) / 2;
+        for (index, (title, _)) in self.menus.iter().enumerate() {
+            let x = bounds.x + (index as u32 * self.title_width()) as i32;
+            let image = if self.open && index == self.current {
+                &self.title_open
+            } else if self.hovered == Some(index) || (state.focused && index == self.current) {
+                &self.title_hover
+            } else {
+                &self.title
+            };
+            self.draw_image(canvas, x, bounds.y, image, bounds);
+
+            // Title centered on its image
+            let label_width = caret_x_sized(title, usize::MAX, self.font_size) as i32;
+            draw_text_sized(
+                canvas,
+                x + (self.title_width() as i32 - label_width) / 2,
+                text_y,
+                Some(bounds),
+                title,
+                TextStyle::with_color(self.text_color),
+                self.font_size,
+            );
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseMove { x, y } => {
+                self.pointer = (*x, *y);
+                self.hovered = self.title_at(*x, *y);
+                if self.open {
+                    // Sliding along the bar switches menus
+                    if let Some(index) = self.hovered
+                        && index != self.current
+                    {
+                        self.open_at(index);
+                    }
+                    if let Some(index) = self.item_at(*x, *y) {
+                        self.highlighted = Some(index);
+                    }
+                }
+                true
+            }
+            WidgetEvent::Click => {
+                let (x, y) = self.pointer;
+                if let Some(index) = self.title_at(x, y) {
+                    if self.open && index == self.current {
+                        self.close();
+                    } else {
+                        self.open_at(index);
+                    }
+                } else if let Some(index) = self.item_at(x, y) {
+                    self.choose(index);
+                } else {
+                    self.close();
+                }
+                true
+            }
+            WidgetEvent::KeyDown { key } => match key.without_select() {
+                KeyCode::Left => {
+                    self.step_title(false);
+                    true
+                }
+                KeyCode::Right => {
+                    self.step_title(true);
+                    true
+                }
+                KeyCode::Up => {
+                    self.step_item(false);
+                    true
+                }
+                KeyCode::Down => {
+                    self.step_item(true);
+                    true
+                }
+                KeyCode::Enter => {
+                    match (self.open, self.highlighted) {
+                        (true, Some(index)) => self.choose(index),
+                        (true, None) => self.close(),
+                        (false, _) => self.step_item(true),
+                    }
+                    true
+                }
+                KeyCode::Escape if self.open => {
+                    self.close();
+                    true
+                }
+                _ => false,
+            },
+            // Clicking elsewhere moves focus away and closes the menu
+            WidgetEvent::FocusLost => {
+                self.close();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn overlay_bounds(&self, bounds: &Rect) -> Option<Rect> {
+        self.open.then(|| self.menu_rect(bounds))
+    }
+
+    fn draw_overlay(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let menu = self.menu_rect(bounds);
+        let row_height = self.row_height() as i32;
+        let text_offset = (row_height - line_height_sized(self.font_size) as i32) / 2;
+        for (index, item) in self.items().iter().enumerate() {
+            let row = Rect::new(menu.x, menu.y + index as i32 * row_height, menu.width, row_height as u32);
+            let image = if self.highlighted == Some(index) {
+                &self.item_hover
+            } else {
+                &self.item_normal
+            };
+            self.draw_image(canvas, row.x, row.y, image, &row);
+            draw_text_sized(
+                canvas,
+                row.x + self.padding as i32,
+                row.y + text_offset,
+                Some(&row),
+                &item.label,
+                TextStyle::with_color(self.text_color),
+                self.font_size,
+            );
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(actions: &[&str]) -> Vec<MenuItem> {
+        actions
+            .iter()
+            .map(|action| MenuItem {
+                label: action.to_string(),
+                action: action.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_menu_bar_mouse_and_keyboard() {
+        let image = |w, h| RgbImage::new(w, h);
+        let mut bar = MenuBar::new(300, 20, image(60, 20), image(60, 20), image(120, 10), image(120, 10))
+            .with_menu("File", items(&["new", "open", "quit"]))
+            .with_menu("Edit", items(&["copy", "paste"]));
+        bar.set_bounds(Rect::new(0, 0, 300, 20));
+        let bounds = Rect::new(0, 0, 300, 20);
+        assert_eq!(bar.overlay_bounds(&bounds), None);
+
+        // Open File, slide over to Edit and choose its second entry
+        bar.on_event(&WidgetEvent::MouseMove { x: 10, y: 5 });
+        bar.on_event(&WidgetEvent::Click);
+        assert_eq!(bar.overlay_bounds(&bounds), Some(Rect::new(0, 20, 120, 30)));
+        bar.on_event(&WidgetEvent::MouseMove { x: 70, y: 5 });
+        assert_eq!(bar.open_menu(), Some(1));
+        bar.on_event(&WidgetEvent::MouseMove { x: 70, y: 35 });
+        bar.on_event(&WidgetEvent::Click);
+        assert_eq!(bar.open_menu(), None);
+        assert_eq!(bar.take_chosen_action(), Some("paste".to_string()));
+        assert_eq!(bar.take_chosen_action(), None);
+
+        // Right wraps back to File, Down opens it on its first entry
+        bar.on_event(&WidgetEvent::KeyDown { key: KeyCode::Right });
+        bar.on_event(&WidgetEvent::KeyDown { key: KeyCode::Down });
+        assert_eq!(bar.open_menu(), Some(0));
+        bar.on_event(&WidgetEvent::KeyDown { key: KeyCode::Down });
+        bar.on_event(&WidgetEvent::KeyDown { key: KeyCode::Enter });
+        assert_eq!(bar.take_chosen_action(), Some("open".to_string()));
+
+        bar.on_event(&WidgetEvent::KeyDown { key: KeyCode::Enter });
+        assert!(bar.on_event(&WidgetEvent::KeyDown { key: KeyCode::Escape }));
+        assert_eq!(bar.open_menu(), None);
+        assert!(!bar.on_event(&WidgetEvent::KeyDown { key: KeyCode::Escape }));
+    }
+}
@@ -1,17 +1,79 @@
+mod animated_sprite;
 mod checkbox;
+mod color_picker;
+mod custom_paint;
 mod directory_picker;
+mod dropdown;
+mod edit_history;
+mod file_path_input;
 mod file_picker;
+mod gauge;
+mod group_box;
+mod image_view;
+mod indicator;
+mod input_mask;
+mod knob;
+mod link;
+mod list_view;
+mod menu_bar;
+mod plot;
+mod progress_bar;
+mod script_canvas;
+mod scroll_view;
+mod seven_segment;
 mod skin_button;
 mod skin_image;
+#[cfg(feature = "video")]
+mod skin_video;
 mod skin_vscroll;
+mod slider;
+mod spinner;
 mod static_text;
+mod status_bar;
+mod stack;
+mod tab_container;
+mod step_progress;
+mod text_area;
 mod text_input;
+mod timer_display;
+mod toggle_switch;
+mod tree_view;
 
+pub use animated_sprite::{AnimatedSprite, DEFAULT_SPRITE_FPS};
 pub use checkbox::Checkbox;
+pub use color_picker::ColorPicker;
+pub use custom_paint::{CustomPaint, Painter, PainterRegistry};
 pub use directory_picker::DirectoryPicker;
+pub use dropdown::Dropdown;
+pub use file_path_input::FilePathInput;
 pub use file_picker::FilePicker;
+pub use gauge::{Gauge, DEFAULT_END_ANGLE, DEFAULT_START_ANGLE};
+pub use group_box::{GroupBox, DEFAULT_GROUP_BORDER_COLOR};
+pub use image_view::ImageView;
+pub use indicator::Indicator;
+pub use knob::Knob;
+pub use link::{Link, DEFAULT_LINK_COLOR, DEFAULT_LINK_HOVER_COLOR};
+pub use list_view::ListView;
+pub use menu_bar::MenuBar;
+pub use plot::{Plot, DEFAULT_AXIS_COLOR, DEFAULT_PLOT_COLORS, MAX_PLOT_POINTS};
+pub use progress_bar::ProgressBar;
+pub use script_canvas::CanvasWidget;
+pub use scroll_view::ScrollView;
+pub use seven_segment::{SevenSegment, DEFAULT_SEGMENT_GLYPHS};
 pub use skin_button::SkinButton;
 pub use skin_image::SkinImage;
+#[cfg(feature = "video")]
+pub use skin_video::{SkinVideo, DEFAULT_VIDEO_BINDING};
 pub use skin_vscroll::SkinVScroll;
+pub use slider::Slider;
+pub use spinner::{Spinner, SPINNER_REPEAT_DELAY, SPINNER_REPEAT_INTERVAL};
+pub use stack::Stack;
 pub use static_text::StaticText;
-pub use text_input::TextInput;
+pub use status_bar::{StatusBar, DEFAULT_STATUS_SEPARATOR_COLOR};
+pub use tab_container::TabContainer;
+pub use step_progress::StepProgress;
+pub use text_area::TextArea;
+pub use text_input::{TextInput, TextLine, DEFAULT_PLACEHOLDER_COLOR};
+pub use timer_display::{format_time, is_time_format, SkinTimerDisplay, DEFAULT_TIME_FORMAT};
+pub use toggle_switch::{ToggleSwitch, TOGGLE_SLIDE_DURATION};
+pub use tree_view::{TreeRow, TreeView};
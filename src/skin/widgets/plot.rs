@@ -0,0 +1,271 @@
+//! Plot widget.
+//!
+//! A read-only line or bar chart of one or more series of numbers, each read
+//! from a store list (`<list>.count` values stored as `<list>.0`, `<list>.1`,
+//! ...) so Lua actions can compute the data and the chart follows it. Sample
+//! `n` of a series sits at x = n. Axis ranges come from the skin; a side left
+//! unset fits the data, and the y range always takes in zero so bars have a
+//! baseline to grow from.
+
+use std::any::Any;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{Canvas, DrawCommand};
+use crate::skin::types::PlotStyle;
+
+/// Series colors, in order, for series the skin gives no color.
+pub const DEFAULT_PLOT_COLORS: &[u32] = &[0x2080E0, 0xE06020, 0x20A040, 0xC02060, 0x8040C0, 0x808020];
+
+/// Axis line color unless the skin says otherwise.
+pub const DEFAULT_AXIS_COLOR: u32 = 0x808080;
+
+/// Most samples read from a series; the rest are ignored.
+pub const MAX_PLOT_POINTS: usize = 10_000;
+
+/// One series of samples.
+#[derive(Debug, Clone)]
+struct Series {
+    /// Store list holding the samples.
+    list: String,
+    color: u32,
+    /// Samples as last read from the store; gaps are NaN.
+    values: Vec<f64>,
+}
+
+/// A chart of store lists.
+pub struct Plot {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Lines or bars.
+    style: PlotStyle,
+    series: Vec<Series>,
+    /// Axis ranges from the skin; unset sides fit the data.
+    x_range: (Option<f64>, Option<f64>),
+    y_range: (Option<f64>, Option<f64>),
+    /// Line width in pixels.
+    thickness: u32,
+    axis_color: u32,
+    /// Color filled in behind the chart; transparent when unset.
+    background: Option<u32>,
+}
+
+impl Plot {
+    /// Create a line chart with no series.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            style: PlotStyle::Line,
+            series: Vec::new(),
+            x_range: (None, None),
+            y_range: (None, None),
+            thickness: 2,
+            axis_color: DEFAULT_AXIS_COLOR,
+            background: None,
+        }
+    }
+
+    /// Set whether samples are joined by lines or drawn as bars.
+    pub fn with_style(mut self, style: PlotStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Add a series read from store list `list`, in the next default color
+    /// unless `color` is given.
+    pub fn with_series(mut self, list: impl Into<String>, color: Option<u32>) -> Self {
+        let color = color.unwrap_or(DEFAULT_PLOT_COLORS[self.series.len() % DEFAULT_PLOT_COLORS.len()]);
+        self.series.push(Series { list: list.into(), color, values: Vec::new() });
+        self
+    }
+
+    /// Set the x axis range in sample numbers; unset sides fit the data.
+    pub fn with_x_range(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.x_range = (min, max);
+        self
+    }
+
+    /// Set the y axis range; unset sides fit the data.
+    pub fn with_y_range(mut self, min: Option<f64>, max: Option<f64>) -> Self {
+        self.y_range = (min, max);
+        self
+    }
+
+    /// Set the line width in pixels.
+    pub fn with_thickness(mut self, thickness: u32) -> Self {
+        self.thickness = thickness.max(1);
+        self
+    }
+
+    /// Set the axis line color.
+    pub fn with_axis_color(mut self, color: u32) -> Self {
+        self.axis_color = color;
+        self
+    }
+
+    /// Set the background color.
+    pub fn with_background(mut self, color: u32) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Get the store lists the series are read from, in order.
+    pub fn lists(&self) -> impl Iterator<Item = &str> {
+        self.series.iter().map(|series| series.list.as_str())
+    }
+
+    /// Get the samples of series `index`.
+    pub fn values(&self, index: usize) -> &[f64] {
+        self.series.get(index).map_or(&[], |series| &series.values)
+    }
+
+    /// Replace the samples of series `index`, keeping at most
+    /// `MAX_PLOT_POINTS`. Returns true if they changed.
+    pub fn set_values(&mut self, index: usize, mut values: Vec<f64>) -> bool {
+        let Some(series) = self.series.get_mut(index) else {
+            return false;
+        };
+        values.truncate(MAX_PLOT_POINTS);
+        // NaN gaps never compare equal, so compare bit patterns
+        let same = values.len() == series.values.len()
+            && values.iter().zip(&series.values).all(|(a, b)| a.to_bits() == b.to_bits());
+        series.values = values;
+        !same
+    }
+
+    /// Get the x axis range in sample numbers. Bars take a slot per sample,
+    /// so by default the range runs half a slot past the first and last.
+    fn x_bounds(&self) -> (f64, f64) {
+        let samples = self.series.iter().map(|series| series.values.len()).max().unwrap_or(0);
+        let (min, max) = match self.style {
+            PlotStyle::Line => (0.0, samples.saturating_sub(1) as f64),
+            PlotStyle::Bar => (-0.5, samples as f64 - 0.5),
+        };
+        let min = self.x_range.0.unwrap_or(min);
+        let max = self.x_range.1.unwrap_or(max);
+        if max > min { (min, max) } else { (min, min + 1.0) }
+    }
+
+    /// Get the y axis range, fitting unset sides to the data and zero.
+    fn y_bounds(&self) -> (f64, f64) {
+        let finite = || self.series.iter().flat_map(|series| &series.values).copied().filter(|v| v.is_finite());
+        let min = self.y_range.0.unwrap_or_else(|| finite().fold(0.0, f64::min));
+        let max = self.y_range.1.unwrap_or_else(|| finite().fold(0.0, f64::max));
+        if max > min { (min, max) } else { (min, min + 1.0) }
+    }
+}
+
+impl Widget for Plot {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let Some(clip) = canvas.clip_rect().map_or(Some(*bounds), |clip| clip.intersect(bounds)) else {
+            return;
+        };
+        if let Some(color) = self.background
+            && clip.right() > 0
+            && clip.bottom() > 0
+        {
+            let (x, y) = (clip.x.max(0), clip.y.max(0));
+            canvas.fill_rect(x as u32, y as u32, (clip.right() - x) as u32, (clip.bottom() - y) as u32, color);
+        }
+        if bounds.width < 2 || bounds.height < 2 {
+            return;
+        }
+
+        // Map samples to pixels relative to the part, y growing upwards
+        let (x_min, x_max) = self.x_bounds();
+        let (y_min, y_max) = self.y_bounds();
+        let (right, bottom) = ((bounds.width - 1) as f64, (bounds.height - 1) as f64);
+        let px = |x: f64| (x - x_min) / (x_max - x_min) * right;
+        let py = |y: f64| bottom - (y.clamp(y_min, y_max) - y_min) / (y_max - y_min) * bottom;
+        let origin = (bounds.x, bounds.y);
+        let baseline = py(0.0);
+
+        match self.style {
+            PlotStyle::Line => {
+                for series in &self.series {
+                    // Gaps break the line
+                    let mut last = None;
+                    for (i, &y) in series.values.iter().enumerate() {
+                        if !y.is_finite() {
+                            last = None;
+                            continue;
+                        }
+                        let point = (px(i as f64), py(y));
+                        let line = DrawCommand::line(last.unwrap_or(point), point, series.color, self.thickness as f64);
+                        line.draw(canvas, origin, &clip);
+                        last = Some(point);
+                    }
+                }
+            }
+            PlotStyle::Bar => {
+                // Series share each slot side by side, with a gap between slots
+                let slot = right / (x_max - x_min);
+                let width = slot * 0.8 / self.series.len().max(1) as f64;
+                for (s, series) in self.series.iter().enumerate() {
+                    for (i, &y) in series.values.iter().enumerate() {
+                        if !y.is_finite() {
+                            continue;
+                        }
+                        let left = px(i as f64) - slot * 0.4 + width * s as f64;
+                        let top = py(y).min(baseline);
+                        let height = (py(y) - baseline).abs().max(1.0);
+                        let bar = DrawCommand::rect(left, top, width.max(1.0), height, series.color, true);
+                        bar.draw(canvas, origin, &clip);
+                    }
+                }
+            }
+        }
+
+        // Axes: the left edge, and zero (or the bottom edge when zero is out of range)
+        DrawCommand::line((0.0, 0.0), (0.0, bottom), self.axis_color, 1.0).draw(canvas, origin, &clip);
+        DrawCommand::line((0.0, baseline), (right, baseline), self.axis_color, 1.0).draw(canvas, origin, &clip);
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranges_fit_data_and_zero() {
+        let mut plot = Plot::new(100, 50).with_series("temps", None).with_series("rain", Some(0x00FF00));
+        assert!(plot.set_values(0, vec![5.0, 12.0, f64::NAN, 8.0]));
+        assert!(!plot.set_values(0, vec![5.0, 12.0, f64::NAN, 8.0]));
+        assert!(plot.set_values(1, vec![-3.0, 1.0]));
+        assert!(!plot.set_values(2, vec![1.0]));
+        assert_eq!(plot.lists().collect::<Vec<_>>(), ["temps", "rain"]);
+
+        assert_eq!(plot.x_bounds(), (0.0, 3.0));
+        assert_eq!(plot.y_bounds(), (-3.0, 12.0));
+
+        // Skin ranges win, and bars get half a slot either side
+        let plot = plot.with_style(PlotStyle::Bar).with_y_range(None, Some(20.0));
+        assert_eq!(plot.x_bounds(), (-0.5, 3.5));
+        assert_eq!(plot.y_bounds(), (-3.0, 20.0));
+
+        let mut buffer = vec![0xFFFFFF; 120 * 70];
+        let mut canvas = Canvas::new(&mut buffer, 120, 70);
+        plot.draw(&mut canvas, &Rect::new(10, 10, 100, 50), WidgetState::default());
+        assert!(buffer.contains(&DEFAULT_PLOT_COLORS[0]) && buffer.contains(&0x00FF00));
+        // Nothing is drawn outside the part
+        let inside = |i: usize| (10..110).contains(&(i % 120)) && (10..60).contains(&(i / 120));
+        assert!(buffer.iter().enumerate().all(|(i, &pixel)| inside(i) || pixel == 0xFFFFFF));
+    }
+}
@@ -0,0 +1,136 @@
+//! Progress bar widget.
+//!
+//! A horizontal bar drawn from a track image with a fill image over it. The
+//! fill is cut off at the current value, a percentage from 0 to 100 read from
+//! a store binding, so scripts drive the bar by setting that key (calling
+//! `app.yield()` between steps to show progress while they run).
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+
+/// A bar showing a 0-100 percentage.
+pub struct ProgressBar {
+    /// Track image, drawn behind the fill.
+    track: RgbImage,
+    /// Fill image, shown up to the current value.
+    fill: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Current value, 0 to 100.
+    value: f64,
+    /// Store binding key for the value.
+    binding: Option<String>,
+}
+
+impl ProgressBar {
+    /// Create an empty progress bar from a track and fill image.
+    pub fn new(width: u32, height: u32, track: RgbImage, fill: RgbImage) -> Self {
+        Self {
+            track,
+            fill,
+            width,
+            height,
+            value: 0.0,
+            binding: None,
+        }
+    }
+
+    /// Set the initial value.
+    pub fn with_value(mut self, value: f64) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value, clamped to 0-100. Returns true if it changed.
+    pub fn set_value(&mut self, value: f64) -> bool {
+        let value = if value.is_nan() { 0.0 } else { value.clamp(0.0, 100.0) };
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+
+    /// Width of the fill in pixels for a bar of the given width.
+    fn fill_width(&self, width: u32) -> u32 {
+        (self.value / 100.0 * width as f64).round() as u32
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for ProgressBar {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let track_y = bounds.y + (bounds.height as i32 - self.track.height() as i32) / 2;
+        self.draw_image(canvas, bounds.x, track_y, &self.track, bounds);
+
+        // Fill from the left edge, cut off at the value
+        let clip = Rect::new(bounds.x, bounds.y, self.fill_width(bounds.width), bounds.height);
+        let fill_y = bounds.y + (bounds.height as i32 - self.fill.height() as i32) / 2;
+        self.draw_image(canvas, bounds.x, fill_y, &self.fill, &clip);
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_follows_value() {
+        let mut bar = ProgressBar::new(200, 10, RgbImage::new(200, 10), RgbImage::new(200, 10));
+        assert!(bar.set_value(25.0));
+        assert_eq!(bar.fill_width(200), 50);
+        assert!(!bar.set_value(25.0));
+
+        // Out of range values are clamped
+        bar.set_value(250.0);
+        assert_eq!(bar.fill_width(200), 200);
+        bar.set_value(f64::NAN);
+        assert_eq!(bar.value(), 0.0);
+    }
+}
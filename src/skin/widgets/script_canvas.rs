@@ -0,0 +1,130 @@
+//! Scripted canvas widget.
+//!
+//! Shows a drawing made by Lua actions with `canvas.line`, `canvas.rect` and
+//! `canvas.text`. The drawing is a list of draw commands kept in the store
+//! key the part is bound to; the widget parses it when the value changes and
+//! replays it on every draw, so charts and custom visuals need no new Rust
+//! widget.
+
+use std::any::Any;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{parse_draw_commands, Canvas, DrawCommand};
+
+/// A part drawn by scripts.
+pub struct CanvasWidget {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Color filled in before the drawing; transparent when unset.
+    background: Option<u32>,
+    /// Store binding key for the drawing.
+    binding: Option<String>,
+    /// The drawing as last read from the store.
+    source: String,
+    /// Commands parsed from the drawing.
+    commands: Vec<DrawCommand>,
+}
+
+impl CanvasWidget {
+    /// Create an empty canvas.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            background: None,
+            binding: None,
+            source: String::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Set the background color.
+    pub fn with_background(mut self, color: u32) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Set the store binding key for the drawing.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the drawn commands.
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Replace the drawing with one read from the store. Returns true if it
+    /// changed.
+    pub fn set_drawing(&mut self, drawing: &str) -> bool {
+        if drawing == self.source {
+            return false;
+        }
+        self.source = drawing.to_string();
+        self.commands = parse_draw_commands(drawing);
+        true
+    }
+}
+
+impl Widget for CanvasWidget {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let Some(clip) = canvas.clip_rect().map_or(Some(*bounds), |clip| clip.intersect(bounds)) else {
+            return;
+        };
+        if let Some(color) = self.background
+            && clip.right() > 0
+            && clip.bottom() > 0
+        {
+            let (x, y) = (clip.x.max(0), clip.y.max(0));
+            canvas.fill_rect(x as u32, y as u32, (clip.right() - x) as u32, (clip.bottom() - y) as u32, color);
+        }
+        for command in &self.commands {
+            command.draw(canvas, (bounds.x, bounds.y), &clip);
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replays_drawing_inside_bounds() {
+        let mut widget = CanvasWidget::new(10, 10).with_background(0x000000).with_binding("chart");
+        assert!(widget.set_drawing("rect 0 0 20 20 #00ff00 1\nline 0 0 9 0 #ff0000 1\n"));
+        assert!(!widget.set_drawing("rect 0 0 20 20 #00ff00 1\nline 0 0 9 0 #ff0000 1\n"));
+        assert_eq!(widget.commands().len(), 2);
+
+        let mut buffer = vec![0xFFFFFF; 30 * 30];
+        let mut canvas = Canvas::new(&mut buffer, 30, 30);
+        widget.draw(&mut canvas, &Rect::new(5, 5, 10, 10), WidgetState::default());
+        assert_eq!(canvas.get_pixel(5, 5), Some(0xFF0000));
+        assert_eq!(canvas.get_pixel(14, 14), Some(0x00FF00));
+        // Commands reaching past the part are cut off at its edge
+        assert_eq!(canvas.get_pixel(15, 15), Some(0xFFFFFF));
+        assert_eq!(canvas.get_pixel(4, 4), Some(0xFFFFFF));
+    }
+}
@@ -0,0 +1,374 @@
+//! Scroll view container widget.
+//!
+//! Unlike `SkinVScroll`, which owns a single child widget, a scroll view is a
+//! tree node whose children are regular nodes: the tree clips them to the
+//! viewport and shifts them by the scroll offset when drawing and hit testing.
+//! Content scrolls with the mouse wheel, by dragging the skinned scrollbar,
+//! or by dragging the view's background, which flicks on with inertia and,
+//! with bounce enabled, can pull past the ends and spring back.
+
+use std::any::Any;
+use std::time::Duration;
+
+use image::RgbImage;
+
+use crate::core::{rubber_band, Inertia, InertiaConfig, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+
+/// How many times harder a flick past the ends slows down.
+const OVERSCROLL_DRAG: f32 = 8.0;
+
+/// How fast content past the ends springs back, per second.
+const SPRING_RATE: f32 = 12.0;
+
+/// A container that scrolls its child nodes vertically.
+pub struct ScrollView {
+    /// Container dimensions.
+    width: u32,
+    height: u32,
+    /// Layout rect, for hit testing the scrollbar.
+    bounds: Rect,
+    /// Current scroll offset in pixels.
+    scroll_y: f32,
+    /// Total height of the children.
+    content_height: u32,
+    /// Scroll speed multiplier for the mouse wheel.
+    scroll_speed: f32,
+    /// Track image (tiled vertically), its width sets the scrollbar width.
+    track_image: RgbImage,
+    /// Thumb image.
+    thumb_image: RgbImage,
+    /// Offset of the cursor from the thumb top while dragging it.
+    drag_offset: Option<i32>,
+    /// Pointer y and scroll offset when a drag of the content started.
+    content_drag: Option<(i32, f32)>,
+    /// Flick tracking for content drags.
+    inertia: Inertia,
+}
+
+impl ScrollView {
+    /// Create a scroll view with the given scrollbar images.
+    pub fn new(width: u32, height: u32, track_image: RgbImage, thumb_image: RgbImage) -> Self {
+        Self {
+            width,
+            height,
+            bounds: Rect::new(0, 0, width, height),
+            scroll_y: 0.0,
+            content_height: height,
+            scroll_speed: 1.0,
+            track_image,
+            thumb_image,
+            drag_offset: None,
+            content_drag: None,
+            inertia: Inertia::default(),
+        }
+    }
+
+    /// Set the height of the scrolled content.
+    pub fn with_content_height(mut self, height: u32) -> Self {
+        self.set_content_height(height);
+        self
+    }
+
+    /// Set the scroll speed multiplier.
+    pub fn with_scroll_speed(mut self, speed: f32) -> Self {
+        self.scroll_speed = speed;
+        self
+    }
+
+    /// Change the height of the scrolled content, keeping the scroll
+    /// position in range.
+    pub fn set_content_height(&mut self, height: u32) {
+        self.content_height = height;
+        self.scroll_y = self.scroll_y.clamp(0.0, self.max_scroll());
+    }
+
+    /// Get the height of the scrolled content.
+    pub fn content_height(&self) -> u32 {
+        self.content_height
+    }
+
+    /// Get the current scroll offset in pixels.
+    pub fn scroll_y(&self) -> f32 {
+        self.scroll_y
+    }
+
+    /// Scroll to an offset, clamped to the content, stopping any flick.
+    pub fn scroll_to(&mut self, y: f32) {
+        self.inertia.stop();
+        self.scroll_y = y.clamp(0.0, self.max_scroll());
+    }
+
+    /// Scroll by a wheel delta (positive scrolls up).
+    pub fn scroll_by(&mut self, delta: f32) {
+        self.scroll_to(self.scroll_y - delta * self.scroll_speed);
+    }
+
+    /// Get the maximum scroll offset.
+    pub fn max_scroll(&self) -> f32 {
+        self.content_height.saturating_sub(self.height) as f32
+    }
+
+    /// Check whether the content is being dragged, flicked or springing
+    /// back from past an end.
+    pub fn is_moving(&self) -> bool {
+        self.content_drag.is_some() || self.inertia.is_flinging() || self.overscroll() != 0.0
+    }
+
+    /// How far the content is scrolled past its nearest end.
+    fn overscroll(&self) -> f32 {
+        self.scroll_y - self.scroll_y.clamp(0.0, self.max_scroll())
+    }
+
+    /// Furthest the content can show past its ends when bouncing.
+    fn overscroll_limit(&self) -> f32 {
+        self.height as f32 / 4.0
+    }
+
+    /// Scroll the dragged content to follow the pointer at `y`.
+    fn drag_content_to(&mut self, y: i32) {
+        let Some((start_y, start_scroll)) = self.content_drag else {
+            return;
+        };
+        let target = start_scroll + (start_y - y) as f32;
+        self.inertia.track(target);
+        let clamped = target.clamp(0.0, self.max_scroll());
+        self.scroll_y = if self.inertia.config().bounce {
+            clamped + rubber_band(target - clamped, self.overscroll_limit())
+        } else {
+            clamped
+        };
+    }
+
+    /// Get the viewport width (container width minus scrollbar).
+    pub fn viewport_width(&self) -> u32 {
+        self.width.saturating_sub(self.track_image.width())
+    }
+
+    /// Scrollbar track area of a container drawn at `bounds`.
+    fn track_rect(&self, bounds: &Rect) -> Rect {
+        Rect::new(
+            bounds.x + self.viewport_width() as i32,
+            bounds.y,
+            self.track_image.width(),
+            self.height,
+        )
+    }
+
+    /// Distance the thumb can travel along the track.
+    fn thumb_travel(&self) -> u32 {
+        self.height.saturating_sub(self.thumb_image.height())
+    }
+
+    /// Thumb top for a container drawn at `bounds`.
+    fn thumb_y(&self, bounds: &Rect) -> i32 {
+        let max = self.max_scroll();
+        let ratio = if max > 0.0 { (self.scroll_y / max).clamp(0.0, 1.0) } else { 0.0 };
+        bounds.y + (self.thumb_travel() as f32 * ratio) as i32
+    }
+
+    /// Scroll so the thumb top is at `thumb_y`.
+    fn drag_thumb_to(&mut self, thumb_y: i32) {
+        let travel = self.thumb_travel();
+        if travel > 0 {
+            let ratio = (thumb_y - self.bounds.y) as f32 / travel as f32;
+            self.scroll_to(ratio * self.max_scroll());
+        }
+    }
+
+    /// Draw an image at a position, respecting canvas clipping.
+    fn draw_image(&self, canvas: &mut Canvas, image: &RgbImage, x: i32, y: i32, max_height: u32) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+            if iy < max_height && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for ScrollView {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        if self.max_scroll() <= 0.0 {
+            return;
+        }
+
+        // Tile the track down the right edge
+        let track = self.track_rect(bounds);
+        let tile_height = self.track_image.height().max(1);
+        let mut y = track.y;
+        while y < track.bottom() {
+            let remaining = (track.bottom() - y) as u32;
+            self.draw_image(canvas, &self.track_image, track.x, y, remaining);
+            y += tile_height as i32;
+        }
+
+        let thumb_y = self.thumb_y(bounds);
+        self.draw_image(canvas, &self.thumb_image, track.x, thumb_y, self.height);
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseWheel { delta_y } if self.max_scroll() > 0.0 => {
+                self.scroll_by(*delta_y);
+                true
+            }
+            WidgetEvent::MouseDown { x, y } => {
+                if self.max_scroll() <= 0.0 {
+                    return false;
+                }
+                if !self.track_rect(&self.bounds).contains(*x, *y) {
+                    // Grabbing the background drags the content, catching a flick
+                    self.content_drag = Some((*y, self.scroll_y));
+                    self.inertia.track(self.scroll_y);
+                    return true;
+                }
+                let thumb_y = self.thumb_y(&self.bounds);
+                let on_thumb = *y >= thumb_y && *y < thumb_y + self.thumb_image.height() as i32;
+                // Clicking the track jumps the thumb's center to the cursor
+                let grab = if on_thumb { y - thumb_y } else { self.thumb_image.height() as i32 / 2 };
+                self.drag_offset = Some(grab);
+                self.drag_thumb_to(y - grab);
+                true
+            }
+            WidgetEvent::MouseMove { y, .. } => match self.drag_offset {
+                Some(grab) => {
+                    self.drag_thumb_to(y - grab);
+                    true
+                }
+                None if self.content_drag.is_some() => {
+                    self.drag_content_to(*y);
+                    true
+                }
+                None => false,
+            },
+            WidgetEvent::MouseUp { .. } => {
+                if self.content_drag.take().is_some() {
+                    self.inertia.release();
+                    return true;
+                }
+                self.drag_offset.take().is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn child_clip(&self, bounds: &Rect) -> Option<Rect> {
+        Some(Rect::new(bounds.x, bounds.y, self.viewport_width(), self.height))
+    }
+
+    fn scroll_offset(&self) -> (i32, i32) {
+        (0, self.scroll_y as i32)
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        self.inertia.advance(dt);
+        let overscroll = self.overscroll();
+        if self.inertia.is_flinging() {
+            let drag = if overscroll != 0.0 { OVERSCROLL_DRAG } else { 1.0 };
+            let target = self.scroll_y + self.inertia.step(dt, drag);
+            let (min, max) = if self.inertia.config().bounce {
+                (-self.overscroll_limit(), self.max_scroll() + self.overscroll_limit())
+            } else {
+                (0.0, self.max_scroll())
+            };
+            self.scroll_y = target.clamp(min, max);
+            if self.scroll_y != target {
+                self.inertia.stop();
+            }
+            true
+        } else if overscroll != 0.0 && self.content_drag.is_none() {
+            // Let go past an end: spring back to it
+            let mut remaining = overscroll * (-SPRING_RATE * dt.as_secs_f32()).exp();
+            if remaining.abs() < 0.5 {
+                remaining = 0.0;
+            }
+            self.scroll_y += remaining - overscroll;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn set_inertia(&mut self, inertia: &InertiaConfig) {
+        self.inertia.set_config(*inertia);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::UiTree;
+    use crate::widgets::Container;
+
+    #[test]
+    fn test_hit_test_scrolled_children() {
+        let mut tree = UiTree::new();
+        let root = tree.add(Container::transparent(200, 200), None);
+        tree.set_bounds(root, Rect::new(0, 0, 200, 200));
+        let view = ScrollView::new(100, 50, RgbImage::new(10, 50), RgbImage::new(10, 10)).with_content_height(100);
+        let view = tree.add(view, Some(root));
+        tree.set_bounds(view, Rect::new(0, 0, 100, 50));
+        let child = tree.add(Container::transparent(90, 20), Some(view));
+        tree.set_bounds(child, Rect::new(0, 60, 90, 20));
+
+        // Below the viewport the child is clipped away
+        assert_eq!(tree.hit_test(5, 65), Some(root));
+
+        tree.get_mut(view).unwrap().widget_mut().on_event(&WidgetEvent::MouseWheel { delta_y: -40.0 });
+        assert_eq!(tree.hit_test(5, 25), Some(child));
+        assert_eq!(tree.to_local(child, 5, 25), (5, 65));
+        assert_eq!(tree.screen_bounds(child), Some(Rect::new(0, 20, 90, 20)));
+
+        // The scrollbar belongs to the view itself
+        assert_eq!(tree.hit_test(95, 25), Some(view));
+    }
+
+    #[test]
+    fn test_flicked_content_bounces_back() {
+        let mut view = ScrollView::new(100, 80, RgbImage::new(10, 80), RgbImage::new(10, 10)).with_content_height(400);
+        view.set_inertia(&InertiaConfig::default().with_bounce(true));
+        view.set_bounds(Rect::new(0, 0, 100, 80));
+
+        // Flick the content up quickly
+        view.on_event(&WidgetEvent::MouseDown { x: 50, y: 70 });
+        for y in [60, 40, 20] {
+            view.tick(Duration::from_millis(10));
+            view.on_event(&WidgetEvent::MouseMove { x: 50, y });
+        }
+        view.on_event(&WidgetEvent::MouseUp { x: 50, y: 20 });
+        assert_eq!(view.scroll_y(), 50.0);
+        assert!(view.is_moving());
+        view.tick(Duration::from_millis(16));
+        assert!(view.scroll_y() > 50.0);
+
+        // Pulling past the top gives way less and less, then springs back
+        view.on_event(&WidgetEvent::MouseDown { x: 50, y: 0 });
+        view.on_event(&WidgetEvent::MouseMove { x: 50, y: 400 });
+        assert!(view.scroll_y() < 0.0 && view.scroll_y() > -20.0);
+        view.on_event(&WidgetEvent::MouseUp { x: 50, y: 400 });
+        for _ in 0..120 {
+            view.tick(Duration::from_millis(16));
+        }
+        assert_eq!(view.scroll_y(), 0.0);
+        assert!(!view.is_moving());
+    }
+}
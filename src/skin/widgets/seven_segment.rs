@@ -0,0 +1,235 @@
+//! Seven-segment display widget.
+//!
+//! A read-only display in the style of an LED or LCD readout, drawn from a
+//! digit atlas: an image holding equal-width glyph cells side by side, one
+//! per character of the skin's glyph list (`0123456789` by default). The
+//! bound value is shown one cell per character; characters the atlas lacks
+//! show the padding glyph, or nothing if it lacks that too. With a fixed
+//! number of digits the value is padded to fill them and longer values keep
+//! their last digits, like a counter rolling over.
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{Constraints, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+use crate::skin::types::TextAlign;
+
+/// Glyphs of a digit atlas unless the skin lists others.
+pub const DEFAULT_SEGMENT_GLYPHS: &str = "0123456789";
+
+/// A readout drawn from a digit atlas.
+pub struct SevenSegment {
+    /// Glyph cells side by side.
+    atlas: RgbImage,
+    /// Character shown by each cell of the atlas, in order.
+    glyphs: Vec<char>,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Pixels between cells.
+    spacing: u32,
+    /// Number of cells shown, or one per character when unset.
+    digits: Option<usize>,
+    /// Character filling cells the value doesn't reach.
+    pad: char,
+    /// Where the cells sit in the bounds, and which side is padded.
+    align: TextAlign,
+    /// Text shown.
+    text: String,
+    /// Store binding key.
+    binding: Option<String>,
+}
+
+impl SevenSegment {
+    /// Create a display from an atlas of cells for `glyphs`, in order.
+    pub fn new(width: u32, height: u32, atlas: RgbImage, glyphs: &str) -> Self {
+        Self {
+            atlas,
+            glyphs: glyphs.chars().collect(),
+            width,
+            height,
+            spacing: 0,
+            digits: None,
+            pad: ' ',
+            align: TextAlign::Right,
+            text: String::new(),
+            binding: None,
+        }
+    }
+
+    /// Set the pixels between cells.
+    pub fn with_spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Show a fixed number of cells, padding shorter values with `pad`.
+    pub fn with_digits(mut self, digits: usize, pad: char) -> Self {
+        self.digits = Some(digits.max(1));
+        self.pad = pad;
+        self
+    }
+
+    /// Set where the cells sit in the bounds.
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the text shown.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the store binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the text shown.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set the text shown. Returns true if it changed.
+    pub fn set_text(&mut self, text: &str) -> bool {
+        if text == self.text {
+            return false;
+        }
+        self.text = text.to_string();
+        true
+    }
+
+    /// Get the width of one cell.
+    fn cell_width(&self) -> u32 {
+        self.atlas.width() / self.glyphs.len().max(1) as u32
+    }
+
+    /// Get the characters shown, one per cell.
+    fn cells(&self) -> Vec<char> {
+        let chars: Vec<char> = self.text.chars().collect();
+        let Some(digits) = self.digits else {
+            return chars;
+        };
+        if chars.len() >= digits {
+            return chars[chars.len() - digits..].to_vec();
+        }
+        let padding = digits - chars.len();
+        let before = match self.align {
+            TextAlign::Left => 0,
+            TextAlign::Center => padding / 2,
+            TextAlign::Right => padding,
+        };
+        let mut cells = vec![self.pad; before];
+        cells.extend(chars);
+        cells.resize(digits, self.pad);
+        cells
+    }
+
+    /// Get the atlas cell showing `c`: its own, the other case's, or the
+    /// padding glyph's.
+    fn glyph(&self, c: char) -> Option<usize> {
+        let find = |c: char| self.glyphs.iter().position(|&glyph| glyph == c);
+        find(c)
+            .or_else(|| find(c.to_ascii_uppercase()))
+            .or_else(|| find(c.to_ascii_lowercase()))
+            .or_else(|| find(self.pad))
+    }
+
+    /// Get the size of `cells` cells side by side.
+    fn content_size(&self, cells: usize) -> (u32, u32) {
+        let cells = cells as u32;
+        let width = self.cell_width().saturating_mul(cells) + self.spacing.saturating_mul(cells.saturating_sub(1));
+        (width, self.atlas.height())
+    }
+}
+
+impl Widget for SevenSegment {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let cells = self.cells();
+        let (width, height) = self.content_size(cells.len());
+        let cell_width = self.cell_width();
+        let free = bounds.width as i32 - width as i32;
+        let left = bounds.x
+            + match self.align {
+                TextAlign::Left => 0,
+                TextAlign::Center => free / 2,
+                TextAlign::Right => free,
+            };
+        let top = bounds.y + (bounds.height as i32 - height as i32) / 2;
+
+        for (i, &c) in cells.iter().enumerate() {
+            let Some(glyph) = self.glyph(c) else {
+                continue;
+            };
+            let x = left + (i as u32 * (cell_width + self.spacing)) as i32;
+            let source = glyph as u32 * cell_width;
+            for iy in 0..height {
+                for ix in 0..cell_width {
+                    let px = x + ix as i32;
+                    let py = top + iy as i32;
+                    if px >= bounds.x.max(0) && px < bounds.right() && py >= bounds.y.max(0) && py < bounds.bottom() {
+                        let [r, g, b] = self.atlas.get_pixel(source + ix, iy).0;
+                        canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+                    }
+                }
+            }
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn intrinsic_size(&self, constraints: Constraints) -> (u32, u32) {
+        let cells = self.digits.unwrap_or_else(|| self.text.chars().count()).max(1);
+        constraints.constrain(self.content_size(cells))
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_fills_digit_cells() {
+        // Cells 2 pixels wide, each a solid shade: "0" is 0x000000, "1" is
+        // 0x010101 and so on, with a blank cell after the digits
+        let atlas = || RgbImage::from_fn(22, 3, |x, _| image::Rgb([(x / 2) as u8; 3]));
+        let display = SevenSegment::new(20, 5, atlas(), "0123456789 ").with_digits(4, '0').with_spacing(1);
+        assert_eq!(display.intrinsic_size(Constraints::new(100, 100)), (11, 3));
+
+        let display = display.with_text("42");
+        assert_eq!(display.cells(), ['0', '0', '4', '2']);
+        assert_eq!(display.with_text("12345").cells(), ['2', '3', '4', '5']);
+
+        // Right-aligned in the bounds, with the blank cell for a missing glyph
+        let display = SevenSegment::new(20, 5, atlas(), "0123456789 ").with_text("7:1");
+        let mut buffer = vec![0xFFFFFF; 8 * 5];
+        let mut canvas = Canvas::new(&mut buffer, 8, 5);
+        display.draw(&mut canvas, &Rect::new(0, 0, 8, 5), WidgetState::default());
+        let row: Vec<u32> = (0..8).map(|x| canvas.get_pixel(x, 1).unwrap()).collect();
+        assert_eq!(row, [0xFFFFFF, 0xFFFFFF, 0x070707, 0x070707, 0x0A0A0A, 0x0A0A0A, 0x010101, 0x010101]);
+    }
+}
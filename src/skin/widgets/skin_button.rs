@@ -1,18 +1,25 @@
 use std::any::Any;
 
-use image::RgbImage;
+use image::{imageops, RgbImage};
 
 use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
-use crate::graphics::Canvas;
+use crate::graphics::{Canvas, ColorEffect, NineSlice};
 
 /// A button widget driven by skin assets for each state.
 pub struct SkinButton {
     normal: RgbImage,
     hover: RgbImage,
     pressed: RgbImage,
+    /// Shown while disabled; the normal image grayed out when unset.
+    disabled_image: Option<RgbImage>,
     width: u32,
     height: u32,
     action: Option<String>,
+    /// Store key of a bool that disables the button while true.
+    disabled_binding: Option<String>,
+    disabled: bool,
+    /// Border kept while the images are stretched to the button's size.
+    slice: Option<NineSlice>,
 }
 
 impl SkinButton {
@@ -29,36 +36,91 @@ impl SkinButton {
             normal,
             hover,
             pressed,
+            disabled_image: None,
             width,
             height,
             action,
+            disabled_binding: None,
+            disabled: false,
+            slice: None,
         }
     }
 
+    /// Create a skin button from a sprite strip of `frames` equal frames,
+    /// side by side or stacked when `vertical`: normal, hover, pressed and,
+    /// if there is a fourth, disabled.
+    pub fn from_strip(strip: &RgbImage, frames: u32, vertical: bool, action: Option<String>) -> Self {
+        let frames = frames.max(1);
+        let (width, height) = if vertical {
+            (strip.width(), strip.height() / frames)
+        } else {
+            (strip.width() / frames, strip.height())
+        };
+        // Frames past the strip's end repeat the first
+        let frame = |index: u32| {
+            let index = if index < frames { index } else { 0 };
+            let (x, y) = if vertical { (0, index * height) } else { (index * width, 0) };
+            imageops::crop_imm(strip, x, y, width, height).to_image()
+        };
+        let mut button = Self::new(frame(0), frame(1), frame(2), action);
+        if frames > 3 {
+            button.disabled_image = Some(frame(3));
+        }
+        button
+    }
+
+    /// Stretch the images to the widget's size, keeping a
+    /// border of them as it is.
+    pub fn with_slice(mut self, slice: NineSlice) -> Self {
+        self.slice = Some(slice);
+        self
+    }
+
+    /// Set the store key of a bool that disables the button while true.
+    pub fn with_disabled_binding(mut self, binding: impl Into<String>) -> Self {
+        self.disabled_binding = Some(binding.into());
+        self
+    }
+
+    /// Get the store key of a bool that disables the button while true.
+    pub fn disabled_binding(&self) -> Option<&str> {
+        self.disabled_binding.as_deref()
+    }
+
+    /// Check whether the button is disabled.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// Disable or enable the button. A disabled button ignores clicks.
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+
     /// Get the action string for this button.
     pub fn action(&self) -> Option<&str> {
         self.action.as_deref()
     }
 
     fn draw_image(&self, canvas: &mut Canvas, bounds: &Rect, image: &RgbImage) {
-        for (ix, iy, pixel) in image.enumerate_pixels() {
-            let x = bounds.x + ix as i32;
-            let y = bounds.y + iy as i32;
-
-            // Clip to bounds
-            if x >= bounds.x && x < bounds.right() && y >= bounds.y && y < bounds.bottom() {
-                if x >= 0 && y >= 0 {
-                    let [r, g, b] = pixel.0;
-                    canvas.set_pixel_rgb(x as u32, y as u32, r, g, b);
-                }
-            }
-        }
+        canvas.draw_image(image, bounds, self.slice.as_ref());
     }
 }
 
 impl Widget for SkinButton {
     fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
-        let image = if state.pressed {
+        let image = if self.disabled {
+            let Some(disabled) = &self.disabled_image else {
+                // Without a disabled image, the normal one is shown grayed out
+                let outer = canvas.effect().copied();
+                let gray = ColorEffect::grayscale(1.0);
+                canvas.set_effect(Some(outer.map_or(gray, |outer| gray.then(&outer))));
+                self.draw_image(canvas, bounds, &self.normal);
+                canvas.set_effect(outer);
+                return;
+            };
+            disabled
+        } else if state.pressed {
             &self.pressed
         } else if state.hovered {
             &self.hover
@@ -74,6 +136,9 @@ impl Widget for SkinButton {
     }
 
     fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        if self.disabled {
+            return false;
+        }
         if let WidgetEvent::Click = event {
             if let Some(action) = &self.action {
                 println!("Button action: {}", action);
@@ -91,3 +156,36 @@ impl Widget for SkinButton {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slices_state_frames_from_strip() {
+        // Four 2x1 frames side by side, each a solid shade
+        let strip = RgbImage::from_fn(8, 1, |x, _| image::Rgb([(x / 2) as u8; 3]));
+        let mut button = SkinButton::from_strip(&strip, 4, false, None);
+        assert_eq!(button.preferred_size(), (2, 1));
+        let draw = |button: &SkinButton, state: WidgetState| {
+            let mut buffer = vec![0xFFFFFF; 2];
+            button.draw(&mut Canvas::new(&mut buffer, 2, 1), &Rect::new(0, 0, 2, 1), state);
+            buffer[0]
+        };
+        let hovered = WidgetState { hovered: true, ..WidgetState::default() };
+        let pressed = WidgetState { hovered: true, pressed: true, ..WidgetState::default() };
+        let states = [WidgetState::default(), hovered, pressed];
+        assert_eq!(states.map(|state| draw(&button, state)), [0, 0x010101, 0x020202]);
+
+        // Disabled, it shows the fourth frame whatever the pointer does, and ignores clicks
+        button.set_disabled(true);
+        assert_eq!(draw(&button, pressed), 0x030303);
+        assert!(!button.on_event(&WidgetEvent::Click));
+
+        // Without a fourth frame, a disabled button shows its normal frame grayed out
+        let strip = RgbImage::from_fn(1, 3, |_, y| image::Rgb([255 - y as u8, 0, 0]));
+        let mut button = SkinButton::from_strip(&strip, 3, true, None);
+        button.set_disabled(true);
+        assert_eq!(draw(&button, hovered), 0x363636);
+    }
+}
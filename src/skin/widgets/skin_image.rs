@@ -1,15 +1,27 @@
 use std::any::Any;
+use std::cell::Cell;
 
 use image::RgbImage;
 
 use crate::core::{Rect, Widget, WidgetState};
-use crate::graphics::Canvas;
+use crate::graphics::{Canvas, ImageHandle, NineSlice};
 
 /// A static image widget driven by a skin asset.
+///
+/// When bound to a Store key holding an `ImageHandle`, the handle's
+/// contents are drawn instead of the skin asset.
 pub struct SkinImage {
     image: RgbImage,
     width: u32,
     height: u32,
+    /// Border kept while the asset is stretched to the widget's size.
+    slice: Option<NineSlice>,
+    /// Store binding key for a dynamic image handle.
+    binding: Option<String>,
+    /// Dynamic image source (overrides the skin asset when set).
+    source: Option<ImageHandle>,
+    /// Version of the source image that was last drawn.
+    drawn_version: Cell<Option<u64>>,
 }
 
 impl SkinImage {
@@ -21,23 +33,59 @@ impl SkinImage {
             image,
             width,
             height,
+            slice: None,
+            binding: None,
+            source: None,
+            drawn_version: Cell::new(None),
         }
     }
+
+    /// Stretch the asset to the widget's size, keeping a border of it as it
+    /// is.
+    pub fn with_slice(mut self, slice: NineSlice) -> Self {
+        self.slice = Some(slice);
+        self
+    }
+
+    /// Set the store binding key for a dynamic image handle.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Set the dynamic image source.
+    pub fn set_source(&mut self, handle: Option<ImageHandle>) {
+        self.source = handle;
+        self.drawn_version.set(None);
+    }
+
+    /// Check if the widget currently displays a dynamic image source.
+    pub fn has_source(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Check if the dynamic source has changed since it was last drawn.
+    pub fn needs_redraw(&self) -> bool {
+        self.source
+            .as_ref()
+            .is_some_and(|handle| self.drawn_version.get() != Some(handle.version()))
+    }
 }
 
 impl Widget for SkinImage {
     fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
-        for (ix, iy, pixel) in self.image.enumerate_pixels() {
-            let x = bounds.x + ix as i32;
-            let y = bounds.y + iy as i32;
-
-            // Clip to bounds
-            if x >= bounds.x && x < bounds.right() && y >= bounds.y && y < bounds.bottom() {
-                if x >= 0 && y >= 0 {
-                    let [r, g, b] = pixel.0;
-                    canvas.set_pixel_rgb(x as u32, y as u32, r, g, b);
-                }
+        match &self.source {
+            Some(handle) => {
+                self.drawn_version.set(Some(handle.version()));
+                // Images set at run time are drawn as they are
+                handle.with_image(|image| canvas.draw_image(image.rgb(), bounds, None));
             }
+            None => canvas.draw_image(&self.image, bounds, self.slice.as_ref()),
         }
     }
 
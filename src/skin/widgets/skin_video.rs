@@ -0,0 +1,308 @@
+//! Video playback widget.
+//!
+//! Decodes a file or stream by piping raw RGB frames out of the system
+//! `ffmpeg` executable, scaled to the widget bounds. Playback state is
+//! exchanged with the store under the widget's binding prefix:
+//!
+//! - `<prefix>.playing` (bool, read/write) - whether the video is playing
+//! - `<prefix>.seek` (number, write-only) - request a seek to this many seconds
+//! - `<prefix>.position` (number, read-only) - current position in seconds
+//! - `<prefix>.duration` (number, read-only) - total duration if known
+//!
+//! The duration comes from `ffprobe`, run on a background thread when the
+//! widget is created so building a screen never waits on it.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::io::{self, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetState};
+use crate::graphics::{Canvas, Image, ImageHandle};
+
+/// Default store prefix for video widgets without a binding.
+pub const DEFAULT_VIDEO_BINDING: &str = "video";
+
+/// A running ffmpeg decode process.
+struct Playback {
+    child: Child,
+    reader: JoinHandle<()>,
+    started_at: Instant,
+    start_position: f64,
+}
+
+/// A widget that plays a video file or stream into its bounds.
+pub struct SkinVideo {
+    /// File path or stream URL.
+    source: String,
+    /// Widget dimensions (frames are scaled to this size).
+    width: u32,
+    height: u32,
+    /// Store key prefix for playback state.
+    binding: String,
+    /// Restart from the beginning when playback reaches the end.
+    looping: bool,
+    /// Latest decoded frame.
+    frame: ImageHandle,
+    /// Version of the frame that was last drawn.
+    drawn_version: Cell<Option<u64>>,
+    /// Position when not playing, in seconds.
+    paused_position: f64,
+    /// Duration reported by ffprobe; unset until the probe finishes, then
+    /// None if it couldn't tell.
+    duration: Arc<OnceLock<Option<f64>>>,
+    /// The active decode process.
+    playback: Option<Playback>,
+}
+
+impl SkinVideo {
+    /// Create a video widget for the given source, scaled to width x height.
+    pub fn new(source: impl Into<String>, width: u32, height: u32) -> Self {
+        let source = source.into();
+        let duration = Arc::new(OnceLock::new());
+        let probed = duration.clone();
+        let probe_source = source.clone();
+        let spawned = thread::Builder::new()
+            .name("crix-video-probe".to_string())
+            .spawn(move || {
+                let _ = probed.set(probe_duration(&probe_source));
+            });
+        if spawned.is_err() {
+            let _ = duration.set(None);
+        }
+        let black = RgbImage::new(width.max(1), height.max(1));
+        Self {
+            source,
+            width,
+            height,
+            binding: DEFAULT_VIDEO_BINDING.to_string(),
+            looping: false,
+            frame: ImageHandle::new(Image::from_rgb(black)),
+            drawn_version: Cell::new(None),
+            paused_position: 0.0,
+            duration,
+            playback: None,
+        }
+    }
+
+    /// Set the store key prefix.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = binding.into();
+        self
+    }
+
+    /// Loop playback when the end is reached.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Get the store key prefix.
+    pub fn binding(&self) -> &str {
+        &self.binding
+    }
+
+    /// Get the total duration in seconds, if known.
+    pub fn duration(&self) -> Option<f64> {
+        self.duration.get().copied().flatten()
+    }
+
+    /// Check if ffprobe is still looking for the duration.
+    pub fn is_probing(&self) -> bool {
+        self.duration.get().is_none()
+    }
+
+    /// Check if the video is currently playing.
+    pub fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Get the current playback position in seconds.
+    pub fn position(&self) -> f64 {
+        let position = match &self.playback {
+            Some(p) => p.start_position + p.started_at.elapsed().as_secs_f64(),
+            None => self.paused_position,
+        };
+        match self.duration() {
+            Some(duration) => position.min(duration),
+            None => position,
+        }
+    }
+
+    /// Start or resume playback from the current position.
+    pub fn play(&mut self) {
+        if self.playback.is_some() {
+            return;
+        }
+        match self.spawn_decoder(self.paused_position) {
+            Ok(playback) => self.playback = Some(playback),
+            Err(e) => eprintln!("Failed to start video '{}': {}", self.source, e),
+        }
+    }
+
+    /// Pause playback, keeping the current position.
+    pub fn pause(&mut self) {
+        self.paused_position = self.position();
+        self.kill_decoder();
+    }
+
+    /// Seek to a position in seconds, continuing playback if playing.
+    pub fn seek(&mut self, position: f64) {
+        let was_playing = self.is_playing();
+        self.kill_decoder();
+        self.paused_position = match self.duration() {
+            Some(duration) => position.clamp(0.0, duration),
+            None => position.max(0.0),
+        };
+        if was_playing {
+            self.play();
+        }
+    }
+
+    /// Advance playback state. Call regularly (e.g. when syncing with the store)
+    /// to detect the end of the stream.
+    pub fn poll(&mut self) {
+        let finished = self
+            .playback
+            .as_ref()
+            .is_some_and(|p| p.reader.is_finished());
+        if finished {
+            self.kill_decoder();
+            if self.looping {
+                self.paused_position = 0.0;
+                self.play();
+            } else {
+                self.paused_position = self.duration().unwrap_or(self.paused_position);
+            }
+        }
+    }
+
+    /// Check if a new frame has arrived since the last draw.
+    pub fn needs_redraw(&self) -> bool {
+        self.drawn_version.get() != Some(self.frame.version())
+    }
+
+    fn spawn_decoder(&self, position: f64) -> io::Result<Playback> {
+        // ffmpeg can't scale to nothing, and there would be no frame to read
+        if self.width == 0 || self.height == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "video has no area to play into"));
+        }
+        let mut child = Command::new("ffmpeg")
+            .args(["-loglevel", "error", "-ss"])
+            .arg(format!("{:.3}", position))
+            .arg("-re")
+            .arg("-i")
+            .arg(&self.source)
+            .args(["-an", "-f", "rawvideo", "-pix_fmt", "rgb24", "-vf"])
+            .arg(format!("scale={}:{}", self.width, self.height))
+            .arg("-")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let frame = self.frame.clone();
+        let (width, height) = (self.width, self.height);
+
+        let reader = thread::Builder::new()
+            .name("crix-video".to_string())
+            .spawn(move || {
+                let mut buffer = vec![0u8; width as usize * height as usize * 3];
+                while stdout.read_exact(&mut buffer).is_ok() {
+                    if let Some(rgb) = RgbImage::from_raw(width, height, buffer.clone()) {
+                        frame.set(Image::from_rgb(rgb));
+                    }
+                }
+            })?;
+
+        Ok(Playback {
+            child,
+            reader,
+            started_at: Instant::now(),
+            start_position: position,
+        })
+    }
+
+    fn kill_decoder(&mut self) {
+        if let Some(mut playback) = self.playback.take() {
+            let _ = playback.child.kill();
+            let _ = playback.child.wait();
+            let _ = playback.reader.join();
+        }
+    }
+}
+
+/// Ask ffprobe for the duration of a media source.
+fn probe_duration(source: &str) -> Option<f64> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "csv=p=0"])
+        .arg(source)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+impl Drop for SkinVideo {
+    fn drop(&mut self) {
+        self.kill_decoder();
+    }
+}
+
+impl Widget for SkinVideo {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        self.drawn_version.set(Some(self.frame.version()));
+        self.frame.with_image(|image| {
+            for (ix, iy, pixel) in image.rgb().enumerate_pixels() {
+                let x = bounds.x + ix as i32;
+                let y = bounds.y + iy as i32;
+
+                if x >= 0 && y >= 0 && x < bounds.right() && y < bounds.bottom() {
+                    let [r, g, b] = pixel.0;
+                    canvas.set_pixel_rgb(x as u32, y as u32, r, g, b);
+                }
+            }
+        });
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_in_background() {
+        let video = SkinVideo::new("/nonexistent/crix-video.mp4", 4, 4);
+
+        let deadline = Instant::now() + std::time::Duration::from_secs(10);
+        while video.is_probing() && Instant::now() < deadline {
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(!video.is_probing());
+        assert_eq!(video.duration(), None);
+        assert_eq!(video.position(), 0.0);
+
+        // Nothing to play into, so no decoder is started
+        let mut empty = SkinVideo::new("/nonexistent/crix-video.mp4", 0, 4);
+        empty.play();
+        assert!(!empty.is_playing());
+    }
+}
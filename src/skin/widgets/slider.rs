@@ -0,0 +1,264 @@
+//! Slider widget.
+//!
+//! A horizontal slider drawn from a track image and a thumb image, with
+//! optional hover and pressed thumb images. The value is kept in a
+//! `min..=max` range, optionally snapped to `step`, and synced to a store
+//! key while dragging. An action can be dispatched when the thumb is released.
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+
+/// A draggable slider bound to a numeric value.
+pub struct Slider {
+    /// Track image, drawn centered vertically.
+    track: RgbImage,
+    /// Thumb images for each state.
+    thumb: RgbImage,
+    thumb_hover: RgbImage,
+    thumb_pressed: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Value range and optional step.
+    min: f64,
+    max: f64,
+    step: Option<f64>,
+    /// Current value.
+    value: f64,
+    /// Store binding key.
+    binding: Option<String>,
+    /// Action to trigger when the thumb is released.
+    action: Option<String>,
+    /// Current layout bounds, for mapping pointer positions to values.
+    bounds: Option<Rect>,
+    /// True while the thumb is being dragged.
+    dragging: bool,
+    /// Flag indicating the value was modified since last sync.
+    dirty: bool,
+    /// Set when a drag ended and the release action hasn't been taken yet.
+    released: bool,
+}
+
+impl Slider {
+    /// Create a slider with a track and thumb image, ranging from 0 to 1.
+    pub fn new(width: u32, height: u32, track: RgbImage, thumb: RgbImage) -> Self {
+        Self {
+            track,
+            thumb_hover: thumb.clone(),
+            thumb_pressed: thumb.clone(),
+            thumb,
+            width,
+            height,
+            min: 0.0,
+            max: 1.0,
+            step: None,
+            value: 0.0,
+            binding: None,
+            action: None,
+            bounds: None,
+            dragging: false,
+            dirty: false,
+            released: false,
+        }
+    }
+
+    /// Set the thumb image shown while hovered.
+    pub fn with_thumb_hover(mut self, image: RgbImage) -> Self {
+        self.thumb_hover = image;
+        self
+    }
+
+    /// Set the thumb image shown while pressed or dragged.
+    pub fn with_thumb_pressed(mut self, image: RgbImage) -> Self {
+        self.thumb_pressed = image;
+        self
+    }
+
+    /// Set the value range. The current value is clamped into it.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max.max(min);
+        self.value = self.constrain(self.value);
+        self
+    }
+
+    /// Snap values to multiples of `step` from `min`.
+    pub fn with_step(mut self, step: f64) -> Self {
+        if step > 0.0 {
+            self.step = Some(step);
+            self.value = self.constrain(self.value);
+        }
+        self
+    }
+
+    /// Set the initial value.
+    pub fn with_value(mut self, value: f64) -> Self {
+        self.value = self.constrain(value);
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the thumb is released.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value without marking the slider dirty (e.g. from the store).
+    /// Ignored while dragging so the store can't fight the pointer.
+    pub fn set_value(&mut self, value: f64) {
+        if !self.dragging {
+            self.value = self.constrain(value);
+        }
+    }
+
+    /// Check if the thumb is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Check if the value has been modified since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the release action if a drag just ended.
+    pub fn take_release_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.released) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Clamp a value into the range and snap it to the step.
+    fn constrain(&self, value: f64) -> f64 {
+        let value = match self.step {
+            Some(step) => self.min + ((value - self.min) / step).round() * step,
+            None => value,
+        };
+        value.clamp(self.min, self.max)
+    }
+
+    /// Horizontal distance the thumb can travel.
+    fn travel(&self, bounds: &Rect) -> u32 {
+        bounds.width.saturating_sub(self.thumb.width())
+    }
+
+    /// Update the value from a pointer x coordinate.
+    fn set_from_pointer(&mut self, x: i32) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+        let travel = self.travel(&bounds);
+        let fraction = if travel == 0 {
+            0.0
+        } else {
+            let offset = x - bounds.x - self.thumb.width() as i32 / 2;
+            (offset as f64 / travel as f64).clamp(0.0, 1.0)
+        };
+        let value = self.constrain(self.min + fraction * (self.max - self.min));
+        if value != self.value {
+            self.value = value;
+            self.dirty = true;
+        }
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for Slider {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        // Track, centered vertically
+        let track_y = bounds.y + (bounds.height as i32 - self.track.height() as i32) / 2;
+        self.draw_image(canvas, bounds.x, track_y, &self.track, bounds);
+
+        // Thumb at the value's position along the track
+        let thumb = if self.dragging || state.pressed {
+            &self.thumb_pressed
+        } else if state.hovered {
+            &self.thumb_hover
+        } else {
+            &self.thumb
+        };
+        let fraction = if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        };
+        let thumb_x = bounds.x + (fraction * self.travel(bounds) as f64).round() as i32;
+        let thumb_y = bounds.y + (bounds.height as i32 - thumb.height() as i32) / 2;
+        self.draw_image(canvas, thumb_x, thumb_y, thumb, bounds);
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseDown { x, .. } => {
+                self.dragging = true;
+                self.set_from_pointer(*x);
+                true
+            }
+            WidgetEvent::MouseMove { x, .. } if self.dragging => {
+                self.set_from_pointer(*x);
+                true
+            }
+            WidgetEvent::MouseUp { .. } if self.dragging => {
+                self.dragging = false;
+                self.released = true;
+                true
+            }
+            WidgetEvent::Click => true,
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = Some(bounds);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
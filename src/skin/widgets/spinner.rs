@@ -0,0 +1,427 @@
+//! Spinner widget.
+//!
+//! A numeric field with up and down buttons on its right edge: the up button
+//! at the top, the down button at the bottom. Clicking a button (or pressing
+//! Up/Down while focused) moves the value by `step`, and holding a button
+//! repeats the step after a short delay. The value is kept in a `min..=max`
+//! range and synced to a store key as it changes. An action can be
+//! dispatched when a button is released.
+
+use std::any::Any;
+use std::time::Duration;
+
+use image::RgbImage;
+
+use crate::core::{KeyCode, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{caret_x_styled, draw_text_sized, line_height_sized, Canvas, TextStyle};
+use crate::skin::types::TextAlign;
+
+/// How long a button must be held before it starts repeating.
+pub const SPINNER_REPEAT_DELAY: Duration = Duration::from_millis(400);
+
+/// Time between steps while a button repeats.
+pub const SPINNER_REPEAT_INTERVAL: Duration = Duration::from_millis(60);
+
+/// Most decimal places shown for fractional steps.
+const MAX_DECIMALS: usize = 6;
+
+/// One of the spinner's buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpinButton {
+    Up,
+    Down,
+}
+
+/// A numeric field stepped by up/down buttons.
+pub struct Spinner {
+    /// Field image, drawn behind the value.
+    field: RgbImage,
+    /// Button images, and the images shown while they are held.
+    up: RgbImage,
+    down: RgbImage,
+    up_pressed: RgbImage,
+    down_pressed: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Value range and step.
+    min: f64,
+    max: f64,
+    step: f64,
+    /// Current value.
+    value: f64,
+    /// Text appearance.
+    style: TextStyle,
+    font_size: Option<f32>,
+    text_align: TextAlign,
+    padding: u32,
+    /// Store binding key.
+    binding: Option<String>,
+    /// Action to trigger when a button is released.
+    action: Option<String>,
+    /// Current layout bounds, for finding the button under the pointer.
+    bounds: Option<Rect>,
+    /// Button being held, and whether the pointer is still over it.
+    held: Option<SpinButton>,
+    over_held: bool,
+    /// Time left until the held button steps again.
+    repeat_in: Duration,
+    /// Flag indicating the value was modified since last sync.
+    dirty: bool,
+    /// Set when a button was released and the action hasn't been taken yet.
+    released: bool,
+}
+
+impl Spinner {
+    /// Create a spinner from its field and button images, ranging from 0
+    /// to 100 in steps of 1.
+    pub fn new(width: u32, height: u32, field: RgbImage, up: RgbImage, down: RgbImage) -> Self {
+        Self {
+            field,
+            up_pressed: up.clone(),
+            down_pressed: down.clone(),
+            up,
+            down,
+            width,
+            height,
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+            value: 0.0,
+            style: TextStyle::with_color(0x000000),
+            font_size: None,
+            text_align: TextAlign::Right,
+            padding: 4,
+            binding: None,
+            action: None,
+            bounds: None,
+            held: None,
+            over_held: false,
+            repeat_in: Duration::ZERO,
+            dirty: false,
+            released: false,
+        }
+    }
+
+    /// Set the up button image shown while held.
+    pub fn with_up_pressed(mut self, image: RgbImage) -> Self {
+        self.up_pressed = image;
+        self
+    }
+
+    /// Set the down button image shown while held.
+    pub fn with_down_pressed(mut self, image: RgbImage) -> Self {
+        self.down_pressed = image;
+        self
+    }
+
+    /// Set the value range. The current value is clamped into it.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max.max(min);
+        self.value = self.constrain(self.value);
+        self
+    }
+
+    /// Set how far each step moves the value. Values snap to multiples of
+    /// `step` from `min`.
+    pub fn with_step(mut self, step: f64) -> Self {
+        if step > 0.0 {
+            self.step = step;
+            self.value = self.constrain(self.value);
+        }
+        self
+    }
+
+    /// Set the initial value.
+    pub fn with_value(mut self, value: f64) -> Self {
+        self.value = self.constrain(value);
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.style.color = color;
+        self
+    }
+
+    /// Set the text style (color, spacing and effects).
+    pub fn with_text_style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Get the text style.
+    pub fn text_style(&self) -> &TextStyle {
+        &self.style
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Set the horizontal alignment of the value in the field.
+    pub fn with_text_align(mut self, align: TextAlign) -> Self {
+        self.text_align = align;
+        self
+    }
+
+    /// Set the padding between the field's edges and the value.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when a button is released.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Set the value without marking the spinner dirty (e.g. from the store).
+    /// Ignored while a button is held so the store can't fight the repeat.
+    pub fn set_value(&mut self, value: f64) {
+        if self.held.is_none() {
+            self.value = self.constrain(value);
+        }
+    }
+
+    /// Check if a button is held and will repeat.
+    pub fn is_repeating(&self) -> bool {
+        self.held.is_some() && self.over_held
+    }
+
+    /// Check if the value has been modified since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the release action if a button was just released.
+    pub fn take_release_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.released) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    /// The value as shown in the field, with as many decimals as the step.
+    pub fn display_text(&self) -> String {
+        let decimals = (0..MAX_DECIMALS)
+            .find(|&d| {
+                let scaled = self.step * 10f64.powi(d as i32);
+                (scaled - scaled.round()).abs() < 1e-9
+            })
+            .unwrap_or(MAX_DECIMALS);
+        format!("{:.*}", decimals, self.value)
+    }
+
+    /// Clamp a value into the range and snap it to the step.
+    fn constrain(&self, value: f64) -> f64 {
+        let value = self.min + ((value - self.min) / self.step).round() * self.step;
+        value.clamp(self.min, self.max)
+    }
+
+    /// Move the value one step in the button's direction.
+    fn step_by(&mut self, button: SpinButton) {
+        let delta = match button {
+            SpinButton::Up => self.step,
+            SpinButton::Down => -self.step,
+        };
+        let value = self.constrain(self.value + delta);
+        if value != self.value {
+            self.value = value;
+            self.dirty = true;
+        }
+    }
+
+    /// Rect of a button within the given bounds.
+    fn button_rect(&self, bounds: &Rect, button: SpinButton) -> Rect {
+        match button {
+            SpinButton::Up => Rect::new(
+                bounds.right() - self.up.width() as i32,
+                bounds.y,
+                self.up.width(),
+                self.up.height(),
+            ),
+            SpinButton::Down => Rect::new(
+                bounds.right() - self.down.width() as i32,
+                bounds.bottom() - self.down.height() as i32,
+                self.down.width(),
+                self.down.height(),
+            ),
+        }
+    }
+
+    /// Button under a pointer position, if any.
+    fn button_at(&self, x: i32, y: i32) -> Option<SpinButton> {
+        let bounds = self.bounds?;
+        [SpinButton::Up, SpinButton::Down]
+            .into_iter()
+            .find(|&button| self.button_rect(&bounds, button).contains(x, y))
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for Spinner {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        self.draw_image(canvas, bounds.x, bounds.y, &self.field, bounds);
+
+        // The value sits in the field left of the buttons
+        let buttons_width = self.up.width().max(self.down.width());
+        let text_left = bounds.x + self.padding as i32;
+        let text_right = bounds.right() - buttons_width as i32 - self.padding as i32;
+        let size = self.font_size.unwrap_or(16.0);
+        let text = self.display_text();
+        let text_width = caret_x_styled(&text, usize::MAX, size, &self.style) as i32;
+        let x = match self.text_align {
+            TextAlign::Left => text_left,
+            TextAlign::Center => text_left + (text_right - text_left - text_width) / 2,
+            TextAlign::Right => text_right - text_width,
+        };
+        let y = bounds.y + (bounds.height as i32 - line_height_sized(size) as i32) / 2;
+        let clip = Rect::new(text_left, bounds.y, (text_right - text_left).max(0) as u32, bounds.height);
+        draw_text_sized(canvas, x, y, Some(&clip), &text, self.style, size);
+
+        for (button, image, pressed) in [
+            (SpinButton::Up, &self.up, &self.up_pressed),
+            (SpinButton::Down, &self.down, &self.down_pressed),
+        ] {
+            let image = if self.held == Some(button) && self.over_held { pressed } else { image };
+            let rect = self.button_rect(bounds, button);
+            self.draw_image(canvas, rect.x, rect.y, image, bounds);
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseDown { x, y } => {
+                if let Some(button) = self.button_at(*x, *y) {
+                    self.held = Some(button);
+                    self.over_held = true;
+                    self.repeat_in = SPINNER_REPEAT_DELAY;
+                    self.step_by(button);
+                }
+                true
+            }
+            WidgetEvent::MouseMove { x, y } if self.held.is_some() => {
+                self.over_held = self.button_at(*x, *y) == self.held;
+                true
+            }
+            WidgetEvent::MouseUp { .. } if self.held.is_some() => {
+                self.held = None;
+                self.released = true;
+                true
+            }
+            WidgetEvent::KeyDown { key: KeyCode::Up } => {
+                self.step_by(SpinButton::Up);
+                true
+            }
+            WidgetEvent::KeyDown { key: KeyCode::Down } => {
+                self.step_by(SpinButton::Down);
+                true
+            }
+            WidgetEvent::Click => true,
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = Some(bounds);
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        let Some(button) = self.held.filter(|_| self.over_held) else {
+            return false;
+        };
+        let mut dt = dt;
+        let mut stepped = false;
+        while dt >= self.repeat_in {
+            dt -= self.repeat_in;
+            self.repeat_in = SPINNER_REPEAT_INTERVAL;
+            self.step_by(button);
+            stepped = true;
+        }
+        self.repeat_in -= dt;
+        stepped
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_held_button_repeats() {
+        let mut spinner = Spinner::new(60, 20, RgbImage::new(60, 20), RgbImage::new(10, 10), RgbImage::new(10, 10))
+            .with_range(0.0, 1.0)
+            .with_step(0.25);
+        spinner.set_bounds(Rect::new(0, 0, 60, 20));
+
+        // Pressing the up button steps once, then repeats after the delay
+        spinner.on_event(&WidgetEvent::MouseDown { x: 55, y: 5 });
+        assert_eq!(spinner.value(), 0.25);
+        assert!(spinner.is_dirty());
+        assert!(!spinner.tick(SPINNER_REPEAT_DELAY / 2));
+        assert!(spinner.tick(SPINNER_REPEAT_DELAY / 2));
+        assert_eq!(spinner.value(), 0.5);
+        assert!(spinner.tick(SPINNER_REPEAT_INTERVAL * 5));
+        assert_eq!(spinner.value(), 1.0);
+        assert_eq!(spinner.display_text(), "1.00");
+
+        // Moving off the button pauses the repeat
+        spinner.on_event(&WidgetEvent::MouseMove { x: 5, y: 5 });
+        assert!(!spinner.is_repeating());
+        spinner.on_event(&WidgetEvent::MouseUp { x: 5, y: 5 });
+
+        // The down button sits at the bottom right
+        spinner.on_event(&WidgetEvent::MouseDown { x: 55, y: 15 });
+        assert_eq!(spinner.value(), 0.75);
+    }
+}
@@ -0,0 +1,143 @@
+//! Stack widget.
+//!
+//! Shows exactly one of its children at a time, like a deck of cards. The
+//! shown child is picked by the store key the stack is bound to, holding
+//! either a child's part id or its index in z-order (the order of the
+//! `children` array for children of equal `z`). Scripts build wizards and
+//! other multi-step flows by writing the key. As with tab containers, the
+//! stack only tracks which child is shown; the skin builder and the app
+//! show that child and hide the rest.
+
+use std::any::Any;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+
+/// A container showing one child at a time.
+pub struct Stack {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Part ids of the children, in order.
+    children: Vec<String>,
+    /// Index of the child shown.
+    active: usize,
+    /// Color filled in behind the children; transparent when unset.
+    background: Option<u32>,
+    /// Store binding key for the child shown.
+    binding: Option<String>,
+}
+
+impl Stack {
+    /// Create a stack without children.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            children: Vec::new(),
+            active: 0,
+            background: None,
+            binding: None,
+        }
+    }
+
+    /// Add a child by part id, after those already added.
+    pub fn with_child(mut self, id: impl Into<String>) -> Self {
+        self.children.push(id.into());
+        self
+    }
+
+    /// Set the color filled in behind the children.
+    pub fn with_background(mut self, color: u32) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Set the store binding key for the child shown.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the index of the child shown.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Get the part id of the child shown.
+    pub fn active_id(&self) -> Option<&str> {
+        self.children.get(self.active).map(String::as_str)
+    }
+
+    /// Show the child with part id `selection`, or else at index
+    /// `selection`. Unknown ids and indexes past the end are ignored.
+    /// Returns true if another child is now shown.
+    pub fn select(&mut self, selection: &str) -> bool {
+        let selection = selection.trim();
+        let index = self.children.iter().position(|id| id == selection).or_else(|| {
+            let index = selection.parse::<f64>().ok().filter(|n| n.fract() == 0.0 && *n >= 0.0)? as usize;
+            (index < self.children.len()).then_some(index)
+        });
+        match index {
+            Some(index) if index != self.active => {
+                self.active = index;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Widget for Stack {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        if let Some(background) = self.background
+            && let Some(visible) = bounds.intersect(&Rect::new(0, 0, canvas.width(), canvas.height()))
+        {
+            canvas.fill_rect(visible.x as u32, visible.y as u32, visible.width, visible.height, background);
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_by_id_or_index() {
+        let mut stack = Stack::new(10, 10).with_child("intro").with_child("details").with_child("done");
+        assert_eq!(stack.active_id(), Some("intro"));
+
+        assert!(stack.select("done"));
+        assert_eq!(stack.active(), 2);
+        assert!(stack.select("1"));
+        assert_eq!(stack.active_id(), Some("details"));
+        assert!(!stack.select("details"));
+
+        // Unknown children leave the shown one as it is
+        assert!(!stack.select("3"));
+        assert!(!stack.select("1.5"));
+        assert!(!stack.select("missing"));
+        assert_eq!(stack.active(), 1);
+    }
+}
@@ -1,20 +1,29 @@
 use std::any::Any;
+use std::borrow::Cow;
+use std::time::Duration;
 
 use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
 use crate::graphics::{
-    caret_x_sized, draw_text_sized, line_height_sized, Canvas, TextStyle,
+    caret_x_styled, draw_spans, draw_text_sized, line_height_styled, line_top_for_baseline, parse_markup,
+    spans_line_height, spans_text, spans_width, Canvas, TextSpan, TextStyle,
 };
-use crate::skin::types::{TextAlign, VerticalAlign};
+use crate::skin::types::{Marquee, TextAlign, VerticalAlign};
+
+/// Space between the end of marquee text and its next repeat, in pixels.
+const MARQUEE_GAP: u32 = 40;
 
 /// A static text widget for displaying non-editable text.
 /// Can be bound to a Store key to display dynamic values.
+/// With markup, the content can mix bold, colored and sized spans. With a
+/// marquee, text wider than the widget scrolls horizontally instead of being
+/// clipped.
 pub struct StaticText {
     /// The text content to display.
     content: String,
     /// Font size in pixels.
     font_size: f32,
-    /// Text color.
-    text_color: u32,
+    /// Text color and spacing.
+    style: TextStyle,
     /// Horizontal alignment.
     text_align: TextAlign,
     /// Vertical alignment.
@@ -23,6 +32,16 @@ pub struct StaticText {
     padding: u32,
     /// Store binding key for reading values.
     binding: Option<String>,
+    /// Styled spans parsed from the content, when it is markup.
+    spans: Option<Vec<TextSpan>>,
+    /// Scrolling for text wider than the widget.
+    marquee: Option<Marquee>,
+    /// How far the marquee text has scrolled, in pixels.
+    marquee_offset: f32,
+    /// Whether the pointer is over the widget.
+    hovered: bool,
+    /// Width available to the text, from the layout bounds.
+    content_width: u32,
 }
 
 impl StaticText {
@@ -31,11 +50,16 @@ impl StaticText {
         Self {
             content,
             font_size: 16.0,
-            text_color: 0x000000, // Black
+            style: TextStyle::with_color(0x000000), // Black
             text_align: TextAlign::Left,
             vertical_align: VerticalAlign::Center,
             padding: 0,
             binding: None,
+            spans: None,
+            marquee: None,
+            marquee_offset: 0.0,
+            hovered: false,
+            content_width: 0,
         }
     }
 
@@ -47,7 +71,13 @@ impl StaticText {
 
     /// Set the text color.
     pub fn with_text_color(mut self, color: u32) -> Self {
-        self.text_color = color;
+        self.style.color = color;
+        self
+    }
+
+    /// Set the text style (color, letter spacing, line height and tab stops).
+    pub fn with_text_style(mut self, style: TextStyle) -> Self {
+        self.style = style;
         self
     }
 
@@ -75,6 +105,18 @@ impl StaticText {
         self
     }
 
+    /// Set whether the content is markup such as `[b]bold[/b]`.
+    pub fn with_markup(mut self, markup: bool) -> Self {
+        self.spans = markup.then(|| parse_markup(&self.content));
+        self
+    }
+
+    /// Scroll text that is wider than the widget.
+    pub fn with_marquee(mut self, marquee: Marquee) -> Self {
+        self.marquee = Some(marquee);
+        self
+    }
+
     /// Get the binding key.
     pub fn binding(&self) -> Option<&str> {
         self.binding.as_deref()
@@ -85,28 +127,94 @@ impl StaticText {
         &self.content
     }
 
-    /// Set the text content.
+    /// Get the text content without markup.
+    pub fn plain_text(&self) -> Cow<'_, str> {
+        match &self.spans {
+            Some(spans) => Cow::Owned(spans_text(spans)),
+            None => Cow::Borrowed(&self.content),
+        }
+    }
+
+    /// Set the text content. New text starts scrolling from its beginning.
     pub fn set_content(&mut self, content: String) {
+        if content != self.content {
+            self.marquee_offset = 0.0;
+            if self.spans.is_some() {
+                self.spans = Some(parse_markup(&content));
+            }
+        }
         self.content = content;
     }
 
+    /// Set whether the pointer is over the widget, which pauses a marquee
+    /// that pauses on hover.
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    /// Check whether the text is a marquee too wide for the widget.
+    fn overflows(&self) -> bool {
+        self.marquee.is_some() && self.text_width() > self.content_width
+    }
+
+    /// Check whether the marquee is currently scrolling.
+    pub fn is_scrolling(&self) -> bool {
+        self.marquee.is_some_and(|marquee| !(marquee.pause_on_hover && self.hovered)) && self.overflows()
+    }
+
+    /// Get the font size.
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Get the text color.
+    pub fn text_color(&self) -> u32 {
+        self.style.color
+    }
+
+    /// Get the text style.
+    pub fn text_style(&self) -> &TextStyle {
+        &self.style
+    }
+
     /// Measure the width of the text.
     fn text_width(&self) -> u32 {
-        caret_x_sized(&self.content, self.content.chars().count(), self.font_size)
+        match &self.spans {
+            Some(spans) => spans_width(spans, self.font_size, &self.style),
+            None => caret_x_styled(&self.content, usize::MAX, self.font_size, &self.style),
+        }
     }
-}
 
-impl Widget for StaticText {
-    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
-        // Calculate content rect (with padding)
-        let content_rect = Rect::new(
+    /// Measure the height of the text's line.
+    fn text_height(&self) -> u32 {
+        match &self.spans {
+            Some(spans) => spans_line_height(spans, self.font_size, &self.style),
+            None => line_height_styled(self.font_size, &self.style),
+        }
+    }
+
+    /// Draw the text with its top-left corner at (`x`, `y`), clipped to `clip`.
+    fn draw_line(&self, canvas: &mut Canvas, x: i32, y: i32, clip: &Rect) {
+        match &self.spans {
+            Some(spans) => draw_spans(canvas, x, y, Some(clip), spans, self.style, self.font_size),
+            None => draw_text_sized(canvas, x, y, Some(clip), &self.content, self.style, self.font_size),
+        }
+    }
+
+    /// Get the content rect (bounds minus padding).
+    fn content_rect(&self, bounds: &Rect) -> Rect {
+        Rect::new(
             bounds.x + self.padding as i32,
             bounds.y + self.padding as i32,
             bounds.width.saturating_sub(self.padding * 2),
             bounds.height.saturating_sub(self.padding * 2),
-        );
+        )
+    }
 
-        let text_height = line_height_sized(self.font_size);
+    /// Get the top-left position of the text within `bounds`, after alignment.
+    pub fn text_origin(&self, bounds: &Rect) -> (i32, i32) {
+        let content_rect = self.content_rect(bounds);
+        let text_height = self.text_height();
         let text_width = self.text_width();
 
         // Calculate x position based on horizontal alignment
@@ -129,23 +237,53 @@ impl Widget for StaticText {
             VerticalAlign::Bottom => {
                 content_rect.y + content_rect.height as i32 - text_height as i32
             }
+            VerticalAlign::Baseline(baseline) => {
+                line_top_for_baseline(bounds.y + baseline as i32, self.font_size)
+            }
         };
 
+        (text_x, text_y)
+    }
+}
+
+impl Widget for StaticText {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let content_rect = self.content_rect(bounds);
+        let (text_x, text_y) = self.text_origin(bounds);
+
+        if self.overflows() {
+            // Draw the text scrolled left, followed by its repeat
+            let x = content_rect.x - self.marquee_offset as i32;
+            let cycle = (self.text_width() + MARQUEE_GAP) as i32;
+            for x in [x, x + cycle] {
+                self.draw_line(canvas, x, text_y, &content_rect);
+            }
+            return;
+        }
+
         // Draw text clipped to content rect
-        draw_text_sized(
-            canvas,
-            text_x,
-            text_y,
-            Some(&content_rect),
-            &self.content,
-            TextStyle::with_color(self.text_color),
-            self.font_size,
-        );
+        self.draw_line(canvas, text_x, text_y, &content_rect);
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.content_width = self.content_rect(&bounds).width;
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        let Some(marquee) = self.marquee else {
+            return false;
+        };
+        if !self.is_scrolling() {
+            return false;
+        }
+        let cycle = (self.text_width() + MARQUEE_GAP) as f32;
+        self.marquee_offset = (self.marquee_offset + marquee.speed * dt.as_secs_f32()).rem_euclid(cycle);
+        true
     }
 
     fn preferred_size(&self) -> (u32, u32) {
         let width = self.text_width() + self.padding * 2;
-        let height = line_height_sized(self.font_size) + self.padding * 2;
+        let height = self.text_height() + self.padding * 2;
         (width, height)
     }
 
@@ -161,3 +299,34 @@ impl Widget for StaticText {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::graphics::init_font;
+
+    #[test]
+    fn test_marquee_scrolls_and_pauses() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let marquee = Marquee { speed: 50.0, pause_on_hover: true };
+        let mut text = StaticText::new("A headline far too long to fit".to_string()).with_marquee(marquee);
+        text.set_bounds(Rect::new(0, 0, 40, 20));
+        assert!(text.tick(Duration::from_millis(100)));
+        assert_eq!(text.marquee_offset, 5.0);
+
+        // Hovering holds the text still
+        text.set_hovered(true);
+        assert!(!text.tick(Duration::from_millis(100)));
+        assert_eq!(text.marquee_offset, 5.0);
+        text.set_hovered(false);
+
+        // New text starts over, and text that fits doesn't scroll
+        text.set_content("Hi".to_string());
+        assert_eq!(text.marquee_offset, 0.0);
+        assert!(!text.tick(Duration::from_millis(100)));
+    }
+}
@@ -0,0 +1,258 @@
+//! Status bar widget.
+//!
+//! A strip of text segments, usually along the bottom of the window, each
+//! showing its own store key so scripts can keep status, progress and error
+//! text in separate slots. Segments are laid out left to right with a
+//! one-pixel separator between them: fixed and percentage widths first, then
+//! the rest shared evenly by the segments that fill. Text that doesn't fit
+//! its segment is cut off at the segment's edge.
+
+use std::any::Any;
+
+use crate::core::{Length, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{caret_x_sized, draw_text_sized, line_height_sized, Canvas, TextStyle};
+use crate::skin::types::TextAlign;
+
+/// Separator color unless the skin says otherwise.
+pub const DEFAULT_STATUS_SEPARATOR_COLOR: u32 = 0xA0A0A0;
+
+/// Space between a segment's text and its sides unless the skin says otherwise.
+const DEFAULT_STATUS_PADDING: u32 = 4;
+
+/// One slot of the bar.
+struct Segment {
+    /// Store binding key.
+    binding: String,
+    width: Length,
+    align: TextAlign,
+    /// Text color, or the bar's when unset.
+    text_color: Option<u32>,
+    /// Text shown.
+    text: String,
+}
+
+/// A row of store-bound text segments.
+pub struct StatusBar {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    segments: Vec<Segment>,
+    /// Color filled in behind the segments; transparent when unset.
+    background: Option<u32>,
+    separator_color: u32,
+    /// Text appearance.
+    text_color: u32,
+    font_size: f32,
+    /// Space between text and the sides of its segment.
+    padding: u32,
+}
+
+impl StatusBar {
+    /// Create a bar without segments.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            segments: Vec::new(),
+            background: None,
+            separator_color: DEFAULT_STATUS_SEPARATOR_COLOR,
+            text_color: 0x000000,
+            font_size: 14.0,
+            padding: DEFAULT_STATUS_PADDING,
+        }
+    }
+
+    /// Add a segment showing `binding`, after those already added. Auto and
+    /// aspect widths fill like `Length::Fill`.
+    pub fn with_segment(
+        mut self,
+        binding: impl Into<String>,
+        width: Length,
+        align: TextAlign,
+        text_color: Option<u32>,
+    ) -> Self {
+        self.segments.push(Segment { binding: binding.into(), width, align, text_color, text: String::new() });
+        self
+    }
+
+    /// Set the color filled in behind the segments.
+    pub fn with_background(mut self, color: u32) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Set the color of the lines between segments.
+    pub fn with_separator_color(mut self, color: u32) -> Self {
+        self.separator_color = color;
+        self
+    }
+
+    /// Set the text color of segments without their own.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set the space between text and the sides of its segment.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Get the store binding keys of the segments, in order.
+    pub fn bindings(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().map(|segment| segment.binding.as_str())
+    }
+
+    /// Get the text of segment `index`.
+    pub fn text(&self, index: usize) -> Option<&str> {
+        self.segments.get(index).map(|segment| segment.text.as_str())
+    }
+
+    /// Set the text of segment `index`. Returns true if it changed.
+    pub fn set_text(&mut self, index: usize, text: &str) -> bool {
+        match self.segments.get_mut(index) {
+            Some(segment) if segment.text != text => {
+                segment.text = text.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the area of each segment in `bounds`, in order.
+    fn segment_rects(&self, bounds: &Rect) -> Vec<Rect> {
+        let separators = self.segments.len().saturating_sub(1) as u32;
+        let widths = segment_widths(bounds.width.saturating_sub(separators), self.segments.iter().map(|s| s.width));
+        let mut x = bounds.x;
+        widths
+            .into_iter()
+            .map(|width| {
+                let rect = Rect::new(x, bounds.y, width, bounds.height);
+                x += width as i32 + 1;
+                rect
+            })
+            .collect()
+    }
+}
+
+/// Share `total` pixels between segments of `lengths`: pixel and percentage
+/// widths get theirs while they last, and the others split what remains,
+/// the first ones taking a pixel more when it doesn't divide evenly.
+fn segment_widths(total: u32, lengths: impl Iterator<Item = Length>) -> Vec<u32> {
+    let lengths: Vec<Length> = lengths.collect();
+    let mut left = total;
+    let mut widths: Vec<Option<u32>> = lengths
+        .iter()
+        .map(|length| {
+            let fixed = match *length {
+                Length::Pixels(pixels) => pixels,
+                Length::Percent(fraction) => (total as f32 * fraction).round() as u32,
+                _ => return None,
+            }
+            .min(left);
+            left -= fixed;
+            Some(fixed)
+        })
+        .collect();
+
+    let fills = widths.iter().filter(|width| width.is_none()).count() as u32;
+    for (index, width) in widths.iter_mut().filter(|width| width.is_none()).enumerate() {
+        *width = Some(left / fills + u32::from((index as u32) < left % fills));
+    }
+    widths.into_iter().flatten().collect()
+}
+
+impl Widget for StatusBar {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let Some(visible) = bounds.intersect(&Rect::new(0, 0, canvas.width(), canvas.height())) else {
+            return;
+        };
+        if let Some(background) = self.background {
+            canvas.fill_rect(visible.x as u32, visible.y as u32, visible.width, visible.height, background);
+        }
+
+        let rects = self.segment_rects(bounds);
+        for (i, (segment, rect)) in self.segments.iter().zip(&rects).enumerate() {
+            if i > 0 && rect.x > bounds.x && rect.x <= bounds.right() {
+                let line = Rect::new(rect.x - 1, bounds.y, 1, bounds.height);
+                if let Some(line) = line.intersect(&visible) {
+                    canvas.fill_rect(line.x as u32, line.y as u32, line.width, line.height, self.separator_color);
+                }
+            }
+            if segment.text.is_empty() {
+                continue;
+            }
+
+            let inner = Rect::new(
+                rect.x + self.padding as i32,
+                rect.y,
+                rect.width.saturating_sub(2 * self.padding),
+                rect.height,
+            );
+            let free = inner.width as i32 - caret_x_sized(&segment.text, usize::MAX, self.font_size) as i32;
+            let x = inner.x
+                + match segment.align {
+                    TextAlign::Left => 0,
+                    TextAlign::Center => free / 2,
+                    TextAlign::Right => free,
+                };
+            // Overlong text starts at the near edge and is cut at the far one
+            let x = if free < 0 { inner.x } else { x };
+            let text_y = bounds.y + (bounds.height as i32 - line_height_sized(self.font_size) as i32) / 2;
+            let color = segment.text_color.unwrap_or(self.text_color);
+            draw_text_sized(canvas, x, text_y, Some(&inner), &segment.text, TextStyle::with_color(color), self.font_size);
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_share_width() {
+        // Fixed and percentage widths first, the rest split evenly
+        let lengths = [Length::Pixels(20), Length::Fill, Length::Percent(0.25), Length::Auto];
+        assert_eq!(segment_widths(101, lengths.into_iter()), [20, 28, 25, 28]);
+        // Fixed widths past the total are cut, leaving fills nothing
+        assert_eq!(segment_widths(30, [Length::Pixels(50), Length::Fill].into_iter()), [30, 0]);
+
+        // Separators sit between the segments
+        let mut bar = StatusBar::new(21, 4)
+            .with_segment("status", Length::Pixels(10), TextAlign::Left, None)
+            .with_segment("error", Length::Fill, TextAlign::Right, Some(0xFF0000))
+            .with_separator_color(0x0000FF);
+        let mut buffer = vec![0xFFFFFF; 21 * 4];
+        bar.draw(&mut Canvas::new(&mut buffer, 21, 4), &Rect::new(0, 0, 21, 4), WidgetState::default());
+        assert_eq!([buffer[9], buffer[10], buffer[11]], [0xFFFFFF, 0x0000FF, 0xFFFFFF]);
+
+        assert!(bar.set_text(1, "Disk full"));
+        assert!(!bar.set_text(1, "Disk full"));
+        assert!(!bar.set_text(2, "No such segment"));
+        assert_eq!(bar.bindings().collect::<Vec<_>>(), ["status", "error"]);
+        assert_eq!(bar.text(1), Some("Disk full"));
+    }
+}
@@ -0,0 +1,99 @@
+//! Step progress indicator widget.
+//!
+//! Draws one marker per wizard step, spread evenly across its width: steps
+//! before the current one use the `done` image, the current step uses
+//! `current` and later steps use `pending`. Progress is read from the
+//! `wizard.step` and `wizard.count` store keys.
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetState};
+use crate::graphics::Canvas;
+
+/// A row of step markers showing wizard progress.
+pub struct StepProgress {
+    done: RgbImage,
+    current: RgbImage,
+    pending: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Current step, starting at 1.
+    step: usize,
+    /// Total number of steps.
+    count: usize,
+}
+
+impl StepProgress {
+    /// Create a progress indicator from marker images for each step state.
+    pub fn new(width: u32, height: u32, done: RgbImage, current: RgbImage, pending: RgbImage) -> Self {
+        Self {
+            done,
+            current,
+            pending,
+            width,
+            height,
+            step: 0,
+            count: 0,
+        }
+    }
+
+    /// Set the current step (starting at 1) and the number of steps.
+    pub fn set_progress(&mut self, step: usize, count: usize) {
+        self.step = step;
+        self.count = count;
+    }
+
+    /// Get the current step and number of steps.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.step, self.count)
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for StepProgress {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        if self.count == 0 {
+            return;
+        }
+
+        // Each marker is centered in an equal slot of the width
+        let slot = bounds.width as f32 / self.count as f32;
+        for i in 0..self.count {
+            let image = match (i + 1).cmp(&self.step) {
+                std::cmp::Ordering::Less => &self.done,
+                std::cmp::Ordering::Equal => &self.current,
+                std::cmp::Ordering::Greater => &self.pending,
+            };
+            let center = bounds.x as f32 + slot * (i as f32 + 0.5);
+            let x = (center - image.width() as f32 / 2.0).round() as i32;
+            let y = bounds.y + (bounds.height as i32 - image.height() as i32) / 2;
+            self.draw_image(canvas, x, y, image, bounds);
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,311 @@
+//! Tab container widget.
+//!
+//! A row of tabs along the top edge with a panel area below. Each tab's
+//! panel is a child node of the container, in tab order; the container only
+//! draws the tabs and tracks which one is active, and the skin builder (and
+//! the app, whenever the active tab changes) shows the active panel and hides
+//! the rest. Clicking a tab, or Left/Right while focused, switches tabs.
+
+use std::any::Any;
+
+use image::RgbImage;
+
+use crate::core::{KeyCode, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{caret_x_sized, draw_text_sized, line_height_sized, Canvas, TextStyle};
+
+/// A tab strip that switches between child panels.
+pub struct TabContainer {
+    /// Tab images; their height sets the height of the tab strip.
+    tab: RgbImage,
+    tab_hover: RgbImage,
+    tab_active: RgbImage,
+    /// Background drawn behind the panels.
+    panel: Option<RgbImage>,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Tab ids and labels, in order.
+    tabs: Vec<(String, String)>,
+    /// Index of the active tab.
+    active: usize,
+    /// Tab under the pointer.
+    hovered: Option<usize>,
+    /// Label appearance.
+    text_color: u32,
+    font_size: f32,
+    /// Store binding key for the active tab id.
+    binding: Option<String>,
+    /// Action to trigger when the active tab changes.
+    action: Option<String>,
+    /// Current layout bounds.
+    bounds: Rect,
+    /// Flag indicating the active tab changed since last sync.
+    dirty: bool,
+    /// Set when the active tab changed and the action hasn't been taken yet.
+    changed: bool,
+}
+
+impl TabContainer {
+    /// Create a tab container with no tabs.
+    pub fn new(width: u32, height: u32, tab: RgbImage, tab_hover: RgbImage) -> Self {
+        Self {
+            tab_active: tab_hover.clone(),
+            tab,
+            tab_hover,
+            panel: None,
+            width,
+            height,
+            tabs: Vec::new(),
+            active: 0,
+            hovered: None,
+            text_color: 0x000000,
+            font_size: 16.0,
+            binding: None,
+            action: None,
+            bounds: Rect::new(0, 0, width, height),
+            dirty: false,
+            changed: false,
+        }
+    }
+
+    /// Add a tab.
+    pub fn with_tab(mut self, id: impl Into<String>, label: impl Into<String>) -> Self {
+        self.tabs.push((id.into(), label.into()));
+        self
+    }
+
+    /// Set the image of the active tab.
+    pub fn with_tab_active(mut self, image: RgbImage) -> Self {
+        self.tab_active = image;
+        self
+    }
+
+    /// Set the background drawn behind the panels.
+    pub fn with_panel(mut self, image: RgbImage) -> Self {
+        self.panel = Some(image);
+        self
+    }
+
+    /// Set the label color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the label font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set the store binding key for the active tab id.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the active tab changes.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the number of tabs.
+    pub fn tab_count(&self) -> usize {
+        self.tabs.len()
+    }
+
+    /// Get the height of the tab strip; panels start below it.
+    pub fn strip_height(&self) -> u32 {
+        self.tab.height()
+    }
+
+    /// Get the index of the active tab.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Get the id of the active tab.
+    pub fn active_id(&self) -> Option<&str> {
+        self.tabs.get(self.active).map(|(id, _)| id.as_str())
+    }
+
+    /// Activate the tab with the given id without marking the container
+    /// dirty (e.g. from the store). Returns true if the active tab changed.
+    pub fn set_active_id(&mut self, id: &str) -> bool {
+        match self.tabs.iter().position(|(tab_id, _)| tab_id == id) {
+            Some(index) if index != self.active => {
+                self.active = index;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if the active tab has changed since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the change action if the active tab just changed.
+    pub fn take_change_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.changed) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    fn tab_width(&self) -> u32 {
+        self.tab.width().max(1)
+    }
+
+    /// Get the tab under a point.
+    fn tab_at(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.bounds.contains(x, y) || y >= self.bounds.y + self.strip_height() as i32 {
+            return None;
+        }
+        let index = ((x - self.bounds.x) as u32 / self.tab_width()) as usize;
+        (index < self.tabs.len()).then_some(index)
+    }
+
+    fn activate(&mut self, index: usize) {
+        if index != self.active {
+            self.active = index;
+            self.dirty = true;
+            self.changed = true;
+        }
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+impl Widget for TabContainer {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let strip_height = self.strip_height() as i32;
+        if let Some(panel) = &self.panel {
+            self.draw_image(canvas, bounds.x, bounds.y + strip_height, panel, bounds);
+        }
+
+        let text_y = bounds.y + (strip_height - line_height_sized(self.font_size) as i32) / 2;
+        for (index, (_, label)) in self.tabs.iter().enumerate() {
+            let x = bounds.x + (index as u32 * self.tab_width()) as i32;
+            let image = if index == self.active {
+                &self.tab_active
+            } else if self.hovered == Some(index) {
+                &self.tab_hover
+            } else {
+                &self.tab
+            };
+            self.draw_image(canvas, x, bounds.y, image, bounds);
+
+            // Label centered on the tab
+            let label_width = caret_x_sized(label, usize::MAX, self.font_size) as i32;
+            draw_text_sized(
+                canvas,
+                x + (self.tab_width() as i32 - label_width) / 2,
+                text_y,
+                Some(bounds),
+                label,
+                TextStyle::with_color(self.text_color),
+                self.font_size,
+            );
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseMove { x, y } => {
+                self.hovered = self.tab_at(*x, *y);
+                true
+            }
+            WidgetEvent::MouseDown { x, y } => {
+                if let Some(index) = self.tab_at(*x, *y) {
+                    self.activate(index);
+                }
+                true
+            }
+            WidgetEvent::KeyDown { key } => {
+                let Some(last) = self.tabs.len().checked_sub(1) else {
+                    return false;
+                };
+                let next = match key.without_select() {
+                    KeyCode::Left => self.active.saturating_sub(1),
+                    KeyCode::Right => (self.active + 1).min(last),
+                    KeyCode::Home => 0,
+                    KeyCode::End => last,
+                    _ => return false,
+                };
+                self.activate(next);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_tabs() {
+        let mut tabs = TabContainer::new(300, 200, RgbImage::new(80, 24), RgbImage::new(80, 24))
+            .with_tab("general", "General")
+            .with_tab("advanced", "Advanced")
+            .with_action("tab_changed");
+        tabs.set_bounds(Rect::new(0, 0, 300, 200));
+
+        // Clicks below the strip or past the last tab don't switch
+        tabs.on_event(&WidgetEvent::MouseDown { x: 100, y: 50 });
+        tabs.on_event(&WidgetEvent::MouseDown { x: 200, y: 10 });
+        assert_eq!(tabs.active_id(), Some("general"));
+
+        tabs.on_event(&WidgetEvent::MouseDown { x: 100, y: 10 });
+        assert_eq!(tabs.active_id(), Some("advanced"));
+        assert!(tabs.is_dirty());
+        assert_eq!(tabs.take_change_action().as_deref(), Some("tab_changed"));
+
+        // Switching from the store doesn't report a change
+        tabs.clear_dirty();
+        assert!(tabs.set_active_id("general"));
+        assert!(!tabs.is_dirty());
+        assert!(!tabs.set_active_id("missing"));
+    }
+}
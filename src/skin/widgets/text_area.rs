@@ -0,0 +1,729 @@
+//! Multiline text area widget.
+//!
+//! An editable, word-wrapped block of text. Enter inserts a newline, Up and
+//! Down move the caret between visual lines, and the content scrolls to keep
+//! the caret in view (or with the mouse wheel) when it's taller than the box.
+//! With spell-check enabled, the app marks misspelled words, which are drawn
+//! with a wavy underline.
+
+use std::any::Any;
+use std::ops::Range;
+use std::time::Duration;
+
+use image::RgbImage;
+
+use crate::core::{KeyCode, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::text::wrap_lines;
+use crate::graphics::{
+    caret_x_styled, draw_caret, draw_text_sized, line_height_styled, Canvas, NineSlice, TextStyle,
+    CARET_BLINK_INTERVAL,
+};
+
+use super::edit_history::{EditHistory, EditKind};
+use super::text_input::TextLine;
+
+/// Color of the wavy underline below misspelled words.
+const MISSPELLED_COLOR: u32 = 0xE02020;
+
+/// A text input for editable multiline text.
+///
+/// ## Limitations (v0)
+/// - No text selection, copy/paste, or IME
+pub struct TextArea {
+    /// The current text content.
+    text: String,
+    /// Cursor position as a byte offset into `text`.
+    cursor: usize,
+    /// Caret x offset kept while moving up and down through shorter lines.
+    goal_x: Option<u32>,
+    /// Background images for different states.
+    normal: RgbImage,
+    hover: RgbImage,
+    focused: RgbImage,
+    /// Border kept while the images are stretched to the widget's size.
+    slice: Option<NineSlice>,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Layout rect, for placing the caret on click.
+    bounds: Rect,
+    /// Text padding from edges.
+    padding: u32,
+    /// Text color and spacing.
+    style: TextStyle,
+    /// Caret color.
+    caret_color: u32,
+    /// Font size in pixels.
+    font_size: f32,
+    /// Maximum number of characters allowed.
+    max_length: Option<u32>,
+    /// Vertical scroll offset in pixels.
+    scroll_y: u32,
+    /// Caret blink timing.
+    caret_visible: bool,
+    blink_elapsed: Duration,
+    has_focus: bool,
+    /// Action to emit on change.
+    on_change_action: Option<String>,
+    /// Store binding key for syncing value.
+    binding: Option<String>,
+    /// Flag indicating the text was modified since last sync.
+    dirty: bool,
+    /// Whether misspelled words are underlined.
+    spellcheck: bool,
+    /// Byte ranges of the misspelled words.
+    misspelled: Vec<Range<usize>>,
+    /// Set when the text changed since the misspelled words were found.
+    spelling_stale: bool,
+    /// Undo/redo steps (Ctrl+Z/Ctrl+Y).
+    history: EditHistory,
+}
+
+impl TextArea {
+    /// Create a new text area with the given state images.
+    pub fn new(normal: RgbImage, hover: RgbImage, focused: RgbImage) -> Self {
+        let width = normal.width();
+        let height = normal.height();
+        Self {
+            text: String::new(),
+            cursor: 0,
+            goal_x: None,
+            normal,
+            hover,
+            focused,
+            slice: None,
+            width,
+            height,
+            bounds: Rect::new(0, 0, width, height),
+            padding: 4,
+            style: TextStyle::with_color(0x000000),
+            caret_color: 0x000000,
+            font_size: 16.0,
+            max_length: None,
+            scroll_y: 0,
+            caret_visible: true,
+            blink_elapsed: Duration::ZERO,
+            has_focus: false,
+            on_change_action: None,
+            binding: None,
+            dirty: false,
+            spellcheck: false,
+            misspelled: Vec::new(),
+            spelling_stale: true,
+            history: EditHistory::new(),
+        }
+    }
+
+    /// Stretch the background images to the widget's size, keeping a
+    /// border of them as it is.
+    pub fn with_slice(mut self, slice: NineSlice) -> Self {
+        self.slice = Some(slice);
+        self
+    }
+
+    /// Set the text padding.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.style.color = color;
+        self
+    }
+
+    /// Set the text style (color, letter spacing, line height and tab stops).
+    pub fn with_text_style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Get the text style.
+    pub fn text_style(&self) -> &TextStyle {
+        &self.style
+    }
+
+    /// Set the caret color.
+    pub fn with_caret_color(mut self, color: u32) -> Self {
+        self.caret_color = color;
+        self
+    }
+
+    /// Set the on_change action.
+    pub fn with_on_change(mut self, action: String) -> Self {
+        self.on_change_action = Some(action);
+        self
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set the maximum length, counting newlines.
+    pub fn with_max_length(mut self, max: u32) -> Self {
+        self.max_length = Some(max);
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: String) -> Self {
+        self.binding = Some(binding);
+        self
+    }
+
+    /// Enable underlining misspelled words.
+    pub fn with_spellcheck(mut self, spellcheck: bool) -> Self {
+        self.spellcheck = spellcheck;
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Check if misspelled words are underlined.
+    pub fn spellcheck(&self) -> bool {
+        self.spellcheck
+    }
+
+    /// Check if the text changed since the misspelled words were last set.
+    pub fn needs_spellcheck(&self) -> bool {
+        self.spellcheck && self.spelling_stale
+    }
+
+    /// Set the byte ranges of the misspelled words to underline.
+    pub fn set_misspelled(&mut self, misspelled: Vec<Range<usize>>) {
+        self.misspelled = misspelled;
+        self.spelling_stale = false;
+    }
+
+    /// Get the misspelled word at a point, as a byte range.
+    pub fn misspelled_at(&self, x: i32, y: i32) -> Option<Range<usize>> {
+        let offset = self.offset_at_point(x, y);
+        self.misspelled
+            .iter()
+            .find(|range| range.start <= offset && offset <= range.end)
+            .cloned()
+    }
+
+    /// Replace a byte range of the text (e.g. a misspelled word with a
+    /// suggestion), leaving the caret after the replacement.
+    pub fn replace_range(&mut self, range: Range<usize>, replacement: &str) {
+        let valid = range.start <= range.end
+            && self.text.is_char_boundary(range.start)
+            && self.text.is_char_boundary(range.end);
+        if !valid {
+            return;
+        }
+        self.history.record(EditKind::Replace, &self.text, self.cursor);
+        self.text.replace_range(range.clone(), replacement);
+        self.cursor = range.start + replacement.len();
+        self.edited();
+        self.notify_change();
+    }
+
+    /// Check if the text has been modified since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Get the current text value.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set the text value. Text set from outside can't be undone back to
+    /// what was typed before.
+    pub fn set_text(&mut self, text: String) {
+        if text != self.text {
+            self.spelling_stale = true;
+            self.history.clear();
+        }
+        self.text = text;
+        self.cursor = self.cursor.min(self.text.len());
+        while !self.text.is_char_boundary(self.cursor) {
+            self.cursor -= 1;
+        }
+        self.goal_x = None;
+        self.scroll_into_view();
+    }
+
+    /// Get the on_change action.
+    pub fn on_change_action(&self) -> Option<&str> {
+        self.on_change_action.as_deref()
+    }
+
+    /// Area the text is drawn in.
+    fn content_rect(&self, bounds: &Rect) -> Rect {
+        Rect::new(
+            bounds.x + self.padding as i32,
+            bounds.y + self.padding as i32,
+            bounds.width.saturating_sub(self.padding * 2),
+            bounds.height.saturating_sub(self.padding * 2),
+        )
+    }
+
+    fn line_height(&self) -> u32 {
+        line_height_styled(self.font_size, &self.style)
+    }
+
+    fn measure(&self, text: &str) -> u32 {
+        caret_x_styled(text, usize::MAX, self.font_size, &self.style)
+    }
+
+    /// Wrap the text to the content width.
+    fn lines(&self) -> Vec<(usize, usize)> {
+        let width = self.content_rect(&self.bounds).width;
+        wrap_lines(&self.text, width, |s| self.measure(s))
+    }
+
+    /// Insert a character at the cursor position.
+    /// Returns true if the text was modified.
+    fn insert_char(&mut self, c: char) -> bool {
+        if let Some(max) = self.max_length
+            && self.text.chars().count() >= max as usize
+        {
+            return false;
+        }
+        if c != '\n' && c.is_control() {
+            return false;
+        }
+
+        self.history.record(EditKind::Insert, &self.text, self.cursor);
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        // Spaces and newlines end the undo step, so undo takes back a word at a time
+        if c.is_whitespace() {
+            self.history.break_group();
+        }
+        self.edited();
+        true
+    }
+
+    /// Delete the character before the cursor (backspace).
+    /// Returns true if the text was modified.
+    fn backspace(&mut self) -> bool {
+        let Some(c) = self.text[..self.cursor].chars().next_back() else {
+            return false;
+        };
+        self.history.record(EditKind::Delete, &self.text, self.cursor);
+        self.cursor -= c.len_utf8();
+        self.text.remove(self.cursor);
+        self.edited();
+        true
+    }
+
+    /// Delete the character at the cursor position.
+    /// Returns true if the text was modified.
+    fn delete(&mut self) -> bool {
+        if self.cursor < self.text.len() {
+            self.history.record(EditKind::Delete, &self.text, self.cursor);
+            self.text.remove(self.cursor);
+            self.edited();
+            return true;
+        }
+        false
+    }
+
+    /// Undo (or redo) the last edit step.
+    /// Returns true if the text was modified.
+    fn undo_redo(&mut self, redo: bool) -> bool {
+        let restored = if redo {
+            self.history.redo(&mut self.text, &mut self.cursor)
+        } else {
+            self.history.undo(&mut self.text, &mut self.cursor)
+        };
+        if restored {
+            self.edited();
+        }
+        restored
+    }
+
+    fn edited(&mut self) {
+        self.dirty = true;
+        self.spelling_stale = true;
+        self.goal_x = None;
+        self.reset_blink();
+        self.scroll_into_view();
+    }
+
+    /// Move the cursor to a byte offset, keeping the goal column if `vertical`.
+    fn move_to(&mut self, cursor: usize, vertical: bool) {
+        self.cursor = cursor;
+        self.history.break_group();
+        if !vertical {
+            self.goal_x = None;
+        }
+        self.reset_blink();
+        self.scroll_into_view();
+    }
+
+    fn move_left(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.move_to(self.cursor - c.len_utf8(), false);
+        }
+    }
+
+    fn move_right(&mut self) {
+        if let Some(c) = self.text[self.cursor..].chars().next() {
+            self.move_to(self.cursor + c.len_utf8(), false);
+        }
+    }
+
+    /// Move to the start of the current visual line.
+    fn move_home(&mut self) {
+        let lines = self.lines();
+        let (start, _) = lines[line_of(&lines, self.cursor)];
+        self.move_to(start, false);
+    }
+
+    /// Move to the end of the current visual line.
+    fn move_end(&mut self) {
+        let lines = self.lines();
+        let line = line_of(&lines, self.cursor);
+        let end = self.line_caret_end(&lines, line);
+        self.move_to(end, false);
+    }
+
+    /// Move the caret to the previous (`-1`) or next (`1`) visual line,
+    /// keeping its horizontal position.
+    fn move_vertical(&mut self, direction: isize) {
+        let lines = self.lines();
+        let line = line_of(&lines, self.cursor);
+        let Some(target) = line.checked_add_signed(direction).filter(|&l| l < lines.len()) else {
+            // Past the first or last line, go to the start or end of the text
+            let cursor = if direction < 0 { 0 } else { self.text.len() };
+            self.move_to(cursor, false);
+            return;
+        };
+
+        let (start, _) = lines[line];
+        let x = self.goal_x.unwrap_or_else(|| self.measure(&self.text[start..self.cursor]));
+        self.goal_x = Some(x);
+        let cursor = self.offset_at_x(&lines, target, x);
+        self.move_to(cursor, true);
+    }
+
+    /// Last caret position on a visual line. A soft-wrapped line ends before
+    /// the character the next line starts with.
+    fn line_caret_end(&self, lines: &[(usize, usize)], line: usize) -> usize {
+        let (start, end) = lines[line];
+        if line + 1 < lines.len() && lines[line + 1].0 == end {
+            self.text[start..end].char_indices().next_back().map_or(start, |(i, _)| start + i)
+        } else {
+            end
+        }
+    }
+
+    /// Find the byte offset on a visual line closest to an x offset.
+    fn offset_at_x(&self, lines: &[(usize, usize)], line: usize, x: u32) -> usize {
+        let (start, _) = lines[line];
+        let end = self.line_caret_end(lines, line);
+        let mut best = start;
+        let mut best_dist = u32::MAX;
+        let offsets = self.text[start..end].char_indices().map(|(i, _)| start + i).chain([end]);
+        for offset in offsets {
+            let dist = self.measure(&self.text[start..offset]).abs_diff(x);
+            if dist < best_dist {
+                best_dist = dist;
+                best = offset;
+            }
+        }
+        best
+    }
+
+    /// Scroll so the caret's line is inside the box.
+    fn scroll_into_view(&mut self) {
+        let lines = self.lines();
+        let line_height = self.line_height();
+        let view_height = self.content_rect(&self.bounds).height;
+        let top = line_of(&lines, self.cursor) as u32 * line_height;
+        if top < self.scroll_y {
+            self.scroll_y = top;
+        } else if top + line_height > self.scroll_y + view_height {
+            self.scroll_y = (top + line_height).saturating_sub(view_height);
+        }
+        self.scroll_y = self.scroll_y.min(self.max_scroll(lines.len()));
+    }
+
+    fn max_scroll(&self, line_count: usize) -> u32 {
+        let content_height = line_count as u32 * self.line_height();
+        content_height.saturating_sub(self.content_rect(&self.bounds).height)
+    }
+
+    /// Find the byte offset closest to a point.
+    fn offset_at_point(&self, x: i32, y: i32) -> usize {
+        let content = self.content_rect(&self.bounds);
+        let lines = self.lines();
+        let row = (y - content.y + self.scroll_y as i32).max(0) as u32 / self.line_height();
+        let line = (row as usize).min(lines.len() - 1);
+        self.offset_at_x(&lines, line, (x - content.x).max(0) as u32)
+    }
+
+    /// Place the cursor at a clicked point.
+    fn set_cursor_from_point(&mut self, x: i32, y: i32) {
+        let cursor = self.offset_at_point(x, y);
+        self.move_to(cursor, false);
+    }
+
+    /// Draw a wavy underline below the misspelled parts of a visual line.
+    fn draw_misspelled(&self, canvas: &mut Canvas, line: (usize, usize), x: i32, y: i32, clip: &Rect) {
+        let (start, end) = line;
+        let baseline = y + self.line_height() as i32 - 3;
+        for range in &self.misspelled {
+            let from = range.start.max(start);
+            let to = range.end.min(end);
+            if from >= to {
+                continue;
+            }
+            let x0 = x + self.measure(&self.text[start..from]) as i32;
+            let x1 = x + self.measure(&self.text[start..to]) as i32;
+            for px in x0..x1 {
+                // Two pixels up, two pixels down
+                let py = baseline + ((px - x0) / 2 % 2);
+                if clip.contains(px, py) && px >= 0 && py >= 0 {
+                    canvas.set_pixel(px as u32, py as u32, MISSPELLED_COLOR);
+                }
+            }
+        }
+    }
+
+    /// Reset the blink timer and make the caret visible.
+    fn reset_blink(&mut self) {
+        self.caret_visible = true;
+        self.blink_elapsed = Duration::ZERO;
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, bounds: &Rect, image: &RgbImage) {
+        canvas.draw_image(image, bounds, self.slice.as_ref());
+    }
+
+    /// Draw the box of an unfocused text area without its text.
+    pub fn draw_background(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        let image = if state.hovered { &self.hover } else { &self.normal };
+        self.draw_image(canvas, bounds, image);
+    }
+
+    /// Get the lines of text shown in `bounds` at the current scroll
+    /// position: those at least half inside the box.
+    pub fn text_lines(&self, bounds: &Rect) -> Vec<TextLine> {
+        let content_rect = self.content_rect(bounds);
+        let line_height = self.line_height();
+        wrap_lines(&self.text, content_rect.width, |s| self.measure(s))
+            .into_iter()
+            .enumerate()
+            .filter_map(|(row, (start, end))| {
+                let y = content_rect.y + (row as u32 * line_height) as i32 - self.scroll_y as i32;
+                let middle = y + line_height as i32 / 2;
+                let text = self.text[start..end].trim_end_matches('\n');
+                (middle >= content_rect.y && middle < content_rect.bottom() && !text.is_empty()).then(|| TextLine {
+                    text: text.to_string(),
+                    x: content_rect.x,
+                    y,
+                    font_size: self.font_size,
+                    color: self.style.color,
+                })
+            })
+            .collect()
+    }
+
+    fn notify_change(&self) {
+        if let Some(action) = &self.on_change_action {
+            println!("TextArea change: {} -> {}", action, self.text);
+        }
+    }
+}
+
+/// Find the visual line holding a byte offset. At a soft wrap the offset
+/// belongs to the following line.
+fn line_of(lines: &[(usize, usize)], offset: usize) -> usize {
+    lines.iter().rposition(|&(start, _)| start <= offset).unwrap_or(0)
+}
+
+impl Widget for TextArea {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        let image = if state.focused {
+            &self.focused
+        } else if state.hovered {
+            &self.hover
+        } else {
+            &self.normal
+        };
+        self.draw_image(canvas, bounds, image);
+
+        let content_rect = self.content_rect(bounds);
+        let line_height = self.line_height();
+        let lines = wrap_lines(&self.text, content_rect.width, |s| self.measure(s));
+
+        // Only draw the lines inside the box
+        let first = (self.scroll_y / line_height.max(1)) as usize;
+        let visible = (content_rect.height / line_height.max(1)) as usize + 2;
+        for (row, &(start, end)) in lines.iter().enumerate().skip(first).take(visible) {
+            let y = content_rect.y + (row as u32 * line_height) as i32 - self.scroll_y as i32;
+            draw_text_sized(
+                canvas,
+                content_rect.x,
+                y,
+                Some(&content_rect),
+                &self.text[start..end],
+                self.style,
+                self.font_size,
+            );
+            if self.spellcheck {
+                self.draw_misspelled(canvas, (start, end), content_rect.x, y, &content_rect);
+            }
+        }
+
+        if state.focused && self.caret_visible {
+            let line = line_of(&lines, self.cursor);
+            let (start, _) = lines[line];
+            let x = content_rect.x + self.measure(&self.text[start..self.cursor]) as i32;
+            let y = content_rect.y + (line as u32 * line_height) as i32 - self.scroll_y as i32;
+            draw_caret(canvas, x, y, line_height, Some(&content_rect), self.caret_color);
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::CharInput { c } => {
+                let modified = self.insert_char(*c);
+                if modified {
+                    self.notify_change();
+                }
+                modified
+            }
+            WidgetEvent::KeyDown { key } => {
+                let modified = match key.without_select() {
+                    KeyCode::Backspace => self.backspace(),
+                    KeyCode::Delete => self.delete(),
+                    KeyCode::Enter => self.insert_char('\n'),
+                    KeyCode::Undo => self.undo_redo(false),
+                    KeyCode::Redo => self.undo_redo(true),
+                    KeyCode::Left => {
+                        self.move_left();
+                        false
+                    }
+                    KeyCode::Right => {
+                        self.move_right();
+                        false
+                    }
+                    KeyCode::Up => {
+                        self.move_vertical(-1);
+                        false
+                    }
+                    KeyCode::Down => {
+                        self.move_vertical(1);
+                        false
+                    }
+                    KeyCode::Home => {
+                        self.move_home();
+                        false
+                    }
+                    KeyCode::End => {
+                        self.move_end();
+                        false
+                    }
+                    KeyCode::Escape => false,
+                    KeyCode::SelectLeft | KeyCode::SelectRight | KeyCode::SelectHome | KeyCode::SelectEnd => false,
+                };
+                if modified {
+                    self.notify_change();
+                }
+                true // Consume all key events when focused
+            }
+            WidgetEvent::MouseDown { x, y } => {
+                self.set_cursor_from_point(*x, *y);
+                true
+            }
+            WidgetEvent::MouseWheel { delta_y } => {
+                let max_scroll = self.max_scroll(self.lines().len());
+                if max_scroll == 0 {
+                    return false;
+                }
+                self.scroll_y = (self.scroll_y as f32 - delta_y).clamp(0.0, max_scroll as f32) as u32;
+                true
+            }
+            WidgetEvent::FocusGained => {
+                self.has_focus = true;
+                self.reset_blink();
+                true
+            }
+            WidgetEvent::FocusLost => {
+                self.has_focus = false;
+                self.caret_visible = false;
+                true
+            }
+            WidgetEvent::Click => true,
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        if !self.has_focus {
+            return false;
+        }
+        self.blink_elapsed += dt;
+        if self.blink_elapsed < CARET_BLINK_INTERVAL {
+            return false;
+        }
+        // Long steps can cover several blinks
+        let blinks = self.blink_elapsed.as_millis() / CARET_BLINK_INTERVAL.as_millis();
+        self.blink_elapsed -= CARET_BLINK_INTERVAL * blinks as u32;
+        if blinks % 2 == 1 {
+            self.caret_visible = !self.caret_visible;
+        }
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_lines() {
+        // One pixel per character
+        let measure = |s: &str| s.chars().count() as u32;
+
+        let text = "the quick brown fox\njumps";
+        let lines: Vec<&str> = wrap_lines(text, 10, measure).iter().map(|&(s, e)| &text[s..e]).collect();
+        assert_eq!(lines, vec!["the quick ", "brown fox", "jumps"]);
+
+        // Words longer than a line are split
+        let text = "abcdefgh";
+        let lines: Vec<&str> = wrap_lines(text, 3, measure).iter().map(|&(s, e)| &text[s..e]).collect();
+        assert_eq!(lines, vec!["abc", "def", "gh"]);
+
+        // Empty paragraphs still get a line, and a soft-wrap offset belongs
+        // to the next line
+        let lines = wrap_lines("a\n\nb", 10, measure);
+        assert_eq!(lines, vec![(0, 1), (2, 2), (3, 4)]);
+        let lines = wrap_lines("abcdef", 3, measure);
+        assert_eq!(line_of(&lines, 3), 1);
+    }
+}
@@ -1,53 +1,116 @@
 use std::any::Any;
-use std::time::Instant;
+use std::ops::Range;
+use std::time::Duration;
 
 use image::RgbImage;
 
 use crate::core::{KeyCode, Rect, Widget, WidgetEvent, WidgetState};
 use crate::graphics::{
-    caret_x_sized, draw_caret, draw_text_sized,
-    line_height_sized, Canvas, TextStyle,
+    caret_x_styled, draw_caret, draw_text_sized, has_glyph, CARET_BLINK_INTERVAL,
+    line_height_styled, line_top_for_baseline, Canvas, NineSlice, TextStyle,
 };
-use crate::skin::types::TextValidation;
+use crate::skin::types::{NumberFormat, TextValidation, VerticalAlign};
+
+use super::edit_history::{EditHistory, EditKind};
+use super::input_mask::InputMask;
+
+/// Character shown for each character of a password.
+pub const PASSWORD_BULLET: char = '\u{2022}';
+
+/// Shown instead of the bullet when the font has no glyph for it.
+const PASSWORD_FALLBACK: char = '*';
+
+/// How long the invalid image flashes after a rejected keystroke.
+const INVALID_FLASH: Duration = Duration::from_millis(300);
+
+/// Selection highlight color unless the skin says otherwise.
+pub const DEFAULT_SELECTION_COLOR: u32 = 0xB3D7FF;
+
+/// Placeholder text color unless the skin says otherwise.
+pub const DEFAULT_PLACEHOLDER_COLOR: u32 = 0x999999;
+
+/// A line of text as a text widget draws it, for exports writing it as text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    /// Top left corner of the line.
+    pub x: i32,
+    pub y: i32,
+    pub font_size: f32,
+    pub color: u32,
+}
 
 /// A text input widget for editable single-line text.
 ///
 /// ## Limitations (v0)
 /// - ASCII input only (characters 32-126)
-/// - No text selection, copy/paste, or IME
+/// - No copy/paste or IME
 /// - No internal scrolling (text is clipped if too long)
-/// - No undo/redo
 pub struct TextInput {
     /// The current text content.
     text: String,
     /// Cursor position (0..=text.len()).
     cursor: usize,
+    /// Other end of the selection; the text between it and the cursor is
+    /// selected.
+    anchor: Option<usize>,
+    /// Whether a mouse drag is extending the selection.
+    dragging: bool,
+    /// Layout bounds, for mapping the pointer to caret positions.
+    bounds: Rect,
     /// Background images for different states.
     normal: RgbImage,
     hover: RgbImage,
     focused: RgbImage,
     /// Optional invalid state background.
     invalid: Option<RgbImage>,
+    /// Border kept while the images are stretched to the widget's size.
+    slice: Option<NineSlice>,
     /// Widget dimensions.
     width: u32,
     height: u32,
     /// Text padding from edges.
     padding: u32,
-    /// Text color.
-    text_color: u32,
+    /// Text color and spacing.
+    style: TextStyle,
+    /// Hint shown while the input is empty and unfocused.
+    placeholder: Option<String>,
+    /// Color and spacing of the placeholder.
+    placeholder_style: TextStyle,
     /// Caret color.
     caret_color: u32,
+    /// Selection highlight color.
+    selection_color: u32,
     /// Custom font size (uses global if None).
     font_size: Option<f32>,
+    /// Vertical text alignment.
+    vertical_align: VerticalAlign,
     /// Maximum number of characters allowed.
     max_length: Option<u32>,
     /// Character validation mode.
     validation: TextValidation,
+    /// Input mask the text is formatted with.
+    mask: Option<InputMask>,
+    /// Number format for the text shown while unfocused.
+    number_format: Option<NumberFormat>,
+    /// Formatted text shown instead of the text while unfocused.
+    display: Option<String>,
+    /// Whether the text is hidden behind bullets.
+    password: bool,
+    /// Whether a password is shown as typed for now.
+    revealed: bool,
+    /// Store key of a bool that reveals the password while true.
+    reveal_binding: Option<String>,
     /// Whether the input is currently marked as invalid.
     is_invalid: bool,
+    /// Whether rejected keystrokes flash the invalid image.
+    flash_invalid: bool,
+    /// Time since a rejected keystroke started a flash.
+    flash_elapsed: Option<Duration>,
     /// Caret blink timing.
     caret_visible: bool,
-    last_blink: Instant,
+    blink_elapsed: Duration,
+    has_focus: bool,
     /// Action to emit on change.
     on_change_action: Option<String>,
     /// Action to emit on submit (Enter).
@@ -56,6 +119,8 @@ pub struct TextInput {
     binding: Option<String>,
     /// Flag indicating the text was modified since last sync.
     dirty: bool,
+    /// Undo/redo steps (Ctrl+Z/Ctrl+Y).
+    history: EditHistory,
 }
 
 impl TextInput {
@@ -71,28 +136,53 @@ impl TextInput {
         Self {
             text: String::new(),
             cursor: 0,
+            anchor: None,
+            dragging: false,
+            bounds: Rect::default(),
             normal,
             hover,
             focused,
             invalid,
+            slice: None,
             width,
             height,
             padding: 4,
-            text_color: 0x000000, // Black text
+            style: TextStyle::with_color(0x000000), // Black text
+            placeholder: None,
+            placeholder_style: TextStyle::with_color(DEFAULT_PLACEHOLDER_COLOR),
             caret_color: 0x000000,
+            selection_color: DEFAULT_SELECTION_COLOR,
             font_size: None,
+            vertical_align: VerticalAlign::Center,
             max_length: None,
             validation: TextValidation::Any,
+            mask: None,
+            number_format: None,
+            display: None,
+            password: false,
+            revealed: false,
+            reveal_binding: None,
             is_invalid: false,
+            flash_invalid: false,
+            flash_elapsed: None,
             caret_visible: true,
-            last_blink: Instant::now(),
+            blink_elapsed: Duration::ZERO,
+            has_focus: false,
             on_change_action: None,
             on_submit_action: None,
             binding: None,
             dirty: false,
+            history: EditHistory::new(),
         }
     }
 
+    /// Stretch the background images to the widget's size, keeping a
+    /// border of them as it is.
+    pub fn with_slice(mut self, slice: NineSlice) -> Self {
+        self.slice = Some(slice);
+        self
+    }
+
     /// Set the text padding.
     pub fn with_padding(mut self, padding: u32) -> Self {
         self.padding = padding;
@@ -101,16 +191,61 @@ impl TextInput {
 
     /// Set the text color.
     pub fn with_text_color(mut self, color: u32) -> Self {
-        self.text_color = color;
+        self.style.color = color;
         self
     }
 
+    /// Set the text style (color, letter spacing, line height and tab stops).
+    pub fn with_text_style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the hint shown while the input is empty and unfocused.
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Set the placeholder's style, usually the text style in a dimmer color.
+    pub fn with_placeholder_style(mut self, style: TextStyle) -> Self {
+        self.placeholder_style = style;
+        self
+    }
+
+    /// Get the placeholder.
+    pub fn placeholder(&self) -> Option<&str> {
+        self.placeholder.as_deref()
+    }
+
+    /// Get the placeholder's style.
+    pub fn placeholder_style(&self) -> &TextStyle {
+        &self.placeholder_style
+    }
+
+    /// Get the text style.
+    pub fn text_style(&self) -> &TextStyle {
+        &self.style
+    }
+
     /// Set the caret color.
     pub fn with_caret_color(mut self, color: u32) -> Self {
         self.caret_color = color;
         self
     }
 
+    /// Set the selection highlight color.
+    pub fn with_selection_color(mut self, color: u32) -> Self {
+        self.selection_color = color;
+        self
+    }
+
+    /// Set the vertical text alignment.
+    pub fn with_vertical_align(mut self, align: VerticalAlign) -> Self {
+        self.vertical_align = align;
+        self
+    }
+
     /// Set the on_change action.
     pub fn with_on_change(mut self, action: String) -> Self {
         self.on_change_action = Some(action);
@@ -141,6 +276,173 @@ impl TextInput {
         self
     }
 
+    /// Briefly show the invalid image when a keystroke is rejected by the
+    /// validation mode, maximum length or mask.
+    pub fn with_flash_invalid(mut self, flash: bool) -> Self {
+        self.flash_invalid = flash;
+        self
+    }
+
+    /// Set an input mask such as `(###) ###-####` (`#` digit, `A` letter,
+    /// `*` letter or digit, anything else literal). The mask replaces the
+    /// validation mode and maximum length.
+    pub fn with_mask(mut self, mask: &str) -> Self {
+        let mask = InputMask::new(mask);
+        self.text = mask.format(&mask.raw(&self.text));
+        self.cursor = self.text.len();
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Show the number formatted while the input isn't focused. The app
+    /// formats it and passes the result to `set_display`.
+    pub fn with_number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = Some(format);
+        self
+    }
+
+    /// Get the number format.
+    pub fn number_format(&self) -> Option<NumberFormat> {
+        self.number_format
+    }
+
+    /// Set the text shown instead of the text while unfocused.
+    /// Returns true if it changed.
+    pub fn set_display(&mut self, display: Option<String>) -> bool {
+        let changed = display != self.display;
+        self.display = display;
+        changed
+    }
+
+    /// Hide the text behind bullets. The real text is still stored.
+    pub fn with_password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Check if the text is hidden behind bullets.
+    pub fn is_password(&self) -> bool {
+        self.password
+    }
+
+    /// Set the store key of a bool that shows the password as typed while
+    /// true, such as one bound to a "show password" checkbox.
+    pub fn with_reveal_binding(mut self, binding: impl Into<String>) -> Self {
+        self.reveal_binding = Some(binding.into());
+        self
+    }
+
+    /// Get the reveal binding key.
+    pub fn reveal_binding(&self) -> Option<&str> {
+        self.reveal_binding.as_deref()
+    }
+
+    /// Show a password as typed, or hide it again. Returns true if it
+    /// changed.
+    pub fn set_revealed(&mut self, revealed: bool) -> bool {
+        let changed = revealed != self.revealed;
+        self.revealed = revealed;
+        changed
+    }
+
+    /// Check if a password is shown as typed.
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    /// Get the text as drawn: bullets for a hidden password.
+    fn shown_text(&self) -> String {
+        if self.password && !self.revealed {
+            let bullet = if has_glyph(PASSWORD_BULLET) { PASSWORD_BULLET } else { PASSWORD_FALLBACK };
+            std::iter::repeat_n(bullet, self.text.chars().count()).collect()
+        } else {
+            self.text.clone()
+        }
+    }
+
+    /// Get the text drawn in the box and its style: formatted while
+    /// unfocused, or the placeholder while empty and unfocused.
+    fn drawn_text<'a>(&'a self, shown: &'a str, focused: bool) -> (&'a str, TextStyle) {
+        match (&self.display, &self.placeholder) {
+            (_, Some(placeholder)) if self.text.is_empty() && !focused => (placeholder, self.placeholder_style),
+            (Some(display), _) if !focused && !self.password => (display, self.style),
+            _ => (shown, self.style),
+        }
+    }
+
+    /// Get the area inside the padding.
+    fn content_rect(&self, bounds: &Rect) -> Rect {
+        Rect::new(
+            bounds.x + self.padding as i32,
+            bounds.y + self.padding as i32,
+            bounds.width.saturating_sub(self.padding * 2),
+            bounds.height.saturating_sub(self.padding * 2),
+        )
+    }
+
+    /// Get the y of the top of the text line.
+    fn text_top(&self, bounds: &Rect) -> i32 {
+        let content_rect = self.content_rect(bounds);
+        let font_size = self.effective_font_size();
+        let text_height = line_height_styled(font_size, &self.style);
+        match self.vertical_align {
+            VerticalAlign::Top => content_rect.y,
+            VerticalAlign::Center => content_rect.y + (content_rect.height as i32 - text_height as i32) / 2,
+            VerticalAlign::Bottom => content_rect.bottom() - text_height as i32,
+            VerticalAlign::Baseline(baseline) => line_top_for_baseline(bounds.y + baseline as i32, font_size),
+        }
+    }
+
+    /// Draw the box of an unfocused input without its text.
+    pub fn draw_background(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
+        let image = match &self.invalid {
+            Some(invalid) if self.is_invalid || self.is_flashing() => invalid,
+            _ if state.hovered => &self.hover,
+            _ => &self.normal,
+        };
+        self.draw_image(canvas, bounds, image);
+    }
+
+    /// Get the text an unfocused input shows in `bounds`, cut where the box
+    /// cuts it off. A hidden password is given as `*`s.
+    pub fn text_lines(&self, bounds: &Rect) -> Vec<TextLine> {
+        let shown = if self.password && !self.revealed {
+            std::iter::repeat_n(PASSWORD_FALLBACK, self.text.chars().count()).collect()
+        } else {
+            self.text.clone()
+        };
+        let (text, style) = self.drawn_text(&shown, false);
+        let content_rect = self.content_rect(bounds);
+        let font_size = self.effective_font_size();
+        let fits = (0..=text.chars().count())
+            .take_while(|&count| caret_x_styled(text, count, font_size, &style) <= content_rect.width)
+            .last()
+            .unwrap_or(0);
+        let text: String = text.chars().take(fits).collect();
+        if text.is_empty() {
+            return Vec::new();
+        }
+        vec![TextLine { text, x: content_rect.x, y: self.text_top(bounds), font_size, color: style.color }]
+    }
+
+    /// Record the state before an edit for undo. Passwords keep no history,
+    /// so earlier ones can't be brought back.
+    fn record_edit(&mut self, kind: EditKind) {
+        if !self.password {
+            self.history.record(kind, &self.text, self.cursor);
+        }
+    }
+
+    /// Get the text for log lines, which never show a password.
+    fn logged_text(&self) -> &str {
+        if self.password { "(password)" } else { &self.text }
+    }
+
+    /// Check if the input has keyboard focus.
+    pub fn has_focus(&self) -> bool {
+        self.has_focus
+    }
+
     /// Set the store binding key.
     pub fn with_binding(mut self, binding: String) -> Self {
         self.binding = Some(binding);
@@ -176,12 +478,41 @@ impl TextInput {
         &self.text
     }
 
-    /// Set the text value.
+    /// Get the value to store: the text, or with a mask just the
+    /// characters typed into its slots.
+    pub fn value(&self) -> String {
+        match &self.mask {
+            Some(mask) => mask.raw(&self.text),
+            None => self.text.clone(),
+        }
+    }
+
+    /// Set the text value. With a mask, the text may be raw or formatted.
+    /// Text set from outside can't be undone back to what was typed before.
     pub fn set_text(&mut self, text: String) {
+        let text = match &self.mask {
+            Some(mask) => mask.format(&mask.raw(&text)),
+            None => text,
+        };
+        if text != self.text {
+            self.history.clear();
+            self.anchor = None;
+        }
         self.text = text;
         self.cursor = self.cursor.min(self.text.len());
     }
 
+    /// Get the byte range of the selected text, if any.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        (anchor != self.cursor).then(|| anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    /// Get the selected text (empty without a selection).
+    pub fn selected_text(&self) -> &str {
+        self.selection().map_or("", |range| &self.text[range])
+    }
+
     /// Mark the input as invalid (e.g., for validation feedback).
     pub fn set_invalid(&mut self, invalid: bool) {
         self.is_invalid = invalid;
@@ -192,6 +523,11 @@ impl TextInput {
         self.is_invalid
     }
 
+    /// Check whether a rejected keystroke is flashing the invalid image.
+    pub fn is_flashing(&self) -> bool {
+        self.flash_elapsed.is_some()
+    }
+
     /// Get the on_change action.
     pub fn on_change_action(&self) -> Option<&str> {
         self.on_change_action.as_deref()
@@ -202,8 +538,9 @@ impl TextInput {
         self.on_submit_action.as_deref()
     }
 
-    /// Check if a character passes validation.
-    fn validate_char(&self, c: char) -> bool {
+    /// Check if a character passes validation when typed at `cursor` in
+    /// `text`.
+    fn validate_char(&self, c: char, text: &str, cursor: usize) -> bool {
         // First check printable ASCII
         if (c as u32) < 32 || (c as u32) > 126 {
             return false;
@@ -212,8 +549,17 @@ impl TextInput {
         match &self.validation {
             TextValidation::Any => true,
             TextValidation::Numeric => c.is_ascii_digit(),
+            TextValidation::Decimal => match c {
+                '.' => !text.contains('.'),
+                '-' => cursor == 0 && !text.starts_with('-'),
+                // Nothing goes before the sign
+                _ => c.is_ascii_digit() && !(cursor == 0 && text.starts_with('-')),
+            },
             TextValidation::Alpha => c.is_ascii_alphabetic(),
             TextValidation::Alphanumeric => c.is_ascii_alphanumeric(),
+            TextValidation::Class { ranges, negated } => {
+                ranges.iter().any(|&(first, last)| (first..=last).contains(&c)) != *negated
+            }
             TextValidation::Pattern(pattern) => {
                 // Pattern is treated as a character whitelist
                 // e.g., "0123456789." allows digits and decimal point
@@ -222,23 +568,118 @@ impl TextInput {
         }
     }
 
-    /// Insert a character at the cursor position.
-    /// Returns true if the text was modified.
-    fn insert_char(&mut self, c: char) -> bool {
-        // Check max length
-        if let Some(max) = self.max_length {
-            if self.text.len() >= max as usize {
-                return false;
+    /// Type a character at the cursor, replacing the selection.
+    /// Returns None if the character is rejected, otherwise whether the text
+    /// was modified.
+    fn insert_char(&mut self, c: char) -> Option<bool> {
+        let (text, cursor) = self.without_selection().unwrap_or_else(|| (self.text.clone(), self.cursor));
+        let (text, cursor) = self.typed(&text, cursor, c)?;
+        self.anchor = None;
+        if text == self.text {
+            // Typing the mask literal under the caret steps over it
+            self.cursor = cursor;
+            self.reset_blink();
+            return Some(false);
+        }
+
+        self.record_edit(EditKind::Insert);
+        self.text = text;
+        self.cursor = cursor;
+        // Typing a space ends the undo step, so undo takes back a word at a time
+        if c.is_whitespace() {
+            self.history.break_group();
+        }
+        self.dirty = true;
+        self.reset_blink();
+        Some(true)
+    }
+
+    /// Get the text and caret after typing `c` at `cursor` in `text`, or
+    /// None if the character is rejected. With a mask the character goes
+    /// into the slot at the cursor, shifting the later slots along, and the
+    /// caret moves past the literals after it.
+    fn typed(&self, text: &str, cursor: usize, c: char) -> Option<(String, usize)> {
+        if let Some(mask) = &self.mask {
+            if cursor < text.len() && mask.literal_at(cursor) == Some(c) {
+                return Some((text.to_string(), cursor + 1));
             }
+            let mut raw = mask.raw(text);
+            let index = mask.slots_before(cursor).min(raw.len());
+            raw.insert(index, c);
+            if !mask.fits(&raw) {
+                return None;
+            }
+            let text = mask.format(&raw);
+            let cursor = mask.skip_literals(mask.slot_position(index) + 1).min(text.len());
+            return Some((text, cursor));
+        }
+
+        if self.max_length.is_some_and(|max| text.len() >= max as usize) || !self.validate_char(c, text, cursor) {
+            return None;
         }
+        let mut text = text.to_string();
+        text.insert(cursor, c);
+        Some((text, cursor + 1))
+    }
 
-        // Validate character
-        if !self.validate_char(c) {
+    /// Get the text and caret with the selected text removed, or None
+    /// without a selection (or if a mask can't hold what is left).
+    fn without_selection(&self) -> Option<(String, usize)> {
+        let range = self.selection()?;
+        let Some(mask) = &self.mask else {
+            let mut text = self.text.clone();
+            text.replace_range(range.clone(), "");
+            return Some((text, range.start));
+        };
+        let mut raw = mask.raw(&self.text);
+        let start = mask.slots_before(range.start).min(raw.len());
+        let end = mask.slots_before(range.end).min(raw.len());
+        raw.replace_range(start..end, "");
+        if !mask.fits(&raw) {
+            return None;
+        }
+        let text = mask.format(&raw);
+        let cursor = mask.slot_position(start).min(text.len());
+        Some((text, cursor))
+    }
+
+    /// Delete the selected text.
+    /// Returns true if the text was modified.
+    fn delete_selection(&mut self) -> bool {
+        let Some((text, cursor)) = self.without_selection() else {
+            return false;
+        };
+        self.record_edit(EditKind::Replace);
+        self.text = text;
+        self.cursor = cursor;
+        self.anchor = None;
+        self.dirty = true;
+        self.reset_blink();
+        true
+    }
+
+    /// Remove the raw character in mask slot `index`, shifting the later
+    /// slots back, and put the caret at `cursor`.
+    /// Returns true if the text was modified.
+    fn remove_masked(&mut self, index: usize, cursor: impl Fn(&InputMask) -> usize) -> bool {
+        let Some(mask) = &self.mask else {
+            return false;
+        };
+        let mut raw = mask.raw(&self.text);
+        if index >= raw.len() {
+            return false;
+        }
+        raw.remove(index);
+        // Characters shifted into slots of another kind can't stay
+        if !mask.fits(&raw) {
             return false;
         }
+        let text = mask.format(&raw);
+        let cursor = cursor(mask).min(text.len());
 
-        self.text.insert(self.cursor, c);
-        self.cursor += 1;
+        self.record_edit(EditKind::Delete);
+        self.text = text;
+        self.cursor = cursor;
         self.dirty = true;
         self.reset_blink();
         true
@@ -247,7 +688,17 @@ impl TextInput {
     /// Delete the character before the cursor (backspace).
     /// Returns true if the text was modified.
     fn backspace(&mut self) -> bool {
+        if self.selection().is_some() {
+            return self.delete_selection();
+        }
+        if let Some(mask) = &self.mask {
+            let Some(index) = mask.slots_before(self.cursor).checked_sub(1) else {
+                return false;
+            };
+            return self.remove_masked(index, |mask| mask.slot_position(index));
+        }
         if self.cursor > 0 {
+            self.record_edit(EditKind::Delete);
             self.cursor -= 1;
             self.text.remove(self.cursor);
             self.dirty = true;
@@ -260,7 +711,16 @@ impl TextInput {
     /// Delete the character at the cursor position.
     /// Returns true if the text was modified.
     fn delete(&mut self) -> bool {
+        if self.selection().is_some() {
+            return self.delete_selection();
+        }
+        if let Some(mask) = &self.mask {
+            let index = mask.slots_before(self.cursor);
+            let cursor = self.cursor;
+            return self.remove_masked(index, |_| cursor);
+        }
         if self.cursor < self.text.len() {
+            self.record_edit(EditKind::Delete);
             self.text.remove(self.cursor);
             self.dirty = true;
             self.reset_blink();
@@ -269,96 +729,103 @@ impl TextInput {
         false
     }
 
-    /// Move cursor left.
-    fn move_left(&mut self) {
+    /// Undo (or redo) the last edit step.
+    /// Returns true if the text was modified.
+    fn undo_redo(&mut self, redo: bool) -> bool {
+        let restored = if redo {
+            self.history.redo(&mut self.text, &mut self.cursor)
+        } else {
+            self.history.undo(&mut self.text, &mut self.cursor)
+        };
+        if restored {
+            self.anchor = None;
+            self.dirty = true;
+            self.reset_blink();
+        }
+        restored
+    }
+
+    /// Start or extend a selection from the caret when `select` is set,
+    /// or drop the selection otherwise.
+    fn select_from_caret(&mut self, select: bool) {
+        if !select {
+            self.anchor = None;
+        } else if self.anchor.is_none() {
+            self.anchor = Some(self.cursor);
+        }
+    }
+
+    /// Move cursor left, stepping back over mask literals. Without `select`
+    /// a selection collapses to its start instead.
+    fn move_left(&mut self, select: bool) {
+        if !select && let Some(range) = self.selection() {
+            self.move_to(range.start, false);
+            return;
+        }
+        self.select_from_caret(select);
         if self.cursor > 0 {
             self.cursor -= 1;
+            if let Some(mask) = &self.mask {
+                while self.cursor > 0 && mask.literal_at(self.cursor - 1).is_some() {
+                    self.cursor -= 1;
+                }
+            }
+            self.history.break_group();
             self.reset_blink();
         }
     }
 
-    /// Move cursor right.
-    fn move_right(&mut self) {
+    /// Move cursor right, stepping over mask literals. Without `select` a
+    /// selection collapses to its end instead.
+    fn move_right(&mut self, select: bool) {
+        if !select && let Some(range) = self.selection() {
+            self.move_to(range.end, false);
+            return;
+        }
+        self.select_from_caret(select);
         if self.cursor < self.text.len() {
             self.cursor += 1;
+            if let Some(mask) = &self.mask {
+                self.cursor = mask.skip_literals(self.cursor).min(self.text.len());
+            }
+            self.history.break_group();
             self.reset_blink();
         }
     }
 
-    /// Move cursor to the beginning.
-    fn move_home(&mut self) {
-        self.cursor = 0;
-        self.reset_blink();
-    }
-
-    /// Move cursor to the end.
-    fn move_end(&mut self) {
-        self.cursor = self.text.len();
+    /// Move cursor to `position`, extending the selection with `select`.
+    fn move_to(&mut self, position: usize, select: bool) {
+        self.select_from_caret(select);
+        self.cursor = position.min(self.text.len());
+        self.history.break_group();
         self.reset_blink();
     }
 
     /// Reset the blink timer and make the caret visible.
     fn reset_blink(&mut self) {
         self.caret_visible = true;
-        self.last_blink = Instant::now();
-    }
-
-    /// Update blink state (call this periodically).
-    #[allow(dead_code)]
-    fn update_blink(&mut self) {
-        let elapsed = self.last_blink.elapsed();
-        if elapsed.as_millis() >= 530 {
-            self.caret_visible = !self.caret_visible;
-            self.last_blink = Instant::now();
-        }
+        self.blink_elapsed = Duration::ZERO;
     }
 
-    /// Set cursor position based on click x position relative to text start.
-    #[allow(dead_code)]
-    fn set_cursor_from_x(&mut self, click_x: i32, text_start_x: i32) {
-        let relative_x = (click_x - text_start_x).max(0) as u32;
+    /// Get the caret position closest to window x coordinate `x`.
+    fn caret_at(&self, x: i32) -> usize {
+        let relative_x = (x - self.bounds.x - self.padding as i32).max(0) as u32;
         let size = self.effective_font_size();
-
-        // Find the character position closest to the click
-        let mut best_pos = 0;
-        let mut best_dist = relative_x;
-
-        for i in 0..=self.text.len() {
-            let char_x = caret_x_sized(&self.text, i, size);
-            let dist = if char_x > relative_x {
-                char_x - relative_x
-            } else {
-                relative_x - char_x
-            };
-            if dist < best_dist {
-                best_dist = dist;
-                best_pos = i;
-            }
-        }
-
-        self.cursor = best_pos;
-        self.reset_blink();
+        let shown = self.shown_text();
+        (0..=self.text.len())
+            .min_by_key(|&i| caret_x_styled(&shown, i, size, &self.style).abs_diff(relative_x))
+            .unwrap_or(0)
     }
 
     fn draw_image(&self, canvas: &mut Canvas, bounds: &Rect, image: &RgbImage) {
-        for (ix, iy, pixel) in image.enumerate_pixels() {
-            let x = bounds.x + ix as i32;
-            let y = bounds.y + iy as i32;
-
-            if x >= bounds.x && x < bounds.right() && y >= bounds.y && y < bounds.bottom() {
-                if x >= 0 && y >= 0 {
-                    let [r, g, b] = pixel.0;
-                    canvas.set_pixel_rgb(x as u32, y as u32, r, g, b);
-                }
-            }
-        }
+        canvas.draw_image(image, bounds, self.slice.as_ref());
     }
 }
 
 impl Widget for TextInput {
     fn draw(&self, canvas: &mut Canvas, bounds: &Rect, state: WidgetState) {
         // Select background image based on state
-        let image = if self.is_invalid && self.invalid.is_some() {
+        let image = if (self.is_invalid || self.is_flashing()) && self.invalid.is_some() {
             self.invalid.as_ref().unwrap()
         } else if state.focused {
             &self.focused
@@ -371,35 +838,45 @@ impl Widget for TextInput {
         // Draw background
         self.draw_image(canvas, bounds, image);
 
-        // Calculate content rect (with padding)
-        let content_rect = Rect::new(
-            bounds.x + self.padding as i32,
-            bounds.y + self.padding as i32,
-            bounds.width.saturating_sub(self.padding * 2),
-            bounds.height.saturating_sub(self.padding * 2),
-        );
-
-        // Get font size (custom or global)
+        let content_rect = self.content_rect(bounds);
         let font_size = self.effective_font_size();
-        let text_height = line_height_sized(font_size);
+        let text_height = line_height_styled(font_size, &self.style);
+        let text_y = self.text_top(bounds);
 
-        // Center text vertically
-        let text_y = content_rect.y + (content_rect.height as i32 - text_height as i32) / 2;
-
-        // Draw text clipped to content rect
+        // Draw text clipped to content rect, formatted while unfocused, or
+        // the placeholder while empty and unfocused
+        let shown = self.shown_text();
+        if state.focused && let Some(range) = self.selection() {
+            let left = content_rect.x + caret_x_styled(&shown, range.start, font_size, &self.style) as i32;
+            let right = content_rect.x + caret_x_styled(&shown, range.end, font_size, &self.style) as i32;
+            let left = left.max(content_rect.x).max(0);
+            let right = right.min(content_rect.right());
+            let top = text_y.max(content_rect.y).max(0);
+            let bottom = (text_y + text_height as i32).min(content_rect.bottom());
+            if right > left && bottom > top {
+                canvas.fill_rect(
+                    left as u32,
+                    top as u32,
+                    (right - left) as u32,
+                    (bottom - top) as u32,
+                    self.selection_color,
+                );
+            }
+        }
+        let (text, style) = self.drawn_text(&shown, state.focused);
         draw_text_sized(
             canvas,
             content_rect.x,
             text_y,
             Some(&content_rect),
-            &self.text,
-            TextStyle::with_color(self.text_color),
+            text,
+            style,
             font_size,
         );
 
         // Draw caret if focused and visible
         if state.focused && self.caret_visible {
-            let caret_offset = caret_x_sized(&self.text, self.cursor, font_size);
+            let caret_offset = caret_x_styled(&shown, self.cursor, font_size, &self.style);
             let caret_x_pos = content_rect.x + caret_offset as i32;
             draw_caret(
                 canvas,
@@ -416,13 +893,22 @@ impl Widget for TextInput {
         (self.width, self.height)
     }
 
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
     fn on_event(&mut self, event: &WidgetEvent) -> bool {
         match event {
             WidgetEvent::CharInput { c } => {
-                let modified = self.insert_char(*c);
+                let Some(modified) = self.insert_char(*c) else {
+                    if self.flash_invalid && !c.is_control() {
+                        self.flash_elapsed = Some(Duration::ZERO);
+                    }
+                    return false;
+                };
                 if modified {
                     if let Some(action) = &self.on_change_action {
-                        println!("TextInput change: {} -> {}", action, self.text);
+                        println!("TextInput change: {} -> {}", action, self.logged_text());
                     }
                 }
                 modified
@@ -431,58 +917,104 @@ impl Widget for TextInput {
                 let modified = match key {
                     KeyCode::Backspace => self.backspace(),
                     KeyCode::Delete => self.delete(),
-                    KeyCode::Left => {
-                        self.move_left();
+                    KeyCode::Undo => self.undo_redo(false),
+                    KeyCode::Redo => self.undo_redo(true),
+                    KeyCode::Left | KeyCode::SelectLeft => {
+                        self.move_left(*key == KeyCode::SelectLeft);
                         false
                     }
-                    KeyCode::Right => {
-                        self.move_right();
+                    KeyCode::Right | KeyCode::SelectRight => {
+                        self.move_right(*key == KeyCode::SelectRight);
                         false
                     }
-                    KeyCode::Home => {
-                        self.move_home();
+                    KeyCode::Home | KeyCode::SelectHome => {
+                        self.move_to(0, *key == KeyCode::SelectHome);
                         false
                     }
-                    KeyCode::End => {
-                        self.move_end();
+                    KeyCode::End | KeyCode::SelectEnd => {
+                        self.move_to(self.text.len(), *key == KeyCode::SelectEnd);
                         false
                     }
+                    KeyCode::Up | KeyCode::Down | KeyCode::Escape => false,
                     KeyCode::Enter => {
                         if let Some(action) = &self.on_submit_action {
-                            println!("TextInput submit: {} -> {}", action, self.text);
+                            println!("TextInput submit: {} -> {}", action, self.logged_text());
                         }
                         false
                     }
                 };
                 if modified {
                     if let Some(action) = &self.on_change_action {
-                        println!("TextInput change: {} -> {}", action, self.text);
+                        println!("TextInput change: {} -> {}", action, self.logged_text());
                     }
                 }
                 true // Consume all key events when focused
             }
             WidgetEvent::FocusGained => {
+                self.has_focus = true;
                 self.reset_blink();
                 true
             }
             WidgetEvent::FocusLost => {
+                self.has_focus = false;
                 self.caret_visible = false;
+                self.anchor = None;
+                self.dragging = false;
                 true
             }
             WidgetEvent::Click => {
                 // Request focus handled externally
                 true
             }
-            WidgetEvent::MouseDown { .. } => {
-                // Set cursor position based on click
-                // We don't have bounds here, so this is handled in Click
-                // For now, just consume the event
+            WidgetEvent::MouseDown { x, .. } => {
+                // Pressing places the caret and starts a drag selection
+                self.cursor = self.caret_at(*x);
+                self.anchor = Some(self.cursor);
+                self.dragging = true;
+                self.history.break_group();
+                self.reset_blink();
+                true
+            }
+            WidgetEvent::MouseMove { x, .. } if self.dragging => {
+                let cursor = self.caret_at(*x);
+                if cursor != self.cursor {
+                    self.cursor = cursor;
+                    self.reset_blink();
+                }
+                true
+            }
+            WidgetEvent::MouseUp { .. } if self.dragging => {
+                self.dragging = false;
                 true
             }
             _ => false,
         }
     }
 
+    fn tick(&mut self, dt: Duration) -> bool {
+        // The flash ends with one more redraw, focused or not
+        let mut redraw = false;
+        if let Some(elapsed) = self.flash_elapsed {
+            let elapsed = elapsed + dt;
+            self.flash_elapsed = (elapsed < INVALID_FLASH).then_some(elapsed);
+            redraw = self.flash_elapsed.is_none();
+        }
+        if !self.has_focus {
+            return redraw;
+        }
+        self.blink_elapsed += dt;
+        if self.blink_elapsed < CARET_BLINK_INTERVAL {
+            return redraw;
+        }
+        // Long steps can cover several blinks
+        let blinks = self.blink_elapsed.as_millis() / CARET_BLINK_INTERVAL.as_millis();
+        self.blink_elapsed -= CARET_BLINK_INTERVAL * blinks as u32;
+        if blinks % 2 == 1 {
+            self.caret_visible = !self.caret_visible;
+        }
+        true
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -491,3 +1023,85 @@ impl Widget for TextInput {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> TextInput {
+        let image = RgbImage::new(4, 4);
+        TextInput::new(image.clone(), image.clone(), image.clone(), Some(image))
+    }
+
+    fn type_text(input: &mut TextInput, text: &str) {
+        for c in text.chars() {
+            input.on_event(&WidgetEvent::CharInput { c });
+        }
+    }
+
+    #[test]
+    fn test_filters_reject_and_flash() {
+        let mut decimal = input().with_validation(TextValidation::Decimal).with_max_length(6);
+        type_text(&mut decimal, "-1a2.5.-3");
+        assert_eq!(decimal.text(), "-12.53");
+
+        let class = TextValidation::Class { ranges: vec![('a', 'f'), ('0', '9')], negated: false };
+        let mut hex = input().with_validation(class).with_flash_invalid(true);
+        type_text(&mut hex, "c0fe");
+        assert!(!hex.is_flashing());
+        type_text(&mut hex, "z");
+        assert_eq!(hex.text(), "c0fe");
+        assert!(hex.is_flashing());
+
+        // The flash ends with one more redraw
+        assert!(!hex.tick(Duration::from_millis(200)));
+        assert!(hex.tick(Duration::from_millis(200)));
+        assert!(!hex.is_flashing());
+    }
+
+    #[test]
+    fn test_selection_is_replaced() {
+        let mut field = input();
+        type_text(&mut field, "hello world");
+        let key = |field: &mut TextInput, key| field.on_event(&WidgetEvent::KeyDown { key });
+        key(&mut field, KeyCode::SelectHome);
+        assert_eq!(field.selected_text(), "hello world");
+
+        // Left collapses the selection to its start
+        key(&mut field, KeyCode::Left);
+        assert_eq!(field.selection(), None);
+        for _ in 0..5 {
+            key(&mut field, KeyCode::SelectRight);
+        }
+        assert_eq!(field.selected_text(), "hello");
+        type_text(&mut field, "J");
+        assert_eq!(field.text(), "J world");
+
+        key(&mut field, KeyCode::SelectEnd);
+        key(&mut field, KeyCode::Delete);
+        assert_eq!(field.text(), "J");
+        key(&mut field, KeyCode::Undo);
+        assert_eq!(field.text(), "J world");
+
+        // Text cleared from outside can't be undone, nor can a password
+        field.set_text(String::new());
+        key(&mut field, KeyCode::Undo);
+        assert_eq!(field.text(), "");
+        let mut pin = input().with_password(true);
+        type_text(&mut pin, "1234");
+        key(&mut pin, KeyCode::Undo);
+        assert_eq!(pin.text(), "1234");
+
+        // A mask keeps its literals when a selection is cut out of it
+        let mut phone = input().with_mask("###-####");
+        type_text(&mut phone, "5551234");
+        key(&mut phone, KeyCode::Home);
+        key(&mut phone, KeyCode::Right);
+        for _ in 0..3 {
+            key(&mut phone, KeyCode::SelectRight);
+        }
+        assert_eq!(phone.selected_text(), "55-1");
+        key(&mut phone, KeyCode::Backspace);
+        assert_eq!(phone.text(), "523-4");
+    }
+}
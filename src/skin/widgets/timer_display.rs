@@ -0,0 +1,267 @@
+//! Timer display widget.
+//!
+//! Shows a number of seconds from the store as a clock reading, such as the
+//! `<name>.elapsed` or `<name>.remaining` key of a timer. A format pattern
+//! picks the fields: `h`, `m` and `s` for hours, minutes and seconds (doubled
+//! to pad them to two digits) and `t` for tenths of a second; separators
+//! such as `:` and `.` are shown as they are. The largest field holds
+//! everything above it, so `mm:ss` shows 90 minutes as `90:00`. The time is
+//! cut to the smallest field shown, or rounded up to it for countdowns,
+//! which then only read zero once they are done. Digits all have the same
+//! width so the reading doesn't shift as it changes.
+
+use std::any::Any;
+
+use crate::core::{Constraints, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{caret_x_styled, draw_text_sized, line_height_sized, Canvas, TextStyle};
+use crate::skin::types::TextAlign;
+
+/// Format pattern unless the skin says otherwise.
+pub const DEFAULT_TIME_FORMAT: &str = "mm:ss";
+
+/// Fields of a format pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    /// Hours, minutes or seconds, padded to `width` digits.
+    Hours(usize),
+    Minutes(usize),
+    Seconds(usize),
+    Tenths,
+}
+
+impl Field {
+    /// Get the length of one unit of the field in tenths of a second.
+    fn unit(self) -> u64 {
+        match self {
+            Field::Hours(_) => 36_000,
+            Field::Minutes(_) => 600,
+            Field::Seconds(_) => 10,
+            Field::Tenths => 1,
+        }
+    }
+}
+
+/// Check that `format` has at least one field.
+pub fn is_time_format(format: &str) -> bool {
+    format.contains(['h', 'm', 's', 't'])
+}
+
+/// Show `seconds` in `format`, cut or rounded up to the smallest field.
+pub fn format_time(seconds: f64, format: &str, round_up: bool) -> String {
+    // Split the pattern into literal text and fields
+    let mut parts: Vec<Result<Field, char>> = Vec::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        let doubled = c != 't' && chars.next_if_eq(&c).is_some();
+        let width = if doubled { 2 } else { 1 };
+        parts.push(match c {
+            'h' => Ok(Field::Hours(width)),
+            'm' => Ok(Field::Minutes(width)),
+            's' => Ok(Field::Seconds(width)),
+            't' => Ok(Field::Tenths),
+            c => Err(c),
+        });
+    }
+    let fields = || parts.iter().filter_map(|part| part.as_ref().ok());
+    let (Some(smallest), Some(largest)) = (fields().map(|f| f.unit()).min(), fields().map(|f| f.unit()).max()) else {
+        return format.to_string();
+    };
+
+    // Whole units of the smallest field, with a little slack for float error
+    let tenths = seconds.max(0.0) * 10.0 / smallest as f64;
+    let units = if round_up { (tenths - 1e-6).ceil() } else { (tenths + 1e-6).floor() };
+    let total = units.max(0.0) as u64 * smallest;
+
+    let mut text = String::new();
+    for part in &parts {
+        match *part {
+            Err(c) => text.push(c),
+            Ok(field) => {
+                // The largest field isn't wrapped, so it holds the time above it
+                let value = total / field.unit();
+                let value = if field.unit() == largest { value } else { value % (wrap(field) / field.unit()) };
+                let width = match field {
+                    Field::Hours(width) | Field::Minutes(width) | Field::Seconds(width) => width,
+                    Field::Tenths => 1,
+                };
+                text.push_str(&format!("{:0width$}", value, width = width));
+            }
+        }
+    }
+    text
+}
+
+/// Get the length of the next larger unit after `field`'s in tenths of a second.
+fn wrap(field: Field) -> u64 {
+    match field {
+        Field::Hours(_) => u64::MAX,
+        Field::Minutes(_) => 36_000,
+        Field::Seconds(_) => 600,
+        Field::Tenths => 10,
+    }
+}
+
+/// A clock reading of a number of seconds.
+pub struct SkinTimerDisplay {
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    format: String,
+    /// Round up to the smallest field instead of cutting.
+    round_up: bool,
+    /// Time shown, in seconds.
+    seconds: f64,
+    /// Color filled in behind the text; transparent when unset.
+    background: Option<u32>,
+    /// Text appearance.
+    text_color: u32,
+    font_size: f32,
+    align: TextAlign,
+    /// Store binding key.
+    binding: Option<String>,
+}
+
+impl SkinTimerDisplay {
+    /// Create a display showing zero as minutes and seconds.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            format: DEFAULT_TIME_FORMAT.to_string(),
+            round_up: false,
+            seconds: 0.0,
+            background: None,
+            text_color: 0x000000,
+            font_size: 16.0,
+            align: TextAlign::Left,
+            binding: None,
+        }
+    }
+
+    /// Set the format pattern.
+    pub fn with_format(mut self, format: impl Into<String>) -> Self {
+        self.format = format.into();
+        self
+    }
+
+    /// Round up to the smallest field, as countdowns do.
+    pub fn with_round_up(mut self, round_up: bool) -> Self {
+        self.round_up = round_up;
+        self
+    }
+
+    /// Set the color filled in behind the text.
+    pub fn with_background(mut self, color: u32) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set the text alignment.
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Get the store binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get the text shown.
+    pub fn text(&self) -> String {
+        format_time(self.seconds, &self.format, self.round_up)
+    }
+
+    /// Set the time shown in seconds. Returns true if it changed.
+    pub fn set_seconds(&mut self, seconds: f64) -> bool {
+        if seconds == self.seconds {
+            return false;
+        }
+        self.seconds = seconds;
+        true
+    }
+
+    fn style(&self) -> TextStyle {
+        TextStyle::with_color(self.text_color).with_tabular_numbers(true)
+    }
+}
+
+impl Widget for SkinTimerDisplay {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        if let Some(background) = self.background
+            && let Some(visible) = bounds.intersect(&Rect::new(0, 0, canvas.width(), canvas.height()))
+        {
+            canvas.fill_rect(visible.x as u32, visible.y as u32, visible.width, visible.height, background);
+        }
+
+        let text = self.text();
+        let style = self.style();
+        let free = bounds.width as i32 - caret_x_styled(&text, usize::MAX, self.font_size, &style) as i32;
+        let x = bounds.x
+            + match self.align {
+                TextAlign::Left => 0,
+                TextAlign::Center => free / 2,
+                TextAlign::Right => free,
+            };
+        let y = bounds.y + (bounds.height as i32 - line_height_sized(self.font_size) as i32) / 2;
+        draw_text_sized(canvas, x, y, Some(bounds), &text, style, self.font_size);
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn intrinsic_size(&self, constraints: Constraints) -> (u32, u32) {
+        let text = self.text();
+        let width = caret_x_styled(&text, usize::MAX, self.font_size, &self.style());
+        constraints.constrain((width, line_height_sized(self.font_size)))
+    }
+
+    fn on_event(&mut self, _event: &WidgetEvent) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(75.4, "mm:ss", false), "01:15");
+        assert_eq!(format_time(5400.0, "mm:ss", false), "90:00");
+        assert_eq!(format_time(3725.0, "h:mm:ss", false), "1:02:05");
+        assert_eq!(format_time(9.05, "s.t", false), "9.0");
+        assert_eq!(format_time(0.3, "m:ss", true), "0:01");
+        assert_eq!(format_time(59.2, "mm:ss", true), "01:00");
+        assert_eq!(format_time(-3.0, "mm:ss", false), "00:00");
+        assert_eq!(format_time(12.0, "--", false), "--");
+        assert!(!is_time_format("--"));
+    }
+}
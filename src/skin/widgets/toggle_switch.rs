@@ -0,0 +1,242 @@
+//! Toggle switch widget.
+//!
+//! An on/off switch drawn from an "off" and an "on" image, with an optional
+//! thumb image. Clicking flips it, and the switch slides between the two
+//! states over a short animation: the "on" image is uncovered from the left
+//! as the thumb moves right, and covered again as it moves back.
+
+use std::any::Any;
+use std::time::Duration;
+
+use image::RgbImage;
+
+use crate::core::{Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::Canvas;
+
+/// How long the switch takes to slide from one side to the other.
+pub const TOGGLE_SLIDE_DURATION: Duration = Duration::from_millis(150);
+
+/// A two-state switch bound to a bool value.
+pub struct ToggleSwitch {
+    /// Images for each state.
+    off: RgbImage,
+    on: RgbImage,
+    /// Thumb drawn at the switch's position, if any.
+    thumb: Option<RgbImage>,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Current state.
+    is_on: bool,
+    /// Drawn position, from 0 (off) to 1 (on).
+    position: f32,
+    /// Set once the switch has been ticked; until then state changes (such
+    /// as the initial store value) show without sliding.
+    ticked: bool,
+    /// Store binding key.
+    binding: Option<String>,
+    /// Action to trigger when the switch is flipped.
+    action: Option<String>,
+    /// Flag indicating the state was modified since last sync.
+    dirty: bool,
+    /// Set when the switch was flipped and the action hasn't been taken yet.
+    changed: bool,
+}
+
+impl ToggleSwitch {
+    /// Create a switch from its off and on images, initially off.
+    pub fn new(width: u32, height: u32, off: RgbImage, on: RgbImage) -> Self {
+        Self {
+            off,
+            on,
+            thumb: None,
+            width,
+            height,
+            is_on: false,
+            position: 0.0,
+            ticked: false,
+            binding: None,
+            action: None,
+            dirty: false,
+            changed: false,
+        }
+    }
+
+    /// Set the thumb image.
+    pub fn with_thumb(mut self, image: RgbImage) -> Self {
+        self.thumb = Some(image);
+        self
+    }
+
+    /// Set the initial state.
+    pub fn with_on(mut self, on: bool) -> Self {
+        self.is_on = on;
+        self.position = if on { 1.0 } else { 0.0 };
+        self
+    }
+
+    /// Set the store binding key.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the switch is flipped.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Check if the switch is on.
+    pub fn is_on(&self) -> bool {
+        self.is_on
+    }
+
+    /// Set the state without marking the switch dirty (e.g. from the store).
+    /// The switch slides to the new state.
+    pub fn set_on(&mut self, on: bool) {
+        self.is_on = on;
+        if !self.ticked {
+            self.position = self.target();
+        }
+    }
+
+    /// Check if the switch is still sliding to its state.
+    pub fn is_sliding(&self) -> bool {
+        self.position != self.target()
+    }
+
+    /// Check if the state has been modified since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the change action if the switch was just flipped.
+    pub fn take_change_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.changed) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Flip the switch, as a click does.
+    pub fn toggle(&mut self) {
+        self.set_on(!self.is_on);
+        self.dirty = true;
+        self.changed = true;
+    }
+
+    fn target(&self) -> f32 {
+        if self.is_on { 1.0 } else { 0.0 }
+    }
+
+    /// Draw the columns `from..to` of an image `x` pixels right of the
+    /// bounds' left edge, centered vertically.
+    fn draw_image(&self, canvas: &mut Canvas, bounds: &Rect, x: u32, image: &RgbImage, from: u32, to: u32) {
+        let y = bounds.y + (bounds.height as i32 - image.height() as i32) / 2;
+        for iy in 0..image.height() {
+            for ix in from..to.min(image.width()) {
+                let px = bounds.x + (x + ix) as i32;
+                let py = y + iy as i32;
+
+                if px >= bounds.x && px < bounds.right() && py >= bounds.y && py < bounds.bottom() && px >= 0 && py >= 0 {
+                    let [r, g, b] = image.get_pixel(ix, iy).0;
+                    canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+                }
+            }
+        }
+    }
+}
+
+impl Widget for ToggleSwitch {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let thumb_width = self.thumb.as_ref().map_or(0, |thumb| thumb.width());
+        let travel = bounds.width.saturating_sub(thumb_width);
+        let thumb_x = (self.position * travel as f32).round() as u32;
+
+        // The "on" image shows left of the thumb's center, "off" right of it
+        let split = thumb_x + thumb_width / 2;
+        self.draw_image(canvas, bounds, 0, &self.on, 0, split);
+        self.draw_image(canvas, bounds, 0, &self.off, split, u32::MAX);
+
+        if let Some(thumb) = &self.thumb {
+            self.draw_image(canvas, bounds, thumb_x, thumb, 0, u32::MAX);
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::Click => {
+                self.toggle();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn tick(&mut self, dt: Duration) -> bool {
+        self.ticked = true;
+        if !self.is_sliding() {
+            return false;
+        }
+        let step = dt.as_secs_f32() / TOGGLE_SLIDE_DURATION.as_secs_f32();
+        let target = self.target();
+        self.position = if self.position < target {
+            (self.position + step).min(target)
+        } else {
+            (self.position - step).max(target)
+        };
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_slides() {
+        let mut switch = ToggleSwitch::new(40, 20, RgbImage::new(40, 20), RgbImage::new(40, 20)).with_action("toggled");
+
+        // Before the first tick, state changes show at once
+        switch.set_on(true);
+        assert!(!switch.is_sliding());
+        switch.set_on(false);
+
+        switch.tick(Duration::ZERO);
+        switch.on_event(&WidgetEvent::Click);
+        assert!(switch.is_on() && switch.is_dirty());
+        assert_eq!(switch.take_change_action().as_deref(), Some("toggled"));
+        assert_eq!(switch.take_change_action(), None);
+
+        // Halfway through the slide, then done
+        assert!(switch.tick(TOGGLE_SLIDE_DURATION / 2));
+        assert!((switch.position - 0.5).abs() < 1e-3);
+        assert!(switch.tick(TOGGLE_SLIDE_DURATION));
+        assert_eq!(switch.position, 1.0);
+        assert!(!switch.tick(TOGGLE_SLIDE_DURATION));
+    }
+}
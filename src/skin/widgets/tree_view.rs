@@ -0,0 +1,538 @@
+//! Tree view widget.
+//!
+//! Shows a hierarchy stored as nested store lists: the top level is
+//! `<tree>.count` entries stored as `<tree>.0.*`, `<tree>.1.*`, ... and each
+//! entry may have its own `children` list (`<tree>.0.children.count`,
+//! `<tree>.0.children.0.*`, ...). Every node is identified by its store path
+//! (e.g. `categories.0.children.2`). Nodes with children get a toggle image
+//! that expands or collapses them; clicking a row (or moving with the arrow
+//! keys while focused) selects it.
+
+use std::any::Any;
+use std::collections::HashSet;
+
+use image::RgbImage;
+
+use crate::core::{KeyCode, Rect, Widget, WidgetEvent, WidgetState};
+use crate::graphics::{draw_text_sized, line_height_sized, Canvas, TextStyle};
+
+/// One node of a tree view, in depth-first order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeRow {
+    /// Store path of the node (`<tree>.<n>`, `<tree>.<n>.children.<m>`, ...).
+    pub path: String,
+    /// Row text, filled from the template.
+    pub text: String,
+    /// Nesting depth; top-level nodes are 0.
+    pub depth: usize,
+    /// Whether the node has children (and so a toggle).
+    pub has_children: bool,
+}
+
+/// A selectable, expandable tree of rows generated from nested store lists.
+pub struct TreeView {
+    /// Row images.
+    row_normal: RgbImage,
+    row_hover: RgbImage,
+    row_selected: RgbImage,
+    /// Toggle images; the toggle width is also the indent per level.
+    expanded_icon: RgbImage,
+    collapsed_icon: RgbImage,
+    /// Widget dimensions.
+    width: u32,
+    height: u32,
+    /// Store list the top-level nodes are generated from.
+    list: String,
+    /// Text template for each row.
+    row_template: String,
+    /// All nodes, depth-first.
+    nodes: Vec<TreeRow>,
+    /// Paths of expanded nodes.
+    expanded: HashSet<String>,
+    /// Indices into `nodes` of the rows currently shown.
+    visible: Vec<usize>,
+    /// Path of the selected node.
+    selected: Option<String>,
+    /// Visible row under the pointer.
+    hovered: Option<usize>,
+    /// Vertical scroll offset in pixels.
+    scroll_y: u32,
+    /// Text appearance.
+    text_color: u32,
+    font_size: Option<f32>,
+    padding: u32,
+    /// Store binding key for the selected path.
+    binding: Option<String>,
+    /// Action to trigger when the selection changes.
+    action: Option<String>,
+    /// Current layout bounds.
+    bounds: Rect,
+    /// Flag indicating the selection changed since last sync.
+    dirty: bool,
+    /// Set when the selection changed and the action hasn't been taken yet.
+    changed: bool,
+}
+
+impl TreeView {
+    /// Create a tree view showing the nodes of a store list.
+    pub fn new(
+        width: u32,
+        height: u32,
+        list: impl Into<String>,
+        row_normal: RgbImage,
+        row_hover: RgbImage,
+        expanded_icon: RgbImage,
+        collapsed_icon: RgbImage,
+    ) -> Self {
+        Self {
+            row_selected: row_hover.clone(),
+            row_normal,
+            row_hover,
+            expanded_icon,
+            collapsed_icon,
+            width,
+            height,
+            list: list.into(),
+            row_template: super::list_view::DEFAULT_ROW_TEMPLATE.to_string(),
+            nodes: Vec::new(),
+            expanded: HashSet::new(),
+            visible: Vec::new(),
+            selected: None,
+            hovered: None,
+            scroll_y: 0,
+            text_color: 0x000000,
+            font_size: None,
+            padding: 6,
+            binding: None,
+            action: None,
+            bounds: Rect::new(0, 0, width, height),
+            dirty: false,
+            changed: false,
+        }
+    }
+
+    /// Set the row image for the selected row.
+    pub fn with_row_selected(mut self, image: RgbImage) -> Self {
+        self.row_selected = image;
+        self
+    }
+
+    /// Set the row text template (`{{.field}}`, `{{#}}` and `{{key}}` placeholders).
+    pub fn with_row_template(mut self, template: impl Into<String>) -> Self {
+        self.row_template = template.into();
+        self
+    }
+
+    /// Set the text color.
+    pub fn with_text_color(mut self, color: u32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    /// Set the font size.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Set the horizontal padding before the toggle and the text.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the store binding key for the selected path.
+    pub fn with_binding(mut self, binding: impl Into<String>) -> Self {
+        self.binding = Some(binding.into());
+        self
+    }
+
+    /// Set the action to trigger when the selection changes.
+    pub fn with_action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Get the store list the top-level nodes are generated from.
+    pub fn list(&self) -> &str {
+        &self.list
+    }
+
+    /// Get the row text template.
+    pub fn row_template(&self) -> &str {
+        &self.row_template
+    }
+
+    /// Get the binding key.
+    pub fn binding(&self) -> Option<&str> {
+        self.binding.as_deref()
+    }
+
+    /// Get all nodes, depth-first.
+    pub fn nodes(&self) -> &[TreeRow] {
+        &self.nodes
+    }
+
+    /// Get the nodes currently shown (ancestors expanded).
+    pub fn visible_rows(&self) -> impl Iterator<Item = &TreeRow> {
+        self.visible.iter().map(|&i| &self.nodes[i])
+    }
+
+    /// Replace the nodes. Expansion of nodes that still exist is kept, and a
+    /// selection that no longer exists is cleared.
+    pub fn set_nodes(&mut self, nodes: Vec<TreeRow>) {
+        if nodes == self.nodes {
+            return;
+        }
+        self.nodes = nodes;
+        let nodes = &self.nodes;
+        self.expanded.retain(|path| nodes.iter().any(|n| &n.path == path && n.has_children));
+        if self.selected.as_ref().is_some_and(|path| !nodes.iter().any(|n| &n.path == path)) {
+            self.selected = None;
+            self.dirty = true;
+        }
+        self.hovered = None;
+        self.update_visible();
+    }
+
+    /// Check whether a node is expanded.
+    pub fn is_expanded(&self, path: &str) -> bool {
+        self.expanded.contains(path)
+    }
+
+    /// Expand or collapse a node. Collapsing a node moves a selection inside
+    /// it to the node itself.
+    pub fn set_expanded(&mut self, path: &str, expanded: bool) {
+        if !self.nodes.iter().any(|n| n.path == path && n.has_children) {
+            return;
+        }
+        if expanded {
+            self.expanded.insert(path.to_string());
+        } else {
+            self.expanded.remove(path);
+            if self.selected.as_deref().is_some_and(|s| is_descendant(s, path)) {
+                self.selected = Some(path.to_string());
+                self.dirty = true;
+                self.changed = true;
+            }
+        }
+        self.hovered = None;
+        self.update_visible();
+    }
+
+    /// Get the selected node's path.
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+
+    /// Get the selected node's text.
+    pub fn selected_text(&self) -> Option<&str> {
+        let path = self.selected.as_deref()?;
+        self.nodes.iter().find(|n| n.path == path).map(|n| n.text.as_str())
+    }
+
+    /// Select a node without marking the tree dirty (e.g. from the store),
+    /// expanding its ancestors so it is shown. Unknown paths clear the selection.
+    pub fn set_selected(&mut self, path: Option<&str>) {
+        let path = path.filter(|p| self.nodes.iter().any(|n| n.path == *p));
+        if path == self.selected.as_deref() {
+            return;
+        }
+        self.selected = path.map(str::to_string);
+        if let Some(path) = path {
+            for node in &self.nodes {
+                if node.has_children && is_descendant(path, &node.path) {
+                    self.expanded.insert(node.path.clone());
+                }
+            }
+            self.update_visible();
+        }
+    }
+
+    /// Check if the selection has changed since last sync.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Clear the dirty flag (call after syncing to store).
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Take the change action if the selection just changed.
+    pub fn take_change_action(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.changed) {
+            self.action.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Recompute the shown rows, skipping descendants of collapsed nodes.
+    fn update_visible(&mut self) {
+        self.visible.clear();
+        let mut hidden_below = None;
+        for (index, node) in self.nodes.iter().enumerate() {
+            if hidden_below.is_some_and(|depth| node.depth > depth) {
+                continue;
+            }
+            hidden_below = None;
+            self.visible.push(index);
+            if node.has_children && !self.expanded.contains(&node.path) {
+                hidden_below = Some(node.depth);
+            }
+        }
+        self.scroll_y = self.scroll_y.min(self.max_scroll());
+    }
+
+    fn row_height(&self) -> u32 {
+        self.row_normal.height().max(1)
+    }
+
+    fn indent(&self) -> u32 {
+        self.collapsed_icon.width()
+    }
+
+    fn max_scroll(&self) -> u32 {
+        (self.visible.len() as u32 * self.row_height()).saturating_sub(self.height)
+    }
+
+    /// Get the visible row under a point.
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.bounds.contains(x, y) {
+            return None;
+        }
+        let index = ((y - self.bounds.y) as u32 + self.scroll_y) / self.row_height();
+        (index < self.visible.len() as u32).then_some(index as usize)
+    }
+
+    /// Get the x offset of a node's toggle from the left edge.
+    fn toggle_x(&self, node: &TreeRow) -> u32 {
+        self.padding + node.depth as u32 * self.indent()
+    }
+
+    /// Get the visible row of the selected node.
+    fn selected_row(&self) -> Option<usize> {
+        let path = self.selected.as_deref()?;
+        self.visible.iter().position(|&i| self.nodes[i].path == path)
+    }
+
+    fn select(&mut self, row: usize) {
+        let path = &self.nodes[self.visible[row]].path;
+        if self.selected.as_ref() != Some(path) {
+            self.selected = Some(path.clone());
+            self.dirty = true;
+            self.changed = true;
+        }
+        self.scroll_to_row(row);
+    }
+
+    /// Scroll just enough to show a row.
+    fn scroll_to_row(&mut self, index: usize) {
+        let top = index as u32 * self.row_height();
+        if top < self.scroll_y {
+            self.scroll_y = top;
+        } else if top + self.row_height() > self.scroll_y + self.height {
+            self.scroll_y = top + self.row_height() - self.height.min(top + self.row_height());
+        }
+    }
+
+    /// Handle Left/Right on the selected row: expand, collapse, or move to
+    /// the first child or the parent.
+    fn expand_or_move(&mut self, row: usize, expand: bool) {
+        let node = &self.nodes[self.visible[row]];
+        let path = node.path.clone();
+        let open = node.has_children && self.expanded.contains(&path);
+        match (expand, node.has_children, open) {
+            (true, true, false) => self.set_expanded(&path, true),
+            (true, true, true) => self.select(row + 1),
+            (false, true, true) => self.set_expanded(&path, false),
+            (false, _, _) => {
+                let parent = (0..row).rev().find(|&r| is_descendant(&path, &self.nodes[self.visible[r]].path));
+                if let Some(parent) = parent {
+                    self.select(parent);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_image(&self, canvas: &mut Canvas, x: i32, y: i32, image: &RgbImage, clip: &Rect) {
+        for (ix, iy, pixel) in image.enumerate_pixels() {
+            let px = x + ix as i32;
+            let py = y + iy as i32;
+
+            if px >= clip.x && px < clip.right() && py >= clip.y && py < clip.bottom() && px >= 0 && py >= 0 {
+                let [r, g, b] = pixel.0;
+                canvas.set_pixel_rgb(px as u32, py as u32, r, g, b);
+            }
+        }
+    }
+}
+
+/// Check whether `path` is inside the subtree of `ancestor`.
+fn is_descendant(path: &str, ancestor: &str) -> bool {
+    path.strip_prefix(ancestor).is_some_and(|rest| rest.starts_with(".children."))
+}
+
+impl Widget for TreeView {
+    fn draw(&self, canvas: &mut Canvas, bounds: &Rect, _state: WidgetState) {
+        let row_height = self.row_height();
+        let size = self.font_size.unwrap_or(16.0);
+        let text_offset = (row_height as i32 - line_height_sized(size) as i32) / 2;
+        let selected = self.selected_row();
+
+        // Only draw the rows inside the box
+        let first = (self.scroll_y / row_height) as usize;
+        let visible = (bounds.height / row_height) as usize + 2;
+        for (row, &index) in self.visible.iter().enumerate().skip(first).take(visible) {
+            let node = &self.nodes[index];
+            let y = bounds.y + (row as u32 * row_height) as i32 - self.scroll_y as i32;
+            let image = if selected == Some(row) {
+                &self.row_selected
+            } else if self.hovered == Some(row) {
+                &self.row_hover
+            } else {
+                &self.row_normal
+            };
+            self.draw_image(canvas, bounds.x, y, image, bounds);
+
+            let toggle_x = bounds.x + self.toggle_x(node) as i32;
+            if node.has_children {
+                let icon = if self.expanded.contains(&node.path) {
+                    &self.expanded_icon
+                } else {
+                    &self.collapsed_icon
+                };
+                let icon_y = y + (row_height as i32 - icon.height() as i32) / 2;
+                self.draw_image(canvas, toggle_x, icon_y, icon, bounds);
+            }
+            draw_text_sized(
+                canvas,
+                toggle_x + (self.indent() + self.padding) as i32,
+                y + text_offset,
+                Some(bounds),
+                &node.text,
+                TextStyle::with_color(self.text_color),
+                size,
+            );
+        }
+    }
+
+    fn preferred_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn on_event(&mut self, event: &WidgetEvent) -> bool {
+        match event {
+            WidgetEvent::MouseMove { x, y } => {
+                self.hovered = self.row_at(*x, *y);
+                true
+            }
+            WidgetEvent::MouseDown { x, y } => {
+                if let Some(row) = self.row_at(*x, *y) {
+                    let node = &self.nodes[self.visible[row]];
+                    let toggle_x = self.bounds.x + self.toggle_x(node) as i32;
+                    if node.has_children && *x >= toggle_x && *x < toggle_x + self.indent() as i32 {
+                        let path = node.path.clone();
+                        let expand = !self.expanded.contains(&path);
+                        self.set_expanded(&path, expand);
+                    } else {
+                        self.select(row);
+                    }
+                }
+                true
+            }
+            WidgetEvent::MouseWheel { delta_y } if self.max_scroll() > 0 => {
+                self.scroll_y = (self.scroll_y as f32 - delta_y).clamp(0.0, self.max_scroll() as f32) as u32;
+                true
+            }
+            WidgetEvent::KeyDown { key } => {
+                let Some(last) = self.visible.len().checked_sub(1) else {
+                    return false;
+                };
+                let current = self.selected_row();
+                match (key.without_select(), current) {
+                    (KeyCode::Up, Some(row)) => self.select(row.saturating_sub(1)),
+                    (KeyCode::Down, Some(row)) => self.select((row + 1).min(last)),
+                    (KeyCode::Up | KeyCode::Down, None) | (KeyCode::Home, _) => self.select(0),
+                    (KeyCode::End, _) => self.select(last),
+                    (KeyCode::Right, Some(row)) => self.expand_or_move(row, true),
+                    (KeyCode::Left, Some(row)) => self.expand_or_move(row, false),
+                    (KeyCode::Enter, Some(row)) => {
+                        let path = self.nodes[self.visible[row]].path.clone();
+                        let expand = !self.expanded.contains(&path);
+                        self.set_expanded(&path, expand);
+                    }
+                    _ => return false,
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_bounds(&mut self, bounds: Rect) {
+        self.bounds = bounds;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(path: &str, depth: usize, has_children: bool) -> TreeRow {
+        TreeRow { path: path.to_string(), text: path.to_string(), depth, has_children }
+    }
+
+    #[test]
+    fn test_expand_and_select() {
+        let mut tree = TreeView::new(
+            100,
+            50,
+            "cats",
+            RgbImage::new(100, 10),
+            RgbImage::new(100, 10),
+            RgbImage::new(8, 8),
+            RgbImage::new(8, 8),
+        );
+        tree.set_bounds(Rect::new(0, 0, 100, 50));
+        tree.set_nodes(vec![
+            row("cats.0", 0, true),
+            row("cats.0.children.0", 1, false),
+            row("cats.0.children.1", 1, false),
+            row("cats.1", 0, false),
+        ]);
+        assert_eq!(tree.visible_rows().count(), 2);
+
+        // Clicking the toggle expands without selecting
+        tree.on_event(&WidgetEvent::MouseDown { x: 8, y: 5 });
+        assert!(tree.is_expanded("cats.0"));
+        assert_eq!(tree.visible_rows().count(), 4);
+        assert_eq!(tree.selected(), None);
+
+        // Clicking the text selects
+        tree.on_event(&WidgetEvent::MouseDown { x: 50, y: 25 });
+        assert_eq!(tree.selected(), Some("cats.0.children.1"));
+        assert!(tree.is_dirty());
+
+        // Left moves to the parent, then collapses it
+        tree.on_event(&WidgetEvent::KeyDown { key: KeyCode::Left });
+        assert_eq!(tree.selected(), Some("cats.0"));
+        tree.on_event(&WidgetEvent::KeyDown { key: KeyCode::Left });
+        assert!(!tree.is_expanded("cats.0"));
+
+        // Selecting from the store expands the ancestors
+        tree.set_selected(Some("cats.0.children.0"));
+        assert!(tree.is_expanded("cats.0"));
+        assert_eq!(tree.selected_text(), Some("cats.0.children.0"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Multi-step form wizards.
+//!
+//! A skin can declare a `wizard` with an ordered list of steps. Each step
+//! names the parts shown while it is active and the validation rules its
+//! store keys must pass before `wizard.next` moves on. Progress is published
+//! to the store:
+//!
+//! - `wizard.step` - current step number, starting at 1
+//! - `wizard.count` - number of steps
+//! - `wizard.step_id` / `wizard.title` - id and title of the current step
+//! - `wizard.error` - message of the first failed rule (empty when valid)
+//! - `wizard.complete` - true once the last step has been submitted
+
+use crate::core::Store;
+
+/// A validation rule kind.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    /// The value must not be empty.
+    Required,
+    /// The value must parse as a number (empty values pass).
+    Number,
+    /// The value must look like an email address (empty values pass).
+    Email,
+    /// The value must have at least this many characters.
+    MinLength(usize),
+    /// The value must be a number no smaller than this.
+    Min(f64),
+    /// The value must be a number no larger than this.
+    Max(f64),
+}
+
+impl Rule {
+    /// Parse a rule such as `"required"`, `"email"` or `"min_length:3"`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (name, arg) = match text.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg.trim())),
+            None => (text.trim(), None),
+        };
+        match (name, arg) {
+            ("required", None) => Some(Rule::Required),
+            ("number", None) => Some(Rule::Number),
+            ("email", None) => Some(Rule::Email),
+            ("min_length", Some(arg)) => arg.parse().ok().map(Rule::MinLength),
+            ("min", Some(arg)) => arg.parse().ok().map(Rule::Min),
+            ("max", Some(arg)) => arg.parse().ok().map(Rule::Max),
+            _ => None,
+        }
+    }
+
+    /// Check a value against the rule.
+    fn accepts(&self, value: &str) -> bool {
+        let value = value.trim();
+        match self {
+            Rule::Required => !value.is_empty(),
+            Rule::Number => value.is_empty() || value.parse::<f64>().is_ok(),
+            Rule::Email => {
+                value.is_empty()
+                    || value.split_once('@').is_some_and(|(user, domain)| {
+                        !user.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+                    })
+            }
+            Rule::MinLength(len) => value.chars().count() >= *len,
+            Rule::Min(min) => value.parse::<f64>().is_ok_and(|n| n >= *min),
+            Rule::Max(max) => value.parse::<f64>().is_ok_and(|n| n <= *max),
+        }
+    }
+
+    /// Default message for a failed rule.
+    fn message(&self, key: &str) -> String {
+        match self {
+            Rule::Required => format!("{} is required", key),
+            Rule::Number => format!("{} must be a number", key),
+            Rule::Email => format!("{} must be an email address", key),
+            Rule::MinLength(len) => format!("{} must be at least {} characters", key, len),
+            Rule::Min(min) => format!("{} must be at least {}", key, min),
+            Rule::Max(max) => format!("{} must be at most {}", key, max),
+        }
+    }
+}
+
+/// A rule applied to a store key.
+#[derive(Debug, Clone)]
+pub struct ValidationRule {
+    /// Store key to check.
+    pub key: String,
+    /// The rule the value must pass.
+    pub rule: Rule,
+    /// Message shown when the rule fails (a default is generated if unset).
+    pub message: Option<String>,
+}
+
+impl ValidationRule {
+    /// Check the rule against the store, returning the failure message.
+    pub fn check(&self, store: &Store) -> Result<(), String> {
+        if self.rule.accepts(&store.get_string(&self.key)) {
+            Ok(())
+        } else {
+            Err(self.message.clone().unwrap_or_else(|| self.rule.message(&self.key)))
+        }
+    }
+}
+
+/// One step of a wizard.
+#[derive(Debug, Clone)]
+pub struct WizardStep {
+    pub id: String,
+    pub title: Option<String>,
+    /// Ids of the parts shown while this step is active.
+    pub parts: Vec<String>,
+    /// Rules checked before leaving the step with `wizard.next`.
+    pub rules: Vec<ValidationRule>,
+}
+
+impl WizardStep {
+    /// Check all rules, returning the message of the first that fails.
+    pub fn validate(&self, store: &Store) -> Result<(), String> {
+        self.rules.iter().try_for_each(|rule| rule.check(store))
+    }
+}
+
+/// A multi-step form declared in the skin.
+#[derive(Debug, Clone)]
+pub struct Wizard {
+    pub steps: Vec<WizardStep>,
+    /// Action dispatched when the last step passes validation.
+    pub on_finish: Option<String>,
+}
+
+impl Wizard {
+    /// Write the progress of step `index` to the `wizard.*` store keys.
+    pub fn publish(&self, index: usize, store: &mut Store) {
+        let Some(step) = self.steps.get(index) else {
+            return;
+        };
+        store.set("wizard.step", (index + 1) as f64);
+        store.set("wizard.count", self.steps.len() as f64);
+        store.set("wizard.step_id", step.id.clone());
+        store.set("wizard.title", step.title.clone().unwrap_or_default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_validation() {
+        let rule = |key: &str, rule: &str| ValidationRule {
+            key: key.to_string(),
+            rule: Rule::parse(rule).unwrap(),
+            message: None,
+        };
+        let step = WizardStep {
+            id: "account".to_string(),
+            title: None,
+            parts: Vec::new(),
+            rules: vec![rule("form.name", "required"), rule("form.email", "email"), rule("form.age", "min:18")],
+        };
+
+        let mut store = Store::new();
+        assert_eq!(step.validate(&store), Err("form.name is required".to_string()));
+
+        store.set("form.name", "Ada");
+        store.set("form.email", "ada@example");
+        assert_eq!(step.validate(&store), Err("form.email must be an email address".to_string()));
+
+        store.set("form.email", "ada@example.com");
+        store.set("form.age", "36");
+        assert_eq!(step.validate(&store), Ok(()));
+        assert!(Rule::parse("min_length").is_none());
+    }
+}
@@ -0,0 +1,203 @@
+//! Command palette overlay.
+//!
+//! A searchable list of action names drawn on top of the view. Typing
+//! filters the list with a fuzzy subsequence match; the embedder feeds it
+//! keys and runs the selected command.
+
+use crate::core::Rect;
+use crate::graphics::{draw_text, line_height, Canvas, TextStyle};
+
+/// Most results shown at once.
+const MAX_VISIBLE: usize = 8;
+
+const BACKGROUND: u32 = 0x202124;
+const BORDER: u32 = 0x5F6368;
+const SELECTED: u32 = 0x3C4043;
+const TEXT: u32 = 0xE8EAED;
+const HINT: u32 = 0x9AA0A6;
+
+/// A fuzzy-searchable list of commands.
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    commands: Vec<String>,
+    /// Indices into `commands` matching the query, best first.
+    matches: Vec<usize>,
+    /// Index into `matches`.
+    selected: usize,
+}
+
+impl CommandPalette {
+    /// Create a closed palette.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the palette with the given commands and an empty query.
+    pub fn open(&mut self, commands: Vec<String>) {
+        self.open = true;
+        self.commands = commands;
+        self.query.clear();
+        self.refresh();
+    }
+
+    /// Close the palette.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Check if the palette is showing.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Get the current search text.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Append a character to the query.
+    pub fn insert_char(&mut self, c: char) {
+        self.query.push(c);
+        self.refresh();
+    }
+
+    /// Remove the last character of the query.
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.refresh();
+    }
+
+    /// Move the selection down, wrapping at the end.
+    pub fn select_next(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + 1) % self.matches.len();
+        }
+    }
+
+    /// Move the selection up, wrapping at the start.
+    pub fn select_previous(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + self.matches.len() - 1) % self.matches.len();
+        }
+    }
+
+    /// Get the selected command, if any match the query.
+    pub fn selected(&self) -> Option<&str> {
+        self.matches
+            .get(self.selected)
+            .map(|&i| self.commands[i].as_str())
+    }
+
+    /// Get the matching commands, best first.
+    pub fn matches(&self) -> impl Iterator<Item = &str> {
+        self.matches.iter().map(|&i| self.commands[i].as_str())
+    }
+
+    fn refresh(&mut self) {
+        let mut scored: Vec<(i32, usize)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, command)| fuzzy_score(&self.query, command).map(|score| (score, i)))
+            .collect();
+        // Best score first, then alphabetical
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| self.commands[a.1].cmp(&self.commands[b.1])));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+
+    /// Draw the palette centered near the top of the canvas.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if !self.open {
+            return;
+        }
+
+        let row_height = line_height() + 8;
+        let rows = self.matches.len().clamp(1, MAX_VISIBLE) as u32;
+        let width = canvas.width().saturating_sub(40).min(480);
+        let height = row_height * (rows + 1) + 8;
+        let x = (canvas.width().saturating_sub(width) / 2) as i32;
+        let y = 40.min(canvas.height().saturating_sub(height) as i32);
+        let panel = Rect::new(x, y, width, height);
+
+        canvas.fill_rect(x as u32, y as u32, width, height, BORDER);
+        canvas.fill_rect(x as u32 + 1, y as u32 + 1, width.saturating_sub(2), height.saturating_sub(2), BACKGROUND);
+
+        // Query line
+        let (prompt, color) = if self.query.is_empty() {
+            ("Type to search actions...".to_string(), HINT)
+        } else {
+            (format!("> {}", self.query), TEXT)
+        };
+        draw_text(canvas, x + 10, y + 6, Some(&panel), &prompt, TextStyle::with_color(color));
+
+        // Results, scrolled so the selection stays visible
+        let first = self.selected.saturating_sub(MAX_VISIBLE - 1);
+        let mut row_y = y + row_height as i32 + 4;
+        if self.matches.is_empty() {
+            draw_text(canvas, x + 10, row_y + 4, Some(&panel), "No matching actions", TextStyle::with_color(HINT));
+        }
+        for (i, &command) in self.matches.iter().enumerate().skip(first).take(MAX_VISIBLE) {
+            if i == self.selected {
+                canvas.fill_rect(x as u32 + 4, row_y as u32, width.saturating_sub(8), row_height, SELECTED);
+            }
+            let name = &self.commands[command];
+            draw_text(canvas, x + 10, row_y + 4, Some(&panel), name, TextStyle::with_color(TEXT));
+            row_y += row_height as i32;
+        }
+    }
+}
+
+/// Score how well `query` fuzzy-matches `candidate` (case-insensitive).
+///
+/// All query characters must appear in order. Consecutive matches and
+/// matches at the start of a word score higher. Returns None if there is
+/// no match; an empty query matches everything with score 0.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.chars().flat_map(char::to_lowercase) {
+        let found = (position..candidate.len()).find(|&i| candidate[i] == q)?;
+        score += 1;
+        if previous == Some(found.wrapping_sub(1)) {
+            score += 5;
+        }
+        if found == 0 || matches!(candidate[found - 1], '.' | '_' | '-' | ' ') {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    // Prefer shorter names among equal matches
+    Some(score * 100 - candidate.len() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_ranking() {
+        let mut palette = CommandPalette::new();
+        palette.open(vec![
+            "export_pdf".to_string(),
+            "print".to_string(),
+            "wizard.previous".to_string(),
+            "profile.switch".to_string(),
+        ]);
+
+        palette.insert_char('p');
+        palette.insert_char('r');
+        assert_eq!(palette.selected(), Some("print"));
+
+        palette.insert_char('s');
+        assert_eq!(palette.matches().collect::<Vec<_>>(), vec!["profile.switch", "wizard.previous"]);
+
+        assert_eq!(fuzzy_score("xyz", "print"), None);
+    }
+}
@@ -0,0 +1,296 @@
+//! Confetti overlay.
+//!
+//! A small particle system for celebrating a finished task or a high score.
+//! A burst throws pieces up from a point; they tumble, fall under gravity and
+//! fade out at the end of their lifetime. Pieces are colored rectangles, or
+//! the skin's sprite images when it names some. The embedder starts bursts,
+//! advances them with the app's clock and draws them on top of the view.
+
+use std::f32::consts::TAU;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use image::RgbImage;
+
+use crate::graphics::Canvas;
+
+/// Pieces in a burst unless the skin says otherwise.
+pub const DEFAULT_CONFETTI_COUNT: u32 = 80;
+
+/// Piece colors unless the skin says otherwise.
+pub const DEFAULT_CONFETTI_COLORS: &[u32] = &[0xE63946, 0xF4A261, 0xE9C46A, 0x2A9D8F, 0x457B9D, 0x9B5DE5];
+
+/// Fraction of its lifetime a piece spends fading out.
+const FADE: f32 = 0.25;
+
+/// Slowest a piece is thrown, as a fraction of the burst speed.
+const MIN_SPEED: f32 = 0.5;
+
+/// Fastest a piece turns, in turns per second.
+const MAX_SPIN: f32 = 3.0;
+
+/// Longest step a tick advances the pieces by. The first tick after a burst
+/// can cover a long idle wait, which would otherwise end it unseen.
+const MAX_STEP: Duration = Duration::from_millis(50);
+
+/// How a burst of confetti looks and moves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfettiConfig {
+    /// Pieces in a burst.
+    pub count: u32,
+    /// Colors of rectangle pieces.
+    pub colors: Vec<u32>,
+    /// Asset keys of sprite images used as pieces instead of rectangles.
+    pub sprites: Vec<String>,
+    /// Downward acceleration in pixels per second squared.
+    pub gravity: f32,
+    /// Fastest a piece is thrown, in pixels per second.
+    pub speed: f32,
+    /// Width in degrees of the cone pieces are thrown in, centered
+    /// straight up; 360 throws them every way.
+    pub spread: f32,
+    /// How long a piece lives.
+    pub lifetime: Duration,
+    /// Width of a rectangle piece in pixels; it is half as tall.
+    pub size: u32,
+}
+
+impl Default for ConfettiConfig {
+    fn default() -> Self {
+        Self {
+            count: DEFAULT_CONFETTI_COUNT,
+            colors: DEFAULT_CONFETTI_COLORS.to_vec(),
+            sprites: Vec::new(),
+            gravity: 600.0,
+            speed: 500.0,
+            spread: 70.0,
+            lifetime: Duration::from_millis(2500),
+            size: 8,
+        }
+    }
+}
+
+/// A piece of confetti in flight.
+#[derive(Debug, Clone)]
+struct Piece {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    /// Turn of the piece around its vertical axis, in radians.
+    angle: f32,
+    /// Radians per second.
+    spin: f32,
+    color: u32,
+    /// Index into the sprites, if the piece is one.
+    sprite: Option<usize>,
+    age: Duration,
+}
+
+/// Bursts of confetti drawn over the view.
+pub struct Confetti {
+    config: ConfettiConfig,
+    /// Images of the configured sprites that were found.
+    sprites: Vec<RgbImage>,
+    pieces: Vec<Piece>,
+    /// State of the xorshift generator scattering the pieces.
+    seed: u64,
+}
+
+impl Default for Confetti {
+    fn default() -> Self {
+        Self::new(ConfettiConfig::default())
+    }
+}
+
+impl Confetti {
+    /// Create a confetti layer with nothing in flight.
+    pub fn new(config: ConfettiConfig) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self {
+            config,
+            sprites: Vec::new(),
+            pieces: Vec::new(),
+            // Xorshift gets stuck at zero
+            seed: nanos | 1,
+        }
+    }
+
+    /// Set the sprite images pieces are drawn with. Black pixels are left
+    /// out, since skin images have no alpha channel.
+    pub fn with_sprites(mut self, sprites: Vec<RgbImage>) -> Self {
+        self.sprites = sprites;
+        self
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &ConfettiConfig {
+        &self.config
+    }
+
+    /// Throw `count` pieces (or the configured number) up from window
+    /// position `origin`.
+    pub fn burst(&mut self, origin: (i32, i32), count: Option<u32>) {
+        let count = count.unwrap_or(self.config.count);
+        let spread = self.config.spread.to_radians();
+        for _ in 0..count {
+            // Straight up, give or take half the spread
+            let direction = -TAU / 4.0 + (self.random() - 0.5) * spread;
+            let speed = self.config.speed * (MIN_SPEED + (1.0 - MIN_SPEED) * self.random());
+            let spin = (self.random() * 2.0 - 1.0) * MAX_SPIN * TAU;
+            let angle = self.random() * TAU;
+            let sprite = (!self.sprites.is_empty()).then(|| self.pick(self.sprites.len()));
+            let color = match self.config.colors.len() {
+                0 => DEFAULT_CONFETTI_COLORS[self.pick(DEFAULT_CONFETTI_COLORS.len())],
+                len => {
+                    let index = self.pick(len);
+                    self.config.colors[index]
+                }
+            };
+            self.pieces.push(Piece {
+                x: origin.0 as f32,
+                y: origin.1 as f32,
+                vx: direction.cos() * speed,
+                vy: direction.sin() * speed,
+                angle,
+                spin,
+                color,
+                sprite,
+                age: Duration::ZERO,
+            });
+        }
+    }
+
+    /// Advance the pieces by `dt`. Returns true if any were in flight, so
+    /// the view is redrawn (once more after the last one is gone).
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        if self.pieces.is_empty() {
+            return false;
+        }
+        let dt = dt.min(MAX_STEP);
+        let secs = dt.as_secs_f32();
+        for piece in &mut self.pieces {
+            piece.vy += self.config.gravity * secs;
+            piece.x += piece.vx * secs;
+            piece.y += piece.vy * secs;
+            piece.angle += piece.spin * secs;
+            piece.age += dt;
+        }
+        let lifetime = self.config.lifetime;
+        self.pieces.retain(|piece| piece.age < lifetime);
+        true
+    }
+
+    /// Check whether any pieces are in flight.
+    pub fn is_active(&self) -> bool {
+        !self.pieces.is_empty()
+    }
+
+    /// Draw the pieces in flight.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        for piece in &self.pieces {
+            let alpha = self.opacity(piece);
+            if alpha == 0 {
+                continue;
+            }
+            match piece.sprite.and_then(|index| self.sprites.get(index)) {
+                Some(sprite) => draw_sprite(canvas, piece, sprite, alpha),
+                None => {
+                    // Turning the piece narrows it, so it seems to flutter
+                    let width = ((self.config.size as f32 * piece.angle.cos().abs()).round() as i32).max(1);
+                    let height = (self.config.size as i32 / 2).max(1);
+                    let left = piece.x.round() as i32 - width / 2;
+                    let top = piece.y.round() as i32 - height / 2;
+                    for y in top.max(0)..top + height {
+                        for x in left.max(0)..left + width {
+                            canvas.blend_pixel(x as u32, y as u32, piece.color, alpha);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get a piece's opacity (0-255), which drops to nothing over the end
+    /// of its lifetime.
+    fn opacity(&self, piece: &Piece) -> u8 {
+        let lifetime = self.config.lifetime.as_secs_f32();
+        if lifetime <= 0.0 {
+            return 0;
+        }
+        let left = 1.0 - piece.age.as_secs_f32() / lifetime;
+        (255.0 * (left / FADE).clamp(0.0, 1.0)) as u8
+    }
+
+    /// Get the next random number in [0, 1).
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Get a random index below `len`.
+    fn pick(&mut self, len: usize) -> usize {
+        ((self.random() * len as f32) as usize).min(len - 1)
+    }
+}
+
+/// Draw a sprite centered on a piece, skipping black pixels.
+fn draw_sprite(canvas: &mut Canvas, piece: &Piece, sprite: &RgbImage, alpha: u8) {
+    let left = piece.x.round() as i32 - sprite.width() as i32 / 2;
+    let top = piece.y.round() as i32 - sprite.height() as i32 / 2;
+    if left >= canvas.width() as i32 || top >= canvas.height() as i32 {
+        return;
+    }
+    for (ix, iy, pixel) in sprite.enumerate_pixels() {
+        let [r, g, b] = pixel.0;
+        let (x, y) = (left + ix as i32, top + iy as i32);
+        if (r, g, b) == (0, 0, 0) || x < 0 || y < 0 {
+            continue;
+        }
+        let color = (r as u32) << 16 | (g as u32) << 8 | b as u32;
+        canvas.blend_pixel(x as u32, y as u32, color, alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_rises_falls_and_ends() {
+        let config = ConfettiConfig { colors: vec![0xFF0000], spread: 0.0, ..Default::default() };
+        let mut confetti = Confetti::new(config);
+        assert!(!confetti.tick(Duration::from_millis(16)));
+
+        confetti.burst((50, 90), Some(10));
+        assert_eq!(confetti.pieces.len(), 10);
+        assert!(confetti.tick(Duration::from_millis(50)));
+        // Thrown straight up, then pulled back down
+        assert!(confetti.pieces.iter().all(|piece| piece.y < 90.0 && (piece.x - 50.0).abs() < 1.0));
+
+        let mut buffer = vec![0xFFFFFF; 100 * 100];
+        let mut canvas = Canvas::new(&mut buffer, 100, 100);
+        confetti.draw(&mut canvas);
+        assert!(buffer.iter().any(|&pixel| pixel != 0xFFFFFF));
+
+        for _ in 0..20 {
+            confetti.tick(Duration::from_millis(50));
+        }
+        assert!(confetti.pieces.iter().all(|piece| piece.vy > 0.0));
+
+        // A long idle wait counts as one short step
+        assert!(confetti.tick(Duration::from_secs(60)));
+        assert!(confetti.is_active());
+
+        // Gone after their lifetime, with one last redraw
+        for _ in 0..28 {
+            assert!(confetti.tick(Duration::from_millis(50)));
+        }
+        assert!(!confetti.is_active());
+        assert!(!confetti.tick(Duration::from_millis(16)));
+    }
+}
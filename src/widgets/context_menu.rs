@@ -0,0 +1,232 @@
+//! Context menu overlay.
+//!
+//! A list of menu entries opened at the pointer by a right-click and drawn
+//! on top of the view. While open it takes the pointer and keyboard: the
+//! embedder feeds it pointer moves, clicks and keys, and runs the action of
+//! the chosen entry. Clicking outside the menu or pressing Escape closes it.
+
+use crate::core::{KeyCode, MenuItem, Rect};
+use crate::graphics::{caret_x_sized, draw_text_sized, line_height_sized, Canvas, TextStyle};
+
+/// Narrowest the menu is drawn.
+const MIN_WIDTH: u32 = 120;
+/// Space around each entry's label.
+const PADDING: u32 = 8;
+const FONT_SIZE: f32 = 14.0;
+
+const BACKGROUND: u32 = 0xF2F2F2;
+const BORDER: u32 = 0x8C8C8C;
+const HIGHLIGHT: u32 = 0x0078D7;
+const TEXT: u32 = 0x000000;
+const HIGHLIGHT_TEXT: u32 = 0xFFFFFF;
+
+/// What a pointer click or key press did to an open menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuResponse {
+    /// The menu is still open.
+    Open,
+    /// The menu closed without choosing an entry.
+    Dismissed,
+    /// An entry was chosen; the menu closed and its action should run.
+    Chosen(String),
+}
+
+/// A popup menu of actions.
+#[derive(Debug, Default)]
+pub struct ContextMenu {
+    items: Vec<MenuItem>,
+    /// Where the menu is drawn, in window coordinates.
+    rect: Rect,
+    /// Entry under the pointer or keyboard highlight.
+    highlighted: Option<usize>,
+    open: bool,
+}
+
+impl ContextMenu {
+    /// Create a closed menu.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the menu with its top-left corner at `position`, moved as
+    /// needed to fit a window of size `bounds`.
+    pub fn open(&mut self, items: Vec<MenuItem>, position: (i32, i32), bounds: (u32, u32)) {
+        let row_height = Self::row_height();
+        let width = items
+            .iter()
+            .map(|item| caret_x_sized(&item.label, usize::MAX, FONT_SIZE) + 2 * PADDING)
+            .max()
+            .unwrap_or(0)
+            .max(MIN_WIDTH);
+        let height = row_height * items.len() as u32 + 2;
+
+        // Open up or left of the pointer where the menu would run off
+        let (mut x, mut y) = position;
+        if x + width as i32 > bounds.0 as i32 {
+            x -= width as i32;
+        }
+        if y + height as i32 > bounds.1 as i32 {
+            y -= height as i32;
+        }
+        self.rect = Rect::new(x.max(0), y.max(0), width, height);
+        self.items = items;
+        self.highlighted = None;
+        self.open = true;
+    }
+
+    /// Close the menu.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Check if the menu is showing.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Get the menu's entries.
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    /// Get where the menu is drawn.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Highlight the entry under the pointer.
+    pub fn pointer_moved(&mut self, x: i32, y: i32) {
+        if let Some(index) = self.item_at(x, y) {
+            self.highlighted = Some(index);
+        }
+    }
+
+    /// Handle a left click at a window position: choose the entry under it,
+    /// or close the menu if the click is outside.
+    pub fn click(&mut self, x: i32, y: i32) -> MenuResponse {
+        if !self.rect.contains(x, y) {
+            self.open = false;
+            return MenuResponse::Dismissed;
+        }
+        match self.item_at(x, y) {
+            Some(index) => self.choose(index),
+            None => MenuResponse::Open,
+        }
+    }
+
+    /// Handle a key: Up/Down move the highlight, Enter chooses it and
+    /// Escape closes the menu.
+    pub fn key(&mut self, key: KeyCode) -> MenuResponse {
+        let last = self.items.len().saturating_sub(1);
+        match key {
+            KeyCode::Up => {
+                self.highlighted = Some(self.highlighted.map_or(last, |i| i.saturating_sub(1)));
+            }
+            KeyCode::Down => {
+                self.highlighted = Some(self.highlighted.map_or(0, |i| (i + 1).min(last)));
+            }
+            KeyCode::Enter => {
+                if let Some(index) = self.highlighted {
+                    return self.choose(index);
+                }
+            }
+            KeyCode::Escape => {
+                self.open = false;
+                return MenuResponse::Dismissed;
+            }
+            _ => {}
+        }
+        MenuResponse::Open
+    }
+
+    fn choose(&mut self, index: usize) -> MenuResponse {
+        self.open = false;
+        match self.items.get(index) {
+            Some(item) => MenuResponse::Chosen(item.action.clone()),
+            None => MenuResponse::Dismissed,
+        }
+    }
+
+    fn row_height() -> u32 {
+        line_height_sized(FONT_SIZE) + PADDING
+    }
+
+    /// Index of the entry at a window position.
+    fn item_at(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.rect.contains(x, y) {
+            return None;
+        }
+        let row = (y - self.rect.y - 1).max(0) as u32 / Self::row_height();
+        (row < self.items.len() as u32).then_some(row as usize)
+    }
+
+    /// Draw the menu if it is open.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if !self.open {
+            return;
+        }
+
+        let Rect { x, y, width, height } = self.rect;
+        canvas.fill_rect(x as u32, y as u32, width, height, BORDER);
+        canvas.fill_rect(x as u32 + 1, y as u32 + 1, width.saturating_sub(2), height.saturating_sub(2), BACKGROUND);
+
+        let row_height = Self::row_height();
+        for (i, item) in self.items.iter().enumerate() {
+            let row_y = y + 1 + (i as u32 * row_height) as i32;
+            let color = if self.highlighted == Some(i) {
+                canvas.fill_rect(x as u32 + 1, row_y as u32, width.saturating_sub(2), row_height, HIGHLIGHT);
+                HIGHLIGHT_TEXT
+            } else {
+                TEXT
+            };
+            draw_text_sized(
+                canvas,
+                x + PADDING as i32,
+                row_y + (PADDING / 2) as i32,
+                Some(&self.rect),
+                &item.label,
+                TextStyle::with_color(color),
+                FONT_SIZE,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::graphics::init_font;
+
+    fn item(label: &str, action: &str) -> MenuItem {
+        MenuItem {
+            label: label.to_string(),
+            action: action.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_menu_chooses_and_dismisses() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        let _ = init_font(&path, 16.0);
+
+        let mut menu = ContextMenu::new();
+        menu.open(vec![item("Copy", "copy"), item("Paste", "paste")], (790, 10), (800, 600));
+        // Flipped left of the pointer to stay in the window
+        assert!(menu.rect().right() <= 800);
+
+        let rect = menu.rect();
+        let second_row = rect.y + 1 + ContextMenu::row_height() as i32 + 2;
+        assert_eq!(menu.click(rect.x + 5, second_row), MenuResponse::Chosen("paste".to_string()));
+        assert!(!menu.is_open());
+
+        menu.open(vec![item("Copy", "copy")], (10, 10), (800, 600));
+        assert_eq!(menu.key(KeyCode::Down), MenuResponse::Open);
+        assert_eq!(menu.key(KeyCode::Enter), MenuResponse::Chosen("copy".to_string()));
+
+        menu.open(vec![item("Copy", "copy")], (10, 10), (800, 600));
+        assert_eq!(menu.click(500, 500), MenuResponse::Dismissed);
+        assert!(!menu.is_open());
+    }
+}
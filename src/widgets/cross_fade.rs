@@ -0,0 +1,117 @@
+//! Cross-fade overlay.
+//!
+//! Softens a swap of the whole screen, such as switching themes or showing
+//! the lock screen. The embedder renders the old screen offscreen just before
+//! the swap; the overlay then draws that frame over the new screen, fading it
+//! out over the transition's duration.
+
+use std::time::Duration;
+
+use crate::graphics::Canvas;
+
+/// Longest step a tick advances the fade by, so the first tick after a swap
+/// that follows a long idle wait doesn't end the fade unseen.
+const MAX_STEP: Duration = Duration::from_millis(50);
+
+/// A fade from a captured frame to whatever is drawn beneath it.
+#[derive(Debug, Default)]
+pub struct CrossFade {
+    /// The old screen, 0x00RRGGBB pixels row by row.
+    frame: Vec<u32>,
+    width: u32,
+    height: u32,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl CrossFade {
+    /// Create a cross-fade with nothing to fade.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start fading out `frame`, a `width` x `height` capture of the old
+    /// screen, over `duration`. A zero duration or a frame of the wrong size
+    /// shows the new screen at once.
+    pub fn start(&mut self, frame: Vec<u32>, width: u32, height: u32, duration: Duration) {
+        if duration.is_zero() || frame.len() != width as usize * height as usize {
+            self.frame.clear();
+            return;
+        }
+        self.frame = frame;
+        self.width = width;
+        self.height = height;
+        self.duration = duration;
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Advance the fade by `dt`. Returns true if it was running, so the view
+    /// is redrawn (once more after it ends).
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        if self.frame.is_empty() {
+            return false;
+        }
+        self.elapsed += dt.min(MAX_STEP);
+        if self.elapsed >= self.duration {
+            self.frame = Vec::new();
+        }
+        true
+    }
+
+    /// Check whether the old screen is still showing through.
+    pub fn is_active(&self) -> bool {
+        !self.frame.is_empty()
+    }
+
+    /// Get the old screen's opacity (0-255).
+    fn opacity(&self) -> u8 {
+        let left = 1.0 - self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        (255.0 * left.clamp(0.0, 1.0)) as u8
+    }
+
+    /// Draw the old screen over the canvas at its current opacity.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if self.frame.is_empty() {
+            return;
+        }
+        let alpha = self.opacity();
+        let width = self.width.min(canvas.width());
+        for y in 0..self.height.min(canvas.height()) {
+            let row = (y * self.width) as usize;
+            for x in 0..width {
+                canvas.blend_pixel(x, y, self.frame[row + x as usize], alpha);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_old_frame_fades_out() {
+        let mut fade = CrossFade::new();
+        fade.start(vec![0xFFFFFF; 4], 2, 2, Duration::from_millis(200));
+        assert!(fade.is_active());
+
+        // Halfway through (a long wait counting as one step), the old white
+        // frame is half over the new black one
+        fade.tick(Duration::from_millis(50));
+        fade.tick(Duration::from_secs(60));
+        let mut buffer = vec![0x000000; 3 * 3];
+        let mut canvas = Canvas::new(&mut buffer, 3, 3);
+        fade.draw(&mut canvas);
+        assert_eq!(canvas.get_pixel(1, 1), Some(0x7F7F7F));
+        assert_eq!(canvas.get_pixel(2, 2), Some(0x000000));
+
+        fade.tick(Duration::from_millis(50));
+        assert!(fade.tick(Duration::from_millis(50)));
+        assert!(!fade.is_active());
+        assert!(!fade.tick(Duration::from_millis(16)));
+
+        // No duration, no fade
+        fade.start(vec![0xFFFFFF; 4], 2, 2, Duration::ZERO);
+        assert!(!fade.is_active());
+    }
+}
@@ -0,0 +1,364 @@
+//! Developer console overlay.
+//!
+//! A scrollback log with a command line, drawn along the bottom of the view,
+//! and a panel of watched store keys whose values update live and light up
+//! when they change. The console only collects input and shows output; the
+//! embedder parses and runs the commands.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::core::{Rect, Store, StoreChange};
+use crate::graphics::{draw_text, line_height, Canvas, TextStyle};
+
+/// Lines kept in the scrollback.
+const MAX_LINES: usize = 200;
+
+/// Lines shown at once.
+const VISIBLE_LINES: usize = 12;
+
+const BACKGROUND: u32 = 0x101214;
+const BORDER: u32 = 0x3C4043;
+const TEXT: u32 = 0xE8EAED;
+const PROMPT: u32 = 0x8AB4F8;
+const ERROR: u32 = 0xF28B82;
+const CHANGE: u32 = 0xFDD663;
+const STATUS: u32 = 0xF28B82;
+
+/// How long a changed watch stays highlighted.
+const HIGHLIGHT: Duration = Duration::from_millis(1500);
+
+/// A watched store key, or all keys below a prefix (`settings.*`).
+#[derive(Debug)]
+struct Watch {
+    pattern: String,
+    /// Pause action dispatch when a matching key changes.
+    breakpoint: bool,
+    /// Matching keys and their values as JSON, sorted by key.
+    values: Vec<(String, String)>,
+    /// When the values last changed.
+    changed_at: Option<Instant>,
+    /// Whether `values` has been read from the store yet.
+    loaded: bool,
+}
+
+impl Watch {
+    fn matches(&self, key: &str) -> bool {
+        match self.pattern.strip_suffix(".*") {
+            Some(prefix) => key.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.')),
+            None => key == self.pattern,
+        }
+    }
+}
+
+/// How a console line is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Regular output.
+    Info,
+    /// An echoed command.
+    Command,
+    /// A store change.
+    Change,
+    /// A failure.
+    Error,
+}
+
+/// A command line and output log for debugging apps.
+#[derive(Debug, Default)]
+pub struct DevConsole {
+    open: bool,
+    input: String,
+    lines: VecDeque<(LineKind, String)>,
+    /// Previously entered commands, oldest first.
+    history: Vec<String>,
+    /// Index into `history` while browsing it with Up/Down.
+    history_index: Option<usize>,
+    watches: Vec<Watch>,
+    /// Shown above the command line, e.g. while paused at a breakpoint.
+    status: Option<String>,
+}
+
+impl DevConsole {
+    /// Create a closed, empty console.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show or hide the console.
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// Show the console.
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    /// Hide the console.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Check if the console is showing.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Append a character to the command line.
+    pub fn insert_char(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    /// Remove the last character of the command line.
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    /// Take the entered command, echoing it to the log and history.
+    /// Returns None if the line is blank.
+    pub fn submit(&mut self) -> Option<String> {
+        let command = std::mem::take(&mut self.input).trim().to_string();
+        self.history_index = None;
+        if command.is_empty() {
+            return None;
+        }
+        self.push(LineKind::Command, format!("> {}", command));
+        if self.history.last() != Some(&command) {
+            self.history.push(command.clone());
+        }
+        Some(command)
+    }
+
+    /// Replace the command line with the previous entry in the history.
+    pub fn history_previous(&mut self) {
+        let index = match self.history_index {
+            Some(0) => return,
+            Some(i) => i - 1,
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+        self.history_index = Some(index);
+        self.input = self.history[index].clone();
+    }
+
+    /// Replace the command line with the next entry in the history.
+    pub fn history_next(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.input = self.history[index + 1].clone();
+        } else {
+            self.history_index = None;
+            self.input.clear();
+        }
+    }
+
+    /// Add a line to the log.
+    pub fn push(&mut self, kind: LineKind, text: impl Into<String>) {
+        if self.lines.len() == MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back((kind, text.into()));
+    }
+
+    /// Add an output line.
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push(LineKind::Info, text);
+    }
+
+    /// Add an error line.
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push(LineKind::Error, text);
+    }
+
+    /// Clear the log.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Set or clear the status line.
+    pub fn set_status(&mut self, status: Option<String>) {
+        self.status = status;
+    }
+
+    /// Watch a store key, or every key below a prefix with `prefix.*`.
+    /// With `breakpoint`, changes to the matching keys pause dispatch (see
+    /// `breakpoint_hit`). Watching an existing pattern updates its breakpoint.
+    pub fn watch(&mut self, pattern: &str, breakpoint: bool) {
+        match self.watches.iter_mut().find(|watch| watch.pattern == pattern) {
+            Some(watch) => watch.breakpoint = breakpoint,
+            None => self.watches.push(Watch {
+                pattern: pattern.to_string(),
+                breakpoint,
+                values: Vec::new(),
+                changed_at: None,
+                loaded: false,
+            }),
+        }
+    }
+
+    /// Stop watching a pattern. Returns true if it was watched.
+    pub fn unwatch(&mut self, pattern: &str) -> bool {
+        let count = self.watches.len();
+        self.watches.retain(|watch| watch.pattern != pattern);
+        self.watches.len() != count
+    }
+
+    /// Get the watched patterns and whether each is a breakpoint.
+    pub fn watches(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.watches.iter().map(|watch| (watch.pattern.as_str(), watch.breakpoint))
+    }
+
+    /// Refresh the watched values from the store. Returns true if the panel
+    /// needs redrawing: a value changed or a highlight ran out.
+    pub fn update_watches(&mut self, store: &Store) -> bool {
+        let mut redraw = false;
+        for watch in &mut self.watches {
+            let mut values: Vec<(String, String)> = store
+                .keys()
+                .filter(|key| watch.matches(key))
+                .filter_map(|key| Some((key.clone(), store.get(key)?.to_json().to_string())))
+                .collect();
+            values.sort();
+            if values != watch.values {
+                // Values found when the watch is added aren't a change
+                if watch.loaded {
+                    watch.changed_at = Some(Instant::now());
+                }
+                watch.values = values;
+                redraw = true;
+            } else if watch.changed_at.is_some_and(|at| at.elapsed() >= HIGHLIGHT) {
+                watch.changed_at = None;
+                redraw = true;
+            }
+            watch.loaded = true;
+        }
+        redraw && self.open
+    }
+
+    /// Check if any watch is highlighted, so the embedder keeps polling
+    /// until the highlight fades.
+    pub fn has_highlights(&self) -> bool {
+        self.open && self.watches.iter().any(|watch| watch.changed_at.is_some())
+    }
+
+    /// Find the first changed key covered by a breakpoint.
+    pub fn breakpoint_hit<'a>(&self, changes: &'a [StoreChange]) -> Option<&'a str> {
+        changes
+            .iter()
+            .map(StoreChange::key)
+            .find(|key| self.watches.iter().any(|watch| watch.breakpoint && watch.matches(key)))
+    }
+
+    /// Get the logged lines, oldest first.
+    pub fn lines(&self) -> impl Iterator<Item = &(LineKind, String)> {
+        self.lines.iter()
+    }
+
+    /// Draw the console along the bottom of the canvas.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if !self.open {
+            return;
+        }
+
+        let row_height = line_height() + 2;
+        let height = (row_height * (VISIBLE_LINES as u32 + 1) + 8).min(canvas.height());
+        let width = canvas.width();
+        let y = (canvas.height() - height) as i32;
+        let panel = Rect::new(0, y, width, height);
+
+        canvas.fill_rect(0, y as u32, width, height, BACKGROUND);
+        canvas.fill_rect(0, y as u32, width, 1, BORDER);
+
+        // Watches take the right third of the panel
+        let log_width = if self.watches.is_empty() { width } else { width * 2 / 3 };
+        let log = Rect::new(0, y, log_width, height);
+        if !self.watches.is_empty() {
+            self.draw_watches(canvas, Rect::new(log_width as i32, y, width - log_width, height), row_height);
+        }
+
+        let mut row_y = y + 4;
+        let first = self.lines.len().saturating_sub(VISIBLE_LINES);
+        for (kind, text) in self.lines.iter().skip(first) {
+            let color = match kind {
+                LineKind::Info => TEXT,
+                LineKind::Command => PROMPT,
+                LineKind::Change => CHANGE,
+                LineKind::Error => ERROR,
+            };
+            draw_text(canvas, 8, row_y, Some(&log), text, TextStyle::with_color(color));
+            row_y += row_height as i32;
+        }
+
+        let prompt_y = y + height as i32 - row_height as i32 - 2;
+        canvas.fill_rect(0, prompt_y as u32 - 2, width, 1, BORDER);
+        let prompt = format!("$ {}_", self.input);
+        draw_text(canvas, 8, prompt_y, Some(&panel), &prompt, TextStyle::with_color(PROMPT));
+        if let Some(status) = &self.status {
+            let status_x = (width / 2) as i32;
+            draw_text(canvas, status_x, prompt_y, Some(&panel), status, TextStyle::with_color(STATUS));
+        }
+    }
+
+    /// Draw the watched values, one key per row.
+    fn draw_watches(&self, canvas: &mut Canvas, area: Rect, row_height: u32) {
+        canvas.fill_rect(area.x as u32, area.y as u32, 1, area.height, BORDER);
+        let bottom = area.bottom() - row_height as i32 - 4;
+        let mut row_y = area.y + 4;
+        for watch in &self.watches {
+            let color = if watch.changed_at.is_some() { CHANGE } else { TEXT };
+            let marker = if watch.breakpoint { "* " } else { "" };
+            let rows: Vec<String> = if watch.values.is_empty() {
+                vec![format!("{}{}: (unset)", marker, watch.pattern)]
+            } else {
+                watch
+                    .values
+                    .iter()
+                    .map(|(key, value)| format!("{}{} = {}", marker, key, value))
+                    .collect()
+            };
+            for row in rows {
+                if row_y > bottom {
+                    return;
+                }
+                draw_text(canvas, area.x + 8, row_y, Some(&area), &row, TextStyle::with_color(color));
+                row_y += row_height as i32;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watches_and_breakpoints() {
+        let mut console = DevConsole::new();
+        console.open();
+        let mut store = Store::new();
+        store.set("cart.total", 5.0);
+
+        console.watch("cart.*", false);
+        console.watch("user.name", true);
+        assert!(console.update_watches(&store));
+        // Initial values are not highlighted as changes
+        assert!(!console.has_highlights());
+
+        let before = store.snapshot();
+        store.set("cart.total", 7.5);
+        store.set("user.name", "Ada");
+        assert!(console.update_watches(&store));
+        assert!(console.has_highlights());
+
+        let changes = Store::diff(&before, &store.snapshot());
+        assert_eq!(console.breakpoint_hit(&changes), Some("user.name"));
+        console.watch("user.name", false);
+        assert_eq!(console.breakpoint_hit(&changes), None);
+        assert!(console.unwatch("cart.*"));
+        assert!(!console.unwatch("cart.*"));
+    }
+}
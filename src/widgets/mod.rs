@@ -1,9 +1,21 @@
 mod button;
+mod command_palette;
+mod confetti;
 mod container;
+mod context_menu;
+mod cross_fade;
+mod dev_console;
 mod image;
+mod tooltip;
 mod vscroll_container;
 
 pub use button::Button;
+pub use command_palette::{fuzzy_score, CommandPalette};
+pub use confetti::{Confetti, ConfettiConfig, DEFAULT_CONFETTI_COLORS, DEFAULT_CONFETTI_COUNT};
 pub use container::Container;
+pub use context_menu::{ContextMenu, MenuResponse};
+pub use cross_fade::CrossFade;
+pub use dev_console::{DevConsole, LineKind};
 pub use image::ImageWidget;
+pub use tooltip::{Tooltip, TOOLTIP_DELAY};
 pub use vscroll_container::VScrollContainer;
@@ -0,0 +1,157 @@
+//! Tooltip overlay.
+//!
+//! Shows a node's tooltip text in a bubble near the pointer once the pointer
+//! has rested over the node for `TOOLTIP_DELAY`. The embedder reports pointer
+//! moves and presses, advances it with the app's clock and draws it on top of
+//! the view.
+
+use std::time::Duration;
+
+use crate::core::{NodeId, Rect, UiTree};
+use crate::graphics::{Canvas, TextLayout, TextStyle};
+
+/// How long the pointer must rest over a node before its tooltip shows.
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+
+/// Distance from the pointer to the bubble's top-left corner.
+const POINTER_OFFSET: i32 = 16;
+/// Space between the bubble's border and its text.
+const PADDING: u32 = 6;
+const FONT_SIZE: f32 = 14.0;
+
+const BACKGROUND: u32 = 0xFFFFE1;
+const BORDER: u32 = 0x767676;
+const TEXT: u32 = 0x000000;
+
+/// The tooltip for the node under the pointer.
+#[derive(Debug, Default)]
+pub struct Tooltip {
+    /// Node whose tooltip is pending or showing, and its text.
+    target: Option<(NodeId, String)>,
+    /// Pointer position in window coordinates.
+    pointer: (i32, i32),
+    /// Time the pointer has rested over the target.
+    elapsed: Duration,
+    /// Set when a press dismissed the tooltip; it stays hidden until the
+    /// pointer moves to another node.
+    dismissed: bool,
+}
+
+impl Tooltip {
+    /// Create a tooltip with nothing to show.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report a pointer move to window position `pointer`. Moving onto a
+    /// different node restarts the delay.
+    pub fn pointer_moved(&mut self, tree: &UiTree, pointer: (i32, i32)) {
+        self.pointer = pointer;
+        let hovered = tree.hovered();
+        if self.target.as_ref().map(|(id, _)| *id) == hovered && hovered.is_some() {
+            return;
+        }
+        self.target = hovered.and_then(|id| Some((id, tree.tooltip_for(id)?.to_string())));
+        self.elapsed = Duration::ZERO;
+        self.dismissed = false;
+    }
+
+    /// Hide the tooltip until the pointer moves to another node, as a
+    /// click or key press does.
+    pub fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Advance the delay by `dt`. Returns true if the tooltip just appeared.
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        if self.target.is_none() || self.dismissed {
+            return false;
+        }
+        let was_visible = self.is_visible();
+        self.elapsed = self.elapsed.saturating_add(dt);
+        !was_visible && self.is_visible()
+    }
+
+    /// Check if the tooltip is showing.
+    pub fn is_visible(&self) -> bool {
+        self.target.is_some() && !self.dismissed && self.elapsed >= TOOLTIP_DELAY
+    }
+
+    /// Time left until the tooltip shows, if one is waiting to.
+    pub fn time_until_visible(&self) -> Option<Duration> {
+        (self.target.is_some() && !self.dismissed && !self.is_visible()).then(|| TOOLTIP_DELAY - self.elapsed)
+    }
+
+    /// Get the text of the pending or showing tooltip.
+    pub fn text(&self) -> Option<&str> {
+        self.target.as_ref().map(|(_, text)| text.as_str())
+    }
+
+    /// Draw the bubble below and right of the pointer, kept on the canvas.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if !self.is_visible() {
+            return;
+        }
+        let Some(text) = self.text() else {
+            return;
+        };
+
+        let layout = TextLayout::styled(text, FONT_SIZE, TextStyle::with_color(TEXT), None);
+        let width = layout.width() + 2 * PADDING;
+        let height = layout.height() + 2 * PADDING;
+
+        // Flip to the other side of the pointer where the bubble would run off
+        let (px, py) = self.pointer;
+        let mut x = px + POINTER_OFFSET;
+        if x + width as i32 > canvas.width() as i32 {
+            x = px - width as i32;
+        }
+        let mut y = py + POINTER_OFFSET;
+        if y + height as i32 > canvas.height() as i32 {
+            y = py - height as i32;
+        }
+        let x = x.max(0);
+        let y = y.max(0);
+
+        let bubble = Rect::new(x, y, width, height);
+        canvas.fill_rect(x as u32, y as u32, width, height, BORDER);
+        canvas.fill_rect(x as u32 + 1, y as u32 + 1, width.saturating_sub(2), height.saturating_sub(2), BACKGROUND);
+        layout.draw(canvas, x + PADDING as i32, y + PADDING as i32, Some(&bubble));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::Container;
+
+    #[test]
+    fn test_tooltip_shows_after_delay() {
+        let mut tree = UiTree::new();
+        let root = tree.add(Container::transparent(100, 100), None);
+        let button = tree.add(Container::transparent(10, 10), Some(root));
+        tree.set_tooltip(root, "Root");
+        tree.set_tooltip(button, "Save the file");
+
+        let mut tooltip = Tooltip::new();
+        tree.set_hovered(Some(button));
+        tooltip.pointer_moved(&tree, (5, 5));
+        assert_eq!(tooltip.text(), Some("Save the file"));
+        assert!(!tooltip.tick(TOOLTIP_DELAY / 2));
+        assert_eq!(tooltip.time_until_visible(), Some(TOOLTIP_DELAY / 2));
+
+        // Moving within the node doesn't restart the delay
+        tooltip.pointer_moved(&tree, (6, 6));
+        assert!(tooltip.tick(TOOLTIP_DELAY / 2));
+        assert!(tooltip.is_visible());
+
+        // A press hides it until the pointer moves to another node
+        tooltip.dismiss();
+        assert!(!tooltip.is_visible());
+        assert_eq!(tooltip.time_until_visible(), None);
+        tree.set_hovered(Some(root));
+        tooltip.pointer_moved(&tree, (50, 50));
+        assert_eq!(tooltip.text(), Some("Root"));
+        assert!(!tooltip.is_visible());
+    }
+}
@@ -0,0 +1,968 @@
+//! Golden-image tests for widget rendering.
+//!
+//! Every case draws one widget through the headless harness in each
+//! interaction state (normal, hovered, pressed, focused) at 1x and 2x size,
+//! and compares the picture pixel for pixel with
+//! `tests/golden/<case>_<state>@<scale>x.png`. Widget images are generated
+//! swatches, and text uses the demo app's font.
+//!
+//! After an intended rendering change, bless the new pictures with
+//!
+//! ```text
+//! CRIX_BLESS=1 cargo test --test golden
+//! ```
+//!
+//! and review the changed PNGs before committing them. A failing case
+//! writes what it drew next to the golden as `<name>.actual.png`.
+//!
+//! `SkinVideo` is behind the `video` feature and isn't covered here.
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Once;
+
+use crix::core::{App, KeyCode, Length, MenuItem, Rect, Store, UiTree, View, Widget, WidgetEvent};
+use crix::skin::widgets::{
+    AnimatedSprite, CanvasWidget, Checkbox, ColorPicker, CustomPaint, DirectoryPicker, Dropdown, FilePathInput,
+    FilePicker, Gauge, GroupBox, ImageView, Indicator, Knob, Link, ListView, MenuBar, Plot, ProgressBar, ScrollView,
+    SevenSegment, SkinButton, SkinImage, SkinTimerDisplay, SkinVScroll, Slider, Spinner, Stack, StaticText, StatusBar,
+    StepProgress, TabContainer, TextArea, TextInput, ToggleSwitch, TreeRow, TreeView,
+};
+use crix::widgets::{Button, Container, ImageWidget};
+use crix::{Canvas, GaugeStyle, Headless, NineSlice, PlotStyle, TextAlign};
+use image::{Rgb, RgbImage};
+
+/// Environment variable that makes the suite write goldens instead of comparing.
+const BLESS_VAR: &str = "CRIX_BLESS";
+
+/// Scales each case is drawn at.
+const SCALES: [u32; 2] = [1, 2];
+
+/// Margin around the widget, so drawing outside its bounds shows up.
+const MARGIN: u32 = 4;
+
+static FONT: Once = Once::new();
+
+fn init_font() {
+    FONT.call_once(|| {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("demo_app.crix/skin/font.ttf");
+        crix::init_font(&path, 16.0).expect("demo font");
+    });
+}
+
+/// A solid image with a darker one pixel border, so its placement shows.
+fn swatch(width: u32, height: u32, color: [u8; 3]) -> RgbImage {
+    let border = color.map(|c| c / 2);
+    RgbImage::from_fn(width, height, |x, y| {
+        if x == 0 || y == 0 || x + 1 == width || y + 1 == height {
+            Rgb(border)
+        } else {
+            Rgb(color)
+        }
+    })
+}
+
+const GRAY: [u8; 3] = [200, 200, 200];
+const BLUE: [u8; 3] = [150, 190, 240];
+const DARK_BLUE: [u8; 3] = [70, 110, 180];
+const YELLOW: [u8; 3] = [250, 230, 140];
+const GREEN: [u8; 3] = [120, 200, 120];
+const RED: [u8; 3] = [230, 120, 120];
+
+/// Interaction states each case is drawn in.
+#[derive(Clone, Copy)]
+enum State {
+    Normal,
+    Hovered,
+    Pressed,
+    Focused,
+}
+
+impl State {
+    const ALL: [State; 4] = [State::Normal, State::Hovered, State::Pressed, State::Focused];
+
+    fn name(self) -> &'static str {
+        match self {
+            State::Normal => "normal",
+            State::Hovered => "hovered",
+            State::Pressed => "pressed",
+            State::Focused => "focused",
+        }
+    }
+}
+
+/// One widget setup to draw.
+struct Case {
+    name: &'static str,
+    /// Widget size at 1x.
+    size: (u32, u32),
+    /// Extra room below the widget at 1x, for overlays such as open lists.
+    overflow: u32,
+    /// Build the widget at a scale.
+    build: fn(u32) -> Box<dyn Widget>,
+}
+
+/// Draws a tree; the harness renders it like a window would.
+struct TreeApp {
+    tree: UiTree,
+}
+
+impl App for TreeApp {
+    fn view(&self) -> &dyn View {
+        &self.tree
+    }
+}
+
+fn render(case: &Case, state: State, scale: u32) -> RgbImage {
+    let (width, height) = (case.size.0 * scale, case.size.1 * scale);
+    let margin = MARGIN * scale;
+
+    let mut tree = UiTree::new();
+    let canvas_height = height + (case.overflow * scale) + margin * 2;
+    let root = tree.add(Container::new(width + margin * 2, canvas_height).with_background(0xFFFFFF), None);
+    tree.set_bounds(root, Rect::new(0, 0, width + margin * 2, canvas_height));
+    let id = tree.add_boxed((case.build)(scale), Some(root));
+    let bounds = Rect::new(margin as i32, margin as i32, width, height);
+    tree.set_bounds(id, bounds);
+
+    let center = (bounds.x + width as i32 / 2, bounds.y + height as i32 / 8);
+    let widget = tree.get_mut(id).expect("widget").widget_mut();
+    match state {
+        State::Normal => {}
+        State::Hovered => {
+            widget.on_event(&WidgetEvent::MouseMove { x: center.0, y: center.1 });
+            tree.set_hovered(Some(id));
+        }
+        State::Pressed => {
+            tree.set_hovered(Some(id));
+            tree.set_pressed(Some(id));
+        }
+        State::Focused => {
+            widget.on_event(&WidgetEvent::FocusGained);
+            tree.set_focused(Some(id));
+        }
+    }
+
+    Headless::new(TreeApp { tree }).render()
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+/// Compare a picture with its golden, or write it when blessing.
+/// Returns a description of the mismatch, if any.
+fn check(name: &str, actual: &RgbImage, bless: bool) -> Option<String> {
+    let path = golden_dir().join(format!("{}.png", name));
+    let actual_path = golden_dir().join(format!("{}.actual.png", name));
+    if bless {
+        actual.save(&path).expect("write golden");
+        let _ = std::fs::remove_file(actual_path);
+        return None;
+    }
+
+    let mismatch = match image::open(&path) {
+        Ok(expected) => {
+            let expected = expected.to_rgb8();
+            if expected.dimensions() != actual.dimensions() {
+                Some(format!("{}: size {:?}, golden is {:?}", name, actual.dimensions(), expected.dimensions()))
+            } else {
+                let differing = expected.pixels().zip(actual.pixels()).filter(|(a, b)| a != b).count();
+                (differing > 0).then(|| format!("{}: {} pixels differ", name, differing))
+            }
+        }
+        Err(_) => Some(format!("{}: no golden at {}", name, path.display())),
+    };
+    if mismatch.is_some() {
+        actual.save(&actual_path).expect("write actual");
+    } else {
+        let _ = std::fs::remove_file(actual_path);
+    }
+    mismatch
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case {
+            name: "skin_button",
+            size: (80, 30),
+            overflow: 0,
+            build: |s| Box::new(SkinButton::new(swatch(80 * s, 30 * s, GRAY), swatch(80 * s, 30 * s, BLUE), swatch(80 * s, 30 * s, DARK_BLUE), None)),
+        },
+        Case {
+            name: "icon_button",
+            size: (30, 30),
+            overflow: 0,
+            build: |s| Box::new(SkinButton::from_strip(&icon_strip(s), 4, false, None)),
+        },
+        Case {
+            name: "icon_button_disabled",
+            size: (30, 30),
+            overflow: 0,
+            build: |s| {
+                let mut button = SkinButton::from_strip(&icon_strip(s), 4, false, None);
+                button.set_disabled(true);
+                Box::new(button)
+            },
+        },
+        Case {
+            name: "text_input",
+            size: (140, 28),
+            overflow: 0,
+            build: |s| {
+                let mut input = TextInput::new(swatch(140 * s, 28 * s, GRAY), swatch(140 * s, 28 * s, BLUE), swatch(140 * s, 28 * s, YELLOW), None)
+                    .with_font_size(16.0 * s as f32)
+                    .with_padding(4 * s);
+                input.set_text("Hello, crix".to_string());
+                Box::new(input)
+            },
+        },
+        Case {
+            name: "text_input_sliced",
+            size: (140, 28),
+            overflow: 0,
+            build: |s| {
+                // A 12x12 background stretched to the input, keeping 4-pixel corners
+                let mut input = TextInput::new(swatch(12 * s, 12 * s, GRAY), swatch(12 * s, 12 * s, BLUE), swatch(12 * s, 12 * s, YELLOW), None)
+                    .with_slice(NineSlice::uniform(4 * s))
+                    .with_font_size(16.0 * s as f32)
+                    .with_padding(4 * s);
+                input.set_text("Hello, crix".to_string());
+                Box::new(input)
+            },
+        },
+        Case {
+            name: "text_input_invalid",
+            size: (140, 28),
+            overflow: 0,
+            build: |s| {
+                let mut input = TextInput::new(
+                    swatch(140 * s, 28 * s, GRAY),
+                    swatch(140 * s, 28 * s, BLUE),
+                    swatch(140 * s, 28 * s, YELLOW),
+                    Some(swatch(140 * s, 28 * s, RED)),
+                )
+                .with_font_size(16.0 * s as f32);
+                input.set_text("12a".to_string());
+                input.set_invalid(true);
+                Box::new(input)
+            },
+        },
+        Case {
+            name: "text_input_placeholder",
+            size: (140, 28),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    TextInput::new(swatch(140 * s, 28 * s, GRAY), swatch(140 * s, 28 * s, BLUE), swatch(140 * s, 28 * s, YELLOW), None)
+                        .with_font_size(16.0 * s as f32)
+                        .with_padding(4 * s)
+                        .with_placeholder("Search"),
+                )
+            },
+        },
+        Case {
+            name: "text_input_selection",
+            size: (140, 28),
+            overflow: 0,
+            build: |s| {
+                let mut input = TextInput::new(swatch(140 * s, 28 * s, GRAY), swatch(140 * s, 28 * s, BLUE), swatch(140 * s, 28 * s, YELLOW), None)
+                    .with_font_size(16.0 * s as f32)
+                    .with_padding(4 * s);
+                input.set_text("Hello, crix".to_string());
+                input.on_event(&WidgetEvent::KeyDown { key: KeyCode::End });
+                for _ in 0..4 {
+                    input.on_event(&WidgetEvent::KeyDown { key: KeyCode::SelectLeft });
+                }
+                Box::new(input)
+            },
+        },
+        Case {
+            name: "text_input_password",
+            size: (140, 28),
+            overflow: 0,
+            build: |s| {
+                let mut input = TextInput::new(swatch(140 * s, 28 * s, GRAY), swatch(140 * s, 28 * s, BLUE), swatch(140 * s, 28 * s, YELLOW), None)
+                    .with_font_size(16.0 * s as f32)
+                    .with_padding(4 * s)
+                    .with_password(true);
+                input.set_text("hunter2".to_string());
+                Box::new(input)
+            },
+        },
+        Case {
+            name: "file_path_input",
+            size: (140, 28),
+            overflow: 0,
+            build: |s| {
+                let input = TextInput::new(swatch(110 * s, 28 * s, GRAY), swatch(110 * s, 28 * s, BLUE), swatch(110 * s, 28 * s, YELLOW), None)
+                    .with_font_size(16.0 * s as f32)
+                    .with_padding(4 * s);
+                let mut path_input = FilePathInput::new(input, swatch(30 * s, 28 * s, GREEN), swatch(30 * s, 28 * s, BLUE));
+                path_input.input_mut().set_text("/tmp/report.pdf".to_string());
+                Box::new(path_input)
+            },
+        },
+        Case {
+            name: "indicator",
+            size: (24, 24),
+            overflow: 0,
+            build: |s| {
+                let mut indicator = Indicator::new(24 * s, 24 * s, swatch(24 * s, 24 * s, GRAY), swatch(24 * s, 24 * s, GREEN));
+                indicator.set_lit(true);
+                Box::new(indicator)
+            },
+        },
+        Case {
+            name: "text_area",
+            size: (140, 60),
+            overflow: 0,
+            build: |s| {
+                let mut area = TextArea::new(swatch(140 * s, 60 * s, GRAY), swatch(140 * s, 60 * s, BLUE), swatch(140 * s, 60 * s, YELLOW))
+                    .with_font_size(16.0 * s as f32)
+                    .with_padding(4 * s);
+                area.set_text("First line\nSecond line".to_string());
+                Box::new(area)
+            },
+        },
+        Case {
+            name: "static_text",
+            size: (140, 24),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    StaticText::new("Centered label".to_string())
+                        .with_font_size(16.0 * s as f32)
+                        .with_text_color(0x204080)
+                        .with_text_align(TextAlign::Center),
+                )
+            },
+        },
+        Case {
+            name: "checkbox",
+            size: (120, 20),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    Checkbox::new(swatch(20 * s, 20 * s, GRAY), swatch(20 * s, 20 * s, GREEN))
+                        .with_unchecked_hover(swatch(20 * s, 20 * s, BLUE))
+                        .with_label("Remember me")
+                        .with_font_size(16.0 * s as f32)
+                        .with_padding(6 * s),
+                )
+            },
+        },
+        Case {
+            name: "checkbox_checked",
+            size: (120, 20),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    Checkbox::new(swatch(20 * s, 20 * s, GRAY), swatch(20 * s, 20 * s, GREEN))
+                        .with_checked_hover(swatch(20 * s, 20 * s, DARK_BLUE))
+                        .with_label("Remember me")
+                        .with_font_size(16.0 * s as f32)
+                        .with_padding(6 * s)
+                        .with_checked(true),
+                )
+            },
+        },
+        Case {
+            name: "slider",
+            size: (120, 20),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    Slider::new(120 * s, 20 * s, swatch(120 * s, 6 * s, GRAY), swatch(12 * s, 20 * s, DARK_BLUE))
+                        .with_thumb_hover(swatch(12 * s, 20 * s, BLUE))
+                        .with_thumb_pressed(swatch(12 * s, 20 * s, YELLOW))
+                        .with_range(0.0, 10.0)
+                        .with_value(6.0),
+                )
+            },
+        },
+        Case {
+            name: "dropdown",
+            size: (120, 24),
+            overflow: 0,
+            build: |s| Box::new(dropdown(s)),
+        },
+        Case {
+            name: "dropdown_open",
+            size: (120, 24),
+            overflow: 72,
+            build: |s| {
+                let mut dropdown = dropdown(s);
+                dropdown.on_event(&WidgetEvent::Click);
+                Box::new(dropdown)
+            },
+        },
+        Case {
+            name: "list_view",
+            size: (120, 60),
+            overflow: 0,
+            build: |s| {
+                let mut list = ListView::new(120 * s, 60 * s, "items", swatch(120 * s, 20 * s, GRAY), swatch(120 * s, 20 * s, BLUE))
+                    .with_row_selected(swatch(120 * s, 20 * s, YELLOW))
+                    .with_font_size(16.0 * s as f32)
+                    .with_padding(6 * s);
+                list.set_rows((1..=4).map(|i| format!("Row {}", i)).collect());
+                list.set_selected(Some(1));
+                Box::new(list)
+            },
+        },
+        Case {
+            name: "progress_bar",
+            size: (120, 12),
+            overflow: 0,
+            build: |s| Box::new(ProgressBar::new(120 * s, 12 * s, swatch(120 * s, 12 * s, GRAY), swatch(120 * s, 12 * s, GREEN)).with_value(40.0)),
+        },
+        Case {
+            name: "plot_line",
+            size: (120, 60),
+            overflow: 0,
+            build: |s| {
+                let mut plot = Plot::new(120 * s, 60 * s)
+                    .with_series("temps", None)
+                    .with_series("rain", None)
+                    .with_thickness(2 * s)
+                    .with_background(0xFFFFFF);
+                plot.set_values(0, vec![3.0, 8.0, 6.0, f64::NAN, 9.0, 12.0]);
+                plot.set_values(1, vec![-2.0, 1.0, 4.0, 2.0, 0.0, 5.0]);
+                Box::new(plot)
+            },
+        },
+        Case {
+            name: "plot_bar",
+            size: (120, 60),
+            overflow: 0,
+            build: |s| {
+                let mut plot = Plot::new(120 * s, 60 * s)
+                    .with_style(PlotStyle::Bar)
+                    .with_series("sales", None)
+                    .with_series("costs", None)
+                    .with_y_range(None, Some(20.0))
+                    .with_background(0xFFFFFF);
+                plot.set_values(0, vec![12.0, 18.0, 7.0, 15.0]);
+                plot.set_values(1, vec![9.0, 11.0, -4.0, 10.0]);
+                Box::new(plot)
+            },
+        },
+        Case {
+            name: "seven_segment",
+            size: (70, 20),
+            overflow: 0,
+            build: |s| Box::new(SevenSegment::new(70 * s, 20 * s, segment_atlas(s), "0123456789: ").with_spacing(2 * s).with_text("12:45")),
+        },
+        Case {
+            name: "group_box",
+            size: (140, 70),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    GroupBox::new(140 * s, 70 * s)
+                        .with_title("Shipping")
+                        .with_border(0x466EB4, s)
+                        .with_background(0xF0F0F0)
+                        .with_font_size(14.0 * s as f32),
+                )
+            },
+        },
+        Case {
+            name: "group_box_frame",
+            size: (140, 70),
+            overflow: 0,
+            build: |s| {
+                // A bevelled 12x12 frame with 4-pixel corners
+                let frame = RgbImage::from_fn(12 * s, 12 * s, |x, y| {
+                    let edge = x.min(y).min(12 * s - 1 - x).min(12 * s - 1 - y);
+                    match edge / s {
+                        0 => Rgb(DARK_BLUE),
+                        1..=3 => Rgb(BLUE),
+                        _ => Rgb([240, 240, 240]),
+                    }
+                });
+                Box::new(
+                    GroupBox::new(140 * s, 70 * s)
+                        .with_title("Payment")
+                        .with_frame(frame, 4 * s)
+                        .with_align(TextAlign::Center)
+                        .with_font_size(14.0 * s as f32),
+                )
+            },
+        },
+        Case {
+            name: "status_bar",
+            size: (200, 20),
+            overflow: 0,
+            build: |s| {
+                let mut bar = StatusBar::new(200 * s, 20 * s)
+                    .with_segment("status", Length::Fill, TextAlign::Left, None)
+                    .with_segment("progress", Length::Pixels(50 * s), TextAlign::Center, None)
+                    .with_segment("error", Length::Percent(0.3), TextAlign::Right, Some(0xC00000))
+                    .with_background(0xE8E8E8)
+                    .with_font_size(12.0 * s as f32)
+                    .with_padding(4 * s);
+                bar.set_text(0, "Saved report.txt");
+                bar.set_text(1, "42%");
+                bar.set_text(2, "Disk almost full");
+                Box::new(bar)
+            },
+        },
+        Case {
+            name: "timer_display",
+            size: (120, 30),
+            overflow: 0,
+            build: |s| {
+                let mut display = SkinTimerDisplay::new(120 * s, 30 * s)
+                    .with_format("h:mm:ss.t")
+                    .with_align(TextAlign::Center)
+                    .with_background(0x202020)
+                    .with_text_color(0x50E070)
+                    .with_font_size(20.0 * s as f32);
+                display.set_seconds(3725.46);
+                Box::new(display)
+            },
+        },
+        Case {
+            name: "step_progress",
+            size: (120, 16),
+            overflow: 0,
+            build: |s| {
+                let mut progress = StepProgress::new(
+                    120 * s,
+                    16 * s,
+                    swatch(16 * s, 16 * s, GREEN),
+                    swatch(16 * s, 16 * s, BLUE),
+                    swatch(16 * s, 16 * s, GRAY),
+                );
+                progress.set_progress(1, 4);
+                Box::new(progress)
+            },
+        },
+        Case {
+            name: "tab_container",
+            size: (180, 80),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    TabContainer::new(180 * s, 80 * s, swatch(60 * s, 20 * s, GRAY), swatch(60 * s, 20 * s, BLUE))
+                        .with_tab_active(swatch(60 * s, 20 * s, YELLOW))
+                        .with_panel(swatch(180 * s, 60 * s, [240, 240, 240]))
+                        .with_tab("general", "General")
+                        .with_tab("advanced", "More")
+                        .with_font_size(14.0 * s as f32),
+                )
+            },
+        },
+        Case {
+            name: "scroll_view",
+            size: (100, 60),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    ScrollView::new(100 * s, 60 * s, swatch(10 * s, 20 * s, GRAY), swatch(10 * s, 24 * s, DARK_BLUE))
+                        .with_content_height(150 * s),
+                )
+            },
+        },
+        Case {
+            name: "skin_vscroll",
+            size: (100, 60),
+            overflow: 0,
+            build: |s| {
+                let mut scroll = SkinVScroll::new(100 * s, 60 * s, swatch(10 * s, 20 * s, GRAY), swatch(10 * s, 24 * s, DARK_BLUE));
+                scroll.set_child(Box::new(SkinImage::new(swatch(90 * s, 150 * s, BLUE))));
+                Box::new(scroll)
+            },
+        },
+        Case {
+            name: "skin_image",
+            size: (40, 30),
+            overflow: 0,
+            build: |s| Box::new(SkinImage::new(swatch(40 * s, 30 * s, GREEN))),
+        },
+        Case {
+            name: "directory_picker",
+            size: (160, 28),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    DirectoryPicker::new(
+                        swatch(160 * s, 28 * s, GRAY),
+                        swatch(160 * s, 28 * s, BLUE),
+                        swatch(28 * s, 28 * s, DARK_BLUE),
+                        swatch(28 * s, 28 * s, YELLOW),
+                    )
+                    .with_placeholder("Choose a folder")
+                    .with_font_size(14.0 * s as f32),
+                )
+            },
+        },
+        Case {
+            name: "file_picker",
+            size: (160, 90),
+            overflow: 0,
+            build: |s| {
+                Box::new(FilePicker::new(
+                    160 * s,
+                    90 * s,
+                    swatch(160 * s, 28 * s, GRAY),
+                    swatch(160 * s, 28 * s, BLUE),
+                    swatch(28 * s, 28 * s, DARK_BLUE),
+                    swatch(28 * s, 28 * s, YELLOW),
+                    swatch(10 * s, 20 * s, GRAY),
+                    swatch(10 * s, 20 * s, DARK_BLUE),
+                    swatch(150 * s, 20 * s, [245, 245, 245]),
+                    swatch(150 * s, 20 * s, BLUE),
+                    swatch(150 * s, 20 * s, YELLOW),
+                ))
+            },
+        },
+        Case {
+            name: "custom_paint",
+            size: (60, 40),
+            overflow: 0,
+            build: |s| {
+                let painter = |canvas: &mut Canvas, bounds: &Rect, _store: &Store| {
+                    canvas.fill_rect(bounds.x as u32 + 4, bounds.y as u32 + 4, bounds.width - 8, bounds.height - 8, 0x40A060);
+                };
+                Box::new(CustomPaint::new("inset", Some(Rc::new(painter)), 60 * s, 40 * s))
+            },
+        },
+        Case {
+            name: "knob",
+            size: (40, 40),
+            overflow: 0,
+            build: |s| Box::new(Knob::new(40 * s, 40 * s, knob_strip(s), 8).with_value(0.6)),
+        },
+        Case {
+            name: "toggle_switch",
+            size: (48, 24),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    ToggleSwitch::new(48 * s, 24 * s, swatch(48 * s, 24 * s, GRAY), swatch(48 * s, 24 * s, GREEN))
+                        .with_thumb(swatch(20 * s, 20 * s, DARK_BLUE)),
+                )
+            },
+        },
+        Case {
+            name: "toggle_switch_on",
+            size: (48, 24),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    ToggleSwitch::new(48 * s, 24 * s, swatch(48 * s, 24 * s, GRAY), swatch(48 * s, 24 * s, GREEN))
+                        .with_thumb(swatch(20 * s, 20 * s, DARK_BLUE))
+                        .with_on(true),
+                )
+            },
+        },
+        Case {
+            name: "spinner",
+            size: (90, 28),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    Spinner::new(90 * s, 28 * s, swatch(90 * s, 28 * s, GRAY), swatch(20 * s, 14 * s, BLUE), swatch(20 * s, 14 * s, BLUE))
+                        .with_up_pressed(swatch(20 * s, 14 * s, DARK_BLUE))
+                        .with_range(0.0, 10.0)
+                        .with_step(0.5)
+                        .with_value(2.5)
+                        .with_font_size(16.0 * s as f32)
+                        .with_padding(4 * s),
+                )
+            },
+        },
+        Case {
+            name: "gauge",
+            size: (60, 60),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    Gauge::new(60 * s, 60 * s, swatch(60 * s, 60 * s, [240, 240, 240]))
+                        .with_range(0.0, 100.0)
+                        .with_thickness(3 * s)
+                        .with_warning(50.0)
+                        .with_value(65.0),
+                )
+            },
+        },
+        Case {
+            name: "gauge_arc",
+            size: (60, 60),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    Gauge::new(60 * s, 60 * s, swatch(60 * s, 60 * s, [240, 240, 240]))
+                        .with_style(GaugeStyle::Arc)
+                        .with_range(0.0, 100.0)
+                        .with_thickness(6 * s)
+                        .with_color(0x466EB4)
+                        .with_value(40.0),
+                )
+            },
+        },
+        Case {
+            name: "menu_bar",
+            size: (160, 24),
+            overflow: 0,
+            build: |s| Box::new(menu_bar(s)),
+        },
+        Case {
+            name: "menu_bar_open",
+            size: (160, 24),
+            overflow: 56,
+            build: |s| {
+                let mut bar = menu_bar(s);
+                bar.on_event(&WidgetEvent::MouseMove { x: 10 * s as i32, y: 10 * s as i32 });
+                bar.on_event(&WidgetEvent::Click);
+                Box::new(bar)
+            },
+        },
+        Case {
+            name: "tree_view",
+            size: (140, 80),
+            overflow: 0,
+            build: |s| {
+                let mut tree = TreeView::new(
+                    140 * s,
+                    80 * s,
+                    "folders",
+                    swatch(140 * s, 20 * s, GRAY),
+                    swatch(140 * s, 20 * s, BLUE),
+                    swatch(16 * s, 20 * s, DARK_BLUE),
+                    swatch(16 * s, 20 * s, GREEN),
+                )
+                .with_row_selected(swatch(140 * s, 20 * s, YELLOW))
+                .with_font_size(14.0 * s as f32)
+                .with_padding(4 * s);
+                let row = |path: &str, text: &str, depth, has_children| TreeRow {
+                    path: path.to_string(),
+                    text: text.to_string(),
+                    depth,
+                    has_children,
+                };
+                tree.set_nodes(vec![
+                    row("folders.0", "Documents", 0, true),
+                    row("folders.0.children.0", "Letters", 1, false),
+                    row("folders.0.children.1", "Taxes", 1, false),
+                    row("folders.1", "Pictures", 0, true),
+                    row("folders.1.children.0", "Holiday", 1, false),
+                ]);
+                tree.set_expanded("folders.0", true);
+                tree.set_selected(Some("folders.0.children.1"));
+                Box::new(tree)
+            },
+        },
+        Case {
+            name: "link",
+            size: (120, 24),
+            overflow: 0,
+            build: |s| Box::new(Link::new("Read the docs").with_font_size(16.0 * s as f32).with_padding(2 * s)),
+        },
+        Case {
+            name: "canvas",
+            size: (80, 50),
+            overflow: 0,
+            build: |s| {
+                let mut canvas = CanvasWidget::new(80 * s, 50 * s).with_background(0xF0F0F0);
+                canvas.set_drawing(&format!(
+                    "rect {0} {0} {1} {2} #96bef0 1\nline {0} {3} {4} {0} #c00000 {5}\ntext {0} {0} {6} #204080 Hi",
+                    4 * s,
+                    40 * s,
+                    20 * s,
+                    46 * s,
+                    76 * s,
+                    s,
+                    14 * s,
+                ));
+                Box::new(canvas)
+            },
+        },
+        Case {
+            name: "color_picker",
+            size: (120, 90),
+            overflow: 0,
+            build: |s| Box::new(ColorPicker::new(120 * s, 90 * s).with_color(0x3080C0)),
+        },
+        Case {
+            name: "animated_sprite",
+            size: (30, 30),
+            overflow: 0,
+            build: |s| {
+                // Three frames side by side; a stopped sprite shows the first
+                let mut sheet = RgbImage::new(90 * s, 30 * s);
+                for (i, color) in [GREEN, BLUE, RED].into_iter().enumerate() {
+                    image::imageops::replace(&mut sheet, &swatch(30 * s, 30 * s, color), i as i64 * 30 * s as i64, 0);
+                }
+                Box::new(AnimatedSprite::new(30 * s, 30 * s, sheet, (30 * s, 30 * s), 3))
+            },
+        },
+        Case {
+            name: "stack",
+            size: (60, 40),
+            overflow: 0,
+            build: |s| Box::new(Stack::new(60 * s, 40 * s).with_child("first").with_child("second").with_background(0x8CE6FA)),
+        },
+        Case {
+            name: "image_view",
+            size: (50, 40),
+            overflow: 0,
+            build: |s| Box::new(ImageView::new(50 * s, 40 * s).with_fallback(swatch(30 * s, 20 * s, YELLOW))),
+        },
+        Case {
+            name: "button",
+            size: (80, 30),
+            overflow: 0,
+            build: |s| {
+                Box::new(
+                    Button::new(80 * s, 30 * s)
+                        .with_color(0xC8C8C8)
+                        .with_hover_color(0x96BEF0)
+                        .with_pressed_color(0x466EB4),
+                )
+            },
+        },
+        Case {
+            name: "container",
+            size: (60, 40),
+            overflow: 0,
+            build: |s| Box::new(Container::new(60 * s, 40 * s).with_background(0xFAE68C)),
+        },
+        Case {
+            name: "image_widget",
+            size: (40, 30),
+            overflow: 0,
+            build: |s| Box::new(ImageWidget::from_rgb(swatch(40 * s, 30 * s, RED))),
+        },
+    ]
+}
+
+fn dropdown(s: u32) -> Dropdown {
+    let mut dropdown = Dropdown::new(
+        120 * s,
+        24 * s,
+        swatch(120 * s, 24 * s, GRAY),
+        swatch(120 * s, 24 * s, BLUE),
+        swatch(120 * s, 24 * s, [245, 245, 245]),
+        swatch(120 * s, 24 * s, BLUE),
+    )
+    .with_item_selected(swatch(120 * s, 24 * s, YELLOW))
+    .with_options(vec!["Apple".to_string(), "Banana".to_string(), "Cherry".to_string()])
+    .with_font_size(16.0 * s as f32)
+    .with_padding(6 * s);
+    dropdown.set_selected_option("Banana");
+    dropdown
+}
+
+fn menu_bar(s: u32) -> MenuBar {
+    let item = |label: &str| MenuItem {
+        label: label.to_string(),
+        action: label.to_lowercase(),
+    };
+    MenuBar::new(
+        160 * s,
+        24 * s,
+        swatch(50 * s, 24 * s, GRAY),
+        swatch(50 * s, 24 * s, BLUE),
+        swatch(90 * s, 24 * s, [245, 245, 245]),
+        swatch(90 * s, 24 * s, BLUE),
+    )
+    .with_menu("File", vec![item("Open"), item("Save")])
+    .with_menu("Edit", vec![item("Undo")])
+    .with_font_size(14.0 * s as f32)
+    .with_padding(6 * s)
+}
+
+/// A knob strip of eight 40x40 (at 1x) frames stacked top to bottom, each
+/// with a marker further round the dial.
+fn knob_strip(s: u32) -> RgbImage {
+    let size = 40 * s;
+    RgbImage::from_fn(size, size * 8, |x, y| {
+        let (frame, x, y) = (y / size, (x / s) as f64 - 19.5, ((y % size) / s) as f64 - 19.5);
+        let distance = (x * x + y * y).sqrt();
+        let angle = std::f64::consts::PI * (1.25 - frame as f64 * 2.5 / 7.0);
+        if (x - angle.cos() * 12.0).powi(2) + (y + angle.sin() * 12.0).powi(2) <= 9.0 {
+            Rgb([255, 255, 255])
+        } else if distance <= 18.0 {
+            Rgb(DARK_BLUE)
+        } else {
+            Rgb(GRAY)
+        }
+    })
+}
+
+/// A strip of four 30x30 (at 1x) play icons side by side: normal, hover,
+/// pressed and disabled.
+fn icon_strip(s: u32) -> RgbImage {
+    let size = 30 * s;
+    RgbImage::from_fn(size * 4, size, |x, y| {
+        let (frame, x, y) = (x / size, (x % size) / s, y / s);
+        // A triangle pointing right, 8 to 22 across and down
+        let inside = (8..22).contains(&x) && y.abs_diff(15) * 2 <= 22 - x && (8..22).contains(&y);
+        let (background, icon) = match frame {
+            0 => (GRAY, DARK_BLUE),
+            1 => (BLUE, DARK_BLUE),
+            2 => (DARK_BLUE, [255, 255, 255]),
+            _ => ([230, 230, 230], [170, 170, 170]),
+        };
+        Rgb(if inside { icon } else { background })
+    })
+}
+
+/// A digit atlas for "0123456789: " drawn as lit segments on a dark cell,
+/// each cell 10x18 at 1x.
+fn segment_atlas(s: u32) -> RgbImage {
+    // Segments a to g of each digit, as bits 0 to 6
+    const DIGITS: [u8; 10] = [0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F];
+    let (width, height) = (10 * s, 18 * s);
+    RgbImage::from_fn(width * 12, height, |x, y| {
+        let (cell, x, y) = ((x / width) as usize, x % width / s, y / s);
+        let segments: u8 = match cell {
+            0..=9 => DIGITS[cell],
+            _ => 0,
+        };
+        let (across, down) = ((2..8).contains(&x), (2..8).contains(&y) || (10..16).contains(&y));
+        let lit = match (x, y) {
+            _ if cell == 10 => (4..6).contains(&x) && (y == 5 || y == 12),
+            (_, 1) => across && segments & 0x01 != 0,
+            (_, 8) => across && segments & 0x40 != 0,
+            (_, 16) => across && segments & 0x08 != 0,
+            (8, 2..=7) => down && segments & 0x02 != 0,
+            (8, 10..=15) => down && segments & 0x04 != 0,
+            (1, 10..=15) => down && segments & 0x10 != 0,
+            (1, 2..=7) => down && segments & 0x20 != 0,
+            _ => false,
+        };
+        if lit { Rgb([255, 60, 40]) } else { Rgb([30, 10, 10]) }
+    })
+}
+
+#[test]
+fn golden_widgets() {
+    init_font();
+    let bless = std::env::var_os(BLESS_VAR).is_some();
+    if bless {
+        std::fs::create_dir_all(golden_dir()).expect("create golden dir");
+    }
+
+    let mut failures = Vec::new();
+    for case in cases() {
+        for scale in SCALES {
+            for state in State::ALL {
+                let name = format!("{}_{}@{}x", case.name, state.name(), scale);
+                let actual = render(&case, state, scale);
+                failures.extend(check(&name, &actual, bless));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} golden images differ (run with {}=1 to bless):\n{}",
+        failures.len(),
+        BLESS_VAR,
+        failures.join("\n")
+    );
+}